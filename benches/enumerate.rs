@@ -0,0 +1,32 @@
+//! Compare one-off enumeration with a reused [`netdev::interface::Enumerator`].
+//!
+//! Run with `cargo bench --bench enumerate`.
+
+use netdev::interface::Enumerator;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 200;
+
+fn bench<F: FnMut()>(name: &str, mut f: F) {
+    // Warm up caches before timing
+    for _ in 0..10 {
+        f();
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let per_iter: Duration = start.elapsed() / ITERATIONS;
+    println!("{:<24} {:>10.1?} / iter", name, per_iter);
+}
+
+fn main() {
+    bench("get_interfaces", || {
+        black_box(netdev::get_interfaces());
+    });
+    let mut enumerator = Enumerator::new();
+    bench("Enumerator::refresh", || {
+        black_box(enumerator.refresh());
+    });
+}
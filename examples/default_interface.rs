@@ -27,13 +27,14 @@ fn main() {
             println!("\tIPv6: {:?}", interface.ipv6);
             println!("\tTransmit Speed: {:?}", interface.transmit_speed);
             println!("\tReceive Speed: {:?}", interface.receive_speed);
-            if let Some(gateway) = interface.gateway {
+            if interface.gateways.is_empty() {
+                println!("Default Gateway: (Not found)");
+            }
+            for gateway in &interface.gateways {
                 println!("Default Gateway");
                 println!("\tMAC Address: {}", gateway.mac_addr);
                 println!("\tIPv4: {:?}", gateway.ipv4);
                 println!("\tIPv6: {:?}", gateway.ipv6);
-            } else {
-                println!("Default Gateway: (Not found)");
             }
             println!("DNS Servers: {:?}", interface.dns_servers);
             println!("Default: {}", interface.default);
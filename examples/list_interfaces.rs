@@ -27,16 +27,18 @@ fn main() {
         println!("\tIPv6: {:?}", interface.ipv6);
         println!("\tTransmit Speed: {:?}", interface.transmit_speed);
         println!("\tReceive Speed: {:?}", interface.receive_speed);
-        if let Some(gateway) = interface.gateway {
+        if interface.gateways.is_empty() {
+            println!("Gateway: (Not found)");
+        }
+        for gateway in &interface.gateways {
             println!("Gateway");
             println!("\tMAC Address: {}", gateway.mac_addr);
             println!("\tIPv4 Address: {:?}", gateway.ipv4);
             println!("\tIPv6 Address: {:?}", gateway.ipv6);
-        } else {
-            println!("Gateway: (Not found)");
         }
         println!("DNS Servers: {:?}", interface.dns_servers);
         println!("Default: {}", interface.default);
+        println!("Health: {:?}", interface.health());
         println!();
     }
 }
@@ -0,0 +1,8 @@
+// This example answers netdev::remote requests on stdin/stdout, so that a
+// client can enumerate this host's interfaces through `ssh host remote_agent`.
+fn main() {
+    if let Err(e) = netdev::remote::serve_stdio() {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use netdev::mac::MacAddr;
+
+fuzz_target!(|data: &str| {
+    if let Ok(mac) = netdev::parse::mac_addr(data) {
+        // Anything accepted must survive a round trip through Display.
+        let formatted = mac.to_string();
+        assert_eq!(formatted.parse::<MacAddr>(), Ok(mac));
+    }
+    // The lenient parser accepts everything the strict one does, with the same result.
+    if let Ok(mac) = netdev::parse::mac_addr_strict(data) {
+        assert_eq!(netdev::parse::mac_addr_lenient(data), Ok(mac));
+    }
+});
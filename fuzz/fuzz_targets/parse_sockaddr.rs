@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = netdev::parse::sockaddr(data);
+    let _ = netdev::parse::link_addr(data);
+    let _ = netdev::parse::link_hardware_addr(data);
+    let _ = netdev::parse::netmask(data, false);
+    let _ = netdev::parse::netmask(data, true);
+});
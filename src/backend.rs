@@ -0,0 +1,190 @@
+//! Pluggable sources of interface and routing data.
+//!
+//! The enumeration functions ([`get_interfaces`](crate::get_interfaces),
+//! [`get_default_interface`](crate::get_default_interface),
+//! [`get_routes`](crate::route::get_routes), ...) use the platform backend
+//! chosen at compile time, [`Native`]. [`set_backend`] replaces it for the whole
+//! process, for example with [`Getifaddrs`] in a sandbox that blocks netlink,
+//! or with a backend that fetches the data of a remote agent.
+//!
+//! ```
+//! use netdev::backend::{self, Backend};
+//! use netdev::interface::{EnumOptions, Interface};
+//! use netdev::route::RouteEntry;
+//! use std::io;
+//!
+//! /// Hides interfaces whose name starts with "veth"
+//! struct NoVeth;
+//!
+//! impl Backend for NoVeth {
+//!     fn name(&self) -> &str {
+//!         "no-veth"
+//!     }
+//!     fn interfaces(&self, options: &EnumOptions) -> Vec<Interface> {
+//!         let mut interfaces = backend::Native.interfaces(options);
+//!         interfaces.retain(|iface| !iface.name.starts_with("veth"));
+//!         interfaces
+//!     }
+//!     fn routes(&self) -> io::Result<Vec<RouteEntry>> {
+//!         backend::Native.routes()
+//!     }
+//! }
+//!
+//! backend::set_backend(NoVeth);
+//! assert_eq!(backend::current().name(), "no-veth");
+//! backend::reset_backend();
+//! ```
+//!
+//! The thread-local fixtures of the `mock` feature take precedence over the
+//! installed backend.
+
+use crate::interface::{self, EnumOptions, Interface};
+use crate::route::{self, RouteEntry};
+use std::io;
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
+
+/// Source of interface and routing data for the enumeration functions
+pub trait Backend: Send + Sync {
+    /// Short name of the backend, for logs and diagnostics
+    fn name(&self) -> &str;
+    /// List the interfaces matching `options`
+    fn interfaces(&self, options: &EnumOptions) -> Vec<Interface>;
+    /// List the entries of the main routing table
+    fn routes(&self) -> io::Result<Vec<RouteEntry>>;
+    /// Local IP address used for outgoing connections. Defaults to the first
+    /// address of the interface marked as default.
+    fn local_ipaddr(&self) -> Option<IpAddr> {
+        let interfaces = self.interfaces(&EnumOptions::default());
+        let iface = interfaces.iter().find(|iface| iface.default)?;
+        iface
+            .ipv4
+            .first()
+            .map(|net| IpAddr::V4(net.addr))
+            .or_else(|| iface.ipv6.first().map(|net| IpAddr::V6(net.addr)))
+    }
+}
+
+/// The platform backend: netlink and sysfs on Linux, the routing socket and
+/// SystemConfiguration on macOS, `GetAdaptersAddresses` on Windows, ...
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Native;
+
+impl Backend for Native {
+    fn name(&self) -> &str {
+        "native"
+    }
+    fn interfaces(&self, options: &EnumOptions) -> Vec<Interface> {
+        interface::os_interfaces(options)
+    }
+    fn routes(&self) -> io::Result<Vec<RouteEntry>> {
+        route::os_routes()
+    }
+    fn local_ipaddr(&self) -> Option<IpAddr> {
+        interface::os_local_ipaddr()
+    }
+}
+
+/// Backend using only `getifaddrs(3)`
+///
+/// Interfaces carry their flags, MAC address and IP addresses, but no gateways,
+/// DNS servers, link speed, driver or type information beyond what the link-layer
+/// address reveals, and none is marked as default. The routing table is not
+/// available.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "ios",
+    target_os = "android"
+))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Getifaddrs;
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "ios",
+    target_os = "android"
+))]
+impl Backend for Getifaddrs {
+    fn name(&self) -> &str {
+        "getifaddrs"
+    }
+    fn interfaces(&self, options: &EnumOptions) -> Vec<Interface> {
+        interface::getifaddrs_interfaces(options)
+    }
+    fn routes(&self) -> io::Result<Vec<RouteEntry>> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the getifaddrs backend cannot read the routing table",
+        ))
+    }
+    fn local_ipaddr(&self) -> Option<IpAddr> {
+        interface::os_local_ipaddr()
+    }
+}
+
+static BACKEND: RwLock<Option<Arc<dyn Backend>>> = RwLock::new(None);
+
+/// Route the enumeration functions of every thread through `backend`,
+/// replacing the previously installed one
+pub fn set_backend<B: Backend + 'static>(backend: B) {
+    *BACKEND.write().unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(backend));
+}
+
+/// Go back to the [`Native`] backend
+pub fn reset_backend() {
+    *BACKEND.write().unwrap_or_else(|err| err.into_inner()) = None;
+}
+
+/// The backend the enumeration functions currently use
+pub fn current() -> Arc<dyn Backend> {
+    installed().unwrap_or_else(|| Arc::new(Native))
+}
+
+/// The backend installed with [`set_backend`], if any
+pub(crate) fn installed() -> Option<Arc<dyn Backend>> {
+    BACKEND
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_native_backend() {
+        assert_eq!(current().name(), "native");
+        let interfaces = Native.interfaces(&EnumOptions::default());
+        println!("{:?}", Native.local_ipaddr());
+        println!("{} interfaces", interfaces.len());
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_getifaddrs_backend() {
+        let options = EnumOptions::default();
+        let mut native: Vec<String> = Native
+            .interfaces(&options)
+            .into_iter()
+            .map(|iface| iface.name)
+            .collect();
+        let mut fallback: Vec<String> = Getifaddrs
+            .interfaces(&options)
+            .into_iter()
+            .map(|iface| iface.name)
+            .collect();
+        native.sort();
+        fallback.sort();
+        assert_eq!(native, fallback);
+        assert!(Getifaddrs.routes().is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use super::{BondMode, BondSlave, BondStatus};
+use crate::mac::MacAddr;
+use std::io;
+
+const BONDING_DIR: &str = "/proc/net/bonding";
+
+pub fn get_bond_status(name: &str) -> io::Result<Option<BondStatus>> {
+    // Keeps a name like "../x" from escaping the directory
+    if name.is_empty() || name.contains('/') {
+        return Ok(None);
+    }
+    match std::fs::read_to_string(format!("{}/{}", BONDING_DIR, name)) {
+        Ok(content) => Ok(Some(parse_bonding(name, &content))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn get_bonds() -> io::Result<Vec<BondStatus>> {
+    let entries = match std::fs::read_dir(BONDING_DIR) {
+        Ok(entries) => entries,
+        // The directory only exists once the bonding driver is loaded
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut bonds = Vec::new();
+    for entry in entries {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if let Some(bond) = get_bond_status(&name)? {
+            bonds.push(bond);
+        }
+    }
+    bonds.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(bonds)
+}
+
+fn parse_mode(value: &str) -> BondMode {
+    match value {
+        "load balancing (round-robin)" => BondMode::RoundRobin,
+        "fault-tolerance (active-backup)" => BondMode::ActiveBackup,
+        "load balancing (xor)" => BondMode::Xor,
+        "fault-tolerance (broadcast)" => BondMode::Broadcast,
+        "IEEE 802.3ad Dynamic link aggregation" => BondMode::Lacp,
+        "transmit load balancing" => BondMode::TransmitLoadBalancing,
+        "adaptive load balancing" => BondMode::AdaptiveLoadBalancing,
+        _ => BondMode::Unknown,
+    }
+}
+
+/// Parse a speed like `1000 Mbps` into bits per second
+fn parse_speed(value: &str) -> Option<u64> {
+    let mbps: u64 = value.strip_suffix(" Mbps")?.parse().ok()?;
+    Some(mbps * 1_000_000)
+}
+
+/// Parse the content of `/proc/net/bonding/<name>`.
+///
+/// The file has a section for the bond, an optional `802.3ad info` section, and a
+/// section per slave starting with `Slave Interface:`. The keys of the partner's
+/// per-slave LACP details are lowercase and do not collide with these.
+fn parse_bonding(name: &str, content: &str) -> BondStatus {
+    let mut bond = BondStatus {
+        name: name.to_string(),
+        mode: BondMode::Unknown,
+        link_up: false,
+        active_slave: None,
+        aggregator_id: None,
+        partner_mac_addr: None,
+        slaves: Vec::new(),
+    };
+    let mut in_aggregator = false;
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        if key == "Slave Interface" {
+            bond.slaves.push(BondSlave {
+                name: value.to_string(),
+                link_up: false,
+                speed: None,
+                link_failures: 0,
+                permanent_mac_addr: None,
+                aggregator_id: None,
+            });
+            continue;
+        }
+        if let Some(slave) = bond.slaves.last_mut() {
+            match key {
+                "MII Status" => slave.link_up = value == "up",
+                "Speed" => slave.speed = parse_speed(value),
+                "Link Failure Count" => slave.link_failures = value.parse().unwrap_or(0),
+                "Permanent HW addr" => slave.permanent_mac_addr = value.parse().ok(),
+                "Aggregator ID" => slave.aggregator_id = value.parse().ok(),
+                _ => {}
+            }
+            continue;
+        }
+        match key {
+            "Bonding Mode" => bond.mode = parse_mode(value),
+            "MII Status" => bond.link_up = value == "up",
+            "Currently Active Slave" if value != "None" => {
+                bond.active_slave = Some(value.to_string());
+            }
+            "Active Aggregator Info" => in_aggregator = true,
+            "Aggregator ID" if in_aggregator => bond.aggregator_id = value.parse().ok(),
+            "Partner Mac Address" if in_aggregator => {
+                bond.partner_mac_addr = value.parse::<MacAddr>().ok();
+            }
+            _ => {}
+        }
+    }
+    bond
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lacp() {
+        let content = "Ethernet Channel Bonding Driver: v5.15.0
+
+Bonding Mode: IEEE 802.3ad Dynamic link aggregation
+Transmit Hash Policy: layer3+4 (1)
+MII Status: up
+MII Polling Interval (ms): 100
+
+802.3ad info
+LACP active: on
+LACP rate: fast
+System priority: 65535
+System MAC address: 52:54:00:12:34:56
+Active Aggregator Info:
+\tAggregator ID: 1
+\tNumber of ports: 2
+\tActor Key: 15
+\tPartner Key: 33
+\tPartner Mac Address: 00:1c:73:aa:bb:cc
+
+Slave Interface: eth0
+MII Status: up
+Speed: 10000 Mbps
+Duplex: full
+Link Failure Count: 0
+Permanent HW addr: 52:54:00:12:34:56
+Slave queue ID: 0
+Aggregator ID: 1
+details partner lacp pdu:
+    system mac address: 00:1c:73:aa:bb:cc
+    port state: 61
+
+Slave Interface: eth1
+MII Status: down
+Speed: Unknown
+Duplex: Unknown
+Link Failure Count: 3
+Permanent HW addr: 52:54:00:12:34:57
+Slave queue ID: 0
+Aggregator ID: 2
+";
+        let bond = parse_bonding("bond0", content);
+        assert_eq!(bond.mode, BondMode::Lacp);
+        assert!(bond.link_up);
+        assert_eq!(bond.aggregator_id, Some(1));
+        assert_eq!(
+            bond.partner_mac_addr,
+            Some(MacAddr::new(0x00, 0x1c, 0x73, 0xaa, 0xbb, 0xcc))
+        );
+        assert_eq!(bond.slaves.len(), 2);
+        assert_eq!(bond.slaves[0].name, "eth0");
+        assert!(bond.slaves[0].link_up);
+        assert_eq!(bond.slaves[0].speed, Some(10_000_000_000));
+        assert_eq!(bond.slaves[0].aggregator_id, Some(1));
+        assert!(!bond.slaves[1].link_up);
+        assert_eq!(bond.slaves[1].speed, None);
+        assert_eq!(bond.slaves[1].link_failures, 3);
+        assert!(bond.is_degraded());
+    }
+
+    #[test]
+    fn test_parse_active_backup() {
+        let content = "Bonding Mode: fault-tolerance (active-backup)
+Primary Slave: None
+Currently Active Slave: eth1
+MII Status: up
+
+Slave Interface: eth0
+MII Status: up
+
+Slave Interface: eth1
+MII Status: up
+";
+        let bond = parse_bonding("bond1", content);
+        assert_eq!(bond.mode, BondMode::ActiveBackup);
+        assert_eq!(bond.active_slave.as_deref(), Some("eth1"));
+        assert_eq!(bond.partner_mac_addr, None);
+        assert!(!bond.is_degraded());
+    }
+}
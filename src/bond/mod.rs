@@ -0,0 +1,154 @@
+//! Link aggregation status of bonded interfaces.
+//!
+//! [`status`] reports the mode of a bond, its active slave, the LACP partner
+//! and the link state of each slave, so a health check can detect a degraded
+//! bond with [`BondStatus::is_degraded`]. On Linux the data comes from
+//! `/proc/net/bonding`. Windows LBFO teams are only exposed through WMI and are
+//! not supported.
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod linux;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use self::linux::*;
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+mod unsupported;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+use self::unsupported::*;
+
+use crate::interface::Interface;
+use crate::mac::MacAddr;
+use std::io;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Bonding mode
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BondMode {
+    /// Round-robin over the slaves (`balance-rr`)
+    RoundRobin,
+    /// One active slave, the others on standby (`active-backup`)
+    ActiveBackup,
+    /// Slave chosen by a hash of the packet (`balance-xor`)
+    Xor,
+    /// Every packet sent on all slaves (`broadcast`)
+    Broadcast,
+    /// IEEE 802.3ad dynamic link aggregation with LACP (`802.3ad`)
+    Lacp,
+    /// Outgoing traffic balanced by load (`balance-tlb`)
+    TransmitLoadBalancing,
+    /// Outgoing and incoming traffic balanced by load (`balance-alb`)
+    AdaptiveLoadBalancing,
+    /// Other or unknown mode
+    Unknown,
+}
+
+/// Slave of a bond
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BondSlave {
+    /// Name of the slave interface
+    pub name: String,
+    /// Whether the link of the slave is up (MII status)
+    pub link_up: bool,
+    /// Speed in bits per second, if reported
+    pub speed: Option<u64>,
+    /// Number of times the link of the slave failed
+    pub link_failures: u64,
+    /// MAC address of the slave before it was enslaved
+    pub permanent_mac_addr: Option<MacAddr>,
+    /// 802.3ad aggregator the slave belongs to
+    pub aggregator_id: Option<u32>,
+}
+
+/// Status of a bond, as returned by [`status`]
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BondStatus {
+    /// Name of the bond interface
+    pub name: String,
+    /// Bonding mode
+    pub mode: BondMode,
+    /// Whether the bond is up (MII status)
+    pub link_up: bool,
+    /// Slave currently carrying the traffic, in active-backup and load balancing modes
+    pub active_slave: Option<String>,
+    /// 802.3ad aggregator in use
+    pub aggregator_id: Option<u32>,
+    /// System ID (MAC address) of the LACP partner, usually the switch
+    pub partner_mac_addr: Option<MacAddr>,
+    /// Slaves of the bond
+    pub slaves: Vec<BondSlave>,
+}
+
+impl BondStatus {
+    /// Check if the bond lost redundancy: it is down, a slave is down, or with
+    /// 802.3ad a slave is not part of the active aggregator
+    pub fn is_degraded(&self) -> bool {
+        if !self.link_up || self.slaves.is_empty() {
+            return true;
+        }
+        self.slaves.iter().any(|slave| {
+            !slave.link_up
+                || (self.mode == BondMode::Lacp
+                    && self.aggregator_id.is_some()
+                    && slave.aggregator_id != self.aggregator_id)
+        })
+    }
+}
+
+/// Get the status of `bond`, or `None` if it is not a bond
+pub fn status(bond: &Interface) -> io::Result<Option<BondStatus>> {
+    get_bond_status(&bond.name)
+}
+
+/// Get the status of all bonds
+pub fn bonds() -> io::Result<Vec<BondStatus>> {
+    get_bonds()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slave(name: &str, link_up: bool, aggregator_id: Option<u32>) -> BondSlave {
+        BondSlave {
+            name: name.to_string(),
+            link_up,
+            speed: None,
+            link_failures: 0,
+            permanent_mac_addr: None,
+            aggregator_id,
+        }
+    }
+
+    #[test]
+    fn test_is_degraded() {
+        let mut bond = BondStatus {
+            name: "bond0".to_string(),
+            mode: BondMode::Lacp,
+            link_up: true,
+            active_slave: None,
+            aggregator_id: Some(1),
+            partner_mac_addr: None,
+            slaves: vec![slave("eth0", true, Some(1)), slave("eth1", true, Some(1))],
+        };
+        assert!(!bond.is_degraded());
+        bond.slaves[1].aggregator_id = Some(2);
+        assert!(bond.is_degraded());
+        bond.mode = BondMode::ActiveBackup;
+        assert!(!bond.is_degraded());
+        bond.slaves[0].link_up = false;
+        assert!(bond.is_degraded());
+    }
+
+    #[test]
+    fn test_bonds() {
+        println!("{:?}", bonds());
+        for interface in crate::interface::get_interfaces() {
+            println!("{}: {:?}", interface.name, status(&interface));
+        }
+    }
+}
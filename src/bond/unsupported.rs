@@ -0,0 +1,17 @@
+use super::BondStatus;
+use std::io;
+
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Bond status is not supported on this platform",
+    )
+}
+
+pub fn get_bond_status(_name: &str) -> io::Result<Option<BondStatus>> {
+    Err(unsupported())
+}
+
+pub fn get_bonds() -> io::Result<Vec<BondStatus>> {
+    Err(unsupported())
+}
@@ -0,0 +1,115 @@
+use super::{FdbEntry, FdbEntryKind};
+use crate::mac::MacAddr;
+use crate::sys::netlink;
+use netlink_packet_route::{
+    rtnl::neighbour::nlas::Nla as NeighbourNla, NeighbourMessage, RtnlMessage, AF_BRIDGE,
+    NTF_EXT_LEARNED, NTF_OFFLOADED, NUD_NOARP, NUD_PERMANENT,
+};
+use std::io;
+use std::time::Duration;
+
+/// Unit of the timestamps in `struct nda_cacheinfo` (USER_HZ)
+const CLOCK_TICKS_PER_SEC: u64 = 100;
+
+pub fn get_fdb(bridge_index: u32) -> io::Result<Vec<FdbEntry>> {
+    let mut msg = NeighbourMessage::default();
+    msg.header.family = AF_BRIDGE as u8;
+    // Asks the kernel to dump only the entries of this bridge
+    msg.nlas
+        .push(NeighbourNla::Master(bridge_index.to_ne_bytes().to_vec()));
+    let msgs = netlink::dump(RtnlMessage::GetNeighbour(msg))?;
+    Ok(msgs
+        .into_iter()
+        .filter_map(|msg| match msg {
+            RtnlMessage::NewNeighbour(neighbour) => parse_fdb_entry(neighbour, bridge_index),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Convert a neighbour message into an entry of the bridge's own database.
+///
+/// Entries without a matching `NDA_MASTER` come from the ports' device
+/// address lists (`self` in `bridge fdb`) and are skipped.
+fn parse_fdb_entry(msg: NeighbourMessage, bridge_index: u32) -> Option<FdbEntry> {
+    let mut master = None;
+    let mut mac_addr = None;
+    let mut vlan = None;
+    let mut updated = None;
+    for nla in msg.nlas {
+        match nla {
+            NeighbourNla::Master(bytes) => master = netlink::read_u32(&bytes, 0),
+            NeighbourNla::LinkLocalAddress(bytes) => {
+                mac_addr = <[u8; 6]>::try_from(bytes).ok().map(MacAddr::from_octets);
+            }
+            NeighbourNla::Vlan(id) => vlan = Some(id),
+            NeighbourNla::CacheInfo(bytes) => {
+                // ndm_confirmed, ndm_used, ndm_updated, ndm_refcnt
+                updated = netlink::read_u32(&bytes, 8)
+                    .map(|ticks| Duration::from_millis(ticks as u64 * 1000 / CLOCK_TICKS_PER_SEC));
+            }
+            _ => {}
+        }
+    }
+    if master != Some(bridge_index) {
+        return None;
+    }
+    let kind = if msg.header.state & NUD_PERMANENT != 0 {
+        FdbEntryKind::Local
+    } else if msg.header.state & NUD_NOARP != 0 {
+        FdbEntryKind::Static
+    } else {
+        FdbEntryKind::Dynamic
+    };
+    Some(FdbEntry {
+        mac_addr: mac_addr?,
+        port_index: msg.header.ifindex,
+        vlan,
+        kind,
+        updated,
+        extern_learn: msg.header.flags & NTF_EXT_LEARNED != 0,
+        offloaded: msg.header.flags & NTF_OFFLOADED != 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use netlink_packet_route::{NTF_SELF, NUD_REACHABLE};
+
+    #[test]
+    fn test_parse_fdb_entry() {
+        let mut msg = NeighbourMessage::default();
+        msg.header.family = AF_BRIDGE as u8;
+        msg.header.ifindex = 4;
+        msg.header.state = NUD_REACHABLE;
+        msg.header.flags = NTF_OFFLOADED;
+        msg.nlas = vec![
+            NeighbourNla::LinkLocalAddress(vec![0x02, 0, 0, 0, 0, 9]),
+            NeighbourNla::Master(3u32.to_ne_bytes().to_vec()),
+            NeighbourNla::Vlan(10),
+            NeighbourNla::CacheInfo(
+                [0u32, 0, 250, 0]
+                    .iter()
+                    .flat_map(|v| v.to_ne_bytes())
+                    .collect(),
+            ),
+        ];
+        let entry = parse_fdb_entry(msg.clone(), 3).unwrap();
+        assert_eq!(entry.mac_addr, MacAddr::new(0x02, 0, 0, 0, 0, 9));
+        assert_eq!(entry.port_index, 4);
+        assert_eq!(entry.vlan, Some(10));
+        assert_eq!(entry.kind, FdbEntryKind::Dynamic);
+        assert_eq!(entry.updated, Some(Duration::from_millis(2500)));
+        assert!(entry.offloaded && !entry.extern_learn);
+
+        // Another bridge's entry
+        assert!(parse_fdb_entry(msg.clone(), 5).is_none());
+        // Address list entry of a port
+        msg.header.state = NUD_PERMANENT;
+        msg.header.flags = NTF_SELF;
+        msg.nlas
+            .retain(|nla| !matches!(nla, NeighbourNla::Master(_)));
+        assert!(parse_fdb_entry(msg, 3).is_none());
+    }
+}
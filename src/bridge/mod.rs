@@ -0,0 +1,74 @@
+//! Bridge forwarding database.
+//!
+//! [`fdb`] lists the MAC addresses a bridge has learned or been configured
+//! with, and the port each one is forwarded to, like `bridge fdb show br <bridge>`.
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod linux;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use self::linux::*;
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+mod unsupported;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+use self::unsupported::*;
+
+use crate::interface::Interface;
+use crate::mac::MacAddr;
+use std::io;
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How a forwarding database entry was created
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FdbEntryKind {
+    /// Address of the bridge or one of its ports, delivered locally
+    Local,
+    /// Entry added by an administrator, which never ages out
+    Static,
+    /// Entry learned from traffic, removed after the ageing time
+    Dynamic,
+}
+
+/// Entry of a bridge forwarding database
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FdbEntry {
+    /// Destination MAC address
+    pub mac_addr: MacAddr,
+    /// Index of the port frames to `mac_addr` are forwarded to.
+    /// Equal to the bridge's own index for addresses of the bridge itself.
+    pub port_index: u32,
+    /// VLAN the entry applies to, on VLAN-filtering bridges
+    pub vlan: Option<u16>,
+    /// How the entry was created
+    pub kind: FdbEntryKind,
+    /// Time since the entry was last refreshed, if reported
+    pub updated: Option<Duration>,
+    /// The entry was learned by a switch driver rather than the bridge
+    pub extern_learn: bool,
+    /// The entry is offloaded to hardware
+    pub offloaded: bool,
+}
+
+/// List the forwarding database of `bridge`.
+///
+/// The list is empty for interfaces that are not bridges.
+pub fn fdb(bridge: &Interface) -> io::Result<Vec<FdbEntry>> {
+    get_fdb(bridge.index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fdb() {
+        for interface in crate::interface::get_interfaces() {
+            println!("{}: {:?}", interface.name, fdb(&interface));
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use super::FdbEntry;
+use std::io;
+
+pub fn get_fdb(_bridge_index: u32) -> io::Result<Vec<FdbEntry>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Bridge forwarding database is not supported on this platform",
+    ))
+}
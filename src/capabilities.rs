@@ -0,0 +1,147 @@
+//! What the crate supports on the current platform and build.
+//!
+//! [`capabilities`] answers at runtime what would otherwise be `cfg!` guesses
+//! in the application that drift from the crate's real support.
+//!
+//! ```
+//! let capabilities = netdev::capabilities();
+//! if !capabilities.stats {
+//!     println!("traffic counters are not available here");
+//! }
+//! if !capabilities.fields.mac_addr {
+//!     println!("MAC addresses are hidden on this platform");
+//! }
+//! ```
+
+use crate::interface::{field_availability, FieldAvailability};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Features supported on the current platform and build, as returned by [`capabilities`]
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Capabilities {
+    /// Default gateway discovery, see [`get_default_gateway`](crate::get_default_gateway)
+    pub gateway_discovery: bool,
+    /// Traffic counters in [`Interface::stats`](crate::Interface::stats)
+    pub stats: bool,
+    /// Change detection with [`Monitor`](crate::monitor::Monitor)
+    pub monitoring: bool,
+    /// Async address watch with `monitor::addresses` (`stream` feature)
+    pub address_stream: bool,
+    /// Wi-Fi interfaces are told apart, with
+    /// [`InterfaceType::Wireless80211`](crate::interface::InterfaceType::Wireless80211)
+    pub wifi_detection: bool,
+    /// Cellular modem information from ModemManager (`wwan` feature, Linux only)
+    pub wwan: bool,
+    /// NetworkManager data in [`Interface::network_manager`](crate::Interface::network_manager)
+    /// (`networkmanager` feature, Linux only)
+    pub network_manager: bool,
+    /// systemd-networkd data in [`Interface::networkd`](crate::Interface::networkd)
+    /// (`networkd` feature, Linux only)
+    pub networkd: bool,
+    /// Route selection queries, see [`route::lookup`](crate::route::lookup)
+    pub route_lookup: bool,
+    /// Listening sockets, see [`sockets::listening`](crate::sockets::listening)
+    pub listening_sockets: bool,
+    /// Bridge forwarding database, see [`bridge::fdb`](crate::bridge::fdb)
+    pub bridge_fdb: bool,
+    /// Link aggregation status, see [`bond::status`](crate::bond::status)
+    pub bond_status: bool,
+    /// Host discovery with `scan::arp_scan` (`scan` feature)
+    pub arp_scan: bool,
+    /// Host discovery with `scan::ndp_scan` (`scan` feature)
+    pub ndp_scan: bool,
+    /// Presence check with `probe::broadcast_ping` (`probe` feature)
+    pub broadcast_ping: bool,
+    /// Path MTU measurement with `probe::path_mtu` (`probe` feature)
+    pub path_mtu: bool,
+    /// Round-trip time measurement with `probe::latency` (`probe` feature)
+    pub latency: bool,
+    /// Traffic marking with `socket::set_dscp` (`socket2` feature)
+    pub dscp: bool,
+    /// Optional [`Interface`](crate::Interface) fields the enumeration fills in
+    pub fields: FieldAvailability,
+}
+
+/// Report what the crate supports on the current platform and build
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        gateway_discovery: cfg!(any(unix, windows)),
+        stats: cfg!(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "openbsd",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "windows"
+        )),
+        monitoring: true,
+        address_stream: cfg!(feature = "stream"),
+        wifi_detection: cfg!(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "windows"
+        )),
+        wwan: cfg!(all(feature = "wwan", target_os = "linux")),
+        network_manager: cfg!(all(feature = "networkmanager", target_os = "linux")),
+        networkd: cfg!(all(feature = "networkd", target_os = "linux")),
+        route_lookup: cfg!(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "windows"
+        )),
+        listening_sockets: cfg!(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "macos",
+            target_os = "windows"
+        )),
+        bridge_fdb: cfg!(any(target_os = "linux", target_os = "android")),
+        bond_status: cfg!(any(target_os = "linux", target_os = "android")),
+        arp_scan: cfg!(all(
+            feature = "scan",
+            any(
+                target_os = "linux",
+                target_os = "android",
+                target_os = "macos",
+                target_os = "openbsd",
+                target_os = "freebsd",
+                target_os = "netbsd"
+            )
+        )),
+        ndp_scan: cfg!(all(feature = "scan", unix)),
+        broadcast_ping: cfg!(feature = "probe"),
+        path_mtu: cfg!(feature = "probe"),
+        latency: cfg!(feature = "probe"),
+        dscp: cfg!(all(feature = "socket2", unix)),
+        fields: field_availability(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities() {
+        let capabilities = capabilities();
+        println!("{:?}", capabilities);
+        assert_eq!(capabilities.fields, field_availability());
+        if !capabilities.route_lookup {
+            assert!(crate::route::lookup([192, 0, 2, 1].into()).is_err());
+        }
+        if !capabilities.listening_sockets {
+            assert!(crate::sockets::listening().is_err());
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use super::InterfaceConfig;
+use crate::interface::Interface;
+use std::ffi::CString;
+use std::io;
+
+const SYSCTL_IPV4_FORWARDING: &str = "net.inet.ip.forwarding";
+const SYSCTL_IPV6_FORWARDING: &str = "net.inet6.ip6.forwarding";
+const SYSCTL_IPV6_ACCEPT_RTADV: &str = "net.inet6.ip6.accept_rtadv";
+const SYSCTL_IPV6_USE_TEMPADDR: &str = "net.inet6.ip6.use_tempaddr";
+const SYSCTL_IPV6_PREFER_TEMPADDR: &str = "net.inet6.ip6.prefer_tempaddr";
+
+#[cfg(feature = "config")]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "This setting is not supported on this platform",
+    )
+}
+
+fn read_sysctl(name: &str) -> io::Result<u32> {
+    let name = CString::new(name)?;
+    let mut value: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>();
+    let ret = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            &mut value as *mut libc::c_int as *mut libc::c_void,
+            &mut len,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(value as u32)
+}
+
+#[cfg(feature = "config")]
+fn write_sysctl(name: &str, value: u32) -> io::Result<()> {
+    let name = CString::new(name)?;
+    let mut value: libc::c_int = value as libc::c_int;
+    let ret = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            &mut value as *mut libc::c_int as *mut libc::c_void,
+            std::mem::size_of::<libc::c_int>(),
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+pub fn get_ipv4_forwarding() -> io::Result<bool> {
+    read_sysctl(SYSCTL_IPV4_FORWARDING).map(|v| v != 0)
+}
+
+pub fn get_ipv6_forwarding() -> io::Result<bool> {
+    read_sysctl(SYSCTL_IPV6_FORWARDING).map(|v| v != 0)
+}
+
+// BSD stacks expose these settings system-wide only.
+pub fn interface_config(_interface: &Interface) -> InterfaceConfig {
+    InterfaceConfig {
+        ipv4_forwarding: get_ipv4_forwarding().ok(),
+        ipv6_forwarding: get_ipv6_forwarding().ok(),
+        accept_ra: read_sysctl(SYSCTL_IPV6_ACCEPT_RTADV).ok(),
+        rp_filter: None,
+        proxy_arp: None,
+        use_tempaddr: use_tempaddr().ok(),
+    }
+}
+
+/// Combine the two temporary address sysctls into a Linux `use_tempaddr` value
+fn use_tempaddr() -> io::Result<i32> {
+    if read_sysctl(SYSCTL_IPV6_USE_TEMPADDR)? == 0 {
+        return Ok(0);
+    }
+    let preferred = read_sysctl(SYSCTL_IPV6_PREFER_TEMPADDR).unwrap_or(0) != 0;
+    Ok(if preferred { 2 } else { 1 })
+}
+
+#[cfg(feature = "config")]
+pub fn set_ipv4_forwarding(enabled: bool) -> io::Result<()> {
+    write_sysctl(SYSCTL_IPV4_FORWARDING, enabled as u32)
+}
+
+#[cfg(feature = "config")]
+pub fn set_ipv6_forwarding_inner(enabled: bool) -> io::Result<()> {
+    write_sysctl(SYSCTL_IPV6_FORWARDING, enabled as u32)
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_accept_ra(_interface: &Interface, _value: u32) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_rp_filter(_interface: &Interface, _value: u32) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_proxy_arp(_interface: &Interface, _enabled: bool) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_use_tempaddr(_interface: &Interface, _value: i32) -> io::Result<()> {
+    Err(unsupported())
+}
@@ -0,0 +1,96 @@
+use super::InterfaceConfig;
+use crate::interface::Interface;
+use std::fs::read_to_string;
+use std::io;
+
+const PATH_IPV4_FORWARD: &str = "/proc/sys/net/ipv4/ip_forward";
+const PATH_IPV6_ALL_FORWARDING: &str = "/proc/sys/net/ipv6/conf/all/forwarding";
+
+fn read_sysctl(path: &str) -> io::Result<u32> {
+    let content = read_to_string(path)?;
+    content
+        .trim()
+        .parse::<u32>()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Read a sysctl that may be negative, such as `use_tempaddr`
+fn read_signed_sysctl(path: &str) -> io::Result<i32> {
+    let content = read_to_string(path)?;
+    content
+        .trim()
+        .parse::<i32>()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(feature = "config")]
+fn write_sysctl(path: &str, value: impl std::fmt::Display) -> io::Result<()> {
+    std::fs::write(path, value.to_string())
+}
+
+fn ipv4_conf_path(if_name: &str, key: &str) -> String {
+    format!("/proc/sys/net/ipv4/conf/{}/{}", if_name, key)
+}
+
+fn ipv6_conf_path(if_name: &str, key: &str) -> String {
+    format!("/proc/sys/net/ipv6/conf/{}/{}", if_name, key)
+}
+
+pub fn get_ipv4_forwarding() -> io::Result<bool> {
+    read_sysctl(PATH_IPV4_FORWARD).map(|v| v != 0)
+}
+
+pub fn get_ipv6_forwarding() -> io::Result<bool> {
+    read_sysctl(PATH_IPV6_ALL_FORWARDING).map(|v| v != 0)
+}
+
+pub fn interface_config(interface: &Interface) -> InterfaceConfig {
+    let if_name = interface.name.as_str();
+    InterfaceConfig {
+        ipv4_forwarding: read_sysctl(&ipv4_conf_path(if_name, "forwarding"))
+            .ok()
+            .map(|v| v != 0),
+        ipv6_forwarding: read_sysctl(&ipv6_conf_path(if_name, "forwarding"))
+            .ok()
+            .map(|v| v != 0),
+        accept_ra: read_sysctl(&ipv6_conf_path(if_name, "accept_ra")).ok(),
+        rp_filter: read_sysctl(&ipv4_conf_path(if_name, "rp_filter")).ok(),
+        proxy_arp: read_sysctl(&ipv4_conf_path(if_name, "proxy_arp"))
+            .ok()
+            .map(|v| v != 0),
+        use_tempaddr: read_signed_sysctl(&ipv6_conf_path(if_name, "use_tempaddr")).ok(),
+    }
+}
+
+#[cfg(feature = "config")]
+pub fn set_ipv4_forwarding(enabled: bool) -> io::Result<()> {
+    write_sysctl(PATH_IPV4_FORWARD, enabled as u32)
+}
+
+#[cfg(feature = "config")]
+pub fn set_ipv6_forwarding_inner(enabled: bool) -> io::Result<()> {
+    write_sysctl(PATH_IPV6_ALL_FORWARDING, enabled as u32)
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_accept_ra(interface: &Interface, value: u32) -> io::Result<()> {
+    write_sysctl(&ipv6_conf_path(&interface.name, "accept_ra"), value)
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_rp_filter(interface: &Interface, value: u32) -> io::Result<()> {
+    write_sysctl(&ipv4_conf_path(&interface.name, "rp_filter"), value)
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_proxy_arp(interface: &Interface, enabled: bool) -> io::Result<()> {
+    write_sysctl(
+        &ipv4_conf_path(&interface.name, "proxy_arp"),
+        enabled as u32,
+    )
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_use_tempaddr(interface: &Interface, value: i32) -> io::Result<()> {
+    write_sysctl(&ipv6_conf_path(&interface.name, "use_tempaddr"), value)
+}
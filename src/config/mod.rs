@@ -0,0 +1,146 @@
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod linux;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use self::linux::*;
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+mod bsd;
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+use self::bsd::*;
+
+#[cfg(target_os = "windows")]
+mod windows;
+#[cfg(target_os = "windows")]
+use self::windows::*;
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "windows"
+)))]
+mod unsupported;
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "windows"
+)))]
+use self::unsupported::*;
+
+use crate::interface::Interface;
+use std::io;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Per-interface IP configuration (kernel or stack settings)
+///
+/// Fields are `None` when the value is not available or has no equivalent on the platform.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InterfaceConfig {
+    /// IPv4 forwarding is enabled on the interface
+    pub ipv4_forwarding: Option<bool>,
+    /// IPv6 forwarding is enabled on the interface
+    pub ipv6_forwarding: Option<bool>,
+    /// Router Advertisement acceptance (`accept_ra`).
+    /// 0: ignore, 1: accept if forwarding is disabled, 2: accept even if forwarding is enabled
+    pub accept_ra: Option<u32>,
+    /// Reverse path filtering mode (`rp_filter`). 0: off, 1: strict, 2: loose
+    pub rp_filter: Option<u32>,
+    /// Proxy ARP is enabled on the interface
+    pub proxy_arp: Option<bool>,
+    /// Generation of IPv6 temporary addresses for privacy (`use_tempaddr`, RFC 8981).
+    /// 0 or less: disabled, 1: enabled but public addresses are preferred as source,
+    /// 2: enabled and temporary addresses are preferred as source.
+    /// BSD stacks and macOS have a system-wide setting only. Always `None`
+    /// on Windows, whose setting is only exposed through WMI.
+    pub use_tempaddr: Option<i32>,
+}
+
+/// Check if IPv4 forwarding is enabled system-wide
+pub fn ip_forwarding_enabled() -> io::Result<bool> {
+    get_ipv4_forwarding()
+}
+
+/// Check if IPv6 forwarding is enabled system-wide
+pub fn ipv6_forwarding_enabled() -> io::Result<bool> {
+    get_ipv6_forwarding()
+}
+
+/// Get the IP configuration of the network interface
+pub fn get_interface_config(interface: &Interface) -> InterfaceConfig {
+    interface_config(interface)
+}
+
+/// Enable or disable IPv4 forwarding system-wide (requires elevated privileges)
+#[cfg(feature = "config")]
+pub fn set_ip_forwarding(enabled: bool) -> io::Result<()> {
+    set_ipv4_forwarding(enabled)
+}
+
+/// Enable or disable IPv6 forwarding system-wide (requires elevated privileges)
+#[cfg(feature = "config")]
+pub fn set_ipv6_forwarding(enabled: bool) -> io::Result<()> {
+    set_ipv6_forwarding_inner(enabled)
+}
+
+/// Set `accept_ra` on the network interface (requires elevated privileges)
+#[cfg(feature = "config")]
+pub fn set_accept_ra(interface: &Interface, value: u32) -> io::Result<()> {
+    set_interface_accept_ra(interface, value)
+}
+
+/// Set `rp_filter` on the network interface (requires elevated privileges)
+#[cfg(feature = "config")]
+pub fn set_rp_filter(interface: &Interface, value: u32) -> io::Result<()> {
+    set_interface_rp_filter(interface, value)
+}
+
+/// Enable or disable proxy ARP on the network interface (requires elevated privileges)
+#[cfg(feature = "config")]
+pub fn set_proxy_arp(interface: &Interface, enabled: bool) -> io::Result<()> {
+    set_interface_proxy_arp(interface, enabled)
+}
+
+/// Set `use_tempaddr` on the network interface (requires elevated privileges).
+///
+/// Temporary addresses are created or stopped being renewed as router
+/// advertisements arrive, so existing addresses are not affected right away.
+#[cfg(feature = "config")]
+pub fn set_use_tempaddr(interface: &Interface, value: i32) -> io::Result<()> {
+    set_interface_use_tempaddr(interface, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_ip_forwarding() {
+        println!("IPv4: {:?}", ip_forwarding_enabled());
+        println!("IPv6: {:?}", ipv6_forwarding_enabled());
+    }
+    #[test]
+    fn test_interface_config() {
+        for interface in crate::get_interfaces() {
+            println!("{}: {:?}", interface.name, get_interface_config(&interface));
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use super::InterfaceConfig;
+use crate::interface::Interface;
+use std::io;
+
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "This setting is not supported on this platform",
+    )
+}
+
+pub fn get_ipv4_forwarding() -> io::Result<bool> {
+    Err(unsupported())
+}
+
+pub fn get_ipv6_forwarding() -> io::Result<bool> {
+    Err(unsupported())
+}
+
+pub fn interface_config(_interface: &Interface) -> InterfaceConfig {
+    InterfaceConfig::default()
+}
+
+#[cfg(feature = "config")]
+pub fn set_ipv4_forwarding(_enabled: bool) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(feature = "config")]
+pub fn set_ipv6_forwarding_inner(_enabled: bool) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_accept_ra(_interface: &Interface, _value: u32) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_rp_filter(_interface: &Interface, _value: u32) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_proxy_arp(_interface: &Interface, _enabled: bool) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_use_tempaddr(_interface: &Interface, _value: i32) -> io::Result<()> {
+    Err(unsupported())
+}
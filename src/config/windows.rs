@@ -0,0 +1,102 @@
+use super::InterfaceConfig;
+use crate::interface::Interface;
+use std::io;
+use windows_sys::Win32::Foundation::NO_ERROR;
+use windows_sys::Win32::NetworkManagement::IpHelper::{
+    GetIpInterfaceEntry, GetIpStatisticsEx, InitializeIpInterfaceEntry, MIB_IPINTERFACE_ROW,
+    MIB_IPSTATS_LH, MIB_IP_FORWARDING,
+};
+use windows_sys::Win32::Networking::WinSock::{
+    RouterDiscoveryDisabled, ADDRESS_FAMILY, AF_INET, AF_INET6,
+};
+
+fn get_forwarding(family: ADDRESS_FAMILY) -> io::Result<bool> {
+    let mut stats: MIB_IPSTATS_LH = unsafe { std::mem::zeroed() };
+    let ret = unsafe { GetIpStatisticsEx(&mut stats, family as u32) };
+    if ret != NO_ERROR {
+        return Err(io::Error::from_raw_os_error(ret as i32));
+    }
+    Ok(unsafe { stats.Anonymous.Forwarding } == MIB_IP_FORWARDING)
+}
+
+fn get_ip_interface_entry(if_index: u32, family: ADDRESS_FAMILY) -> Option<MIB_IPINTERFACE_ROW> {
+    let mut row: MIB_IPINTERFACE_ROW = unsafe { std::mem::zeroed() };
+    unsafe { InitializeIpInterfaceEntry(&mut row) };
+    row.Family = family;
+    row.InterfaceIndex = if_index;
+    if unsafe { GetIpInterfaceEntry(&mut row) } == NO_ERROR {
+        Some(row)
+    } else {
+        None
+    }
+}
+
+pub fn get_ipv4_forwarding() -> io::Result<bool> {
+    get_forwarding(AF_INET)
+}
+
+pub fn get_ipv6_forwarding() -> io::Result<bool> {
+    get_forwarding(AF_INET6)
+}
+
+pub fn interface_config(interface: &Interface) -> InterfaceConfig {
+    let ipv4_row = get_ip_interface_entry(interface.index, AF_INET);
+    let ipv6_row = get_ip_interface_entry(interface.index, AF_INET6);
+    InterfaceConfig {
+        ipv4_forwarding: ipv4_row.map(|row| row.ForwardingEnabled != 0),
+        ipv6_forwarding: ipv6_row.map(|row| row.ForwardingEnabled != 0),
+        accept_ra: ipv6_row.map(|row| {
+            if row.RouterDiscoveryBehavior == RouterDiscoveryDisabled {
+                0
+            } else {
+                1
+            }
+        }),
+        // Windows has no equivalent of rp_filter or proxy_arp.
+        rp_filter: None,
+        proxy_arp: None,
+        // The privacy setting (`netsh interface ipv6 show privacy`) is
+        // system-wide and has no IP Helper API or documented registry value:
+        // it is only exposed through WMI, as
+        // MSFT_NetIPv6Protocol.UseTemporaryAddresses, which is not queried.
+        use_tempaddr: None,
+    }
+}
+
+#[cfg(feature = "config")]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "This setting is not supported on this platform",
+    )
+}
+
+#[cfg(feature = "config")]
+pub fn set_ipv4_forwarding(_enabled: bool) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(feature = "config")]
+pub fn set_ipv6_forwarding_inner(_enabled: bool) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_accept_ra(_interface: &Interface, _value: u32) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_rp_filter(_interface: &Interface, _value: u32) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_proxy_arp(_interface: &Interface, _enabled: bool) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(feature = "config")]
+pub fn set_interface_use_tempaddr(_interface: &Interface, _value: i32) -> io::Result<()> {
+    Err(unsupported())
+}
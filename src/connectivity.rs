@@ -0,0 +1,252 @@
+//! Internet connectivity and captive portal detection.
+//!
+//! The check walks up the stack: a usable interface address, a default gateway, DNS
+//! resolution of the probe host and finally an HTTP request that is expected to return
+//! `204 No Content`. Any other HTTP response means the request was intercepted,
+//! which is how captive portals behave.
+
+use crate::dns::{self, DNS_TYPE_A, DNS_TYPE_AAAA};
+use crate::interface::{self, Interface};
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Host used for the HTTP probe
+pub const PROBE_HOST: &str = "connectivitycheck.gstatic.com";
+/// Path used for the HTTP probe. The server answers with `204 No Content`.
+pub const PROBE_PATH: &str = "/generate_204";
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const DNS_PORT: u16 = 53;
+
+/// Level of connectivity available through an interface
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Connectivity {
+    /// No usable interface or address
+    None,
+    /// Internet traffic is intercepted by a captive portal
+    Portal,
+    /// The local network is reachable, but the internet is not
+    Limited,
+    /// The internet is reachable
+    Full,
+}
+
+/// Check connectivity through the interface, or through the default interface if `None`.
+pub fn check(interface: Option<&Interface>) -> Connectivity {
+    let default_interface;
+    let interface = match interface {
+        Some(interface) => interface,
+        None => match interface::get_default_interface() {
+            Ok(iface) => {
+                default_interface = iface;
+                &default_interface
+            }
+            Err(_) => return Connectivity::None,
+        },
+    };
+    if !interface.is_up() {
+        return Connectivity::None;
+    }
+    let local_ip = match source_addr(interface) {
+        Some(ip) => ip,
+        None => return Connectivity::None,
+    };
+    if interface.gateways.is_empty() {
+        return Connectivity::Limited;
+    }
+    let probe_ip = match resolve(interface, local_ip, PROBE_HOST) {
+        Some(ip) => ip,
+        None => return Connectivity::Limited,
+    };
+    match http_status(local_ip, SocketAddr::new(probe_ip, 80)) {
+        Ok(204) => Connectivity::Full,
+        Ok(_) => Connectivity::Portal,
+        Err(_) => Connectivity::Limited,
+    }
+}
+
+/// Pick the address probes are sent from. IPv4 is preferred as captive portals rarely
+/// intercept IPv6.
+fn source_addr(interface: &Interface) -> Option<IpAddr> {
+    if let Some(net) = interface
+        .ipv4
+        .iter()
+        .find(|net| !net.addr.is_unspecified() && !net.addr.is_link_local())
+    {
+        return Some(IpAddr::V4(net.addr));
+    }
+    interface
+        .ipv6
+        .iter()
+        .find(|net| {
+            let seg = net.addr.segments();
+            !net.addr.is_unspecified() && !net.addr.is_loopback() && (seg[0] & 0xffc0) != 0xfe80
+        })
+        .map(|net| IpAddr::V6(net.addr))
+}
+
+fn resolve(interface: &Interface, local_ip: IpAddr, host: &str) -> Option<IpAddr> {
+    let servers: Vec<IpAddr> = interface
+        .dns_servers
+        .iter()
+        .filter(|server| server.is_ipv4() == local_ip.is_ipv4())
+        .cloned()
+        .collect();
+    if servers.is_empty() {
+        // Fall back to the system resolver.
+        return (host, 0)
+            .to_socket_addrs()
+            .ok()?
+            .map(|addr| addr.ip())
+            .find(|ip| ip.is_ipv4() == local_ip.is_ipv4());
+    }
+    servers
+        .iter()
+        .find_map(|server| dns_query(local_ip, *server, host).ok().flatten())
+}
+
+fn dns_query(local_ip: IpAddr, server: IpAddr, host: &str) -> io::Result<Option<IpAddr>> {
+    let qtype = if local_ip.is_ipv4() {
+        DNS_TYPE_A
+    } else {
+        DNS_TYPE_AAAA
+    };
+    // A guessable id would let anyone on the path fake the answer
+    let id = dns::random_id();
+    let query = dns::build_query(id, host, qtype, dns::FLAGS_RECURSION_DESIRED);
+    let socket = UdpSocket::bind(SocketAddr::new(local_ip, 0))?;
+    socket.set_read_timeout(Some(PROBE_TIMEOUT))?;
+    socket.connect(SocketAddr::new(server, DNS_PORT))?;
+    socket.send(&query)?;
+    let deadline = Instant::now() + PROBE_TIMEOUT;
+    let mut buf = [0u8; 512];
+    loop {
+        let len = socket.recv(&mut buf)?;
+        // Skip stray datagrams that are not the answer to this query
+        if let Some(addr) = parse_dns_response(&buf[..len], id, host, qtype) {
+            return Ok(addr);
+        }
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "no answer"));
+        }
+        socket.set_read_timeout(Some(left))?;
+    }
+}
+
+/// Decode the answer to the query for `host` with the given id and type: `None`
+/// if `buf` is not that answer, `Some(None)` if it has no address
+fn parse_dns_response(buf: &[u8], id: u16, host: &str, qtype: u16) -> Option<Option<IpAddr>> {
+    let (name, rtype) = dns::question(buf)?;
+    if rtype != qtype || !name.eq_ignore_ascii_case(host) {
+        return None;
+    }
+    let records = dns::parse_answers(buf, id)?;
+    Some(
+        records
+            .into_iter()
+            .filter(|record| record.rtype == qtype)
+            .find_map(|record| match record.rdata.len() {
+                4 => Some(IpAddr::V4(Ipv4Addr::from(
+                    <[u8; 4]>::try_from(record.rdata).ok()?,
+                ))),
+                16 => Some(IpAddr::V6(Ipv6Addr::from(
+                    <[u8; 16]>::try_from(record.rdata).ok()?,
+                ))),
+                _ => None,
+            }),
+    )
+}
+
+fn http_status(local_ip: IpAddr, addr: SocketAddr) -> io::Result<u16> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+    socket.bind(&SockAddr::from(SocketAddr::new(local_ip, 0)))?;
+    socket.connect_timeout(&SockAddr::from(addr), PROBE_TIMEOUT)?;
+    socket.set_read_timeout(Some(PROBE_TIMEOUT))?;
+    socket.set_write_timeout(Some(PROBE_TIMEOUT))?;
+    let mut stream: std::net::TcpStream = socket.into();
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        PROBE_PATH, PROBE_HOST
+    )?;
+    let mut buf = [0u8; 64];
+    let mut len = 0;
+    while len < buf.len() {
+        let n = stream.read(&mut buf[len..])?;
+        if n == 0 {
+            break;
+        }
+        len += n;
+        if buf[..len].contains(&b'\n') {
+            break;
+        }
+    }
+    parse_status_line(&buf[..len])
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid HTTP response"))
+}
+
+fn parse_status_line(buf: &[u8]) -> Option<u16> {
+    let line = std::str::from_utf8(buf).ok()?.lines().next()?;
+    let mut parts = line.split_whitespace();
+    if !parts.next()?.starts_with("HTTP/") {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dns_response() {
+        let id = 0x1234;
+        let mut resp = dns::build_query(id, PROBE_HOST, DNS_TYPE_A, dns::FLAGS_RECURSION_DESIRED);
+        // Turn the query into a response, first without and then with one answer.
+        resp[2] = 0x81;
+        resp[3] = 0x80;
+        let empty = resp.clone();
+        resp[7] = 1;
+        resp.extend_from_slice(&[0xc0, 0x0c, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 142, 250, 0, 94]);
+        assert_eq!(
+            parse_dns_response(&resp, id, PROBE_HOST, DNS_TYPE_A),
+            Some(Some(IpAddr::V4(Ipv4Addr::new(142, 250, 0, 94))))
+        );
+        assert_eq!(
+            parse_dns_response(&empty, id, PROBE_HOST, DNS_TYPE_A),
+            Some(None)
+        );
+        assert_eq!(
+            parse_dns_response(&resp, id + 1, PROBE_HOST, DNS_TYPE_A),
+            None
+        );
+        assert_eq!(
+            parse_dns_response(&resp, id, PROBE_HOST, DNS_TYPE_AAAA),
+            None
+        );
+        assert_eq!(
+            parse_dns_response(&resp, id, "example.com", DNS_TYPE_A),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_status_line() {
+        assert_eq!(parse_status_line(b"HTTP/1.1 204 No Content\r\n"), Some(204));
+        assert_eq!(parse_status_line(b"HTTP/1.0 302 Found\r\n"), Some(302));
+        assert_eq!(parse_status_line(b"SSH-2.0-OpenSSH\r\n"), None);
+    }
+
+    #[test]
+    #[ignore = "needs network access"]
+    fn test_check() {
+        println!("{:?}", check(None));
+    }
+}
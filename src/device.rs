@@ -26,3 +26,9 @@ impl NetworkDevice {
         }
     }
 }
+
+impl Default for NetworkDevice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
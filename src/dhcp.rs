@@ -0,0 +1,355 @@
+//! DHCPv4 server discovery.
+//!
+//! [`probe`] broadcasts a DHCPDISCOVER out of an interface and collects the
+//! DHCPOFFERs that answer it (RFC 2131). No DHCPREQUEST is ever sent, so no
+//! lease is committed and the interface configuration is left untouched. More
+//! than one offer on a network that should have a single server points to a
+//! rogue DHCP server.
+//!
+//! The client port 68 is privileged on Unix, so probing needs root or
+//! `CAP_NET_BIND_SERVICE`.
+
+use crate::interface::Interface;
+use crate::mac::MacAddr;
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+use std::io;
+use std::mem::MaybeUninit;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// UDP port DHCP servers listen on
+pub const SERVER_PORT: u16 = 67;
+/// UDP port DHCP clients listen on
+pub const CLIENT_PORT: u16 = 68;
+
+const BOOTREQUEST: u8 = 1;
+const BOOTREPLY: u8 = 2;
+const HTYPE_ETHERNET: u8 = 1;
+/// Asks servers to broadcast their reply, as the client has no address yet
+const FLAG_BROADCAST: u16 = 0x8000;
+const MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+/// Offset of the options, after the fixed BOOTP fields and the magic cookie
+const OPTIONS_OFFSET: usize = 240;
+/// Minimum BOOTP message length some servers insist on
+const MIN_MESSAGE_LEN: usize = 300;
+
+const DHCPDISCOVER: u8 = 1;
+const DHCPOFFER: u8 = 2;
+
+const OPT_PAD: u8 = 0;
+const OPT_SUBNET_MASK: u8 = 1;
+const OPT_ROUTER: u8 = 3;
+const OPT_DNS_SERVERS: u8 = 6;
+const OPT_DOMAIN_NAME: u8 = 15;
+const OPT_LEASE_TIME: u8 = 51;
+const OPT_MESSAGE_TYPE: u8 = 53;
+const OPT_SERVER_ID: u8 = 54;
+const OPT_PARAMETER_LIST: u8 = 55;
+const OPT_END: u8 = 255;
+
+/// Option of a DHCP message, as sent on the wire
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DhcpOption {
+    /// Option code
+    pub code: u8,
+    /// Option data
+    pub data: Vec<u8>,
+}
+
+/// DHCPOFFER received from a server
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DhcpOffer {
+    /// Address the offer came from, which is a relay agent if the server is on another subnet
+    pub source: Ipv4Addr,
+    /// Server identifier option, the address of the server
+    pub server_id: Option<Ipv4Addr>,
+    /// Offered address
+    pub offered_addr: Ipv4Addr,
+    /// Subnet mask of the offered address
+    pub subnet_mask: Option<Ipv4Addr>,
+    /// Routers on the subnet
+    pub routers: Vec<Ipv4Addr>,
+    /// DNS servers
+    pub dns_servers: Vec<Ipv4Addr>,
+    /// Domain name
+    pub domain_name: Option<String>,
+    /// Lease time of the offered address
+    pub lease_time: Option<Duration>,
+    /// All options of the offer, in the order they were sent
+    pub options: Vec<DhcpOption>,
+}
+
+impl DhcpOffer {
+    /// Returns the data of option `code`
+    pub fn option(&self, code: u8) -> Option<&[u8]> {
+        self.options
+            .iter()
+            .find(|option| option.code == code)
+            .map(|option| option.data.as_slice())
+    }
+}
+
+fn read_ipv4(buf: &[u8], offset: usize) -> Ipv4Addr {
+    Ipv4Addr::new(
+        buf[offset],
+        buf[offset + 1],
+        buf[offset + 2],
+        buf[offset + 3],
+    )
+}
+
+fn ipv4_list(data: &[u8]) -> Vec<Ipv4Addr> {
+    data.chunks_exact(4)
+        .map(|chunk| read_ipv4(chunk, 0))
+        .collect()
+}
+
+/// Build a DHCPDISCOVER from `mac_addr` with transaction ID `xid`
+fn build_discover(xid: u32, mac_addr: MacAddr) -> Vec<u8> {
+    let mut msg = vec![0u8; OPTIONS_OFFSET];
+    msg[0] = BOOTREQUEST;
+    msg[1] = HTYPE_ETHERNET;
+    msg[2] = 6;
+    msg[4..8].copy_from_slice(&xid.to_be_bytes());
+    msg[10..12].copy_from_slice(&FLAG_BROADCAST.to_be_bytes());
+    msg[28..34].copy_from_slice(&mac_addr.octets());
+    msg[236..240].copy_from_slice(&MAGIC_COOKIE);
+    msg.extend_from_slice(&[OPT_MESSAGE_TYPE, 1, DHCPDISCOVER]);
+    msg.extend_from_slice(&[
+        OPT_PARAMETER_LIST,
+        5,
+        OPT_SUBNET_MASK,
+        OPT_ROUTER,
+        OPT_DNS_SERVERS,
+        OPT_DOMAIN_NAME,
+        OPT_LEASE_TIME,
+    ]);
+    msg.push(OPT_END);
+    msg.resize(MIN_MESSAGE_LEN, OPT_PAD);
+    msg
+}
+
+/// Parse the options of a DHCP message, returning `None` if they are truncated
+fn parse_options(mut buf: &[u8]) -> Option<Vec<DhcpOption>> {
+    let mut options = Vec::new();
+    while let Some((&code, rest)) = buf.split_first() {
+        match code {
+            OPT_PAD => buf = rest,
+            OPT_END => break,
+            _ => {
+                let (&len, rest) = rest.split_first()?;
+                let data = rest.get(..len as usize)?;
+                options.push(DhcpOption {
+                    code,
+                    data: data.to_vec(),
+                });
+                buf = &rest[len as usize..];
+            }
+        }
+    }
+    Some(options)
+}
+
+/// Parse a DHCP message from `source`, returning `None` if it is not an offer
+/// answering transaction `xid` of `mac_addr`
+pub fn parse_offer(buf: &[u8], source: Ipv4Addr, xid: u32, mac_addr: MacAddr) -> Option<DhcpOffer> {
+    if buf.len() < OPTIONS_OFFSET
+        || buf[0] != BOOTREPLY
+        || buf[4..8] != xid.to_be_bytes()
+        || buf[28..34] != mac_addr.octets()
+        || buf[236..240] != MAGIC_COOKIE
+    {
+        return None;
+    }
+    let mut offer = DhcpOffer {
+        source,
+        server_id: None,
+        offered_addr: read_ipv4(buf, 16),
+        subnet_mask: None,
+        routers: Vec::new(),
+        dns_servers: Vec::new(),
+        domain_name: None,
+        lease_time: None,
+        options: parse_options(&buf[OPTIONS_OFFSET..])?,
+    };
+    if offer.option(OPT_MESSAGE_TYPE) != Some(&[DHCPOFFER]) {
+        return None;
+    }
+    for option in &offer.options {
+        let data = option.data.as_slice();
+        match option.code {
+            OPT_SERVER_ID if data.len() == 4 => offer.server_id = Some(read_ipv4(data, 0)),
+            OPT_SUBNET_MASK if data.len() == 4 => offer.subnet_mask = Some(read_ipv4(data, 0)),
+            OPT_ROUTER => offer.routers = ipv4_list(data),
+            OPT_DNS_SERVERS => offer.dns_servers = ipv4_list(data),
+            OPT_DOMAIN_NAME => {
+                offer.domain_name = Some(String::from_utf8_lossy(data).into_owned());
+            }
+            OPT_LEASE_TIME if data.len() == 4 => {
+                let secs = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+                offer.lease_time = Some(Duration::from_secs(secs as u64));
+            }
+            _ => {}
+        }
+    }
+    Some(offer)
+}
+
+/// Bind the socket to the interface so the discover leaves through it and only
+/// its replies are received
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios"
+))]
+fn bind_interface(socket: &Socket, interface: &Interface) -> io::Result<()> {
+    socket.bind_device_by_index_v4(std::num::NonZeroU32::new(interface.index))?;
+    socket.bind(&SockAddr::from(SocketAddrV4::new(
+        Ipv4Addr::UNSPECIFIED,
+        CLIENT_PORT,
+    )))
+}
+
+/// Bind the socket to the address of the interface, which picks the interface
+/// broadcasts are sent out of. Without an address, the routing table decides.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios"
+)))]
+fn bind_interface(socket: &Socket, interface: &Interface) -> io::Result<()> {
+    let addr = interface
+        .ipv4
+        .first()
+        .map(|net| net.addr)
+        .unwrap_or(Ipv4Addr::UNSPECIFIED);
+    socket.bind(&SockAddr::from(SocketAddrV4::new(addr, CLIENT_PORT)))
+}
+
+/// Broadcast a DHCPDISCOVER out of `interface` and collect the offers received
+/// until `timeout` elapses, one per server.
+///
+/// The interface must have a MAC address, which identifies the client.
+pub fn probe(interface: &Interface, timeout: Duration) -> io::Result<Vec<DhcpOffer>> {
+    let mac_addr = interface.mac_addr.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "DHCP probing needs an interface with a MAC address",
+        )
+    })?;
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    socket.set_broadcast(true)?;
+    bind_interface(&socket, interface)?;
+
+    // A fresh xid per probe keeps late offers to an earlier one out
+    let xid = crate::dns::random_u32();
+    let dst = SocketAddrV4::new(Ipv4Addr::BROADCAST, SERVER_PORT);
+    socket.send_to(&build_discover(xid, mac_addr), &SockAddr::from(dst))?;
+
+    let deadline = Instant::now() + timeout;
+    let mut offers: Vec<DhcpOffer> = Vec::new();
+    let mut buf = [MaybeUninit::<u8>::uninit(); 1500];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        socket.set_read_timeout(Some(remaining))?;
+        let (len, src) = match socket.recv_from(&mut buf) {
+            Ok(received) => received,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                break
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        // SAFETY: recv_from initialized the first `len` bytes
+        let msg = unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const u8, len) };
+        let Some(SocketAddr::V4(src)) = src.as_socket() else {
+            continue;
+        };
+        if let Some(offer) = parse_offer(msg, *src.ip(), xid, mac_addr) {
+            // Servers may retransmit their offer
+            offers
+                .retain(|other| (other.server_id, other.source) != (offer.server_id, offer.source));
+            offers.push(offer);
+        }
+    }
+    Ok(offers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_discover() {
+        let mac_addr = MacAddr::new(0x02, 0, 0, 0, 0, 1);
+        let msg = build_discover(0x1234_5678, mac_addr);
+        assert_eq!(msg.len(), MIN_MESSAGE_LEN);
+        assert_eq!(&msg[..4], &[BOOTREQUEST, HTYPE_ETHERNET, 6, 0]);
+        assert_eq!(&msg[4..8], &[0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(&msg[10..12], &[0x80, 0]);
+        assert_eq!(&msg[28..34], &mac_addr.octets());
+        let options = parse_options(&msg[OPTIONS_OFFSET..]).unwrap();
+        assert_eq!(options[0].data, vec![DHCPDISCOVER]);
+        assert_eq!(options[1].code, OPT_PARAMETER_LIST);
+    }
+
+    #[test]
+    fn test_parse_offer() {
+        let mac_addr = MacAddr::new(0x02, 0, 0, 0, 0, 1);
+        let xid = 42;
+        let mut msg = build_discover(xid, mac_addr);
+        msg.truncate(OPTIONS_OFFSET);
+        msg[0] = BOOTREPLY;
+        msg[16..20].copy_from_slice(&[192, 168, 1, 50]);
+        msg.extend_from_slice(&[OPT_MESSAGE_TYPE, 1, DHCPOFFER]);
+        msg.extend_from_slice(&[OPT_SERVER_ID, 4, 192, 168, 1, 1]);
+        msg.extend_from_slice(&[OPT_SUBNET_MASK, 4, 255, 255, 255, 0]);
+        msg.extend_from_slice(&[OPT_ROUTER, 4, 192, 168, 1, 1]);
+        msg.extend_from_slice(&[OPT_DNS_SERVERS, 8, 192, 168, 1, 1, 9, 9, 9, 9]);
+        msg.extend_from_slice(&[OPT_DOMAIN_NAME, 3, b'l', b'a', b'n']);
+        msg.extend_from_slice(&[OPT_LEASE_TIME, 4, 0, 0, 0x0e, 0x10, OPT_PAD, OPT_END]);
+
+        let source = Ipv4Addr::new(192, 168, 1, 1);
+        let offer = parse_offer(&msg, source, xid, mac_addr).unwrap();
+        assert_eq!(offer.server_id, Some(source));
+        assert_eq!(offer.offered_addr, Ipv4Addr::new(192, 168, 1, 50));
+        assert_eq!(offer.subnet_mask, Some(Ipv4Addr::new(255, 255, 255, 0)));
+        assert_eq!(offer.routers, vec![source]);
+        assert_eq!(offer.dns_servers, vec![source, Ipv4Addr::new(9, 9, 9, 9)]);
+        assert_eq!(offer.domain_name.as_deref(), Some("lan"));
+        assert_eq!(offer.lease_time, Some(Duration::from_secs(3600)));
+        assert_eq!(offer.options.len(), 7);
+
+        // Another transaction
+        assert!(parse_offer(&msg, source, xid + 1, mac_addr).is_none());
+        // Truncated option
+        assert!(parse_offer(&msg[..msg.len() - 6], source, xid, mac_addr).is_none());
+        // Acknowledgement of a request
+        msg[OPTIONS_OFFSET + 2] = 5;
+        assert!(parse_offer(&msg, source, xid, mac_addr).is_none());
+    }
+
+    #[test]
+    #[ignore = "broadcasts DHCP discovers on the local network"]
+    fn test_probe() {
+        if let Ok(interface) = crate::get_default_interface() {
+            println!("{:?}", probe(&interface, Duration::from_secs(1)));
+        }
+    }
+}
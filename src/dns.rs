@@ -0,0 +1,159 @@
+//! Minimal DNS message encoding shared by the connectivity probe, mDNS and
+//! reverse resolution.
+
+pub(crate) const DNS_TYPE_A: u16 = 1;
+pub(crate) const DNS_TYPE_PTR: u16 = 12;
+pub(crate) const DNS_TYPE_AAAA: u16 = 28;
+/// Standard query with recursion desired
+pub(crate) const FLAGS_RECURSION_DESIRED: u16 = 0x0100;
+/// Response code of a query for a name that does not exist
+pub(crate) const RCODE_NXDOMAIN: u16 = 3;
+
+/// Resource record from the answer section of a response
+#[derive(Clone, Debug)]
+pub(crate) struct Record {
+    pub name: String,
+    pub rtype: u16,
+    pub rdata: Vec<u8>,
+    /// Offset of the data in the message, to decode names it holds
+    pub rdata_pos: usize,
+}
+
+/// Build a query with one question of class IN
+pub(crate) fn build_query(id: u16, host: &str, qtype: u16, flags: u16) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(18 + host.len());
+    buf.extend_from_slice(&id.to_be_bytes());
+    buf.extend_from_slice(&flags.to_be_bytes());
+    // One question, no other records.
+    buf.extend_from_slice(&[0x00, 0x01, 0, 0, 0, 0, 0, 0]);
+    for label in host.trim_end_matches('.').split('.') {
+        buf.push(label.len() as u8);
+        buf.extend_from_slice(label.as_bytes());
+    }
+    buf.push(0);
+    buf.extend_from_slice(&qtype.to_be_bytes());
+    buf.extend_from_slice(&1u16.to_be_bytes());
+    buf
+}
+
+/// Random id for a query sent to a server that may be off-link, so that
+/// its answer cannot be guessed by someone else
+pub(crate) fn random_id() -> u16 {
+    random_u32() as u16
+}
+
+/// Random number from the same source as [`random_id`], for the wider
+/// transaction ids of other protocols
+pub(crate) fn random_u32() -> u32 {
+    use std::hash::{BuildHasher, Hasher};
+    // The hasher of a new RandomState is keyed with random seeds
+    std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish() as u32
+}
+
+/// Returns the name and type of the first question of `buf`
+pub(crate) fn question(buf: &[u8]) -> Option<(String, u16)> {
+    if read_u16(buf, 4)? == 0 {
+        return None;
+    }
+    let name = read_name(buf, 12)?;
+    let qtype = read_u16(buf, skip_name(buf, 12)?)?;
+    Some((name, qtype))
+}
+
+pub(crate) fn read_u16(buf: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*buf.get(pos)?, *buf.get(pos + 1)?]))
+}
+
+fn skip_name(buf: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let len = *buf.get(pos)? as usize;
+        if len == 0 {
+            return Some(pos + 1);
+        }
+        if len & 0xc0 == 0xc0 {
+            // Compression pointer
+            return Some(pos + 2);
+        }
+        pos += len + 1;
+    }
+}
+
+/// Decode the name at `pos`, following compression pointers
+pub(crate) fn read_name(buf: &[u8], mut pos: usize) -> Option<String> {
+    let mut labels = Vec::new();
+    // Each pointer must go backwards, which rules out loops
+    let mut limit = pos;
+    loop {
+        let len = *buf.get(pos)? as usize;
+        if len == 0 {
+            return Some(labels.join("."));
+        }
+        if len & 0xc0 == 0xc0 {
+            let target = (read_u16(buf, pos)? & 0x3fff) as usize;
+            if target >= limit {
+                return None;
+            }
+            limit = target;
+            pos = target;
+            continue;
+        }
+        let label = buf.get(pos + 1..pos + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        pos += len + 1;
+    }
+}
+
+/// Parse a successful response to the query with the given id, returning its answers
+pub(crate) fn parse_answers(buf: &[u8], id: u16) -> Option<Vec<Record>> {
+    if read_u16(buf, 0)? != id {
+        return None;
+    }
+    let flags = read_u16(buf, 2)?;
+    // Must be a response with RCODE 0
+    if flags & 0x8000 == 0 || flags & 0x000f != 0 {
+        return None;
+    }
+    let qdcount = read_u16(buf, 4)?;
+    let ancount = read_u16(buf, 6)?;
+    let mut pos = 12;
+    for _ in 0..qdcount {
+        pos = skip_name(buf, pos)? + 4;
+    }
+    let mut records = Vec::with_capacity(ancount as usize);
+    for _ in 0..ancount {
+        let name = read_name(buf, pos)?;
+        pos = skip_name(buf, pos)?;
+        let rtype = read_u16(buf, pos)?;
+        let rdlen = read_u16(buf, pos + 8)? as usize;
+        let rdata = buf.get(pos + 10..pos + 10 + rdlen)?;
+        pos += 10 + rdlen;
+        records.push(Record {
+            name,
+            rtype,
+            rdata: rdata.to_vec(),
+            rdata_pos: pos - rdlen,
+        });
+    }
+    Some(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_name() {
+        let mut buf = build_query(1, "host.local", DNS_TYPE_A, 0);
+        assert_eq!(read_name(&buf, 12).as_deref(), Some("host.local"));
+        // "www" followed by a pointer to the question name
+        let pos = buf.len();
+        buf.extend_from_slice(&[3, b'w', b'w', b'w', 0xc0, 0x0c]);
+        assert_eq!(read_name(&buf, pos).as_deref(), Some("www.host.local"));
+        // A pointer to itself is rejected
+        let pos = buf.len();
+        buf.extend_from_slice(&(0xc000 | pos as u16).to_be_bytes());
+        assert_eq!(read_name(&buf, pos), None);
+    }
+}
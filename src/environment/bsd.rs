@@ -0,0 +1,83 @@
+#[cfg(target_os = "freebsd")]
+use super::ContainerKind;
+use super::Environment;
+#[cfg(target_os = "freebsd")]
+use crate::interface::HypervisorKind;
+use std::ffi::CString;
+use std::io;
+
+fn sysctl<T: Copy>(name: &str, value: &mut [T]) -> io::Result<usize> {
+    let name = CString::new(name).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+    let mut len = std::mem::size_of_val(value);
+    let ret = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            value.as_mut_ptr() as *mut libc::c_void,
+            &mut len,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(len)
+}
+
+/// `kern.hv_vmm_present` is 1 inside a virtual machine
+#[cfg(target_os = "macos")]
+pub fn detect_environment() -> Environment {
+    let mut present: [libc::c_int; 1] = [0];
+    match sysctl("kern.hv_vmm_present", &mut present) {
+        Ok(_) if present[0] != 0 => Environment::VirtualMachine(None),
+        Ok(_) => Environment::BareMetal,
+        Err(_) => Environment::Unknown,
+    }
+}
+
+/// Map the `kern.vm_guest` names, `none` meaning bare metal
+#[cfg(target_os = "freebsd")]
+fn parse_vm_guest(vm_guest: &str) -> Environment {
+    let kind = match vm_guest {
+        "none" => return Environment::BareMetal,
+        "vmware" => Some(HypervisorKind::Vmware),
+        "hv" => Some(HypervisorKind::HyperV),
+        "kvm" => Some(HypervisorKind::Kvm),
+        "xen" => Some(HypervisorKind::Xen),
+        "vbox" => Some(HypervisorKind::VirtualBox),
+        "parallels" => Some(HypervisorKind::Parallels),
+        _ => None,
+    };
+    Environment::VirtualMachine(kind)
+}
+
+#[cfg(target_os = "freebsd")]
+pub fn detect_environment() -> Environment {
+    if crate::jail::current().is_ok_and(|jail| jail.jailed) {
+        return Environment::Container(ContainerKind::Jail);
+    }
+    let mut buf = [0u8; 32];
+    match sysctl("kern.vm_guest", &mut buf) {
+        Ok(len) => {
+            let value = &buf[..len];
+            let value = value.split(|b| *b == 0).next().unwrap_or_default();
+            parse_vm_guest(&String::from_utf8_lossy(value))
+        }
+        Err(_) => Environment::Unknown,
+    }
+}
+
+#[cfg(all(test, target_os = "freebsd"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vm_guest() {
+        assert_eq!(parse_vm_guest("none"), Environment::BareMetal);
+        assert_eq!(
+            parse_vm_guest("hv"),
+            Environment::VirtualMachine(Some(HypervisorKind::HyperV))
+        );
+        assert_eq!(parse_vm_guest("bhyve"), Environment::VirtualMachine(None));
+    }
+}
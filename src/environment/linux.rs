@@ -0,0 +1,179 @@
+use super::{ContainerKind, Environment};
+use crate::interface::HypervisorKind;
+use std::env;
+use std::fs::read_to_string;
+use std::path::Path;
+
+/// Returns the WSL version from the kernel release, such as
+/// `5.15.153.1-microsoft-standard-WSL2`. WSL 1 reports `4.4.0-19041-Microsoft`.
+fn parse_wsl_version(osrelease: &str) -> Option<u8> {
+    let osrelease = osrelease.trim();
+    if osrelease.to_ascii_lowercase().contains("wsl2") {
+        Some(2)
+    } else if osrelease.ends_with("-Microsoft") {
+        Some(1)
+    } else if osrelease.contains("microsoft") {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// Recognize the runtime by the control groups of process 1
+fn parse_cgroup(cgroup: &str) -> Option<ContainerKind> {
+    if cgroup.contains("kubepods") {
+        Some(ContainerKind::Kubernetes)
+    } else if cgroup.contains("docker") {
+        Some(ContainerKind::Docker)
+    } else if cgroup.contains("libpod") {
+        Some(ContainerKind::Podman)
+    } else if cgroup.contains("/lxc") {
+        Some(ContainerKind::Lxc)
+    } else {
+        None
+    }
+}
+
+/// Recognize the runtime by the `container` variable set by systemd-aware runtimes
+fn parse_container_var(value: &str) -> ContainerKind {
+    match value {
+        "docker" => ContainerKind::Docker,
+        "podman" => ContainerKind::Podman,
+        "lxc" | "lxc-libvirt" => ContainerKind::Lxc,
+        _ => ContainerKind::Other,
+    }
+}
+
+fn detect_container() -> Option<ContainerKind> {
+    // Kubernetes injects the API address into every pod, whatever the runtime
+    if env::var_os("KUBERNETES_SERVICE_HOST").is_some() {
+        return Some(ContainerKind::Kubernetes);
+    }
+    if Path::new("/.dockerenv").exists() {
+        return Some(ContainerKind::Docker);
+    }
+    if Path::new("/run/.containerenv").exists() {
+        return Some(ContainerKind::Podman);
+    }
+    // Only set for process 1, so read its environment as well
+    let init_env = std::fs::read("/proc/1/environ").unwrap_or_default();
+    let container = env::var("container").ok().or_else(|| {
+        init_env
+            .split(|b| *b == 0)
+            .find_map(|var| var.strip_prefix(b"container="))
+            .map(|value| String::from_utf8_lossy(value).into_owned())
+    });
+    if let Some(container) = container {
+        return Some(parse_container_var(&container));
+    }
+    parse_cgroup(&read_to_string("/proc/1/cgroup").unwrap_or_default())
+}
+
+/// Recognize the hypervisor by the DMI system vendor and product name
+fn parse_dmi(sys_vendor: &str, product_name: &str) -> Option<HypervisorKind> {
+    let vendor = sys_vendor.trim();
+    let product = product_name.trim();
+    if vendor.starts_with("VMware") {
+        Some(HypervisorKind::Vmware)
+    } else if product == "VirtualBox" || vendor == "innotek GmbH" {
+        Some(HypervisorKind::VirtualBox)
+    } else if vendor.starts_with("Parallels") {
+        Some(HypervisorKind::Parallels)
+    } else if vendor == "Microsoft Corporation" && product == "Virtual Machine" {
+        Some(HypervisorKind::HyperV)
+    } else if vendor == "Xen" || product == "HVM domU" {
+        Some(HypervisorKind::Xen)
+    } else if vendor == "QEMU" || product.contains("KVM") || product.starts_with("Standard PC") {
+        Some(HypervisorKind::Kvm)
+    } else {
+        None
+    }
+}
+
+fn has_hypervisor_flag(cpuinfo: &str) -> bool {
+    cpuinfo
+        .lines()
+        .filter(|line| line.starts_with("flags"))
+        .any(|line| line.split_whitespace().any(|flag| flag == "hypervisor"))
+}
+
+pub fn detect_environment() -> Environment {
+    if let Some(kind) = detect_container() {
+        return Environment::Container(kind);
+    }
+    if let Some(version) = read_to_string("/proc/sys/kernel/osrelease")
+        .ok()
+        .and_then(|osrelease| parse_wsl_version(&osrelease))
+    {
+        return Environment::Wsl(version);
+    }
+    let dmi =
+        |field: &str| read_to_string(format!("/sys/class/dmi/id/{}", field)).unwrap_or_default();
+    if let Some(kind) = parse_dmi(&dmi("sys_vendor"), &dmi("product_name")) {
+        return Environment::VirtualMachine(Some(kind));
+    }
+    if has_hypervisor_flag(&read_to_string("/proc/cpuinfo").unwrap_or_default()) {
+        return Environment::VirtualMachine(None);
+    }
+    Environment::BareMetal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wsl_version() {
+        assert_eq!(
+            parse_wsl_version("5.15.153.1-microsoft-standard-WSL2\n"),
+            Some(2)
+        );
+        assert_eq!(parse_wsl_version("4.19.128-microsoft-standard"), Some(2));
+        assert_eq!(parse_wsl_version("4.4.0-19041-Microsoft"), Some(1));
+        assert_eq!(parse_wsl_version("6.8.0-45-generic"), None);
+    }
+
+    #[test]
+    fn test_parse_cgroup() {
+        assert_eq!(
+            parse_cgroup("0::/kubepods/besteffort/pod1234/abcd\n"),
+            Some(ContainerKind::Kubernetes)
+        );
+        assert_eq!(
+            parse_cgroup("12:pids:/docker/0123456789ab\n"),
+            Some(ContainerKind::Docker)
+        );
+        assert_eq!(
+            parse_cgroup("0::/machine.slice/libpod-0123.scope\n"),
+            Some(ContainerKind::Podman)
+        );
+        assert_eq!(parse_cgroup("0::/init.scope\n"), None);
+        assert_eq!(parse_container_var("systemd-nspawn"), ContainerKind::Other);
+    }
+
+    #[test]
+    fn test_parse_dmi() {
+        assert_eq!(
+            parse_dmi("VMware, Inc.\n", "VMware Virtual Platform\n"),
+            Some(HypervisorKind::Vmware)
+        );
+        assert_eq!(
+            parse_dmi("innotek GmbH", "VirtualBox"),
+            Some(HypervisorKind::VirtualBox)
+        );
+        assert_eq!(
+            parse_dmi("Microsoft Corporation", "Virtual Machine"),
+            Some(HypervisorKind::HyperV)
+        );
+        assert_eq!(
+            parse_dmi("QEMU", "Standard PC (Q35 + ICH9, 2009)"),
+            Some(HypervisorKind::Kvm)
+        );
+        assert_eq!(parse_dmi("Microsoft Corporation", "Surface Laptop 5"), None);
+        assert_eq!(parse_dmi("Dell Inc.", "XPS 13 9310"), None);
+        assert!(has_hypervisor_flag(
+            "flags\t\t: fpu vme de hypervisor lahf_lm\n"
+        ));
+        assert!(!has_hypervisor_flag("flags\t\t: fpu vme de lahf_lm\n"));
+    }
+}
@@ -0,0 +1,113 @@
+//! Detection of the environment the process runs in.
+//!
+//! Network tools see a very different picture inside WSL 2, where `eth0` sits
+//! behind a NAT of the Windows host, inside a container, where the interfaces
+//! belong to a network namespace of the host, or inside a virtual machine.
+//! [`detect`] tells these apart so callers can explain results or adjust
+//! heuristics. Inside WSL 2, [`wsl_host_interfaces`] lists the addresses of
+//! the Windows host through the WSL interop layer (`serde_json` feature).
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod linux;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use self::linux::*;
+
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+mod bsd;
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+use self::bsd::*;
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "freebsd"
+)))]
+mod unsupported;
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "freebsd"
+)))]
+use self::unsupported::*;
+
+#[cfg(all(target_os = "linux", feature = "serde_json"))]
+mod wsl;
+#[cfg(all(target_os = "linux", feature = "serde_json"))]
+pub use self::wsl::*;
+
+use crate::interface::HypervisorKind;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Container runtime, as far as it can be told from inside
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum ContainerKind {
+    /// Docker
+    Docker,
+    /// Podman
+    Podman,
+    /// A pod of Kubernetes, whatever its runtime
+    Kubernetes,
+    /// LXC or LXD
+    Lxc,
+    /// A FreeBSD jail
+    Jail,
+    /// Another runtime, such as systemd-nspawn
+    Other,
+}
+
+/// Environment the process runs in, as returned by [`detect`]
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum Environment {
+    /// Directly on the hardware
+    BareMetal,
+    /// In a virtual machine, of the given hypervisor if it is known
+    VirtualMachine(Option<HypervisorKind>),
+    /// In a container. The machine running it may itself be virtual.
+    Container(ContainerKind),
+    /// In the Windows Subsystem for Linux, version 1 or 2
+    Wsl(u8),
+    /// On a platform where the environment cannot be told
+    Unknown,
+}
+
+impl Environment {
+    /// Check if the interfaces seen by the process are not those of the
+    /// machine's network adapters, as in containers and WSL 2
+    pub fn is_isolated(&self) -> bool {
+        matches!(self, Environment::Container(_) | Environment::Wsl(2))
+    }
+}
+
+/// Detect the environment the process runs in.
+///
+/// On Linux, containers are recognized by the marker files and cgroups of the
+/// common runtimes, WSL by the kernel release, and virtual machines by the
+/// DMI vendor strings and the `hypervisor` CPU flag. macOS and FreeBSD ask the
+/// kernel whether a hypervisor is present, and FreeBSD also reports jails.
+/// Other platforms return [`Environment::Unknown`].
+pub fn detect() -> Environment {
+    detect_environment()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let environment = detect();
+        println!("{:?}", environment);
+        assert_eq!(
+            environment.is_isolated(),
+            matches!(environment, Environment::Container(_) | Environment::Wsl(2))
+        );
+    }
+}
@@ -0,0 +1,5 @@
+use super::Environment;
+
+pub fn detect_environment() -> Environment {
+    Environment::Unknown
+}
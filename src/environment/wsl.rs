@@ -0,0 +1,132 @@
+use crate::interface::Interface;
+use serde::Deserialize;
+use std::io;
+use std::net::IpAddr;
+use std::path::Path;
+use std::process::Command;
+
+/// Registered by WSL when Windows executables can be run from Linux
+const INTEROP_PATHS: [&str; 2] = [
+    "/proc/sys/fs/binfmt_misc/WSLInterop",
+    "/proc/sys/fs/binfmt_misc/WSLInterop-late",
+];
+/// Used when the Windows directories are not appended to `PATH`
+const POWERSHELL_PATH: &str = "/mnt/c/Windows/System32/WindowsPowerShell/v1.0/powershell.exe";
+/// `@()` keeps a single address an array
+const GET_ADDRESSES: &str = "ConvertTo-Json -Compress -InputObject @(Get-NetIPAddress | \
+    Select-Object InterfaceIndex,InterfaceAlias,IPAddress,PrefixLength)";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AddressEntry {
+    interface_index: u32,
+    interface_alias: String,
+    #[serde(rename = "IPAddress")]
+    ip_address: String,
+    prefix_length: u8,
+}
+
+fn parse_addresses(json: &str) -> io::Result<Vec<Interface>> {
+    let entries: Vec<AddressEntry> = serde_json::from_str(json.trim())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut interfaces: Vec<Interface> = Vec::new();
+    for entry in entries {
+        // Link-local addresses carry their zone, as in `fe80::1%12`
+        let addr = entry.ip_address.split('%').next().unwrap_or_default();
+        let Ok(addr) = addr.parse::<IpAddr>() else {
+            continue;
+        };
+        let position = interfaces
+            .iter()
+            .position(|interface| interface.index == entry.interface_index);
+        let interface = match position {
+            Some(i) => &mut interfaces[i],
+            None => {
+                interfaces.push(
+                    Interface::builder()
+                        .index(entry.interface_index)
+                        .name(&entry.interface_alias)
+                        .friendly_name(&entry.interface_alias)
+                        .build(),
+                );
+                interfaces.last_mut().unwrap()
+            }
+        };
+        match addr {
+            IpAddr::V4(addr) => interface
+                .ipv4
+                .push(crate::ip::Ipv4Net::new(addr, entry.prefix_length.min(32))),
+            IpAddr::V6(addr) => interface
+                .ipv6
+                .push(crate::ip::Ipv6Net::new(addr, entry.prefix_length.min(128))),
+        }
+    }
+    interfaces.sort_by_key(|interface| interface.index);
+    Ok(interfaces)
+}
+
+/// List the addresses of the Windows host from inside WSL, by running
+/// `Get-NetIPAddress` in PowerShell through the WSL interop layer. Takes about
+/// a second, as PowerShell has to start.
+///
+/// The interfaces only have their index, alias and addresses filled in, as
+/// `name` and `friendly_name`. Fails with `Unsupported` outside WSL or if
+/// interop is disabled in `/etc/wsl.conf`.
+pub fn wsl_host_interfaces() -> io::Result<Vec<Interface>> {
+    if !INTEROP_PATHS.iter().any(|path| Path::new(path).exists()) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "WSL interop is not available",
+        ));
+    }
+    let run = |program: &str| {
+        Command::new(program)
+            .args(["-NoProfile", "-NonInteractive", "-Command", GET_ADDRESSES])
+            .output()
+    };
+    let output = match run("powershell.exe") {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => run(POWERSHELL_PATH)?,
+        result => result?,
+    };
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "powershell.exe failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    parse_addresses(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_addresses() {
+        let json = r#"[{"InterfaceIndex":12,"InterfaceAlias":"Wi-Fi","IPAddress":"fe80::1c2a:3b4c:5d6e:7f80%12","PrefixLength":64},
+            {"InterfaceIndex":12,"InterfaceAlias":"Wi-Fi","IPAddress":"192.168.1.20","PrefixLength":24},
+            {"InterfaceIndex":1,"InterfaceAlias":"Loopback Pseudo-Interface 1","IPAddress":"127.0.0.1","PrefixLength":8}]
+"#;
+        let interfaces = parse_addresses(json).unwrap();
+        assert_eq!(interfaces.len(), 2);
+        assert_eq!(interfaces[0].index, 1);
+        let wifi = &interfaces[1];
+        assert_eq!(wifi.friendly_name.as_deref(), Some("Wi-Fi"));
+        assert_eq!(wifi.ipv4[0].prefix_len, 24);
+        assert_eq!(
+            wifi.ipv6[0].addr,
+            "fe80::1c2a:3b4c:5d6e:7f80"
+                .parse::<std::net::Ipv6Addr>()
+                .unwrap()
+        );
+        assert!(parse_addresses("not json").is_err());
+    }
+
+    #[test]
+    fn test_wsl_host_interfaces() {
+        println!(
+            "{:?}",
+            wsl_host_interfaces().map(|interfaces| interfaces.len())
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Errors carrying the OS call that failed.
+//!
+//! Functions returning [`io::Result`] wrap OS failures in an [`Error`], which
+//! keeps the [`io::ErrorKind`] and the OS error code (errno, or the Win32
+//! error on Windows) and adds the name of the call and the interface it was
+//! made for. Recover it with [`Error::from_io`]:
+//!
+//! ```no_run
+//! let interface = netdev::get_default_interface().unwrap();
+//! if let Err(err) = interface.offload_info() {
+//!     match netdev::Error::from_io(&err) {
+//!         Some(err) => println!("{} failed: {:?}", err.call(), err.raw_os_error()),
+//!         None => println!("{}", err),
+//!     }
+//! }
+//! ```
+
+use std::error;
+use std::fmt;
+use std::io;
+
+/// Failure of an OS call
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A system call or OS API function failed
+    Syscall {
+        /// Name of the call, such as `getifaddrs` or `ioctl(SIOCETHTOOL)`
+        call: &'static str,
+        /// Name of the interface being processed, if any
+        interface: Option<String>,
+        /// The OS error
+        source: io::Error,
+    },
+}
+
+impl Error {
+    /// Create an error for `call` failing with `source`
+    pub fn syscall(call: &'static str, source: io::Error) -> Error {
+        Error::Syscall {
+            call,
+            interface: None,
+            source,
+        }
+    }
+    /// Create an error for `call` failing with the last OS error of this thread
+    pub fn last_os_error(call: &'static str) -> Error {
+        Error::syscall(call, io::Error::last_os_error())
+    }
+    /// Record the interface being processed
+    pub fn on(mut self, if_name: &str) -> Error {
+        match &mut self {
+            Error::Syscall { interface, .. } => *interface = Some(if_name.to_string()),
+        }
+        self
+    }
+    /// Returns the name of the call that failed
+    pub fn call(&self) -> &'static str {
+        match self {
+            Error::Syscall { call, .. } => call,
+        }
+    }
+    /// Returns the name of the interface being processed
+    pub fn interface(&self) -> Option<&str> {
+        match self {
+            Error::Syscall { interface, .. } => interface.as_deref(),
+        }
+    }
+    /// Returns the OS error code
+    pub fn raw_os_error(&self) -> Option<i32> {
+        match self {
+            Error::Syscall { source, .. } => source.raw_os_error(),
+        }
+    }
+    /// Returns the kind of the OS error
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Error::Syscall { source, .. } => source.kind(),
+        }
+    }
+    /// Returns the error wrapped in `err`, if it is one
+    pub fn from_io(err: &io::Error) -> Option<&Error> {
+        err.get_ref()?.downcast_ref()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Syscall {
+                call,
+                interface: Some(interface),
+                source,
+            } => write!(f, "{} on {} failed: {}", call, interface, source),
+            Error::Syscall {
+                call,
+                interface: None,
+                source,
+            } => write!(f, "{} failed: {}", call, source),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Syscall { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> io::Error {
+        io::Error::new(err.kind(), err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error() {
+        let err = Error::syscall("ioctl(SIOCGIFMTU)", io::Error::from_raw_os_error(19)).on("eth9");
+        assert_eq!(err.interface(), Some("eth9"));
+        assert!(err
+            .to_string()
+            .starts_with("ioctl(SIOCGIFMTU) on eth9 failed: "));
+        let io_err: io::Error = err.into();
+        let err = Error::from_io(&io_err).unwrap();
+        assert_eq!(err.call(), "ioctl(SIOCGIFMTU)");
+        assert_eq!(err.raw_os_error(), Some(19));
+        assert_eq!(io_err.kind(), err.kind());
+        assert!(Error::from_io(&io::Error::other("plain")).is_none());
+    }
+}
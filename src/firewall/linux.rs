@@ -0,0 +1,122 @@
+use super::{FirewallZone, NetworkCategory, ZoneSource};
+use crate::interface::Interface;
+use std::collections::HashMap;
+use std::io;
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+
+const FIREWALLD_SERVICE: &str = "org.fedoraproject.FirewallD1";
+const FIREWALLD_PATH: &str = "/org/fedoraproject/FirewallD1";
+const FIREWALLD: &str = "org.fedoraproject.FirewallD1";
+const FIREWALLD_ZONE: &str = "org.fedoraproject.FirewallD1.zone";
+
+const NM_SERVICE: &str = "org.freedesktop.NetworkManager";
+const NM_PATH: &str = "/org/freedesktop/NetworkManager";
+const NM: &str = "org.freedesktop.NetworkManager";
+const NM_DEVICE: &str = "org.freedesktop.NetworkManager.Device";
+const NM_ACTIVE_CONNECTION: &str = "org.freedesktop.NetworkManager.Connection.Active";
+const NM_SETTINGS_CONNECTION: &str = "org.freedesktop.NetworkManager.Settings.Connection";
+
+/// D-Bus errors meaning the service is not running or does not know the interface
+const NOT_MANAGED_ERRORS: [&str; 2] = [
+    "org.freedesktop.DBus.Error.ServiceUnknown",
+    "org.freedesktop.NetworkManager.UnknownDevice",
+];
+
+fn dbus_error(err: zbus::Error) -> io::Error {
+    match err {
+        zbus::Error::InputOutput(err) => io::Error::new(err.kind(), err.to_string()),
+        err => io::Error::other(err),
+    }
+}
+
+fn is_not_managed(err: &zbus::Error) -> bool {
+    match err {
+        zbus::Error::MethodError(name, _, _) => NOT_MANAGED_ERRORS.contains(&name.as_str()),
+        zbus::Error::FDO(err) => matches!(**err, zbus::fdo::Error::ServiceUnknown(_)),
+        _ => false,
+    }
+}
+
+/// Category of a built-in firewalld zone
+fn zone_category(name: &str) -> Option<NetworkCategory> {
+    match name {
+        "drop" | "block" | "public" | "external" | "dmz" => Some(NetworkCategory::Public),
+        "work" | "home" | "internal" | "trusted" => Some(NetworkCategory::Private),
+        _ => None,
+    }
+}
+
+fn new_zone(name: String, default: bool, source: ZoneSource) -> FirewallZone {
+    FirewallZone {
+        category: zone_category(&name),
+        name,
+        default,
+        source,
+    }
+}
+
+fn firewalld_zone(conn: &Connection, if_name: &str) -> zbus::Result<FirewallZone> {
+    let zones = Proxy::new(conn, FIREWALLD_SERVICE, FIREWALLD_PATH, FIREWALLD_ZONE)?;
+    let name: String = zones.call("getZoneOfInterface", &(if_name,))?;
+    if !name.is_empty() {
+        return Ok(new_zone(name, false, ZoneSource::Firewalld));
+    }
+    // Interfaces not bound to a zone are handled by the default zone
+    let firewalld = Proxy::new(conn, FIREWALLD_SERVICE, FIREWALLD_PATH, FIREWALLD)?;
+    let name: String = firewalld.call("getDefaultZone", &())?;
+    Ok(new_zone(name, true, ZoneSource::Firewalld))
+}
+
+fn network_manager_zone(conn: &Connection, if_name: &str) -> zbus::Result<Option<FirewallZone>> {
+    let nm = Proxy::new(conn, NM_SERVICE, NM_PATH, NM)?;
+    let device: OwnedObjectPath = nm.call("GetDeviceByIpIface", &(if_name,))?;
+    let device = Proxy::new(conn, NM_SERVICE, device.as_ref(), NM_DEVICE)?;
+    let active: OwnedObjectPath = device.get_property("ActiveConnection")?;
+    if active.as_str() == "/" {
+        return Ok(None);
+    }
+    let active = Proxy::new(conn, NM_SERVICE, active.as_ref(), NM_ACTIVE_CONNECTION)?;
+    let connection: OwnedObjectPath = active.get_property("Connection")?;
+    let connection = Proxy::new(
+        conn,
+        NM_SERVICE,
+        connection.as_ref(),
+        NM_SETTINGS_CONNECTION,
+    )?;
+    let settings: HashMap<String, HashMap<String, OwnedValue>> =
+        connection.call("GetSettings", &())?;
+    let name = settings
+        .get("connection")
+        .and_then(|connection| connection.get("zone"))
+        .and_then(|zone| zone.try_clone().ok())
+        .and_then(|zone| String::try_from(zone).ok())
+        .filter(|zone| !zone.is_empty());
+    Ok(name.map(|name| new_zone(name, false, ZoneSource::NetworkManager)))
+}
+
+pub fn get_zone(interface: &Interface) -> io::Result<Option<FirewallZone>> {
+    let conn = Connection::system().map_err(dbus_error)?;
+    match firewalld_zone(&conn, &interface.name) {
+        Ok(zone) => return Ok(Some(zone)),
+        Err(err) if is_not_managed(&err) => {}
+        Err(err) => return Err(dbus_error(err)),
+    }
+    match network_manager_zone(&conn, &interface.name) {
+        Ok(zone) => Ok(zone),
+        Err(err) if is_not_managed(&err) => Ok(None),
+        Err(err) => Err(dbus_error(err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zone_category() {
+        assert_eq!(zone_category("public"), Some(NetworkCategory::Public));
+        assert_eq!(zone_category("home"), Some(NetworkCategory::Private));
+        assert_eq!(zone_category("libvirt"), None);
+    }
+}
@@ -0,0 +1,100 @@
+//! Firewall zone and network category of interfaces.
+//!
+//! [`zone`] tells how much the network behind an interface is trusted, so an
+//! application can decide whether to open a listener on it. On Linux this is
+//! the firewalld zone, or the zone NetworkManager assigned to the connection
+//! when firewalld is not running, both queried over the system D-Bus. On
+//! Windows it is the category of the network profile (Public, Private or
+//! Domain) from the Network List Manager.
+//!
+//! ```no_run
+//! use netdev::firewall::{self, NetworkCategory};
+//!
+//! let interface = netdev::get_default_interface().unwrap();
+//! match firewall::zone(&interface) {
+//!     Ok(Some(zone)) if zone.category == Some(NetworkCategory::Public) => {
+//!         println!("{} is on an untrusted network", interface.name);
+//!     }
+//!     Ok(zone) => println!("{}: {:?}", interface.name, zone),
+//!     Err(e) => println!("zone unknown: {}", e),
+//! }
+//! ```
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+use self::linux::*;
+
+#[cfg(target_os = "windows")]
+mod windows;
+#[cfg(target_os = "windows")]
+use self::windows::*;
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+mod unsupported;
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+use self::unsupported::*;
+
+use crate::interface::Interface;
+use std::io;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How much a network is trusted
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NetworkCategory {
+    /// Untrusted network, such as a café hotspot
+    Public,
+    /// Trusted network, such as a home or work network
+    Private,
+    /// Network of an Active Directory domain the host authenticated to
+    Domain,
+}
+
+/// Where a [`FirewallZone`] was read from
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ZoneSource {
+    /// firewalld
+    Firewalld,
+    /// The `connection.zone` setting of the active NetworkManager connection
+    NetworkManager,
+    /// The network profile of the Windows Network List Manager
+    NetworkList,
+}
+
+/// Firewall zone of an interface, as returned by [`zone`]
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FirewallZone {
+    /// Name of the zone, e.g. `public` or `home`. On Windows, the name of the network.
+    pub name: String,
+    /// Category of the zone. `None` for custom firewalld zones.
+    pub category: Option<NetworkCategory>,
+    /// firewalld applies its default zone because none is assigned to the interface
+    pub default: bool,
+    /// Where the zone was read from
+    pub source: ZoneSource,
+}
+
+/// Get the firewall zone of the interface.
+///
+/// Returns `Ok(None)` if no zone applies, e.g. when neither firewalld nor
+/// NetworkManager manages the interface, or Windows has no network profile for it.
+pub fn zone(interface: &Interface) -> io::Result<Option<FirewallZone>> {
+    get_zone(interface)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zone() {
+        for interface in crate::get_interfaces() {
+            println!("{}: {:?}", interface.name, zone(&interface));
+        }
+    }
+}
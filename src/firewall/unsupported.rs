@@ -0,0 +1,10 @@
+use super::FirewallZone;
+use crate::interface::Interface;
+use std::io;
+
+pub fn get_zone(_interface: &Interface) -> io::Result<Option<FirewallZone>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Firewall zones are not supported on this platform",
+    ))
+}
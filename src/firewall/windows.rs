@@ -0,0 +1,211 @@
+use super::{FirewallZone, NetworkCategory, ZoneSource};
+use crate::interface::Interface;
+use std::ffi::c_void;
+use std::marker::PhantomData;
+use std::{io, ptr};
+use windows_sys::core::{BSTR, GUID, HRESULT};
+use windows_sys::Win32::Foundation::{SysFreeString, SysStringLen, RPC_E_CHANGED_MODE};
+use windows_sys::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_ALL, COINIT_MULTITHREADED,
+};
+
+// The Network List Manager is a COM API that windows-sys has no bindings for,
+// so the parts used here are declared by hand following netlistmgr.h.
+const CLSID_NETWORK_LIST_MANAGER: GUID = GUID::from_u128(0xdcb00c01_570f_4a9b_8d69_199fdba5723b);
+const IID_INETWORK_LIST_MANAGER: GUID = GUID::from_u128(0xdcb00000_570f_4a9b_8d69_199fdba5723b);
+
+// NLM_NETWORK_CATEGORY
+const NLM_NETWORK_CATEGORY_PUBLIC: i32 = 0;
+const NLM_NETWORK_CATEGORY_PRIVATE: i32 = 1;
+const NLM_NETWORK_CATEGORY_DOMAIN_AUTHENTICATED: i32 = 2;
+
+/// Slot of a method that is not called
+type Unused = usize;
+
+#[repr(C)]
+struct IUnknownVtbl {
+    query_interface: Unused,
+    add_ref: Unused,
+    release: unsafe extern "system" fn(*mut c_void) -> u32,
+}
+
+#[repr(C)]
+struct IDispatchVtbl {
+    unknown: IUnknownVtbl,
+    // GetTypeInfoCount, GetTypeInfo, GetIDsOfNames, Invoke
+    dispatch: [Unused; 4],
+}
+
+#[repr(C)]
+struct INetworkListManagerVtbl {
+    dispatch: IDispatchVtbl,
+    get_networks: Unused,
+    get_network: Unused,
+    get_network_connections: unsafe extern "system" fn(*mut c_void, *mut *mut c_void) -> HRESULT,
+}
+
+#[repr(C)]
+struct IEnumNetworkConnectionsVtbl {
+    dispatch: IDispatchVtbl,
+    new_enum: Unused,
+    next: unsafe extern "system" fn(*mut c_void, u32, *mut *mut c_void, *mut u32) -> HRESULT,
+}
+
+#[repr(C)]
+struct INetworkConnectionVtbl {
+    dispatch: IDispatchVtbl,
+    get_network: unsafe extern "system" fn(*mut c_void, *mut *mut c_void) -> HRESULT,
+    // get_IsConnectedToInternet, get_IsConnected, GetConnectivity, GetConnectionId
+    unused: [Unused; 4],
+    get_adapter_id: unsafe extern "system" fn(*mut c_void, *mut GUID) -> HRESULT,
+}
+
+#[repr(C)]
+struct INetworkVtbl {
+    dispatch: IDispatchVtbl,
+    get_name: unsafe extern "system" fn(*mut c_void, *mut BSTR) -> HRESULT,
+    // SetName, GetDescription, SetDescription, GetNetworkId, GetDomainType,
+    // GetNetworkConnections, GetTimeCreatedAndConnected, get_IsConnectedToInternet,
+    // get_IsConnected, GetConnectivity
+    unused: [Unused; 10],
+    get_category: unsafe extern "system" fn(*mut c_void, *mut i32) -> HRESULT,
+}
+
+fn check(hr: HRESULT) -> io::Result<()> {
+    if hr < 0 {
+        Err(io::Error::from_raw_os_error(hr))
+    } else {
+        Ok(())
+    }
+}
+
+/// Owned COM interface pointer with vtable `V`, released on drop
+struct ComPtr<V> {
+    raw: *mut c_void,
+    vtbl: PhantomData<V>,
+}
+
+impl<V> ComPtr<V> {
+    fn from_raw(raw: *mut c_void) -> io::Result<ComPtr<V>> {
+        if raw.is_null() {
+            return Err(io::Error::other("COM call returned no interface"));
+        }
+        Ok(ComPtr {
+            raw,
+            vtbl: PhantomData,
+        })
+    }
+    fn vtbl(&self) -> &V {
+        // SAFETY: a COM interface pointer points at a pointer to its vtable
+        unsafe { &**(self.raw as *const *const V) }
+    }
+}
+
+impl<V> Drop for ComPtr<V> {
+    fn drop(&mut self) {
+        // SAFETY: every COM vtable starts with the IUnknown methods
+        unsafe {
+            let vtbl = &**(self.raw as *const *const IUnknownVtbl);
+            (vtbl.release)(self.raw);
+        }
+    }
+}
+
+/// Keeps COM initialized on the current thread
+struct ComInit {
+    initialized: bool,
+}
+
+impl ComInit {
+    fn new() -> io::Result<ComInit> {
+        let hr = unsafe { CoInitializeEx(ptr::null(), COINIT_MULTITHREADED as u32) };
+        // Already initialized as a single-threaded apartment, which works as well
+        if hr == RPC_E_CHANGED_MODE {
+            return Ok(ComInit { initialized: false });
+        }
+        check(hr)?;
+        Ok(ComInit { initialized: true })
+    }
+}
+
+impl Drop for ComInit {
+    fn drop(&mut self) {
+        if self.initialized {
+            unsafe { CoUninitialize() };
+        }
+    }
+}
+
+fn same_guid(a: &GUID, b: &GUID) -> bool {
+    a.data1 == b.data1 && a.data2 == b.data2 && a.data3 == b.data3 && a.data4 == b.data4
+}
+
+fn take_bstr(bstr: BSTR) -> String {
+    if bstr.is_null() {
+        return String::new();
+    }
+    let len = unsafe { SysStringLen(bstr) } as usize;
+    let s = String::from_utf16_lossy(unsafe { std::slice::from_raw_parts(bstr, len) });
+    unsafe { SysFreeString(bstr) };
+    s
+}
+
+fn category(value: i32) -> Option<NetworkCategory> {
+    match value {
+        NLM_NETWORK_CATEGORY_PUBLIC => Some(NetworkCategory::Public),
+        NLM_NETWORK_CATEGORY_PRIVATE => Some(NetworkCategory::Private),
+        NLM_NETWORK_CATEGORY_DOMAIN_AUTHENTICATED => Some(NetworkCategory::Domain),
+        _ => None,
+    }
+}
+
+fn network_zone(connection: &ComPtr<INetworkConnectionVtbl>) -> io::Result<FirewallZone> {
+    let mut raw = ptr::null_mut();
+    check(unsafe { (connection.vtbl().get_network)(connection.raw, &mut raw) })?;
+    let network = ComPtr::<INetworkVtbl>::from_raw(raw)?;
+    let mut name: BSTR = ptr::null();
+    check(unsafe { (network.vtbl().get_name)(network.raw, &mut name) })?;
+    let name = take_bstr(name);
+    let mut value = -1;
+    check(unsafe { (network.vtbl().get_category)(network.raw, &mut value) })?;
+    Ok(FirewallZone {
+        name,
+        category: category(value),
+        default: false,
+        source: ZoneSource::NetworkList,
+    })
+}
+
+pub fn get_zone(interface: &Interface) -> io::Result<Option<FirewallZone>> {
+    let adapter = crate::interface::guid_of(interface.index)?;
+    // Declared first so COM is uninitialized after the interfaces are released
+    let _com = ComInit::new()?;
+    let mut raw = ptr::null_mut();
+    check(unsafe {
+        CoCreateInstance(
+            &CLSID_NETWORK_LIST_MANAGER,
+            ptr::null_mut(),
+            CLSCTX_ALL,
+            &IID_INETWORK_LIST_MANAGER,
+            &mut raw,
+        )
+    })?;
+    let manager = ComPtr::<INetworkListManagerVtbl>::from_raw(raw)?;
+    let mut raw = ptr::null_mut();
+    check(unsafe { (manager.vtbl().get_network_connections)(manager.raw, &mut raw) })?;
+    let connections = ComPtr::<IEnumNetworkConnectionsVtbl>::from_raw(raw)?;
+    loop {
+        let mut raw = ptr::null_mut();
+        let mut fetched = 0;
+        check(unsafe { (connections.vtbl().next)(connections.raw, 1, &mut raw, &mut fetched) })?;
+        if fetched == 0 {
+            return Ok(None);
+        }
+        let connection = ComPtr::<INetworkConnectionVtbl>::from_raw(raw)?;
+        let mut id = GUID::from_u128(0);
+        check(unsafe { (connection.vtbl().get_adapter_id)(connection.raw, &mut id) })?;
+        if same_guid(&id, &adapter) {
+            return network_zone(&connection).map(Some);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::interface::Interface;
+use std::io;
+use std::net::{IpAddr, Ipv6Addr};
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// DNS and NTP configuration handed to an interface by the routers and DHCP
+/// servers on its link
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Advertised {
+    /// Recursive DNS servers from the RDNSS options of a router advertisement (RFC 8106)
+    pub rdnss: Vec<Ipv6Addr>,
+    /// Search domains from the DNSSL options of a router advertisement (RFC 8106)
+    pub dnssl: Vec<String>,
+    /// DNS servers from the DHCP or DHCPv6 lease
+    pub dhcp_dns_servers: Vec<IpAddr>,
+    /// NTP servers from the DHCP (option 42) or DHCPv6 (option 56) lease
+    pub ntp_servers: Vec<IpAddr>,
+}
+
+impl Advertised {
+    /// Check if nothing was advertised
+    pub fn is_empty(&self) -> bool {
+        self.rdnss.is_empty()
+            && self.dnssl.is_empty()
+            && self.dhcp_dns_servers.is_empty()
+            && self.ntp_servers.is_empty()
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use super::linux::{capture_ra_dns, read_dhcp_leases};
+
+/// Read the DNS and NTP servers of the current DHCP leases of `interface`.
+///
+/// On Linux the leases of systemd-networkd, NetworkManager's internal DHCP
+/// client and dhclient are read. Router advertisement options are not stored
+/// by the kernel, so `rdnss` and `dnssl` stay empty; see [`capture_advertised`].
+pub fn get_advertised(interface: &Interface) -> io::Result<Advertised> {
+    read_dhcp_leases(interface)
+}
+
+/// Same as [`get_advertised`], and also wait up to `timeout` for the next router
+/// advertisement on `interface` to record its RDNSS and DNSSL options.
+///
+/// Routers advertise every few minutes unless solicited, so `rdnss` and
+/// `dnssl` are empty if none arrived in time. Linux only.
+pub fn capture_advertised(interface: &Interface, timeout: Duration) -> io::Result<Advertised> {
+    let mut advertised = read_dhcp_leases(interface)?;
+    if let Some((rdnss, dnssl)) = capture_ra_dns(interface.index, timeout)? {
+        advertised.rdnss = rdnss;
+        advertised.dnssl = dnssl;
+    }
+    Ok(advertised)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn read_dhcp_leases(_interface: &Interface) -> io::Result<Advertised> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Advertised DNS and NTP servers are not supported on this platform",
+    ))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn capture_ra_dns(
+    _if_index: u32,
+    _timeout: Duration,
+) -> io::Result<Option<(Vec<Ipv6Addr>, Vec<String>)>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Router advertisement capture is not supported on this platform",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_advertised() {
+        for interface in crate::interface::get_interfaces() {
+            println!("{}: {:?}", interface.name, get_advertised(&interface));
+        }
+    }
+}
@@ -1,13 +1,37 @@
+use super::{Advertised, Ipv6Router, RouterPreference};
 use crate::device::NetworkDevice;
+use crate::interface::{AddressFamily, Interface};
 use crate::mac::MacAddr;
+use crate::sys::netlink;
+use netlink_packet_route::{
+    rtnl::neighbour::nlas::Nla as NeighbourNla, rtnl::route::nlas::Nla as RouteNla,
+    NeighbourMessage, RouteMessage, RtnlMessage, AF_INET6, RTN_UNICAST, RTPROT_RA, RT_TABLE_MAIN,
+};
+use netlink_sys::{protocols::NETLINK_ROUTE, Socket};
 use std::collections::HashMap;
+use std::ffi::CString;
 use std::fs::read_to_string;
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::os::fd::AsRawFd;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 
-const PATH_PROC_NET_ROUTE: &str = "/proc/net/route";
-const PATH_PROC_NET_IPV6_ROUTE: &str = "/proc/net/ipv6_route";
-const PATH_PROC_NET_ARP: &str = "/proc/net/arp";
+const PROC_NET_ROUTE: &str = "route";
+const PROC_NET_IPV6_ROUTE: &str = "ipv6_route";
+const PROC_NET_ARP: &str = "arp";
+
+// include/uapi/linux/ipv6_route.h
+const RTF_GATEWAY: u32 = 0x0002;
+const RTF_ADDRCONF: u32 = 0x0004_0000;
+const RTF_PREF_SHIFT: u32 = 27;
+
+// Prefer the calling thread's view of /proc/net so that lookups made from a thread
+// that entered another network namespace (see `netns`) see that namespace's tables.
+pub(crate) fn read_proc_net(file: &str) -> std::io::Result<String> {
+    read_to_string(format!("/proc/thread-self/net/{}", file))
+        .or_else(|_| read_to_string(format!("/proc/net/{}", file)))
+}
 
 fn convert_hex_ipv4(hex_ip: &str) -> Ipv4Addr {
     if hex_ip.len() != 8 {
@@ -37,85 +61,588 @@ fn convert_hex_ipv6(hex_ip: &str) -> Ipv6Addr {
 
 fn get_arp_map() -> HashMap<Ipv4Addr, MacAddr> {
     let mut arp_map: HashMap<Ipv4Addr, MacAddr> = HashMap::new();
-    let arp_data = read_to_string(PATH_PROC_NET_ARP);
-    let arp_text = match arp_data {
-        Ok(content) => content,
-        Err(_) => String::new(),
-    };
+    let arp_data = read_proc_net(PROC_NET_ARP);
+    let arp_text = arp_data.unwrap_or_default();
     let arp_table: Vec<&str> = arp_text.trim().split("\n").collect();
     for row in arp_table {
         let mut fields: Vec<&str> = row.split(" ").collect();
-        fields.retain(|value| *value != "");
+        fields.retain(|value| !value.is_empty());
         if fields.len() >= 6 {
             // fields[0]: IP Address
             // fields[3]: MAC Address (colon-separated string of hex format)
             // fields[5]: Interface Name
-            match Ipv4Addr::from_str(fields[0]) {
-                Ok(ipv4_addr) => {
-                    arp_map.insert(ipv4_addr, MacAddr::from_hex_format(fields[3]));
-                }
-                Err(_) => {}
+            if let Ok(ipv4_addr) = Ipv4Addr::from_str(fields[0]) {
+                arp_map.insert(ipv4_addr, MacAddr::from_hex_format(fields[3]));
             }
         }
     }
     arp_map
 }
 
-fn get_ipv4_gateway_map() -> HashMap<String, Ipv4Addr> {
-    let mut ipv4_gateway_map: HashMap<String, Ipv4Addr> = HashMap::new();
-    let route_data = read_to_string(PATH_PROC_NET_ROUTE);
-    let route_text = match route_data {
-        Ok(content) => content,
-        Err(_) => String::new(),
+fn if_name_to_index(name: &str) -> u32 {
+    match CString::new(name) {
+        Ok(name) => unsafe { libc::if_nametoindex(name.as_ptr()) },
+        Err(_) => 0,
+    }
+}
+
+/// Parse the IPv4 default routes in `/proc/net/route` as (interface name, metric, gateway)
+fn parse_ipv4_default_routes(text: &str) -> Vec<(String, u32, Ipv4Addr)> {
+    let mut routes = Vec::new();
+    for row in text.lines() {
+        let fields: Vec<&str> = row.split_whitespace().collect();
+        if fields.len() < 8 {
+            continue;
+        }
+        // fields[0]: interface name, fields[1]: destination
+        // fields[2]: gateway, fields[6]: metric, fields[7]: mask
+        if fields[1] != "00000000" || fields[7] != "00000000" {
+            continue;
+        }
+        let gateway = convert_hex_ipv4(fields[2]);
+        if gateway.is_unspecified() {
+            continue;
+        }
+        let metric = fields[6].parse().unwrap_or(0);
+        routes.push((fields[0].to_string(), metric, gateway));
+    }
+    routes
+}
+
+/// Link-layer addresses of the IPv6 neighbours, keyed by interface index and address
+fn get_ipv6_neighbour_map() -> HashMap<(u32, Ipv6Addr), MacAddr> {
+    let mut neighbour_map = HashMap::new();
+    let mut msg = NeighbourMessage::default();
+    msg.header.family = AF_INET6 as u8;
+    let msgs = match netlink::dump(RtnlMessage::GetNeighbour(msg)) {
+        Ok(msgs) => msgs,
+        Err(_) => return neighbour_map,
     };
-    let route_table: Vec<&str> = route_text.trim().split("\n").collect();
-    for row in route_table {
-        let fields: Vec<&str> = row.split("\t").collect();
-        if fields.len() >= 3 {
-            // fields[0]: Interface Name
-            // fields[2]: IPv4 Address 8 hex chars
-            if fields[2] != "00000000" {
-                ipv4_gateway_map.insert(fields[0].to_string(), convert_hex_ipv4(fields[2]));
+    for msg in msgs {
+        let RtnlMessage::NewNeighbour(neighbour) = msg else {
+            continue;
+        };
+        let mut addr = None;
+        let mut mac_addr = None;
+        for nla in neighbour.nlas {
+            match nla {
+                NeighbourNla::Destination(bytes) => {
+                    addr = <[u8; 16]>::try_from(bytes).ok().map(Ipv6Addr::from);
+                }
+                NeighbourNla::LinkLocalAddress(bytes) => {
+                    mac_addr = <[u8; 6]>::try_from(bytes).ok().map(MacAddr::from_octets);
+                }
+                _ => {}
             }
         }
+        if let (Some(addr), Some(mac_addr)) = (addr, mac_addr) {
+            neighbour_map.insert((neighbour.header.ifindex, addr), mac_addr);
+        }
+    }
+    neighbour_map
+}
+
+/// Get the default gateways of each interface, keyed by interface index.
+///
+/// Gateways are ordered by address family (IPv4 first) and then by route metric.
+pub fn get_gateway_map(family: AddressFamily) -> HashMap<u32, Vec<NetworkDevice>> {
+    // (if_index, metric, gateway, MAC address)
+    let mut hops: Vec<(u32, u32, IpAddr, MacAddr)> = Vec::new();
+    if family.includes_v4() {
+        let arp_map: HashMap<Ipv4Addr, MacAddr> = get_arp_map();
+        let route_text = read_proc_net(PROC_NET_ROUTE).unwrap_or_default();
+        for (if_name, metric, ipv4_addr) in parse_ipv4_default_routes(&route_text) {
+            let mac_addr = arp_map.get(&ipv4_addr).copied().unwrap_or(MacAddr::zero());
+            hops.push((
+                if_name_to_index(&if_name),
+                metric,
+                IpAddr::V4(ipv4_addr),
+                mac_addr,
+            ));
+        }
     }
-    ipv4_gateway_map
+    if family.includes_v6() {
+        let neighbour_map = get_ipv6_neighbour_map();
+        for router in get_ipv6_default_routers().unwrap_or_default() {
+            let mac_addr = neighbour_map
+                .get(&(router.if_index, router.addr))
+                .copied()
+                .unwrap_or(MacAddr::zero());
+            hops.push((
+                router.if_index,
+                router.metric,
+                IpAddr::V6(router.addr),
+                mac_addr,
+            ));
+        }
+    }
+    hops.sort_by_key(|(_, metric, ip, _)| (ip.is_ipv6(), *metric));
+    let mut hop_map: HashMap<u32, Vec<(IpAddr, MacAddr)>> = HashMap::new();
+    for (if_index, _, ip, mac_addr) in hops {
+        hop_map.entry(if_index).or_default().push((ip, mac_addr));
+    }
+    hop_map
+        .into_iter()
+        .map(|(if_index, hops)| (if_index, super::group_gateways(hops)))
+        .collect()
 }
 
-fn get_ipv6_gateway_map() -> HashMap<String, Ipv6Addr> {
-    let mut ipv6_gateway_map: HashMap<String, Ipv6Addr> = HashMap::new();
-    let route_data = read_to_string(PATH_PROC_NET_IPV6_ROUTE);
-    let route_text = match route_data {
-        Ok(content) => content,
-        Err(_) => String::new(),
+/// Get the IPv6 default routers from the main routing table.
+///
+/// Lifetime and preference are only available via netlink, `/proc/net/ipv6_route` is
+/// used as a fallback if the netlink socket cannot be opened.
+pub fn get_ipv6_default_routers() -> io::Result<Vec<Ipv6Router>> {
+    let mut msg = RouteMessage::default();
+    msg.header.address_family = AF_INET6 as u8;
+    match netlink::dump(RtnlMessage::GetRoute(msg)) {
+        Ok(msgs) => Ok(msgs
+            .into_iter()
+            .filter_map(|msg| match msg {
+                RtnlMessage::NewRoute(route) => route_to_router(route),
+                _ => None,
+            })
+            .collect()),
+        Err(_) => Ok(parse_ipv6_route_table(&read_proc_net(PROC_NET_IPV6_ROUTE)?)),
+    }
+}
+
+fn route_to_router(route: RouteMessage) -> Option<Ipv6Router> {
+    if route.header.destination_prefix_length != 0 || route.header.kind != RTN_UNICAST {
+        return None;
+    }
+    let mut table = route.header.table as u32;
+    let mut router = Ipv6Router {
+        addr: Ipv6Addr::UNSPECIFIED,
+        if_index: 0,
+        lifetime: None,
+        preference: None,
+        metric: 0,
+        from_ra: route.header.protocol == RTPROT_RA,
     };
-    let route_table: Vec<&str> = route_text.trim().split("\n").collect();
-    for row in route_table {
-        let fields: Vec<&str> = row.split("\t").collect();
-        if fields.len() >= 10 {
-            // fields[4]: IPv6 Address 32 hex chars without colons
-            // fields[9]: Interface Name
-            if fields[4] != "00000000000000000000000000000000" {
-                ipv6_gateway_map.insert(fields[9].to_string(), convert_hex_ipv6(fields[4]));
+    for nla in route.nlas {
+        match nla {
+            RouteNla::Gateway(addr) => {
+                router.addr = Ipv6Addr::from(<[u8; 16]>::try_from(addr).ok()?);
             }
+            RouteNla::Oif(index) => router.if_index = index,
+            RouteNla::Priority(metric) => router.metric = metric,
+            RouteNla::Table(id) => table = id,
+            RouteNla::Pref(pref) => {
+                router.preference = pref.first().map(|prf| RouterPreference::from_prf(*prf));
+            }
+            RouteNla::CacheInfo(info) => {
+                // struct rta_cacheinfo: rta_expires is the third field, in clock ticks
+                if let Some(expires) = info.get(8..12) {
+                    let ticks = u32::from_ne_bytes(expires.try_into().ok()?);
+                    router.lifetime = ticks_to_duration(ticks);
+                }
+            }
+            _ => {}
         }
     }
-    ipv6_gateway_map
+    if table != RT_TABLE_MAIN as u32 || router.addr.is_unspecified() {
+        return None;
+    }
+    Some(router)
+}
+
+fn ticks_to_duration(ticks: u32) -> Option<Duration> {
+    if ticks == 0 {
+        return None;
+    }
+    let hz = match unsafe { libc::sysconf(libc::_SC_CLK_TCK) } {
+        hz if hz > 0 => hz as u64,
+        _ => 100,
+    };
+    Some(Duration::from_millis(ticks as u64 * 1000 / hz))
 }
 
-pub fn get_gateway_map() -> HashMap<String, NetworkDevice> {
-    let mut gateway_map: HashMap<String, NetworkDevice> = HashMap::new();
-    let arp_map: HashMap<Ipv4Addr, MacAddr> = get_arp_map();
-    for (if_name, ipv4_addr) in get_ipv4_gateway_map() {
-        let gateway = gateway_map.entry(if_name).or_insert(NetworkDevice::new());
-        if let Some(mac_addr) = arp_map.get(&ipv4_addr) {
-            gateway.mac_addr = mac_addr.clone();
+fn parse_ipv6_route_table(text: &str) -> Vec<Ipv6Router> {
+    let mut routers = Vec::new();
+    for row in text.lines() {
+        let fields: Vec<&str> = row.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+        // fields[0], fields[1]: destination and prefix length
+        // fields[4]: next hop
+        // fields[5]: metric, fields[8]: flags
+        // fields[9]: interface name
+        let flags = u32::from_str_radix(fields[8], 0x10).unwrap_or(0);
+        if fields[1] != "00" || flags & RTF_GATEWAY == 0 {
+            continue;
         }
-        gateway.ipv4.push(ipv4_addr);
+        let addr = convert_hex_ipv6(fields[4]);
+        if addr.is_unspecified() {
+            continue;
+        }
+        let if_index = if_name_to_index(fields[9]);
+        routers.push(Ipv6Router {
+            addr,
+            if_index,
+            lifetime: None,
+            preference: Some(RouterPreference::from_prf((flags >> RTF_PREF_SHIFT) as u8)),
+            metric: u32::from_str_radix(fields[5], 0x10).unwrap_or(0),
+            from_ra: flags & RTF_ADDRCONF != 0,
+        });
+    }
+    routers
+}
+
+/// Lease files of systemd-networkd, named after the interface index
+const NETWORKD_LEASES_DIR: &str = "/run/systemd/netif/leases";
+/// Leases of NetworkManager's internal DHCP client, `internal-<uuid>-<name>.lease`
+const NETWORKMANAGER_LEASES_DIR: &str = "/var/lib/NetworkManager";
+const DHCLIENT_LEASES_DIRS: [&str; 2] = ["/var/lib/dhcp", "/var/lib/dhclient"];
+
+fn push_unique<T: PartialEq>(list: &mut Vec<T>, item: T) {
+    if !list.contains(&item) {
+        list.push(item);
     }
-    for (if_name, ipv6_addr) in get_ipv6_gateway_map() {
-        let gateway = gateway_map.entry(if_name).or_insert(NetworkDevice::new());
-        gateway.ipv6.push(ipv6_addr);
+}
+
+/// Parse a lease in the `KEY=value` format systemd-networkd and NetworkManager's
+/// internal client write
+fn parse_sd_lease(text: &str, advertised: &mut Advertised) {
+    for line in text.lines() {
+        let (target, value) = match line.split_once('=') {
+            Some(("DNS", value)) => (&mut advertised.dhcp_dns_servers, value),
+            Some(("NTP", value)) => (&mut advertised.ntp_servers, value),
+            _ => continue,
+        };
+        for ip in value.split_whitespace().filter_map(|s| s.parse().ok()) {
+            push_unique(target, ip);
+        }
+    }
+}
+
+/// Parse the last lease of `if_name` in a dhclient lease database.
+///
+/// Only the statements of the lease itself are kept: dhclient6 nests the
+/// addresses of a lease in `ia-na { iaaddr { } }` blocks.
+fn parse_dhclient_leases(text: &str, if_name: &str, advertised: &mut Advertised) {
+    let interface_line = format!("interface \"{}\";", if_name);
+    let mut block: Vec<&str> = Vec::new();
+    let mut last: Option<Vec<&str>> = None;
+    // Brace depth, 1 inside a lease
+    let mut depth = 0usize;
+    for line in text.lines().map(str::trim) {
+        if line.ends_with('{') {
+            if depth == 0 && line.starts_with("lease") {
+                block.clear();
+            }
+            depth += 1;
+        } else if line == "}" {
+            depth = depth.saturating_sub(1);
+            if depth == 0 && block.contains(&interface_line.as_str()) {
+                last = Some(std::mem::take(&mut block));
+            }
+        } else if depth == 1 {
+            block.push(line);
+        }
+    }
+    for line in last.unwrap_or_default() {
+        let Some(option) = line.strip_prefix("option ") else {
+            continue;
+        };
+        let Some((name, value)) = option.trim_end_matches(';').split_once(' ') else {
+            continue;
+        };
+        let target = match name {
+            "domain-name-servers" | "dhcp6.name-servers" => &mut advertised.dhcp_dns_servers,
+            "ntp-servers" | "dhcp6.sntp-servers" => &mut advertised.ntp_servers,
+            _ => continue,
+        };
+        for ip in value.split(',').filter_map(|s| s.trim().parse().ok()) {
+            push_unique(target, ip);
+        }
+    }
+}
+
+fn lease_files(dir: &str) -> Vec<std::path::PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default()
+}
+
+pub fn read_dhcp_leases(interface: &Interface) -> io::Result<Advertised> {
+    let mut advertised = Advertised::default();
+    let networkd = format!("{}/{}", NETWORKD_LEASES_DIR, interface.index);
+    if let Ok(text) = read_to_string(networkd) {
+        parse_sd_lease(&text, &mut advertised);
+    }
+    let nm_suffix = format!("-{}.lease", interface.name);
+    for path in lease_files(NETWORKMANAGER_LEASES_DIR) {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.starts_with("internal-") && name.ends_with(&nm_suffix) {
+            if let Ok(text) = read_to_string(&path) {
+                parse_sd_lease(&text, &mut advertised);
+            }
+        }
+    }
+    for path in DHCLIENT_LEASES_DIRS.iter().flat_map(|dir| lease_files(dir)) {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.ends_with(".leases") || name.ends_with(".lease") {
+            if let Ok(text) = read_to_string(&path) {
+                parse_dhclient_leases(&text, &interface.name, &mut advertised);
+            }
+        }
+    }
+    Ok(advertised)
+}
+
+/// Multicast group of the ND options the kernel passes to user space
+const RTNLGRP_ND_USEROPT: u32 = 20;
+const RTM_NEWNDUSEROPT: u16 = 68;
+/// Size of `struct nduseroptmsg`
+const NDUSEROPTMSG_LEN: usize = 16;
+const ND_ROUTER_ADVERT: u8 = 134;
+const ND_OPT_RDNSS: u8 = 25;
+const ND_OPT_DNSSL: u8 = 31;
+
+/// Decode the uncompressed domain names of a DNSSL option, which are padded with zeros
+fn parse_dnssl(mut buf: &[u8]) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut labels: Vec<String> = Vec::new();
+    while let Some((&len, rest)) = buf.split_first() {
+        let len = len as usize;
+        if len == 0 {
+            if !labels.is_empty() {
+                names.push(labels.join("."));
+                labels.clear();
+            }
+            buf = rest;
+            continue;
+        }
+        let Some(label) = rest.get(..len) else {
+            break;
+        };
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        buf = &rest[len..];
+    }
+    names
+}
+
+/// Extract the RDNSS and DNSSL options of a router advertisement received by
+/// `if_index` from the payload of an `RTM_NEWNDUSEROPT` message
+fn parse_nd_useropt(payload: &[u8], if_index: u32) -> Option<(Vec<Ipv6Addr>, Vec<String>)> {
+    if payload.len() < NDUSEROPTMSG_LEN
+        || payload[0] as u16 != AF_INET6
+        || netlink::read_u32(payload, 4)? != if_index
+        || payload[8] != ND_ROUTER_ADVERT
+    {
+        return None;
+    }
+    let opts_len = u16::from_ne_bytes([payload[2], payload[3]]) as usize;
+    let mut opts = payload.get(NDUSEROPTMSG_LEN..NDUSEROPTMSG_LEN + opts_len)?;
+    let mut rdnss = Vec::new();
+    let mut dnssl = Vec::new();
+    // Each option: type, length in units of 8 bytes, 2 reserved bytes, lifetime
+    while opts.len() >= 8 {
+        let len = opts[1] as usize * 8;
+        if len == 0 || len > opts.len() {
+            break;
+        }
+        let body = &opts[8..len];
+        match opts[0] {
+            ND_OPT_RDNSS => {
+                for addr in body.chunks_exact(16) {
+                    push_unique(&mut rdnss, Ipv6Addr::from(<[u8; 16]>::try_from(addr).ok()?));
+                }
+            }
+            ND_OPT_DNSSL => {
+                for name in parse_dnssl(body) {
+                    push_unique(&mut dnssl, name);
+                }
+            }
+            _ => {}
+        }
+        opts = &opts[len..];
+    }
+    Some((rdnss, dnssl))
+}
+
+/// Wait up to `timeout` for a router advertisement on `if_index` and return
+/// its RDNSS and DNSSL options, or `None` if none arrived
+pub fn capture_ra_dns(
+    if_index: u32,
+    timeout: Duration,
+) -> io::Result<Option<(Vec<Ipv6Addr>, Vec<String>)>> {
+    let mut socket = Socket::new(NETLINK_ROUTE)?;
+    socket.bind_auto()?;
+    socket.add_membership(RTNLGRP_ND_USEROPT)?;
+    let deadline = Instant::now() + timeout;
+    let mut buf = vec![0u8; 8192];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(None);
+        }
+        let mut pollfd = libc::pollfd {
+            fd: socket.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let millis = remaining.as_millis().clamp(1, i32::MAX as u128) as libc::c_int;
+        let ret = unsafe { libc::poll(&mut pollfd, 1, millis) };
+        if ret < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(err);
+        }
+        if ret == 0 {
+            return Ok(None);
+        }
+        let size = socket.recv(&mut &mut buf[..], 0)?;
+        let mut data = &buf[..size];
+        while data.len() >= 16 {
+            let len = netlink::read_u32(data, 0).unwrap_or(0) as usize;
+            if len < 16 || len > data.len() {
+                break;
+            }
+            let kind = u16::from_ne_bytes([data[4], data[5]]);
+            if kind == RTM_NEWNDUSEROPT {
+                if let Some(options) = parse_nd_useropt(&data[16..len], if_index) {
+                    return Ok(Some(options));
+                }
+            }
+            data = &data[((len + 3) & !3).min(data.len())..];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ipv4_default_routes() {
+        let text = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+eth0\t00000000\t010200C0\t0003\t0\t0\t100\t00000000\t0\t0\t0
+eth0\t000200C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0
+eth0\t0000000A\tFE0200C0\t0003\t0\t0\t0\t000000FF\t0\t0\t0
+wlan0\t00000000\t0101A8C0\t0003\t0\t0\t600\t00000000\t0\t0\t0
+";
+        let routes = parse_ipv4_default_routes(text);
+        assert_eq!(
+            routes,
+            vec![
+                ("eth0".to_string(), 100, Ipv4Addr::new(192, 0, 2, 1)),
+                ("wlan0".to_string(), 600, Ipv4Addr::new(192, 168, 1, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_ipv6_route_table() {
+        let text = "\
+fe800000000000000000000000000000 40 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000002 00000000 00000001     eth0
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 fe800000000000000000000000000001 00000400 00000001 00000000 08450003       lo
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 00000000000000000000000000000000 ffffffff 00000001 00000000 00200200       lo
+";
+        let routers = parse_ipv6_route_table(text);
+        assert_eq!(routers.len(), 1);
+        let router = &routers[0];
+        assert_eq!(router.addr, Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1));
+        assert_eq!(router.if_index, 1);
+        assert_eq!(router.metric, 0x400);
+        assert_eq!(router.preference, Some(RouterPreference::High));
+        assert!(router.from_ra);
+    }
+
+    #[test]
+    fn test_parse_leases() {
+        let mut advertised = Advertised::default();
+        parse_sd_lease(
+            "# This is private data. Do not parse.\nADDRESS=192.0.2.20\nDNS=192.0.2.1 192.0.2.2\nNTP=192.0.2.123\n",
+            &mut advertised,
+        );
+        let dhclient = r#"
+lease {
+  interface "eth0";
+  option domain-name-servers 192.0.2.9;
+}
+lease {
+  interface "wlan0";
+  option domain-name-servers 198.51.100.1;
+}
+lease {
+  interface "eth0";
+  fixed-address 192.0.2.20;
+  option domain-name-servers 192.0.2.2,192.0.2.3;
+  option ntp-servers 192.0.2.123;
+}
+"#;
+        parse_dhclient_leases(dhclient, "eth0", &mut advertised);
+        let ips = |list: &[&str]| {
+            list.iter()
+                .map(|s| s.parse().unwrap())
+                .collect::<Vec<IpAddr>>()
+        };
+        assert_eq!(
+            advertised.dhcp_dns_servers,
+            ips(&["192.0.2.1", "192.0.2.2", "192.0.2.3"])
+        );
+        assert_eq!(advertised.ntp_servers, ips(&["192.0.2.123"]));
+
+        let mut advertised = Advertised::default();
+        let dhclient6 = r#"
+default-duid "\000\001\000\001\055\226\177\024RT\000\022\064\126";
+lease6 {
+  interface "eth0";
+  ia-na 5e:a4:12:34 {
+    starts 1700000000;
+    renew 1800;
+    rebind 2880;
+    iaaddr 2001:db8::20 {
+      starts 1700000000;
+      preferred-life 4500;
+      max-life 7200;
+    }
+  }
+  option dhcp6.client-id 0:1:0:1:2d:96:7f:14:52:54:0:12:34:56;
+  option dhcp6.server-id 0:1:0:1:2c:11:22:33:52:54:0:ab:cd:ef;
+  option dhcp6.name-servers 2001:db8::53,2001:db8::54;
+  option dhcp6.sntp-servers 2001:db8::123;
+}
+lease6 {
+  interface "wlan0";
+  ia-na 1a:2b:3c:4d {
+    iaaddr 2001:db8:1::20 {
+      max-life 7200;
+    }
+  }
+  option dhcp6.name-servers 2001:db8:1::53;
+}
+"#;
+        parse_dhclient_leases(dhclient6, "eth0", &mut advertised);
+        assert_eq!(
+            advertised.dhcp_dns_servers,
+            ips(&["2001:db8::53", "2001:db8::54"])
+        );
+        assert_eq!(advertised.ntp_servers, ips(&["2001:db8::123"]));
+    }
+
+    #[test]
+    fn test_parse_nd_useropt() {
+        let dns: Ipv6Addr = "2001:db8::53".parse().unwrap();
+        let mut opts = vec![ND_OPT_RDNSS, 3, 0, 0, 0, 0, 0x0e, 0x10];
+        opts.extend_from_slice(&dns.octets());
+        // "example.com" padded to 8 bytes
+        opts.extend_from_slice(&[ND_OPT_DNSSL, 3, 0, 0, 0, 0, 0x0e, 0x10]);
+        opts.extend_from_slice(b"\x07example\x03com\x00\x00\x00\x00");
+        let mut payload = vec![AF_INET6 as u8, 0];
+        payload.extend_from_slice(&(opts.len() as u16).to_ne_bytes());
+        payload.extend_from_slice(&2i32.to_ne_bytes());
+        payload.extend_from_slice(&[ND_ROUTER_ADVERT, 0, 0, 0, 0, 0, 0, 0]);
+        payload.extend_from_slice(&opts);
+        assert_eq!(
+            parse_nd_useropt(&payload, 2),
+            Some((vec![dns], vec!["example.com".to_string()]))
+        );
+        assert_eq!(parse_nd_useropt(&payload, 3), None);
     }
-    gateway_map
 }
@@ -1,7 +1,10 @@
 #![allow(non_camel_case_types)]
 
+use super::Ipv6Router;
 use crate::device::NetworkDevice;
+use crate::interface::AddressFamily;
 use crate::mac::MacAddr;
+use crate::parse::{self, SockAddrView};
 
 use std::{
     collections::HashMap,
@@ -9,12 +12,9 @@ use std::{
     net::{IpAddr, Ipv4Addr, Ipv6Addr},
 };
 
-use libc::RTAX_MAX;
-
 const CTL_NET: u32 = 4;
 const AF_INET: u32 = 2;
 const AF_ROUTE: u32 = 17;
-const AF_LINK: u32 = 18;
 const AF_INET6: u32 = 30;
 const PF_ROUTE: u32 = 17;
 const NET_RT_DUMP: u32 = 1;
@@ -32,9 +32,6 @@ type __uint16_t = ::std::os::raw::c_ushort;
 type __uint32_t = ::std::os::raw::c_uint;
 type __darwin_size_t = ::std::os::raw::c_ulong;
 type __darwin_pid_t = __int32_t;
-type sa_family_t = __uint8_t;
-type in_addr_t = __uint32_t;
-type in_port_t = __uint16_t;
 type u_int = ::std::os::raw::c_uint;
 type u_short = ::std::os::raw::c_ushort;
 type u_char = ::std::os::raw::c_uchar;
@@ -87,55 +84,6 @@ struct rt_metrics {
     pub rmx_filler: [u_int32_t; 3usize],
 }
 
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-struct sockaddr {
-    pub sa_len: __uint8_t,
-    pub sa_family: sa_family_t,
-    pub sa_data: [::std::os::raw::c_char; 14usize],
-}
-
-#[repr(C)]
-#[derive(Copy, Clone)]
-union in6_addr_bind {
-    pub __u6_addr8: [__uint8_t; 16usize],
-    pub __u6_addr16: [__uint16_t; 8usize],
-    pub __u6_addr32: [__uint32_t; 4usize],
-}
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct in_addr {
-    pub s_addr: in_addr_t,
-}
-
-#[repr(C)]
-#[derive(Copy, Clone)]
-struct in6_addr {
-    pub __u6_addr: in6_addr_bind,
-}
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-struct sockaddr_in {
-    pub sin_len: __uint8_t,
-    pub sin_family: sa_family_t,
-    pub sin_port: in_port_t,
-    pub sin_addr: in_addr,
-    pub sin_zero: [::std::os::raw::c_char; 8usize],
-}
-
-#[repr(C)]
-#[derive(Copy, Clone)]
-struct sockaddr_in6 {
-    pub sin6_len: __uint8_t,
-    pub sin6_family: sa_family_t,
-    pub sin6_port: in_port_t,
-    pub sin6_flowinfo: __uint32_t,
-    pub sin6_addr: in6_addr,
-    pub sin6_scope_id: __uint32_t,
-}
-
 fn code_to_error(err: i32) -> io::Error {
     let kind = match err {
         17 => io::ErrorKind::AlreadyExists, // EEXIST
@@ -147,21 +95,8 @@ fn code_to_error(err: i32) -> io::Error {
     io::Error::new(kind, format!("rtm_errno {}", err))
 }
 
-fn socketaddr_to_ipaddr(sa: &sockaddr) -> Option<IpAddr> {
-    match sa.sa_family as u32 {
-        AF_INET => {
-            let inet: &sockaddr_in = unsafe { std::mem::transmute(sa) };
-            let octets: [u8; 4] = inet.sin_addr.s_addr.to_ne_bytes();
-            Some(IpAddr::from(octets))
-        }
-        AF_INET6 => {
-            let inet6: &sockaddr_in6 = unsafe { std::mem::transmute(sa) };
-            let octets: [u8; 16] = unsafe { inet6.sin6_addr.__u6_addr.__u6_addr8 };
-            Some(IpAddr::from(octets))
-        }
-        AF_LINK => None,
-        _ => None,
-    }
+fn socketaddr_to_ipaddr(sa: &[u8]) -> Option<IpAddr> {
+    parse::sockaddr(sa).ok().map(|addr| addr.ip())
 }
 
 // https://opensource.apple.com/source/network_cmds/network_cmds-606.40.2/netstat.tproj/route.c.auto.html
@@ -172,26 +107,8 @@ fn message_to_route(hdr: &rt_msghdr, msg: &[u8]) -> Option<Route> {
     if hdr.rtm_addrs & (1 << RTAX_DST) == 0 {
         return None;
     }
-    let mut route_addresses = [None; RTAX_MAX as usize];
-    let mut cur_pos = 0;
-    for idx in 0..RTAX_MAX as usize {
-        if hdr.rtm_addrs & (1 << idx) != 0 {
-            let buf = &msg[cur_pos..];
-            let sa: &sockaddr = unsafe { &*(buf.as_ptr() as *const sockaddr) };
-            route_addresses[idx] = Some(sa);
-            let aligned_len = if sa.sa_len == 0 {
-                4
-            } else {
-                ((sa.sa_len - 1) | 0x3) + 1
-            };
-            cur_pos += aligned_len as usize;
-        }
-    }
-
-    let sa = match route_addresses[RTAX_DST as usize] {
-        Some(sa) => sa,
-        None => return None,
-    };
+    let route_addresses = parse::rt_addrs(msg, hdr.rtm_addrs).ok()?;
+    let sa = route_addresses[RTAX_DST as usize]?;
     let destination = socketaddr_to_ipaddr(sa)?;
 
     let mut prefix = match destination {
@@ -200,11 +117,7 @@ fn message_to_route(hdr: &rt_msghdr, msg: &[u8]) -> Option<Route> {
     };
 
     // Check if message has a gateway
-    if hdr.rtm_addrs & (1 << RTAX_GATEWAY) != 0 {
-        let gw_sa = match route_addresses[RTAX_GATEWAY as usize] {
-            Some(sa) => sa,
-            None => return None,
-        };
+    if let Some(gw_sa) = route_addresses[RTAX_GATEWAY as usize] {
         gateway = socketaddr_to_ipaddr(gw_sa);
         if let Some(IpAddr::V6(ipv6gw)) = gateway {
             // Unicast link local start with FE80::
@@ -225,23 +138,11 @@ fn message_to_route(hdr: &rt_msghdr, msg: &[u8]) -> Option<Route> {
     }
 
     // Check if message has netmask
-    if hdr.rtm_addrs & (1 << RTAX_NETMASK) != 0 {
-        let sa = route_addresses[RTAX_NETMASK as usize].unwrap();
-        if sa.sa_len == 0 {
-            prefix = 0;
-        } else {
-            match destination {
-                IpAddr::V4(_) => {
-                    let mask_sa: &sockaddr_in = unsafe { std::mem::transmute(sa) };
-                    prefix = u32::from_be(mask_sa.sin_addr.s_addr).leading_ones() as u8;
-                }
-                IpAddr::V6(_) => {
-                    let mask_sa: &sockaddr_in6 = unsafe { std::mem::transmute(sa) };
-                    prefix = u128::from_be_bytes(unsafe { mask_sa.sin6_addr.__u6_addr.__u6_addr8 })
-                        .leading_ones() as u8;
-                }
-            }
-        }
+    if let Some(sa) = route_addresses[RTAX_NETMASK as usize] {
+        prefix = match parse::netmask(sa, destination.is_ipv6()) {
+            IpAddr::V4(mask) => u32::from(mask).leading_ones() as u8,
+            IpAddr::V6(mask) => u128::from(mask).leading_ones() as u8,
+        };
     }
 
     Some(Route {
@@ -260,14 +161,19 @@ pub struct Route {
     pub ifindex: Option<u32>,
 }
 
-fn list_routes() -> io::Result<Vec<Route>> {
+pub(crate) fn list_routes(family: AddressFamily) -> io::Result<Vec<Route>> {
     let mut mib: [u32; 6] = [0; 6];
     let mut len = 0;
 
     mib[0] = CTL_NET;
     mib[1] = AF_ROUTE;
     mib[2] = 0;
-    mib[3] = 0; // AddressFamily: IPv4 & IPv6
+    // AddressFamily: 0 for IPv4 & IPv6
+    mib[3] = match family {
+        AddressFamily::V4 => AF_INET,
+        AddressFamily::V6 => AF_INET6,
+        AddressFamily::Both => 0,
+    };
     mib[4] = NET_RT_DUMP;
     // mib[5] flags: 0
 
@@ -304,14 +210,8 @@ fn list_routes() -> io::Result<Vec<Route>> {
     let mut routes = vec![];
     let mut offset = 0;
 
-    while offset + std::mem::size_of::<rt_msghdr>() <= len as usize {
-        let buf = &mut msgs_buf[offset..];
-
-        if buf.len() < std::mem::size_of::<rt_msghdr>() {
-            break;
-        }
-
-        let rt_hdr = unsafe { std::mem::transmute::<_, &rt_msghdr>(buf.as_ptr()) };
+    while offset < len as usize {
+        let (rt_hdr, rt_msg) = read_message(&msgs_buf[offset..len as usize])?;
         if rt_hdr.rtm_version as u32 != RTM_VERSION {
             eprintln!(
                 "Unexpected RTM_VERSION: {} in {:?}",
@@ -327,15 +227,12 @@ fn list_routes() -> io::Result<Vec<Route>> {
             return Err(code_to_error(rt_hdr.rtm_errno));
         }
 
-        let msg_len = rt_hdr.rtm_msglen as usize;
-        offset += msg_len;
+        offset += rt_hdr.rtm_msglen as usize;
 
         if rt_hdr.rtm_flags as u32 & RTF_WASCLONED != 0 {
             continue;
         }
-        let rt_msg = &mut buf[std::mem::size_of::<rt_msghdr>()..msg_len];
-
-        if let Some(route) = message_to_route(rt_hdr, rt_msg) {
+        if let Some(route) = message_to_route(&rt_hdr, rt_msg) {
             routes.push(route);
         }
     }
@@ -343,38 +240,39 @@ fn list_routes() -> io::Result<Vec<Route>> {
     Ok(routes)
 }
 
-fn message_to_arppair(msg_bytes: *mut u8) -> (IpAddr, MacAddr) {
-    const IP_START_INDEX: usize = 4;
-    const IP_END_INDEX: usize = 7;
-    const MAC_START_INDEX: usize = 24;
-    const MAC_END_INDEX: usize = 29;
-    let ip_bytes = unsafe {
-        std::slice::from_raw_parts(
-            msg_bytes.add(IP_START_INDEX),
-            IP_END_INDEX + 1 - IP_START_INDEX,
-        )
-    };
-    let mac_bytes = unsafe {
-        std::slice::from_raw_parts(
-            msg_bytes.add(MAC_START_INDEX),
-            MAC_END_INDEX + 1 - MAC_START_INDEX,
-        )
-    };
-    let ip_addr = IpAddr::V4(Ipv4Addr::new(
-        ip_bytes[0],
-        ip_bytes[1],
-        ip_bytes[2],
-        ip_bytes[3],
-    ));
-    let mac_addr = MacAddr::from_octets([
-        mac_bytes[0],
-        mac_bytes[1],
-        mac_bytes[2],
-        mac_bytes[3],
-        mac_bytes[4],
-        mac_bytes[5],
-    ]);
-    (ip_addr, mac_addr)
+/// Decode the IPv4 address and MAC address of an ARP entry, `None` if it is incomplete
+fn message_to_arppair(hdr: &rt_msghdr, msg: &[u8]) -> Option<(IpAddr, MacAddr)> {
+    let addrs = parse::rt_addrs(msg, hdr.rtm_addrs).ok()?;
+    let ip = SockAddrView::new(addrs[RTAX_DST as usize]?)
+        .ok()?
+        .ip()
+        .ok()?;
+    let mac = SockAddrView::new(addrs[RTAX_GATEWAY as usize]?)
+        .ok()?
+        .mac_addr()
+        .ok()??;
+    Some((ip, mac))
+}
+
+/// Copy the header of the routing message at the start of `buf`, which is not
+/// necessarily aligned for it, and return it with the rest of the message
+fn read_message(buf: &[u8]) -> io::Result<(rt_msghdr, &[u8])> {
+    let header_len = std::mem::size_of::<rt_msghdr>();
+    if buf.len() < header_len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "routing message header is truncated",
+        ));
+    }
+    let hdr = unsafe { std::ptr::read_unaligned(buf.as_ptr() as *const rt_msghdr) };
+    let msg_len = hdr.rtm_msglen as usize;
+    if msg_len < header_len || msg_len > buf.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid routing message length {}", msg_len),
+        ));
+    }
+    Ok((hdr, &buf[header_len..msg_len]))
 }
 
 fn get_arp_table() -> io::Result<HashMap<IpAddr, MacAddr>> {
@@ -417,14 +315,8 @@ fn get_arp_table() -> io::Result<HashMap<IpAddr, MacAddr>> {
         return Err(io::Error::last_os_error());
     }
     let mut offset = 0;
-    loop {
-        let buf = &mut msgs_buf[offset..];
-
-        if buf.len() < std::mem::size_of::<rt_msghdr>() {
-            break;
-        }
-
-        let rt_hdr = unsafe { std::mem::transmute::<_, &rt_msghdr>(buf.as_ptr()) };
+    while offset < len as usize {
+        let (rt_hdr, rt_msg) = read_message(&msgs_buf[offset..len as usize])?;
         if rt_hdr.rtm_version as u32 != RTM_VERSION {
             eprintln!(
                 "Unexpected RTM_VERSION: {} in {:?}",
@@ -439,57 +331,70 @@ fn get_arp_table() -> io::Result<HashMap<IpAddr, MacAddr>> {
             return Err(code_to_error(rt_hdr.rtm_errno));
         }
 
-        let msg_len = rt_hdr.rtm_msglen as usize;
-        offset += msg_len;
+        offset += rt_hdr.rtm_msglen as usize;
 
-        let rt_msg: &mut [u8] = &mut buf[std::mem::size_of::<rt_msghdr>()..msg_len];
-        let (ip, mac) = message_to_arppair(rt_msg.as_mut_ptr());
-        arp_map.insert(ip, mac);
+        if let Some((ip, mac)) = message_to_arppair(&rt_hdr, rt_msg) {
+            arp_map.insert(ip, mac);
+        }
     }
     Ok(arp_map)
 }
 
-fn get_default_routes() -> Vec<Route> {
+fn get_default_routes(family: AddressFamily) -> Vec<Route> {
     let mut default_routes = Vec::new();
-    match list_routes() {
-        Ok(routes) => {
-            for route in routes {
-                if (route.destination == Ipv4Addr::UNSPECIFIED
-                    || route.destination == Ipv6Addr::UNSPECIFIED)
-                    && route.prefix == 0
-                    && route.gateway != Some(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
-                    && route.gateway != Some(IpAddr::V6(Ipv6Addr::UNSPECIFIED))
-                {
-                    default_routes.push(route);
-                }
+    if let Ok(routes) = list_routes(family) {
+        for route in routes {
+            if (route.destination == Ipv4Addr::UNSPECIFIED
+                || route.destination == Ipv6Addr::UNSPECIFIED)
+                && route.prefix == 0
+                && route.gateway != Some(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+                && route.gateway != Some(IpAddr::V6(Ipv6Addr::UNSPECIFIED))
+            {
+                default_routes.push(route);
             }
         }
-        Err(_) => {}
     }
     default_routes
 }
 
-pub fn get_gateway_map() -> HashMap<u32, NetworkDevice> {
-    let mut gateway_map: HashMap<u32, NetworkDevice> = HashMap::new();
-    let routes = get_default_routes();
-    let arp_map = get_arp_table().unwrap_or(HashMap::new());
-    for route in routes {
+pub fn get_ipv6_default_routers() -> io::Result<Vec<Ipv6Router>> {
+    let mut routers = Vec::new();
+    for route in list_routes(AddressFamily::V6)? {
+        if route.prefix != 0 || route.destination != Ipv6Addr::UNSPECIFIED {
+            continue;
+        }
+        if let Some(IpAddr::V6(addr)) = route.gateway {
+            if addr.is_unspecified() {
+                continue;
+            }
+            routers.push(Ipv6Router {
+                addr,
+                if_index: route.ifindex.unwrap_or(0),
+                lifetime: None,
+                preference: None,
+                metric: 0,
+                from_ra: false,
+            });
+        }
+    }
+    Ok(routers)
+}
+
+/// Get the default gateways of each interface, keyed by interface index
+pub fn get_gateway_map(family: AddressFamily) -> HashMap<u32, Vec<NetworkDevice>> {
+    let arp_map = get_arp_table().unwrap_or_default();
+    let mut hop_map: HashMap<u32, Vec<(IpAddr, MacAddr)>> = HashMap::new();
+    for route in get_default_routes(family) {
         if let Some(gw_ip) = route.gateway {
-            let gateway = gateway_map
+            let mac_addr = arp_map.get(&gw_ip).copied().unwrap_or(MacAddr::zero());
+            hop_map
                 .entry(route.ifindex.unwrap_or(0))
-                .or_insert(NetworkDevice::new());
-            if let Some(mac_addr) = arp_map.get(&gw_ip) {
-                gateway.mac_addr = mac_addr.clone();
-            }
-            match gw_ip {
-                IpAddr::V4(ipv4) => {
-                    gateway.ipv4.push(ipv4);
-                }
-                IpAddr::V6(ipv6) => {
-                    gateway.ipv6.push(ipv6);
-                }
-            }
+                .or_default()
+                .push((gw_ip, mac_addr));
         }
     }
-    gateway_map
+    hop_map
+        .into_iter()
+        .map(|(if_index, hops)| (if_index, super::group_gateways(hops)))
+        .collect()
 }
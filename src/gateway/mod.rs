@@ -7,11 +7,133 @@ pub(crate) mod macos;
 #[cfg(any(target_os = "linux", target_os = "android"))]
 pub(crate) mod linux;
 
+#[cfg(target_os = "windows")]
+pub(crate) mod windows;
+
+mod advertised;
+pub use self::advertised::*;
+
 use crate::device::NetworkDevice;
 use crate::interface::{self, Interface};
-use std::net::IpAddr;
+use crate::mac::MacAddr;
+use std::io;
+use std::net::{IpAddr, Ipv6Addr};
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Default router preference advertised in a Router Advertisement (RFC 4191)
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RouterPreference {
+    Low,
+    Medium,
+    High,
+}
+
+impl RouterPreference {
+    /// Decode the 2-bit Prf field of a Router Advertisement.
+    /// The reserved value `0b10` is treated as `Medium`, as RFC 4191 requires.
+    pub fn from_prf(prf: u8) -> RouterPreference {
+        match prf & 0b11 {
+            0b01 => RouterPreference::High,
+            0b11 => RouterPreference::Low,
+            _ => RouterPreference::Medium,
+        }
+    }
+}
+
+/// IPv6 default router
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ipv6Router {
+    /// Address of the router, usually link-local
+    pub addr: Ipv6Addr,
+    /// Index of the interface the router is reachable on
+    pub if_index: u32,
+    /// Remaining router lifetime. `None` if the route does not expire or the
+    /// platform does not report it.
+    pub lifetime: Option<Duration>,
+    /// Router preference, if reported by the platform
+    pub preference: Option<RouterPreference>,
+    /// Route metric
+    pub metric: u32,
+    /// Whether the route was learned from a Router Advertisement
+    /// (always `false` on BSD and macOS, which do not report it)
+    pub from_ra: bool,
+}
+
+/// Get the IPv6 default routers.
+///
+/// Unlike [`get_default_gateway`] this is independent of IPv4, so an empty list
+/// means the host has no IPv6 default route even when IPv4 works.
+pub fn get_ipv6_default_routers() -> io::Result<Vec<Ipv6Router>> {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let routers = linux::get_ipv6_default_routers();
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    let routers = macos::get_ipv6_default_routers();
+    #[cfg(any(target_os = "openbsd", target_os = "freebsd", target_os = "netbsd"))]
+    let routers = bsd::get_ipv6_default_routers();
+    #[cfg(target_os = "windows")]
+    let routers = windows::get_ipv6_default_routers();
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "openbsd",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "windows"
+    )))]
+    let routers = Err(io::Error::from(io::ErrorKind::Unsupported));
+    routers
+}
 
-/// Get default Gateway
+/// Group gateway addresses into one [`NetworkDevice`] per router, keeping their order.
+///
+/// Addresses resolving to the same MAC address belong to the same router. An address
+/// whose MAC address is unknown (zero) gets an entry of its own.
+pub(crate) fn group_gateways<I>(hops: I) -> Vec<NetworkDevice>
+where
+    I: IntoIterator<Item = (IpAddr, MacAddr)>,
+{
+    let mut gateways: Vec<NetworkDevice> = Vec::new();
+    for (ip, mac_addr) in hops {
+        let known = gateways.iter().any(|gw| match ip {
+            IpAddr::V4(ipv4) => gw.ipv4.contains(&ipv4),
+            IpAddr::V6(ipv6) => gw.ipv6.contains(&ipv6),
+        });
+        if known {
+            continue;
+        }
+        let existing = if mac_addr == MacAddr::zero() {
+            None
+        } else {
+            gateways.iter_mut().find(|gw| gw.mac_addr == mac_addr)
+        };
+        let gateway = match existing {
+            Some(gateway) => gateway,
+            None => {
+                gateways.push(NetworkDevice {
+                    mac_addr,
+                    ipv4: Vec::new(),
+                    ipv6: Vec::new(),
+                });
+                gateways.last_mut().unwrap()
+            }
+        };
+        match ip {
+            IpAddr::V4(ipv4) => gateway.ipv4.push(ipv4),
+            IpAddr::V6(ipv6) => gateway.ipv6.push(ipv6),
+        }
+    }
+    gateways
+}
+
+/// Get the effective default gateway: the preferred gateway of the default interface
+/// for the address family of the local IP address
 pub fn get_default_gateway() -> Result<NetworkDevice, String> {
     let local_ip: IpAddr = match interface::get_local_ipaddr() {
         Some(local_ip) => local_ip,
@@ -19,21 +141,17 @@ pub fn get_default_gateway() -> Result<NetworkDevice, String> {
     };
     let interfaces: Vec<Interface> = interface::get_interfaces();
     for iface in interfaces {
-        match local_ip {
-            IpAddr::V4(local_ipv4) => {
-                if iface.ipv4.iter().any(|x| x.addr == local_ipv4) {
-                    if let Some(gateway) = iface.gateway {
-                        return Ok(gateway);
-                    }
-                }
+        let gateway = match local_ip {
+            IpAddr::V4(local_ipv4) if iface.ipv4.iter().any(|x| x.addr == local_ipv4) => {
+                iface.gateways.into_iter().find(|gw| !gw.ipv4.is_empty())
             }
-            IpAddr::V6(local_ipv6) => {
-                if iface.ipv6.iter().any(|x| x.addr == local_ipv6) {
-                    if let Some(gateway) = iface.gateway {
-                        return Ok(gateway);
-                    }
-                }
+            IpAddr::V6(local_ipv6) if iface.ipv6.iter().any(|x| x.addr == local_ipv6) => {
+                iface.gateways.into_iter().find(|gw| !gw.ipv6.is_empty())
             }
+            _ => continue,
+        };
+        if let Some(gateway) = gateway {
+            return Ok(gateway);
         }
     }
     Err(String::from("Default Gateway not found"))
@@ -42,8 +160,46 @@ pub fn get_default_gateway() -> Result<NetworkDevice, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::net::Ipv4Addr;
     #[test]
     fn test_default_gateway() {
         println!("{:?}", get_default_gateway());
     }
+    #[test]
+    fn test_group_gateways() {
+        let router = MacAddr::new(0x02, 0, 0, 0, 0, 1);
+        let gateways = group_gateways(vec![
+            (IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)), router),
+            (IpAddr::V4(Ipv4Addr::new(192, 168, 1, 254)), MacAddr::zero()),
+            (
+                IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)),
+                router,
+            ),
+            (
+                IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 2)),
+                MacAddr::zero(),
+            ),
+            (IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)), router),
+        ]);
+        assert_eq!(gateways.len(), 3);
+        assert_eq!(gateways[0].mac_addr, router);
+        assert_eq!(gateways[0].ipv4, vec![Ipv4Addr::new(192, 168, 1, 1)]);
+        assert_eq!(
+            gateways[0].ipv6,
+            vec![Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)]
+        );
+        assert_eq!(gateways[1].ipv4, vec![Ipv4Addr::new(192, 168, 1, 254)]);
+        assert!(gateways[2].ipv4.is_empty());
+    }
+    #[test]
+    fn test_router_preference() {
+        assert_eq!(RouterPreference::from_prf(0b00), RouterPreference::Medium);
+        assert_eq!(RouterPreference::from_prf(0b01), RouterPreference::High);
+        assert_eq!(RouterPreference::from_prf(0b10), RouterPreference::Medium);
+        assert_eq!(RouterPreference::from_prf(0b11), RouterPreference::Low);
+    }
+    #[test]
+    fn test_ipv6_default_routers() {
+        println!("{:?}", get_ipv6_default_routers());
+    }
 }
@@ -0,0 +1,46 @@
+use super::Ipv6Router;
+use std::io;
+use std::net::Ipv6Addr;
+use std::time::Duration;
+use windows_sys::Win32::Foundation::NO_ERROR;
+use windows_sys::Win32::NetworkManagement::IpHelper::{
+    FreeMibTable, GetIpForwardTable2, MIB_IPFORWARD_TABLE2,
+};
+use windows_sys::Win32::Networking::WinSock::{NlroRouterAdvertisement, AF_INET6};
+
+/// ValidLifetime of a route that never expires
+const INFINITE_LIFETIME: u32 = 0xffff_ffff;
+
+pub fn get_ipv6_default_routers() -> io::Result<Vec<Ipv6Router>> {
+    let mut table: *mut MIB_IPFORWARD_TABLE2 = std::ptr::null_mut();
+    let ret = unsafe { GetIpForwardTable2(AF_INET6, &mut table) };
+    if ret != NO_ERROR {
+        return Err(io::Error::from_raw_os_error(ret as i32));
+    }
+    let rows = unsafe {
+        std::slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize)
+    };
+    let mut routers = Vec::new();
+    for row in rows {
+        if row.DestinationPrefix.PrefixLength != 0 {
+            continue;
+        }
+        let addr = Ipv6Addr::from(unsafe { row.NextHop.Ipv6.sin6_addr.u.Byte });
+        if addr.is_unspecified() {
+            continue;
+        }
+        routers.push(Ipv6Router {
+            addr,
+            if_index: row.InterfaceIndex,
+            lifetime: match row.ValidLifetime {
+                INFINITE_LIFETIME => None,
+                secs => Some(Duration::from_secs(secs as u64)),
+            },
+            preference: None,
+            metric: row.Metric,
+            from_ra: row.Origin == NlroRouterAdvertisement,
+        });
+    }
+    unsafe { FreeMibTable(table as *const _) };
+    Ok(routers)
+}
@@ -0,0 +1,80 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Duplicate Address Detection state of an assigned address
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DadState {
+    /// DAD is still in progress; the address cannot be used yet
+    Tentative,
+    /// DAD is in progress, but the address is usable (RFC 4429)
+    Optimistic,
+    /// DAD failed: another node on the link uses the address
+    Duplicate,
+    /// DAD passed and the address is preferred
+    Preferred,
+    /// DAD passed, but the address is deprecated and should not be used for new connections
+    Deprecated,
+}
+
+impl DadState {
+    /// Returns true if the address passed DAD
+    pub fn passed(&self) -> bool {
+        matches!(self, DadState::Preferred | DadState::Deprecated)
+    }
+}
+
+/// How an address was assigned
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AddressOrigin {
+    /// Configured statically by an administrator
+    Manual,
+    /// Leased from a DHCP or DHCPv6 server
+    Dhcp,
+    /// Self-assigned link-local address (IPv4 APIPA or IPv6 `fe80::/10`)
+    LinkLocal,
+    /// Autoconfigured from a router advertisement prefix (SLAAC)
+    RouterAdvertisement,
+    /// Randomly generated interface identifier, such as an IPv6 temporary address
+    Random,
+    /// Any other origin, such as the loopback address
+    Other,
+}
+
+/// OS-reported state of a single address assigned to an interface
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AddressInfo {
+    /// The assigned address
+    pub addr: IpAddr,
+    /// Prefix length of the on-link subnet
+    pub prefix_len: u8,
+    /// DAD state, or `None` if no DAD is performed for the address
+    /// (e.g. IPv4 on Linux, or IPv6 with `nodad`)
+    pub dad_state: Option<DadState>,
+    /// Remaining time until the address is removed. `None` if the address does
+    /// not expire (e.g. statically configured) or the platform does not report it.
+    pub valid_lifetime: Option<Duration>,
+    /// Remaining time until the address becomes deprecated. `None` if it never
+    /// does or the platform does not report it.
+    pub preferred_lifetime: Option<Duration>,
+    /// Label of an IPv4 address on Linux, such as `eth0:1` for an alias
+    pub label: Option<String>,
+    /// The address is a secondary IPv4 address, in a subnet that already has
+    /// a primary address on the interface (Linux only)
+    pub secondary: bool,
+    /// How the address was assigned. On Linux this is inferred from the address
+    /// flags and lifetimes, as the kernel does not record DHCP leases.
+    pub origin: AddressOrigin,
+    /// The address is an IPv6 temporary address (RFC 8981), generated for
+    /// privacy and replaced regularly. Windows does not flag them, so they are
+    /// told from the stable address of their prefix by their shorter lifetime:
+    /// random addresses of a prefix whose lifetimes are equal, such as right
+    /// after a router advertisement with short lifetimes, are all reported as
+    /// not temporary there.
+    pub temporary: bool,
+}
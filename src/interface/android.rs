@@ -47,9 +47,6 @@ pub mod netlink {
     //!
     //! Based on the logic found in https://git.musl-libc.org/cgit/musl/tree/src/network/getifaddrs.c
 
-    use netlink_packet_core::{
-        NetlinkHeader, NetlinkMessage, NetlinkPayload, NLM_F_DUMP, NLM_F_REQUEST,
-    };
     use netlink_packet_route::{
         rtnl::address::nlas::Nla as AddressNla, rtnl::link::nlas::Nla as LinkNla, AddressMessage,
         LinkMessage, RtnlMessage,
@@ -59,9 +56,12 @@ pub mod netlink {
     use std::net::{Ipv4Addr, Ipv6Addr};
 
     use crate::interface::{Interface, InterfaceType, Ipv4Net, Ipv6Net};
-    use crate::mac::MacAddr;
+    use crate::mac::{HardwareAddr, MacAddr};
+    use crate::stats;
+    use crate::sys::netlink::NetlinkIter;
 
     pub fn unix_interfaces() -> Vec<Interface> {
+        let _span = debug_span!("netlink_ifaddrs");
         let mut ifaces = Vec::new();
         if let Ok(socket) = Socket::new(NETLINK_ROUTE) {
             if let Err(err) = enumerate_netlink(
@@ -85,198 +85,106 @@ pub mod netlink {
     }
 
     fn handle_new_link(ifaces: &mut Vec<Interface>, msg: RtnlMessage) -> io::Result<()> {
-        match msg {
-            RtnlMessage::NewLink(link_msg) => {
-                let mut interface: Interface = Interface {
-                    index: link_msg.header.index,
-                    name: String::new(),
-                    friendly_name: None,
-                    description: None,
-                    if_type: InterfaceType::try_from(link_msg.header.link_layer_type as u32)
-                        .unwrap_or(InterfaceType::Unknown),
-                    mac_addr: None,
-                    ipv4: Vec::new(),
-                    ipv6: Vec::new(),
-                    flags: link_msg.header.flags,
-                    transmit_speed: None,
-                    receive_speed: None,
-                    gateway: None,
-                    dns_servers: Vec::new(),
-                    default: false,
-                };
+        if let RtnlMessage::NewLink(link_msg) = msg {
+            let mut interface: Interface = Interface {
+                index: link_msg.header.index,
+                name: String::new(),
+                friendly_name: None,
+                description: None,
+                if_type: InterfaceType::try_from(link_msg.header.link_layer_type as u32)
+                    .unwrap_or(InterfaceType::Unknown),
+                mac_addr: None,
+                hardware_addr: None,
+                ipv4: Vec::new(),
+                ipv6: Vec::new(),
+                flags: link_msg.header.flags,
+                transmit_speed: None,
+                receive_speed: None,
+                gateways: Vec::new(),
+                dns_servers: Vec::new(),
+                default: false,
+                stats: None,
+                driver: None,
+                device_path: None,
+                carrier_changes: None,
+                link_up_since: None,
+                network_manager: None,
+                networkd: None,
+                netns: None,
+            };
 
-                for nla in link_msg.nlas {
-                    match nla {
-                        LinkNla::IfName(name) => {
-                            interface.name = name;
+            for nla in link_msg.nlas {
+                match nla {
+                    LinkNla::IfName(name) => {
+                        interface.name = name;
+                    }
+                    LinkNla::CarrierChanges(count) => {
+                        interface.carrier_changes = Some(count);
+                    }
+                    LinkNla::Stats64(bytes) => {
+                        interface.stats = stats::stats_from_stats64(&bytes);
+                    }
+                    LinkNla::Address(addr) => match addr.len() {
+                        6 => {
+                            let mac = MacAddr::from_octets(addr.try_into().unwrap());
+                            interface.mac_addr = Some(mac);
+                            interface.hardware_addr = Some(mac.into());
                         }
-                        LinkNla::Address(addr) => {
-                            match addr.len() {
-                                6 => {
-                                    interface.mac_addr =
-                                        Some(MacAddr::from_octets(addr.try_into().unwrap()));
-                                }
-                                4 => {
-                                    let ip = Ipv4Addr::from(<[u8; 4]>::try_from(addr).unwrap());
-                                    interface
-                                        .ipv4
-                                        .push(Ipv4Net::new_with_netmask(ip, Ipv4Addr::UNSPECIFIED));
-                                }
-                                _ => {
-                                    // unclear what these would be
-                                }
-                            }
+                        4 => {
+                            let ip = Ipv4Addr::from(<[u8; 4]>::try_from(addr).unwrap());
+                            interface
+                                .ipv4
+                                .push(Ipv4Net::new_with_netmask(ip, Ipv4Addr::UNSPECIFIED));
                         }
-                        _ => {}
-                    }
-                }
-                ifaces.push(interface);
-            }
-            _ => {}
-        }
-
-        Ok(())
-    }
-
-    fn handle_new_addr(ifaces: &mut Vec<Interface>, msg: RtnlMessage) -> io::Result<()> {
-        match msg {
-            RtnlMessage::NewAddress(addr_msg) => {
-                if let Some(interface) =
-                    ifaces.iter_mut().find(|i| i.index == addr_msg.header.index)
-                {
-                    for nla in addr_msg.nlas {
-                        match nla {
-                            AddressNla::Address(addr) => match addr.len() {
-                                4 => {
-                                    let ip = Ipv4Addr::from(<[u8; 4]>::try_from(addr).unwrap());
-                                    interface
-                                        .ipv4
-                                        .push(Ipv4Net::new(ip, addr_msg.header.prefix_len));
-                                }
-                                16 => {
-                                    let ip = Ipv6Addr::from(<[u8; 16]>::try_from(addr).unwrap());
-                                    interface
-                                        .ipv6
-                                        .push(Ipv6Net::new(ip, addr_msg.header.prefix_len));
-                                }
-                                _ => {
-                                    // what else?
-                                }
-                            },
-                            _ => {}
+                        0 => {}
+                        _ => {
+                            interface.hardware_addr = Some(HardwareAddr::from_bytes(&addr));
                         }
-                    }
-                } else {
-                    eprintln!(
-                        "found unknown interface with index: {}",
-                        addr_msg.header.index
-                    );
+                    },
+                    _ => {}
                 }
             }
-            _ => {}
+            ifaces.push(interface);
         }
 
         Ok(())
     }
 
-    struct NetlinkIter<'a> {
-        socket: &'a Socket,
-        /// Buffer for received data.
-        buf: Vec<u8>,
-        /// Size of the data available in `buf`.
-        size: usize,
-        /// Offset into the data currently in `buf`.
-        offset: usize,
-        /// Are we don iterating?
-        done: bool,
-    }
-
-    impl<'a> NetlinkIter<'a> {
-        fn new(socket: &'a Socket, msg: RtnlMessage) -> io::Result<Self> {
-            let mut packet =
-                NetlinkMessage::new(NetlinkHeader::default(), NetlinkPayload::from(msg));
-            packet.header.flags = NLM_F_DUMP | NLM_F_REQUEST;
-            packet.header.sequence_number = 1;
-            packet.finalize();
-
-            let mut buf = vec![0; packet.header.length as usize];
-            if buf.len() != packet.buffer_len() {
-                return Err(io::Error::new(
-                    io::ErrorKind::Other,
-                    "Buffer length mismatch in netlink packet",
-                ));
-            }
-            packet.serialize(&mut buf[..]);
-            socket.send(&buf[..], 0)?;
-
-            Ok(NetlinkIter {
-                socket,
-                offset: 0,
-                size: 0,
-                buf: vec![0u8; 4096],
-                done: false,
-            })
-        }
-    }
-
-    impl<'a> Iterator for NetlinkIter<'a> {
-        type Item = io::Result<RtnlMessage>;
-
-        fn next(&mut self) -> Option<Self::Item> {
-            if self.done {
-                return None;
-            }
-
-            while !self.done {
-                // Outer loop
-                if self.size == 0 {
-                    match self.socket.recv(&mut &mut self.buf[..], 0) {
-                        Ok(size) => {
-                            self.size = size;
-                            self.offset = 0;
-                        }
-                        Err(err) => {
-                            self.done = true;
-                            return Some(Err(err));
-                        }
-                    }
-                }
-
-                let bytes = &self.buf[self.offset..];
-                match NetlinkMessage::<RtnlMessage>::deserialize(bytes) {
-                    Ok(packet) => {
-                        self.offset += packet.header.length as usize;
-                        if packet.header.length == 0 || self.offset == self.size {
-                            // mark this message as fully read
-                            self.size = 0;
-                        }
-                        match packet.payload {
-                            NetlinkPayload::Done(_) => {
-                                self.done = true;
-                                return None;
+    // Takes a Vec to match the handler type of enumerate_netlink
+    #[allow(clippy::ptr_arg)]
+    fn handle_new_addr(ifaces: &mut Vec<Interface>, msg: RtnlMessage) -> io::Result<()> {
+        if let RtnlMessage::NewAddress(addr_msg) = msg {
+            if let Some(interface) = ifaces.iter_mut().find(|i| i.index == addr_msg.header.index) {
+                for nla in addr_msg.nlas {
+                    if let AddressNla::Address(addr) = nla {
+                        match addr.len() {
+                            4 => {
+                                let ip = Ipv4Addr::from(<[u8; 4]>::try_from(addr).unwrap());
+                                interface
+                                    .ipv4
+                                    .push(Ipv4Net::new(ip, addr_msg.header.prefix_len));
                             }
-                            NetlinkPayload::Error(err) => {
-                                self.done = true;
-                                return Some(Err(io::Error::new(
-                                    io::ErrorKind::Other,
-                                    err.to_string(),
-                                )));
+                            16 => {
+                                let ip = Ipv6Addr::from(<[u8; 16]>::try_from(addr).unwrap());
+                                interface
+                                    .ipv6
+                                    .push(Ipv6Net::new(ip, addr_msg.header.prefix_len));
                             }
-                            NetlinkPayload::InnerMessage(msg) => return Some(Ok(msg)),
                             _ => {
-                                continue;
+                                // what else?
                             }
                         }
                     }
-                    Err(err) => {
-                        self.done = true;
-                        return Some(Err(io::Error::new(io::ErrorKind::Other, err.to_string())));
-                    }
                 }
+            } else {
+                eprintln!(
+                    "found unknown interface with index: {}",
+                    addr_msg.header.index
+                );
             }
-
-            None
         }
+
+        Ok(())
     }
 
     fn enumerate_netlink<F>(
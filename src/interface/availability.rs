@@ -0,0 +1,94 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Which optional fields of [`Interface`](super::Interface) the enumeration can
+/// fill in on the current platform.
+///
+/// A `false` field is always empty, so it tells "not available here" apart from
+/// "the interface has none".
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FieldAvailability {
+    /// `mac_addr` and `hardware_addr`. iOS hides them from apps.
+    pub mac_addr: bool,
+    /// `friendly_name`
+    pub friendly_name: bool,
+    /// `description`
+    pub description: bool,
+    /// `transmit_speed` and `receive_speed`
+    pub speed: bool,
+    /// `gateways`
+    pub gateways: bool,
+    /// `dns_servers`
+    pub dns_servers: bool,
+    /// `stats`
+    pub stats: bool,
+    /// `driver`
+    pub driver: bool,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const FIELD_AVAILABILITY: FieldAvailability = FieldAvailability {
+    mac_addr: true,
+    friendly_name: false,
+    description: true,
+    speed: true,
+    gateways: true,
+    dns_servers: true,
+    stats: true,
+    driver: true,
+};
+
+#[cfg(target_os = "macos")]
+const FIELD_AVAILABILITY: FieldAvailability = FieldAvailability {
+    mac_addr: true,
+    friendly_name: true,
+    description: true,
+    speed: false,
+    gateways: true,
+    dns_servers: true,
+    stats: true,
+    driver: true,
+};
+
+#[cfg(target_os = "ios")]
+const FIELD_AVAILABILITY: FieldAvailability = FieldAvailability {
+    mac_addr: false,
+    friendly_name: false,
+    description: false,
+    speed: false,
+    gateways: true,
+    dns_servers: false,
+    stats: true,
+    driver: false,
+};
+
+#[cfg(any(target_os = "openbsd", target_os = "freebsd", target_os = "netbsd"))]
+const FIELD_AVAILABILITY: FieldAvailability = FieldAvailability {
+    mac_addr: true,
+    friendly_name: false,
+    description: false,
+    speed: false,
+    gateways: true,
+    dns_servers: true,
+    stats: true,
+    driver: false,
+};
+
+#[cfg(target_os = "windows")]
+const FIELD_AVAILABILITY: FieldAvailability = FieldAvailability {
+    mac_addr: true,
+    friendly_name: true,
+    description: true,
+    speed: true,
+    gateways: true,
+    dns_servers: true,
+    stats: true,
+    driver: true,
+};
+
+/// Returns which optional [`Interface`](super::Interface) fields the
+/// enumeration fills in on this platform
+pub fn field_availability() -> FieldAvailability {
+    FIELD_AVAILABILITY
+}
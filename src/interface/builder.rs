@@ -0,0 +1,164 @@
+use super::{DriverInfo, Interface, InterfaceType};
+use crate::device::NetworkDevice;
+use crate::ip::{Ipv4Net, Ipv6Net};
+use crate::mac::{HardwareAddr, MacAddr};
+use crate::stats::InterfaceStats;
+use crate::sys;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Builder for [`Interface`] values made up by the caller, such as test
+/// fixtures and the data of a [`Backend`](crate::backend::Backend).
+///
+/// Fields without a setter keep their empty value, so code using the builder
+/// keeps compiling when fields are added to [`Interface`].
+#[derive(Clone, Debug)]
+pub struct InterfaceBuilder {
+    interface: Interface,
+}
+
+impl InterfaceBuilder {
+    /// Start an interface with the given index and name. It is down and has
+    /// no addresses.
+    pub fn new(index: u32, name: &str) -> InterfaceBuilder {
+        let mut interface = Interface::dummy();
+        interface.index = index;
+        interface.name = name.to_string();
+        InterfaceBuilder { interface }
+    }
+    /// Set the index
+    pub fn index(mut self, index: u32) -> InterfaceBuilder {
+        self.interface.index = index;
+        self
+    }
+    /// Set the name
+    pub fn name(mut self, name: &str) -> InterfaceBuilder {
+        self.interface.name = name.to_string();
+        self
+    }
+    /// Set the friendly name
+    pub fn friendly_name(mut self, friendly_name: &str) -> InterfaceBuilder {
+        self.interface.friendly_name = Some(friendly_name.to_string());
+        self
+    }
+    /// Set the description
+    pub fn description(mut self, description: &str) -> InterfaceBuilder {
+        self.interface.description = Some(description.to_string());
+        self
+    }
+    /// Set the interface type
+    pub fn if_type(mut self, if_type: InterfaceType) -> InterfaceBuilder {
+        self.interface.if_type = if_type;
+        self
+    }
+    /// Set the MAC address
+    pub fn mac_addr(mut self, mac_addr: MacAddr) -> InterfaceBuilder {
+        self.interface.mac_addr = Some(mac_addr);
+        self.interface.hardware_addr = Some(mac_addr.into());
+        self
+    }
+    /// Set a hardware address of any length, and the MAC address if it is 6 bytes long
+    pub fn hardware_addr(mut self, hardware_addr: HardwareAddr) -> InterfaceBuilder {
+        self.interface.mac_addr = hardware_addr.mac_addr();
+        self.interface.hardware_addr = Some(hardware_addr);
+        self
+    }
+    /// Add an IPv4 address
+    pub fn ipv4(mut self, addr: Ipv4Addr, prefix_len: u8) -> InterfaceBuilder {
+        self.interface.ipv4.push(Ipv4Net::new(addr, prefix_len));
+        self
+    }
+    /// Add an IPv6 address
+    pub fn ipv6(mut self, addr: Ipv6Addr, prefix_len: u8) -> InterfaceBuilder {
+        self.interface.ipv6.push(Ipv6Net::new(addr, prefix_len));
+        self
+    }
+    /// Add OS-specific flags
+    pub fn flags(mut self, flags: u32) -> InterfaceBuilder {
+        self.interface.flags |= flags;
+        self
+    }
+    /// Mark the interface as up and running
+    pub fn up(self) -> InterfaceBuilder {
+        #[cfg(unix)]
+        let flags = sys::IFF_UP as u32 | sys::IFF_RUNNING as u32;
+        #[cfg(not(unix))]
+        let flags = sys::IFF_UP;
+        self.flags(flags)
+    }
+    /// Mark the interface as a loopback interface
+    pub fn loopback(self) -> InterfaceBuilder {
+        #[cfg(unix)]
+        let flags = sys::IFF_LOOPBACK as u32;
+        #[cfg(not(unix))]
+        let flags = sys::IFF_LOOPBACK;
+        self.if_type(InterfaceType::Loopback).flags(flags)
+    }
+    /// Set the transmit and receive speed in bits per second
+    pub fn speed(mut self, bits_per_second: u64) -> InterfaceBuilder {
+        self.interface.transmit_speed = Some(bits_per_second);
+        self.interface.receive_speed = Some(bits_per_second);
+        self
+    }
+    /// Add a gateway
+    pub fn gateway(mut self, gateway: NetworkDevice) -> InterfaceBuilder {
+        self.interface.gateways.push(gateway);
+        self
+    }
+    /// Add a DNS server
+    pub fn dns_server(mut self, addr: IpAddr) -> InterfaceBuilder {
+        self.interface.dns_servers.push(addr);
+        self
+    }
+    /// Mark the interface as the one holding the default route
+    pub fn default_route(mut self, default: bool) -> InterfaceBuilder {
+        self.interface.default = default;
+        self
+    }
+    /// Set the traffic counters
+    pub fn stats(mut self, stats: InterfaceStats) -> InterfaceBuilder {
+        self.interface.stats = Some(stats);
+        self
+    }
+    /// Set the driver information
+    pub fn driver(mut self, driver: DriverInfo) -> InterfaceBuilder {
+        self.interface.driver = Some(driver);
+        self
+    }
+    /// Set the inode number of the network namespace
+    pub fn netns(mut self, netns: u64) -> InterfaceBuilder {
+        self.interface.netns = Some(netns);
+        self
+    }
+    /// Finish the interface
+    pub fn build(self) -> Interface {
+        self.interface
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder() {
+        let interface = Interface::builder()
+            .name("test0")
+            .index(7)
+            .ipv4(Ipv4Addr::new(10, 0, 0, 2), 24)
+            .ipv6(Ipv6Addr::LOCALHOST, 128)
+            .mac_addr(MacAddr::new(0x02, 0, 0, 0, 0, 0x07))
+            .speed(1_000_000_000)
+            .up()
+            .build();
+        assert_eq!((interface.index, interface.name.as_str()), (7, "test0"));
+        assert_eq!(interface.ipv4[0].prefix_len, 24);
+        assert_eq!(interface.ipv6.len(), 1);
+        assert_eq!(interface.hardware_addr, interface.mac_addr.map(Into::into));
+        assert_eq!(interface.receive_speed, Some(1_000_000_000));
+        assert!(interface.is_up() && !interface.is_loopback());
+        assert!(InterfaceBuilder::new(1, "lo")
+            .loopback()
+            .build()
+            .is_loopback());
+    }
+}
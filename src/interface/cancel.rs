@@ -0,0 +1,161 @@
+use crate::interface::{enumerate, DetailLevel, EnumOptions, Interface};
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a waiting caller checks its [`CancelToken`]
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Shared flag for abandoning an enumeration started with [`interfaces_with_cancel`]
+///
+/// Clones share the same flag, so one clone can be handed to a UI thread.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Create a token that is not cancelled
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+    /// Cancel every enumeration waiting on this token
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+    /// Check if the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Get the network interfaces, giving up after `timeout`
+///
+/// Enumeration runs on a worker thread. If it has not finished by `timeout`,
+/// the interfaces are returned with the details of [`DetailLevel::Basic`]
+/// only, when those were listed in time, or else an error of kind
+/// [`io::ErrorKind::TimedOut`]. The workers still running are left to finish
+/// in the background and their results are discarded.
+pub fn interfaces_with_timeout(timeout: Duration) -> io::Result<Vec<Interface>> {
+    interfaces_with_cancel(&EnumOptions::default(), &CancelToken::new(), Some(timeout))
+}
+
+/// Get the network interfaces with the given options, returning early if `token`
+/// is cancelled ([`io::ErrorKind::Interrupted`]) or `timeout` elapses.
+///
+/// On timeout, the interfaces are returned with the details of
+/// [`DetailLevel::Basic`] only if those were listed in time, as for
+/// [`interfaces_with_timeout`]; otherwise [`io::ErrorKind::TimedOut`] is
+/// returned. Lists have no partial form below that level: the OS returns the
+/// whole interface list at once, so one that does not return in time has
+/// produced nothing yet.
+pub fn interfaces_with_cancel(
+    options: &EnumOptions,
+    token: &CancelToken,
+    timeout: Option<Duration>,
+) -> io::Result<Vec<Interface>> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let (tx, rx) = mpsc::channel();
+    // The basic list is taken alongside the full one, as a fallback that
+    // does not wait for the lookups of the higher levels
+    let mut levels = vec![options.detail_level];
+    if timeout.is_some() && options.detail_level > DetailLevel::Basic {
+        levels.push(DetailLevel::Basic);
+    }
+    for level in levels {
+        let tx = tx.clone();
+        let options = EnumOptions {
+            detail_level: level,
+            ..options.clone()
+        };
+        #[cfg(feature = "mock")]
+        let provider = crate::mock::current();
+        thread::Builder::new()
+            .name("netdev-enum".to_string())
+            .spawn(move || {
+                #[cfg(feature = "mock")]
+                let _guard = crate::mock::install(provider);
+                // The receiver may be gone after a timeout.
+                let _ = tx.send((level, enumerate(&options)));
+            })?;
+    }
+    drop(tx);
+    let mut partial: Option<Vec<Interface>> = None;
+    loop {
+        if token.is_cancelled() {
+            return Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "interface enumeration was cancelled",
+            ));
+        }
+        let mut wait = POLL_INTERVAL;
+        if let Some(deadline) = deadline {
+            let now = Instant::now();
+            if now >= deadline {
+                return partial.ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::TimedOut, "interface enumeration timed out")
+                });
+            }
+            wait = wait.min(deadline - now);
+        }
+        match rx.recv_timeout(wait) {
+            Ok((level, interfaces)) if level == options.detail_level => return Ok(interfaces),
+            Ok((_, interfaces)) => partial = Some(interfaces),
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => {
+                return partial.ok_or_else(|| io::Error::other("interface enumeration failed"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interfaces_with_timeout() {
+        let interfaces = interfaces_with_timeout(Duration::from_secs(30)).unwrap();
+        assert_eq!(interfaces.len(), crate::interface::get_interfaces().len());
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn test_timeout_partial() {
+        use crate::mock::{self, InterfaceBuilder, InterfaceProvider};
+
+        // Stalls above the basic level, like a hung driver query
+        struct Stalled;
+        impl InterfaceProvider for Stalled {
+            fn interfaces(&self, options: &EnumOptions) -> Vec<Interface> {
+                if options.detail_level > DetailLevel::Basic {
+                    thread::sleep(Duration::from_secs(5));
+                }
+                vec![InterfaceBuilder::new(2, "eth0").up().build()]
+            }
+        }
+        let _guard = mock::set_provider(Stalled);
+        let options = EnumOptions::default();
+        let timeout = Some(Duration::from_millis(500));
+        let interfaces = interfaces_with_cancel(&options, &CancelToken::new(), timeout).unwrap();
+        assert_eq!(interfaces.len(), 1);
+        assert_eq!(interfaces[0].name, "eth0");
+
+        let options = EnumOptions {
+            detail_level: DetailLevel::Basic,
+            ..EnumOptions::default()
+        };
+        let err = interfaces_with_cancel(&options, &CancelToken::new(), Some(Duration::ZERO))
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let token = CancelToken::new();
+        token.clone().cancel();
+        let err = interfaces_with_cancel(&EnumOptions::default(), &token, None).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::interface::Interface;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Interfaces backed by the same physical network card
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeviceGroup {
+    /// Location of the card: the common part of the members' `device_path`,
+    /// without the PCI function or USB interface number
+    pub device: String,
+    /// Interfaces of the card, in the order they were given
+    pub interfaces: Vec<Interface>,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use super::linux::get_physfn_path;
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn get_physfn_path(_device_path: &str) -> Option<String> {
+    None
+}
+
+/// Check if `s` is a PCI address such as `0000:03:00.1`
+fn is_pci_address(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() == 12
+        && b[4] == b':'
+        && b[7] == b':'
+        && b[10] == b'.'
+        && b.iter()
+            .enumerate()
+            .all(|(i, c)| matches!(i, 4 | 7 | 10) || c.is_ascii_hexdigit())
+}
+
+/// Check if `s` is a USB interface such as `1-2.4:1.0`, as opposed to a USB device
+fn is_usb_interface(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_digit()) && s.contains('-') && s.contains(':')
+}
+
+/// Reduce a device path to the card it belongs to, so that the functions of a
+/// multi-port PCI card, or the interfaces of a USB device, share one key
+fn card_of(device_path: &str) -> String {
+    // Windows: "PCI bus 3, device 0, function 1"
+    if let Some((card, function)) = device_path.rsplit_once(", function ") {
+        if function.parse::<u8>().is_ok() {
+            return card.to_string();
+        }
+    }
+    let mut segments: Vec<&str> = device_path.split('/').collect();
+    if segments.last().is_some_and(|last| is_usb_interface(last)) {
+        segments.pop();
+        return segments.join("/");
+    }
+    match segments.pop() {
+        Some(last) if is_pci_address(last) => {
+            segments.push(&last[..last.len() - 2]);
+            segments.join("/")
+        }
+        _ => device_path.to_string(),
+    }
+}
+
+/// Group interfaces by the physical card they belong to: the ports of a
+/// multi-port card, VLANs and other interfaces stacked on them, and, on Linux,
+/// SR-IOV virtual functions together with their physical function.
+///
+/// Interfaces without a [`device_path`](Interface::device_path) are left out.
+/// Groups are in the order of their first interface.
+pub fn group_by_device(interfaces: &[Interface]) -> Vec<DeviceGroup> {
+    let mut groups: Vec<DeviceGroup> = Vec::new();
+    for interface in interfaces {
+        let Some(path) = &interface.device_path else {
+            continue;
+        };
+        let device = card_of(&get_physfn_path(path).unwrap_or_else(|| path.clone()));
+        match groups.iter_mut().find(|group| group.device == device) {
+            Some(group) => group.interfaces.push(interface.clone()),
+            None => groups.push(DeviceGroup {
+                device,
+                interfaces: vec![interface.clone()],
+            }),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_of() {
+        assert_eq!(
+            card_of("pci0000:00/0000:00:1c.0/0000:03:00.1"),
+            "pci0000:00/0000:00:1c.0/0000:03:00"
+        );
+        assert_eq!(
+            card_of("pci0000:00/0000:00:14.0/usb1/1-2/1-2:1.0"),
+            "pci0000:00/0000:00:14.0/usb1/1-2"
+        );
+        assert_eq!(
+            card_of("platform/ff540000.ethernet"),
+            "platform/ff540000.ethernet"
+        );
+        assert_eq!(
+            card_of("PCI bus 3, device 0, function 1"),
+            "PCI bus 3, device 0"
+        );
+        assert_eq!(card_of("Port_#0002.Hub_#0001"), "Port_#0002.Hub_#0001");
+    }
+
+    #[test]
+    fn test_group_by_device() {
+        let interface = |name: &str, path: Option<&str>| {
+            let mut interface = Interface::dummy();
+            interface.name = name.to_string();
+            interface.device_path = path.map(str::to_string);
+            interface
+        };
+        let interfaces = vec![
+            interface("enp3s0f0", Some("pci0000:00/0000:00:1c.0/0000:03:00.0")),
+            interface("wlp4s0", Some("pci0000:00/0000:00:1c.1/0000:04:00.0")),
+            interface("enp3s0f1", Some("pci0000:00/0000:00:1c.0/0000:03:00.1")),
+            interface("enp3s0f0.10", Some("pci0000:00/0000:00:1c.0/0000:03:00.0")),
+            interface("lo", None),
+        ];
+        let groups = group_by_device(&interfaces);
+        let names: Vec<Vec<&str>> = groups
+            .iter()
+            .map(|group| group.interfaces.iter().map(|i| i.name.as_str()).collect())
+            .collect();
+        assert_eq!(
+            names,
+            vec![vec!["enp3s0f0", "enp3s0f1", "enp3s0f0.10"], vec!["wlp4s0"]]
+        );
+        for group in group_by_device(&crate::interface::get_interfaces()) {
+            println!(
+                "{}: {:?}",
+                group.device,
+                group.interfaces.iter().map(|i| &i.name).collect::<Vec<_>>()
+            );
+        }
+    }
+}
@@ -0,0 +1,16 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Driver and firmware information of a network interface
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DriverInfo {
+    /// Name of the driver (kernel module, IOKit class or Windows service)
+    pub name: String,
+    /// Driver version
+    pub version: Option<String>,
+    /// Firmware version of the device
+    pub firmware_version: Option<String>,
+    /// Bus location of the device (e.g. PCI address)
+    pub bus_info: Option<String>,
+}
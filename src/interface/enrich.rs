@@ -0,0 +1,45 @@
+use crate::interface::Interface;
+
+/// Fewest interfaces per thread worth spawning one for
+#[cfg(feature = "parallel")]
+const INTERFACES_PER_THREAD: usize = 8;
+
+/// Run the per-interface lookups `f` on every interface.
+///
+/// With the `parallel` feature, hosts with many interfaces get them spread
+/// over scoped threads, as each lookup mostly waits on syscalls.
+#[cfg(feature = "parallel")]
+pub(crate) fn for_each(interfaces: &mut [Interface], f: impl Fn(&mut Interface) + Sync) {
+    let threads = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(interfaces.len() / INTERFACES_PER_THREAD);
+    if threads <= 1 {
+        interfaces.iter_mut().for_each(f);
+        return;
+    }
+    let chunk_size = interfaces.len().div_ceil(threads);
+    let f = &f;
+    std::thread::scope(|scope| {
+        for chunk in interfaces.chunks_mut(chunk_size) {
+            scope.spawn(move || chunk.iter_mut().for_each(f));
+        }
+    });
+}
+
+/// Run the per-interface lookups `f` on every interface
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn for_each(interfaces: &mut [Interface], f: impl Fn(&mut Interface) + Sync) {
+    interfaces.iter_mut().for_each(f);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_each() {
+        let mut interfaces: Vec<Interface> = (0..100).map(|_| Interface::dummy()).collect();
+        for_each(&mut interfaces, |iface| iface.index += 1);
+        assert!(interfaces.iter().all(|iface| iface.index == 1));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::interface::{EnumOptions, Interface};
+
+/// Reusable enumerator for callers that list interfaces repeatedly,
+/// such as a monitoring loop polling once a second
+///
+/// On Windows the working buffer for `GetAdaptersAddresses`, which grows to
+/// several hundred KB on hosts with many adapters, is kept between calls to
+/// [`refresh`](Enumerator::refresh) instead of being allocated each time.
+/// On other platforms `refresh` is equivalent to calling
+/// [`get_interfaces_with_options`](super::get_interfaces_with_options).
+#[derive(Clone, Debug, Default)]
+pub struct Enumerator {
+    options: EnumOptions,
+    interfaces: Vec<Interface>,
+    #[cfg(target_os = "windows")]
+    buf: Vec<u8>,
+}
+
+impl Enumerator {
+    /// Create an enumerator with the default options.
+    /// No interfaces are listed until the first call to [`refresh`](Enumerator::refresh).
+    pub fn new() -> Enumerator {
+        Enumerator::default()
+    }
+    /// Create an enumerator with the given options
+    pub fn with_options(options: EnumOptions) -> Enumerator {
+        Enumerator {
+            options,
+            ..Enumerator::default()
+        }
+    }
+    /// Returns the options used for enumeration
+    pub fn options(&self) -> &EnumOptions {
+        &self.options
+    }
+    /// Enumerate the interfaces again, replacing the previous result
+    pub fn refresh(&mut self) -> &[Interface] {
+        #[cfg(feature = "mock")]
+        if let Some(provider) = crate::mock::current() {
+            self.interfaces = provider.interfaces(&self.options);
+            return &self.interfaces;
+        }
+        if let Some(backend) = crate::backend::installed() {
+            self.interfaces = backend.interfaces(&self.options);
+            return &self.interfaces;
+        }
+        #[cfg(target_os = "windows")]
+        {
+            self.interfaces = super::interfaces_into(&self.options, &mut self.buf);
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            self.interfaces = super::interfaces(&self.options);
+        }
+        &self.interfaces
+    }
+    /// Returns the interfaces found by the last [`refresh`](Enumerator::refresh)
+    pub fn interfaces(&self) -> &[Interface] {
+        &self.interfaces
+    }
+    /// Consume the enumerator, returning the interfaces found by the last refresh
+    pub fn into_interfaces(self) -> Vec<Interface> {
+        self.interfaces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn test_refresh() {
+        use crate::mock::{self, InterfaceBuilder, MockProvider};
+
+        let provider = MockProvider::new()
+            .interface(InterfaceBuilder::new(1, "lo").loopback().build())
+            .interface(InterfaceBuilder::new(2, "eth0").up().build());
+        let _guard = mock::set_provider(provider);
+        let mut enumerator = Enumerator::new();
+        assert!(enumerator.interfaces().is_empty());
+        let names = |interfaces: &[Interface]| -> Vec<String> {
+            interfaces.iter().map(|i| i.name.clone()).collect()
+        };
+        let first = names(enumerator.refresh());
+        assert_eq!(first, ["lo", "eth0"]);
+        assert_eq!(names(enumerator.refresh()), first);
+        assert_eq!(names(&enumerator.into_interfaces()), first);
+    }
+
+    #[test]
+    fn test_refresh_live() {
+        // Interfaces may come and go between calls, so only print them
+        let mut enumerator = Enumerator::new();
+        enumerator.refresh();
+        let names: Vec<&str> = enumerator
+            .refresh()
+            .iter()
+            .map(|i| i.name.as_str())
+            .collect();
+        println!("{:?}", names);
+    }
+}
@@ -0,0 +1,29 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// One-glance verdict on whether an interface can reach beyond the local link,
+/// as returned by [`Interface::health`](super::Interface::health).
+///
+/// Checks run in declaration order, so an interface that is down and has no
+/// address is reported as `Down`.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum InterfaceHealth {
+    /// Administratively down, or without carrier
+    Down,
+    /// No address other than IPv6 link-local
+    NoAddress,
+    /// Only self-assigned link-local addresses, typically because DHCP failed
+    LinkLocalOnly,
+    /// Addressed, but without a default gateway
+    NoGateway,
+    /// Up, addressed, and with a default gateway
+    Ok,
+}
+
+impl InterfaceHealth {
+    /// Returns true for [`InterfaceHealth::Ok`]
+    pub fn is_ok(&self) -> bool {
+        *self == InterfaceHealth::Ok
+    }
+}
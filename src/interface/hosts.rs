@@ -0,0 +1,135 @@
+use crate::ip::{Ipv4Hosts, Ipv4Net};
+use std::iter::FusedIterator;
+use std::net::Ipv4Addr;
+
+/// Iterator over the other hosts of the IPv4 subnets attached to an interface,
+/// returned by [`Interface::hosts_v4`](crate::Interface::hosts_v4)
+#[derive(Clone, Debug)]
+pub struct InterfaceHostsV4 {
+    /// Subnets still to visit, in reverse order
+    nets: Vec<Ipv4Net>,
+    current: Option<Ipv4Hosts>,
+    /// Addresses of the interface itself
+    own: Vec<Ipv4Addr>,
+}
+
+impl InterfaceHostsV4 {
+    pub(crate) fn new(ipv4: &[Ipv4Net]) -> InterfaceHostsV4 {
+        let mut nets: Vec<Ipv4Net> = ipv4.iter().map(Ipv4Net::trunc).collect();
+        // Shortest prefixes first, so that subnets covered by another one are dropped
+        nets.sort_by_key(|net| (net.prefix_len, net.addr));
+        let mut unique: Vec<Ipv4Net> = Vec::with_capacity(nets.len());
+        for net in nets {
+            if !unique.iter().any(|other| other.contains_net(&net)) {
+                unique.push(net);
+            }
+        }
+        unique.sort_by_key(|net| std::cmp::Reverse(net.addr));
+        InterfaceHostsV4 {
+            nets: unique,
+            current: None,
+            own: ipv4.iter().map(|net| net.addr).collect(),
+        }
+    }
+    /// Group the addresses into batches of up to `size`, such as one batch of
+    /// probes per round of a scan.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn chunks(self, size: usize) -> HostChunksV4 {
+        assert!(size != 0, "chunk size must be non-zero");
+        HostChunksV4 { hosts: self, size }
+    }
+}
+
+impl Iterator for InterfaceHostsV4 {
+    type Item = Ipv4Addr;
+    fn next(&mut self) -> Option<Ipv4Addr> {
+        loop {
+            if let Some(addr) = self.current.as_mut().and_then(Iterator::next) {
+                if !self.own.contains(&addr) {
+                    return Some(addr);
+                }
+                continue;
+            }
+            self.current = Some(self.nets.pop()?.hosts());
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let current = self.current.as_ref().map_or(0, |hosts| hosts.size_hint().0);
+        let upper = self.nets.iter().try_fold(current, |sum, net| {
+            sum.checked_add(net.hosts().size_hint().1?)
+        });
+        (current.saturating_sub(self.own.len()), upper)
+    }
+}
+
+impl FusedIterator for InterfaceHostsV4 {}
+
+/// Batches of host addresses, returned by [`InterfaceHostsV4::chunks`]
+#[derive(Clone, Debug)]
+pub struct HostChunksV4 {
+    hosts: InterfaceHostsV4,
+    size: usize,
+}
+
+impl Iterator for HostChunksV4 {
+    type Item = Vec<Ipv4Addr>;
+    fn next(&mut self) -> Option<Vec<Ipv4Addr>> {
+        let chunk: Vec<Ipv4Addr> = self.hosts.by_ref().take(self.size).collect();
+        if chunk.is_empty() {
+            None
+        } else {
+            Some(chunk)
+        }
+    }
+}
+
+impl FusedIterator for HostChunksV4 {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hosts(nets: &[&str]) -> Vec<Ipv4Addr> {
+        let nets: Vec<Ipv4Net> = nets.iter().map(|s| s.parse().unwrap()).collect();
+        InterfaceHostsV4::new(&nets).collect()
+    }
+
+    #[test]
+    fn test_hosts_v4() {
+        let addr = |last: u8| Ipv4Addr::new(192, 168, 1, last);
+        assert_eq!(
+            hosts(&["192.168.1.1/29"]),
+            vec![addr(2), addr(3), addr(4), addr(5), addr(6)]
+        );
+        // The peer of a point-to-point /31 link
+        assert_eq!(hosts(&["192.168.1.4/31"]), vec![addr(5)]);
+        assert_eq!(hosts(&["192.168.1.5/31"]), vec![addr(4)]);
+        assert!(hosts(&["192.168.1.1/32"]).is_empty());
+        // Overlapping subnets are visited once, without any of the own addresses
+        assert_eq!(
+            hosts(&["192.168.1.3/30", "192.168.1.1/29", "10.0.0.1/30"]),
+            vec![
+                Ipv4Addr::new(10, 0, 0, 2),
+                addr(2),
+                addr(4),
+                addr(5),
+                addr(6)
+            ]
+        );
+        assert_eq!(hosts(&["10.0.0.1/16"]).len(), 65533);
+
+        let nets: Vec<Ipv4Net> = vec!["192.168.1.1/29".parse().unwrap()];
+        let chunks: Vec<Vec<Ipv4Addr>> = InterfaceHostsV4::new(&nets).chunks(2).collect();
+        assert_eq!(
+            chunks,
+            vec![
+                vec![addr(2), addr(3)],
+                vec![addr(4), addr(5)],
+                vec![addr(6)]
+            ]
+        );
+    }
+}
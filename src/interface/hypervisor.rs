@@ -0,0 +1,288 @@
+use super::Interface;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Virtualization software behind a network adapter, as returned by
+/// [`Interface::hypervisor_kind`](super::Interface::hypervisor_kind)
+///
+/// Covers both the adapters a hypervisor adds to the host, such as VirtualBox
+/// host-only networks, and the emulated adapters of its guests.
+///
+/// New kinds may be added in minor releases, so matches need a wildcard arm.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum HypervisorKind {
+    /// Microsoft Hyper-V, including the Default Switch
+    HyperV,
+    /// The Hyper-V switch of the Windows Subsystem for Linux, or the adapter
+    /// of a WSL 2 distribution
+    Wsl,
+    /// VMware Workstation, Fusion or ESXi
+    Vmware,
+    /// Oracle VirtualBox
+    VirtualBox,
+    /// Parallels Desktop
+    Parallels,
+    /// QEMU with or without KVM, including most cloud instances
+    Kvm,
+    /// Xen
+    Xen,
+}
+
+/// Texts an adapter may be recognized by, in lower case
+struct Names {
+    name: String,
+    friendly_name: String,
+    driver: String,
+    description: String,
+}
+
+impl Names {
+    fn of(interface: &Interface) -> Names {
+        let lower = |s: Option<&str>| s.unwrap_or_default().to_ascii_lowercase();
+        Names {
+            name: interface.name.to_ascii_lowercase(),
+            friendly_name: lower(interface.friendly_name.as_deref()),
+            driver: lower(interface.driver.as_ref().map(|driver| driver.name.as_str())),
+            description: lower(interface.description.as_deref()),
+        }
+    }
+    /// Check if the name, friendly name or description includes `s`
+    fn mention(&self, s: &str) -> bool {
+        self.name.contains(s) || self.friendly_name.contains(s) || self.description.contains(s)
+    }
+    fn name_starts_with(&self, prefix: &str) -> bool {
+        self.name.starts_with(prefix) || self.friendly_name.starts_with(prefix)
+    }
+}
+
+/// Organizationally unique identifiers the hypervisors assign MAC addresses from
+const HYPERVISOR_OUIS: &[([u8; 3], HypervisorKind)] = &[
+    ([0x00, 0x03, 0xff], HypervisorKind::HyperV),
+    ([0x00, 0x15, 0x5d], HypervisorKind::HyperV),
+    ([0x00, 0x05, 0x69], HypervisorKind::Vmware),
+    ([0x00, 0x0c, 0x29], HypervisorKind::Vmware),
+    ([0x00, 0x1c, 0x14], HypervisorKind::Vmware),
+    ([0x00, 0x50, 0x56], HypervisorKind::Vmware),
+    ([0x08, 0x00, 0x27], HypervisorKind::VirtualBox),
+    ([0x0a, 0x00, 0x27], HypervisorKind::VirtualBox),
+    ([0x00, 0x1c, 0x42], HypervisorKind::Parallels),
+    ([0x52, 0x54, 0x00], HypervisorKind::Kvm),
+    ([0x00, 0x16, 0x3e], HypervisorKind::Xen),
+];
+
+/// Recognize a hypervisor adapter from its names, driver and description,
+/// then from the vendor of its MAC address. The paravirtualized adapters of
+/// KVM and Xen guests are only recognized by the latter, as `virtio_net` and
+/// `xen-netfront` also serve other hypervisors. Guest adapters found this way
+/// are still physical, see [`is_host_adapter`].
+pub(crate) fn classify(interface: &Interface) -> Option<HypervisorKind> {
+    let names = Names::of(interface);
+    let kind = if names.mention("(wsl") {
+        HypervisorKind::Wsl
+    } else if names.mention("hyper-v")
+        || names.name_starts_with("vethernet")
+        || names.driver == "hv_netvsc"
+        || names.driver == "vmsmp"
+    {
+        HypervisorKind::HyperV
+    } else if names.mention("vmware")
+        || names.name_starts_with("vmnet")
+        || names.name_starts_with("vmenet")
+        || names.driver == "vmxnet3"
+    {
+        HypervisorKind::Vmware
+    } else if names.mention("virtualbox")
+        || names.name_starts_with("vboxnet")
+        || names.driver.starts_with("vbox")
+    {
+        HypervisorKind::VirtualBox
+    } else if names.mention("parallels") || names.driver.starts_with("prl_") {
+        HypervisorKind::Parallels
+    } else {
+        let oui = interface.mac_addr?.oui();
+        return HYPERVISOR_OUIS
+            .iter()
+            .find(|(prefix, _)| *prefix == oui)
+            .map(|(_, kind)| *kind);
+    };
+    Some(kind)
+}
+
+/// Recognize the adapters a hypervisor adds to its host for the networks of
+/// its guests: the vEthernet switches of Hyper-V and WSL, the vmnet adapters
+/// of VMware and the host-only networks of VirtualBox and Parallels.
+///
+/// The emulated adapters of a guest, such as `hv_netvsc`, `vmxnet3` or a NIC
+/// with a hypervisor's MAC address, are not, as they are the guest's uplink.
+pub(crate) fn is_host_adapter(interface: &Interface) -> bool {
+    let names = Names::of(interface);
+    names.mention("(wsl")
+        || names.name_starts_with("vethernet")
+        || names.mention("hyper-v virtual ethernet adapter")
+        || names.name_starts_with("vmnet")
+        || names.name_starts_with("vmenet")
+        || names.mention("vmware virtual ethernet adapter")
+        || names.name_starts_with("vboxnet")
+        || names.mention("host-only")
+        || names.mention("parallels shared")
+}
+
+/// Recognize Thunderbolt and USB4 host-to-host networking: the Thunderbolt
+/// Bridge of macOS, `thunderbolt-net` on Linux and Thunderbolt Networking on
+/// Windows
+pub(crate) fn is_thunderbolt(interface: &Interface) -> bool {
+    let names = Names::of(interface);
+    names.mention("thunderbolt") || names.driver.contains("thunderbolt")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interface::DriverInfo;
+    use crate::mac::MacAddr;
+
+    fn driver(name: &str) -> DriverInfo {
+        DriverInfo {
+            name: name.to_string(),
+            version: None,
+            firmware_version: None,
+            bus_info: None,
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        let cases = [
+            (
+                Interface::builder()
+                    .name("{4D36E972-E325-11CE-BFC1-08002BE10318}")
+                    .friendly_name("vEthernet (WSL (Hyper-V firewall))")
+                    .description("Hyper-V Virtual Ethernet Adapter #2")
+                    .build(),
+                Some(HypervisorKind::Wsl),
+            ),
+            (
+                Interface::builder()
+                    .friendly_name("vEthernet (Default Switch)")
+                    .build(),
+                Some(HypervisorKind::HyperV),
+            ),
+            (
+                Interface::builder()
+                    .name("eth0")
+                    .mac_addr(MacAddr::new(0x00, 0x15, 0x5d, 0x12, 0x34, 0x56))
+                    .build(),
+                Some(HypervisorKind::HyperV),
+            ),
+            (
+                Interface::builder().name("vmnet8").build(),
+                Some(HypervisorKind::Vmware),
+            ),
+            (
+                Interface::builder()
+                    .name("ens160")
+                    .driver(driver("vmxnet3"))
+                    .build(),
+                Some(HypervisorKind::Vmware),
+            ),
+            (
+                Interface::builder()
+                    .friendly_name("Ethernet 2")
+                    .description("VirtualBox Host-Only Ethernet Adapter")
+                    .build(),
+                Some(HypervisorKind::VirtualBox),
+            ),
+            (
+                Interface::builder().name("vboxnet0").build(),
+                Some(HypervisorKind::VirtualBox),
+            ),
+            (
+                Interface::builder()
+                    .name("en0")
+                    .mac_addr(MacAddr::new(0x00, 0x1c, 0x42, 0, 0, 1))
+                    .build(),
+                Some(HypervisorKind::Parallels),
+            ),
+            (
+                Interface::builder()
+                    .name("enp0s31f6")
+                    .driver(driver("e1000e"))
+                    .mac_addr(MacAddr::new(0x3c, 0x52, 0x82, 0, 0, 1))
+                    .build(),
+                None,
+            ),
+        ];
+        for (interface, kind) in cases {
+            assert_eq!(classify(&interface), kind, "{:?}", interface);
+        }
+    }
+
+    #[test]
+    fn test_is_host_adapter() {
+        let host = [
+            Interface::builder()
+                .friendly_name("vEthernet (Default Switch)")
+                .description("Hyper-V Virtual Ethernet Adapter")
+                .build(),
+            Interface::builder().name("vmnet8").build(),
+            Interface::builder()
+                .friendly_name("VMware Network Adapter VMnet1")
+                .description("VMware Virtual Ethernet Adapter for VMnet1")
+                .mac_addr(MacAddr::new(0x00, 0x50, 0x56, 0xc0, 0, 1))
+                .build(),
+            Interface::builder().name("vboxnet0").build(),
+            Interface::builder()
+                .friendly_name("Ethernet 2")
+                .description("VirtualBox Host-Only Ethernet Adapter")
+                .build(),
+        ];
+        for interface in &host {
+            assert!(is_host_adapter(interface), "{:?}", interface);
+        }
+        // Adapters of guests, which are their uplink
+        let guest = [
+            Interface::builder()
+                .name("eth0")
+                .driver(driver("hv_netvsc"))
+                .mac_addr(MacAddr::new(0x00, 0x15, 0x5d, 0x12, 0x34, 0x56))
+                .build(),
+            Interface::builder()
+                .friendly_name("Ethernet0")
+                .description("vmxnet3 Ethernet Adapter")
+                .mac_addr(MacAddr::new(0x00, 0x0c, 0x29, 0, 0, 1))
+                .build(),
+            Interface::builder()
+                .name("ens192")
+                .driver(driver("vmxnet3"))
+                .mac_addr(MacAddr::new(0x00, 0x50, 0x56, 0x80, 0, 1))
+                .build(),
+            Interface::builder()
+                .name("enp1s0")
+                .driver(driver("virtio_net"))
+                .mac_addr(MacAddr::new(0x52, 0x54, 0x00, 0x12, 0x34, 0x56))
+                .build(),
+        ];
+        for interface in &guest {
+            assert!(interface.hypervisor_kind().is_some(), "{:?}", interface);
+            assert!(!is_host_adapter(interface), "{:?}", interface);
+        }
+    }
+
+    #[test]
+    fn test_is_thunderbolt() {
+        let bridge = Interface::builder()
+            .name("bridge0")
+            .friendly_name("Thunderbolt Bridge")
+            .build();
+        assert!(is_thunderbolt(&bridge));
+        let linux = Interface::builder()
+            .name("thunderbolt0")
+            .driver(driver("thunderbolt-net"))
+            .build();
+        assert!(is_thunderbolt(&linux));
+        assert!(!is_thunderbolt(&Interface::builder().name("en0").build()));
+    }
+}
@@ -0,0 +1,65 @@
+//! Interface details on iOS, where apps run sandboxed.
+//!
+//! SystemConfiguration's interface and dynamic store APIs and IOKit are not
+//! available to apps, and the OS hands out the same placeholder in place of
+//! every MAC address. The BSD APIs still list names, addresses, counters and
+//! routes, so the type of an interface is inferred from the naming scheme.
+
+use crate::interface::{Interface, InterfaceType};
+use crate::mac::MacAddr;
+
+/// Address iOS reports instead of the real MAC address of every interface
+const PLACEHOLDER_MAC: MacAddr = MacAddr(0x02, 0, 0, 0, 0, 0);
+
+/// Infer the type of an interface from its name, falling back to the type
+/// `getifaddrs` reported, which is `Ethernet` for Wi-Fi
+pub fn get_interface_type(if_name: &str, reported: InterfaceType) -> InterfaceType {
+    let kind = if_name.trim_end_matches(|c: char| c.is_ascii_digit());
+    match kind {
+        // en0 is always Wi-Fi, other en interfaces are wired adapters
+        "en" if if_name == "en0" => InterfaceType::Wireless80211,
+        // Cellular data contexts
+        "pdp_ip" => InterfaceType::Wwanpp,
+        // Apple Wireless Direct Link and low-latency Wi-Fi, for AirDrop and the like
+        "awdl" | "llw" | "ap" => InterfaceType::Wireless80211,
+        "utun" | "ipsec" => InterfaceType::Tunnel,
+        "bridge" => InterfaceType::Bridge,
+        "lo" => InterfaceType::Loopback,
+        _ => reported,
+    }
+}
+
+/// Drop the placeholder hardware address of an interface
+pub fn clear_placeholder_mac(interface: &mut Interface) {
+    if interface.mac_addr == Some(PLACEHOLDER_MAC) {
+        interface.mac_addr = None;
+        interface.hardware_addr = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_interface_type() {
+        let ether = InterfaceType::Ethernet;
+        assert_eq!(
+            get_interface_type("en0", ether),
+            InterfaceType::Wireless80211
+        );
+        assert_eq!(get_interface_type("en2", ether), ether);
+        assert_eq!(
+            get_interface_type("pdp_ip0", InterfaceType::Unknown),
+            InterfaceType::Wwanpp
+        );
+        assert_eq!(
+            get_interface_type("utun3", InterfaceType::Unknown),
+            InterfaceType::Tunnel
+        );
+        assert_eq!(
+            get_interface_type("lo0", InterfaceType::Unknown),
+            InterfaceType::Loopback
+        );
+    }
+}
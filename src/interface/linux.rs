@@ -1,6 +1,35 @@
-use crate::interface::InterfaceType;
+use crate::interface::{
+    AddressInfo, AddressOrigin, DadState, DriverInfo, InterfaceType, OffloadInfo, OnLinkPrefix,
+    Qdisc, QdiscStats, SriovInfo, SriovRole, TimestampingCaps, TunnelInfo, TunnelKind, VfLinkState,
+    VirtualFunction,
+};
+use crate::ip::IpNet;
+use crate::mac::MacAddr;
+use crate::sys::{ethtool, netlink};
+use crate::wol::{WolMode, WolModes};
+use netlink_packet_route::{
+    rtnl::address::nlas::Nla as AddressNla, rtnl::link::nlas::Nla as LinkNla,
+    rtnl::route::nlas::Nla as RouteNla, AddressMessage, LinkMessage, RouteMessage, RtnlMessage,
+    AF_INET, AF_INET6, IFA_F_DADFAILED, IFA_F_DEPRECATED, IFA_F_NODAD, IFA_F_OPTIMISTIC,
+    IFA_F_PERMANENT, IFA_F_SECONDARY, IFA_F_TEMPORARY, IFA_F_TENTATIVE, RTN_UNICAST, RTPROT_BOOT,
+    RTPROT_DHCP, RTPROT_KERNEL, RTPROT_RA, RTPROT_STATIC, RT_TABLE_MAIN,
+};
+use std::collections::BTreeMap;
 use std::convert::TryFrom;
+use std::ffi::c_void;
 use std::fs::{read_link, read_to_string};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "config")]
+use netlink_packet_core::{NLM_F_CREATE, NLM_F_EXCL, NLM_F_REPLACE};
+#[cfg(feature = "config")]
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+#[cfg(feature = "config")]
+use std::time::Instant;
 
 fn is_wifi_interface(interface_name: &str) -> bool {
     let wireless_path = format!("/sys/class/net/{}/wireless", interface_name);
@@ -31,23 +60,19 @@ pub fn get_interface_type(if_name: String) -> InterfaceType {
                         // Since some Wi-Fi interfaces may also be reported as Ethernet,
                         // further check if the interface is actually Wi-Fi.
                         if is_wifi_interface(&if_name) {
-                            return InterfaceType::Wireless80211;
+                            InterfaceType::Wireless80211
                         } else {
-                            return InterfaceType::Ethernet;
+                            InterfaceType::Ethernet
                         }
                     } else {
-                        return InterfaceType::try_from(if_type).unwrap_or(InterfaceType::Unknown);
+                        InterfaceType::try_from(if_type).unwrap_or(InterfaceType::Unknown)
                     }
                 }
-                Err(_) => {
-                    return InterfaceType::Unknown;
-                }
+                Err(_) => InterfaceType::Unknown,
             }
         }
-        Err(_) => {
-            return InterfaceType::Unknown;
-        }
-    };
+        Err(_) => InterfaceType::Unknown,
+    }
 }
 
 pub fn get_interface_speed(if_name: String) -> Option<u64> {
@@ -57,17 +82,1491 @@ pub fn get_interface_speed(if_name: String) -> Option<u64> {
         Ok(content) => {
             let if_speed_string = content.trim().to_string();
             match if_speed_string.parse::<u64>() {
-                Ok(if_speed) => {
-                    // Convert Mbps to bps
-                    return Some(if_speed * 1000000);
+                // Convert Mbps to bps
+                Ok(if_speed) => Some(if_speed * 1000000),
+                Err(_) => None,
+            }
+        }
+        Err(_) => None,
+    }
+}
+
+/// Path of `path`'s target relative to `/sys/devices`
+fn sys_device_path(path: &str) -> Option<String> {
+    let target = std::fs::canonicalize(path).ok()?;
+    let relative = target.strip_prefix("/sys/devices").ok()?;
+    Some(relative.to_string_lossy().into_owned())
+}
+
+pub fn get_device_path(if_name: &str) -> Option<String> {
+    get_device_path_at_depth(if_name, 0)
+}
+
+fn get_device_path_at_depth(if_name: &str, depth: usize) -> Option<String> {
+    if let Some(path) = sys_device_path(&format!("/sys/class/net/{}/device", if_name)) {
+        return Some(path);
+    }
+    // Stacked interfaces such as VLANs have no device of their own. Follow them
+    // down only if there is a single lower interface, unlike bonds.
+    let mut lowers = std::fs::read_dir(format!("/sys/class/net/{}", if_name))
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            name.strip_prefix("lower_").map(str::to_string)
+        });
+    let lower = lowers.next()?;
+    if lowers.next().is_some() || depth >= 8 {
+        return None;
+    }
+    get_device_path_at_depth(&lower, depth + 1)
+}
+
+/// ID databases of the pciutils and usbutils packages, in the locations used by
+/// the common distributions
+const PCI_IDS: [&str; 3] = [
+    "/usr/share/hwdata/pci.ids",
+    "/usr/share/misc/pci.ids",
+    "/usr/share/pci.ids",
+];
+const USB_IDS: [&str; 3] = [
+    "/usr/share/hwdata/usb.ids",
+    "/usr/share/misc/usb.ids",
+    "/usr/share/usb.ids",
+];
+
+/// Device identity decoded from a `MODALIAS` value
+#[derive(Debug, PartialEq)]
+enum Modalias {
+    Pci { vendor: u16, device: u16 },
+    Usb { vendor: u16, product: u16 },
+    Virtio { device: u32 },
+}
+
+/// Read the hex number of `len` digits following `tag` at the start of `s`
+fn modalias_field(s: &str, tag: char, len: usize) -> Option<u32> {
+    u32::from_str_radix(s.strip_prefix(tag)?.get(..len)?, 16).ok()
+}
+
+/// Decode `pci:v00008086d000015B8sv...`, `usb:v0BDAp8153d3000...` and
+/// `virtio:d00000001v00001AF4`
+fn parse_modalias(modalias: &str) -> Option<Modalias> {
+    let (bus, rest) = modalias.split_once(':')?;
+    match bus {
+        "pci" => Some(Modalias::Pci {
+            vendor: modalias_field(rest, 'v', 8)? as u16,
+            device: modalias_field(rest.get(9..)?, 'd', 8)? as u16,
+        }),
+        "usb" => Some(Modalias::Usb {
+            vendor: modalias_field(rest, 'v', 4)? as u16,
+            product: modalias_field(rest.get(5..)?, 'p', 4)? as u16,
+        }),
+        "virtio" => Some(Modalias::Virtio {
+            device: modalias_field(rest, 'd', 8)?,
+        }),
+        _ => None,
+    }
+}
+
+/// Vendor names and the names of their devices, by ID
+type IdDb = BTreeMap<u16, (String, BTreeMap<u16, String>)>;
+
+/// Split an `xxxx  Name` line of an ID database
+fn parse_id_line(line: &str) -> Option<(u16, &str)> {
+    let name = line.get(4..)?.strip_prefix("  ")?;
+    Some((u16::from_str_radix(&line[..4], 16).ok()?, name))
+}
+
+/// Parse the vendors and devices of a `pci.ids` or `usb.ids` database. Subsystems
+/// and the sections after the vendor list, such as device classes, are skipped.
+fn parse_ids(db: &str) -> IdDb {
+    let mut ids = IdDb::new();
+    let mut vendor = None;
+    for line in db.lines() {
+        if line.starts_with('#') || line.starts_with("\t\t") {
+            continue;
+        }
+        if let Some(line) = line.strip_prefix('\t') {
+            if let (Some(vendor), Some((id, name))) = (vendor, parse_id_line(line)) {
+                if let Some((_, devices)) = ids.get_mut(&vendor) {
+                    devices.entry(id).or_insert_with(|| name.to_string());
                 }
-                Err(_) => {
-                    return None;
+            }
+        } else {
+            let entry = parse_id_line(line);
+            if let Some((id, name)) = entry {
+                ids.entry(id)
+                    .or_insert_with(|| (name.to_string(), BTreeMap::new()));
+            }
+            vendor = entry.map(|(id, _)| id);
+        }
+    }
+    ids
+}
+
+/// Parse the first of `paths` that can be read, once per process, as the
+/// databases are a few MB and descriptions are looked up for every interface
+fn load_ids(cache: &'static OnceLock<Option<IdDb>>, paths: &[&str]) -> Option<&'static IdDb> {
+    cache
+        .get_or_init(|| {
+            let db = paths.iter().find_map(|path| read_to_string(path).ok())?;
+            Some(parse_ids(&db))
+        })
+        .as_ref()
+}
+
+/// Look up the vendor and device names in a parsed ID database
+fn lookup_ids(db: &IdDb, vendor: u16, device: u16) -> Option<(&str, &str)> {
+    let (vendor_name, devices) = db.get(&vendor)?;
+    Some((vendor_name, devices.get(&device)?))
+}
+
+/// Prefix `model` with `vendor` unless it already names it
+fn with_vendor(vendor: &str, model: &str) -> String {
+    if vendor.is_empty() || model.starts_with(vendor) {
+        model.to_string()
+    } else {
+        format!("{} {}", vendor, model)
+    }
+}
+
+/// Build a description from the names the udev hardware database attached to the
+/// interface
+fn parse_udev_description(data: &str) -> Option<String> {
+    let value = |key: &str| {
+        data.lines().find_map(|line| {
+            line.strip_prefix("E:")?
+                .strip_prefix(key)?
+                .strip_prefix('=')
+        })
+    };
+    let model = value("ID_MODEL_FROM_DATABASE")?;
+    Some(with_vendor(
+        value("ID_VENDOR_FROM_DATABASE").unwrap_or(""),
+        model,
+    ))
+}
+
+/// Vendor and model of the device behind the interface, from the udev database,
+/// the USB string descriptors or the PCI and USB ID databases
+pub fn get_description(if_name: &str) -> Option<String> {
+    let index = read_to_string(format!("/sys/class/net/{}/ifindex", if_name)).ok()?;
+    let udev = read_to_string(format!("/run/udev/data/n{}", index.trim()));
+    if let Some(description) = udev.ok().as_deref().and_then(parse_udev_description) {
+        return Some(description);
+    }
+    let device = format!("/sys/devices/{}", get_device_path(if_name)?);
+    let uevent = read_to_string(format!("{}/uevent", device)).ok()?;
+    let modalias = uevent
+        .lines()
+        .find_map(|line| line.strip_prefix("MODALIAS="))?;
+    static PCI_DB: OnceLock<Option<IdDb>> = OnceLock::new();
+    static USB_DB: OnceLock<Option<IdDb>> = OnceLock::new();
+    match parse_modalias(modalias)? {
+        Modalias::Pci { vendor, device } => {
+            let (vendor, model) = lookup_ids(load_ids(&PCI_DB, &PCI_IDS)?, vendor, device)?;
+            Some(with_vendor(vendor, model))
+        }
+        Modalias::Usb { vendor, product } => {
+            // The USB device above the interface carries its own strings
+            let read = |name: &str| read_to_string(format!("{}/../{}", device, name));
+            if let Ok(model) = read("product") {
+                let vendor = read("manufacturer").unwrap_or_default();
+                return Some(with_vendor(vendor.trim(), model.trim()));
+            }
+            let (vendor, model) = lookup_ids(load_ids(&USB_DB, &USB_IDS)?, vendor, product)?;
+            Some(with_vendor(vendor, model))
+        }
+        Modalias::Virtio { device: 1 } => Some("Virtio network device".to_string()),
+        Modalias::Virtio { .. } => None,
+    }
+}
+
+/// Device path of the SR-IOV physical function `device_path` is a virtual function of
+pub fn get_physfn_path(device_path: &str) -> Option<String> {
+    sys_device_path(&format!("/sys/devices/{}/physfn", device_path))
+}
+
+/// Inode number of the network namespace of the calling thread. `/proc/self`
+/// would name the main thread's, which differs inside `netns::enter`.
+pub fn current_netns() -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata("/proc/thread-self/ns/net")
+        .ok()
+        .map(|metadata| metadata.ino())
+}
+
+pub fn get_carrier_changes(if_name: &str) -> Option<u32> {
+    let path = format!("/sys/class/net/{}/carrier_changes", if_name);
+    read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Carrier-up count of each link seen by this process, keyed by index, and when
+/// the count was seen to change
+static LINK_UP: Mutex<BTreeMap<u32, (u32, Option<SystemTime>)>> = Mutex::new(BTreeMap::new());
+
+/// When the link of the interface came up.
+///
+/// The kernel only counts carrier transitions, so the time is when this process
+/// first saw the count change, to the precision of the interval between
+/// enumerations. `None` while the carrier is down, and until the link has come
+/// up after its first enumeration in this process.
+pub fn get_link_up_since(if_index: u32, if_name: &str) -> Option<SystemTime> {
+    let read = |file: &str| -> Option<u32> {
+        let path = format!("/sys/class/net/{}/{}", if_name, file);
+        read_to_string(path).ok()?.trim().parse().ok()
+    };
+    // Reading the carrier of an interface that is administratively down fails
+    let up = read("carrier") == Some(1);
+    let up_count = read("carrier_up_count")?;
+    let mut links = LINK_UP.lock().unwrap_or_else(|err| err.into_inner());
+    observe_link(&mut links, if_index, up, up_count, SystemTime::now())
+}
+
+fn observe_link(
+    links: &mut BTreeMap<u32, (u32, Option<SystemTime>)>,
+    if_index: u32,
+    up: bool,
+    up_count: u32,
+    now: SystemTime,
+) -> Option<SystemTime> {
+    let since = match links.get(&if_index) {
+        Some((count, since)) if *count == up_count => *since,
+        // Came up since the previous enumeration
+        Some(_) => Some(now),
+        // Up before this process looked, at an unknown time
+        None => None,
+    };
+    links.insert(if_index, (up_count, since));
+    since.filter(|_| up)
+}
+
+pub fn get_offload_info(if_name: &str) -> io::Result<OffloadInfo> {
+    let names = ethtool::get_strings(if_name, ethtool::ETH_SS_FEATURES)?;
+    let blocks = names.len().div_ceil(32);
+    // struct ethtool_gfeatures: cmd, size, then size * {available, requested, active, never_changed}
+    let mut buf: Vec<u32> = vec![0; 2 + blocks * 4];
+    buf[0] = ethtool::ETHTOOL_GFEATURES;
+    buf[1] = blocks as u32;
+    // SAFETY: the buffer holds the header and `blocks` feature blocks
+    unsafe { ethtool::ethtool_ioctl(if_name, buf.as_mut_ptr() as *mut c_void)? };
+    let active = |feature: &str| -> Option<bool> {
+        let bit = names.iter().position(|name| name == feature)?;
+        Some(buf[2 + (bit / 32) * 4 + 2] & (1 << (bit % 32)) != 0)
+    };
+    let any_active = |features: &[&str]| -> Option<bool> {
+        features
+            .iter()
+            .filter_map(|feature| active(feature))
+            .reduce(|a, b| a || b)
+    };
+    Ok(OffloadInfo {
+        rx_checksum: active("rx-checksum"),
+        tx_checksum: any_active(&[
+            "tx-checksum-ipv4",
+            "tx-checksum-ip-generic",
+            "tx-checksum-ipv6",
+        ]),
+        tso: any_active(&["tx-tcp-segmentation", "tx-tcp6-segmentation"]),
+        gso: active("tx-generic-segmentation"),
+        gro: active("rx-gro"),
+        lro: active("rx-lro"),
+    })
+}
+
+#[repr(C)]
+struct EthtoolWolInfo {
+    cmd: u32,
+    supported: u32,
+    wolopts: u32,
+    sopass: [u8; 6],
+}
+
+fn wol_modes_from_bits(bits: u32) -> Vec<WolMode> {
+    [
+        (ethtool::WAKE_PHY, WolMode::Phy),
+        (ethtool::WAKE_UCAST, WolMode::Unicast),
+        (ethtool::WAKE_MCAST, WolMode::Multicast),
+        (ethtool::WAKE_BCAST, WolMode::Broadcast),
+        (ethtool::WAKE_ARP, WolMode::Arp),
+        (ethtool::WAKE_MAGIC, WolMode::MagicPacket),
+        (ethtool::WAKE_MAGICSECURE, WolMode::SecureOn),
+        (ethtool::WAKE_FILTER, WolMode::Filter),
+    ]
+    .into_iter()
+    .filter(|(bit, _)| bits & bit != 0)
+    .map(|(_, mode)| mode)
+    .collect()
+}
+
+pub fn get_wol_modes(if_name: &str) -> io::Result<WolModes> {
+    let mut info: EthtoolWolInfo = unsafe { std::mem::zeroed() };
+    info.cmd = ethtool::ETHTOOL_GWOL;
+    // SAFETY: info is a struct ethtool_wolinfo
+    unsafe { ethtool::ethtool_ioctl(if_name, &mut info as *mut EthtoolWolInfo as *mut c_void)? };
+    Ok(WolModes {
+        supported: wol_modes_from_bits(info.supported),
+        enabled: wol_modes_from_bits(info.wolopts),
+    })
+}
+
+#[repr(C)]
+struct EthtoolTsInfo {
+    cmd: u32,
+    so_timestamping: u32,
+    phc_index: i32,
+    tx_types: u32,
+    tx_reserved: [u32; 3],
+    rx_filters: u32,
+    rx_reserved: [u32; 3],
+}
+
+pub fn get_timestamping_caps(if_name: &str) -> io::Result<TimestampingCaps> {
+    let mut info: EthtoolTsInfo = unsafe { std::mem::zeroed() };
+    info.cmd = ethtool::ETHTOOL_GET_TS_INFO;
+    // SAFETY: info is a struct ethtool_ts_info
+    unsafe { ethtool::ethtool_ioctl(if_name, &mut info as *mut EthtoolTsInfo as *mut c_void)? };
+    Ok(TimestampingCaps {
+        flags: info.so_timestamping,
+        // -1 when there is no PTP hardware clock
+        phc_index: u32::try_from(info.phc_index).ok(),
+        tx_types: info.tx_types,
+        rx_filters: info.rx_filters,
+    })
+}
+
+/// Request VF information in link dumps
+const RTEXT_FILTER_VF: u32 = 1;
+
+const IFLA_VF_INFO: u16 = 1;
+const IFLA_VF_MAC: u16 = 1;
+const IFLA_VF_VLAN: u16 = 2;
+const IFLA_VF_SPOOFCHK: u16 = 4;
+const IFLA_VF_LINK_STATE: u16 = 5;
+const IFLA_VF_TRUST: u16 = 9;
+
+/// Decode the VF configuration in an `IFLA_VFINFO_LIST` attribute
+fn parse_vf_info_list(buf: &[u8]) -> Vec<VirtualFunction> {
+    let mut vfs = Vec::new();
+    for (kind, info) in netlink::iter_nlas(buf) {
+        if kind != IFLA_VF_INFO {
+            continue;
+        }
+        let mut vf = VirtualFunction::default();
+        // Each attribute starts with the VF index
+        for (kind, attr) in netlink::iter_nlas(info) {
+            let index = match netlink::read_u32(attr, 0) {
+                Some(index) => index,
+                None => continue,
+            };
+            vf.index = index;
+            match kind {
+                IFLA_VF_MAC => {
+                    if let Some(mac) = attr.get(4..10) {
+                        let mac = MacAddr::from_octets(mac.try_into().unwrap());
+                        if mac != MacAddr::zero() {
+                            vf.mac_addr = Some(mac);
+                        }
+                    }
+                }
+                IFLA_VF_VLAN => vf.vlan = netlink::read_u32(attr, 4).map(|vlan| vlan as u16),
+                IFLA_VF_SPOOFCHK => vf.spoof_check = netlink::read_u32(attr, 4).map(|on| on != 0),
+                IFLA_VF_TRUST => vf.trust = netlink::read_u32(attr, 4).map(|on| on != 0),
+                IFLA_VF_LINK_STATE => {
+                    vf.link_state = match netlink::read_u32(attr, 4) {
+                        Some(0) => Some(VfLinkState::Auto),
+                        Some(1) => Some(VfLinkState::Enable),
+                        Some(2) => Some(VfLinkState::Disable),
+                        _ => None,
+                    }
+                }
+                _ => {}
+            }
+        }
+        vfs.push(vf);
+    }
+    vfs
+}
+
+/// Get the VF configuration of the physical function with the given index from netlink
+fn get_vf_config(if_index: u32) -> io::Result<Vec<VirtualFunction>> {
+    let mut msg = LinkMessage::default();
+    msg.header.index = if_index;
+    msg.nlas.push(LinkNla::ExtMask(RTEXT_FILTER_VF));
+    let RtnlMessage::NewLink(link) = netlink::get(RtnlMessage::GetLink(msg))? else {
+        return Ok(Vec::new());
+    };
+    for nla in link.nlas {
+        if let LinkNla::VfInfoList(buf) = nla {
+            return Ok(parse_vf_info_list(&buf));
+        }
+    }
+    Ok(Vec::new())
+}
+
+/// Name of the first network interface of a PCI device directory in sysfs
+fn pci_net_name(device: &Path) -> Option<String> {
+    let mut entries = std::fs::read_dir(device.join("net")).ok()?;
+    let entry = entries.next()?.ok()?;
+    Some(entry.file_name().to_string_lossy().into_owned())
+}
+
+fn read_sysfs_u32(path: &Path) -> Option<u32> {
+    read_to_string(path).ok()?.trim().parse().ok()
+}
+
+pub fn get_sriov_info(if_name: &str, if_index: u32) -> io::Result<Option<SriovInfo>> {
+    let device = Path::new("/sys/class/net").join(if_name).join("device");
+    let physfn = device.join("physfn");
+    if physfn.exists() {
+        // Find which of the PF's virtfnN links points back at this device
+        let this = std::fs::canonicalize(&device)?;
+        let vf_index = std::fs::read_dir(&physfn)?
+            .filter_map(|entry| entry.ok())
+            .find_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let index = name.strip_prefix("virtfn")?.parse().ok()?;
+                (std::fs::canonicalize(entry.path()).ok()? == this).then_some(index)
+            });
+        return Ok(Some(SriovInfo {
+            role: SriovRole::VirtualFunction,
+            total_vfs: 0,
+            num_vfs: 0,
+            vfs: Vec::new(),
+            physical_function: pci_net_name(&physfn),
+            vf_index,
+        }));
+    }
+    let total_vfs = match read_sysfs_u32(&device.join("sriov_totalvfs")) {
+        Some(total_vfs) => total_vfs,
+        None => return Ok(None),
+    };
+    let num_vfs = read_sysfs_u32(&device.join("sriov_numvfs")).unwrap_or(0);
+    // The VF configuration is optional; the topology comes from sysfs
+    let config = match get_vf_config(if_index) {
+        Ok(config) => config,
+        Err(err) => {
+            debug!("{}: failed to get VF configuration: {}", if_name, err);
+            Vec::new()
+        }
+    };
+    let vfs = (0..num_vfs)
+        .map(|index| {
+            let virtfn = device.join(format!("virtfn{}", index));
+            let mut vf = config
+                .iter()
+                .find(|vf| vf.index == index)
+                .cloned()
+                .unwrap_or_default();
+            vf.index = index;
+            vf.pci_address = read_link(&virtfn)
+                .ok()
+                .and_then(|link| Some(link.file_name()?.to_string_lossy().into_owned()));
+            vf.name = pci_net_name(&virtfn);
+            vf
+        })
+        .collect();
+    Ok(Some(SriovInfo {
+        role: SriovRole::PhysicalFunction,
+        total_vfs,
+        num_vfs,
+        vfs,
+        physical_function: None,
+        vf_index: None,
+    }))
+}
+
+const RTM_GETQDISC: u16 = 38;
+/// Size of `struct tcmsg`
+const TCMSG_LEN: usize = 20;
+const TCA_KIND: u16 = 1;
+const TCA_STATS: u16 = 3;
+const TCA_STATS2: u16 = 7;
+const TCA_STATS_BASIC: u16 = 1;
+const TCA_STATS_QUEUE: u16 = 3;
+const TCA_STATS_PKT64: u16 = 8;
+
+fn read_u64(buf: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_ne_bytes(
+        buf.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+/// Decode the payload of an `RTM_NEWQDISC` message, returning the interface
+/// index and the qdisc.
+///
+/// The message is parsed by hand because `netlink_packet_route` rejects the
+/// options of several common qdiscs.
+fn parse_qdisc(buf: &[u8]) -> Option<(u32, Qdisc)> {
+    if buf.len() < TCMSG_LEN {
+        return None;
+    }
+    let if_index = netlink::read_u32(buf, 4)?;
+    let mut qdisc = Qdisc {
+        kind: String::new(),
+        handle: netlink::read_u32(buf, 8)?,
+        parent: netlink::read_u32(buf, 12)?,
+        stats: QdiscStats::default(),
+    };
+    let mut has_stats2 = false;
+    for (kind, attr) in netlink::iter_nlas(&buf[TCMSG_LEN..]) {
+        match kind {
+            TCA_KIND => {
+                let name = attr.split(|b| *b == 0).next().unwrap_or_default();
+                qdisc.kind = String::from_utf8_lossy(name).into_owned();
+            }
+            // struct tc_stats, superseded by TCA_STATS2 when both are present
+            TCA_STATS if !has_stats2 => {
+                let stats = &mut qdisc.stats;
+                stats.bytes = read_u64(attr, 0).unwrap_or(0);
+                stats.packets = netlink::read_u32(attr, 8).unwrap_or(0) as u64;
+                stats.drops = netlink::read_u32(attr, 12).unwrap_or(0) as u64;
+                stats.overlimits = netlink::read_u32(attr, 16).unwrap_or(0) as u64;
+                stats.qlen = netlink::read_u32(attr, 28).unwrap_or(0);
+                stats.backlog = netlink::read_u32(attr, 32).unwrap_or(0);
+            }
+            TCA_STATS2 => {
+                has_stats2 = true;
+                let stats = &mut qdisc.stats;
+                for (kind, attr) in netlink::iter_nlas(attr) {
+                    match kind {
+                        // struct gnet_stats_basic: bytes, packets
+                        TCA_STATS_BASIC => {
+                            stats.bytes = read_u64(attr, 0).unwrap_or(0);
+                            stats.packets = netlink::read_u32(attr, 8).unwrap_or(0) as u64;
+                        }
+                        // Only sent once the packet count overflows 32 bits
+                        TCA_STATS_PKT64 => stats.packets = read_u64(attr, 0).unwrap_or(0),
+                        // struct gnet_stats_queue: qlen, backlog, drops, requeues, overlimits
+                        TCA_STATS_QUEUE => {
+                            stats.qlen = netlink::read_u32(attr, 0).unwrap_or(0);
+                            stats.backlog = netlink::read_u32(attr, 4).unwrap_or(0);
+                            stats.drops = netlink::read_u32(attr, 8).unwrap_or(0) as u64;
+                            stats.requeues = netlink::read_u32(attr, 12).unwrap_or(0) as u64;
+                            stats.overlimits = netlink::read_u32(attr, 16).unwrap_or(0) as u64;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Some((if_index, qdisc))
+}
+
+pub fn get_qdiscs(if_index: u32) -> io::Result<Vec<Qdisc>> {
+    let mut tcmsg = [0u8; TCMSG_LEN];
+    tcmsg[4..8].copy_from_slice(&if_index.to_ne_bytes());
+    let mut qdiscs: Vec<Qdisc> = netlink::dump_raw(RTM_GETQDISC, &tcmsg)?
+        .iter()
+        .filter_map(|payload| parse_qdisc(payload))
+        // Older kernels ignore the index and dump every interface
+        .filter(|(index, _)| *index == if_index)
+        .map(|(_, qdisc)| qdisc)
+        .collect();
+    qdiscs.sort_by_key(|qdisc| !qdisc.is_root());
+    Ok(qdiscs)
+}
+
+#[repr(C)]
+struct EthtoolDrvInfo {
+    cmd: u32,
+    driver: [u8; 32],
+    version: [u8; 32],
+    fw_version: [u8; 32],
+    bus_info: [u8; 32],
+    erom_version: [u8; 32],
+    reserved2: [u8; 12],
+    n_priv_flags: u32,
+    n_stats: u32,
+    testinfo_len: u32,
+    eedump_len: u32,
+    regdump_len: u32,
+}
+
+fn drvinfo_string(field: &[u8]) -> Option<String> {
+    let end = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+    let value = String::from_utf8_lossy(&field[..end]).trim().to_string();
+    if value.is_empty() || value == "N/A" {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+pub fn get_driver_info(if_name: &str) -> Option<DriverInfo> {
+    let mut info: EthtoolDrvInfo = unsafe { std::mem::zeroed() };
+    info.cmd = ethtool::ETHTOOL_GDRVINFO;
+    // SAFETY: info is a struct ethtool_drvinfo
+    unsafe { ethtool::ethtool_ioctl(if_name, &mut info as *mut EthtoolDrvInfo as *mut c_void) }
+        .ok()?;
+    Some(DriverInfo {
+        name: drvinfo_string(&info.driver)?,
+        version: drvinfo_string(&info.version),
+        firmware_version: drvinfo_string(&info.fw_version),
+        bus_info: drvinfo_string(&info.bus_info),
+    })
+}
+
+fn address_of(msg: &AddressMessage) -> Option<IpAddr> {
+    let mut address = None;
+    let mut local = None;
+    for nla in &msg.nlas {
+        match nla {
+            AddressNla::Address(bytes) => address = Some(bytes),
+            AddressNla::Local(bytes) => local = Some(bytes),
+            _ => {}
+        }
+    }
+    // On point-to-point links IFA_ADDRESS is the peer, IFA_LOCAL the local address.
+    let bytes = local.or(address)?;
+    match msg.header.family as u16 {
+        AF_INET => Some(IpAddr::V4(Ipv4Addr::from(
+            <[u8; 4]>::try_from(&bytes[..]).ok()?,
+        ))),
+        AF_INET6 => Some(IpAddr::V6(Ipv6Addr::from(
+            <[u8; 16]>::try_from(&bytes[..]).ok()?,
+        ))),
+        _ => None,
+    }
+}
+
+fn address_flags(msg: &AddressMessage) -> u32 {
+    // IFA_FLAGS supersedes the 8-bit header field when present.
+    msg.nlas
+        .iter()
+        .find_map(|nla| match nla {
+            AddressNla::Flags(flags) => Some(*flags),
+            _ => None,
+        })
+        .unwrap_or(msg.header.flags as u32)
+}
+
+/// Lifetime value meaning "forever" in `struct ifa_cacheinfo`
+const INFINITY_LIFE_TIME: u32 = 0xffff_ffff;
+
+/// Preferred and valid lifetimes from IFA_CACHEINFO
+fn address_lifetimes(msg: &AddressMessage) -> (Option<Duration>, Option<Duration>) {
+    let Some(info) = msg.nlas.iter().find_map(|nla| match nla {
+        AddressNla::CacheInfo(info) => Some(info),
+        _ => None,
+    }) else {
+        return (None, None);
+    };
+    // struct ifa_cacheinfo { ifa_prefered, ifa_valid, cstamp, tstamp }, in seconds
+    let lifetime = |offset: usize| {
+        let secs = u32::from_ne_bytes(info.get(offset..offset + 4)?.try_into().ok()?);
+        match secs {
+            INFINITY_LIFE_TIME => None,
+            secs => Some(Duration::from_secs(secs as u64)),
+        }
+    };
+    (lifetime(0), lifetime(4))
+}
+
+fn dad_state_from_flags(family: u16, flags: u32) -> Option<DadState> {
+    if family != AF_INET6 || flags & IFA_F_NODAD != 0 {
+        return None;
+    }
+    Some(if flags & IFA_F_DADFAILED != 0 {
+        DadState::Duplicate
+    } else if flags & IFA_F_OPTIMISTIC != 0 {
+        DadState::Optimistic
+    } else if flags & IFA_F_TENTATIVE != 0 {
+        DadState::Tentative
+    } else if flags & IFA_F_DEPRECATED != 0 {
+        DadState::Deprecated
+    } else {
+        DadState::Preferred
+    })
+}
+
+fn address_messages(if_index: u32) -> io::Result<Vec<AddressMessage>> {
+    let msgs = netlink::dump(RtnlMessage::GetAddress(AddressMessage::default()))?;
+    Ok(msgs
+        .into_iter()
+        .filter_map(|msg| match msg {
+            RtnlMessage::NewAddress(msg) if msg.header.index == if_index => Some(msg),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Classify an address from its scope, flags and lifetime
+fn address_origin(
+    addr: IpAddr,
+    prefix_len: u8,
+    flags: u32,
+    valid_lifetime: Option<Duration>,
+) -> AddressOrigin {
+    match addr {
+        IpAddr::V4(addr) if addr.is_loopback() => AddressOrigin::Other,
+        IpAddr::V4(addr) if addr.is_link_local() => AddressOrigin::LinkLocal,
+        // DHCP clients add their leases with the lease time as lifetime
+        IpAddr::V4(_) if valid_lifetime.is_some() => AddressOrigin::Dhcp,
+        IpAddr::V4(_) => AddressOrigin::Manual,
+        IpAddr::V6(addr) if addr.is_loopback() => AddressOrigin::Other,
+        IpAddr::V6(addr) if crate::zone::is_link_local(&addr) => AddressOrigin::LinkLocal,
+        // IFA_F_SECONDARY doubles as IFA_F_TEMPORARY for IPv6
+        IpAddr::V6(_) if flags & IFA_F_TEMPORARY != 0 => AddressOrigin::Random,
+        IpAddr::V6(_) if flags & IFA_F_PERMANENT != 0 => AddressOrigin::Manual,
+        // DHCPv6 clients add single addresses, SLAAC a /64 prefix
+        IpAddr::V6(_) if prefix_len == 128 => AddressOrigin::Dhcp,
+        IpAddr::V6(_) => AddressOrigin::RouterAdvertisement,
+    }
+}
+
+pub fn get_address_info(if_index: u32) -> io::Result<Vec<AddressInfo>> {
+    let mut addrs = Vec::new();
+    for msg in address_messages(if_index)? {
+        if let Some(addr) = address_of(&msg) {
+            let (preferred_lifetime, valid_lifetime) = address_lifetimes(&msg);
+            let flags = address_flags(&msg);
+            let prefix_len = msg.header.prefix_len;
+            let label = msg.nlas.iter().find_map(|nla| match nla {
+                AddressNla::Label(label) => Some(label.clone()),
+                _ => None,
+            });
+            addrs.push(AddressInfo {
+                addr,
+                prefix_len,
+                dad_state: dad_state_from_flags(msg.header.family as u16, flags),
+                valid_lifetime,
+                preferred_lifetime,
+                label,
+                secondary: addr.is_ipv4() && flags & IFA_F_SECONDARY != 0,
+                origin: address_origin(addr, prefix_len, flags, valid_lifetime),
+                temporary: addr.is_ipv6() && flags & IFA_F_TEMPORARY != 0,
+            });
+        }
+    }
+    Ok(addrs)
+}
+
+/// Unit of `rta_expires` in `struct rta_cacheinfo` (USER_HZ)
+const CLOCK_TICKS_PER_SEC: u64 = 100;
+
+/// Convert a gateway-less unicast route of the main table through `if_index`
+/// into an on-link prefix
+fn route_to_on_link_prefix(msg: RouteMessage, if_index: u32) -> Option<OnLinkPrefix> {
+    let family = msg.header.address_family as u16;
+    if msg.header.kind != RTN_UNICAST || msg.header.destination_prefix_length == 0 {
+        return None;
+    }
+    let mut destination = None;
+    let mut oif = None;
+    let mut table = msg.header.table as u32;
+    let mut valid_lifetime = None;
+    for nla in msg.nlas {
+        match nla {
+            RouteNla::Destination(bytes) => {
+                destination = match family {
+                    AF_INET => <[u8; 4]>::try_from(bytes).ok().map(IpAddr::from),
+                    AF_INET6 => <[u8; 16]>::try_from(bytes).ok().map(IpAddr::from),
+                    _ => None,
+                };
+            }
+            RouteNla::Gateway(_) | RouteNla::MultiPath(_) => return None,
+            RouteNla::Oif(index) => oif = Some(index),
+            RouteNla::Table(id) => table = id,
+            RouteNla::CacheInfo(info) => {
+                // rta_clntref, rta_lastuse, rta_expires; 0 if the route does not expire
+                valid_lifetime = netlink::read_u32(&info, 8)
+                    .filter(|ticks| *ticks != 0)
+                    .map(|ticks| Duration::from_millis(ticks as u64 * 1000 / CLOCK_TICKS_PER_SEC));
+            }
+            _ => {}
+        }
+    }
+    if table != RT_TABLE_MAIN as u32 || oif != Some(if_index) {
+        return None;
+    }
+    let prefix = IpNet::new(destination?, msg.header.destination_prefix_length);
+    let link_local = match prefix.addr() {
+        IpAddr::V4(addr) => addr.is_link_local(),
+        IpAddr::V6(addr) => crate::zone::is_link_local(&addr),
+    };
+    let origin = match msg.header.protocol {
+        RTPROT_RA => AddressOrigin::RouterAdvertisement,
+        RTPROT_DHCP => AddressOrigin::Dhcp,
+        RTPROT_BOOT | RTPROT_STATIC => AddressOrigin::Manual,
+        RTPROT_KERNEL if link_local => AddressOrigin::LinkLocal,
+        _ => AddressOrigin::Other,
+    };
+    Some(OnLinkPrefix {
+        prefix,
+        valid_lifetime,
+        origin,
+    })
+}
+
+pub fn get_on_link_prefixes(if_index: u32) -> io::Result<Vec<OnLinkPrefix>> {
+    let msgs = netlink::dump(RtnlMessage::GetRoute(RouteMessage::default()))?;
+    Ok(msgs
+        .into_iter()
+        .filter_map(|msg| match msg {
+            RtnlMessage::NewRoute(msg) => route_to_on_link_prefix(msg, if_index),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Remove the address and add it back with the same prefix, lifetimes and flags,
+/// which makes the kernel run DAD for it again.
+///
+/// The kernel has no request that restarts DAD in place: replacing the address
+/// or toggling `IFA_F_NODAD` leaves its state alone, and `IFA_F_TENTATIVE`
+/// cannot be set from user space. While the address is gone, the prefix route
+/// is removed and sockets bound to the address are closed, and it comes back
+/// as a static address even if it was configured by SLAAC. If adding it back
+/// fails, the address as dumped is restored and the error of the add returned.
+#[cfg(feature = "config")]
+pub fn restart_dad(if_index: u32, addr: Ipv6Addr) -> io::Result<()> {
+    let msg = address_messages(if_index)?
+        .into_iter()
+        .find(|msg| address_of(msg) == Some(IpAddr::V6(addr)))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is not assigned to the interface", addr),
+            )
+        })?;
+    let flags = address_flags(&msg);
+    if flags & IFA_F_NODAD != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "DAD is disabled for the address",
+        ));
+    }
+    let flags = flags & !(IFA_F_TENTATIVE | IFA_F_DADFAILED);
+
+    let mut del = AddressMessage::default();
+    del.header = msg.header.clone();
+    del.nlas = msg
+        .nlas
+        .iter()
+        .filter(|nla| matches!(nla, AddressNla::Address(_) | AddressNla::Local(_)))
+        .cloned()
+        .collect();
+    netlink::request(RtnlMessage::DelAddress(del), 0)?;
+
+    let mut add = AddressMessage::default();
+    add.header = msg.header.clone();
+    add.header.flags = flags as u8;
+    add.nlas = msg
+        .nlas
+        .iter()
+        .filter(|nla| {
+            matches!(
+                nla,
+                AddressNla::Address(_) | AddressNla::Local(_) | AddressNla::CacheInfo(_)
+            )
+        })
+        .cloned()
+        .collect();
+    add.nlas.push(AddressNla::Flags(flags));
+    netlink::request(RtnlMessage::NewAddress(add), NLM_F_CREATE | NLM_F_EXCL).inspect_err(|_| {
+        // Replace rather than fail if a racing SLAAC or networkd re-add got there first
+        if let Err(e) = netlink::request(RtnlMessage::NewAddress(msg), NLM_F_CREATE | NLM_F_REPLACE)
+        {
+            debug!("restoring {} failed: {}", addr, e);
+        }
+    })
+}
+
+#[cfg(feature = "config")]
+const ARP_PROBE_NUM: usize = 3;
+#[cfg(feature = "config")]
+const ARP_PROBE_WAIT: Duration = Duration::from_secs(1);
+
+/// ARP request with an all-zero sender protocol address (RFC 5227 section 2.1.1)
+#[cfg(feature = "config")]
+fn build_arp_probe(sender: MacAddr, target: Ipv4Addr) -> [u8; 28] {
+    let mut pkt = [0u8; 28];
+    pkt[0..2].copy_from_slice(&1u16.to_be_bytes()); // Ethernet
+    pkt[2..4].copy_from_slice(&0x0800u16.to_be_bytes()); // IPv4
+    pkt[4] = 6;
+    pkt[5] = 4;
+    pkt[6..8].copy_from_slice(&1u16.to_be_bytes()); // request
+    pkt[8..14].copy_from_slice(&sender.octets());
+    pkt[24..28].copy_from_slice(&target.octets());
+    pkt
+}
+
+/// Returns the sender hardware address if `pkt` shows another host using or probing `target`
+#[cfg(feature = "config")]
+fn arp_conflict(pkt: &[u8], own: MacAddr, target: Ipv4Addr) -> Option<MacAddr> {
+    if pkt.len() < 28 || pkt[4] != 6 || pkt[5] != 4 {
+        return None;
+    }
+    let sha = MacAddr::from_octets(pkt[8..14].try_into().ok()?);
+    let spa = Ipv4Addr::from(<[u8; 4]>::try_from(&pkt[14..18]).ok()?);
+    let tpa = Ipv4Addr::from(<[u8; 4]>::try_from(&pkt[24..28]).ok()?);
+    if sha == own {
+        return None;
+    }
+    let probe = spa.is_unspecified() && tpa == target;
+    if spa == target || probe {
+        Some(sha)
+    } else {
+        None
+    }
+}
+
+#[cfg(feature = "config")]
+pub fn probe_ipv4_conflict(
+    if_index: u32,
+    mac_addr: MacAddr,
+    target: Ipv4Addr,
+) -> io::Result<Option<MacAddr>> {
+    const ETH_P_ARP: u16 = 0x0806;
+
+    let fd = unsafe {
+        libc::socket(
+            libc::AF_PACKET,
+            libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+            ETH_P_ARP.to_be() as libc::c_int,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    let mut sll: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+    sll.sll_family = libc::AF_PACKET as u16;
+    sll.sll_protocol = ETH_P_ARP.to_be();
+    sll.sll_ifindex = if_index as libc::c_int;
+    sll.sll_halen = 6;
+    sll.sll_addr[..6].copy_from_slice(&MacAddr::broadcast().octets());
+    let sll_len = std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t;
+    let sll_ptr = &sll as *const libc::sockaddr_ll as *const libc::sockaddr;
+    if unsafe { libc::bind(fd.as_raw_fd(), sll_ptr, sll_len) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let timeout = libc::timeval {
+        tv_sec: 0,
+        tv_usec: 100_000,
+    };
+    let ret = unsafe {
+        libc::setsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_RCVTIMEO,
+            &timeout as *const libc::timeval as *const c_void,
+            std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+        )
+    };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let probe = build_arp_probe(mac_addr, target);
+    let mut buf = [0u8; 64];
+    for _ in 0..ARP_PROBE_NUM {
+        let sent = unsafe {
+            libc::sendto(
+                fd.as_raw_fd(),
+                probe.as_ptr() as *const c_void,
+                probe.len(),
+                0,
+                sll_ptr,
+                sll_len,
+            )
+        };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let deadline = Instant::now() + ARP_PROBE_WAIT;
+        while Instant::now() < deadline {
+            let n = unsafe {
+                libc::recv(
+                    fd.as_raw_fd(),
+                    buf.as_mut_ptr() as *mut c_void,
+                    buf.len(),
+                    0,
+                )
+            };
+            if n < 0 {
+                let err = io::Error::last_os_error();
+                match err.kind() {
+                    io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted => continue,
+                    _ => return Err(err),
                 }
             }
+            if let Some(mac) = arp_conflict(&buf[..n as usize], mac_addr, target) {
+                return Ok(Some(mac));
+            }
         }
-        Err(_) => {
-            return None;
+    }
+    Ok(None)
+}
+
+const RTM_GETLINK: u16 = 18;
+/// Size of `struct ifinfomsg`
+const IFINFOMSG_LEN: usize = 16;
+const IFLA_LINKINFO: u16 = 18;
+const IFLA_INFO_KIND: u16 = 1;
+const IFLA_INFO_DATA: u16 = 2;
+// Attributes of IFLA_INFO_DATA for gre, gretap, ip6gre and ip6gretap links
+const IFLA_GRE_LINK: u16 = 1;
+const IFLA_GRE_OKEY: u16 = 5;
+const IFLA_GRE_LOCAL: u16 = 6;
+const IFLA_GRE_REMOTE: u16 = 7;
+const IFLA_GRE_TTL: u16 = 8;
+// Attributes of IFLA_INFO_DATA for ipip, sit and ip6tnl links
+const IFLA_IPTUN_LINK: u16 = 1;
+const IFLA_IPTUN_LOCAL: u16 = 2;
+const IFLA_IPTUN_REMOTE: u16 = 3;
+const IFLA_IPTUN_TTL: u16 = 4;
+// Attributes of IFLA_INFO_DATA for vti and vti6 links
+const IFLA_VTI_LINK: u16 = 1;
+const IFLA_VTI_OKEY: u16 = 3;
+const IFLA_VTI_LOCAL: u16 = 4;
+const IFLA_VTI_REMOTE: u16 = 5;
+// Attributes of IFLA_INFO_DATA for vxlan links
+const IFLA_VXLAN_ID: u16 = 1;
+const IFLA_VXLAN_GROUP: u16 = 2;
+const IFLA_VXLAN_LINK: u16 = 3;
+const IFLA_VXLAN_LOCAL: u16 = 4;
+const IFLA_VXLAN_TTL: u16 = 5;
+const IFLA_VXLAN_PORT: u16 = 15;
+const IFLA_VXLAN_GROUP6: u16 = 16;
+const IFLA_VXLAN_LOCAL6: u16 = 17;
+// Attributes of IFLA_INFO_DATA for geneve links
+const IFLA_GENEVE_ID: u16 = 1;
+const IFLA_GENEVE_REMOTE: u16 = 2;
+const IFLA_GENEVE_TTL: u16 = 3;
+const IFLA_GENEVE_PORT: u16 = 5;
+const IFLA_GENEVE_REMOTE6: u16 = 7;
+
+fn tunnel_kind(kind: &[u8]) -> Option<TunnelKind> {
+    match kind {
+        b"gre" | b"ip6gre" => Some(TunnelKind::Gre),
+        b"gretap" | b"ip6gretap" => Some(TunnelKind::GreTap),
+        b"ipip" | b"ip6tnl" => Some(TunnelKind::Ipip),
+        b"sit" => Some(TunnelKind::Sit),
+        b"vti" | b"vti6" => Some(TunnelKind::Vti),
+        b"vxlan" => Some(TunnelKind::Vxlan),
+        b"geneve" => Some(TunnelKind::Geneve),
+        _ => None,
+    }
+}
+
+/// Address attribute of a tunnel, `None` if unspecified
+fn tunnel_addr(payload: &[u8]) -> Option<IpAddr> {
+    let addr = match payload.len() {
+        4 => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(payload).ok()?)),
+        16 => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(payload).ok()?)),
+        _ => return None,
+    };
+    (!addr.is_unspecified()).then_some(addr)
+}
+
+fn read_be16(payload: &[u8]) -> Option<u16> {
+    Some(u16::from_be_bytes(payload.get(..2)?.try_into().ok()?))
+}
+
+fn read_be32(payload: &[u8]) -> Option<u32> {
+    Some(u32::from_be_bytes(payload.get(..4)?.try_into().ok()?))
+}
+
+/// Parse the `IFLA_INFO_DATA` of a tunnel link.
+///
+/// Decoded by hand, as `netlink_packet_route` keeps the data of most tunnel kinds
+/// opaque and rejects VXLAN attributes added by newer kernels.
+fn parse_tunnel_data(kind: TunnelKind, data: &[u8]) -> TunnelInfo {
+    use TunnelKind::*;
+    let mut info = TunnelInfo {
+        kind,
+        local: None,
+        remote: None,
+        vni: None,
+        key: None,
+        ttl: None,
+        port: None,
+        link: None,
+    };
+    for (nla_type, payload) in netlink::iter_nlas(data) {
+        match (kind, nla_type) {
+            (Gre | GreTap, IFLA_GRE_LINK)
+            | (Ipip | Sit, IFLA_IPTUN_LINK)
+            | (Vti, IFLA_VTI_LINK)
+            | (Vxlan, IFLA_VXLAN_LINK) => {
+                info.link = netlink::read_u32(payload, 0).filter(|index| *index != 0);
+            }
+            (Gre | GreTap, IFLA_GRE_LOCAL)
+            | (Ipip | Sit, IFLA_IPTUN_LOCAL)
+            | (Vti, IFLA_VTI_LOCAL)
+            | (Vxlan, IFLA_VXLAN_LOCAL | IFLA_VXLAN_LOCAL6) => {
+                info.local = tunnel_addr(payload).or(info.local);
+            }
+            (Gre | GreTap, IFLA_GRE_REMOTE)
+            | (Ipip | Sit, IFLA_IPTUN_REMOTE)
+            | (Vti, IFLA_VTI_REMOTE)
+            | (Vxlan, IFLA_VXLAN_GROUP | IFLA_VXLAN_GROUP6)
+            | (Geneve, IFLA_GENEVE_REMOTE | IFLA_GENEVE_REMOTE6) => {
+                info.remote = tunnel_addr(payload).or(info.remote);
+            }
+            (Gre | GreTap, IFLA_GRE_OKEY) | (Vti, IFLA_VTI_OKEY) => {
+                info.key = read_be32(payload).filter(|key| *key != 0);
+            }
+            (Gre | GreTap, IFLA_GRE_TTL)
+            | (Ipip | Sit, IFLA_IPTUN_TTL)
+            | (Vxlan, IFLA_VXLAN_TTL)
+            | (Geneve, IFLA_GENEVE_TTL) => {
+                info.ttl = payload.first().copied().filter(|ttl| *ttl != 0);
+            }
+            (Vxlan, IFLA_VXLAN_ID) | (Geneve, IFLA_GENEVE_ID) => {
+                info.vni = netlink::read_u32(payload, 0);
+            }
+            (Vxlan, IFLA_VXLAN_PORT) | (Geneve, IFLA_GENEVE_PORT) => info.port = read_be16(payload),
+            _ => {}
         }
+    }
+    info
+}
+
+pub fn get_tunnel_info(if_index: u32) -> io::Result<Option<TunnelInfo>> {
+    let mut ifinfomsg = [0u8; IFINFOMSG_LEN];
+    ifinfomsg[4..8].copy_from_slice(&if_index.to_ne_bytes());
+    let Some(reply) = netlink::get_raw(RTM_GETLINK, &ifinfomsg)? else {
+        return Ok(None);
+    };
+    let Some(linkinfo) = reply
+        .get(IFINFOMSG_LEN..)
+        .and_then(|attrs| netlink::iter_nlas(attrs).find(|(kind, _)| *kind == IFLA_LINKINFO))
+    else {
+        return Ok(None);
     };
+    let mut kind = None;
+    let mut data: &[u8] = &[];
+    for (nla_type, payload) in netlink::iter_nlas(linkinfo.1) {
+        match nla_type {
+            // NUL-terminated
+            IFLA_INFO_KIND => kind = tunnel_kind(payload.split(|b| *b == 0).next().unwrap_or(&[])),
+            IFLA_INFO_DATA => data = payload,
+            _ => {}
+        }
+    }
+    Ok(kind.map(|kind| parse_tunnel_data(kind, data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interface::TC_H_ROOT;
+
+    #[test]
+    fn test_description_sources() {
+        assert_eq!(
+            parse_modalias("pci:v00008086d000015B8sv00001028sd000007A1bc02sc00i00"),
+            Some(Modalias::Pci {
+                vendor: 0x8086,
+                device: 0x15b8
+            })
+        );
+        assert_eq!(
+            parse_modalias("usb:v0BDAp8153d3000dc00dsc00dp00icFFiscFFip00in00"),
+            Some(Modalias::Usb {
+                vendor: 0x0bda,
+                product: 0x8153
+            })
+        );
+        assert_eq!(
+            parse_modalias("virtio:d00000001v00001AF4"),
+            Some(Modalias::Virtio { device: 1 })
+        );
+        assert_eq!(parse_modalias("platform:bcmgenet"), None);
+
+        let db = "# pci.ids\n\
+                  8086  Intel Corporation\n\
+                  \t1533  I210 Gigabit Network Connection\n\
+                  \t\t8086 0001  Ethernet Server Adapter I210-T1\n\
+                  \t15b8  Ethernet Connection (2) I219-V\n\
+                  8087  Intel Corporation\n\
+                  \t0029  AX200 Bluetooth\n\
+                  # List of known device classes\n\
+                  C 02  Network controller\n\
+                  \t00  Ethernet controller\n";
+        let db = parse_ids(db);
+        assert_eq!(
+            lookup_ids(&db, 0x8086, 0x15b8),
+            Some(("Intel Corporation", "Ethernet Connection (2) I219-V"))
+        );
+        assert_eq!(lookup_ids(&db, 0x8086, 0x0029), None);
+        assert_eq!(lookup_ids(&db, 0x10ec, 0x8168), None);
+        assert_eq!(db.len(), 2);
+
+        let udev = "I:5181550\n\
+                    E:ID_NET_NAME_PATH=enp0s31f6\n\
+                    E:ID_VENDOR_FROM_DATABASE=Intel Corporation\n\
+                    E:ID_MODEL_FROM_DATABASE=Ethernet Connection (2) I219-LM\n";
+        assert_eq!(
+            parse_udev_description(udev).as_deref(),
+            Some("Intel Corporation Ethernet Connection (2) I219-LM")
+        );
+        assert_eq!(parse_udev_description("E:ID_NET_NAME_PATH=eth0\n"), None);
+        assert_eq!(
+            with_vendor("Realtek", "Realtek USB 10/100/1000 LAN"),
+            "Realtek USB 10/100/1000 LAN"
+        );
+    }
+
+    #[test]
+    fn test_dad_state_from_flags() {
+        let nodad = dad_state_from_flags(AF_INET6, IFA_F_NODAD | IFA_F_PERMANENT);
+        assert_eq!(nodad, None);
+        assert_eq!(dad_state_from_flags(AF_INET, IFA_F_PERMANENT), None);
+        assert_eq!(
+            dad_state_from_flags(AF_INET6, IFA_F_TENTATIVE | IFA_F_DADFAILED),
+            Some(DadState::Duplicate)
+        );
+        assert_eq!(
+            dad_state_from_flags(AF_INET6, IFA_F_TENTATIVE | IFA_F_OPTIMISTIC),
+            Some(DadState::Optimistic)
+        );
+        assert_eq!(
+            dad_state_from_flags(AF_INET6, IFA_F_TENTATIVE),
+            Some(DadState::Tentative)
+        );
+        assert_eq!(
+            dad_state_from_flags(AF_INET6, IFA_F_PERMANENT),
+            Some(DadState::Preferred)
+        );
+    }
+
+    #[test]
+    fn test_address_origin() {
+        let lease = Some(Duration::from_secs(3600));
+        let v4 = |s: &str| IpAddr::V4(s.parse().unwrap());
+        let v6 = |s: &str| IpAddr::V6(s.parse().unwrap());
+        assert_eq!(
+            address_origin(v4("192.168.1.5"), 24, IFA_F_PERMANENT, None),
+            AddressOrigin::Manual
+        );
+        assert_eq!(
+            address_origin(v4("192.168.1.5"), 24, 0, lease),
+            AddressOrigin::Dhcp
+        );
+        assert_eq!(
+            address_origin(v4("169.254.3.4"), 16, IFA_F_PERMANENT, None),
+            AddressOrigin::LinkLocal
+        );
+        assert_eq!(
+            address_origin(v6("2001:db8::1"), 64, IFA_F_TEMPORARY, lease),
+            AddressOrigin::Random
+        );
+        assert_eq!(
+            address_origin(v6("2001:db8::2"), 64, 0, lease),
+            AddressOrigin::RouterAdvertisement
+        );
+        assert_eq!(
+            address_origin(v6("2001:db8::3"), 128, 0, lease),
+            AddressOrigin::Dhcp
+        );
+        assert_eq!(
+            address_origin(v6("::1"), 128, IFA_F_PERMANENT, None),
+            AddressOrigin::Other
+        );
+    }
+
+    fn nla(kind: u16, payload: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&(4 + payload.len() as u16).to_ne_bytes());
+        buf.extend_from_slice(&kind.to_ne_bytes());
+        buf.extend_from_slice(payload);
+        buf.resize((buf.len() + 3) & !3, 0);
+        buf
+    }
+
+    #[test]
+    fn test_parse_qdisc() {
+        let mut msg = vec![0u8; TCMSG_LEN];
+        msg[4..8].copy_from_slice(&3u32.to_ne_bytes());
+        msg[8..12].copy_from_slice(&0x8001_0000u32.to_ne_bytes());
+        msg[12..16].copy_from_slice(&TC_H_ROOT.to_ne_bytes());
+        msg.extend(nla(TCA_KIND, b"fq_codel\0"));
+        let mut basic = 1500u64.to_ne_bytes().to_vec();
+        basic.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 0]);
+        let queue: Vec<u8> = [2u32, 3000, 4, 5, 6]
+            .iter()
+            .flat_map(|v| v.to_ne_bytes())
+            .collect();
+        let mut stats2 = nla(TCA_STATS_BASIC, &basic);
+        stats2.extend(nla(TCA_STATS_QUEUE, &queue));
+        msg.extend(nla(TCA_STATS2, &stats2));
+
+        let (if_index, qdisc) = parse_qdisc(&msg).unwrap();
+        assert_eq!(if_index, 3);
+        assert_eq!(qdisc.kind, "fq_codel");
+        assert_eq!(qdisc.handle, 0x8001_0000);
+        assert!(qdisc.is_root());
+        assert_eq!(
+            qdisc.stats,
+            QdiscStats {
+                bytes: 1500,
+                packets: 1,
+                drops: 4,
+                overlimits: 6,
+                requeues: 5,
+                qlen: 2,
+                backlog: 3000,
+            }
+        );
+        assert!(parse_qdisc(&msg[..8]).is_none());
+    }
+
+    #[test]
+    fn test_route_to_on_link_prefix() {
+        let mut msg = RouteMessage::default();
+        msg.header.address_family = AF_INET6 as u8;
+        msg.header.kind = RTN_UNICAST;
+        msg.header.table = RT_TABLE_MAIN;
+        msg.header.protocol = RTPROT_RA;
+        msg.header.destination_prefix_length = 64;
+        let prefix: Ipv6Addr = "2001:db8:1::".parse().unwrap();
+        msg.nlas = vec![
+            RouteNla::Destination(prefix.octets().to_vec()),
+            RouteNla::Oif(2),
+            RouteNla::CacheInfo(
+                [0u32, 0, 8640000, 0]
+                    .iter()
+                    .flat_map(|v| v.to_ne_bytes())
+                    .collect(),
+            ),
+        ];
+        let on_link = route_to_on_link_prefix(msg.clone(), 2).unwrap();
+        assert_eq!(on_link.prefix, IpNet::new(IpAddr::V6(prefix), 64));
+        assert_eq!(on_link.origin, AddressOrigin::RouterAdvertisement);
+        assert_eq!(on_link.valid_lifetime, Some(Duration::from_secs(86400)));
+        assert!(on_link.contains("2001:db8:1::53".parse().unwrap()));
+        // Other interface
+        assert!(route_to_on_link_prefix(msg.clone(), 3).is_none());
+        // Through a router
+        let mut routed = msg.clone();
+        routed
+            .nlas
+            .push(RouteNla::Gateway(Ipv6Addr::LOCALHOST.octets().to_vec()));
+        assert!(route_to_on_link_prefix(routed, 2).is_none());
+        // Default route
+        msg.header.destination_prefix_length = 0;
+        assert!(route_to_on_link_prefix(msg, 2).is_none());
+    }
+
+    #[test]
+    fn test_parse_vf_info_list() {
+        let mut mac = 3u32.to_ne_bytes().to_vec();
+        mac.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x03]);
+        mac.resize(36, 0);
+        let vlan = [3u32, 100, 0].map(u32::to_ne_bytes).concat();
+        let link_state = [3u32, 2].map(u32::to_ne_bytes).concat();
+        let info = [
+            nla(IFLA_VF_MAC, &mac),
+            nla(IFLA_VF_VLAN, &vlan),
+            nla(IFLA_VF_LINK_STATE, &link_state),
+        ]
+        .concat();
+        let list = nla(IFLA_VF_INFO | 0x8000, &info);
+        let vfs = parse_vf_info_list(&list);
+        assert_eq!(vfs.len(), 1);
+        assert_eq!(vfs[0].index, 3);
+        assert_eq!(vfs[0].mac_addr, Some(MacAddr::new(0x02, 0, 0, 0, 0, 0x03)));
+        assert_eq!(vfs[0].vlan, Some(100));
+        assert_eq!(vfs[0].link_state, Some(VfLinkState::Disable));
+        assert_eq!(vfs[0].trust, None);
+    }
+
+    #[test]
+    fn test_parse_tunnel_data() {
+        let gre = [
+            nla(IFLA_GRE_LINK, &2u32.to_ne_bytes()),
+            nla(IFLA_GRE_OKEY, &42u32.to_be_bytes()),
+            nla(IFLA_GRE_LOCAL, &[192, 0, 2, 1]),
+            nla(IFLA_GRE_REMOTE, &[198, 51, 100, 1]),
+            nla(IFLA_GRE_TTL, &[64]),
+        ]
+        .concat();
+        let info = parse_tunnel_data(TunnelKind::Gre, &gre);
+        assert_eq!(info.link, Some(2));
+        assert_eq!(info.key, Some(42));
+        assert_eq!(info.local, Some(IpAddr::from([192, 0, 2, 1])));
+        assert_eq!(info.remote, Some(IpAddr::from([198, 51, 100, 1])));
+        assert_eq!(info.ttl, Some(64));
+
+        let remote6: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let geneve = [
+            nla(IFLA_GENEVE_ID, &100u32.to_ne_bytes()),
+            nla(IFLA_GENEVE_REMOTE, &[0, 0, 0, 0]),
+            nla(IFLA_GENEVE_REMOTE6, &remote6.octets()),
+            nla(IFLA_GENEVE_TTL, &[0]),
+            nla(IFLA_GENEVE_PORT, &6081u16.to_be_bytes()),
+        ]
+        .concat();
+        let info = parse_tunnel_data(TunnelKind::Geneve, &geneve);
+        assert_eq!(info.vni, Some(100));
+        assert_eq!(info.remote, Some(IpAddr::V6(remote6)));
+        assert_eq!(info.ttl, None);
+        assert_eq!(info.port, Some(6081));
+        assert_eq!(info.local, None);
+    }
+
+    #[test]
+    fn test_address_lifetimes() {
+        let mut msg = AddressMessage::default();
+        assert_eq!(address_lifetimes(&msg), (None, None));
+        let mut info = Vec::new();
+        for value in [1800u32, INFINITY_LIFE_TIME, 100, 200] {
+            info.extend_from_slice(&value.to_ne_bytes());
+        }
+        msg.nlas.push(AddressNla::CacheInfo(info));
+        assert_eq!(
+            address_lifetimes(&msg),
+            (Some(Duration::from_secs(1800)), None)
+        );
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_arp_conflict() {
+        let own = MacAddr::new(0x02, 0, 0, 0, 0, 1);
+        let other = MacAddr::new(0x02, 0, 0, 0, 0, 2);
+        let target = Ipv4Addr::new(192, 168, 1, 10);
+
+        // Our own probe looped back is not a conflict
+        assert_eq!(
+            arp_conflict(&build_arp_probe(own, target), own, target),
+            None
+        );
+        // Another host probing for the same address
+        let probe = build_arp_probe(other, target);
+        assert_eq!(arp_conflict(&probe, own, target), Some(other));
+        // A reply from the current owner
+        let mut reply = probe;
+        reply[7] = 2;
+        reply[14..18].copy_from_slice(&target.octets());
+        reply[18..24].copy_from_slice(&own.octets());
+        reply[24..28].copy_from_slice(&[0, 0, 0, 0]);
+        assert_eq!(arp_conflict(&reply, own, target), Some(other));
+        // Unrelated traffic
+        let unrelated = build_arp_probe(other, Ipv4Addr::new(192, 168, 1, 11));
+        assert_eq!(arp_conflict(&unrelated, own, target), None);
+    }
+
+    #[test]
+    fn test_observe_link() {
+        let mut links = BTreeMap::new();
+        let t = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        // Already up on the first look
+        assert_eq!(observe_link(&mut links, 2, true, 1, t(10)), None);
+        assert_eq!(observe_link(&mut links, 2, true, 1, t(20)), None);
+        // Goes down, then comes back up
+        assert_eq!(observe_link(&mut links, 2, false, 1, t(30)), None);
+        assert_eq!(observe_link(&mut links, 2, true, 2, t(40)), Some(t(40)));
+        assert_eq!(observe_link(&mut links, 2, true, 2, t(50)), Some(t(40)));
+        // Flapped between two looks
+        assert_eq!(observe_link(&mut links, 2, true, 3, t(60)), Some(t(60)));
+        assert_eq!(observe_link(&mut links, 2, false, 3, t(70)), None);
+    }
 }
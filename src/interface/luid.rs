@@ -0,0 +1,155 @@
+//! Conversions between the identifiers Windows uses for a network interface.
+//!
+//! Each adapter has a numeric index (`IfIndex`), a 64-bit LUID, an adapter
+//! GUID (which is also [`Interface::name`] on Windows) and an alias, the
+//! user-visible name shown in the control panel and used by `netsh`.
+//! The index may be reused after an adapter is removed, while the LUID and
+//! GUID persist.
+
+use crate::interface::Interface;
+use std::io;
+use windows_sys::core::GUID;
+use windows_sys::Win32::Foundation::NO_ERROR;
+use windows_sys::Win32::NetworkManagement::IpHelper::{
+    ConvertInterfaceAliasToLuid, ConvertInterfaceGuidToLuid, ConvertInterfaceIndexToLuid,
+    ConvertInterfaceLuidToAlias, ConvertInterfaceLuidToGuid, ConvertInterfaceLuidToIndex,
+};
+use windows_sys::Win32::NetworkManagement::Ndis::{IF_MAX_STRING_SIZE, NET_LUID_LH};
+
+fn check(ret: u32) -> io::Result<()> {
+    if ret == NO_ERROR {
+        Ok(())
+    } else {
+        Err(io::Error::from_raw_os_error(ret as i32))
+    }
+}
+
+/// Get the LUID of the interface with the given index
+pub fn index_to_luid(if_index: u32) -> io::Result<u64> {
+    let mut luid = NET_LUID_LH { Value: 0 };
+    check(unsafe { ConvertInterfaceIndexToLuid(if_index, &mut luid) })?;
+    Ok(unsafe { luid.Value })
+}
+
+/// Get the index of the interface with the given LUID
+pub fn luid_to_index(luid: u64) -> io::Result<u32> {
+    let luid = NET_LUID_LH { Value: luid };
+    let mut if_index = 0;
+    check(unsafe { ConvertInterfaceLuidToIndex(&luid, &mut if_index) })?;
+    Ok(if_index)
+}
+
+/// Get the adapter GUID of the interface with the given LUID,
+/// formatted as `{XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}`
+pub fn luid_to_guid(luid: u64) -> io::Result<String> {
+    let luid = NET_LUID_LH { Value: luid };
+    let mut guid = GUID::from_u128(0);
+    check(unsafe { ConvertInterfaceLuidToGuid(&luid, &mut guid) })?;
+    Ok(format_guid(&guid))
+}
+
+/// Get the LUID of the interface with the given adapter GUID.
+/// The surrounding braces are optional.
+pub fn guid_to_luid(guid: &str) -> io::Result<u64> {
+    let guid = parse_guid(guid)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid GUID"))?;
+    let mut luid = NET_LUID_LH { Value: 0 };
+    check(unsafe { ConvertInterfaceGuidToLuid(&guid, &mut luid) })?;
+    Ok(unsafe { luid.Value })
+}
+
+/// Get the alias (e.g. `Ethernet 2`) of the interface with the given LUID
+pub fn luid_to_alias(luid: u64) -> io::Result<String> {
+    let luid = NET_LUID_LH { Value: luid };
+    let mut buf = [0u16; IF_MAX_STRING_SIZE as usize + 1];
+    check(unsafe { ConvertInterfaceLuidToAlias(&luid, buf.as_mut_ptr(), buf.len()) })?;
+    let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+    Ok(String::from_utf16_lossy(&buf[..len]))
+}
+
+/// Get the LUID of the interface with the given alias
+pub fn alias_to_luid(alias: &str) -> io::Result<u64> {
+    let alias: Vec<u16> = alias.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut luid = NET_LUID_LH { Value: 0 };
+    check(unsafe { ConvertInterfaceAliasToLuid(alias.as_ptr(), &mut luid) })?;
+    Ok(unsafe { luid.Value })
+}
+
+#[cfg(any(feature = "firewall", feature = "wwan"))]
+pub(crate) fn guid_of(if_index: u32) -> io::Result<GUID> {
+    let luid = NET_LUID_LH {
+        Value: index_to_luid(if_index)?,
+    };
+    let mut guid = GUID::from_u128(0);
+    check(unsafe { ConvertInterfaceLuidToGuid(&luid, &mut guid) })?;
+    Ok(guid)
+}
+
+fn format_guid(guid: &GUID) -> String {
+    let d = guid.data4;
+    format!(
+        "{{{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}}}",
+        guid.data1, guid.data2, guid.data3, d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]
+    )
+}
+
+fn parse_guid(s: &str) -> Option<GUID> {
+    let s = s
+        .strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
+        .unwrap_or(s);
+    let groups: Vec<&str> = s.split('-').collect();
+    let lens = [8, 4, 4, 4, 12];
+    if groups.len() != lens.len()
+        || groups
+            .iter()
+            .zip(lens)
+            .any(|(g, len)| g.len() != len || !g.bytes().all(|b| b.is_ascii_hexdigit()))
+    {
+        return None;
+    }
+    let hex: String = groups.concat();
+    u128::from_str_radix(&hex, 16).ok().map(GUID::from_u128)
+}
+
+impl Interface {
+    /// Returns the adapter GUID of the network interface,
+    /// formatted as `{XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}`
+    pub fn guid(&self) -> io::Result<String> {
+        luid_to_guid(index_to_luid(self.index)?)
+    }
+    /// Returns the alias of the network interface, as shown by `netsh` and `Get-NetAdapter`
+    pub fn alias(&self) -> io::Result<String> {
+        luid_to_alias(index_to_luid(self.index)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guid_format() {
+        let text = "{4D36E972-E325-11CE-BFC1-08002BE10318}";
+        let guid = parse_guid(text).unwrap();
+        assert_eq!(guid.data1, 0x4D36E972);
+        assert_eq!(guid.data4, [0xBF, 0xC1, 0x08, 0x00, 0x2B, 0xE1, 0x03, 0x18]);
+        assert_eq!(format_guid(&guid), text);
+        assert!(parse_guid("4d36e972-e325-11ce-bfc1-08002be10318").is_some());
+        assert!(parse_guid("{4D36E972-E325-11CE-BFC108002BE10318}").is_none());
+        assert!(parse_guid("{4D36E972-E325-11CE-BFC1-08002BE1031G}").is_none());
+    }
+
+    #[test]
+    fn test_identifier_round_trip() {
+        for iface in crate::interface::get_interfaces() {
+            let luid = index_to_luid(iface.index).unwrap();
+            let guid = luid_to_guid(luid).unwrap();
+            let alias = luid_to_alias(luid).unwrap();
+            println!("{} {:#x} {} {}", iface.index, luid, guid, alias);
+            assert_eq!(luid_to_index(luid).unwrap(), iface.index);
+            assert_eq!(guid_to_luid(&guid).unwrap(), luid);
+            assert_eq!(alias_to_luid(&alias).unwrap(), luid);
+        }
+    }
+}
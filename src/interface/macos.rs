@@ -1,4 +1,4 @@
-use crate::interface::InterfaceType;
+use crate::interface::{DriverInfo, InterfaceType, NetworkService};
 use std::collections::HashMap;
 use system_configuration::network_configuration;
 
@@ -36,17 +36,299 @@ pub fn get_if_type_map() -> HashMap<String, SCInterface> {
         } else {
             continue;
         };
-        let friendly_name: Option<String> = if let Some(name) = interface.display_name() {
-            Some(name.to_string())
-        } else {
-            None
-        };
+        let friendly_name: Option<String> = interface.display_name().map(|name| name.to_string());
         let sc_if = SCInterface {
             name: if_name.clone(),
-            friendly_name: friendly_name,
+            friendly_name,
             interface_type: get_if_type_from_id(type_id),
         };
         map.insert(if_name, sc_if);
     }
-    return map;
+    map
+}
+
+mod iokit {
+    use std::os::raw::c_char;
+    use system_configuration::core_foundation::array::CFArrayRef;
+    use system_configuration::core_foundation::base::{CFAllocatorRef, CFTypeRef};
+    use system_configuration::core_foundation::dictionary::{
+        CFDictionaryRef, CFMutableDictionaryRef,
+    };
+    use system_configuration::core_foundation::string::CFStringRef;
+
+    pub type IoObject = u32;
+    pub const IO_MAIN_PORT_DEFAULT: u32 = 0;
+    pub const IO_SERVICE_PLANE: &[u8] = b"IOService\0";
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        pub fn IOBSDNameMatching(
+            main_port: u32,
+            options: u32,
+            bsd_name: *const c_char,
+        ) -> CFMutableDictionaryRef;
+        pub fn IOServiceGetMatchingService(main_port: u32, matching: CFDictionaryRef) -> IoObject;
+        pub fn IORegistryEntryGetParentEntry(
+            entry: IoObject,
+            plane: *const c_char,
+            parent: *mut IoObject,
+        ) -> i32;
+        pub fn IOObjectGetClass(object: IoObject, class_name: *mut c_char) -> i32;
+        pub fn IORegistryEntryCreateCFProperty(
+            entry: IoObject,
+            key: CFStringRef,
+            allocator: CFAllocatorRef,
+            options: u32,
+        ) -> CFTypeRef;
+        pub fn IOObjectRelease(object: IoObject) -> i32;
+        pub fn KextManagerCopyLoadedKextInfo(
+            kext_identifiers: CFArrayRef,
+            info_keys: CFArrayRef,
+        ) -> CFDictionaryRef;
+    }
+}
+
+fn registry_property(
+    entry: iokit::IoObject,
+    key: &str,
+) -> Option<system_configuration::core_foundation::base::CFType> {
+    use system_configuration::core_foundation::base::{kCFAllocatorDefault, CFType, TCFType};
+    use system_configuration::core_foundation::string::CFString;
+
+    let key = CFString::new(key);
+    let value = unsafe {
+        iokit::IORegistryEntryCreateCFProperty(
+            entry,
+            key.as_concrete_TypeRef(),
+            kCFAllocatorDefault,
+            0,
+        )
+    };
+    if value.is_null() {
+        return None;
+    }
+    Some(unsafe { CFType::wrap_under_create_rule(value) })
+}
+
+fn registry_string(entry: iokit::IoObject, key: &str) -> Option<String> {
+    use system_configuration::core_foundation::string::CFString;
+
+    registry_property(entry, key)?
+        .downcast::<CFString>()
+        .map(|s| s.to_string())
+}
+
+/// Read a property holding either a string or a NUL-terminated byte string,
+/// as the `model` of PCI devices does
+fn registry_text(entry: iokit::IoObject, key: &str) -> Option<String> {
+    use system_configuration::core_foundation::data::CFData;
+    use system_configuration::core_foundation::string::CFString;
+
+    let value = registry_property(entry, key)?;
+    let text = match value.downcast::<CFString>() {
+        Some(s) => s.to_string(),
+        None => {
+            let data = value.downcast::<CFData>()?;
+            let bytes = data.bytes();
+            let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            String::from_utf8_lossy(&bytes[..end]).into_owned()
+        }
+    };
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Look up the IONetworkController behind a BSD interface in the IO registry.
+/// The caller releases the returned object.
+fn get_controller(if_name: &str) -> Option<iokit::IoObject> {
+    use std::ffi::CString;
+    use std::os::raw::c_char;
+
+    let c_name = CString::new(if_name).ok()?;
+    let plane = iokit::IO_SERVICE_PLANE.as_ptr() as *const c_char;
+    unsafe {
+        let matching = iokit::IOBSDNameMatching(iokit::IO_MAIN_PORT_DEFAULT, 0, c_name.as_ptr());
+        if matching.is_null() {
+            return None;
+        }
+        // Consumes the matching dictionary
+        let netif = iokit::IOServiceGetMatchingService(iokit::IO_MAIN_PORT_DEFAULT, matching);
+        if netif == 0 {
+            return None;
+        }
+        let mut controller: iokit::IoObject = 0;
+        let kr = iokit::IORegistryEntryGetParentEntry(netif, plane, &mut controller);
+        iokit::IOObjectRelease(netif);
+        if kr != 0 {
+            return None;
+        }
+        Some(controller)
+    }
+}
+
+/// Product name of the device behind a BSD interface: the USB product string
+/// or PCI model of the closest device above the controller, or the vendor and
+/// model the controller reports
+pub fn get_description(if_name: &str) -> Option<String> {
+    use std::os::raw::c_char;
+
+    let controller = get_controller(if_name)?;
+    let plane = iokit::IO_SERVICE_PLANE.as_ptr() as *const c_char;
+    let own = registry_text(controller, "IOModel").map(|model| {
+        match registry_text(controller, "IOVendor") {
+            Some(vendor) if !model.starts_with(&vendor) => format!("{} {}", vendor, model),
+            _ => model,
+        }
+    });
+    let mut entry = controller;
+    let mut description = None;
+    // Walk up from the controller through the USB interface and device, or the PCI device
+    for _ in 0..4 {
+        let mut parent: iokit::IoObject = 0;
+        let kr = unsafe { iokit::IORegistryEntryGetParentEntry(entry, plane, &mut parent) };
+        unsafe { iokit::IOObjectRelease(entry) };
+        if kr != 0 {
+            return own;
+        }
+        entry = parent;
+        description = ["USB Product Name", "kUSBProductString", "model"]
+            .iter()
+            .find_map(|key| registry_text(entry, key));
+        if description.is_some() {
+            break;
+        }
+    }
+    unsafe { iokit::IOObjectRelease(entry) };
+    description.or(own)
+}
+
+fn kext_version(bundle_id: &str) -> Option<String> {
+    use system_configuration::core_foundation::array::CFArray;
+    use system_configuration::core_foundation::base::{CFType, TCFType};
+    use system_configuration::core_foundation::dictionary::CFDictionary;
+    use system_configuration::core_foundation::string::CFString;
+
+    let id = CFString::new(bundle_id);
+    let ids = CFArray::from_CFTypes(std::slice::from_ref(&id));
+    let info = unsafe {
+        iokit::KextManagerCopyLoadedKextInfo(ids.as_concrete_TypeRef(), std::ptr::null())
+    };
+    if info.is_null() {
+        return None;
+    }
+    let info: CFDictionary<CFString, CFType> =
+        unsafe { CFDictionary::wrap_under_create_rule(info) };
+    let kext = info.find(&id)?.downcast::<CFDictionary>()?;
+    let version_key = CFString::from_static_string("CFBundleVersion");
+    let version = kext.find(version_key.as_CFTypeRef())?;
+    let version = unsafe { CFType::wrap_under_get_rule(*version) };
+    version.downcast::<CFString>().map(|s| s.to_string())
+}
+
+/// Look up the IONetworkController behind a BSD interface in the IO registry
+pub fn get_driver_info(if_name: &str) -> Option<DriverInfo> {
+    use std::ffi::CStr;
+    use std::os::raw::c_char;
+
+    let controller = get_controller(if_name)?;
+    let plane = iokit::IO_SERVICE_PLANE.as_ptr() as *const c_char;
+    unsafe {
+        let mut class_name = [0 as c_char; 128];
+        let name = if iokit::IOObjectGetClass(controller, class_name.as_mut_ptr()) == 0 {
+            CStr::from_ptr(class_name.as_ptr())
+                .to_string_lossy()
+                .into_owned()
+        } else {
+            String::new()
+        };
+        let version = registry_string(controller, "CFBundleIdentifier")
+            .and_then(|bundle_id| kext_version(&bundle_id));
+        let mut device: iokit::IoObject = 0;
+        let bus_info = if iokit::IORegistryEntryGetParentEntry(controller, plane, &mut device) == 0
+        {
+            let location = registry_string(device, "pcidebug");
+            iokit::IOObjectRelease(device);
+            location
+        } else {
+            None
+        };
+        iokit::IOObjectRelease(controller);
+        if name.is_empty() {
+            return None;
+        }
+        Some(DriverInfo {
+            name,
+            version,
+            firmware_version: None,
+            bus_info,
+        })
+    }
+}
+
+mod store {
+    use system_configuration::core_foundation::array::CFArray;
+    use system_configuration::core_foundation::base::{CFType, TCFType};
+    use system_configuration::core_foundation::dictionary::CFDictionary;
+    use system_configuration::core_foundation::string::CFString;
+    use system_configuration::dynamic_store::SCDynamicStore;
+
+    /// Value of `key` in the dictionary stored at `path` in the dynamic store
+    fn value(store: &SCDynamicStore, path: &str, key: &'static str) -> Option<CFType> {
+        let dict = store.get(path)?.downcast_into::<CFDictionary>()?;
+        let key = CFString::from_static_string(key);
+        let value = dict.find(key.as_CFTypeRef())?;
+        Some(unsafe { CFType::wrap_under_get_rule(*value) })
+    }
+
+    pub fn string(store: &SCDynamicStore, path: &str, key: &'static str) -> Option<String> {
+        value(store, path, key)?
+            .downcast::<CFString>()
+            .map(|s| s.to_string())
+    }
+
+    pub fn strings(store: &SCDynamicStore, path: &str, key: &'static str) -> Vec<String> {
+        let Some(array) = value(store, path, key).and_then(|v| v.downcast::<CFArray>()) else {
+            return Vec::new();
+        };
+        array
+            .iter()
+            .filter_map(|item| unsafe { CFType::wrap_under_get_rule(*item) }.downcast::<CFString>())
+            .map(|s| s.to_string())
+            .collect()
+    }
+}
+
+const SETUP_SERVICE_PREFIX: &str = "Setup:/Network/Service/";
+
+/// Read the network services from the SystemConfiguration dynamic store
+pub fn get_network_services() -> std::io::Result<Vec<NetworkService>> {
+    use system_configuration::dynamic_store::SCDynamicStoreBuilder;
+
+    let store = SCDynamicStoreBuilder::new("netdev").build();
+    let order = store::strings(&store, "Setup:/Network/Global/IPv4", "ServiceOrder");
+    let primary = store::string(&store, "State:/Network/Global/IPv4", "PrimaryService");
+    let keys = store
+        .get_keys(format!("{}[^/]+$", SETUP_SERVICE_PREFIX).as_str())
+        .ok_or_else(|| {
+            std::io::Error::other("SystemConfiguration dynamic store is not available")
+        })?;
+    let mut services: Vec<NetworkService> = keys
+        .iter()
+        .filter_map(|key| {
+            let key = key.to_string();
+            let id = key.strip_prefix(SETUP_SERVICE_PREFIX)?.to_string();
+            let interface_key = format!("{}/Interface", key);
+            Some(NetworkService {
+                name: store::string(&store, &key, "UserDefinedName")
+                    .or_else(|| store::string(&store, &interface_key, "UserDefinedName"))
+                    .unwrap_or_default(),
+                if_name: store::string(&store, &interface_key, "DeviceName"),
+                order: order.iter().position(|ordered| *ordered == id),
+                primary: primary.as_deref() == Some(id.as_str()),
+                id,
+            })
+        })
+        .collect();
+    services.sort_by_key(|service| service.order.unwrap_or(usize::MAX));
+    Ok(services)
 }
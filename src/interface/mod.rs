@@ -1,9 +1,84 @@
 mod shared;
 pub use self::shared::*;
 
+mod name;
+pub use self::name::*;
+
 mod types;
 pub use self::types::*;
 
+mod options;
+pub use self::options::*;
+
+mod builder;
+pub use self::builder::*;
+
+mod offload;
+pub use self::offload::*;
+
+mod timestamping;
+pub use self::timestamping::*;
+
+mod sriov;
+pub use self::sriov::*;
+
+mod tunnel;
+pub use self::tunnel::*;
+
+mod network_manager;
+pub use self::network_manager::*;
+
+mod networkd;
+pub use self::networkd::*;
+
+mod qdisc;
+pub use self::qdisc::*;
+
+mod health;
+pub use self::health::*;
+
+mod prefix;
+pub use self::prefix::*;
+
+mod hosts;
+pub use self::hosts::*;
+
+mod service;
+pub use self::service::*;
+
+mod path;
+pub use self::path::*;
+
+mod availability;
+pub use self::availability::*;
+
+mod address;
+pub use self::address::*;
+
+mod cancel;
+pub use self::cancel::*;
+
+mod enumerator;
+pub use self::enumerator::*;
+
+mod snapshot;
+pub use self::snapshot::*;
+
+mod driver;
+pub use self::driver::*;
+
+mod device_group;
+pub use self::device_group::*;
+
+mod rank;
+pub use self::rank::*;
+
+mod tether;
+pub use self::tether::*;
+
+mod hypervisor;
+pub use self::hypervisor::*;
+
 #[cfg(any(
     target_os = "linux",
     target_os = "macos",
@@ -30,27 +105,54 @@ mod windows;
 #[cfg(target_os = "windows")]
 use self::windows::*;
 
+#[cfg(target_os = "windows")]
+mod luid;
+#[cfg(target_os = "windows")]
+pub use self::luid::*;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+mod enrich;
+
 #[cfg(any(target_os = "linux", target_os = "android"))]
 mod linux;
 
 #[cfg(target_os = "android")]
 mod android;
 
-#[cfg(any(target_os = "macos", target_os = "ios"))]
+#[cfg(target_os = "macos")]
 mod macos;
 
+#[cfg(target_os = "ios")]
+mod ios;
+
 use crate::device::NetworkDevice;
+use crate::error::Error;
 use crate::ip::{Ipv4Net, Ipv6Net};
-use crate::mac::MacAddr;
+use crate::mac::{HardwareAddr, MacAddr};
+use crate::stats::{self, InterfaceStats};
 use crate::sys;
-use std::net::IpAddr;
+use crate::wol::WolModes;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, UdpSocket};
+use std::time::{Duration, SystemTime};
 
 /// Structure of Network Interface information
-#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+///
+/// New fields may be added in minor releases. Outside this crate, build values
+/// with [`Interface::builder`] and read them through the fields or the accessor
+/// methods.
+///
+/// Equality and hashing are by identity: two values are equal if they describe
+/// the same interface, with the same namespace, index, name and MAC address,
+/// even if its addresses, flags or counters differ. Use
+/// [`Interface::content_eq`] to compare every field.
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 pub struct Interface {
     /// Index of network interface
     pub index: u32,
@@ -58,12 +160,18 @@ pub struct Interface {
     pub name: String,
     /// Friendly Name of network interface
     pub friendly_name: Option<String>,
-    /// Description of the network interface
+    /// Description of the network interface, such as the adapter model
+    /// `Intel Corporation Ethernet Connection (2) I219-LM`. On Linux it comes
+    /// from the udev hardware database or the PCI and USB ID databases.
     pub description: Option<String>,
     /// Interface Type
     pub if_type: InterfaceType,
     /// MAC address of network interface
     pub mac_addr: Option<MacAddr>,
+    /// Link-layer address of any length. Holds the same address as `mac_addr`
+    /// when that is set, and is the only address of interfaces with longer ones,
+    /// such as InfiniBand.
+    pub hardware_addr: Option<HardwareAddr>,
     /// List of Ipv4Net for the network interface
     pub ipv4: Vec<Ipv4Net>,
     /// List of Ipv6Net for the network interface
@@ -74,18 +182,67 @@ pub struct Interface {
     pub transmit_speed: Option<u64>,
     /// Speed in bits per second of the receive for the network interface
     pub receive_speed: Option<u64>,
-    /// Default gateway for the network interface
-    pub gateway: Option<NetworkDevice>,
+    /// Default gateways for the network interface, one per router, most preferred first.
+    /// A router's MAC address is zero if it is not in the neighbour cache.
+    pub gateways: Vec<NetworkDevice>,
     /// DNS servers for the network interface
     pub dns_servers: Vec<IpAddr>,
     /// is default interface
     pub default: bool,
+    /// Traffic counters, as of enumeration or the last `update_stats()`
+    pub stats: Option<InterfaceStats>,
+    /// Driver and firmware information
+    pub driver: Option<DriverInfo>,
+    /// Location of the physical device on its bus, such as
+    /// `pci0000:00/0000:00:1c.0/0000:03:00.1` on Linux (relative to `/sys/devices`)
+    /// or `PCI bus 3, device 0, function 1` on Windows. Stacked interfaces like
+    /// VLANs report the device of their lower interface. `None` for virtual
+    /// interfaces, and on other platforms.
+    pub device_path: Option<String>,
+    /// Number of times the link went up or down since the interface was created (Linux only)
+    pub carrier_changes: Option<u32>,
+    /// When the link entered its current up state. `None` while the link is down
+    /// or if the platform does not report it (Linux and Windows only). Linux
+    /// only knows it for links this process has seen come up.
+    pub link_up_since: Option<SystemTime>,
+    /// NetworkManager's view of the interface, with the `networkmanager` feature
+    /// on Linux. `None` if NetworkManager is not running or does not know it.
+    pub network_manager: Option<NetworkManagerInfo>,
+    /// systemd-networkd's view of the interface, with the `networkd` feature
+    /// on Linux. `None` if networkd is not running.
+    pub networkd: Option<NetworkdInfo>,
+    /// Inode number of the network namespace the interface was enumerated in,
+    /// as listed by `ls -iL /proc/<pid>/ns/net` (Linux only). Names and indexes
+    /// are only unique within a namespace, so interfaces gathered from several
+    /// namespaces are told apart by `(netns, index)`.
+    pub netns: Option<u64>,
+}
+
+impl PartialEq for Interface {
+    fn eq(&self, other: &Interface) -> bool {
+        self.netns == other.netns
+            && self.index == other.index
+            && self.name == other.name
+            && self.mac_addr == other.mac_addr
+    }
+}
+
+impl Eq for Interface {}
+
+impl Hash for Interface {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.netns.hash(state);
+        self.index.hash(state);
+        self.name.hash(state);
+        self.mac_addr.hash(state);
+    }
 }
 
 impl Interface {
     /// Construct a new default Interface instance
+    #[allow(clippy::should_implement_trait)]
     pub fn default() -> Result<Interface, String> {
-        let interfaces: Vec<Interface> = interfaces();
+        let interfaces: Vec<Interface> = enumerate(&EnumOptions::default());
         for iface in &interfaces {
             if iface.default {
                 return Ok(iface.clone());
@@ -111,6 +268,11 @@ impl Interface {
         }
         Err(String::from("Default Interface not found"))
     }
+    /// Start building an interface with index 0 and an empty name, see
+    /// [`InterfaceBuilder`]
+    pub fn builder() -> InterfaceBuilder {
+        InterfaceBuilder::new(0, "")
+    }
     // Construct a dummy Interface instance
     pub fn dummy() -> Interface {
         Interface {
@@ -120,33 +282,229 @@ impl Interface {
             description: None,
             if_type: InterfaceType::Unknown,
             mac_addr: None,
+            hardware_addr: None,
             ipv4: Vec::new(),
             ipv6: Vec::new(),
             flags: 0,
             transmit_speed: None,
             receive_speed: None,
-            gateway: None,
+            gateways: Vec::new(),
             dns_servers: Vec::new(),
             default: false,
+            stats: None,
+            driver: None,
+            device_path: None,
+            carrier_changes: None,
+            link_up_since: None,
+            network_manager: None,
+            networkd: None,
+            netns: None,
+        }
+    }
+    /// Check if every field of the two interfaces is equal, unlike `==`
+    /// which only compares their identity
+    pub fn content_eq(&self, other: &Interface) -> bool {
+        // Destructured so that new fields cannot be forgotten here
+        let Interface {
+            index,
+            name,
+            friendly_name,
+            description,
+            if_type,
+            mac_addr,
+            hardware_addr,
+            ipv4,
+            ipv6,
+            flags,
+            transmit_speed,
+            receive_speed,
+            gateways,
+            dns_servers,
+            default,
+            stats,
+            driver,
+            device_path,
+            carrier_changes,
+            link_up_since,
+            network_manager,
+            networkd,
+            netns,
+        } = self;
+        *index == other.index
+            && *name == other.name
+            && *friendly_name == other.friendly_name
+            && *description == other.description
+            && *if_type == other.if_type
+            && *mac_addr == other.mac_addr
+            && *hardware_addr == other.hardware_addr
+            && *ipv4 == other.ipv4
+            && *ipv6 == other.ipv6
+            && *flags == other.flags
+            && *transmit_speed == other.transmit_speed
+            && *receive_speed == other.receive_speed
+            && *gateways == other.gateways
+            && *dns_servers == other.dns_servers
+            && *default == other.default
+            && *stats == other.stats
+            && *driver == other.driver
+            && *device_path == other.device_path
+            && *carrier_changes == other.carrier_changes
+            && *link_up_since == other.link_up_since
+            && *network_manager == other.network_manager
+            && *networkd == other.networkd
+            && *netns == other.netns
+    }
+    /// Refresh the traffic counters of the interface
+    pub fn update_stats(&mut self) -> io::Result<()> {
+        match stats::get_interface_stats(self) {
+            Some(stats) => {
+                self.stats = Some(stats);
+                Ok(())
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("traffic counters of {} are not available", self.name),
+            )),
         }
     }
+    /// Refresh the traffic counters of the interface, including the per-queue and
+    /// driver-specific counters where the platform reports them
+    pub fn update_detailed_stats(&mut self) -> io::Result<()> {
+        match stats::get_detailed_stats(self) {
+            Some(stats) => {
+                self.stats = Some(stats);
+                Ok(())
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("traffic counters of {} are not available", self.name),
+            )),
+        }
+    }
+    /// Returns the index of the interface
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// Returns the name of the interface
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Returns the friendly name of the interface
+    pub fn friendly_name(&self) -> Option<&str> {
+        self.friendly_name.as_deref()
+    }
+    /// Returns the description of the interface
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+    /// Returns the type of the interface
+    pub fn if_type(&self) -> InterfaceType {
+        self.if_type
+    }
+    /// Returns the MAC address of the interface
+    pub fn mac_addr(&self) -> Option<MacAddr> {
+        self.mac_addr
+    }
+    /// Returns the link-layer address of the interface
+    pub fn hardware_addr(&self) -> Option<&HardwareAddr> {
+        self.hardware_addr.as_ref()
+    }
+    /// Returns the IPv4 addresses of the interface
+    pub fn ipv4(&self) -> &[Ipv4Net] {
+        &self.ipv4
+    }
+    /// Returns the IPv6 addresses of the interface
+    pub fn ipv6(&self) -> &[Ipv6Net] {
+        &self.ipv6
+    }
+    /// Returns the OS-specific flags of the interface
+    pub fn flags(&self) -> u32 {
+        self.flags
+    }
+    /// Returns the transmit speed in bits per second
+    pub fn transmit_speed(&self) -> Option<u64> {
+        self.transmit_speed
+    }
+    /// Returns the receive speed in bits per second
+    pub fn receive_speed(&self) -> Option<u64> {
+        self.receive_speed
+    }
+    /// Returns the default gateways of the interface
+    pub fn gateways(&self) -> &[NetworkDevice] {
+        &self.gateways
+    }
+    /// Returns the DNS servers of the interface
+    pub fn dns_servers(&self) -> &[IpAddr] {
+        &self.dns_servers
+    }
+    /// Check if the interface is the default interface
+    pub fn is_default(&self) -> bool {
+        self.default
+    }
+    /// Returns the traffic counters, as of enumeration or the last `update_stats()`
+    pub fn stats(&self) -> Option<&InterfaceStats> {
+        self.stats.as_ref()
+    }
+    /// Returns the driver and firmware information
+    pub fn driver(&self) -> Option<&DriverInfo> {
+        self.driver.as_ref()
+    }
+    /// Returns the location of the physical device on its bus
+    pub fn device_path(&self) -> Option<&str> {
+        self.device_path.as_deref()
+    }
+    /// Returns the number of times the link went up or down (Linux only)
+    pub fn carrier_changes(&self) -> Option<u32> {
+        self.carrier_changes
+    }
+    /// Returns when the link entered its current up state (Linux and Windows only)
+    pub fn link_up_since(&self) -> Option<SystemTime> {
+        self.link_up_since
+    }
+    /// Returns NetworkManager's view of the interface
+    pub fn network_manager(&self) -> Option<&NetworkManagerInfo> {
+        self.network_manager.as_ref()
+    }
+    /// Returns systemd-networkd's view of the interface
+    pub fn networkd(&self) -> Option<&NetworkdInfo> {
+        self.networkd.as_ref()
+    }
+    /// Returns the inode number of the network namespace of the interface
+    pub fn netns(&self) -> Option<u64> {
+        self.netns
+    }
+    /// Returns the OS interface index (`if_nametoindex` on Unix, `IfIndex` on Windows)
+    pub fn os_index(&self) -> u32 {
+        self.index
+    }
+    /// Returns the LUID (Locally Unique Identifier) of the network interface
+    #[cfg(target_os = "windows")]
+    pub fn luid(&self) -> Option<u64> {
+        get_interface_luid(self.index)
+    }
     /// Check if the network interface is up
+    // The flag constants are c_int on Unix and u32 on Windows
+    #[cfg_attr(target_os = "windows", allow(clippy::unnecessary_cast))]
     pub fn is_up(&self) -> bool {
         self.flags & (sys::IFF_UP as u32) != 0
     }
     /// Check if the network interface is a Loopback interface
+    #[cfg_attr(target_os = "windows", allow(clippy::unnecessary_cast))]
     pub fn is_loopback(&self) -> bool {
         self.flags & (sys::IFF_LOOPBACK as u32) != 0
     }
     /// Check if the network interface is a Point-to-Point interface
+    #[cfg_attr(target_os = "windows", allow(clippy::unnecessary_cast))]
     pub fn is_point_to_point(&self) -> bool {
         self.flags & (sys::IFF_POINTOPOINT as u32) != 0
     }
     /// Check if the network interface is a Multicast interface
+    #[cfg_attr(target_os = "windows", allow(clippy::unnecessary_cast))]
     pub fn is_multicast(&self) -> bool {
         self.flags & (sys::IFF_MULTICAST as u32) != 0
     }
     /// Check if the network interface is a Broadcast interface
+    #[cfg_attr(target_os = "windows", allow(clippy::unnecessary_cast))]
     pub fn is_broadcast(&self) -> bool {
         self.flags & (sys::IFF_BROADCAST as u32) != 0
     }
@@ -156,19 +514,203 @@ impl Interface {
     }
     /// Check if the network interface is running and ready to send/receive packets
     pub fn is_running(&self) -> bool {
-        is_running(&self)
+        is_running(self)
+    }
+    /// Check if the network interface is in promiscuous mode.
+    ///
+    /// Unlike the other checks, this queries the current state from the OS.
+    pub fn is_promiscuous(&self) -> io::Result<bool> {
+        get_promiscuous_mode(self)
     }
-    /// Check if the network interface is a physical interface
+    /// Enable or disable promiscuous mode (requires elevated privileges)
+    #[cfg(feature = "config")]
+    pub fn set_promiscuous(&self, enabled: bool) -> io::Result<()> {
+        set_promiscuous_mode(self, enabled)
+    }
+    /// Get the offload features currently enabled on the network interface
+    pub fn offload_info(&self) -> io::Result<OffloadInfo> {
+        get_offload_info(self)
+    }
+    /// Get the packet timestamping capabilities of the network interface
+    pub fn timestamping_caps(&self) -> io::Result<TimestampingCaps> {
+        get_timestamping_caps(self)
+    }
+    /// Get the SR-IOV role of the network interface and, for a physical function,
+    /// its virtual functions. Returns `None` if the interface is not part of SR-IOV.
+    pub fn sriov_info(&self) -> io::Result<Option<SriovInfo>> {
+        get_sriov_info(self)
+    }
+    /// Get the underlay endpoints and identifiers of a GRE, IP-in-IP, VXLAN or
+    /// Geneve interface. Returns `None` if the interface is not a tunnel.
+    pub fn tunnel_info(&self) -> io::Result<Option<TunnelInfo>> {
+        get_tunnel_info(self)
+    }
+    /// Get the queueing disciplines attached to the network interface, root first
+    pub fn qdiscs(&self) -> io::Result<Vec<Qdisc>> {
+        get_qdiscs(self)
+    }
+    /// Get the Wake-on-LAN modes the network interface supports and has enabled
+    pub fn wol_modes(&self) -> io::Result<WolModes> {
+        get_wol_modes(self)
+    }
+    /// Query the current state of every address assigned to the interface,
+    /// including whether IPv6 addresses passed Duplicate Address Detection
+    pub fn address_info(&self) -> io::Result<Vec<AddressInfo>> {
+        get_address_info(self)
+    }
+    /// Get the IPv6 temporary addresses (RFC 8981) currently assigned to the
+    /// interface, apart from its stable addresses. See [`AddressInfo::temporary`].
+    pub fn temporary_addresses(&self) -> io::Result<Vec<Ipv6Addr>> {
+        let addrs = get_address_info(self)?
+            .into_iter()
+            .filter(|info| info.temporary)
+            .filter_map(|info| match info.addr {
+                IpAddr::V6(addr) => Some(addr),
+                IpAddr::V4(_) => None,
+            })
+            .collect();
+        Ok(addrs)
+    }
+    /// Returns how long the link has been up, if known
+    pub fn link_uptime(&self) -> Option<Duration> {
+        self.link_up_since.and_then(|since| since.elapsed().ok())
+    }
+    /// Get the prefixes that are on-link for the interface, such as those announced
+    /// in router advertisements, separately from the addresses assigned to it.
+    /// An address within one of them is reachable without a router even if the
+    /// interface has no address in that prefix, e.g. with DHCPv6-assigned /128s.
+    pub fn on_link_prefixes(&self) -> io::Result<Vec<OnLinkPrefix>> {
+        get_on_link_prefixes(self)
+    }
+    /// Check whether another host on the link already uses `addr` by sending
+    /// RFC 5227 ARP probes (requires elevated privileges).
+    ///
+    /// Returns the MAC address of the conflicting host, or `None` if no host
+    /// answered within the probe period.
+    #[cfg(feature = "config")]
+    pub fn probe_ipv4_conflict(&self, addr: Ipv4Addr) -> io::Result<Option<MacAddr>> {
+        probe_ipv4_conflict(self, addr)
+    }
+    /// Restart Duplicate Address Detection for an assigned IPv6 address
+    /// (requires elevated privileges). Poll [`Interface::address_info`] for the outcome.
+    ///
+    /// On Linux the address is removed and added back, as the kernel cannot
+    /// restart DAD in place. Its prefix route goes away meanwhile, sockets
+    /// bound to it are closed, and an address configured by SLAAC comes back
+    /// as a static one. If adding it back fails the original is restored.
+    #[cfg(feature = "config")]
+    pub fn restart_dad(&self, addr: Ipv6Addr) -> io::Result<()> {
+        restart_dad(self, addr)
+    }
+    /// Join the IPv4 multicast `group` on `socket`, receiving through this interface
+    pub fn join_multicast_v4(&self, socket: &UdpSocket, group: Ipv4Addr) -> io::Result<()> {
+        multicast_membership_v4(self, socket, group, true)
+    }
+    /// Leave an IPv4 multicast group joined with [`Interface::join_multicast_v4`]
+    pub fn leave_multicast_v4(&self, socket: &UdpSocket, group: Ipv4Addr) -> io::Result<()> {
+        multicast_membership_v4(self, socket, group, false)
+    }
+    /// Send IPv4 multicast from `socket` through this interface (`IP_MULTICAST_IF`)
+    pub fn set_multicast_if_v4(&self, socket: &UdpSocket) -> io::Result<()> {
+        set_multicast_if_v4(self, socket)
+    }
+    /// Join the IPv6 multicast `group` on `socket`, receiving through this interface
+    pub fn join_multicast_v6(&self, socket: &UdpSocket, group: Ipv6Addr) -> io::Result<()> {
+        socket.join_multicast_v6(&group, self.index)
+    }
+    /// Leave an IPv6 multicast group joined with [`Interface::join_multicast_v6`]
+    pub fn leave_multicast_v6(&self, socket: &UdpSocket, group: Ipv6Addr) -> io::Result<()> {
+        socket.leave_multicast_v6(&group, self.index)
+    }
+    /// Check if the network interface is a physical interface.
+    ///
+    /// The adapters a hypervisor adds to its host, such as Hyper-V vEthernet
+    /// switches or VirtualBox host-only networks, are not. The emulated
+    /// adapters of a virtual machine are physical from inside the guest, as
+    /// they are its uplink; [`Interface::hypervisor_kind`] tells them apart.
     pub fn is_physical(&self) -> bool {
-        is_physical_interface(&self)
-            && !crate::db::oui::is_virtual_mac(&self.mac_addr.unwrap_or(MacAddr::zero()))
+        is_physical_interface(self)
+            && !hypervisor::is_host_adapter(self)
             && !crate::db::oui::is_known_loopback_mac(&self.mac_addr.unwrap_or(MacAddr::zero()))
     }
+    /// Recognize the adapters of Hyper-V, WSL, VMware, VirtualBox, Parallels,
+    /// KVM and Xen from the interface names, the driver and the description, or
+    /// else from the vendor of the MAC address
+    pub fn hypervisor_kind(&self) -> Option<HypervisorKind> {
+        hypervisor::classify(self)
+    }
+    /// Check if the interface is Thunderbolt or USB4 networking, which links
+    /// two machines directly rather than to a network
+    pub fn is_thunderbolt(&self) -> bool {
+        hypervisor::is_thunderbolt(self)
+    }
+    /// Returns the most preferred network service configured on the network
+    /// interface, see [`network_services`]
+    pub fn network_service(&self) -> io::Result<Option<NetworkService>> {
+        Ok(network_services()?
+            .into_iter()
+            .find(|service| service.if_name.as_deref() == Some(self.name.as_str())))
+    }
+    /// Iterate over the addresses of the other hosts that may be on the IPv4
+    /// subnets of the network interface, such as for a LAN scan.
+    ///
+    /// The network and broadcast addresses and the interface's own addresses
+    /// are skipped, as are subnets covered by another one. A /31 yields the
+    /// peer address, a /32 nothing.
+    pub fn hosts_v4(&self) -> InterfaceHostsV4 {
+        InterfaceHostsV4::new(&self.ipv4)
+    }
+    /// Check if all IPv4 addresses of the network interface are self-assigned
+    /// link-local (APIPA, `169.254.0.0/16`) addresses.
+    /// Returns false if the interface has no IPv4 address.
+    pub fn has_only_link_local_v4(&self) -> bool {
+        !self.ipv4.is_empty() && self.ipv4.iter().all(|net| net.addr.is_link_local())
+    }
+    /// Recognize Bluetooth PAN and USB tethering to a phone, which downstream
+    /// applications may want to treat as metered.
+    ///
+    /// Relies on the driver and the description, which are only filled in with
+    /// [`DetailLevel::Full`], except for Bluetooth PAN on Linux, which is
+    /// recognized by its `bnep` interface name.
+    pub fn tether_kind(&self) -> Option<TetherKind> {
+        tether::classify(
+            &self.name,
+            self.driver.as_ref().map(|driver| driver.name.as_str()),
+            self.description.as_deref(),
+        )
+    }
+    /// Check if the interface is a tethered link, see [`Interface::tether_kind`]
+    pub fn is_tethered(&self) -> bool {
+        self.tether_kind().is_some()
+    }
+    /// Summarize the state of the network interface from its flags, addresses
+    /// and gateways. Loopback interfaces need no gateway to be `Ok`.
+    pub fn health(&self) -> InterfaceHealth {
+        if !self.is_up() || !self.is_running() {
+            return InterfaceHealth::Down;
+        }
+        let routable = self.ipv4.iter().any(|net| !net.addr.is_link_local())
+            || self
+                .ipv6
+                .iter()
+                .any(|net| !crate::zone::is_link_local(&net.addr));
+        if !routable {
+            return if self.has_only_link_local_v4() {
+                InterfaceHealth::LinkLocalOnly
+            } else {
+                InterfaceHealth::NoAddress
+            };
+        }
+        if self.gateways.is_empty() && !self.is_loopback() {
+            return InterfaceHealth::NoGateway;
+        }
+        InterfaceHealth::Ok
+    }
 }
 
 /// Get default Network Interface
 pub fn get_default_interface() -> Result<Interface, String> {
-    let interfaces: Vec<Interface> = interfaces();
+    let interfaces: Vec<Interface> = enumerate(&EnumOptions::default());
     for iface in &interfaces {
         if iface.default {
             return Ok(iface.clone());
@@ -197,7 +739,76 @@ pub fn get_default_interface() -> Result<Interface, String> {
 
 /// Get a list of available Network Interfaces
 pub fn get_interfaces() -> Vec<Interface> {
-    interfaces()
+    enumerate(&EnumOptions::default())
+}
+
+/// Get a list of available Network Interfaces with the given enumeration options
+pub fn get_interfaces_with_options(options: &EnumOptions) -> Vec<Interface> {
+    enumerate(options)
+}
+
+/// Get a list of available Network Interfaces, collecting only addresses of the given family
+///
+/// The backends skip addresses, gateways and DNS servers of other families while walking
+/// the OS structures, which is cheaper than filtering the result of [`get_interfaces`].
+pub fn interfaces_with(family: AddressFamily) -> Vec<Interface> {
+    let options = EnumOptions {
+        address_families: family,
+        ..EnumOptions::default()
+    };
+    enumerate(&options)
+}
+
+/// Get a list of available Network Interfaces with the given enumeration options,
+/// failing with the OS call that went wrong instead of returning an empty list.
+///
+/// Only the interface list itself is an error; details that cannot be read
+/// for an interface are left empty as with [`get_interfaces_with_options`].
+pub fn try_get_interfaces_with_options(options: &EnumOptions) -> Result<Vec<Interface>, Error> {
+    #[cfg(feature = "mock")]
+    if let Some(provider) = crate::mock::current() {
+        return Ok(provider.interfaces(options));
+    }
+    if let Some(backend) = crate::backend::installed() {
+        return Ok(backend.interfaces(options));
+    }
+    try_interfaces(options)
+}
+
+/// Enumerate through the mock provider installed on this thread, if any,
+/// the installed [`Backend`](crate::backend::Backend), or the OS
+pub(crate) fn enumerate(options: &EnumOptions) -> Vec<Interface> {
+    try_get_interfaces_with_options(options).unwrap_or_default()
+}
+
+/// Enumerate with the platform backend
+pub(crate) fn interfaces(options: &EnumOptions) -> Vec<Interface> {
+    try_interfaces(options).unwrap_or_default()
+}
+
+/// Same as [`interfaces`], reusing `mem` as the working buffer
+#[cfg(target_os = "windows")]
+pub(crate) fn interfaces_into(options: &EnumOptions, mem: &mut Vec<u8>) -> Vec<Interface> {
+    try_interfaces_into(options, mem).unwrap_or_default()
+}
+
+/// Enumerate with the platform backend
+pub(crate) fn os_interfaces(options: &EnumOptions) -> Vec<Interface> {
+    interfaces(options)
+}
+
+/// Enumerate with `getifaddrs(3)` alone
+#[cfg(any(
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "ios",
+    target_os = "android"
+))]
+pub(crate) fn getifaddrs_interfaces(options: &EnumOptions) -> Vec<Interface> {
+    unix_interfaces(options).unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -211,6 +822,188 @@ mod tests {
         }
     }
     #[test]
+    fn test_identity() {
+        use std::collections::HashSet;
+        let eth0 = Interface::builder()
+            .index(2)
+            .name("eth0")
+            .mac_addr(MacAddr::new(0x02, 0, 0, 0, 0, 1))
+            .build();
+        let mut renumbered = eth0.clone();
+        renumbered
+            .ipv4
+            .push(Ipv4Net::new(Ipv4Addr::new(10, 0, 0, 2), 24));
+        assert_eq!(eth0, renumbered);
+        assert!(!eth0.content_eq(&renumbered));
+        assert!(eth0.content_eq(&eth0.clone()));
+        let mut renamed = eth0.clone();
+        renamed.name = String::from("eth1");
+        assert_ne!(eth0, renamed);
+        let set: HashSet<Interface> = [eth0, renumbered, renamed].into_iter().collect();
+        assert_eq!(set.len(), 2);
+    }
+    #[test]
+    fn test_try_get_interfaces() {
+        let interfaces = try_get_interfaces_with_options(&EnumOptions::default()).unwrap();
+        assert_eq!(interfaces.len(), get_interfaces().len());
+    }
+    #[test]
+    fn test_interfaces_with_options() {
+        let options = EnumOptions {
+            include_loopback: false,
+            address_families: AddressFamily::V4,
+            ..EnumOptions::default()
+        };
+        let interfaces = get_interfaces_with_options(&options);
+        for interface in interfaces {
+            assert!(!interface.is_loopback());
+            assert!(interface.ipv6.is_empty());
+        }
+    }
+    #[test]
+    fn test_detail_level() {
+        let options = EnumOptions {
+            detail_level: DetailLevel::Basic,
+            ..EnumOptions::default()
+        };
+        for interface in get_interfaces_with_options(&options) {
+            assert!(interface.gateways.is_empty() && interface.dns_servers.is_empty());
+            assert!(interface.driver.is_none());
+        }
+    }
+    #[test]
+    fn test_atomic_stats() {
+        let options = EnumOptions {
+            detail_level: DetailLevel::Basic,
+            ..EnumOptions::default()
+        };
+        let first = snapshot(&options);
+        #[cfg(target_os = "linux")]
+        assert!(first.interfaces.iter().all(|iface| iface.stats.is_some()));
+        let second = snapshot(&EnumOptions::default());
+        assert!(second.taken_at >= first.taken_at);
+        for interface in &second.interfaces {
+            println!("{}: {:?}", interface.name, interface.stats);
+        }
+    }
+    #[test]
+    fn test_interfaces_with_family() {
+        for interface in interfaces_with(AddressFamily::V6) {
+            assert!(interface.ipv4.is_empty());
+            assert!(interface.dns_servers.iter().all(|ip| ip.is_ipv6()));
+        }
+    }
+    #[test]
+    fn test_promiscuous() {
+        for interface in get_interfaces() {
+            println!("{}: {:?}", interface.name, interface.is_promiscuous());
+        }
+    }
+    #[test]
+    fn test_offload_info() {
+        for interface in get_interfaces() {
+            println!("{}: {:?}", interface.name, interface.offload_info());
+        }
+    }
+    #[test]
+    fn test_timestamping_caps() {
+        for interface in get_interfaces() {
+            println!("{}: {:?}", interface.name, interface.timestamping_caps());
+        }
+    }
+    #[test]
+    fn test_link_changes() {
+        for interface in get_interfaces() {
+            println!(
+                "{}: {:?} carrier changes, up for {:?}",
+                interface.name,
+                interface.carrier_changes,
+                interface.link_uptime()
+            );
+        }
+    }
+    #[test]
+    fn test_on_link_prefixes() {
+        for interface in get_interfaces() {
+            println!("{}: {:?}", interface.name, interface.on_link_prefixes());
+        }
+    }
+    #[test]
+    fn test_sriov_info() {
+        for interface in get_interfaces() {
+            println!("{}: {:?}", interface.name, interface.sriov_info());
+        }
+    }
+
+    #[test]
+    fn test_tunnel_info() {
+        for interface in get_interfaces() {
+            println!("{}: {:?}", interface.name, interface.tunnel_info());
+        }
+    }
+
+    #[test]
+    fn test_health() {
+        let mut interface = Interface::builder().up().build();
+        assert_eq!(interface.health(), InterfaceHealth::NoAddress);
+        interface
+            .ipv6
+            .push(Ipv6Net::new(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1), 64));
+        assert_eq!(interface.health(), InterfaceHealth::NoAddress);
+        interface
+            .ipv4
+            .push(Ipv4Net::new(Ipv4Addr::new(169, 254, 10, 20), 16));
+        assert!(interface.has_only_link_local_v4());
+        assert_eq!(interface.health(), InterfaceHealth::LinkLocalOnly);
+        interface
+            .ipv4
+            .push(Ipv4Net::new(Ipv4Addr::new(192, 168, 1, 20), 24));
+        assert!(!interface.has_only_link_local_v4());
+        assert_eq!(interface.health(), InterfaceHealth::NoGateway);
+        interface.gateways.push(NetworkDevice::new());
+        assert!(interface.health().is_ok());
+        interface.flags = 0;
+        assert_eq!(interface.health(), InterfaceHealth::Down);
+    }
+
+    #[test]
+    fn test_qdiscs() {
+        for interface in get_interfaces() {
+            println!("{}: {:?}", interface.name, interface.qdiscs());
+        }
+    }
+    #[test]
+    fn test_address_info() {
+        for interface in get_interfaces() {
+            println!("{}: {:?}", interface.name, interface.address_info());
+            println!("{}: {:?}", interface.name, interface.temporary_addresses());
+        }
+    }
+    #[test]
+    fn test_join_multicast() {
+        let mdns_v4 = Ipv4Addr::new(224, 0, 0, 251);
+        let mdns_v6 = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0xfb);
+        for interface in get_interfaces() {
+            if !interface.is_up() || !interface.is_multicast() {
+                continue;
+            }
+            if !interface.ipv4.is_empty() {
+                let socket = UdpSocket::bind("0.0.0.0:0").unwrap();
+                interface.join_multicast_v4(&socket, mdns_v4).unwrap();
+                interface.leave_multicast_v4(&socket, mdns_v4).unwrap();
+            }
+            if !interface.ipv6.is_empty() {
+                if let Ok(socket) = UdpSocket::bind("[::]:0") {
+                    println!(
+                        "{}: {:?}",
+                        interface.name,
+                        interface.join_multicast_v6(&socket, mdns_v6)
+                    );
+                }
+            }
+        }
+    }
+    #[test]
     fn test_default_interface() {
         println!("{:#?}", get_default_interface());
     }
@@ -0,0 +1,72 @@
+#[cfg(unix)]
+use crate::error::Error;
+use std::io;
+
+/// Get the index of the interface with the given name, without enumerating
+/// the interfaces.
+///
+/// On Windows, the name is either the adapter GUID, which is what
+/// [`Interface::name`](super::Interface::name) holds, or the alias such as `Ethernet 2`.
+#[cfg(unix)]
+pub fn name_to_index(name: &str) -> io::Result<u32> {
+    let name = std::ffi::CString::new(name)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "interface name contains NUL"))?;
+    match unsafe { libc::if_nametoindex(name.as_ptr()) } {
+        0 => Err(Error::last_os_error("if_nametoindex")
+            .on(&name.to_string_lossy())
+            .into()),
+        index => Ok(index),
+    }
+}
+
+/// Get the name of the interface with the given index, without enumerating
+/// the interfaces.
+///
+/// On Windows, this is the adapter GUID, like [`Interface::name`](super::Interface::name).
+#[cfg(unix)]
+pub fn index_to_name(if_index: u32) -> io::Result<String> {
+    let mut buf = [0 as libc::c_char; libc::IF_NAMESIZE];
+    let name = unsafe { libc::if_indextoname(if_index, buf.as_mut_ptr()) };
+    if name.is_null() {
+        return Err(Error::last_os_error("if_indextoname").into());
+    }
+    let name = unsafe { std::ffi::CStr::from_ptr(name) };
+    Ok(name.to_string_lossy().into_owned())
+}
+
+/// Get the index of the interface with the given name, without enumerating
+/// the interfaces.
+///
+/// On Windows, the name is either the adapter GUID, which is what
+/// [`Interface::name`](super::Interface::name) holds, or the alias such as `Ethernet 2`.
+#[cfg(windows)]
+pub fn name_to_index(name: &str) -> io::Result<u32> {
+    use super::luid;
+    let luid = luid::guid_to_luid(name).or_else(|_| luid::alias_to_luid(name))?;
+    luid::luid_to_index(luid)
+}
+
+/// Get the name of the interface with the given index, without enumerating
+/// the interfaces.
+///
+/// On Windows, this is the adapter GUID, like [`Interface::name`](super::Interface::name).
+#[cfg(windows)]
+pub fn index_to_name(if_index: u32) -> io::Result<String> {
+    use super::luid;
+    luid::luid_to_guid(luid::index_to_luid(if_index)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_index_round_trip() {
+        for iface in crate::interface::get_interfaces() {
+            assert_eq!(name_to_index(&iface.name).unwrap(), iface.index);
+            assert_eq!(index_to_name(iface.index).unwrap(), iface.name);
+        }
+        assert!(name_to_index("no-such-interface0").is_err());
+        assert!(name_to_index("eth\0").is_err());
+    }
+}
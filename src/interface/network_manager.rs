@@ -0,0 +1,131 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// State of a device in NetworkManager (`NMDeviceState`)
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NetworkManagerState {
+    /// Not managed by NetworkManager
+    Unmanaged,
+    /// Managed, but not ready for activation, e.g. without carrier
+    Unavailable,
+    /// Ready for activation, with no active connection
+    Disconnected,
+    /// A connection is being activated
+    Activating,
+    /// A connection is active
+    Activated,
+    /// The connection is being torn down
+    Deactivating,
+    /// The last activation failed
+    Failed,
+    /// Other or unknown state
+    Unknown,
+}
+
+/// NetworkManager's view of an interface
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NetworkManagerInfo {
+    /// State of the device
+    pub state: NetworkManagerState,
+    /// Whether NetworkManager may activate a connection on the device by itself
+    pub autoconnect: bool,
+    /// Name of the active connection profile, e.g. `Wired connection 1`
+    pub connection_id: Option<String>,
+    /// UUID of the active connection profile
+    pub connection_uuid: Option<String>,
+}
+
+#[cfg(all(feature = "networkmanager", target_os = "linux"))]
+mod dbus {
+    use super::{NetworkManagerInfo, NetworkManagerState};
+    use crate::interface::Interface;
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::OwnedObjectPath;
+
+    const NM_SERVICE: &str = "org.freedesktop.NetworkManager";
+    const NM_PATH: &str = "/org/freedesktop/NetworkManager";
+    const NM: &str = "org.freedesktop.NetworkManager";
+    const NM_DEVICE: &str = "org.freedesktop.NetworkManager.Device";
+    const NM_ACTIVE_CONNECTION: &str = "org.freedesktop.NetworkManager.Connection.Active";
+
+    fn state(value: u32) -> NetworkManagerState {
+        match value {
+            10 => NetworkManagerState::Unmanaged,
+            20 => NetworkManagerState::Unavailable,
+            30 => NetworkManagerState::Disconnected,
+            // PREPARE, CONFIG, NEED_AUTH, IP_CONFIG, IP_CHECK and SECONDARIES
+            40..=90 => NetworkManagerState::Activating,
+            100 => NetworkManagerState::Activated,
+            110 => NetworkManagerState::Deactivating,
+            120 => NetworkManagerState::Failed,
+            _ => NetworkManagerState::Unknown,
+        }
+    }
+
+    /// Read the device at `path`, returning the names it is known by and its info
+    fn device_info(
+        conn: &Connection,
+        path: &OwnedObjectPath,
+    ) -> zbus::Result<([String; 2], NetworkManagerInfo)> {
+        let device = Proxy::new(conn, NM_SERVICE, path.as_ref(), NM_DEVICE)?;
+        let names = [
+            device.get_property("Interface")?,
+            device.get_property("IpInterface")?,
+        ];
+        let mut info = NetworkManagerInfo {
+            state: state(device.get_property("State")?),
+            autoconnect: device.get_property("Autoconnect")?,
+            connection_id: None,
+            connection_uuid: None,
+        };
+        let active: OwnedObjectPath = device.get_property("ActiveConnection")?;
+        if active.as_str() != "/" {
+            let active = Proxy::new(conn, NM_SERVICE, active.as_ref(), NM_ACTIVE_CONNECTION)?;
+            info.connection_id = active.get_property("Id").ok();
+            info.connection_uuid = active.get_property("Uuid").ok();
+        }
+        Ok((names, info))
+    }
+
+    /// Fill in `network_manager` of the interfaces NetworkManager knows.
+    ///
+    /// Leaves the interfaces untouched if NetworkManager is not running.
+    pub(crate) fn apply(interfaces: &mut [Interface]) {
+        let Ok(conn) = Connection::system() else {
+            return;
+        };
+        let Ok(nm) = Proxy::new(&conn, NM_SERVICE, NM_PATH, NM) else {
+            return;
+        };
+        let Ok(devices) = nm.call::<_, _, Vec<OwnedObjectPath>>("GetDevices", &()) else {
+            return;
+        };
+        for path in &devices {
+            let Ok((names, info)) = device_info(&conn, path) else {
+                continue;
+            };
+            if let Some(iface) = interfaces
+                .iter_mut()
+                .find(|iface| names.contains(&iface.name))
+            {
+                iface.network_manager = Some(info);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_state() {
+            assert_eq!(state(100), NetworkManagerState::Activated);
+            assert_eq!(state(70), NetworkManagerState::Activating);
+            assert_eq!(state(0), NetworkManagerState::Unknown);
+        }
+    }
+}
+#[cfg(all(feature = "networkmanager", target_os = "linux"))]
+pub(crate) use self::dbus::apply;
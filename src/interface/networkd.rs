@@ -0,0 +1,206 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Operational state of a link in systemd-networkd, as shown by `networkctl`
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum OperationalState {
+    /// The link is down
+    Off,
+    /// The link is up but has no carrier
+    NoCarrier,
+    /// The link has carrier but is dormant, e.g. waiting for 802.1X
+    Dormant,
+    /// An enslaved link has carrier, but its master does not
+    DegradedCarrier,
+    /// The link has carrier
+    Carrier,
+    /// The link has only link-local addresses
+    Degraded,
+    /// The link has carrier and is enslaved to a master, e.g. a bridge port
+    Enslaved,
+    /// The link has a routable address
+    Routable,
+    /// Other or unknown state
+    Unknown,
+}
+
+/// Setup state of a link in systemd-networkd
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SetupState {
+    /// udev has not processed the link yet
+    Pending,
+    /// The link is ready, but no `.network` file matches it
+    Initialized,
+    /// The link is being configured
+    Configuring,
+    /// The link is configured
+    Configured,
+    /// The link is not managed by networkd
+    Unmanaged,
+    /// The configuration failed
+    Failed,
+    /// The link is gone, but networkd still holds its state
+    Linger,
+    /// Other or unknown state
+    Unknown,
+}
+
+/// systemd-networkd's view of an interface
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NetworkdInfo {
+    /// Operational state, e.g. routable or degraded
+    pub operational_state: OperationalState,
+    /// Setup state, e.g. configured or unmanaged
+    pub setup_state: SetupState,
+    /// Path of the `.network` file applied to the link
+    pub network_file: Option<String>,
+    /// Whether the link is waited for by `systemd-networkd-wait-online`
+    pub required_for_online: Option<bool>,
+}
+
+#[cfg(all(feature = "networkd", target_os = "linux"))]
+mod dbus {
+    use super::{NetworkdInfo, OperationalState, SetupState};
+    use crate::interface::Interface;
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::OwnedObjectPath;
+
+    const NETWORKD_SERVICE: &str = "org.freedesktop.network1";
+    const NETWORKD_PATH: &str = "/org/freedesktop/network1";
+    const NETWORKD_MANAGER: &str = "org.freedesktop.network1.Manager";
+    const NETWORKD_LINK: &str = "org.freedesktop.network1.Link";
+
+    fn operational_state(value: &str) -> OperationalState {
+        match value {
+            "off" => OperationalState::Off,
+            "no-carrier" => OperationalState::NoCarrier,
+            "dormant" => OperationalState::Dormant,
+            "degraded-carrier" => OperationalState::DegradedCarrier,
+            "carrier" => OperationalState::Carrier,
+            "degraded" => OperationalState::Degraded,
+            "enslaved" => OperationalState::Enslaved,
+            "routable" => OperationalState::Routable,
+            _ => OperationalState::Unknown,
+        }
+    }
+
+    fn setup_state(value: &str) -> SetupState {
+        match value {
+            "pending" => SetupState::Pending,
+            "initialized" => SetupState::Initialized,
+            "configuring" => SetupState::Configuring,
+            "configured" => SetupState::Configured,
+            "unmanaged" => SetupState::Unmanaged,
+            "failed" => SetupState::Failed,
+            "linger" => SetupState::Linger,
+            _ => SetupState::Unknown,
+        }
+    }
+
+    /// Take the `.network` file and whether the link is required for online
+    /// from the JSON description of a link
+    fn apply_description(info: &mut NetworkdInfo, json: &str) {
+        let Ok(desc) = serde_json::from_str::<serde_json::Value>(json) else {
+            return;
+        };
+        info.network_file = desc
+            .get("NetworkFile")
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string);
+        info.required_for_online = desc
+            .get("RequiredForOnline")
+            .and_then(serde_json::Value::as_bool);
+    }
+
+    /// Read the link at `path`
+    fn link_info(conn: &Connection, path: &OwnedObjectPath) -> zbus::Result<NetworkdInfo> {
+        let link = Proxy::new(conn, NETWORKD_SERVICE, path.as_ref(), NETWORKD_LINK)?;
+        let operational: String = link.get_property("OperationalState")?;
+        let administrative: String = link.get_property("AdministrativeState")?;
+        let mut info = NetworkdInfo {
+            operational_state: operational_state(&operational),
+            setup_state: setup_state(&administrative),
+            network_file: None,
+            required_for_online: None,
+        };
+        // Describe was added in systemd 248
+        if let Ok(json) = link.call::<_, _, String>("Describe", &()) {
+            apply_description(&mut info, &json);
+        }
+        Ok(info)
+    }
+
+    /// Fill in `networkd` of the interfaces networkd knows.
+    ///
+    /// Leaves the interfaces untouched if networkd is not running.
+    pub(crate) fn apply(interfaces: &mut [Interface]) {
+        let Ok(conn) = Connection::system() else {
+            return;
+        };
+        let Ok(manager) = Proxy::new(&conn, NETWORKD_SERVICE, NETWORKD_PATH, NETWORKD_MANAGER)
+        else {
+            return;
+        };
+        let Ok(links) = manager.call::<_, _, Vec<(i32, String, OwnedObjectPath)>>("ListLinks", &())
+        else {
+            return;
+        };
+        for (index, _, path) in &links {
+            let Some(iface) = interfaces
+                .iter_mut()
+                .find(|iface| i32::try_from(iface.index) == Ok(*index))
+            else {
+                continue;
+            };
+            if let Ok(info) = link_info(&conn, path) {
+                iface.networkd = Some(info);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_states() {
+            assert_eq!(operational_state("routable"), OperationalState::Routable);
+            assert_eq!(
+                operational_state("degraded-carrier"),
+                OperationalState::DegradedCarrier
+            );
+            assert_eq!(operational_state("missing"), OperationalState::Unknown);
+            assert_eq!(setup_state("configured"), SetupState::Configured);
+            assert_eq!(setup_state("unmanaged"), SetupState::Unmanaged);
+        }
+
+        #[test]
+        fn test_apply_description() {
+            let mut info = NetworkdInfo {
+                operational_state: OperationalState::Routable,
+                setup_state: SetupState::Configured,
+                network_file: None,
+                required_for_online: None,
+            };
+            let json = r#"{"Index":2,"Name":"eth0","SetupState":"configured",
+                "NetworkFile":"/etc/systemd/network/20-wired.network",
+                "RequiredForOnline":true,"OperationalState":"routable"}"#;
+            apply_description(&mut info, json);
+            assert_eq!(
+                info.network_file.as_deref(),
+                Some("/etc/systemd/network/20-wired.network")
+            );
+            assert_eq!(info.required_for_online, Some(true));
+
+            // Unmanaged links have no network file
+            apply_description(&mut info, r#"{"Index":3,"Name":"docker0"}"#);
+            assert_eq!(info.network_file, None);
+            assert_eq!(info.required_for_online, None);
+        }
+    }
+}
+#[cfg(all(feature = "networkd", target_os = "linux"))]
+pub(crate) use self::dbus::apply;
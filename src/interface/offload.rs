@@ -0,0 +1,24 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// NIC offload features currently enabled on an interface
+///
+/// Fields are `None` when the state could not be determined on the platform.
+/// When checksum offload is enabled, captured outgoing frames typically carry
+/// checksums that have not been computed yet.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OffloadInfo {
+    /// Receive checksum verification is done by the NIC
+    pub rx_checksum: Option<bool>,
+    /// Transmit checksum calculation is done by the NIC
+    pub tx_checksum: Option<bool>,
+    /// TCP segmentation offload (TSO / LSO)
+    pub tso: Option<bool>,
+    /// Generic segmentation offload
+    pub gso: Option<bool>,
+    /// Generic receive offload
+    pub gro: Option<bool>,
+    /// Large receive offload (LRO / RSC)
+    pub lro: Option<bool>,
+}
@@ -0,0 +1,191 @@
+use crate::sys;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// IP address family selection for interface enumeration
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AddressFamily {
+    /// IPv4 addresses only
+    V4,
+    /// IPv6 addresses only
+    V6,
+    /// Both IPv4 and IPv6 addresses
+    #[default]
+    Both,
+}
+
+impl AddressFamily {
+    /// Check if IPv4 addresses are selected
+    pub fn includes_v4(&self) -> bool {
+        matches!(self, AddressFamily::V4 | AddressFamily::Both)
+    }
+    /// Check if IPv6 addresses are selected
+    pub fn includes_v6(&self) -> bool {
+        matches!(self, AddressFamily::V6 | AddressFamily::Both)
+    }
+}
+
+/// How much information interface enumeration collects.
+///
+/// Each level includes the previous one.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DetailLevel {
+    /// What the OS interface list holds: index, name, flags, hardware and IP
+    /// addresses, and the type where the list reports it
+    Basic,
+    /// Gateways, DNS servers and the default interface, which come from
+    /// system-wide tables, and on macOS the types and friendly names
+    Standard,
+    /// Lookups made for each interface: driver, device path, description, link
+    /// speed, type and counters from sysfs and ethtool on Linux, the driver and
+    /// description from IOKit on macOS, and the driver, counters and link change
+    /// time on Windows
+    #[default]
+    Full,
+}
+
+/// Options for network interface enumeration
+///
+/// The default options match the behavior of [`get_interfaces`](super::get_interfaces).
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EnumOptions {
+    /// Include interfaces that are not up
+    pub include_down: bool,
+    /// Include hidden and disabled adapters (Windows only)
+    pub include_hidden: bool,
+    /// Include loopback interfaces
+    pub include_loopback: bool,
+    /// IP address families to report for each interface
+    pub address_families: AddressFamily,
+    /// Skip interfaces whose name matches any of these patterns, where `*`
+    /// matches any run of characters and `?` any single character. On Windows,
+    /// the friendly name is matched too.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub exclude_patterns: Vec<String>,
+    /// Information to collect about each interface
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub detail_level: DetailLevel,
+    /// Take the counters from the same kernel dump as the interface list, so
+    /// that they cannot belong to an interface that was replaced in between.
+    ///
+    /// On Linux, this reads the 64-bit counters of every interface from a
+    /// single `RTM_GETLINK` dump right after the list, matched to it by index
+    /// and name, instead of sysfs, at any detail level. BSD and macOS always
+    /// take them from the list, and Windows ignores it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub atomic_stats: bool,
+}
+
+impl Default for EnumOptions {
+    fn default() -> Self {
+        EnumOptions {
+            include_down: true,
+            include_hidden: false,
+            include_loopback: true,
+            address_families: AddressFamily::Both,
+            exclude_patterns: Vec::new(),
+            detail_level: DetailLevel::Full,
+            atomic_stats: false,
+        }
+    }
+}
+
+impl EnumOptions {
+    /// Construct a new EnumOptions instance with default options
+    pub fn new() -> EnumOptions {
+        EnumOptions::default()
+    }
+    /// Skip interfaces whose name matches any of `patterns`, such as `veth*`.
+    ///
+    /// Excluded interfaces are dropped before their addresses and other
+    /// details are read.
+    pub fn exclude_patterns(mut self, patterns: &[&str]) -> EnumOptions {
+        self.exclude_patterns = patterns.iter().map(|p| p.to_string()).collect();
+        self
+    }
+    /// Take the counters from the same dump as the interface list, see
+    /// [`atomic_stats`](EnumOptions::atomic_stats)
+    pub fn atomic_stats(mut self, atomic: bool) -> EnumOptions {
+        self.atomic_stats = atomic;
+        self
+    }
+    /// Check if an interface with the given name should be enumerated
+    pub(crate) fn accepts_name(&self, name: &str) -> bool {
+        !self
+            .exclude_patterns
+            .iter()
+            .any(|pattern| matches_pattern(pattern.as_bytes(), name.as_bytes()))
+    }
+    /// Check if an interface with the given flags should be enumerated
+    pub(crate) fn accepts_flags(&self, flags: u32) -> bool {
+        #[cfg(unix)]
+        let (up, loopback) = (sys::IFF_UP as u32, sys::IFF_LOOPBACK as u32);
+        #[cfg(not(unix))]
+        let (up, loopback) = (sys::IFF_UP, sys::IFF_LOOPBACK);
+        if !self.include_down && flags & up == 0 {
+            return false;
+        }
+        if !self.include_loopback && flags & loopback != 0 {
+            return false;
+        }
+        true
+    }
+}
+
+/// Match `name` against a pattern where `*` matches any run of bytes and `?`
+/// any single byte
+fn matches_pattern(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*` and the name position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                p += 1;
+                backtrack = Some((p, n));
+            }
+            Some(&c) if c == b'?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more byte
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exclude_patterns() {
+        let options = EnumOptions::new().exclude_patterns(&["docker*", "veth*", "br-*", "tap?"]);
+        assert!(!options.accepts_name("docker0"));
+        assert!(!options.accepts_name("veth1a2b3c"));
+        assert!(!options.accepts_name("br-0123abcd"));
+        assert!(!options.accepts_name("tap0"));
+        assert!(options.accepts_name("tap10"));
+        assert!(options.accepts_name("eth0"));
+        assert!(options.accepts_name("br0"));
+        assert!(EnumOptions::new().accepts_name("veth0"));
+
+        assert!(matches_pattern(b"*", b""));
+        assert!(matches_pattern(b"e*h*0", b"eth0"));
+        assert!(matches_pattern(b"*.100", b"eth0.100"));
+        assert!(!matches_pattern(b"*.100", b"eth0.1000"));
+        assert!(!matches_pattern(b"eth", b"eth0"));
+    }
+}
@@ -0,0 +1,61 @@
+use std::io;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// State of the system's default network path, like `NWPath` of Apple's
+/// Network framework
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PathStatus {
+    /// Internet addresses can be reached now
+    pub satisfied: bool,
+    /// A connection, such as a VPN on demand, must be established first
+    pub requires_connection: bool,
+    /// The path goes over cellular data, which may be metered
+    pub expensive: bool,
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn get_path_status() -> io::Result<PathStatus> {
+    use std::net::{Ipv4Addr, SocketAddr};
+    use system_configuration::network_reachability::{ReachabilityFlags, SCNetworkReachability};
+
+    // The unspecified address stands for the default route
+    let reachability = SCNetworkReachability::from(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)));
+    let flags = reachability
+        .reachability()
+        .map_err(|_| io::Error::other("Failed to read the reachability flags"))?;
+    let requires_connection = flags.contains(ReachabilityFlags::CONNECTION_REQUIRED);
+    Ok(PathStatus {
+        satisfied: flags.contains(ReachabilityFlags::REACHABLE) && !requires_connection,
+        requires_connection,
+        expensive: flags.contains(ReachabilityFlags::IS_WWAN),
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+fn get_path_status() -> io::Result<PathStatus> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Path status is not supported on this platform",
+    ))
+}
+
+/// Returns the state of the default network path, from SystemConfiguration's
+/// reachability API on macOS and iOS.
+///
+/// Unlike the interface list, this is available to sandboxed iOS apps.
+pub fn path_status() -> io::Result<PathStatus> {
+    get_path_status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_status() {
+        println!("{:?}", path_status());
+    }
+}
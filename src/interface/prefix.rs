@@ -0,0 +1,40 @@
+use crate::interface::AddressOrigin;
+use crate::ip::IpNet;
+use std::net::IpAddr;
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Prefix whose addresses are reachable directly on the link of an interface,
+/// without going through a router
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OnLinkPrefix {
+    /// The on-link network
+    pub prefix: IpNet,
+    /// Remaining time until the prefix stops being on-link. `None` if it does
+    /// not expire or the platform does not report it.
+    pub valid_lifetime: Option<Duration>,
+    /// Where the prefix came from: `RouterAdvertisement` for the Prefix
+    /// Information option of a router advertisement, otherwise the origin of
+    /// the configuration or address the prefix belongs to
+    pub origin: AddressOrigin,
+}
+
+impl OnLinkPrefix {
+    /// Check if `addr` can be reached without a router through this prefix
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        self.prefix.contains(addr)
+    }
+}
+
+/// Check if a gateway-less route to `prefix` describes an on-link subnet,
+/// rather than the default route, a single host or a multicast range
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub(crate) fn is_on_link_subnet(prefix: &IpNet) -> bool {
+    prefix.prefix_len() != 0
+        && prefix.prefix_len() != prefix.max_prefix_len()
+        && !prefix.addr().is_multicast()
+        && !prefix.addr().is_unspecified()
+}
@@ -0,0 +1,52 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Parent handle of a qdisc attached directly to the interface (`TC_H_ROOT`)
+pub const TC_H_ROOT: u32 = 0xffff_ffff;
+/// Parent handle of the ingress qdisc (`TC_H_INGRESS`)
+pub const TC_H_INGRESS: u32 = 0xffff_fff1;
+
+/// Counters of a queueing discipline
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QdiscStats {
+    /// Bytes sent
+    pub bytes: u64,
+    /// Packets sent
+    pub packets: u64,
+    /// Packets dropped
+    pub drops: u64,
+    /// Times the qdisc throttled traffic to stay within its limits
+    pub overlimits: u64,
+    /// Packets handed back to the qdisc because the driver was busy
+    pub requeues: u64,
+    /// Packets currently queued
+    pub qlen: u32,
+    /// Bytes currently queued
+    pub backlog: u32,
+}
+
+/// Queueing discipline attached to an interface, as shown by `tc -s qdisc`
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Qdisc {
+    /// Name of the discipline, such as `fq_codel`, `htb` or `noqueue`
+    pub kind: String,
+    /// Handle, with the major number in the upper 16 bits
+    pub handle: u32,
+    /// Handle of the parent qdisc or class, [`TC_H_ROOT`] for the root qdisc
+    pub parent: u32,
+    /// Counters since the qdisc was created
+    pub stats: QdiscStats,
+}
+
+impl Qdisc {
+    /// Check if the qdisc is the root egress qdisc of the interface
+    pub fn is_root(&self) -> bool {
+        self.parent == TC_H_ROOT
+    }
+    /// Check if the qdisc handles ingress traffic
+    pub fn is_ingress(&self) -> bool {
+        self.parent == TC_H_INGRESS
+    }
+}
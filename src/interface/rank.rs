@@ -0,0 +1,128 @@
+use crate::interface::{get_interfaces, Interface};
+use crate::zone;
+use std::cmp::Reverse;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+fn is_global_v4(addr: &Ipv4Addr) -> bool {
+    !addr.is_unspecified() && !addr.is_loopback() && !addr.is_link_local()
+}
+
+fn is_global_v6(addr: &Ipv6Addr) -> bool {
+    !addr.is_unspecified() && !addr.is_loopback() && !zone::is_link_local(addr)
+}
+
+/// Default scoring used by [`rank_interfaces`]; higher is more useful.
+///
+/// The interface holding the default route wins, followed by interfaces that are up
+/// and have a routable address. Virtual interfaces, including hypervisor
+/// adapters and Thunderbolt links, are ranked below physical ones, and loopback
+/// interfaces come last.
+pub fn default_score(interface: &Interface) -> i64 {
+    let mut score = 0;
+    if interface.default {
+        score += 1000;
+    }
+    if interface.is_up() {
+        score += 100;
+        if interface.is_running() {
+            score += 50;
+        }
+    }
+    if interface.ipv4.iter().any(|net| is_global_v4(&net.addr))
+        || interface.ipv6.iter().any(|net| is_global_v6(&net.addr))
+    {
+        score += 200;
+    }
+    if !interface.gateways.is_empty() {
+        score += 100;
+    }
+    if interface.is_loopback() {
+        score -= 1000;
+    } else if interface.is_physical() && !interface.is_thunderbolt() {
+        score += 50;
+    }
+    score
+}
+
+fn sort_by_score<F>(interfaces: &mut [Interface], score: F)
+where
+    F: FnMut(&Interface) -> i64,
+{
+    let mut score = score;
+    // Stable, so interfaces with equal scores keep the OS order.
+    interfaces.sort_by_cached_key(|iface| Reverse(score(iface)));
+}
+
+/// Get the network interfaces sorted by likely usefulness, best first
+///
+/// Useful for picking an interface automatically when the user cannot be asked.
+/// See [`default_score`] for the heuristic.
+pub fn rank_interfaces() -> Vec<Interface> {
+    rank_interfaces_by(default_score)
+}
+
+/// Get the network interfaces sorted by a custom score, highest first
+pub fn rank_interfaces_by<F>(score: F) -> Vec<Interface>
+where
+    F: FnMut(&Interface) -> i64,
+{
+    let mut interfaces = get_interfaces();
+    sort_by_score(&mut interfaces, score);
+    interfaces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ip::Ipv4Net;
+
+    #[test]
+    fn test_sort_by_score() {
+        let mut interfaces: Vec<Interface> = (1..=4)
+            .map(|index| {
+                let mut iface = Interface::dummy();
+                iface.index = index;
+                iface
+            })
+            .collect();
+        interfaces[2].default = true;
+        interfaces[3]
+            .ipv4
+            .push(Ipv4Net::new(Ipv4Addr::new(192, 168, 1, 2), 24));
+        sort_by_score(&mut interfaces, |iface| {
+            let mut score = 0;
+            if iface.default {
+                score += 10;
+            }
+            if !iface.ipv4.is_empty() {
+                score += 1;
+            }
+            score
+        });
+        let order: Vec<u32> = interfaces.iter().map(|iface| iface.index).collect();
+        assert_eq!(order, vec![3, 4, 1, 2]);
+    }
+
+    #[test]
+    fn test_global_address() {
+        assert!(is_global_v4(&Ipv4Addr::new(10, 0, 0, 1)));
+        assert!(!is_global_v4(&Ipv4Addr::new(169, 254, 1, 1)));
+        assert!(!is_global_v4(&Ipv4Addr::LOCALHOST));
+        assert!(is_global_v6(&"2001:db8::1".parse().unwrap()));
+        assert!(!is_global_v6(&"fe80::1".parse().unwrap()));
+        assert!(!is_global_v6(&Ipv6Addr::LOCALHOST));
+    }
+
+    #[test]
+    fn test_rank_interfaces() {
+        let ranked = rank_interfaces();
+        for iface in &ranked {
+            println!("{} {}", default_score(iface), iface.name);
+        }
+        if let Some(first) = ranked.first() {
+            assert!(ranked
+                .iter()
+                .all(|iface| default_score(iface) <= default_score(first)));
+        }
+    }
+}
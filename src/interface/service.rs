@@ -0,0 +1,58 @@
+use std::io;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Network service of the macOS network settings, the user-facing
+/// configuration an interface is used through
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NetworkService {
+    /// Identifier of the service, a UUID
+    pub id: String,
+    /// Name shown in the network settings, such as `Wi-Fi` or `USB 10/100/1000 LAN`
+    pub name: String,
+    /// BSD name of the interface of the service, such as `en0`. `None` for
+    /// services without one, like a VPN that is not connected.
+    pub if_name: Option<String>,
+    /// Position of the service in the service order, 0 being the most preferred.
+    /// `None` if the service is missing from the order.
+    pub order: Option<usize>,
+    /// The service is the primary one, providing the default route and DNS
+    pub primary: bool,
+}
+
+#[cfg(target_os = "macos")]
+use super::macos::get_network_services;
+
+#[cfg(not(target_os = "macos"))]
+fn get_network_services() -> io::Result<Vec<NetworkService>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Network services are not supported on this platform",
+    ))
+}
+
+/// List the configured network services, most preferred first.
+///
+/// Read from the SystemConfiguration dynamic store on macOS.
+pub fn network_services() -> io::Result<Vec<NetworkService>> {
+    get_network_services()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_services() {
+        match network_services() {
+            Ok(services) => {
+                for service in services {
+                    println!("{:?}", service);
+                }
+            }
+            Err(e) => println!("{}", e),
+        }
+    }
+}
@@ -17,6 +17,18 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
 /// - `Some(IpAddr)`: IP address of the default network interface if successful.
 /// - `None`: If any error occurs during the operations.
 pub fn get_local_ipaddr() -> Option<IpAddr> {
+    #[cfg(feature = "mock")]
+    if let Some(provider) = crate::mock::current() {
+        return provider.local_ipaddr();
+    }
+    if let Some(backend) = crate::backend::installed() {
+        return backend.local_ipaddr();
+    }
+    os_local_ipaddr()
+}
+
+/// [`get_local_ipaddr`] as seen by the OS, ignoring any installed backend
+pub(crate) fn os_local_ipaddr() -> Option<IpAddr> {
     // Attempt to bind a UDP socket to an unspecified address and port.
     let socket = match UdpSocket::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)) {
         Ok(s) => s,
@@ -33,7 +45,7 @@ pub fn get_local_ipaddr() -> Option<IpAddr> {
     };
     // Retrieve and return the local IP address from the socket.
     match socket.local_addr() {
-        Ok(addr) => return Some(addr.ip()),
-        Err(_) => return None,
-    };
+        Ok(addr) => Some(addr.ip()),
+        Err(_) => None,
+    }
 }
@@ -0,0 +1,46 @@
+use super::{enumerate, EnumOptions, Interface};
+use std::time::{Duration, Instant};
+
+/// Interface list taken at one point in time.
+///
+/// With [`Snapshot::take`], the counters are read together with the
+/// interface list, see [`EnumOptions::atomic_stats`], so two snapshots can be
+/// compared to compute rates without an interface changing identity in between.
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    /// Monotonic time right before the interfaces were listed
+    pub taken_at: Instant,
+    /// The interfaces
+    pub interfaces: Vec<Interface>,
+    /// Change generation of the [`Monitor`](crate::monitor::Monitor) the
+    /// snapshot comes from, or 0 for a snapshot taken on its own
+    pub generation: u64,
+}
+
+impl Snapshot {
+    /// Enumerate the interfaces with `options`, reading the counters together
+    /// with the interface list whatever `options.atomic_stats` says
+    pub fn take(options: &EnumOptions) -> Snapshot {
+        let options = options.clone().atomic_stats(true);
+        let taken_at = Instant::now();
+        Snapshot {
+            taken_at,
+            interfaces: enumerate(&options),
+            generation: 0,
+        }
+    }
+    /// Returns the interface with index `index`
+    pub fn interface(&self, index: u32) -> Option<&Interface> {
+        self.interfaces.iter().find(|iface| iface.index == index)
+    }
+    /// Returns the time elapsed from `earlier` to this snapshot, or zero if
+    /// `earlier` was taken later
+    pub fn duration_since(&self, earlier: &Snapshot) -> Duration {
+        self.taken_at.saturating_duration_since(earlier.taken_at)
+    }
+}
+
+/// Take a [`Snapshot`] of the interfaces with the given enumeration options
+pub fn snapshot(options: &EnumOptions) -> Snapshot {
+    Snapshot::take(options)
+}
@@ -0,0 +1,69 @@
+use crate::mac::MacAddr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Role of an interface in SR-IOV
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SriovRole {
+    /// Physical function, the full-featured PCI function that owns the port
+    PhysicalFunction,
+    /// Virtual function, a lightweight PCI function carved out of a physical function
+    VirtualFunction,
+}
+
+/// Administrative link state the physical function imposes on a virtual function
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VfLinkState {
+    /// Follows the link state of the physical port
+    Auto,
+    /// Always up
+    Enable,
+    /// Always down
+    Disable,
+}
+
+/// Virtual function of a physical function
+///
+/// The configuration fields are `None` when the driver does not report them.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VirtualFunction {
+    /// Index of the VF on its physical function
+    pub index: u32,
+    /// PCI address of the VF, e.g. `0000:3b:02.1`
+    pub pci_address: Option<String>,
+    /// Name of the VF's network interface in this namespace, if it has one.
+    /// A VF passed through to a VM or bound to a userspace driver has none.
+    pub name: Option<String>,
+    /// MAC address assigned by the physical function
+    pub mac_addr: Option<MacAddr>,
+    /// VLAN the physical function tags the VF's traffic with
+    pub vlan: Option<u16>,
+    /// Administrative link state
+    pub link_state: Option<VfLinkState>,
+    /// Whether frames with a spoofed source MAC address are dropped
+    pub spoof_check: Option<bool>,
+    /// Whether the VF may change its MAC address and enable promiscuous mode
+    pub trust: Option<bool>,
+}
+
+/// SR-IOV topology of an interface
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SriovInfo {
+    /// Whether the interface is a physical or a virtual function
+    pub role: SriovRole,
+    /// Number of VFs the physical function supports. 0 for a VF.
+    pub total_vfs: u32,
+    /// Number of VFs currently enabled. 0 for a VF.
+    pub num_vfs: u32,
+    /// Enabled VFs of a physical function, in index order
+    pub vfs: Vec<VirtualFunction>,
+    /// For a VF, the name of its physical function's interface
+    pub physical_function: Option<String>,
+    /// For a VF, its index on the physical function
+    pub vf_index: Option<u32>,
+}
@@ -0,0 +1,126 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Kind of tethered link, as returned by [`Interface::tether_kind`](super::Interface::tether_kind)
+///
+/// Android phones share their connection over USB with RNDIS, or NCM on
+/// recent versions, so they are reported as [`UsbRndis`](TetherKind::UsbRndis)
+/// or [`UsbNcm`](TetherKind::UsbNcm).
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TetherKind {
+    /// Bluetooth Personal Area Network (BNEP)
+    BluetoothPan,
+    /// USB Remote NDIS, used by Android phones and mobile hotspots
+    UsbRndis,
+    /// USB Network Control Model, used by Android phones and 5G modems
+    UsbNcm,
+    /// USB tethering to an iPhone or iPad
+    IPhone,
+}
+
+/// Bluetooth PAN: the Linux interface name, the Linux, Windows and macOS
+/// drivers and the Windows adapter description
+fn is_bluetooth_pan(name: &str, driver: &str, description: &str) -> bool {
+    name.starts_with("bnep")
+        || driver == "bnep"
+        || driver == "bthpan"
+        || driver.contains("bluetoothpan")
+        || description.contains("personal area network")
+        || description.starts_with("bluetooth pan")
+}
+
+fn is_iphone(driver: &str, description: &str) -> bool {
+    driver == "ipheth"
+        || driver.starts_with("netaapl")
+        || description.contains("apple mobile device ethernet")
+        || description.starts_with("iphone")
+        || description.starts_with("ipad")
+}
+
+fn is_rndis(driver: &str, description: &str) -> bool {
+    driver == "rndis_host"
+        || driver.starts_with("usb_rndis")
+        || driver.starts_with("rndismp")
+        || description.contains("remote ndis")
+}
+
+fn is_ncm(driver: &str, description: &str) -> bool {
+    driver == "cdc_ncm"
+        || driver == "huawei_cdc_ncm"
+        || driver.starts_with("usbncm")
+        || description.contains("usbncm")
+        || description.contains("ncm host")
+}
+
+/// Recognize a tethered link from the interface name, the driver name and
+/// the adapter description
+pub(crate) fn classify(
+    name: &str,
+    driver: Option<&str>,
+    description: Option<&str>,
+) -> Option<TetherKind> {
+    let driver = driver.unwrap_or_default().to_ascii_lowercase();
+    let description = description.unwrap_or_default().to_ascii_lowercase();
+    if is_bluetooth_pan(name, &driver, &description) {
+        Some(TetherKind::BluetoothPan)
+    } else if is_iphone(&driver, &description) {
+        Some(TetherKind::IPhone)
+    } else if is_rndis(&driver, &description) {
+        Some(TetherKind::UsbRndis)
+    } else if is_ncm(&driver, &description) {
+        Some(TetherKind::UsbNcm)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let cases = [
+            ("bnep0", None, None, Some(TetherKind::BluetoothPan)),
+            (
+                "Ethernet 3",
+                Some("BthPan"),
+                Some("Bluetooth Device (Personal Area Network)"),
+                Some(TetherKind::BluetoothPan),
+            ),
+            ("eth1", Some("ipheth"), None, Some(TetherKind::IPhone)),
+            (
+                "Ethernet 4",
+                Some("netaapl64"),
+                Some("Apple Mobile Device Ethernet"),
+                Some(TetherKind::IPhone),
+            ),
+            ("en8", None, Some("iPhone"), Some(TetherKind::IPhone)),
+            ("usb0", Some("rndis_host"), None, Some(TetherKind::UsbRndis)),
+            (
+                "Ethernet 5",
+                Some("usb_rndis6"),
+                Some("Remote NDIS based Internet Sharing Device"),
+                Some(TetherKind::UsbRndis),
+            ),
+            ("usb0", Some("cdc_ncm"), None, Some(TetherKind::UsbNcm)),
+            (
+                "Ethernet 6",
+                Some("UsbNcm"),
+                Some("UsbNcm Host Device"),
+                Some(TetherKind::UsbNcm),
+            ),
+            (
+                "enp0s31f6",
+                Some("e1000e"),
+                Some("Intel Corporation Ethernet Connection (2) I219-LM"),
+                None,
+            ),
+            ("eth0", Some("cdc_ether"), None, None),
+        ];
+        for (name, driver, description, kind) in cases {
+            assert_eq!(classify(name, driver, description), kind, "{}", name);
+        }
+    }
+}
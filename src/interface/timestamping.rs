@@ -0,0 +1,96 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Timestamps generated by the NIC when a frame is sent
+pub const SOF_TIMESTAMPING_TX_HARDWARE: u32 = 1 << 0;
+/// Timestamps generated by the kernel when a frame is handed to the driver
+pub const SOF_TIMESTAMPING_TX_SOFTWARE: u32 = 1 << 1;
+/// Timestamps generated by the NIC when a frame arrives
+pub const SOF_TIMESTAMPING_RX_HARDWARE: u32 = 1 << 2;
+/// Timestamps generated by the kernel when a frame enters the stack
+pub const SOF_TIMESTAMPING_RX_SOFTWARE: u32 = 1 << 3;
+/// Software timestamps can be reported to the application
+pub const SOF_TIMESTAMPING_SOFTWARE: u32 = 1 << 4;
+/// Hardware timestamps can be reported to the application
+pub const SOF_TIMESTAMPING_RAW_HARDWARE: u32 = 1 << 6;
+
+/// Hardware transmit timestamping for all outgoing frames (`HWTSTAMP_TX_ON`)
+pub const HWTSTAMP_TX_ON: u32 = 1;
+/// Hardware receive timestamping of all incoming frames (`HWTSTAMP_FILTER_ALL`)
+pub const HWTSTAMP_FILTER_ALL: u32 = 1;
+/// Hardware receive timestamping of PTPv2 event messages (`HWTSTAMP_FILTER_PTP_V2_EVENT`)
+pub const HWTSTAMP_FILTER_PTP_V2_EVENT: u32 = 12;
+
+/// Packet timestamping capabilities of an interface
+///
+/// Capture and measurement tools use this to choose between hardware timestamps,
+/// taken by the NIC against its PTP hardware clock, and software timestamps taken
+/// by the kernel. Note that with GRO or LRO enabled (see
+/// [`Interface::offload_info`](super::Interface::offload_info)) the kernel merges
+/// received segments, and the merged packet carries the timestamp of its first
+/// segment only.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimestampingCaps {
+    /// Supported `SOF_TIMESTAMPING_*` flags
+    pub flags: u32,
+    /// Index of the PTP hardware clock, `/dev/ptpN` on Linux
+    pub phc_index: Option<u32>,
+    /// Supported hardware transmit modes: bit `n` is set if `HWTSTAMP_TX_*` value `n` is
+    pub tx_types: u32,
+    /// Supported hardware receive filters: bit `n` is set if `HWTSTAMP_FILTER_*` value `n` is
+    pub rx_filters: u32,
+}
+
+impl TimestampingCaps {
+    /// Check if the NIC can timestamp outgoing frames
+    pub fn hardware_tx(&self) -> bool {
+        self.has_flags(SOF_TIMESTAMPING_TX_HARDWARE | SOF_TIMESTAMPING_RAW_HARDWARE)
+            && self.tx_types & (1 << HWTSTAMP_TX_ON) != 0
+    }
+    /// Check if the NIC can timestamp incoming frames.
+    /// Some NICs only timestamp PTP frames; see [`rx_filters`](Self::rx_filters).
+    pub fn hardware_rx(&self) -> bool {
+        self.has_flags(SOF_TIMESTAMPING_RX_HARDWARE | SOF_TIMESTAMPING_RAW_HARDWARE)
+    }
+    /// Check if the NIC can timestamp every incoming frame, not just PTP frames
+    pub fn hardware_rx_all(&self) -> bool {
+        self.hardware_rx() && self.rx_filters & (1 << HWTSTAMP_FILTER_ALL) != 0
+    }
+    /// Check if the kernel can timestamp outgoing frames
+    pub fn software_tx(&self) -> bool {
+        self.has_flags(SOF_TIMESTAMPING_TX_SOFTWARE | SOF_TIMESTAMPING_SOFTWARE)
+    }
+    /// Check if the kernel can timestamp incoming frames
+    pub fn software_rx(&self) -> bool {
+        self.has_flags(SOF_TIMESTAMPING_RX_SOFTWARE | SOF_TIMESTAMPING_SOFTWARE)
+    }
+    fn has_flags(&self, flags: u32) -> bool {
+        self.flags & flags == flags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamping_caps() {
+        // A NIC that only timestamps PTPv2 frames in hardware
+        let caps = TimestampingCaps {
+            flags: SOF_TIMESTAMPING_TX_HARDWARE
+                | SOF_TIMESTAMPING_RX_HARDWARE
+                | SOF_TIMESTAMPING_RAW_HARDWARE
+                | SOF_TIMESTAMPING_RX_SOFTWARE
+                | SOF_TIMESTAMPING_SOFTWARE,
+            phc_index: Some(0),
+            tx_types: 1 << HWTSTAMP_TX_ON,
+            rx_filters: 1 << HWTSTAMP_FILTER_PTP_V2_EVENT,
+        };
+        assert!(caps.hardware_tx());
+        assert!(caps.hardware_rx());
+        assert!(!caps.hardware_rx_all());
+        assert!(caps.software_rx());
+        assert!(!caps.software_tx());
+    }
+}
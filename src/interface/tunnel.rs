@@ -0,0 +1,49 @@
+use std::net::IpAddr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Kind of tunnel interface
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TunnelKind {
+    /// GRE carrying IP packets (`gre`, `ip6gre`)
+    Gre,
+    /// GRE carrying Ethernet frames (`gretap`, `ip6gretap`)
+    GreTap,
+    /// IP in IP (`ipip`, `ip6tnl`)
+    Ipip,
+    /// IPv6 in IPv4 (`sit`)
+    Sit,
+    /// Virtual tunnel interface for IPsec (`vti`, `vti6`)
+    Vti,
+    /// VXLAN
+    Vxlan,
+    /// Geneve
+    Geneve,
+}
+
+/// Parameters of a tunnel interface
+///
+/// Fields are `None` when the tunnel does not set them, e.g. an unspecified
+/// local endpoint or a TTL inherited from the inner packet.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TunnelInfo {
+    /// Kind of tunnel
+    pub kind: TunnelKind,
+    /// Local endpoint of the underlay
+    pub local: Option<IpAddr>,
+    /// Remote endpoint of the underlay. For VXLAN this may be a multicast group.
+    pub remote: Option<IpAddr>,
+    /// VXLAN or Geneve network identifier
+    pub vni: Option<u32>,
+    /// Outgoing GRE or VTI key
+    pub key: Option<u32>,
+    /// TTL of the outer packets
+    pub ttl: Option<u8>,
+    /// UDP destination port of VXLAN and Geneve
+    pub port: Option<u16>,
+    /// Index of the underlay interface the tunnel is bound to
+    pub link: Option<u32>,
+}
@@ -1,11 +1,16 @@
 use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// Type of Network Interface
+///
+/// New types may be added in minor releases, so matches need a wildcard arm.
 #[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 pub enum InterfaceType {
     /// Unknown interface type
     Unknown,
@@ -55,9 +60,9 @@ pub enum InterfaceType {
     Tunnel,
     /// The network interface using a Multirate Digital Subscriber Line
     MultiRateSymmetricDsl,
-    /// The network interface using a High Performance Serial Bus
+    /// The network interface using a High Performance Serial Bus (IEEE 1394, FireWire)
     HighPerformanceSerialBus,
-    /// The network interface using a mobile broadband interface for WiMax devices
+    /// The network interface using a mobile broadband interface for WiMax devices (IEEE 802.16)
     Wman,
     /// The network interface using a mobile broadband interface for GSM-based devices
     Wwanpp,
@@ -67,13 +72,16 @@ pub enum InterfaceType {
     Bridge,
     /// Controller Area Network
     Can,
+    /// Low-rate wireless personal area network (IEEE 802.15.4)
+    Ieee802154,
+    /// InfiniBand
+    Infiniband,
 }
 
 impl InterfaceType {
-    /// Returns OS-specific value of InterfaceType
-    #[cfg(target_os = "windows")]
-    pub fn value(&self) -> u32 {
-        match *self {
+    /// Returns the Windows `IFTYPE` value (`IfType` of `IP_ADAPTER_ADDRESSES`)
+    pub fn to_windows_value(&self) -> Option<u32> {
+        let value = match *self {
             InterfaceType::Unknown => 1,
             InterfaceType::Ethernet => 6,
             InterfaceType::TokenRing => 9,
@@ -102,44 +110,47 @@ impl InterfaceType {
             InterfaceType::Wman => 237,
             InterfaceType::Wwanpp => 243,
             InterfaceType::Wwanpp2 => 244,
-            _ => u32::MAX,
-        }
+            InterfaceType::Infiniband => 199,
+            InterfaceType::Bridge => 209,
+            InterfaceType::Ieee802154 => 259,
+            InterfaceType::Can => return None,
+        };
+        Some(value)
     }
-    /// Returns OS-specific value of InterfaceType
-    #[cfg(any(target_os = "linux", target_os = "android"))]
-    pub fn value(&self) -> u32 {
-        use crate::sys;
-        match *self {
-            InterfaceType::Ethernet => sys::if_arp::ARPHRD_ETHER,
-            InterfaceType::TokenRing => sys::if_arp::ARPHRD_IEEE802,
-            InterfaceType::Fddi => sys::if_arp::ARPHRD_FDDI,
-            InterfaceType::Ppp => sys::if_arp::ARPHRD_PPP,
-            InterfaceType::Loopback => sys::if_arp::ARPHRD_LOOPBACK,
-            InterfaceType::Ethernet3Megabit => sys::if_arp::ARPHRD_EETHER,
-            InterfaceType::Slip => sys::if_arp::ARPHRD_SLIP,
-            InterfaceType::Atm => sys::if_arp::ARPHRD_ATM,
-            InterfaceType::Wireless80211 => sys::if_arp::ARPHRD_IEEE80211,
-            InterfaceType::Tunnel => sys::if_arp::ARPHRD_TUNNEL,
-            InterfaceType::Isdn => sys::if_arp::ARPHRD_X25,
-            InterfaceType::HighPerformanceSerialBus => sys::if_arp::ARPHRD_IEEE1394,
-            InterfaceType::Can => sys::if_arp::ARPHRD_CAN,
-            _ => u32::MAX,
-        }
+    /// Returns the Linux `ARPHRD_*` value (`ifi_type`, `/sys/class/net/<name>/type`)
+    pub fn to_linux_arphrd(&self) -> Option<u32> {
+        // Values from linux/if_arp.h
+        let value = match *self {
+            InterfaceType::Ethernet => 1,
+            InterfaceType::Ethernet3Megabit => 2,
+            InterfaceType::TokenRing => 6,
+            InterfaceType::Atm => 19,
+            InterfaceType::HighPerformanceSerialBus => 24,
+            InterfaceType::Infiniband => 32,
+            InterfaceType::Slip => 256,
+            InterfaceType::Isdn => 271,
+            InterfaceType::Can => 280,
+            InterfaceType::Ppp => 512,
+            InterfaceType::Tunnel => 768,
+            InterfaceType::Loopback => 772,
+            InterfaceType::Fddi => 774,
+            InterfaceType::Wireless80211 => 801,
+            InterfaceType::Ieee802154 => 804,
+            _ => return None,
+        };
+        Some(value)
     }
-    /// Returns OS-specific value of InterfaceType
-    #[cfg(any(
-        target_os = "macos",
-        target_os = "openbsd",
-        target_os = "freebsd",
-        target_os = "netbsd",
-        target_os = "ios"
-    ))]
-    pub fn value(&self) -> u32 {
-        match *self {
+    /// Returns the BSD `IFT_*` value (`ifi_type` of `struct if_data`).
+    ///
+    /// `Wwanpp` maps to `IFT_CELLULAR`, which only Apple platforms define.
+    pub fn to_bsd_ift(&self) -> Option<u32> {
+        // Values from net/if_types.h
+        let value = match *self {
+            InterfaceType::Unknown => 0x1,
             InterfaceType::Ethernet => 0x6,
             InterfaceType::TokenRing => 0x9,
             InterfaceType::Fddi => 0xf,
-            InterfaceType::Isdn => 0x14,
+            InterfaceType::BasicIsdn => 0x14,
             InterfaceType::PrimaryIsdn => 0x15,
             InterfaceType::Ppp => 0x17,
             InterfaceType::Loopback => 0x18,
@@ -147,14 +158,77 @@ impl InterfaceType {
             InterfaceType::Slip => 0x1c,
             InterfaceType::Atm => 0x25,
             InterfaceType::GenericModem => 0x30,
+            InterfaceType::FastEthernetT => 0x3e,
+            InterfaceType::Isdn => 0x3f,
+            InterfaceType::FastEthernetFx => 0x45,
             InterfaceType::Wireless80211 => 0x47,
-            InterfaceType::AsymmetricDsl => 0x95,
-            InterfaceType::RateAdaptDsl => 0x96,
-            InterfaceType::SymmetricDsl => 0x97,
-            InterfaceType::IPOverAtm => 0x31,
+            InterfaceType::AsymmetricDsl => 0x5e,
+            InterfaceType::RateAdaptDsl => 0x5f,
+            InterfaceType::SymmetricDsl => 0x60,
+            InterfaceType::VeryHighSpeedDsl => 0x61,
+            InterfaceType::IPOverAtm => 0x72,
+            InterfaceType::GigabitEthernet => 0x75,
+            InterfaceType::Tunnel => 0x83,
+            InterfaceType::MultiRateSymmetricDsl => 0x8f,
             InterfaceType::HighPerformanceSerialBus => 0x90,
-            _ => u32::MAX,
-        }
+            InterfaceType::Infiniband => 0xc7,
+            InterfaceType::Bridge => 0xd1,
+            InterfaceType::Wman => 0xed,
+            InterfaceType::Wwanpp => 0xff,
+            _ => return None,
+        };
+        Some(value)
+    }
+    /// Returns the type with the given Windows `IFTYPE` value
+    pub fn from_windows_value(value: u32) -> Option<InterfaceType> {
+        InterfaceType::all()
+            .iter()
+            .find(|t| t.to_windows_value() == Some(value))
+            .copied()
+    }
+    /// Returns the type with the given Linux `ARPHRD_*` value
+    pub fn from_linux_arphrd(value: u32) -> Option<InterfaceType> {
+        InterfaceType::all()
+            .iter()
+            .find(|t| t.to_linux_arphrd() == Some(value))
+            .copied()
+    }
+    /// Returns the type with the given BSD `IFT_*` value
+    pub fn from_bsd_ift(value: u32) -> Option<InterfaceType> {
+        InterfaceType::all()
+            .iter()
+            .find(|t| t.to_bsd_ift() == Some(value))
+            .copied()
+    }
+    /// Returns the value of InterfaceType on the target OS, or `u32::MAX` if it has none.
+    ///
+    /// Use [`to_windows_value`](InterfaceType::to_windows_value),
+    /// [`to_linux_arphrd`](InterfaceType::to_linux_arphrd) or
+    /// [`to_bsd_ift`](InterfaceType::to_bsd_ift) for a specific OS.
+    pub fn value(&self) -> u32 {
+        #[cfg(target_os = "windows")]
+        let value = self.to_windows_value();
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        let value = self.to_linux_arphrd();
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        let value = self.to_bsd_ift();
+        #[cfg(any(target_os = "openbsd", target_os = "freebsd", target_os = "netbsd"))]
+        let value = match *self {
+            InterfaceType::Wwanpp => None,
+            _ => self.to_bsd_ift(),
+        };
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "android",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "openbsd",
+            target_os = "freebsd",
+            target_os = "netbsd"
+        )))]
+        let value = None;
+        value.unwrap_or(u32::MAX)
     }
     /// Returns name of InterfaceType
     pub fn name(&self) -> String {
@@ -189,52 +263,188 @@ impl InterfaceType {
             InterfaceType::Wwanpp => String::from("WWANPP"),
             InterfaceType::Wwanpp2 => String::from("WWANPP2"),
             InterfaceType::Can => String::from("CAN"),
+            InterfaceType::Ieee802154 => String::from("IEEE 802.15.4"),
+            InterfaceType::Infiniband => String::from("InfiniBand"),
         }
     }
+    /// Returns all known InterfaceType variants
+    pub fn all() -> &'static [InterfaceType] {
+        &[
+            InterfaceType::Unknown,
+            InterfaceType::Ethernet,
+            InterfaceType::TokenRing,
+            InterfaceType::Fddi,
+            InterfaceType::BasicIsdn,
+            InterfaceType::PrimaryIsdn,
+            InterfaceType::Ppp,
+            InterfaceType::Loopback,
+            InterfaceType::Ethernet3Megabit,
+            InterfaceType::Slip,
+            InterfaceType::Atm,
+            InterfaceType::GenericModem,
+            InterfaceType::FastEthernetT,
+            InterfaceType::Isdn,
+            InterfaceType::FastEthernetFx,
+            InterfaceType::Wireless80211,
+            InterfaceType::AsymmetricDsl,
+            InterfaceType::RateAdaptDsl,
+            InterfaceType::SymmetricDsl,
+            InterfaceType::VeryHighSpeedDsl,
+            InterfaceType::IPOverAtm,
+            InterfaceType::GigabitEthernet,
+            InterfaceType::Tunnel,
+            InterfaceType::MultiRateSymmetricDsl,
+            InterfaceType::HighPerformanceSerialBus,
+            InterfaceType::Wman,
+            InterfaceType::Wwanpp,
+            InterfaceType::Wwanpp2,
+            InterfaceType::Bridge,
+            InterfaceType::Can,
+            InterfaceType::Ieee802154,
+            InterfaceType::Infiniband,
+        ]
+    }
+}
+
+impl fmt::Display for InterfaceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+/// Error returned when parsing an unrecognized interface type name
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseInterfaceTypeError(String);
+
+impl fmt::Display for ParseInterfaceTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown interface type: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseInterfaceTypeError {}
+
+impl FromStr for InterfaceType {
+    type Err = ParseInterfaceTypeError;
+    /// Parses either the display name (`"Token Ring"`) or the variant name (`"TokenRing"`),
+    /// ignoring ASCII case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        InterfaceType::all()
+            .iter()
+            .find(|t| {
+                t.name().eq_ignore_ascii_case(s) || format!("{:?}", t).eq_ignore_ascii_case(s)
+            })
+            .copied()
+            .ok_or_else(|| ParseInterfaceTypeError(s.to_string()))
+    }
 }
 
+/// Converts the value of InterfaceType on the target OS, as returned by
+/// [`value`](InterfaceType::value)
 impl TryFrom<u32> for InterfaceType {
     type Error = ();
     fn try_from(v: u32) -> Result<Self, Self::Error> {
-        match v {
-            x if x == InterfaceType::Unknown.value() => Ok(InterfaceType::Unknown),
-            x if x == InterfaceType::Ethernet.value() => Ok(InterfaceType::Ethernet),
-            x if x == InterfaceType::TokenRing.value() => Ok(InterfaceType::TokenRing),
-            x if x == InterfaceType::Fddi.value() => Ok(InterfaceType::Fddi),
-            x if x == InterfaceType::BasicIsdn.value() => Ok(InterfaceType::BasicIsdn),
-            x if x == InterfaceType::PrimaryIsdn.value() => Ok(InterfaceType::PrimaryIsdn),
-            x if x == InterfaceType::Ppp.value() => Ok(InterfaceType::Ppp),
-            x if x == InterfaceType::Loopback.value() => Ok(InterfaceType::Loopback),
-            x if x == InterfaceType::Ethernet3Megabit.value() => {
-                Ok(InterfaceType::Ethernet3Megabit)
+        if v == u32::MAX {
+            return Err(());
+        }
+        InterfaceType::all()
+            .iter()
+            .find(|t| t.value() == v)
+            .copied()
+            .ok_or(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_from_str() {
+        for t in InterfaceType::all() {
+            assert_eq!(t.to_string().parse::<InterfaceType>(), Ok(*t));
+            assert_eq!(format!("{:?}", t).parse::<InterfaceType>(), Ok(*t));
+        }
+        assert_eq!(
+            "wireless ieee 802.11".parse(),
+            Ok(InterfaceType::Wireless80211)
+        );
+        assert!("not-a-type".parse::<InterfaceType>().is_err());
+    }
+
+    #[test]
+    fn test_value_round_trip() {
+        for t in InterfaceType::all() {
+            if t.value() != u32::MAX {
+                assert_eq!(InterfaceType::try_from(t.value()), Ok(*t));
             }
-            x if x == InterfaceType::Slip.value() => Ok(InterfaceType::Slip),
-            x if x == InterfaceType::Atm.value() => Ok(InterfaceType::Atm),
-            x if x == InterfaceType::GenericModem.value() => Ok(InterfaceType::GenericModem),
-            x if x == InterfaceType::FastEthernetT.value() => Ok(InterfaceType::FastEthernetT),
-            x if x == InterfaceType::Isdn.value() => Ok(InterfaceType::Isdn),
-            x if x == InterfaceType::FastEthernetFx.value() => Ok(InterfaceType::FastEthernetFx),
-            x if x == InterfaceType::Wireless80211.value() => Ok(InterfaceType::Wireless80211),
-            x if x == InterfaceType::AsymmetricDsl.value() => Ok(InterfaceType::AsymmetricDsl),
-            x if x == InterfaceType::RateAdaptDsl.value() => Ok(InterfaceType::RateAdaptDsl),
-            x if x == InterfaceType::SymmetricDsl.value() => Ok(InterfaceType::SymmetricDsl),
-            x if x == InterfaceType::VeryHighSpeedDsl.value() => {
-                Ok(InterfaceType::VeryHighSpeedDsl)
+        }
+    }
+
+    #[test]
+    fn test_os_values() {
+        for t in InterfaceType::all() {
+            if let Some(v) = t.to_windows_value() {
+                assert_eq!(InterfaceType::from_windows_value(v), Some(*t));
             }
-            x if x == InterfaceType::IPOverAtm.value() => Ok(InterfaceType::IPOverAtm),
-            x if x == InterfaceType::GigabitEthernet.value() => Ok(InterfaceType::GigabitEthernet),
-            x if x == InterfaceType::Tunnel.value() => Ok(InterfaceType::Tunnel),
-            x if x == InterfaceType::MultiRateSymmetricDsl.value() => {
-                Ok(InterfaceType::MultiRateSymmetricDsl)
+            if let Some(v) = t.to_linux_arphrd() {
+                assert_eq!(InterfaceType::from_linux_arphrd(v), Some(*t));
             }
-            x if x == InterfaceType::HighPerformanceSerialBus.value() => {
-                Ok(InterfaceType::HighPerformanceSerialBus)
+            if let Some(v) = t.to_bsd_ift() {
+                assert_eq!(InterfaceType::from_bsd_ift(v), Some(*t));
             }
-            x if x == InterfaceType::Wman.value() => Ok(InterfaceType::Wman),
-            x if x == InterfaceType::Wwanpp.value() => Ok(InterfaceType::Wwanpp),
-            x if x == InterfaceType::Wwanpp2.value() => Ok(InterfaceType::Wwanpp2),
-            x if x == InterfaceType::Can.value() => Ok(InterfaceType::Can),
-            _ => Err(()),
+        }
+        // Independent of the target OS
+        assert_eq!(
+            InterfaceType::from_windows_value(71),
+            Some(InterfaceType::Wireless80211)
+        );
+        assert_eq!(
+            InterfaceType::from_linux_arphrd(801),
+            Some(InterfaceType::Wireless80211)
+        );
+        assert_eq!(
+            InterfaceType::from_bsd_ift(0x47),
+            Some(InterfaceType::Wireless80211)
+        );
+        assert_eq!(
+            InterfaceType::from_linux_arphrd(6),
+            Some(InterfaceType::TokenRing)
+        );
+        assert_eq!(
+            InterfaceType::from_windows_value(6),
+            Some(InterfaceType::Ethernet)
+        );
+        assert_eq!(InterfaceType::from_linux_arphrd(0xffff), None);
+        assert_eq!(InterfaceType::Can.to_windows_value(), None);
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn test_linux_arphrd_matches_libc() {
+        let expected = [
+            (InterfaceType::Ethernet, libc::ARPHRD_ETHER),
+            (InterfaceType::TokenRing, libc::ARPHRD_IEEE802),
+            (InterfaceType::Fddi, libc::ARPHRD_FDDI),
+            (InterfaceType::Ppp, libc::ARPHRD_PPP),
+            (InterfaceType::Loopback, libc::ARPHRD_LOOPBACK),
+            (InterfaceType::Ethernet3Megabit, libc::ARPHRD_EETHER),
+            (InterfaceType::Slip, libc::ARPHRD_SLIP),
+            (InterfaceType::Atm, libc::ARPHRD_ATM),
+            (InterfaceType::Wireless80211, libc::ARPHRD_IEEE80211),
+            (InterfaceType::Tunnel, libc::ARPHRD_TUNNEL),
+            (InterfaceType::Isdn, libc::ARPHRD_X25),
+            (
+                InterfaceType::HighPerformanceSerialBus,
+                libc::ARPHRD_IEEE1394,
+            ),
+            (InterfaceType::Can, libc::ARPHRD_CAN),
+            (InterfaceType::Infiniband, libc::ARPHRD_INFINIBAND),
+            (InterfaceType::Ieee802154, libc::ARPHRD_IEEE802154),
+        ];
+        for (t, arphrd) in expected {
+            assert_eq!(t.to_linux_arphrd(), Some(arphrd as u32), "{:?}", t);
         }
     }
 }
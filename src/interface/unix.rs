@@ -1,18 +1,49 @@
+use super::AddressInfo;
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+use super::AddressOrigin;
+use super::HardwareAddr;
 use super::Interface;
+#[cfg(feature = "config")]
 use super::MacAddr;
+use super::{DetailLevel, EnumOptions};
+use super::{OffloadInfo, OnLinkPrefix, Qdisc, SriovInfo, TimestampingCaps, TunnelInfo};
+use crate::error::Error;
 use crate::gateway;
 use crate::interface::InterfaceType;
 use crate::ip::{Ipv4Net, Ipv6Net};
+use crate::parse::{self, SockAddrView};
+use crate::stats::{self, InterfaceStats};
 use crate::sys;
+use crate::wol::WolModes;
 use libc;
-use std::ffi::{CStr, CString};
+use std::ffi::CStr;
+use std::io;
 use std::mem::{self, MaybeUninit};
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, UdpSocket};
+use std::os::fd::AsRawFd;
 use std::os::raw::c_char;
 use std::str::from_utf8_unchecked;
 
+#[cfg(not(target_os = "ios"))]
+fn get_dns_servers(options: &EnumOptions) -> Vec<IpAddr> {
+    let mut dns_servers = get_system_dns_conf();
+    dns_servers.retain(|ip| match ip {
+        IpAddr::V4(_) => options.address_families.includes_v4(),
+        IpAddr::V6(_) => options.address_families.includes_v6(),
+    });
+    dns_servers
+}
+
+#[cfg(not(target_os = "ios"))]
 pub fn get_system_dns_conf() -> Vec<IpAddr> {
     use std::fs::read_to_string;
+    use std::net::ToSocketAddrs;
     const PATH_RESOLV_CONF: &str = "/etc/resolv.conf";
     let r = read_to_string(PATH_RESOLV_CONF);
     match r {
@@ -42,184 +73,205 @@ pub fn get_system_dns_conf() -> Vec<IpAddr> {
     }
 }
 
-#[cfg(any(target_os = "macos", target_os = "ios"))]
-pub fn interfaces() -> Vec<Interface> {
-    use super::macos;
+#[cfg(target_os = "ios")]
+pub fn try_interfaces(options: &EnumOptions) -> Result<Vec<Interface>, Error> {
+    use super::ios;
 
-    let type_map = macos::get_if_type_map();
-    let mut interfaces: Vec<Interface> = unix_interfaces();
-    let local_ip: IpAddr = match super::get_local_ipaddr() {
+    let mut interfaces: Vec<Interface> = unix_interfaces(options)?;
+    for iface in &mut interfaces {
+        iface.if_type = ios::get_interface_type(&iface.name, iface.if_type);
+        ios::clear_placeholder_mac(iface);
+    }
+    if options.detail_level < DetailLevel::Standard {
+        return Ok(interfaces);
+    }
+    let local_ip: IpAddr = match super::os_local_ipaddr() {
         Some(local_ip) => local_ip,
-        None => return interfaces,
+        None => return Ok(interfaces),
     };
-    let gateway_map = gateway::macos::get_gateway_map();
+    let gateway_map = gateway::macos::get_gateway_map(options.address_families);
+    for iface in &mut interfaces {
+        if let Some(gateways) = gateway_map.get(&iface.index) {
+            iface.gateways = gateways.clone();
+        }
+        iface.default = iface
+            .ipv4
+            .iter()
+            .any(|ipv4| IpAddr::V4(ipv4.addr) == local_ip)
+            || iface
+                .ipv6
+                .iter()
+                .any(|ipv6| IpAddr::V6(ipv6.addr) == local_ip);
+    }
+    Ok(interfaces)
+}
+
+#[cfg(target_os = "macos")]
+pub fn try_interfaces(options: &EnumOptions) -> Result<Vec<Interface>, Error> {
+    use super::{enrich, macos};
+
+    let mut interfaces: Vec<Interface> = unix_interfaces(options)?;
+    if options.detail_level < DetailLevel::Standard {
+        return Ok(interfaces);
+    }
+    let type_map = macos::get_if_type_map();
     for iface in &mut interfaces {
         if let Some(sc_interface) = type_map.get(&iface.name) {
             iface.if_type = sc_interface.interface_type;
             iface.friendly_name = sc_interface.friendly_name.clone();
         }
-        if let Some(gateway) = gateway_map.get(&iface.index) {
-            iface.gateway = Some(gateway.clone());
+    }
+    if options.detail_level >= DetailLevel::Full {
+        enrich::for_each(&mut interfaces, |iface| {
+            iface.driver = macos::get_driver_info(&iface.name);
+            iface.description = macos::get_description(&iface.name);
+        });
+    }
+    let local_ip: IpAddr = match super::os_local_ipaddr() {
+        Some(local_ip) => local_ip,
+        None => return Ok(interfaces),
+    };
+    let gateway_map = gateway::macos::get_gateway_map(options.address_families);
+    for iface in &mut interfaces {
+        if let Some(gateways) = gateway_map.get(&iface.index) {
+            iface.gateways = gateways.clone();
         }
         iface.ipv4.iter().for_each(|ipv4| {
             if IpAddr::V4(ipv4.addr) == local_ip {
-                iface.dns_servers = get_system_dns_conf();
+                iface.dns_servers = get_dns_servers(options);
                 iface.default = true;
             }
         });
         iface.ipv6.iter().for_each(|ipv6| {
             if IpAddr::V6(ipv6.addr) == local_ip {
-                iface.dns_servers = get_system_dns_conf();
+                iface.dns_servers = get_dns_servers(options);
                 iface.default = true;
             }
         });
     }
-    interfaces
+    Ok(interfaces)
 }
 
 #[cfg(any(target_os = "linux", target_os = "android"))]
-pub fn interfaces() -> Vec<Interface> {
-    use super::linux;
+pub fn try_interfaces(options: &EnumOptions) -> Result<Vec<Interface>, Error> {
+    use super::{enrich, linux};
 
-    let mut interfaces: Vec<Interface> = unix_interfaces();
-    let local_ip: IpAddr = match super::get_local_ipaddr() {
-        Some(local_ip) => local_ip,
-        None => return interfaces,
-    };
-    let gateway_map = gateway::linux::get_gateway_map();
+    let mut interfaces: Vec<Interface> = unix_interfaces(options)?;
+    if options.detail_level >= DetailLevel::Full {
+        enrich::for_each(&mut interfaces, |iface| {
+            iface.if_type = linux::get_interface_type(iface.name.clone());
+            let if_speed: Option<u64> = linux::get_interface_speed(iface.name.clone());
+            iface.transmit_speed = if_speed;
+            iface.receive_speed = if_speed;
+            if !options.atomic_stats || iface.stats.is_none() {
+                iface.stats = stats::get_interface_stats(iface);
+            }
+            iface.driver = linux::get_driver_info(&iface.name);
+            iface.device_path = linux::get_device_path(&iface.name);
+            iface.description = linux::get_description(&iface.name);
+            // Android may deny access to sysfs, keep what netlink reported then
+            iface.carrier_changes =
+                linux::get_carrier_changes(&iface.name).or(iface.carrier_changes);
+            iface.link_up_since = linux::get_link_up_since(iface.index, &iface.name);
+        });
+        #[cfg(all(feature = "networkmanager", target_os = "linux"))]
+        super::network_manager::apply(&mut interfaces);
+        #[cfg(all(feature = "networkd", target_os = "linux"))]
+        super::networkd::apply(&mut interfaces);
+    }
+    if options.detail_level < DetailLevel::Standard {
+        return Ok(interfaces);
+    }
+    let local_ip: Option<IpAddr> = super::os_local_ipaddr();
+    let gateway_map = gateway::linux::get_gateway_map(options.address_families);
     for iface in &mut interfaces {
-        iface.if_type = linux::get_interface_type(iface.name.clone());
-        let if_speed: Option<u64> = linux::get_interface_speed(iface.name.clone());
-        iface.transmit_speed = if_speed;
-        iface.receive_speed = if_speed;
-        if let Some(gateway) = gateway_map.get(&iface.name) {
-            iface.gateway = Some(gateway.clone());
+        let local_ip = match local_ip {
+            Some(local_ip) => local_ip,
+            None => continue,
+        };
+        if let Some(gateways) = gateway_map.get(&iface.index) {
+            iface.gateways = gateways.clone();
         }
         match local_ip {
             IpAddr::V4(local_ipv4) => {
                 if iface.ipv4.iter().any(|x| x.addr == local_ipv4) {
                     iface.default = true;
-                    iface.dns_servers = get_system_dns_conf();
+                    iface.dns_servers = get_dns_servers(options);
                 }
             }
             IpAddr::V6(local_ipv6) => {
                 if iface.ipv6.iter().any(|x| x.addr == local_ipv6) {
                     iface.default = true;
-                    iface.dns_servers = get_system_dns_conf();
+                    iface.dns_servers = get_dns_servers(options);
                 }
             }
         }
     }
-    interfaces
+    Ok(interfaces)
 }
 
 #[cfg(any(target_os = "openbsd", target_os = "freebsd", target_os = "netbsd"))]
-pub fn interfaces() -> Vec<Interface> {
-    let mut interfaces: Vec<Interface> = unix_interfaces();
-    let local_ip: IpAddr = match super::get_local_ipaddr() {
+pub fn try_interfaces(options: &EnumOptions) -> Result<Vec<Interface>, Error> {
+    let mut interfaces: Vec<Interface> = unix_interfaces(options)?;
+    if options.detail_level < DetailLevel::Standard {
+        return Ok(interfaces);
+    }
+    let local_ip: IpAddr = match super::os_local_ipaddr() {
         Some(local_ip) => local_ip,
-        None => return interfaces,
+        None => return Ok(interfaces),
     };
-    let gateway_map = gateway::bsd::get_gateway_map();
+    let gateway_map = gateway::bsd::get_gateway_map(options.address_families);
     for iface in &mut interfaces {
-        if let Some(gateway) = gateway_map.get(&iface.index) {
-            iface.gateway = Some(gateway.clone());
+        if let Some(gateways) = gateway_map.get(&iface.index) {
+            iface.gateways = gateways.clone();
         }
         iface.ipv4.iter().for_each(|ipv4| {
             if IpAddr::V4(ipv4.addr) == local_ip {
-                iface.dns_servers = get_system_dns_conf();
+                iface.dns_servers = get_dns_servers(options);
                 iface.default = true;
             }
         });
         iface.ipv6.iter().for_each(|ipv6| {
             if IpAddr::V6(ipv6.addr) == local_ip {
-                iface.dns_servers = get_system_dns_conf();
+                iface.dns_servers = get_dns_servers(options);
                 iface.default = true;
             }
         });
     }
-    interfaces
+    Ok(interfaces)
 }
 
-#[cfg(any(target_os = "linux", target_os = "android"))]
-pub(super) fn sockaddr_to_network_addr(
-    sa: *mut libc::sockaddr,
-) -> (Option<MacAddr>, Option<IpAddr>) {
-    use std::net::SocketAddr;
-
-    unsafe {
-        if sa.is_null() {
-            (None, None)
-        } else if (*sa).sa_family as libc::c_int == libc::AF_PACKET {
-            let sll: *const libc::sockaddr_ll = mem::transmute(sa);
-            let mac = MacAddr(
-                (*sll).sll_addr[0],
-                (*sll).sll_addr[1],
-                (*sll).sll_addr[2],
-                (*sll).sll_addr[3],
-                (*sll).sll_addr[4],
-                (*sll).sll_addr[5],
-            );
-
-            (Some(mac), None)
-        } else {
-            let addr =
-                sys::sockaddr_to_addr(mem::transmute(sa), mem::size_of::<libc::sockaddr_storage>());
-
-            match addr {
-                Ok(SocketAddr::V4(sa)) => (None, Some(IpAddr::V4(*sa.ip()))),
-                Ok(SocketAddr::V6(sa)) => (None, Some(IpAddr::V6(*sa.ip()))),
-                Err(_) => (None, None),
-            }
+fn sockaddr_to_network_addr(sa: *mut libc::sockaddr) -> (Option<HardwareAddr>, Option<IpAddr>) {
+    let view = match unsafe { sys::sockaddr_bytes(sa) }.map(SockAddrView::new) {
+        Some(Ok(view)) => view,
+        Some(Err(err)) => {
+            debug!("skipping sockaddr: {}", err);
+            return (None, None);
+        }
+        None => return (None, None),
+    };
+    match view.hardware_addr() {
+        Ok(Some(addr)) => return (Some(addr), None),
+        Ok(None) => {}
+        Err(err) => {
+            trace!("skipping link-layer sockaddr: {}", err);
+            return (None, None);
         }
     }
-}
-
-#[cfg(any(
-    target_os = "openbsd",
-    target_os = "freebsd",
-    target_os = "netbsd",
-    target_os = "macos",
-    target_os = "ios"
-))]
-fn sockaddr_to_network_addr(sa: *mut libc::sockaddr) -> (Option<MacAddr>, Option<IpAddr>) {
-    use std::net::SocketAddr;
-
-    unsafe {
-        if sa.is_null() {
+    match view.ip() {
+        Ok(ip) => (None, Some(ip)),
+        Err(err) => {
+            debug!("skipping sockaddr: {}", err);
             (None, None)
-        } else if (*sa).sa_family as libc::c_int == libc::AF_LINK {
-            let nlen: i8 = (*sa).sa_data[3];
-            let alen: i8 = (*sa).sa_data[4];
-            if alen > 0 && alen as u8 + nlen as u8 + 8 <= (*sa).sa_len {
-                let ptr = (*sa).sa_data.as_mut_ptr();
-                let extended =
-                    std::slice::from_raw_parts_mut(ptr, 6 + nlen as usize + alen as usize);
-
-                let mac = MacAddr(
-                    extended[6 + nlen as usize] as u8,
-                    extended[6 + nlen as usize + 1] as u8,
-                    extended[6 + nlen as usize + 2] as u8,
-                    extended[6 + nlen as usize + 3] as u8,
-                    extended[6 + nlen as usize + 4] as u8,
-                    extended[6 + nlen as usize + 5] as u8,
-                );
-                return (Some(mac), None);
-            }
-            (None, None)
-        } else {
-            let addr =
-                sys::sockaddr_to_addr(mem::transmute(sa), mem::size_of::<libc::sockaddr_storage>());
-
-            match addr {
-                Ok(SocketAddr::V4(sa)) => (None, Some(IpAddr::V4(*sa.ip()))),
-                Ok(SocketAddr::V6(sa)) => (None, Some(IpAddr::V6(*sa.ip()))),
-                Err(_) => (None, None),
-            }
         }
     }
 }
 
+fn sockaddr_to_netmask(sa: *mut libc::sockaddr, ip: Option<IpAddr>) -> Option<IpAddr> {
+    let buf = unsafe { sys::sockaddr_bytes(sa) }?;
+    Some(parse::netmask(buf, ip?.is_ipv6()))
+}
+
 #[cfg(any(
     target_os = "macos",
     target_os = "ios",
@@ -241,10 +293,366 @@ fn get_interface_type(_addr_ref: &libc::ifaddrs) -> InterfaceType {
     InterfaceType::Unknown
 }
 
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+fn get_interface_stats(addr_ref: &libc::ifaddrs, _options: &EnumOptions) -> Option<InterfaceStats> {
+    stats::stats_from_ifaddrs(addr_ref)
+}
+
+// Counters are read once the interface list is complete, from sysfs or from
+// a single link dump.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn get_interface_stats(
+    _addr_ref: &libc::ifaddrs,
+    _options: &EnumOptions,
+) -> Option<InterfaceStats> {
+    None
+}
+
 pub fn is_running(interface: &Interface) -> bool {
     interface.flags & (crate::sys::IFF_RUNNING as u32) != 0
 }
 
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn get_offload_info(interface: &Interface) -> io::Result<OffloadInfo> {
+    super::linux::get_offload_info(&interface.name)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn get_timestamping_caps(interface: &Interface) -> io::Result<TimestampingCaps> {
+    super::linux::get_timestamping_caps(&interface.name)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn get_sriov_info(interface: &Interface) -> io::Result<Option<SriovInfo>> {
+    super::linux::get_sriov_info(&interface.name, interface.index)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn get_tunnel_info(interface: &Interface) -> io::Result<Option<TunnelInfo>> {
+    super::linux::get_tunnel_info(interface.index)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn get_qdiscs(interface: &Interface) -> io::Result<Vec<Qdisc>> {
+    super::linux::get_qdiscs(interface.index)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn get_wol_modes(interface: &Interface) -> io::Result<WolModes> {
+    super::linux::get_wol_modes(&interface.name)
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+pub fn get_offload_info(_interface: &Interface) -> io::Result<OffloadInfo> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Offload information is not supported on this platform",
+    ))
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+pub fn get_timestamping_caps(_interface: &Interface) -> io::Result<TimestampingCaps> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Timestamping capabilities are not supported on this platform",
+    ))
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+pub fn get_sriov_info(_interface: &Interface) -> io::Result<Option<SriovInfo>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "SR-IOV information is not supported on this platform",
+    ))
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+pub fn get_tunnel_info(_interface: &Interface) -> io::Result<Option<TunnelInfo>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Tunnel parameters are not supported on this platform",
+    ))
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+pub fn get_qdiscs(_interface: &Interface) -> io::Result<Vec<Qdisc>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Queueing disciplines are not supported on this platform",
+    ))
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+pub fn get_wol_modes(_interface: &Interface) -> io::Result<WolModes> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Wake-on-LAN information is not supported on this platform",
+    ))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn get_address_info(interface: &Interface) -> io::Result<Vec<AddressInfo>> {
+    super::linux::get_address_info(interface.index)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn get_on_link_prefixes(interface: &Interface) -> io::Result<Vec<OnLinkPrefix>> {
+    super::linux::get_on_link_prefixes(interface.index)
+}
+
+#[cfg(all(feature = "config", any(target_os = "linux", target_os = "android")))]
+pub fn probe_ipv4_conflict(interface: &Interface, addr: Ipv4Addr) -> io::Result<Option<MacAddr>> {
+    let mac_addr = interface.mac_addr.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} has no MAC address", interface.name),
+        )
+    })?;
+    super::linux::probe_ipv4_conflict(interface.index, mac_addr, addr)
+}
+
+#[cfg(all(feature = "config", any(target_os = "linux", target_os = "android")))]
+pub fn restart_dad(interface: &Interface, addr: Ipv6Addr) -> io::Result<()> {
+    super::linux::restart_dad(interface.index, addr)
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+pub fn get_address_info(_interface: &Interface) -> io::Result<Vec<AddressInfo>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Address state is not supported on this platform",
+    ))
+}
+
+/// The routing socket does not say where a route came from, so only
+/// link-local prefixes get a specific origin.
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+pub fn get_on_link_prefixes(interface: &Interface) -> io::Result<Vec<OnLinkPrefix>> {
+    let mut prefixes: Vec<OnLinkPrefix> = Vec::new();
+    for route in crate::route::os_routes()? {
+        if route.gateway.is_some()
+            || route.if_index != Some(interface.index)
+            || !super::is_on_link_subnet(&route.destination)
+        {
+            continue;
+        }
+        let prefix = route.destination.trunc();
+        if prefixes.iter().any(|other| other.prefix == prefix) {
+            continue;
+        }
+        let origin = match prefix.addr() {
+            IpAddr::V6(addr) if crate::zone::is_link_local(&addr) => AddressOrigin::LinkLocal,
+            IpAddr::V4(addr) if addr.is_link_local() => AddressOrigin::LinkLocal,
+            _ => AddressOrigin::Other,
+        };
+        prefixes.push(OnLinkPrefix {
+            prefix,
+            valid_lifetime: None,
+            origin,
+        });
+    }
+    Ok(prefixes)
+}
+
+#[cfg(all(
+    feature = "config",
+    any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "openbsd",
+        target_os = "freebsd",
+        target_os = "netbsd"
+    )
+))]
+pub fn probe_ipv4_conflict(_interface: &Interface, _addr: Ipv4Addr) -> io::Result<Option<MacAddr>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "ARP probing is not supported on this platform",
+    ))
+}
+
+#[cfg(all(
+    feature = "config",
+    any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "openbsd",
+        target_os = "freebsd",
+        target_os = "netbsd"
+    )
+))]
+pub fn restart_dad(_interface: &Interface, _addr: Ipv6Addr) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Restarting DAD is not supported on this platform",
+    ))
+}
+
+fn set_ip_option<T>(socket: &UdpSocket, option: libc::c_int, value: &T) -> io::Result<()> {
+    let ret = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_IP,
+            option,
+            value as *const T as *const libc::c_void,
+            mem::size_of::<T>() as libc::socklen_t,
+        )
+    };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+// These kernels accept `ip_mreqn`, which selects the interface by index.
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+pub fn multicast_membership_v4(
+    interface: &Interface,
+    socket: &UdpSocket,
+    group: Ipv4Addr,
+    join: bool,
+) -> io::Result<()> {
+    let mreq = libc::ip_mreqn {
+        imr_multiaddr: libc::in_addr {
+            s_addr: u32::from_ne_bytes(group.octets()),
+        },
+        imr_address: libc::in_addr { s_addr: 0 },
+        imr_ifindex: interface.index as libc::c_int,
+    };
+    let option = if join {
+        libc::IP_ADD_MEMBERSHIP
+    } else {
+        libc::IP_DROP_MEMBERSHIP
+    };
+    set_ip_option(socket, option, &mreq)
+}
+
+// Elsewhere `ip_mreq` identifies the interface by one of its IPv4 addresses.
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "netbsd"
+))]
+pub fn multicast_membership_v4(
+    interface: &Interface,
+    socket: &UdpSocket,
+    group: Ipv4Addr,
+    join: bool,
+) -> io::Result<()> {
+    let local = interface.ipv4.first().map(|net| net.addr).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::AddrNotAvailable,
+            format!("{} has no IPv4 address", interface.name),
+        )
+    })?;
+    let mreq = libc::ip_mreq {
+        imr_multiaddr: libc::in_addr {
+            s_addr: u32::from_ne_bytes(group.octets()),
+        },
+        imr_interface: libc::in_addr {
+            s_addr: u32::from_ne_bytes(local.octets()),
+        },
+    };
+    let option = if join {
+        libc::IP_ADD_MEMBERSHIP
+    } else {
+        libc::IP_DROP_MEMBERSHIP
+    };
+    set_ip_option(socket, option, &mreq)
+}
+
+pub fn set_multicast_if_v4(interface: &Interface, socket: &UdpSocket) -> io::Result<()> {
+    let local = interface.ipv4.first().map(|net| net.addr).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::AddrNotAvailable,
+            format!("{} has no IPv4 address", interface.name),
+        )
+    })?;
+    let addr = libc::in_addr {
+        s_addr: u32::from_ne_bytes(local.octets()),
+    };
+    set_ip_option(socket, libc::IP_MULTICAST_IF, &addr)
+}
+
+pub fn get_promiscuous_mode(interface: &Interface) -> io::Result<bool> {
+    let mut req = sys::IfReqFlags::new(&interface.name)?;
+    sys::ifreq_ioctl(sys::SIOCGIFFLAGS, &mut req)?;
+    Ok(req.flags() & (sys::IFF_PROMISC as u32) != 0)
+}
+
+#[cfg(feature = "config")]
+pub fn set_promiscuous_mode(interface: &Interface, enabled: bool) -> io::Result<()> {
+    #[cfg(target_os = "freebsd")]
+    let flag: u32 = sys::IFF_PPROMISC;
+    #[cfg(not(target_os = "freebsd"))]
+    let flag: u32 = sys::IFF_PROMISC as u32;
+
+    let mut req = sys::IfReqFlags::new(&interface.name)?;
+    sys::ifreq_ioctl(sys::SIOCGIFFLAGS, &mut req)?;
+    let flags = if enabled {
+        req.flags() | flag
+    } else {
+        req.flags() & !flag
+    };
+    req.set_flags(flags);
+    sys::ifreq_ioctl(sys::SIOCSIFFLAGS, &mut req)
+}
+
 #[cfg(any(
     target_os = "macos",
     target_os = "ios",
@@ -264,40 +672,96 @@ pub fn is_physical_interface(interface: &Interface) -> bool {
 }
 
 #[cfg(target_os = "android")]
-pub fn unix_interfaces() -> Vec<Interface> {
+pub fn unix_interfaces(options: &EnumOptions) -> Result<Vec<Interface>, Error> {
     use super::android;
 
     if let Some((getifaddrs, freeifaddrs)) = android::get_libc_ifaddrs() {
-        return unix_interfaces_inner(getifaddrs, freeifaddrs);
+        return unix_interfaces_inner(getifaddrs, freeifaddrs, options);
     }
 
-    android::netlink::unix_interfaces()
+    let mut ifaces = android::netlink::unix_interfaces();
+    ifaces.retain(|iface| options.accepts_flags(iface.flags) && options.accepts_name(&iface.name));
+    let netns = super::linux::current_netns();
+    for iface in &mut ifaces {
+        iface.netns = netns;
+        if !options.atomic_stats {
+            iface.stats = None;
+        }
+        if !options.address_families.includes_v4() {
+            iface.ipv4.clear();
+        }
+        if !options.address_families.includes_v6() {
+            iface.ipv6.clear();
+        }
+    }
+    Ok(ifaces)
 }
 
 #[cfg(not(target_os = "android"))]
-pub fn unix_interfaces() -> Vec<Interface> {
-    unix_interfaces_inner(libc::getifaddrs, libc::freeifaddrs)
+pub fn unix_interfaces(options: &EnumOptions) -> Result<Vec<Interface>, Error> {
+    unix_interfaces_inner(libc::getifaddrs, libc::freeifaddrs, options)
+}
+
+// Skip IP address entries of families that were not requested before decoding them.
+// Link-layer entries are always kept since they carry the MAC address.
+fn accepts_address_family(sa: *const libc::sockaddr, options: &EnumOptions) -> bool {
+    if sa.is_null() {
+        return true;
+    }
+    match unsafe { (*sa).sa_family } as libc::c_int {
+        libc::AF_INET => options.address_families.includes_v4(),
+        libc::AF_INET6 => options.address_families.includes_v6(),
+        _ => true,
+    }
 }
 
 fn unix_interfaces_inner(
     getifaddrs: unsafe extern "C" fn(*mut *mut libc::ifaddrs) -> libc::c_int,
     freeifaddrs: unsafe extern "C" fn(*mut libc::ifaddrs),
-) -> Vec<Interface> {
+    options: &EnumOptions,
+) -> Result<Vec<Interface>, Error> {
+    let _span = debug_span!("getifaddrs");
     let mut ifaces: Vec<Interface> = vec![];
     let mut addrs: MaybeUninit<*mut libc::ifaddrs> = MaybeUninit::uninit();
     if unsafe { getifaddrs(addrs.as_mut_ptr()) } != 0 {
-        return ifaces;
+        let err = Error::last_os_error("getifaddrs");
+        debug!("{}", err);
+        return Err(err);
     }
     let addrs = unsafe { addrs.assume_init() };
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let netns = super::linux::current_netns();
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let netns = None;
     let mut addr = addrs;
     while !addr.is_null() {
         let addr_ref: &libc::ifaddrs = unsafe { &*addr };
-        let if_type = get_interface_type(addr_ref);
         let c_str = addr_ref.ifa_name as *const c_char;
         let bytes = unsafe { CStr::from_ptr(c_str).to_bytes() };
         let name = unsafe { from_utf8_unchecked(bytes).to_owned() };
-        let (mac, ip) = sockaddr_to_network_addr(addr_ref.ifa_addr as *mut libc::sockaddr);
-        let (_, netmask) = sockaddr_to_network_addr(addr_ref.ifa_netmask as *mut libc::sockaddr);
+        if !options.accepts_flags(addr_ref.ifa_flags)
+            || !accepts_address_family(addr_ref.ifa_addr, options)
+            || !options.accepts_name(&name)
+        {
+            trace!("{}: entry filtered out by enumeration options", name);
+            addr = addr_ref.ifa_next;
+            continue;
+        }
+        // Names are only unique within a namespace, so entries are merged by
+        // index. The index is 0 if the interface went away in the meantime.
+        let index = unsafe { libc::if_nametoindex(c_str) };
+        if index == 0 {
+            debug!(
+                "if_nametoindex({}) failed: {}",
+                name,
+                io::Error::last_os_error()
+            );
+        }
+        let if_type = get_interface_type(addr_ref);
+        let stats = get_interface_stats(addr_ref, options);
+        let (hardware_addr, ip) = sockaddr_to_network_addr(addr_ref.ifa_addr);
+        let mac = hardware_addr.as_ref().and_then(HardwareAddr::mac_addr);
+        let netmask = sockaddr_to_netmask(addr_ref.ifa_netmask, ip);
         let mut ini_ipv4: Vec<Ipv4Net> = vec![];
         let mut ini_ipv6: Vec<Ipv6Net> = vec![];
         if let Some(ip) = ip {
@@ -327,27 +791,42 @@ fn unix_interfaces_inner(
             }
         }
         let interface: Interface = Interface {
-            index: 0,
+            index,
             name: name.clone(),
             friendly_name: None,
             description: None,
-            if_type: if_type,
-            mac_addr: mac.clone(),
+            if_type,
+            mac_addr: mac,
+            hardware_addr: hardware_addr.clone(),
             ipv4: ini_ipv4,
             ipv6: ini_ipv6,
             flags: addr_ref.ifa_flags,
             transmit_speed: None,
             receive_speed: None,
-            gateway: None,
+            gateways: Vec::new(),
             dns_servers: Vec::new(),
             default: false,
+            stats: stats.clone(),
+            driver: None,
+            device_path: None,
+            carrier_changes: None,
+            link_up_since: None,
+            network_manager: None,
+            networkd: None,
+            netns,
         };
         let mut found: bool = false;
         for iface in &mut ifaces {
-            if name == iface.name {
-                if let Some(mac) = mac.clone() {
+            if iface.index == index && (index != 0 || iface.name == name) {
+                if let Some(mac) = mac {
                     iface.mac_addr = Some(mac);
                 }
+                if hardware_addr.is_some() {
+                    iface.hardware_addr = hardware_addr.clone();
+                }
+                if stats.is_some() {
+                    iface.stats = stats.clone();
+                }
                 if let Some(ip) = ip {
                     match ip {
                         IpAddr::V4(ipv4) => {
@@ -385,13 +864,14 @@ fn unix_interfaces_inner(
     unsafe {
         freeifaddrs(addrs);
     }
-    for iface in &mut ifaces {
-        let name = CString::new(iface.name.as_bytes()).unwrap();
-        unsafe {
-            iface.index = libc::if_nametoindex(name.as_ptr());
+    debug!("getifaddrs returned {} interfaces", ifaces.len());
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if options.atomic_stats {
+        if let Err(e) = stats::add_link_stats(&mut ifaces) {
+            debug!("RTM_GETLINK dump for the counters failed: {}", e);
         }
     }
-    ifaces
+    Ok(ifaces)
 }
 
 #[cfg(test)]
@@ -399,7 +879,7 @@ mod tests {
     use super::*;
     #[test]
     fn test_unix_interfaces() {
-        let interfaces = interfaces();
+        let interfaces = try_interfaces(&EnumOptions::default()).unwrap();
         for interface in interfaces {
             println!("{:#?}", interface);
         }
@@ -1,20 +1,58 @@
 use std::convert::TryFrom;
-use std::net::{IpAddr, Ipv4Addr};
-use windows_sys::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, NO_ERROR};
+use std::ffi::c_void;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, UdpSocket};
+use std::os::windows::io::AsRawSocket;
+use std::time::{Duration, SystemTime};
+use windows_sys::Win32::Foundation::{
+    CloseHandle, ERROR_BUFFER_OVERFLOW, INVALID_HANDLE_VALUE, NO_ERROR,
+};
+#[cfg(feature = "config")]
+use windows_sys::Win32::NetworkManagement::IpHelper::ResolveIpNetEntry2;
 use windows_sys::Win32::NetworkManagement::IpHelper::{
-    GetAdaptersAddresses, GetIfEntry2, SendARP, GAA_FLAG_INCLUDE_GATEWAYS, IP_ADAPTER_ADDRESSES_LH,
-    MIB_IF_ROW2, MIB_IF_ROW2_0,
+    FreeMibTable, GetAdaptersAddresses, GetIfEntry, GetIfEntry2,
+    GetInterfaceSupportedTimestampCapabilities, GetIpNetEntry2, GetUnicastIpAddressTable, SendARP,
+    GAA_FLAG_INCLUDE_ALL_INTERFACES, GAA_FLAG_INCLUDE_GATEWAYS, GAA_FLAG_INCLUDE_PREFIX,
+    GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER, GAA_FLAG_SKIP_MULTICAST,
+    INTERFACE_TIMESTAMP_CAPABILITIES, IP_ADAPTER_ADDRESSES_LH, MIB_IFROW, MIB_IF_ROW2,
+    MIB_IF_ROW2_0, MIB_IPNET_ROW2, MIB_UNICASTIPADDRESS_TABLE,
+};
+use windows_sys::Win32::NetworkManagement::Ndis::{
+    NDIS_OFFLOAD, NDIS_PACKET_TYPE_PROMISCUOUS, NDIS_PNP_WAKE_UP_LINK_CHANGE,
+    NDIS_PNP_WAKE_UP_MAGIC_PACKET, NDIS_PNP_WAKE_UP_PATTERN_MATCH, NET_IF_OPER_STATUS_UP,
+    NET_LUID_LH, OID_GEN_CURRENT_PACKET_FILTER, OID_PNP_CAPABILITIES, OID_PNP_ENABLE_WAKE_UP,
+    OID_TCP_OFFLOAD_CURRENT_CONFIG,
 };
-use windows_sys::Win32::NetworkManagement::Ndis::NET_IF_OPER_STATUS_UP;
 use windows_sys::Win32::Networking::WinSock::{
-    AF_INET, AF_INET6, AF_UNSPEC, SOCKADDR_INET, SOCKET_ADDRESS,
+    setsockopt, IpDadStateDeprecated, IpDadStateDuplicate, IpDadStatePreferred,
+    IpDadStateTentative, IpPrefixOriginDhcp, IpPrefixOriginManual,
+    IpPrefixOriginRouterAdvertisement, IpSuffixOriginDhcp, IpSuffixOriginManual,
+    IpSuffixOriginRandom, AF_INET, AF_INET6, AF_UNSPEC, IPPROTO_IP, IP_MULTICAST_IF, NL_DAD_STATE,
+    NL_PREFIX_ORIGIN, NL_SUFFIX_ORIGIN, SOCKADDR_INET, SOCKET, SOCKET_ADDRESS,
+};
+use windows_sys::Win32::Storage::FileSystem::{
+    CreateFileW, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
 };
+use windows_sys::Win32::System::Registry::{
+    RegCloseKey, RegEnumKeyExW, RegOpenKeyExW, RegQueryValueExW, HKEY, HKEY_LOCAL_MACHINE,
+    KEY_READ, REG_SZ, REG_VALUE_TYPE,
+};
+use windows_sys::Win32::System::SystemInformation::GetTickCount64;
+use windows_sys::Win32::System::IO::DeviceIoControl;
 
-use crate::device::NetworkDevice;
-use crate::interface::{Interface, InterfaceType};
-use crate::ip::{Ipv4Net, Ipv6Net};
-use crate::mac::MacAddr;
+use crate::error::Error;
+use crate::gateway;
+use crate::interface::{
+    AddressFamily, AddressInfo, AddressOrigin, DadState, DetailLevel, DriverInfo, EnumOptions,
+    Interface, InterfaceType, OffloadInfo, OnLinkPrefix, Qdisc, SriovInfo, TimestampingCaps,
+    TunnelInfo,
+};
+use crate::ip::{IpNet, Ipv4Net, Ipv6Net};
+use crate::mac::{HardwareAddr, MacAddr};
+use crate::stats;
 use crate::sys;
+use crate::wol::{WolMode, WolModes};
+use std::collections::HashMap;
 use std::ffi::CStr;
 use std::mem::MaybeUninit;
 
@@ -48,6 +86,21 @@ fn get_mac_through_arp(src_ip: Ipv4Addr, dst_ip: Ipv4Addr) -> MacAddr {
     }
 }
 
+/// Look up an IPv6 neighbour in the cache, without sending a solicitation
+fn get_neighbour_mac(if_index: u32, addr: Ipv6Addr) -> MacAddr {
+    let mut row: MIB_IPNET_ROW2 = unsafe { std::mem::zeroed() };
+    row.InterfaceIndex = if_index;
+    row.Address.Ipv6.sin6_family = AF_INET6;
+    row.Address.Ipv6.sin6_addr.u.Byte = addr.octets();
+    let ret = unsafe { GetIpNetEntry2(&mut row) };
+    if ret != NO_ERROR || row.PhysicalAddressLength != 6 {
+        return MacAddr::zero();
+    }
+    let mut octets = [0u8; 6];
+    octets.copy_from_slice(&row.PhysicalAddress[..6]);
+    MacAddr::from_octets(octets)
+}
+
 unsafe fn socket_address_to_ipaddr(addr: &SOCKET_ADDRESS) -> Option<IpAddr> {
     let sockaddr = addr.lpSockaddr.cast::<SOCKADDR_INET>().as_ref()?;
 
@@ -81,6 +134,408 @@ fn is_connector_present(if_index: u32) -> bool {
     oper_status_flags._bitfield & IFF_CONNECTOR_PRESENT != 0
 }
 
+/// Time the interface entered its current operational state, from the
+/// `dwLastChange` of its MIB-II row, which holds the 32-bit tick count of the change
+fn get_last_change(if_index: u32) -> Option<SystemTime> {
+    let mut row: MIB_IFROW = unsafe { std::mem::zeroed() };
+    row.dwIndex = if_index;
+    if unsafe { GetIfEntry(&mut row) } != NO_ERROR {
+        return None;
+    }
+    let ago = ticks_since(unsafe { GetTickCount64() }, row.dwLastChange);
+    SystemTime::now().checked_sub(Duration::from_millis(ago))
+}
+
+/// Milliseconds from the 32-bit tick count `then` to the 64-bit tick count `now`.
+///
+/// The 32-bit count wraps every 49.7 days, so a change longer ago than that is
+/// taken to be the most recent time with the same tick count.
+fn ticks_since(now: u64, then: u32) -> u64 {
+    ((now as u32).wrapping_sub(then) as u64).min(now)
+}
+
+/// Get the LUID (Locally Unique Identifier) of the network interface with the given index
+pub fn get_interface_luid(if_index: u32) -> Option<u64> {
+    super::index_to_luid(if_index).ok()
+}
+
+/// Query an NDIS OID through the adapter device, returning the number of bytes written
+fn query_ndis_oid(interface: &Interface, oid: u32, buf: &mut [u8]) -> io::Result<usize> {
+    // The adapter name is the device GUID, which NDIS exposes as \\.\{GUID}
+    let path: Vec<u16> = format!("\\\\.\\{}", interface.name)
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let handle = unsafe {
+        CreateFileW(
+            path.as_ptr(),
+            0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            std::ptr::null(),
+            OPEN_EXISTING,
+            0,
+            0,
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return Err(io::Error::last_os_error());
+    }
+    let mut returned: u32 = 0;
+    let ok = unsafe {
+        DeviceIoControl(
+            handle,
+            sys::IOCTL_NDIS_QUERY_GLOBAL_STATS,
+            &oid as *const u32 as *const c_void,
+            std::mem::size_of::<u32>() as u32,
+            buf.as_mut_ptr() as *mut c_void,
+            buf.len() as u32,
+            &mut returned,
+            std::ptr::null_mut(),
+        )
+    };
+    let err = io::Error::last_os_error();
+    unsafe { CloseHandle(handle) };
+    if ok == 0 {
+        return Err(err);
+    }
+    Ok(returned as usize)
+}
+
+pub fn get_promiscuous_mode(interface: &Interface) -> io::Result<bool> {
+    let mut buf = [0u8; 4];
+    query_ndis_oid(interface, OID_GEN_CURRENT_PACKET_FILTER, &mut buf)?;
+    Ok(u32::from_ne_bytes(buf) & NDIS_PACKET_TYPE_PROMISCUOUS != 0)
+}
+
+pub fn get_offload_info(interface: &Interface) -> io::Result<OffloadInfo> {
+    // Newer NDIS revisions append fields, so leave room beyond the base structure.
+    let mut buf = [0u8; 512];
+    let len = query_ndis_oid(interface, OID_TCP_OFFLOAD_CURRENT_CONFIG, &mut buf)?;
+    if len < std::mem::size_of::<NDIS_OFFLOAD>() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "NDIS_OFFLOAD response is too short",
+        ));
+    }
+    let offload: NDIS_OFFLOAD = unsafe { std::ptr::read_unaligned(buf.as_ptr() as *const _) };
+    // TcpChecksum, UdpChecksum and IpChecksum are the 2-bit fields at bits 4..10.
+    const CHECKSUM_BITS: u32 = 0x3f0;
+    let checksum = &offload.Checksum;
+    Ok(OffloadInfo {
+        rx_checksum: Some(
+            checksum.IPv4Receive._bitfield & CHECKSUM_BITS != 0
+                || checksum.IPv6Receive._bitfield & CHECKSUM_BITS != 0,
+        ),
+        tx_checksum: Some(
+            checksum.IPv4Transmit._bitfield & CHECKSUM_BITS != 0
+                || checksum.IPv6Transmit._bitfield & CHECKSUM_BITS != 0,
+        ),
+        tso: Some(
+            offload.LsoV1.IPv4.MaxOffLoadSize != 0
+                || offload.LsoV2.IPv4.MaxOffLoadSize != 0
+                || offload.LsoV2.IPv6.MaxOffLoadSize != 0,
+        ),
+        gso: None,
+        gro: None,
+        lro: None,
+    })
+}
+
+pub fn get_timestamping_caps(interface: &Interface) -> io::Result<TimestampingCaps> {
+    use crate::interface::{
+        HWTSTAMP_FILTER_ALL, HWTSTAMP_FILTER_PTP_V2_EVENT, HWTSTAMP_TX_ON,
+        SOF_TIMESTAMPING_RAW_HARDWARE, SOF_TIMESTAMPING_RX_HARDWARE, SOF_TIMESTAMPING_RX_SOFTWARE,
+        SOF_TIMESTAMPING_SOFTWARE, SOF_TIMESTAMPING_TX_HARDWARE, SOF_TIMESTAMPING_TX_SOFTWARE,
+    };
+
+    let luid = NET_LUID_LH {
+        Value: super::index_to_luid(interface.index)?,
+    };
+    let mut caps: INTERFACE_TIMESTAMP_CAPABILITIES = unsafe { std::mem::zeroed() };
+    let ret = unsafe { GetInterfaceSupportedTimestampCapabilities(&luid, &mut caps) };
+    if ret != NO_ERROR {
+        return Err(io::Error::from_raw_os_error(ret as i32));
+    }
+    // Map to the Linux SOF_TIMESTAMPING_* / HWTSTAMP_* representation
+    let hw = &caps.HardwareCapabilities;
+    let sw = &caps.SoftwareCapabilities;
+    let mut result = TimestampingCaps::default();
+    if hw.AllTransmit != 0 || hw.TaggedTransmit != 0 {
+        result.flags |= SOF_TIMESTAMPING_TX_HARDWARE | SOF_TIMESTAMPING_RAW_HARDWARE;
+        result.tx_types |= 1 << HWTSTAMP_TX_ON;
+    }
+    let ptp_rx =
+        hw.PtpV2OverUdpIPv4EventMessageReceive != 0 || hw.PtpV2OverUdpIPv6EventMessageReceive != 0;
+    if hw.AllReceive != 0 || ptp_rx {
+        result.flags |= SOF_TIMESTAMPING_RX_HARDWARE | SOF_TIMESTAMPING_RAW_HARDWARE;
+    }
+    if hw.AllReceive != 0 {
+        result.rx_filters |= 1 << HWTSTAMP_FILTER_ALL;
+    }
+    if ptp_rx {
+        result.rx_filters |= 1 << HWTSTAMP_FILTER_PTP_V2_EVENT;
+    }
+    if sw.AllTransmit != 0 || sw.TaggedTransmit != 0 {
+        result.flags |= SOF_TIMESTAMPING_TX_SOFTWARE | SOF_TIMESTAMPING_SOFTWARE;
+    }
+    if sw.AllReceive != 0 {
+        result.flags |= SOF_TIMESTAMPING_RX_SOFTWARE | SOF_TIMESTAMPING_SOFTWARE;
+    }
+    Ok(result)
+}
+
+pub fn get_sriov_info(_interface: &Interface) -> io::Result<Option<SriovInfo>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "SR-IOV information is not supported on this platform",
+    ))
+}
+
+pub fn get_tunnel_info(_interface: &Interface) -> io::Result<Option<TunnelInfo>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Tunnel parameters are not supported on this platform",
+    ))
+}
+
+pub fn get_qdiscs(_interface: &Interface) -> io::Result<Vec<Qdisc>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Queueing disciplines are not supported on this platform",
+    ))
+}
+
+pub fn get_wol_modes(interface: &Interface) -> io::Result<WolModes> {
+    // NDIS_PNP_CAPABILITIES: Flags, then the lowest device power state from which
+    // each event can wake the system. NdisDeviceStateUnspecified (0) means never.
+    let mut caps = [0u8; 16];
+    query_ndis_oid(interface, OID_PNP_CAPABILITIES, &mut caps)?;
+    let state = |offset: usize| i32::from_ne_bytes(caps[offset..offset + 4].try_into().unwrap());
+    let wake_modes = [
+        (
+            NDIS_PNP_WAKE_UP_MAGIC_PACKET,
+            WolMode::MagicPacket,
+            state(4),
+        ),
+        (NDIS_PNP_WAKE_UP_PATTERN_MATCH, WolMode::Filter, state(8)),
+        (NDIS_PNP_WAKE_UP_LINK_CHANGE, WolMode::Phy, state(12)),
+    ];
+    let mut buf = [0u8; 4];
+    query_ndis_oid(interface, OID_PNP_ENABLE_WAKE_UP, &mut buf)?;
+    let enabled = u32::from_ne_bytes(buf);
+    Ok(WolModes {
+        supported: wake_modes
+            .iter()
+            .filter(|(_, _, state)| *state != 0)
+            .map(|(_, mode, _)| *mode)
+            .collect(),
+        enabled: wake_modes
+            .iter()
+            .filter(|(bit, _, _)| enabled & bit != 0)
+            .map(|(_, mode, _)| *mode)
+            .collect(),
+    })
+}
+
+#[cfg(feature = "config")]
+pub fn set_promiscuous_mode(_interface: &Interface, _enabled: bool) -> io::Result<()> {
+    // OID_GEN_CURRENT_PACKET_FILTER can only be set through an NDIS protocol
+    // or filter driver (e.g. Npcap), not from user mode.
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Setting promiscuous mode requires an NDIS driver on Windows",
+    ))
+}
+
+fn dad_state(state: NL_DAD_STATE) -> Option<DadState> {
+    match state {
+        s if s == IpDadStateTentative => Some(DadState::Tentative),
+        s if s == IpDadStateDuplicate => Some(DadState::Duplicate),
+        s if s == IpDadStateDeprecated => Some(DadState::Deprecated),
+        s if s == IpDadStatePreferred => Some(DadState::Preferred),
+        _ => None,
+    }
+}
+
+/// Map the origins of the prefix and interface identifier parts of an address
+fn address_origin(
+    addr: IpAddr,
+    prefix_origin: NL_PREFIX_ORIGIN,
+    suffix_origin: NL_SUFFIX_ORIGIN,
+) -> AddressOrigin {
+    let link_local = match addr {
+        IpAddr::V4(addr) => addr.is_link_local(),
+        IpAddr::V6(addr) => crate::zone::is_link_local(&addr),
+    };
+    if link_local {
+        AddressOrigin::LinkLocal
+    } else if suffix_origin == IpSuffixOriginRandom {
+        AddressOrigin::Random
+    } else if prefix_origin == IpPrefixOriginDhcp || suffix_origin == IpSuffixOriginDhcp {
+        AddressOrigin::Dhcp
+    } else if prefix_origin == IpPrefixOriginRouterAdvertisement {
+        AddressOrigin::RouterAdvertisement
+    } else if prefix_origin == IpPrefixOriginManual || suffix_origin == IpSuffixOriginManual {
+        AddressOrigin::Manual
+    } else {
+        AddressOrigin::Other
+    }
+}
+
+/// Convert a lifetime in seconds, where `0xffffffff` means infinite
+fn lifetime(secs: u32) -> Option<Duration> {
+    match secs {
+        u32::MAX => None,
+        secs => Some(Duration::from_secs(secs as u64)),
+    }
+}
+
+pub fn get_address_info(interface: &Interface) -> io::Result<Vec<AddressInfo>> {
+    let mut table: *mut MIB_UNICASTIPADDRESS_TABLE = std::ptr::null_mut();
+    let ret = unsafe { GetUnicastIpAddressTable(AF_UNSPEC, &mut table) };
+    if ret != NO_ERROR {
+        return Err(io::Error::from_raw_os_error(ret as i32));
+    }
+    let rows = unsafe {
+        std::slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize)
+    };
+    let mut addrs = Vec::new();
+    for row in rows {
+        if row.InterfaceIndex != interface.index {
+            continue;
+        }
+        let addr: IpAddr = match unsafe { row.Address.si_family } {
+            AF_INET => unsafe { row.Address.Ipv4.sin_addr.S_un.S_addr }
+                .to_ne_bytes()
+                .into(),
+            AF_INET6 => unsafe { row.Address.Ipv6.sin6_addr.u.Byte }.into(),
+            _ => continue,
+        };
+        addrs.push(AddressInfo {
+            addr,
+            prefix_len: row.OnLinkPrefixLength,
+            dad_state: dad_state(row.DadState),
+            valid_lifetime: lifetime(row.ValidLifetime),
+            preferred_lifetime: lifetime(row.PreferredLifetime),
+            label: None,
+            secondary: false,
+            origin: address_origin(addr, row.PrefixOrigin, row.SuffixOrigin),
+            temporary: false,
+        });
+    }
+    unsafe { FreeMibTable(table as *const _) };
+    mark_temporary(&mut addrs);
+    Ok(addrs)
+}
+
+/// Flag the temporary addresses among the autoconfigured IPv6 ones.
+///
+/// Windows reports a random interface identifier for temporary addresses, but
+/// also for the stable address of a prefix unless `RandomizeIdentifiers` is
+/// disabled. Each router advertisement renews the stable address to the full
+/// lifetime of the prefix, while temporary addresses expire sooner, so a random
+/// address is temporary if another address of its /64 outlives it.
+///
+/// When the lifetimes are equal, such as when the prefix lifetime is shorter
+/// than the temporary address lifetime, the addresses cannot be told apart
+/// and none of them is flagged: a temporary address may then be reported as
+/// stable, but a stable one is never reported as temporary.
+fn mark_temporary(addrs: &mut [AddressInfo]) {
+    let slaac = |info: &AddressInfo| {
+        info.addr.is_ipv6()
+            && matches!(
+                info.origin,
+                AddressOrigin::Random | AddressOrigin::RouterAdvertisement
+            )
+    };
+    let prefix = |info: &AddressInfo| match info.addr {
+        IpAddr::V6(addr) => addr.segments()[..4].to_vec(),
+        IpAddr::V4(_) => Vec::new(),
+    };
+    let temporary: Vec<bool> = addrs
+        .iter()
+        .map(|info| {
+            info.origin == AddressOrigin::Random
+                && slaac(info)
+                && addrs.iter().any(|other| {
+                    slaac(other)
+                        && prefix(other) == prefix(info)
+                        && (other.origin == AddressOrigin::RouterAdvertisement
+                            || match (other.valid_lifetime, info.valid_lifetime) {
+                                (None, Some(_)) => true,
+                                (Some(theirs), Some(ours)) => theirs > ours,
+                                _ => false,
+                            })
+                })
+        })
+        .collect();
+    for (info, temporary) in addrs.iter_mut().zip(temporary) {
+        info.temporary = temporary;
+    }
+}
+
+/// Windows has no raw ARP access, so ask the neighbor layer to resolve the
+/// address on the interface instead; any answer means the address is taken.
+#[cfg(feature = "config")]
+pub fn probe_ipv4_conflict(interface: &Interface, addr: Ipv4Addr) -> io::Result<Option<MacAddr>> {
+    let mut row: MIB_IPNET_ROW2 = unsafe { std::mem::zeroed() };
+    row.InterfaceIndex = interface.index;
+    row.Address.Ipv4.sin_family = AF_INET;
+    row.Address.Ipv4.sin_addr.S_un.S_addr = u32::from_ne_bytes(addr.octets());
+    let ret = unsafe { ResolveIpNetEntry2(&mut row, std::ptr::null()) };
+    if ret != NO_ERROR || row.PhysicalAddressLength != 6 {
+        return Ok(None);
+    }
+    let mut octets = [0u8; 6];
+    octets.copy_from_slice(&row.PhysicalAddress[..6]);
+    let mac = MacAddr::from_octets(octets);
+    if Some(mac) == interface.mac_addr {
+        return Ok(None);
+    }
+    Ok(Some(mac))
+}
+
+#[cfg(feature = "config")]
+pub fn restart_dad(_interface: &Interface, _addr: Ipv6Addr) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Restarting DAD is not supported on Windows",
+    ))
+}
+
+/// Winsock reads an `imr_interface` of the form `0.0.0.x` as interface index `x`.
+pub fn multicast_membership_v4(
+    interface: &Interface,
+    socket: &UdpSocket,
+    group: Ipv4Addr,
+    join: bool,
+) -> io::Result<()> {
+    let index = Ipv4Addr::from(interface.index);
+    if join {
+        socket.join_multicast_v4(&group, &index)
+    } else {
+        socket.leave_multicast_v4(&group, &index)
+    }
+}
+
+/// `IP_MULTICAST_IF` also takes the interface index, in network byte order.
+pub fn set_multicast_if_v4(interface: &Interface, socket: &UdpSocket) -> io::Result<()> {
+    let index = interface.index.to_be();
+    let ret = unsafe {
+        setsockopt(
+            socket.as_raw_socket() as SOCKET,
+            IPPROTO_IP,
+            IP_MULTICAST_IF,
+            &index as *const u32 as *const u8,
+            std::mem::size_of::<u32>() as i32,
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
 pub fn is_physical_interface(interface: &Interface) -> bool {
     is_connector_present(interface.index)
         || (interface.is_up()
@@ -109,6 +564,123 @@ unsafe fn linked_list_iter<T>(ptr: &*mut T, next: fn(&T) -> *mut T) -> impl Iter
     })
 }
 
+// Registry key holding one subkey per network adapter (GUID_DEVCLASS_NET)
+const NET_CLASS_KEY: &str =
+    "SYSTEM\\CurrentControlSet\\Control\\Class\\{4d36e972-e325-11ce-bfc1-08002be10318}";
+/// Parent key of the device instances, keyed by device instance ID
+const ENUM_KEY: &str = "SYSTEM\\CurrentControlSet\\Enum";
+
+struct RegKey(HKEY);
+
+impl RegKey {
+    fn open(parent: HKEY, path: &str) -> Option<RegKey> {
+        let path: Vec<u16> = path.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut key: HKEY = 0;
+        if unsafe { RegOpenKeyExW(parent, path.as_ptr(), 0, KEY_READ, &mut key) } != NO_ERROR {
+            return None;
+        }
+        Some(RegKey(key))
+    }
+    fn subkey_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut buf = [0u16; 256];
+        for index in 0.. {
+            let mut len = buf.len() as u32;
+            let ret = unsafe {
+                RegEnumKeyExW(
+                    self.0,
+                    index,
+                    buf.as_mut_ptr(),
+                    &mut len,
+                    std::ptr::null(),
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                )
+            };
+            if ret != NO_ERROR {
+                break;
+            }
+            names.push(String::from_utf16_lossy(&buf[..len as usize]));
+        }
+        names
+    }
+    fn string(&self, name: &str) -> Option<String> {
+        let name: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut value_type: REG_VALUE_TYPE = 0;
+        let mut buf = [0u16; 512];
+        let mut size = (buf.len() * 2) as u32;
+        let ret = unsafe {
+            RegQueryValueExW(
+                self.0,
+                name.as_ptr(),
+                std::ptr::null(),
+                &mut value_type,
+                buf.as_mut_ptr() as *mut u8,
+                &mut size,
+            )
+        };
+        if ret != NO_ERROR || value_type != REG_SZ {
+            return None;
+        }
+        let len = (size as usize / 2).min(buf.len());
+        let value = String::from_utf16_lossy(&buf[..len]);
+        let value = value.trim_end_matches('\0').trim();
+        if value.is_empty() {
+            None
+        } else {
+            Some(value.to_string())
+        }
+    }
+}
+
+impl Drop for RegKey {
+    fn drop(&mut self) {
+        unsafe { RegCloseKey(self.0) };
+    }
+}
+
+/// Map of adapter GUID (upper case, with braces) to driver details from the adapter class key
+fn get_driver_info_map() -> HashMap<String, DriverInfo> {
+    let mut map = HashMap::new();
+    let class_key = match RegKey::open(HKEY_LOCAL_MACHINE, NET_CLASS_KEY) {
+        Some(key) => key,
+        None => return map,
+    };
+    for subkey in class_key.subkey_names() {
+        let adapter = match RegKey::open(class_key.0, &subkey) {
+            Some(key) => key,
+            None => continue,
+        };
+        let guid = match adapter.string("NetCfgInstanceId") {
+            Some(guid) => guid.to_uppercase(),
+            None => continue,
+        };
+        // The driver service name lives under the Ndi subkey
+        let service = RegKey::open(adapter.0, "Ndi").and_then(|ndi| ndi.string("Service"));
+        let name = match service.or_else(|| adapter.string("DriverDesc")) {
+            Some(name) => name,
+            None => continue,
+        };
+        map.insert(
+            guid,
+            DriverInfo {
+                name,
+                version: adapter.string("DriverVersion"),
+                firmware_version: None,
+                bus_info: adapter.string("DeviceInstanceID"),
+            },
+        );
+    }
+    map
+}
+
+/// Bus location of a device, such as `PCI bus 3, device 0, function 1`
+fn get_device_location(device_instance_id: &str) -> Option<String> {
+    let path = format!("{}\\{}", ENUM_KEY, device_instance_id);
+    RegKey::open(HKEY_LOCAL_MACHINE, &path)?.string("LocationInformation")
+}
+
 // The `Next` element is always the same, so use a macro to avoid the repetition.
 macro_rules! linked_list_iter {
     ($ptr:expr) => {
@@ -116,23 +688,15 @@ macro_rules! linked_list_iter {
     };
 }
 
-// Get network interfaces using the IP Helper API
-// Reference: https://docs.microsoft.com/en-us/windows/win32/api/iphlpapi/nf-iphlpapi-getadaptersaddresses
-pub fn interfaces() -> Vec<Interface> {
-    let local_ip: IpAddr = match super::get_local_ipaddr() {
-        Some(local_ip) => local_ip,
-        None => IpAddr::V4(Ipv4Addr::LOCALHOST),
-    };
-    // "The recommended method of calling the GetAdaptersAddresses function is to pre-allocate a 15KB working buffer pointed to by the AdapterAddresses parameter."
-    // (c) https://learn.microsoft.com/en-us/windows/win32/api/iphlpapi/nf-iphlpapi-getadaptersaddresses
-    let mut mem = Vec::<u8>::with_capacity(15000);
+// Fill `mem` with the IP_ADAPTER_ADDRESSES_LH list, growing it as requested by the OS
+fn get_adapters_addresses(family: u16, flags: u32, mem: &mut Vec<u8>) -> Result<(), u32> {
     let mut retries = 3;
     loop {
         let mut dwsize = mem.capacity() as u32;
         let ret_val = unsafe {
             GetAdaptersAddresses(
-                AF_UNSPEC as u32,
-                GAA_FLAG_INCLUDE_GATEWAYS,
+                family as u32,
+                flags,
                 std::ptr::null_mut(),
                 mem.as_mut_ptr().cast(),
                 &mut dwsize,
@@ -143,23 +707,136 @@ pub fn interfaces() -> Vec<Interface> {
                 unsafe {
                     mem.set_len(dwsize as usize);
                 }
-                break;
+                return Ok(());
             }
             ERROR_BUFFER_OVERFLOW if retries > 0 => {
                 mem.reserve(dwsize as usize);
                 retries -= 1;
             }
-            _ => {
-                // TODO: return errors as a Result someday?
-                return vec![];
+            _ => return Err(ret_val),
+        }
+    }
+}
+
+/// The adapter prefix list has no origins or lifetimes, so a prefix takes the
+/// origin of an address the adapter has in it.
+pub fn get_on_link_prefixes(interface: &Interface) -> io::Result<Vec<OnLinkPrefix>> {
+    let mut mem: Vec<u8> = Vec::with_capacity(15000);
+    let flags = GAA_FLAG_INCLUDE_PREFIX
+        | GAA_FLAG_SKIP_ANYCAST
+        | GAA_FLAG_SKIP_MULTICAST
+        | GAA_FLAG_SKIP_DNS_SERVER;
+    get_adapters_addresses(AF_UNSPEC, flags, &mut mem)
+        .map_err(|ret_val| io::Error::from_raw_os_error(ret_val as i32))?;
+    let mem = mem.as_mut_ptr().cast::<IP_ADAPTER_ADDRESSES_LH>();
+    let mut prefixes: Vec<OnLinkPrefix> = Vec::new();
+    for cur in unsafe { linked_list_iter!(&mem) } {
+        if unsafe { cur.Anonymous1.Anonymous.IfIndex } != interface.index {
+            continue;
+        }
+        let addrs: Vec<(IpAddr, AddressOrigin)> =
+            unsafe { linked_list_iter!(&cur.FirstUnicastAddress) }
+                .filter_map(|cur_a| {
+                    let addr = unsafe { socket_address_to_ipaddr(&cur_a.Address) }?;
+                    let origin = address_origin(addr, cur_a.PrefixOrigin, cur_a.SuffixOrigin);
+                    Some((addr, origin))
+                })
+                .collect();
+        for cur_p in unsafe { linked_list_iter!(&cur.FirstPrefix) } {
+            let Some(addr) = (unsafe { socket_address_to_ipaddr(&cur_p.Address) }) else {
+                continue;
+            };
+            let prefix = IpNet::new(addr, cur_p.PrefixLength as u8);
+            if !super::is_on_link_subnet(&prefix) {
+                continue;
             }
+            let origin = addrs
+                .iter()
+                .find(|(addr, _)| prefix.contains(*addr))
+                // Addresses from DHCPv6 or with random identifiers are still
+                // assigned within a prefix announced by a router
+                .map(|(_, origin)| match origin {
+                    AddressOrigin::Random => AddressOrigin::RouterAdvertisement,
+                    origin => *origin,
+                })
+                .unwrap_or(AddressOrigin::Other);
+            prefixes.push(OnLinkPrefix {
+                prefix,
+                valid_lifetime: None,
+                origin,
+            });
         }
     }
+    Ok(prefixes)
+}
+
+// Get network interfaces using the IP Helper API
+// Reference: https://docs.microsoft.com/en-us/windows/win32/api/iphlpapi/nf-iphlpapi-getadaptersaddresses
+pub fn try_interfaces(options: &EnumOptions) -> Result<Vec<Interface>, Error> {
+    try_interfaces_into(options, &mut Vec::new())
+}
+
+// Same as `try_interfaces`, but reuses `mem` as the GetAdaptersAddresses working buffer
+// so that repeated calls do not reallocate it
+pub fn try_interfaces_into(
+    options: &EnumOptions,
+    mem: &mut Vec<u8>,
+) -> Result<Vec<Interface>, Error> {
+    let standard = options.detail_level >= DetailLevel::Standard;
+    let full = options.detail_level >= DetailLevel::Full;
+    let local_ip: Option<IpAddr> = if standard {
+        super::os_local_ipaddr()
+    } else {
+        None
+    };
+    // "The recommended method of calling the GetAdaptersAddresses function is to pre-allocate a 15KB working buffer pointed to by the AdapterAddresses parameter."
+    // (c) https://learn.microsoft.com/en-us/windows/win32/api/iphlpapi/nf-iphlpapi-getadaptersaddresses
+    mem.clear();
+    if mem.capacity() < 15000 {
+        mem.reserve(15000);
+    }
+    let mut gaa_flags = 0;
+    if standard {
+        gaa_flags |= GAA_FLAG_INCLUDE_GATEWAYS;
+    } else {
+        gaa_flags |= GAA_FLAG_SKIP_DNS_SERVER;
+    }
+    if options.include_hidden {
+        // Also return disabled adapters and those not bound to an address family
+        gaa_flags |= GAA_FLAG_INCLUDE_ALL_INTERFACES;
+    }
+    // Let the OS skip addresses of families that were not requested
+    let family = match options.address_families {
+        AddressFamily::V4 => AF_INET,
+        AddressFamily::V6 => AF_INET6,
+        AddressFamily::Both => AF_UNSPEC,
+    };
+    let driver_map = if full {
+        get_driver_info_map()
+    } else {
+        HashMap::new()
+    };
+    let _span = debug_span!("GetAdaptersAddresses");
+    if let Err(ret_val) = get_adapters_addresses(family, gaa_flags, mem) {
+        let err = Error::syscall(
+            "GetAdaptersAddresses",
+            io::Error::from_raw_os_error(ret_val as i32),
+        );
+        debug!("{}", err);
+        return Err(err);
+    }
     // Enumerate all adapters
     let mem = mem.as_mut_ptr().cast::<IP_ADAPTER_ADDRESSES_LH>();
-    unsafe { linked_list_iter!(&mem) }
+    let interfaces = unsafe { linked_list_iter!(&mem) }
         .filter_map(|cur| {
-            let if_type = InterfaceType::try_from(cur.IfType).ok()?;
+            let if_type = match InterfaceType::try_from(cur.IfType) {
+                Ok(if_type) => if_type,
+                Err(_) if options.include_hidden => InterfaceType::Unknown,
+                Err(_) => {
+                    trace!("skipping adapter of unknown IfType {}", cur.IfType);
+                    return None;
+                }
+            };
             // Index
             let index = {
                 let anon1 = cur.Anonymous1;
@@ -189,13 +866,26 @@ pub fn interfaces() -> Vec<Interface> {
                 }
                 _ => {}
             }
+            if !options.accepts_flags(flags) {
+                return None;
+            }
             // Name
             let adapter_name = unsafe { CStr::from_ptr(cur.AdapterName.cast()) }
                 .to_string_lossy()
                 .into_owned();
+            let friendly_name = unsafe { from_wide_string(cur.FriendlyName) };
+            if !options.accepts_name(&adapter_name) || !options.accepts_name(&friendly_name) {
+                return None;
+            }
             // MAC address
             let mac_addr_arr: [u8; 6] = cur.PhysicalAddress[..6].try_into().unwrap_or_default();
             let mac_addr: MacAddr = MacAddr::from_octets(mac_addr_arr);
+            let hardware_addr = match cur.PhysicalAddressLength as usize {
+                0 => None,
+                len => Some(HardwareAddr::from_bytes(
+                    &cur.PhysicalAddress[..len.min(cur.PhysicalAddress.len())],
+                )),
+            };
             let mut ipv4_vec: Vec<Ipv4Net> = vec![];
             let mut ipv6_vec: Vec<Ipv6Net> = vec![];
             // Enumerate all IPs
@@ -219,20 +909,20 @@ pub fn interfaces() -> Vec<Interface> {
             let gateway_ips: Vec<IpAddr> = unsafe { linked_list_iter!(&cur.FirstGatewayAddress) }
                 .filter_map(|cur_g| unsafe { socket_address_to_ipaddr(&cur_g.Address) })
                 .collect();
-            let mut default_gateway: NetworkDevice = NetworkDevice::new();
-            if flags & sys::IFF_UP != 0 {
+            let mut gateway_hops: Vec<(IpAddr, MacAddr)> = Vec::new();
+            if standard && flags & sys::IFF_UP != 0 {
                 for gateway_ip in gateway_ips {
                     match gateway_ip {
                         IpAddr::V4(ipv4) => {
                             if let Some(ip_net) = ipv4_vec.first() {
                                 let mac_addr = get_mac_through_arp(ip_net.addr, ipv4);
-                                default_gateway.mac_addr = mac_addr;
-                                default_gateway.ipv4.push(ipv4);
+                                gateway_hops.push((gateway_ip, mac_addr));
                             }
                         }
                         IpAddr::V6(ipv6) => {
                             if !ipv6_vec.is_empty() {
-                                default_gateway.ipv6.push(ipv6);
+                                let mac_addr = get_neighbour_mac(index, ipv6);
+                                gateway_hops.push((gateway_ip, mac_addr));
                             }
                         }
                     }
@@ -243,30 +933,108 @@ pub fn interfaces() -> Vec<Interface> {
                 .filter_map(|cur_d| unsafe { socket_address_to_ipaddr(&cur_d.Address) })
                 .collect();
             let default: bool = match local_ip {
-                IpAddr::V4(local_ipv4) => ipv4_vec.iter().any(|x| x.addr == local_ipv4),
-                IpAddr::V6(local_ipv6) => ipv6_vec.iter().any(|x| x.addr == local_ipv6),
+                Some(IpAddr::V4(local_ipv4)) => ipv4_vec.iter().any(|x| x.addr == local_ipv4),
+                Some(IpAddr::V6(local_ipv6)) => ipv6_vec.iter().any(|x| x.addr == local_ipv6),
+                None => false,
             };
+            let driver = driver_map.get(&adapter_name.to_uppercase()).cloned();
             let interface: Interface = Interface {
                 index,
                 name: adapter_name,
-                friendly_name: Some(unsafe { from_wide_string(cur.FriendlyName) }),
+                friendly_name: Some(friendly_name),
                 description: Some(unsafe { from_wide_string(cur.Description) }),
                 if_type,
                 mac_addr: Some(mac_addr),
+                hardware_addr,
                 ipv4: ipv4_vec,
                 ipv6: ipv6_vec,
                 flags,
                 transmit_speed: Some(cur.TransmitLinkSpeed),
                 receive_speed: Some(cur.ReceiveLinkSpeed),
-                gateway: if default_gateway.mac_addr == MacAddr::zero() {
+                gateways: gateway::group_gateways(gateway_hops),
+                dns_servers,
+                default,
+                stats: if full {
+                    stats::get_interface_stats_by_index(index)
+                } else {
                     None
+                },
+                device_path: driver
+                    .as_ref()
+                    .and_then(|driver| driver.bus_info.as_deref())
+                    .and_then(get_device_location),
+                driver,
+                carrier_changes: None,
+                link_up_since: if full && flags & sys::IFF_UP != 0 {
+                    get_last_change(index)
                 } else {
-                    Some(default_gateway)
+                    None
                 },
-                dns_servers,
-                default,
+                network_manager: None,
+                networkd: None,
+                netns: None,
             };
             Some(interface)
         })
-        .collect()
+        .collect();
+    Ok(interfaces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(addr: &str, origin: AddressOrigin, valid_secs: Option<u64>) -> AddressInfo {
+        AddressInfo {
+            addr: addr.parse().unwrap(),
+            prefix_len: 64,
+            dad_state: Some(DadState::Preferred),
+            valid_lifetime: valid_secs.map(Duration::from_secs),
+            preferred_lifetime: None,
+            label: None,
+            secondary: false,
+            origin,
+            temporary: false,
+        }
+    }
+
+    #[test]
+    fn test_mark_temporary() {
+        let day = 86400;
+        let mut addrs = vec![
+            // Stable and temporary addresses, both with random identifiers
+            info("2001:db8:1::a", AddressOrigin::Random, Some(30 * day)),
+            info("2001:db8:1::b", AddressOrigin::Random, Some(6 * day)),
+            // Stable address derived from the MAC address
+            info(
+                "2001:db8:2::200:5eff:fe00:5301",
+                AddressOrigin::RouterAdvertisement,
+                Some(day),
+            ),
+            info("2001:db8:2::c", AddressOrigin::Random, Some(day)),
+            // Lone random address, taken as stable
+            info("2001:db8:3::d", AddressOrigin::Random, Some(day)),
+            info("fe80::1", AddressOrigin::LinkLocal, None),
+            // Random addresses with equal lifetimes cannot be told apart
+            info("2001:db8:4::e", AddressOrigin::Random, Some(day)),
+            info("2001:db8:4::f", AddressOrigin::Random, Some(day)),
+        ];
+        mark_temporary(&mut addrs);
+        let temporary: Vec<bool> = addrs.iter().map(|info| info.temporary).collect();
+        assert_eq!(
+            temporary,
+            [false, true, false, true, false, false, false, false]
+        );
+    }
+
+    #[test]
+    fn test_ticks_since() {
+        assert_eq!(ticks_since(5_000, 1_000), 4_000);
+        // The 32-bit count wrapped since the change
+        let wrap = 1u64 << 32;
+        assert_eq!(ticks_since(wrap + 500, u32::MAX - 499), 1_000);
+        assert_eq!(ticks_since(3 * wrap + 500, 100), 400);
+        // Never before boot
+        assert_eq!(ticks_since(1_000, 2_000), 1_000);
+    }
 }
@@ -0,0 +1,5 @@
+#[cfg(feature = "pnet")]
+mod pnet;
+
+#[cfg(feature = "socket2")]
+mod socket;
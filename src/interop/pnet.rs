@@ -0,0 +1,109 @@
+use crate::interface::Interface;
+use crate::ip::{Ipv4Net, Ipv6Net};
+use crate::mac::{HardwareAddr, MacAddr};
+use ipnetwork::IpNetwork;
+
+impl From<MacAddr> for pnet_datalink::MacAddr {
+    fn from(mac: MacAddr) -> pnet_datalink::MacAddr {
+        pnet_datalink::MacAddr::new(mac.0, mac.1, mac.2, mac.3, mac.4, mac.5)
+    }
+}
+
+impl From<pnet_datalink::MacAddr> for MacAddr {
+    fn from(mac: pnet_datalink::MacAddr) -> MacAddr {
+        MacAddr::new(mac.0, mac.1, mac.2, mac.3, mac.4, mac.5)
+    }
+}
+
+impl From<&Interface> for pnet_datalink::NetworkInterface {
+    fn from(iface: &Interface) -> pnet_datalink::NetworkInterface {
+        let mut ips: Vec<IpNetwork> = Vec::new();
+        for ipv4 in &iface.ipv4 {
+            if let Ok(net) = IpNetwork::new(ipv4.addr.into(), ipv4.prefix_len) {
+                ips.push(net);
+            }
+        }
+        for ipv6 in &iface.ipv6 {
+            if let Ok(net) = IpNetwork::new(ipv6.addr.into(), ipv6.prefix_len) {
+                ips.push(net);
+            }
+        }
+        pnet_datalink::NetworkInterface {
+            name: iface.name.clone(),
+            description: iface.description.clone().unwrap_or_default(),
+            index: iface.index,
+            mac: iface.mac_addr.map(|mac| mac.into()),
+            ips,
+            flags: iface.flags,
+        }
+    }
+}
+
+impl From<Interface> for pnet_datalink::NetworkInterface {
+    fn from(iface: Interface) -> pnet_datalink::NetworkInterface {
+        pnet_datalink::NetworkInterface::from(&iface)
+    }
+}
+
+impl From<&pnet_datalink::NetworkInterface> for Interface {
+    /// Fields that `pnet_datalink` does not know about (type, speeds, gateway, DNS servers, ...)
+    /// are left at their defaults.
+    fn from(iface: &pnet_datalink::NetworkInterface) -> Interface {
+        let mut interface = Interface::dummy();
+        interface.index = iface.index;
+        interface.name = iface.name.clone();
+        if !iface.description.is_empty() {
+            interface.description = Some(iface.description.clone());
+        }
+        interface.mac_addr = iface.mac.map(|mac| mac.into());
+        interface.hardware_addr = interface.mac_addr.map(HardwareAddr::from);
+        for ip in &iface.ips {
+            match ip {
+                IpNetwork::V4(net) => {
+                    interface.ipv4.push(Ipv4Net::new(net.ip(), net.prefix()));
+                }
+                IpNetwork::V6(net) => {
+                    interface.ipv6.push(Ipv6Net::new(net.ip(), net.prefix()));
+                }
+            }
+        }
+        interface.flags = iface.flags;
+        interface
+    }
+}
+
+impl From<pnet_datalink::NetworkInterface> for Interface {
+    fn from(iface: pnet_datalink::NetworkInterface) -> Interface {
+        Interface::from(&iface)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    #[test]
+    fn test_pnet_round_trip() {
+        let mut iface = Interface::dummy();
+        iface.index = 2;
+        iface.name = String::from("eth0");
+        iface.mac_addr = Some(MacAddr::new(0x00, 0x11, 0x22, 0x33, 0x44, 0x55));
+        iface
+            .ipv4
+            .push(Ipv4Net::new(Ipv4Addr::new(192, 168, 1, 10), 24));
+        iface
+            .ipv6
+            .push(Ipv6Net::new(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1), 64));
+        iface.flags = 0x1003;
+        let pnet_iface = pnet_datalink::NetworkInterface::from(&iface);
+        assert_eq!(pnet_iface.ips.len(), 2);
+        let converted = Interface::from(&pnet_iface);
+        assert_eq!(converted.index, iface.index);
+        assert_eq!(converted.name, iface.name);
+        assert_eq!(converted.mac_addr, iface.mac_addr);
+        assert_eq!(converted.ipv4, iface.ipv4);
+        assert_eq!(converted.ipv6, iface.ipv6);
+        assert_eq!(converted.flags, iface.flags);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::interface::Interface;
+use std::io;
+
+#[cfg(not(target_os = "netbsd"))]
+impl From<&Interface> for socket2::InterfaceIndexOrAddress {
+    /// Multicast interface selection by index, e.g. for `Socket::join_multicast_v4_n`.
+    fn from(iface: &Interface) -> socket2::InterfaceIndexOrAddress {
+        socket2::InterfaceIndexOrAddress::Index(iface.index)
+    }
+}
+
+impl Interface {
+    /// Bind the socket to this network interface so that traffic only flows through it.
+    ///
+    /// Uses `SO_BINDTODEVICE` on Linux/Android and `IP_BOUND_IF`/`IPV6_BOUND_IF` on macOS/iOS.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn bind_socket(&self, socket: &socket2::Socket) -> io::Result<()> {
+        socket.bind_device(Some(self.name.as_bytes()))
+    }
+    /// Bind the socket to this network interface so that traffic only flows through it.
+    ///
+    /// Uses `SO_BINDTODEVICE` on Linux/Android and `IP_BOUND_IF`/`IPV6_BOUND_IF` on macOS/iOS.
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    pub fn bind_socket(&self, socket: &socket2::Socket) -> io::Result<()> {
+        let index = std::num::NonZeroU32::new(self.index).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "Invalid interface index")
+        })?;
+        if socket.local_addr()?.is_ipv6() {
+            socket.bind_device_by_index_v6(Some(index))
+        } else {
+            socket.bind_device_by_index_v4(Some(index))
+        }
+    }
+    /// Bind the socket to this network interface so that traffic only flows through it.
+    ///
+    /// Not supported on this platform.
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios"
+    )))]
+    pub fn bind_socket(&self, _socket: &socket2::Socket) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Binding a socket to an interface is not supported on this platform",
+        ))
+    }
+}
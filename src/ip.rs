@@ -1,5 +1,8 @@
 use core::fmt;
+use core::str::FromStr;
+use std::error;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::ops::RangeInclusive;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -87,6 +90,91 @@ impl IpNet {
             },
         }
     }
+    /// Checks if `other` is the same network or a subnet of it.
+    pub fn contains_net(&self, other: &IpNet) -> bool {
+        match (self, other) {
+            (IpNet::V4(a), IpNet::V4(b)) => a.contains_net(b),
+            (IpNet::V6(a), IpNet::V6(b)) => a.contains_net(b),
+            _ => false,
+        }
+    }
+    /// Returns the network with the host bits of the address cleared.
+    pub fn trunc(&self) -> IpNet {
+        match *self {
+            IpNet::V4(ref a) => IpNet::V4(a.trunc()),
+            IpNet::V6(ref a) => IpNet::V6(a.trunc()),
+        }
+    }
+    /// Returns the network one bit shorter, or `None` for a /0 network.
+    pub fn supernet(&self) -> Option<IpNet> {
+        match *self {
+            IpNet::V4(ref a) => a.supernet().map(IpNet::V4),
+            IpNet::V6(ref a) => a.supernet().map(IpNet::V6),
+        }
+    }
+}
+
+impl fmt::Display for IpNet {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IpNet::V4(ref a) => fmt::Display::fmt(a, fmt),
+            IpNet::V6(ref a) => fmt::Display::fmt(a, fmt),
+        }
+    }
+}
+
+/// Parses CIDR notation, such as `192.168.1.0/24` or `2001:db8::/32`
+impl FromStr for IpNet {
+    type Err = ParseIpNetError;
+    fn from_str(s: &str) -> Result<IpNet, ParseIpNetError> {
+        let (addr, prefix_len) = split_cidr(s)?;
+        match addr.parse::<IpAddr>() {
+            Ok(IpAddr::V4(addr)) => Ipv4Net::checked(addr, prefix_len).map(IpNet::V4),
+            Ok(IpAddr::V6(addr)) => Ipv6Net::checked(addr, prefix_len).map(IpNet::V6),
+            Err(_) => Err(ParseIpNetError::InvalidAddress),
+        }
+    }
+}
+
+/// Represents an error which occurred whilst parsing a network in CIDR notation
+#[derive(Copy, Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum ParseIpNetError {
+    /// The `/` and prefix length are missing, eg. 192.168.1.0
+    MissingPrefixLen,
+    /// The address part is not a valid IP address, eg. 192.168.1/24
+    InvalidAddress,
+    /// The prefix length is not a number or too long for the address family, eg. 10.0.0.0/33
+    InvalidPrefixLen,
+}
+
+impl error::Error for ParseIpNetError {}
+
+impl ParseIpNetError {
+    fn description(&self) -> &str {
+        match *self {
+            ParseIpNetError::MissingPrefixLen => "Missing prefix length in a network string",
+            ParseIpNetError::InvalidAddress => "Invalid address in a network string",
+            ParseIpNetError::InvalidPrefixLen => "Invalid prefix length in a network string",
+        }
+    }
+}
+
+impl fmt::Display for ParseIpNetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+fn split_cidr(s: &str) -> Result<(&str, u8), ParseIpNetError> {
+    let (addr, prefix_len) = s.split_once('/').ok_or(ParseIpNetError::MissingPrefixLen)?;
+    if prefix_len.is_empty() || !prefix_len.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseIpNetError::InvalidPrefixLen);
+    }
+    let prefix_len = prefix_len
+        .parse()
+        .map_err(|_| ParseIpNetError::InvalidPrefixLen)?;
+    Ok((addr, prefix_len))
 }
 
 impl From<Ipv4Net> for IpNet {
@@ -127,7 +215,7 @@ impl Ipv4Net {
     pub fn new(ipv4_addr: Ipv4Addr, prefix_len: u8) -> Ipv4Net {
         Ipv4Net {
             addr: ipv4_addr,
-            prefix_len: prefix_len,
+            prefix_len,
             netmask: prefix_to_ipv4_netmask(prefix_len),
         }
     }
@@ -136,7 +224,7 @@ impl Ipv4Net {
         Ipv4Net {
             addr: ipv4_addr,
             prefix_len: ipv4_netmask_to_prefix(netmask),
-            netmask: netmask,
+            netmask,
         }
     }
     /// Returns the maximum valid prefix length.
@@ -149,7 +237,7 @@ impl Ipv4Net {
     }
     /// Returns the network mask. (u32)
     fn netmask_u32(&self) -> u32 {
-        u32::max_value()
+        u32::MAX
             .checked_shl(32 - self.prefix_len as u32)
             .unwrap_or(0)
     }
@@ -159,9 +247,7 @@ impl Ipv4Net {
     }
     /// Returns the host mask. (u32)
     fn hostmask_u32(&self) -> u32 {
-        u32::max_value()
-            .checked_shr(self.prefix_len as u32)
-            .unwrap_or(0)
+        u32::MAX.checked_shr(self.prefix_len as u32).unwrap_or(0)
     }
     /// Returns the network address.
     pub fn network(&self) -> Ipv4Addr {
@@ -175,6 +261,104 @@ impl Ipv4Net {
     pub fn contains(&self, ip: Ipv4Addr) -> bool {
         self.network() == Ipv4Addr::from(u32::from(ip) & self.netmask_u32())
     }
+    /// Checks if `other` is the same network or a subnet of it.
+    pub fn contains_net(&self, other: &Ipv4Net) -> bool {
+        other.prefix_len >= self.prefix_len && self.contains(other.addr)
+    }
+    /// Returns the network with the host bits of the address cleared.
+    pub fn trunc(&self) -> Ipv4Net {
+        Ipv4Net::new(self.network(), self.prefix_len)
+    }
+    /// Returns the network one bit shorter, or `None` for a /0 network.
+    pub fn supernet(&self) -> Option<Ipv4Net> {
+        let prefix_len = self.prefix_len.checked_sub(1)?;
+        Some(Ipv4Net::new(self.addr, prefix_len).trunc())
+    }
+    /// Iterate over the subnets with the given prefix length.
+    ///
+    /// Returns `None` if `prefix_len` is shorter than the network's or longer than 32.
+    pub fn subnets(&self, prefix_len: u8) -> Option<Ipv4Subnets> {
+        if prefix_len < self.prefix_len || prefix_len > self.max_prefix_len() {
+            return None;
+        }
+        Some(Ipv4Subnets {
+            next: Some(u32::from(self.network())),
+            end: u32::from(self.broadcast()),
+            step: 1u32.checked_shl(32 - prefix_len as u32),
+            prefix_len,
+        })
+    }
+    /// Iterate over the usable host addresses.
+    ///
+    /// The network and broadcast addresses are skipped, except in /31 and /32
+    /// networks, where every address is a host (RFC 3021).
+    pub fn hosts(&self) -> Ipv4Hosts {
+        let start = u32::from(self.network());
+        let end = u32::from(self.broadcast());
+        if self.prefix_len >= 31 {
+            Ipv4Hosts(start..=end)
+        } else {
+            Ipv4Hosts(start + 1..=end - 1)
+        }
+    }
+    fn checked(addr: Ipv4Addr, prefix_len: u8) -> Result<Ipv4Net, ParseIpNetError> {
+        if prefix_len > 32 {
+            return Err(ParseIpNetError::InvalidPrefixLen);
+        }
+        Ok(Ipv4Net::new(addr, prefix_len))
+    }
+}
+
+/// Parses CIDR notation, such as `192.168.1.0/24`
+impl FromStr for Ipv4Net {
+    type Err = ParseIpNetError;
+    fn from_str(s: &str) -> Result<Ipv4Net, ParseIpNetError> {
+        let (addr, prefix_len) = split_cidr(s)?;
+        let addr = addr.parse().map_err(|_| ParseIpNetError::InvalidAddress)?;
+        Ipv4Net::checked(addr, prefix_len)
+    }
+}
+
+/// Iterator over the host addresses of an [`Ipv4Net`]
+#[derive(Clone, Debug)]
+pub struct Ipv4Hosts(RangeInclusive<u32>);
+
+impl Iterator for Ipv4Hosts {
+    type Item = Ipv4Addr;
+    fn next(&mut self) -> Option<Ipv4Addr> {
+        self.0.next().map(Ipv4Addr::from)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Ipv4Hosts {
+    fn next_back(&mut self) -> Option<Ipv4Addr> {
+        self.0.next_back().map(Ipv4Addr::from)
+    }
+}
+
+/// Iterator over the subnets of an [`Ipv4Net`]
+#[derive(Clone, Debug)]
+pub struct Ipv4Subnets {
+    next: Option<u32>,
+    end: u32,
+    /// `None` if there is only one subnet (a /0 network split into /0)
+    step: Option<u32>,
+    prefix_len: u8,
+}
+
+impl Iterator for Ipv4Subnets {
+    type Item = Ipv4Net;
+    fn next(&mut self) -> Option<Ipv4Net> {
+        let addr = self.next?;
+        self.next = self
+            .step
+            .and_then(|step| addr.checked_add(step))
+            .filter(|next| *next <= self.end);
+        Some(Ipv4Net::new(Ipv4Addr::from(addr), self.prefix_len))
+    }
 }
 
 impl fmt::Debug for Ipv4Net {
@@ -216,7 +400,7 @@ impl Ipv6Net {
     pub fn new(ipv6_addr: Ipv6Addr, prefix_len: u8) -> Ipv6Net {
         Ipv6Net {
             addr: ipv6_addr,
-            prefix_len: prefix_len,
+            prefix_len,
             netmask: prefix_to_ipv6_netmask(prefix_len),
         }
     }
@@ -225,7 +409,7 @@ impl Ipv6Net {
         Ipv6Net {
             addr: ipv6_addr,
             prefix_len: ipv6_netmask_to_prefix(netmask),
-            netmask: netmask,
+            netmask,
         }
     }
     /// Returns the maximum valid prefix length.
@@ -238,9 +422,9 @@ impl Ipv6Net {
     }
     /// Returns the network mask. (u128)
     fn netmask_u128(&self) -> u128 {
-        u128::max_value()
+        u128::MAX
             .checked_shl((128 - self.prefix_len) as u32)
-            .unwrap_or(u128::min_value())
+            .unwrap_or(u128::MIN)
     }
     /// Returns the host mask.
     pub fn hostmask(&self) -> Ipv6Addr {
@@ -248,9 +432,9 @@ impl Ipv6Net {
     }
     /// Returns the host mask. (u128)
     fn hostmask_u128(&self) -> u128 {
-        u128::max_value()
+        u128::MAX
             .checked_shr(self.prefix_len as u32)
-            .unwrap_or(u128::min_value())
+            .unwrap_or(u128::MIN)
     }
     /// Returns the network address.
     pub fn network(&self) -> Ipv6Addr {
@@ -265,6 +449,93 @@ impl Ipv6Net {
         let ipv6_network: Ipv6Addr = (u128::from(ip) & self.netmask_u128()).into();
         self.network() == ipv6_network
     }
+    /// Checks if `other` is the same network or a subnet of it.
+    pub fn contains_net(&self, other: &Ipv6Net) -> bool {
+        other.prefix_len >= self.prefix_len && self.contains(other.addr)
+    }
+    /// Returns the network with the host bits of the address cleared.
+    pub fn trunc(&self) -> Ipv6Net {
+        Ipv6Net::new(self.network(), self.prefix_len)
+    }
+    /// Returns the network one bit shorter, or `None` for a /0 network.
+    pub fn supernet(&self) -> Option<Ipv6Net> {
+        let prefix_len = self.prefix_len.checked_sub(1)?;
+        Some(Ipv6Net::new(self.addr, prefix_len).trunc())
+    }
+    /// Iterate over the subnets with the given prefix length.
+    ///
+    /// Returns `None` if `prefix_len` is shorter than the network's or longer than 128.
+    pub fn subnets(&self, prefix_len: u8) -> Option<Ipv6Subnets> {
+        if prefix_len < self.prefix_len || prefix_len > self.max_prefix_len() {
+            return None;
+        }
+        Some(Ipv6Subnets {
+            next: Some(u128::from(self.network())),
+            end: u128::from(self.broadcast()),
+            step: 1u128.checked_shl(128 - prefix_len as u32),
+            prefix_len,
+        })
+    }
+    /// Iterate over every address in the network. IPv6 has no broadcast
+    /// address, so nothing is skipped.
+    pub fn hosts(&self) -> Ipv6Hosts {
+        Ipv6Hosts(u128::from(self.network())..=u128::from(self.broadcast()))
+    }
+    fn checked(addr: Ipv6Addr, prefix_len: u8) -> Result<Ipv6Net, ParseIpNetError> {
+        if prefix_len > 128 {
+            return Err(ParseIpNetError::InvalidPrefixLen);
+        }
+        Ok(Ipv6Net::new(addr, prefix_len))
+    }
+}
+
+/// Parses CIDR notation, such as `2001:db8::/32`
+impl FromStr for Ipv6Net {
+    type Err = ParseIpNetError;
+    fn from_str(s: &str) -> Result<Ipv6Net, ParseIpNetError> {
+        let (addr, prefix_len) = split_cidr(s)?;
+        let addr = addr.parse().map_err(|_| ParseIpNetError::InvalidAddress)?;
+        Ipv6Net::checked(addr, prefix_len)
+    }
+}
+
+/// Iterator over the addresses of an [`Ipv6Net`]
+#[derive(Clone, Debug)]
+pub struct Ipv6Hosts(RangeInclusive<u128>);
+
+impl Iterator for Ipv6Hosts {
+    type Item = Ipv6Addr;
+    fn next(&mut self) -> Option<Ipv6Addr> {
+        self.0.next().map(Ipv6Addr::from)
+    }
+}
+
+impl DoubleEndedIterator for Ipv6Hosts {
+    fn next_back(&mut self) -> Option<Ipv6Addr> {
+        self.0.next_back().map(Ipv6Addr::from)
+    }
+}
+
+/// Iterator over the subnets of an [`Ipv6Net`]
+#[derive(Clone, Debug)]
+pub struct Ipv6Subnets {
+    next: Option<u128>,
+    end: u128,
+    /// `None` if there is only one subnet (a /0 network split into /0)
+    step: Option<u128>,
+    prefix_len: u8,
+}
+
+impl Iterator for Ipv6Subnets {
+    type Item = Ipv6Net;
+    fn next(&mut self) -> Option<Ipv6Net> {
+        let addr = self.next?;
+        self.next = self
+            .step
+            .and_then(|step| addr.checked_add(step))
+            .filter(|next| *next <= self.end);
+        Some(Ipv6Net::new(Ipv6Addr::from(addr), self.prefix_len))
+    }
 }
 
 impl fmt::Debug for Ipv6Net {
@@ -333,15 +604,117 @@ fn ipv6_netmask_to_prefix(netmask: Ipv6Addr) -> u8 {
 }
 
 fn prefix_to_ipv4_netmask(prefix_len: u8) -> Ipv4Addr {
-    let netmask_u32: u32 = u32::max_value()
-        .checked_shl(32 - prefix_len as u32)
-        .unwrap_or(0);
+    let netmask_u32: u32 = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
     Ipv4Addr::from(netmask_u32)
 }
 
 fn prefix_to_ipv6_netmask(prefix_len: u8) -> Ipv6Addr {
-    let netmask_u128: u128 = u128::max_value()
+    let netmask_u128: u128 = u128::MAX
         .checked_shl((128 - prefix_len) as u32)
-        .unwrap_or(u128::min_value());
+        .unwrap_or(u128::MIN);
     Ipv6Addr::from(netmask_u128)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let net: Ipv4Net = "192.168.1.10/24".parse().unwrap();
+        assert_eq!(net.addr, Ipv4Addr::new(192, 168, 1, 10));
+        assert_eq!(net.netmask, Ipv4Addr::new(255, 255, 255, 0));
+        assert_eq!(net.to_string(), "192.168.1.10/24");
+        let net: Ipv6Net = "2001:db8::1/64".parse().unwrap();
+        assert_eq!(net.prefix_len, 64);
+        assert_eq!(
+            "10.0.0.0/8".parse::<IpNet>().unwrap(),
+            IpNet::V4(Ipv4Net::new(Ipv4Addr::new(10, 0, 0, 0), 8))
+        );
+        assert_eq!(
+            "10.0.0.0".parse::<IpNet>(),
+            Err(ParseIpNetError::MissingPrefixLen)
+        );
+        assert_eq!(
+            "10.0.0/8".parse::<Ipv4Net>(),
+            Err(ParseIpNetError::InvalidAddress)
+        );
+        for bad in ["10.0.0.0/33", "10.0.0.0/", "10.0.0.0/+8", "::/129"] {
+            assert_eq!(
+                bad.parse::<IpNet>(),
+                Err(ParseIpNetError::InvalidPrefixLen),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_ipv4_math() {
+        let net: Ipv4Net = "192.168.1.10/30".parse().unwrap();
+        assert_eq!(net.trunc().addr, Ipv4Addr::new(192, 168, 1, 8));
+        assert_eq!(net.broadcast(), Ipv4Addr::new(192, 168, 1, 11));
+        let hosts: Vec<Ipv4Addr> = net.hosts().collect();
+        assert_eq!(
+            hosts,
+            vec![
+                Ipv4Addr::new(192, 168, 1, 9),
+                Ipv4Addr::new(192, 168, 1, 10)
+            ]
+        );
+        let p2p: Ipv4Net = "10.0.0.0/31".parse().unwrap();
+        assert_eq!(p2p.hosts().count(), 2);
+        assert_eq!(Ipv4Net::from(Ipv4Addr::LOCALHOST).hosts().count(), 1);
+        assert_eq!(net.supernet().unwrap().to_string(), "192.168.1.8/29");
+        assert!(Ipv4Net::new(Ipv4Addr::UNSPECIFIED, 0).supernet().is_none());
+
+        let net: Ipv4Net = "10.0.0.0/24".parse().unwrap();
+        let subnets: Vec<String> = net.subnets(26).unwrap().map(|n| n.to_string()).collect();
+        assert_eq!(
+            subnets,
+            vec![
+                "10.0.0.0/26",
+                "10.0.0.64/26",
+                "10.0.0.128/26",
+                "10.0.0.192/26"
+            ]
+        );
+        assert_eq!(net.subnets(24).unwrap().count(), 1);
+        assert!(net.subnets(23).is_none());
+        assert!(net.contains_net(&"10.0.0.128/25".parse().unwrap()));
+        assert!(!net.contains_net(&"10.0.0.0/16".parse().unwrap()));
+        let all = Ipv4Net::new(Ipv4Addr::UNSPECIFIED, 0);
+        assert_eq!(all.subnets(0).unwrap().count(), 1);
+        assert_eq!(all.subnets(2).unwrap().count(), 4);
+    }
+
+    #[test]
+    fn test_ipv6_math() {
+        let net: Ipv6Net = "2001:db8::1/126".parse().unwrap();
+        assert_eq!(net.trunc().addr, "2001:db8::".parse::<Ipv6Addr>().unwrap());
+        assert_eq!(net.hosts().count(), 4);
+        assert_eq!(
+            net.hosts().next_back(),
+            Some("2001:db8::3".parse().unwrap())
+        );
+        let net: Ipv6Net = "2001:db8::/32".parse().unwrap();
+        let subnets: Vec<String> = net.subnets(34).unwrap().map(|n| n.to_string()).collect();
+        assert_eq!(
+            subnets,
+            vec![
+                "2001:db8::/34",
+                "2001:db8:4000::/34",
+                "2001:db8:8000::/34",
+                "2001:db8:c000::/34"
+            ]
+        );
+        assert!(net.contains_net(&"2001:db8:1::/48".parse().unwrap()));
+        assert_eq!(net.supernet().unwrap().to_string(), "2001:db8::/31");
+        let all = Ipv6Net::new(Ipv6Addr::UNSPECIFIED, 0);
+        assert_eq!(all.subnets(0).unwrap().count(), 1);
+        assert_eq!(all.subnets(1).unwrap().count(), 2);
+        let mut hosts = all.hosts();
+        assert_eq!(hosts.next(), Some(Ipv6Addr::UNSPECIFIED));
+        assert_eq!(hosts.next_back(), Some(Ipv6Addr::from(u128::MAX)));
+    }
+}
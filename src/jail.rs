@@ -0,0 +1,263 @@
+//! FreeBSD jail and vnet awareness
+//!
+//! A jail without its own network stack (vnet) shares the host's interfaces but
+//! only sees the addresses assigned to the jail, while a vnet jail has interfaces
+//! of its own that the host does not list. [`current`] tells which case a process
+//! is in, and [`interfaces_in`] enumerates the interfaces seen from another jail.
+//!
+//! Unlike `setns(2)`, `jail_attach(2)` moves the whole process and cannot be undone,
+//! so the enumeration runs in a forked child process that reports back over a pipe.
+//! Attaching to a jail requires root privileges on the host.
+
+use std::ffi::{CStr, CString};
+use std::io;
+
+#[cfg(feature = "serde_json")]
+use crate::interface::{self, Interface};
+#[cfg(feature = "serde_json")]
+use std::fs::File;
+#[cfg(feature = "serde_json")]
+use std::io::{Read, Write};
+#[cfg(feature = "serde_json")]
+use std::os::unix::io::FromRawFd;
+
+/// Jail status of the current process
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+pub struct JailInfo {
+    /// The process runs inside a jail
+    pub jailed: bool,
+    /// The jail has its own virtual network stack, and so its own interfaces,
+    /// routes and firewall
+    pub vnet: bool,
+}
+
+fn sysctl_int(name: &str) -> io::Result<libc::c_int> {
+    let name = CString::new(name).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+    let mut value: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>();
+    let ret = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            &mut value as *mut libc::c_int as *mut libc::c_void,
+            &mut len,
+            std::ptr::null(),
+            0,
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(value)
+}
+
+/// Returns whether the current process runs in a jail, and whether that jail has a vnet
+pub fn current() -> io::Result<JailInfo> {
+    let jailed = sysctl_int("security.jail.jailed")? != 0;
+    // Missing on kernels built without VIMAGE
+    let vnet = jailed && sysctl_int("security.jail.vnet").unwrap_or(0) != 0;
+    Ok(JailInfo { jailed, vnet })
+}
+
+fn iovec(ptr: *const u8, len: usize) -> libc::iovec {
+    libc::iovec {
+        iov_base: ptr as *mut libc::c_void,
+        iov_len: len,
+    }
+}
+
+fn str_iovec(s: &CStr) -> libc::iovec {
+    iovec(s.as_ptr().cast(), s.to_bytes_with_nul().len())
+}
+
+/// Returns the id of the jail with the given name or id, as `jls` lists them
+pub fn jid(name: &str) -> io::Result<i32> {
+    if let Ok(jid) = name.parse::<i32>() {
+        return Ok(jid);
+    }
+    let value = CString::new(name)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "invalid jail name"))?;
+    let mut jid: libc::c_int = 0;
+    // jail_get(2) takes parameters as pairs of name and value
+    let mut iov = [
+        str_iovec(c"name"),
+        str_iovec(&value),
+        str_iovec(c"jid"),
+        iovec(
+            &mut jid as *mut libc::c_int as *const u8,
+            std::mem::size_of::<libc::c_int>(),
+        ),
+    ];
+    let ret = unsafe { libc::jail_get(iov.as_mut_ptr(), iov.len() as libc::c_uint, 0) };
+    if ret < 0 {
+        let err = io::Error::last_os_error();
+        if err.raw_os_error() == Some(libc::ENOENT) {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("jail {} not found", name),
+            ));
+        }
+        return Err(err);
+    }
+    Ok(ret)
+}
+
+/// Attach to jail `jid` and write the interfaces seen there to `out`, one
+/// JSON object per line after a status line
+#[cfg(feature = "serde_json")]
+fn run_child(jid: i32, out: &mut File) -> libc::c_int {
+    if unsafe { libc::jail_attach(jid) } != 0 {
+        let errno = io::Error::last_os_error().raw_os_error().unwrap_or(0);
+        let _ = writeln!(out, "error {}", errno);
+        return 1;
+    }
+    if writeln!(out, "ok").is_err() {
+        return 1;
+    }
+    for interface in interface::get_interfaces() {
+        match interface.to_json() {
+            Ok(json) if writeln!(out, "{}", json).is_ok() => {}
+            _ => return 1,
+        }
+    }
+    0
+}
+
+/// Check the wait status of the child process
+#[cfg(feature = "serde_json")]
+fn exit_status(status: libc::c_int) -> io::Result<()> {
+    if libc::WIFEXITED(status) {
+        return match libc::WEXITSTATUS(status) {
+            0 => Ok(()),
+            code => Err(io::Error::other(format!(
+                "jail worker process exited with status {}",
+                code
+            ))),
+        };
+    }
+    if libc::WIFSIGNALED(status) {
+        return Err(io::Error::other(format!(
+            "jail worker process was killed by signal {}",
+            libc::WTERMSIG(status)
+        )));
+    }
+    Err(io::Error::other("jail worker process ended abnormally"))
+}
+
+#[cfg(feature = "serde_json")]
+fn parse_child_output(output: &str) -> io::Result<Vec<Interface>> {
+    let mut lines = output.lines();
+    match lines.next() {
+        Some("ok") => {}
+        Some(line) => {
+            let errno = line
+                .strip_prefix("error ")
+                .and_then(|errno| errno.parse().ok());
+            return Err(match errno {
+                Some(errno) => io::Error::from_raw_os_error(errno),
+                None => io::Error::new(io::ErrorKind::InvalidData, "unexpected jail worker output"),
+            });
+        }
+        None => {
+            return Err(io::Error::other(
+                "jail worker process exited without a result",
+            ))
+        }
+    }
+    lines
+        .map(|line| {
+            Interface::from_json(line)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+        })
+        .collect()
+}
+
+/// Get a list of the network interfaces seen from jail `jid`, see [`jid`] to
+/// look a jail up by name.
+///
+/// For a vnet jail these are the interfaces of its own network stack. The
+/// interfaces are passed from the child process in the [`crate::json`] schema.
+///
+/// The child is forked without exec and enumerates with the usual functions, so
+/// a lock held by another thread of the caller at the time of the fork stays
+/// held in it. Call this while no other thread enumerates interfaces, or from a
+/// single-threaded process.
+#[cfg(feature = "serde_json")]
+pub fn interfaces_in(jid: i32) -> io::Result<Vec<Interface>> {
+    let mut fds: [libc::c_int; 2] = [0; 2];
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let (read_fd, write_fd) = (fds[0], fds[1]);
+    let pid = unsafe { libc::fork() };
+    if pid < 0 {
+        let err = io::Error::last_os_error();
+        unsafe {
+            libc::close(read_fd);
+            libc::close(write_fd);
+        }
+        return Err(err);
+    }
+    if pid == 0 {
+        unsafe { libc::close(read_fd) };
+        let mut out = unsafe { File::from_raw_fd(write_fd) };
+        let code = run_child(jid, &mut out);
+        unsafe { libc::_exit(code) };
+    }
+    unsafe { libc::close(write_fd) };
+    let mut output = String::new();
+    let read = unsafe { File::from_raw_fd(read_fd) }.read_to_string(&mut output);
+    let mut status = 0;
+    while unsafe { libc::waitpid(pid, &mut status, 0) } < 0 {
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+    read?;
+    // A failed attach is reported on the pipe, anything else by the exit status
+    if !output.starts_with("error ") {
+        exit_status(status)?;
+    }
+    parse_child_output(&output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current() {
+        println!("{:?}", current());
+        assert!(jid("netdev-test-missing").is_err());
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_interfaces_in() {
+        // Attaching requires root and a running jail, so only print the result.
+        match jid("0").and_then(interfaces_in) {
+            Ok(interfaces) => {
+                for interface in interfaces {
+                    println!("{}", interface.name);
+                }
+            }
+            Err(e) => println!("{}", e),
+        }
+        assert_eq!(
+            parse_child_output("error 1\n").unwrap_err().raw_os_error(),
+            Some(libc::EPERM)
+        );
+        assert!(parse_child_output("").is_err());
+        assert!(parse_child_output("ok\n").unwrap().is_empty());
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_exit_status() {
+        let code = |code: libc::c_int| code << 8;
+        assert!(exit_status(code(0)).is_ok());
+        assert!(exit_status(code(1)).is_err());
+        // Killed by SIGSEGV
+        assert!(exit_status(libc::SIGSEGV).is_err());
+    }
+}
@@ -0,0 +1,495 @@
+//! Versioned JSON representation of [`Interface`].
+//!
+//! The schema is defined here independently of the serde derives on the public types,
+//! so the crate's data structures can evolve without breaking readers on the other
+//! end of a pipe or socket. Within a schema version keys are only ever added, never
+//! renamed or removed, and readers ignore keys they do not know.
+//!
+//! Schema version 1:
+//!
+//! ```text
+//! {
+//!   "version": 1,
+//!   "index": u32,
+//!   "name": string,
+//!   "friendly_name": string | null,
+//!   "description": string | null,
+//!   "if_type": string,              // InterfaceType variant name, e.g. "Ethernet"
+//!   "mac_addr": string | null,      // "aa:bb:cc:dd:ee:ff"
+//!   "hardware_addr": string | null, // colon-separated bytes, of any length
+//!   "ipv4": [{"addr": string, "prefix_len": u8}],
+//!   "ipv6": [{"addr": string, "prefix_len": u8}],
+//!   "flags": u32,
+//!   "transmit_speed": u64 | null,   // bits per second
+//!   "receive_speed": u64 | null,
+//!   "gateway": Gateway | null,      // the first entry of "gateways"
+//!   "gateways": [Gateway],          // Gateway = {"mac_addr": string, "ipv4": [string], "ipv6": [string]}
+//!   "dns_servers": [string],
+//!   "default": bool,
+//!   "stats": {
+//!     "rx_bytes": u64, "tx_bytes": u64, "rx_packets": u64, "tx_packets": u64,
+//!     "rx_errors": u64, "tx_errors": u64, "rx_dropped": u64, "tx_dropped": u64,
+//!     "timestamp_ms": u64 | null,   // milliseconds since the Unix epoch
+//!     "rx_queues": [Queue],         // Queue = {"index": u32, "packets": u64, "bytes": u64}
+//!     "tx_queues": [Queue],
+//!     "nic": {string: u64}          // driver-specific counters
+//!   } | null,
+//!   "driver": {
+//!     "name": string, "version": string | null,
+//!     "firmware_version": string | null, "bus_info": string | null
+//!   } | null,
+//!   "device_path": string | null,
+//!   "carrier_changes": u32 | null,
+//!   "link_up_since_ms": u64 | null, // milliseconds since the Unix epoch
+//!   "netns": u64 | null             // inode number of the network namespace
+//! }
+//! ```
+//!
+//! Interface types unknown to the reader are decoded as `Unknown`.
+
+use crate::device::NetworkDevice;
+use crate::interface::{DriverInfo, Interface, InterfaceType};
+use crate::ip::{Ipv4Net, Ipv6Net};
+use crate::mac::{HardwareAddr, MacAddr};
+use crate::stats::{InterfaceStats, QueueStats};
+use serde::{de::Error as _, Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Version of the schema written by [`Interface::to_json`]
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct InterfaceV1 {
+    version: u32,
+    index: u32,
+    name: String,
+    #[serde(default)]
+    friendly_name: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    if_type: String,
+    #[serde(default)]
+    mac_addr: Option<String>,
+    #[serde(default)]
+    hardware_addr: Option<String>,
+    #[serde(default)]
+    ipv4: Vec<NetV1<Ipv4Addr>>,
+    #[serde(default)]
+    ipv6: Vec<NetV1<Ipv6Addr>>,
+    #[serde(default)]
+    flags: u32,
+    #[serde(default)]
+    transmit_speed: Option<u64>,
+    #[serde(default)]
+    receive_speed: Option<u64>,
+    #[serde(default)]
+    gateway: Option<GatewayV1>,
+    #[serde(default)]
+    gateways: Vec<GatewayV1>,
+    #[serde(default)]
+    dns_servers: Vec<IpAddr>,
+    #[serde(default)]
+    default: bool,
+    #[serde(default)]
+    stats: Option<StatsV1>,
+    #[serde(default)]
+    driver: Option<DriverV1>,
+    #[serde(default)]
+    device_path: Option<String>,
+    #[serde(default)]
+    carrier_changes: Option<u32>,
+    #[serde(default)]
+    link_up_since_ms: Option<u64>,
+    #[serde(default)]
+    netns: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NetV1<A> {
+    addr: A,
+    prefix_len: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GatewayV1 {
+    mac_addr: String,
+    #[serde(default)]
+    ipv4: Vec<Ipv4Addr>,
+    #[serde(default)]
+    ipv6: Vec<Ipv6Addr>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StatsV1 {
+    rx_bytes: u64,
+    tx_bytes: u64,
+    rx_packets: u64,
+    tx_packets: u64,
+    rx_errors: u64,
+    tx_errors: u64,
+    rx_dropped: u64,
+    tx_dropped: u64,
+    #[serde(default)]
+    timestamp_ms: Option<u64>,
+    #[serde(default)]
+    rx_queues: Vec<QueueV1>,
+    #[serde(default)]
+    tx_queues: Vec<QueueV1>,
+    #[serde(default)]
+    nic: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct QueueV1 {
+    index: u32,
+    packets: u64,
+    bytes: u64,
+}
+
+impl From<&QueueStats> for QueueV1 {
+    fn from(queue: &QueueStats) -> Self {
+        QueueV1 {
+            index: queue.index,
+            packets: queue.packets,
+            bytes: queue.bytes,
+        }
+    }
+}
+
+impl From<QueueV1> for QueueStats {
+    fn from(queue: QueueV1) -> Self {
+        QueueStats {
+            index: queue.index,
+            packets: queue.packets,
+            bytes: queue.bytes,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct DriverV1 {
+    name: String,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    firmware_version: Option<String>,
+    #[serde(default)]
+    bus_info: Option<String>,
+}
+
+impl From<&NetworkDevice> for GatewayV1 {
+    fn from(gw: &NetworkDevice) -> Self {
+        GatewayV1 {
+            mac_addr: gw.mac_addr.to_string(),
+            ipv4: gw.ipv4.clone(),
+            ipv6: gw.ipv6.clone(),
+        }
+    }
+}
+
+impl GatewayV1 {
+    fn into_device(self) -> Result<NetworkDevice, serde_json::Error> {
+        Ok(NetworkDevice {
+            mac_addr: parse_mac(&self.mac_addr)?,
+            ipv4: self.ipv4,
+            ipv6: self.ipv6,
+        })
+    }
+}
+
+fn parse_mac<E: serde::de::Error>(mac: &str) -> Result<MacAddr, E> {
+    mac.parse().map_err(E::custom)
+}
+
+/// Convert milliseconds since the Unix epoch, which may not fit a `SystemTime`
+fn time_from_millis<E: serde::de::Error>(ms: u64) -> Result<SystemTime, E> {
+    UNIX_EPOCH
+        .checked_add(Duration::from_millis(ms))
+        .ok_or_else(|| E::custom(format!("timestamp {} out of range", ms)))
+}
+
+/// Check that a prefix length fits an address of `bits` bits
+fn check_prefix_len<E: serde::de::Error>(prefix_len: u8, bits: u8) -> Result<u8, E> {
+    if prefix_len > bits {
+        return Err(E::custom(format!("invalid prefix length {}", prefix_len)));
+    }
+    Ok(prefix_len)
+}
+
+impl From<&Interface> for InterfaceV1 {
+    fn from(iface: &Interface) -> Self {
+        InterfaceV1 {
+            version: SCHEMA_VERSION,
+            index: iface.index,
+            name: iface.name.clone(),
+            friendly_name: iface.friendly_name.clone(),
+            description: iface.description.clone(),
+            if_type: format!("{:?}", iface.if_type),
+            mac_addr: iface.mac_addr.map(|mac| mac.to_string()),
+            hardware_addr: iface.hardware_addr.as_ref().map(|addr| addr.to_string()),
+            ipv4: iface
+                .ipv4
+                .iter()
+                .map(|net| NetV1 {
+                    addr: net.addr,
+                    prefix_len: net.prefix_len,
+                })
+                .collect(),
+            ipv6: iface
+                .ipv6
+                .iter()
+                .map(|net| NetV1 {
+                    addr: net.addr,
+                    prefix_len: net.prefix_len,
+                })
+                .collect(),
+            flags: iface.flags,
+            transmit_speed: iface.transmit_speed,
+            receive_speed: iface.receive_speed,
+            gateway: iface.gateways.first().map(GatewayV1::from),
+            gateways: iface.gateways.iter().map(GatewayV1::from).collect(),
+            dns_servers: iface.dns_servers.clone(),
+            default: iface.default,
+            stats: iface.stats.as_ref().map(|stats| StatsV1 {
+                rx_bytes: stats.rx_bytes,
+                tx_bytes: stats.tx_bytes,
+                rx_packets: stats.rx_packets,
+                tx_packets: stats.tx_packets,
+                rx_errors: stats.rx_errors,
+                tx_errors: stats.tx_errors,
+                rx_dropped: stats.rx_dropped,
+                tx_dropped: stats.tx_dropped,
+                timestamp_ms: stats
+                    .timestamp
+                    .and_then(|ts| ts.duration_since(UNIX_EPOCH).ok())
+                    .map(|ts| ts.as_millis() as u64),
+                rx_queues: stats.rx_queues.iter().map(QueueV1::from).collect(),
+                tx_queues: stats.tx_queues.iter().map(QueueV1::from).collect(),
+                nic: stats.nic.clone(),
+            }),
+            driver: iface.driver.as_ref().map(|driver| DriverV1 {
+                name: driver.name.clone(),
+                version: driver.version.clone(),
+                firmware_version: driver.firmware_version.clone(),
+                bus_info: driver.bus_info.clone(),
+            }),
+            device_path: iface.device_path.clone(),
+            carrier_changes: iface.carrier_changes,
+            link_up_since_ms: iface
+                .link_up_since
+                .and_then(|since| since.duration_since(UNIX_EPOCH).ok())
+                .map(|since| since.as_millis() as u64),
+            netns: iface.netns,
+        }
+    }
+}
+
+impl InterfaceV1 {
+    fn into_interface(self) -> Result<Interface, serde_json::Error> {
+        if self.version == 0 || self.version > SCHEMA_VERSION {
+            return Err(serde_json::Error::custom(format!(
+                "unsupported schema version {}",
+                self.version
+            )));
+        }
+        let mac_addr = match self.mac_addr {
+            Some(mac) => Some(parse_mac(&mac)?),
+            None => None,
+        };
+        // Writers older than "hardware_addr" only set "mac_addr".
+        let hardware_addr = match self.hardware_addr {
+            Some(addr) => Some(addr.parse().map_err(serde_json::Error::custom)?),
+            None => mac_addr.map(HardwareAddr::from),
+        };
+        // Writers older than "gateways" only set "gateway".
+        let gateways = if self.gateways.is_empty() {
+            self.gateway.into_iter().collect()
+        } else {
+            self.gateways
+        };
+        let gateways = gateways
+            .into_iter()
+            .map(GatewayV1::into_device)
+            .collect::<Result<Vec<_>, _>>()?;
+        let ipv4 = self
+            .ipv4
+            .into_iter()
+            .map(|net| {
+                Ok(Ipv4Net::new(
+                    net.addr,
+                    check_prefix_len(net.prefix_len, 32)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, serde_json::Error>>()?;
+        let ipv6 = self
+            .ipv6
+            .into_iter()
+            .map(|net| {
+                Ok(Ipv6Net::new(
+                    net.addr,
+                    check_prefix_len(net.prefix_len, 128)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, serde_json::Error>>()?;
+        let stats = match self.stats {
+            Some(stats) => Some(InterfaceStats {
+                rx_bytes: stats.rx_bytes,
+                tx_bytes: stats.tx_bytes,
+                rx_packets: stats.rx_packets,
+                tx_packets: stats.tx_packets,
+                rx_errors: stats.rx_errors,
+                tx_errors: stats.tx_errors,
+                rx_dropped: stats.rx_dropped,
+                tx_dropped: stats.tx_dropped,
+                timestamp: stats.timestamp_ms.map(time_from_millis).transpose()?,
+                rx_queues: stats.rx_queues.into_iter().map(QueueStats::from).collect(),
+                tx_queues: stats.tx_queues.into_iter().map(QueueStats::from).collect(),
+                nic: stats.nic,
+            }),
+            None => None,
+        };
+        Ok(Interface {
+            index: self.index,
+            name: self.name,
+            friendly_name: self.friendly_name,
+            description: self.description,
+            if_type: self.if_type.parse().unwrap_or(InterfaceType::Unknown),
+            mac_addr,
+            hardware_addr,
+            ipv4,
+            ipv6,
+            flags: self.flags,
+            transmit_speed: self.transmit_speed,
+            receive_speed: self.receive_speed,
+            gateways,
+            dns_servers: self.dns_servers,
+            default: self.default,
+            stats,
+            driver: self.driver.map(|driver| DriverInfo {
+                name: driver.name,
+                version: driver.version,
+                firmware_version: driver.firmware_version,
+                bus_info: driver.bus_info,
+            }),
+            device_path: self.device_path,
+            carrier_changes: self.carrier_changes,
+            link_up_since: self.link_up_since_ms.map(time_from_millis).transpose()?,
+            network_manager: None,
+            networkd: None,
+            netns: self.netns,
+        })
+    }
+}
+
+impl Interface {
+    /// Serialize the interface using the versioned schema described in [`crate::json`]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&InterfaceV1::from(self))
+    }
+    /// Deserialize an interface written by [`Interface::to_json`].
+    ///
+    /// Fails on documents with a newer schema version than this crate knows.
+    pub fn from_json(json: &str) -> Result<Interface, serde_json::Error> {
+        serde_json::from_str::<InterfaceV1>(json)?.into_interface()
+    }
+}
+
+/// Encode an interface for embedding in a larger document
+#[cfg(feature = "remote")]
+pub(crate) fn interface_to_value(
+    iface: &Interface,
+) -> Result<serde_json::Value, serde_json::Error> {
+    serde_json::to_value(InterfaceV1::from(iface))
+}
+
+/// Decode an interface encoded with [`interface_to_value`]
+#[cfg(feature = "remote")]
+pub(crate) fn interface_from_value(
+    value: serde_json::Value,
+) -> Result<Interface, serde_json::Error> {
+    serde_json::from_value::<InterfaceV1>(value)?.into_interface()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut iface = Interface::dummy();
+        iface.index = 3;
+        iface.name = String::from("eth0");
+        iface.if_type = InterfaceType::Ethernet;
+        iface.mac_addr = Some(MacAddr::new(0x02, 0, 0, 0, 0, 0x01));
+        iface.hardware_addr = iface.mac_addr.map(HardwareAddr::from);
+        iface
+            .ipv4
+            .push(Ipv4Net::new(Ipv4Addr::new(192, 0, 2, 10), 24));
+        iface
+            .ipv6
+            .push(Ipv6Net::new(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1), 64));
+        iface.gateways = vec![
+            NetworkDevice {
+                mac_addr: MacAddr::new(0x02, 0, 0, 0, 0, 0xfe),
+                ipv4: vec![Ipv4Addr::new(192, 0, 2, 1)],
+                ipv6: Vec::new(),
+            },
+            NetworkDevice {
+                mac_addr: MacAddr::zero(),
+                ipv4: Vec::new(),
+                ipv6: vec![Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 0xfe)],
+            },
+        ];
+        iface.stats = Some(InterfaceStats {
+            rx_bytes: 1000,
+            timestamp: Some(UNIX_EPOCH + Duration::from_millis(1_700_000_000_123)),
+            ..Default::default()
+        });
+        iface.driver = Some(DriverInfo {
+            name: String::from("e1000e"),
+            version: Some(String::from("3.2.6-k")),
+            firmware_version: None,
+            bus_info: Some(String::from("0000:00:19.0")),
+        });
+        iface.device_path = Some(String::from("pci0000:00/0000:00:19.0"));
+        iface.carrier_changes = Some(4);
+        iface.link_up_since = Some(UNIX_EPOCH + Duration::from_millis(1_700_000_100_000));
+        iface.netns = Some(4026531840);
+        let json = iface.to_json().unwrap();
+        assert!(Interface::from_json(&json).unwrap().content_eq(&iface));
+    }
+
+    #[test]
+    fn test_schema_keys() {
+        let json = r#"{"version":1,"index":2,"name":"wlan0","if_type":"Wireless80211",
+            "mac_addr":"02:00:00:00:00:01","ipv4":[{"addr":"10.0.0.2","prefix_len":8}],
+            "some_future_key":true}"#;
+        let iface = Interface::from_json(json).unwrap();
+        assert_eq!(iface.name, "wlan0");
+        assert_eq!(iface.hardware_addr, iface.mac_addr.map(HardwareAddr::from));
+        assert_eq!(iface.if_type, InterfaceType::Wireless80211);
+        assert_eq!(iface.ipv4[0].netmask, Ipv4Addr::new(255, 0, 0, 0));
+
+        let json = r#"{"version":1,"index":2,"name":"wlan0","if_type":"Ethernet",
+            "gateway":{"mac_addr":"02:00:00:00:00:fe","ipv4":["10.0.0.1"]}}"#;
+        let iface = Interface::from_json(json).unwrap();
+        assert_eq!(iface.gateways.len(), 1);
+        assert_eq!(iface.gateways[0].ipv4, vec![Ipv4Addr::new(10, 0, 0, 1)]);
+
+        let json = r#"{"version":2,"index":2,"name":"wlan0","if_type":"Ethernet"}"#;
+        assert!(Interface::from_json(json).is_err());
+
+        let json = r#"{"version":1,"index":2,"name":"wlan0","if_type":"Ethernet",
+            "ipv4":[{"addr":"10.0.0.2","prefix_len":33}]}"#;
+        let err = Interface::from_json(json).unwrap_err();
+        assert_eq!(
+            std::io::Error::from(err).kind(),
+            std::io::ErrorKind::InvalidData
+        );
+        let json = r#"{"version":1,"index":2,"name":"wlan0","if_type":"Ethernet",
+            "ipv6":[{"addr":"fe80::1","prefix_len":129}]}"#;
+        assert!(Interface::from_json(json).is_err());
+    }
+}
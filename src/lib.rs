@@ -1,13 +1,79 @@
+#[macro_use]
+mod macros;
+
+pub mod backend;
+pub mod bond;
+pub mod bridge;
+pub mod capabilities;
+pub mod config;
+#[cfg(feature = "connectivity")]
+pub mod connectivity;
 mod db;
 pub mod device;
+#[cfg(feature = "dhcp")]
+pub mod dhcp;
+mod dns;
+pub mod environment;
+pub mod error;
+#[cfg(feature = "firewall")]
+pub mod firewall;
 pub mod gateway;
 pub mod interface;
+#[cfg(any(feature = "pnet", feature = "socket2"))]
+mod interop;
 pub mod ip;
+#[cfg(target_os = "freebsd")]
+pub mod jail;
+#[cfg(feature = "serde_json")]
+pub mod json;
 pub mod mac;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod monitor;
+#[cfg(feature = "natpmp")]
+pub mod natpmp;
+#[cfg(feature = "config")]
+pub mod neighbor;
+#[cfg(target_os = "linux")]
+pub mod netns;
+pub mod parse;
+pub mod privileges;
+#[cfg(feature = "probe")]
+pub mod probe;
+#[cfg(feature = "ra")]
+pub mod ra;
+#[cfg(feature = "raw")]
+pub mod raw;
+#[cfg(feature = "remote")]
+pub mod remote;
+pub mod resolve;
+pub mod route;
+#[cfg(feature = "scan")]
+pub mod scan;
+#[cfg(feature = "socket2")]
+pub mod socket;
+pub mod sockets;
+pub mod stats;
 mod sys;
+#[cfg(feature = "wireguard")]
+pub mod wireguard;
+pub mod wol;
+#[cfg(feature = "wwan")]
+pub mod wwan;
+pub mod zone;
 
+pub use capabilities::capabilities;
 pub use device::NetworkDevice;
+pub use error::Error;
 pub use gateway::get_default_gateway;
 pub use interface::get_default_interface;
 pub use interface::get_interfaces;
+pub use interface::group_by_device;
+pub use interface::index_to_name;
+pub use interface::interfaces_with;
+pub use interface::name_to_index;
+pub use interface::rank_interfaces;
+pub use interface::snapshot;
 pub use interface::Interface;
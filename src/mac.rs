@@ -1,4 +1,5 @@
 use core::str::FromStr;
+use std::array::TryFromSliceError;
 use std::error;
 use std::fmt;
 
@@ -24,12 +25,31 @@ impl MacAddr {
     pub fn octets(&self) -> [u8; 6] {
         [self.0, self.1, self.2, self.3, self.4, self.5]
     }
-    /// Return a formatted string of MAC address
+    /// Return a formatted string of MAC address, same as `to_string()`
     pub fn address(&self) -> String {
-        format!(
-            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
-            self.0, self.1, self.2, self.3, self.4, self.5
-        )
+        self.to_string()
+    }
+    /// Format the address as `00:1b:21:0a:bc:de` into `buf` and return it as a `str`
+    pub fn format_into<'a>(&self, buf: &'a mut [u8; MAC_ADDR_STR_LEN]) -> &'a str {
+        for (i, octet) in self.octets().iter().enumerate() {
+            buf[i * 3] = HEX_DIGITS[(octet >> 4) as usize];
+            buf[i * 3 + 1] = HEX_DIGITS[(octet & 0xf) as usize];
+            if i < 5 {
+                buf[i * 3 + 2] = b':';
+            }
+        }
+        // Only ASCII hex digits and colons were written
+        std::str::from_utf8(buf).unwrap()
+    }
+    /// Write the address as `00:1b:21:0a:bc:de` to `w` without allocating
+    pub fn write_to(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        w.write_str(self.to_mac_string().as_str())
+    }
+    /// Format the address as `00:1b:21:0a:bc:de` into a stack-allocated string
+    pub fn to_mac_string(&self) -> MacAddrString {
+        let mut buf = [0u8; MAC_ADDR_STR_LEN];
+        self.format_into(&mut buf);
+        MacAddrString { buf }
     }
     /// Construct an all-zero MacAddr instance
     pub fn zero() -> MacAddr {
@@ -39,36 +59,317 @@ impl MacAddr {
     pub fn broadcast() -> MacAddr {
         MacAddr(0xff, 0xff, 0xff, 0xff, 0xff, 0xff)
     }
+    /// Returns the Organizationally Unique Identifier (first three octets)
+    pub fn oui(&self) -> [u8; 3] {
+        [self.0, self.1, self.2]
+    }
+    /// Check if the MAC address is a unicast address (I/G bit cleared)
+    pub fn is_unicast(&self) -> bool {
+        self.0 & 0x01 == 0
+    }
+    /// Check if the MAC address is a multicast address (I/G bit set)
+    pub fn is_multicast(&self) -> bool {
+        self.0 & 0x01 != 0
+    }
+    /// Check if the MAC address is universally administered (U/L bit cleared)
+    pub fn is_universal(&self) -> bool {
+        self.0 & 0x02 == 0
+    }
+    /// Check if the MAC address is locally administered (U/L bit set)
+    pub fn is_locally_administered(&self) -> bool {
+        self.0 & 0x02 != 0
+    }
+    /// Construct a random unicast MacAddr instance
+    #[cfg(feature = "rand")]
+    pub fn random() -> MacAddr {
+        let mut octets: [u8; 6] = rand::random();
+        octets[0] &= !0x01;
+        MacAddr::from_octets(octets)
+    }
+    /// Construct a random unicast, locally administered MacAddr instance
+    ///
+    /// Suitable for virtual interfaces, since it cannot collide with vendor-assigned addresses.
+    #[cfg(feature = "rand")]
+    pub fn random_locally_administered() -> MacAddr {
+        let mut octets: [u8; 6] = rand::random();
+        octets[0] = (octets[0] & !0x01) | 0x02;
+        MacAddr::from_octets(octets)
+    }
+    /// Construct a MacAddr instance with the given OUI and a random device-specific part
+    #[cfg(feature = "rand")]
+    pub fn with_oui(oui: [u8; 3]) -> MacAddr {
+        let nic: [u8; 3] = rand::random();
+        MacAddr(oui[0], oui[1], oui[2], nic[0], nic[1], nic[2])
+    }
     /// Construct a new MacAddr instance from a colon-separated string of hex format
     pub fn from_hex_format(hex_mac_addr: &str) -> MacAddr {
         if hex_mac_addr.len() != 17 {
             return MacAddr(0, 0, 0, 0, 0, 0);
         }
         let fields: Vec<&str> = hex_mac_addr.split(":").collect();
-        let o1: u8 = u8::from_str_radix(&fields[0], 0x10).unwrap_or(0);
-        let o2: u8 = u8::from_str_radix(&fields[1], 0x10).unwrap_or(0);
-        let o3: u8 = u8::from_str_radix(&fields[2], 0x10).unwrap_or(0);
-        let o4: u8 = u8::from_str_radix(&fields[3], 0x10).unwrap_or(0);
-        let o5: u8 = u8::from_str_radix(&fields[4], 0x10).unwrap_or(0);
-        let o6: u8 = u8::from_str_radix(&fields[5], 0x10).unwrap_or(0);
+        let o1: u8 = u8::from_str_radix(fields[0], 0x10).unwrap_or(0);
+        let o2: u8 = u8::from_str_radix(fields[1], 0x10).unwrap_or(0);
+        let o3: u8 = u8::from_str_radix(fields[2], 0x10).unwrap_or(0);
+        let o4: u8 = u8::from_str_radix(fields[3], 0x10).unwrap_or(0);
+        let o5: u8 = u8::from_str_radix(fields[4], 0x10).unwrap_or(0);
+        let o6: u8 = u8::from_str_radix(fields[5], 0x10).unwrap_or(0);
         MacAddr(o1, o2, o3, o4, o5, o6)
     }
+    /// Parse six two-digit hex octets separated by either `:` or `-` throughout,
+    /// such as `00:1b:21:0a:bc:de`. Suited to machine-generated input like sysfs.
+    pub fn parse_strict(s: &str) -> Result<MacAddr, ParseMacAddrError> {
+        crate::parse::mac_addr_strict(s)
+    }
+    /// Parse a MAC address typed by a user or printed by another tool, tolerating
+    /// whitespace, single-digit octets, mixed separators and `0x` prefixes.
+    /// See [`parse::mac_addr_lenient`](crate::parse::mac_addr_lenient) for the accepted forms.
+    pub fn parse_lenient(s: &str) -> Result<MacAddr, ParseMacAddrError> {
+        crate::parse::mac_addr_lenient(s)
+    }
+    fn to_u64(self) -> u64 {
+        let mut bytes = [0u8; 8];
+        bytes[2..].copy_from_slice(&self.octets());
+        u64::from_be_bytes(bytes)
+    }
+    fn from_u64(value: u64) -> MacAddr {
+        let bytes = value.to_be_bytes();
+        MacAddr(bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7])
+    }
+    /// Returns the address `n` after this one, treating the address as a 48-bit
+    /// number, or `None` if that would pass `ff:ff:ff:ff:ff:ff`.
+    ///
+    /// Carries propagate into the OUI, so callers allocating within one OUI
+    /// should check [`oui`](MacAddr::oui) of the result.
+    pub fn checked_add(&self, n: u64) -> Option<MacAddr> {
+        let value = self.to_u64().checked_add(n)?;
+        (value <= MAX_MAC_VALUE).then(|| MacAddr::from_u64(value))
+    }
+    /// Returns the address following this one, or `None` for `ff:ff:ff:ff:ff:ff`
+    pub fn next(&self) -> Option<MacAddr> {
+        self.checked_add(1)
+    }
 }
 
-impl std::fmt::Display for MacAddr {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let _ = write!(
-            f,
-            "{:<02x}:{:<02x}:{:<02x}:{:<02x}:{:<02x}:{:<02x}",
-            self.0, self.1, self.2, self.3, self.4, self.5
-        );
+/// Length of a MAC address formatted as `00:1b:21:0a:bc:de`
+pub const MAC_ADDR_STR_LEN: usize = 17;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// MAC address formatted as `00:1b:21:0a:bc:de`, stored inline.
+///
+/// Returned by [`MacAddr::to_mac_string`] for logging paths that should not
+/// allocate a `String` per address.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct MacAddrString {
+    buf: [u8; MAC_ADDR_STR_LEN],
+}
+
+impl MacAddrString {
+    /// Returns the formatted address
+    pub fn as_str(&self) -> &str {
+        // Only ever filled by `MacAddr::format_into`
+        std::str::from_utf8(&self.buf).unwrap()
+    }
+}
+
+impl std::ops::Deref for MacAddrString {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for MacAddrString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for MacAddrString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl fmt::Debug for MacAddrString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+/// Largest MAC address as a 48-bit number
+const MAX_MAC_VALUE: u64 = (1 << 48) - 1;
+
+/// Iterator over a range of consecutive MAC addresses, both ends included
+///
+/// ```
+/// use netdev::mac::{MacAddr, MacAddrRange};
+///
+/// let first = MacAddr::new(0x02, 0, 0, 0, 0, 0xfe);
+/// let pool: Vec<MacAddr> = MacAddrRange::with_count(first, 3).collect();
+/// assert_eq!(pool.last(), Some(&MacAddr::new(0x02, 0, 0, 0, 1, 0x00)));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MacAddrRange {
+    /// Next address to yield from the front
+    front: u64,
+    /// Next address to yield from the back; the range is empty once `front > back`
+    back: u64,
+}
+
+impl MacAddrRange {
+    /// Create a range from `first` to `last`, inclusive.
+    /// The range is empty if `last` is before `first`.
+    pub fn new(first: MacAddr, last: MacAddr) -> MacAddrRange {
+        let (front, back) = (first.to_u64(), last.to_u64());
+        if back < front {
+            return MacAddrRange { front: 1, back: 0 };
+        }
+        MacAddrRange { front, back }
+    }
+    /// Create a range of `count` addresses starting at `first`, cut short at
+    /// `ff:ff:ff:ff:ff:ff`
+    pub fn with_count(first: MacAddr, count: u64) -> MacAddrRange {
+        if count == 0 {
+            return MacAddrRange { front: 1, back: 0 };
+        }
+        let front = first.to_u64();
+        let back = front.saturating_add(count - 1).min(MAX_MAC_VALUE);
+        MacAddrRange { front, back }
+    }
+    /// Returns the number of addresses left in the range
+    pub fn remaining(&self) -> u64 {
+        if self.front > self.back {
+            0
+        } else {
+            self.back - self.front + 1
+        }
+    }
+    /// Check if `mac_addr` is among the addresses left in the range
+    pub fn contains(&self, mac_addr: &MacAddr) -> bool {
+        let value = mac_addr.to_u64();
+        self.front <= value && value <= self.back
+    }
+}
+
+impl Iterator for MacAddrRange {
+    type Item = MacAddr;
+
+    fn next(&mut self) -> Option<MacAddr> {
+        if self.front > self.back {
+            return None;
+        }
+        let mac_addr = MacAddr::from_u64(self.front);
+        self.front += 1;
+        Some(mac_addr)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.remaining()) {
+            Ok(n) => (n, Some(n)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+impl DoubleEndedIterator for MacAddrRange {
+    fn next_back(&mut self) -> Option<MacAddr> {
+        if self.front > self.back {
+            return None;
+        }
+        let mac_addr = MacAddr::from_u64(self.back);
+        // `back` is 0 only when `front` is too, so mark the range empty instead
+        if self.back == 0 {
+            self.front = 1;
+        } else {
+            self.back -= 1;
+        }
+        Some(mac_addr)
+    }
+}
+
+impl std::iter::FusedIterator for MacAddrRange {}
+
+/// Formats as lowercase and colon-separated (`00:1b:21:0a:bc:de`).
+///
+/// The alternate form `{:#}` is uppercase and hyphen-separated
+/// (`00-1B-21-0A-BC-DE`), as used on Windows.
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let o = self.octets();
+        if f.alternate() {
+            write!(
+                f,
+                "{:02X}-{:02X}-{:02X}-{:02X}-{:02X}-{:02X}",
+                o[0], o[1], o[2], o[3], o[4], o[5]
+            )
+        } else {
+            f.write_str(self.to_mac_string().as_str())
+        }
+    }
+}
+
+/// Formats as 12 lowercase hex digits without separators (`001b210abcde`),
+/// with a `0x` prefix in the alternate form
+impl fmt::LowerHex for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        for octet in self.octets() {
+            write!(f, "{:02x}", octet)?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats as 12 uppercase hex digits without separators (`001B210ABCDE`),
+/// with a `0x` prefix in the alternate form
+impl fmt::UpperHex for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        for octet in self.octets() {
+            write!(f, "{:02X}", octet)?;
+        }
         Ok(())
     }
 }
 
+impl From<[u8; 6]> for MacAddr {
+    fn from(octets: [u8; 6]) -> MacAddr {
+        MacAddr::from_octets(octets)
+    }
+}
+
+impl From<MacAddr> for [u8; 6] {
+    fn from(mac: MacAddr) -> [u8; 6] {
+        mac.octets()
+    }
+}
+
+/// Converts a slice of exactly six bytes, such as the source address field of
+/// an Ethernet frame
+impl TryFrom<&[u8]> for MacAddr {
+    type Error = TryFromSliceError;
+    fn try_from(bytes: &[u8]) -> Result<MacAddr, TryFromSliceError> {
+        <[u8; 6]>::try_from(bytes).map(MacAddr::from_octets)
+    }
+}
+
+/// Converts to the address as a 48-bit number in the low bits, so
+/// `00:1b:21:0a:bc:de` becomes `0x001b210abcde`
+impl From<MacAddr> for u64 {
+    fn from(mac: MacAddr) -> u64 {
+        mac.to_u64()
+    }
+}
+
 #[cfg(feature = "serde")]
 impl Serialize for MacAddr {
-    /// Serializes the MAC address.
+    /// Serializes the MAC address as a string such as `"aa:bb:cc:dd:ee:ff"` for
+    /// human-readable formats, and as 6 bytes for binary formats.
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         if serializer.is_human_readable() {
             serializer.collect_str(self)
@@ -80,7 +381,9 @@ impl Serialize for MacAddr {
 
 #[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for MacAddr {
-    /// Deserializes the MAC address.
+    /// Deserializes the MAC address from a string or 6 bytes. Human-readable
+    /// formats also accept a sequence of 6 numbers, as written by
+    /// [`serde_tuple`].
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         struct MacAddrVisitor;
         impl<'de> de::Visitor<'de> for MacAddrVisitor {
@@ -98,6 +401,19 @@ impl<'de> Deserialize<'de> for MacAddr {
                 }
             }
 
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<MacAddr, A::Error> {
+                let mut octets = [0u8; 6];
+                for (i, octet) in octets.iter_mut().enumerate() {
+                    *octet = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+                }
+                if seq.next_element::<de::IgnoredAny>()?.is_some() {
+                    return Err(de::Error::invalid_length(7, &self));
+                }
+                Ok(MacAddr::from_octets(octets))
+            }
+
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
                 write!(
                     formatter,
@@ -106,17 +422,55 @@ impl<'de> Deserialize<'de> for MacAddr {
             }
         }
 
-        // Decide what hint to provide to the deserializer based on if it is human readable or not
+        // Decide what hint to provide to the deserializer based on if it is human readable or not.
+        // Self-describing formats pick the visitor method from the data.
         if deserializer.is_human_readable() {
-            deserializer.deserialize_str(MacAddrVisitor)
+            deserializer.deserialize_any(MacAddrVisitor)
         } else {
             deserializer.deserialize_bytes(MacAddrVisitor)
         }
     }
 }
 
+/// Serialize a [`MacAddr`] as a tuple of 6 numbers, the representation of the
+/// derived implementation of earlier releases.
+///
+/// Use it with `#[serde(with = "netdev::mac::serde_tuple")]` on fields whose
+/// data was stored by an earlier release, in particular with binary formats,
+/// which cannot tell the tuple and the 6 bytes apart.
+///
+/// ```
+/// use netdev::mac::MacAddr;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Record {
+///     #[serde(with = "netdev::mac::serde_tuple")]
+///     mac: MacAddr,
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub mod serde_tuple {
+    use super::MacAddr;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    type Tuple = (u8, u8, u8, u8, u8, u8);
+
+    /// Serialize `mac` as a tuple of 6 numbers
+    pub fn serialize<S: Serializer>(mac: &MacAddr, serializer: S) -> Result<S::Ok, S::Error> {
+        (mac.0, mac.1, mac.2, mac.3, mac.4, mac.5).serialize(serializer)
+    }
+
+    /// Deserialize a MAC address from a tuple of 6 numbers
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MacAddr, D::Error> {
+        let (a, b, c, d, e, f) = Tuple::deserialize(deserializer)?;
+        Ok(MacAddr(a, b, c, d, e, f))
+    }
+}
+
 /// Represents an error which occurred whilst parsing a MAC address
 #[derive(Copy, Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
 pub enum ParseMacAddrError {
     /// The MAC address has too many components, eg. 00:11:22:33:44:55:66
     TooManyComponents,
@@ -144,29 +498,309 @@ impl fmt::Display for ParseMacAddrError {
     }
 }
 
+/// Parses six two-digit hex octets separated by either `:` or `-` throughout,
+/// as [`MacAddr::parse_strict`] does
 impl FromStr for MacAddr {
     type Err = ParseMacAddrError;
     fn from_str(s: &str) -> Result<MacAddr, ParseMacAddrError> {
-        let mut parts = [0u8; 6];
-        let splits = s.split(':');
-        let mut i = 0;
-        for split in splits {
-            if i == 6 {
-                return Err(ParseMacAddrError::TooManyComponents);
+        crate::parse::mac_addr_strict(s)
+    }
+}
+
+/// Link-layer address of any length
+///
+/// Ethernet and Wi-Fi use 6-byte MAC addresses, while InfiniBand (and IPoIB)
+/// interfaces have 20-byte addresses made of queue pair flags, the queue pair
+/// number and the port GID.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum HardwareAddr {
+    /// 6-byte IEEE 802 MAC address
+    Mac6(MacAddr),
+    /// 20-byte InfiniBand address
+    Infiniband20([u8; 20]),
+    /// Address of another length
+    Other(Vec<u8>),
+}
+
+impl HardwareAddr {
+    /// Construct a `HardwareAddr` from raw bytes, picking the variant by length
+    pub fn from_bytes(bytes: &[u8]) -> HardwareAddr {
+        if let Ok(octets) = <[u8; 6]>::try_from(bytes) {
+            HardwareAddr::Mac6(MacAddr::from_octets(octets))
+        } else if let Ok(octets) = <[u8; 20]>::try_from(bytes) {
+            HardwareAddr::Infiniband20(octets)
+        } else {
+            HardwareAddr::Other(bytes.to_vec())
+        }
+    }
+    /// Returns the address bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            HardwareAddr::Mac6(mac) => mac.octets().to_vec(),
+            HardwareAddr::Infiniband20(octets) => octets.to_vec(),
+            HardwareAddr::Other(octets) => octets.clone(),
+        }
+    }
+    /// Returns the length of the address in bytes
+    pub fn len(&self) -> usize {
+        match self {
+            HardwareAddr::Mac6(_) => 6,
+            HardwareAddr::Infiniband20(_) => 20,
+            HardwareAddr::Other(octets) => octets.len(),
+        }
+    }
+    /// Check if the address has no bytes
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the MAC address, if this is a 6-byte address
+    pub fn mac_addr(&self) -> Option<MacAddr> {
+        match self {
+            HardwareAddr::Mac6(mac) => Some(*mac),
+            _ => None,
+        }
+    }
+}
+
+impl From<MacAddr> for HardwareAddr {
+    fn from(mac: MacAddr) -> HardwareAddr {
+        HardwareAddr::Mac6(mac)
+    }
+}
+
+/// Formats as lowercase, colon-separated bytes, like [`MacAddr`]
+impl fmt::Display for HardwareAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, octet) in self.to_bytes().iter().enumerate() {
+            if i > 0 {
+                f.write_str(":")?;
+            }
+            write!(f, "{:02x}", octet)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for HardwareAddr {
+    type Err = ParseMacAddrError;
+    fn from_str(s: &str) -> Result<HardwareAddr, ParseMacAddrError> {
+        crate::parse::hardware_addr(s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for HardwareAddr {
+    /// Serializes the address as a string, or as bytes for binary formats
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_bytes(&self.to_bytes())
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for HardwareAddr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct HardwareAddrVisitor;
+        impl<'de> de::Visitor<'de> for HardwareAddrVisitor {
+            type Value = HardwareAddr;
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<HardwareAddr, E> {
+                value.parse().map_err(|err| E::custom(err))
             }
-            match u8::from_str_radix(split, 16) {
-                Ok(b) if split.len() != 0 => parts[i] = b,
-                _ => return Err(ParseMacAddrError::InvalidComponent),
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<HardwareAddr, E> {
+                Ok(HardwareAddr::from_bytes(v))
+            }
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(
+                    formatter,
+                    "either a string representation of a hardware address or a byte array"
+                )
             }
-            i += 1;
         }
 
-        if i == 6 {
-            Ok(MacAddr(
-                parts[0], parts[1], parts[2], parts[3], parts[4], parts[5],
-            ))
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(HardwareAddrVisitor)
         } else {
-            Err(ParseMacAddrError::TooFewComponents)
+            deserializer.deserialize_bytes(HardwareAddrVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hardware_addr() {
+        let mac = MacAddr::new(0x00, 0x1b, 0x21, 0x0a, 0xbc, 0xde);
+        let hw = HardwareAddr::from_bytes(&mac.octets());
+        assert_eq!(hw, HardwareAddr::from(mac));
+        assert_eq!(hw.to_string(), mac.to_string());
+        assert_eq!(hw.mac_addr(), Some(mac));
+
+        let ib = "80:00:02:08:fe:80:00:00:00:00:00:00:00:02:c9:03:00:0a:bc:de";
+        let hw: HardwareAddr = ib.parse().unwrap();
+        assert!(matches!(hw, HardwareAddr::Infiniband20(_)));
+        assert_eq!(hw.len(), 20);
+        assert_eq!(hw.to_string(), ib);
+        assert_eq!(hw.mac_addr(), None);
+        assert_eq!(
+            "01:02".parse::<HardwareAddr>(),
+            Ok(HardwareAddr::Other(vec![1, 2]))
+        );
+    }
+
+    #[test]
+    fn test_format_into() {
+        let mac = MacAddr::new(0x00, 0x1b, 0x21, 0x0a, 0xbc, 0xde);
+        let mut buf = [0u8; MAC_ADDR_STR_LEN];
+        assert_eq!(mac.format_into(&mut buf), "00:1b:21:0a:bc:de");
+        assert_eq!(mac.to_mac_string().as_str(), mac.to_string());
+        assert_eq!(MacAddr::broadcast().to_mac_string().len(), MAC_ADDR_STR_LEN);
+        let mut out = String::from("src=");
+        mac.write_to(&mut out).unwrap();
+        assert_eq!(out, "src=00:1b:21:0a:bc:de");
+        assert_eq!(
+            format!("{:>19}", mac.to_mac_string()),
+            "  00:1b:21:0a:bc:de"
+        );
+        assert_eq!(
+            format!("{:?}", MacAddr::zero().to_mac_string()),
+            "\"00:00:00:00:00:00\""
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        #[derive(Serialize, Deserialize)]
+        struct Legacy {
+            #[serde(with = "serde_tuple")]
+            mac: MacAddr,
+        }
+
+        let mac = MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff);
+        let json = serde_json::to_string(&mac).unwrap();
+        assert_eq!(json, "\"aa:bb:cc:dd:ee:ff\"");
+        assert_eq!(serde_json::from_str::<MacAddr>(&json).unwrap(), mac);
+        assert_eq!(
+            serde_json::from_str::<MacAddr>("[170,187,204,221,238,255]").unwrap(),
+            mac
+        );
+        assert!(serde_json::from_str::<MacAddr>("[1,2,3,4,5]").is_err());
+        assert!(serde_json::from_str::<MacAddr>("[1,2,3,4,5,6,7]").is_err());
+
+        let legacy = serde_json::to_string(&Legacy { mac }).unwrap();
+        assert_eq!(legacy, r#"{"mac":[170,187,204,221,238,255]}"#);
+        assert_eq!(serde_json::from_str::<Legacy>(&legacy).unwrap().mac, mac);
+    }
+
+    #[test]
+    fn test_parse_modes() {
+        let mac = MacAddr::new(0, 1, 2, 3, 4, 5);
+        assert_eq!("00:01:02:03:04:05".parse::<MacAddr>(), Ok(mac));
+        assert!("0:1:2:3:4:5".parse::<MacAddr>().is_err());
+        assert!(MacAddr::parse_strict("0:1:2:3:4:5").is_err());
+        assert_eq!(MacAddr::parse_lenient("0:1:2:3:4:5"), Ok(mac));
+        assert_eq!(MacAddr::parse_strict("00-01-02-03-04-05"), Ok(mac));
+    }
+
+    #[test]
+    fn test_mac_arithmetic() {
+        let mac = MacAddr::new(0x02, 0x00, 0x00, 0x00, 0x00, 0xff);
+        assert_eq!(mac.next(), Some(MacAddr::new(0x02, 0, 0, 0, 1, 0)));
+        assert_eq!(
+            mac.checked_add(0x0100_0000),
+            Some(MacAddr::new(0x02, 0, 1, 0, 0, 0xff))
+        );
+        assert_eq!(MacAddr::broadcast().next(), None);
+        assert_eq!(MacAddr::zero().checked_add(1 << 48), None);
+        assert_eq!(mac.checked_add(u64::MAX), None);
+        assert_eq!(
+            MacAddr::zero().checked_add((1 << 48) - 1),
+            Some(MacAddr::broadcast())
+        );
+    }
+
+    #[test]
+    fn test_mac_range() {
+        let first = MacAddr::new(0x02, 0, 0, 0, 0, 0xfe);
+        let last = MacAddr::new(0x02, 0, 0, 0, 1, 0x01);
+        let range = MacAddrRange::new(first, last);
+        assert_eq!(range.remaining(), 4);
+        assert_eq!(range.size_hint(), (4, Some(4)));
+        assert!(range.contains(&MacAddr::new(0x02, 0, 0, 0, 1, 0)));
+        assert!(!range.contains(&MacAddr::new(0x02, 0, 0, 0, 1, 2)));
+        let macs: Vec<MacAddr> = range.clone().collect();
+        assert_eq!(macs.len(), 4);
+        assert_eq!(macs[2], MacAddr::new(0x02, 0, 0, 0, 1, 0));
+        assert_eq!(range.clone().next_back(), Some(last));
+
+        assert_eq!(MacAddrRange::new(last, first).next(), None);
+        assert_eq!(MacAddrRange::with_count(first, 0).next(), None);
+        // Stops at the last address instead of wrapping around
+        let range = MacAddrRange::with_count(MacAddr::broadcast(), 10);
+        assert_eq!(range.collect::<Vec<_>>(), vec![MacAddr::broadcast()]);
+        let mut range = MacAddrRange::new(MacAddr::zero(), MacAddr::zero());
+        assert_eq!(range.next_back(), Some(MacAddr::zero()));
+        assert_eq!(range.next(), None);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let octets = [0x00, 0x1b, 0x21, 0x0a, 0xbc, 0xde];
+        let mac = MacAddr::from(octets);
+        assert_eq!(<[u8; 6]>::from(mac), octets);
+        assert_eq!(u64::from(mac), 0x001b_210a_bcde);
+        assert_eq!(MacAddr::try_from(&octets[..]).ok(), Some(mac));
+        assert!(MacAddr::try_from(&octets[..5]).is_err());
+        // Ethernet header: destination, then source
+        let frame = [
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x1b, 0x21, 0x0a, 0xbc, 0xde,
+        ];
+        assert_eq!(MacAddr::try_from(&frame[6..12]).ok(), Some(mac));
+    }
+
+    #[test]
+    fn test_address_bits() {
+        let mac = MacAddr::new(0x02, 0x00, 0x5e, 0x10, 0x00, 0x01);
+        assert!(mac.is_unicast());
+        assert!(mac.is_locally_administered());
+        assert!(!mac.is_universal());
+        assert!(MacAddr::broadcast().is_multicast());
+        assert_eq!(mac.oui(), [0x02, 0x00, 0x5e]);
+    }
+
+    #[test]
+    fn test_format() {
+        let mac = MacAddr::new(0x00, 0x1b, 0x21, 0x0a, 0xbc, 0xde);
+        assert_eq!(mac.to_string(), "00:1b:21:0a:bc:de");
+        assert_eq!(mac.address(), "00:1b:21:0a:bc:de");
+        assert_eq!(format!("{:#}", mac), "00-1B-21-0A-BC-DE");
+        assert_eq!(format!("{:x}", mac), "001b210abcde");
+        assert_eq!(format!("{:X}", mac), "001B210ABCDE");
+        assert_eq!(format!("{:#x}", mac), "0x001b210abcde");
+        assert_eq!(
+            MacAddr::new(1, 2, 3, 4, 5, 6).to_string(),
+            "01:02:03:04:05:06"
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_random() {
+        for _ in 0..64 {
+            assert!(MacAddr::random().is_unicast());
+            let local = MacAddr::random_locally_administered();
+            assert!(local.is_unicast());
+            assert!(local.is_locally_administered());
+            let vendor = MacAddr::with_oui([0x00, 0x1b, 0x21]);
+            assert_eq!(vendor.oui(), [0x00, 0x1b, 0x21]);
         }
     }
 }
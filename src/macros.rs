@@ -0,0 +1,38 @@
+//! Internal logging macros.
+//!
+//! With the `tracing` feature enabled these forward to the `tracing` crate.
+//! Otherwise they expand to nothing, while still type-checking their arguments.
+
+macro_rules! debug {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        let _ = format_args!($($arg)+);
+    }};
+}
+
+macro_rules! trace {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        let _ = format_args!($($arg)+);
+    }};
+}
+
+/// Enter a debug-level span for the rest of the enclosing scope:
+/// `let _span = debug_span!("getifaddrs");`
+macro_rules! debug_span {
+    ($name:literal) => {{
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!($name).entered();
+        #[cfg(not(feature = "tracing"))]
+        let span = $crate::macros::NoSpan;
+        span
+    }};
+}
+
+/// Stand-in for an entered span when tracing is disabled
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
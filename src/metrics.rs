@@ -0,0 +1,230 @@
+//! Prometheus text exposition of interface state and traffic counters.
+
+use crate::interface::Interface;
+use crate::stats::InterfaceStats;
+use std::fmt::Write;
+
+struct Family {
+    name: &'static str,
+    kind: &'static str,
+    help: &'static str,
+}
+
+const INFO: Family = Family {
+    name: "netdev_interface_info",
+    kind: "gauge",
+    help: "Interface metadata, always 1",
+};
+const UP: Family = Family {
+    name: "netdev_interface_up",
+    kind: "gauge",
+    help: "Whether the interface is administratively up",
+};
+const RUNNING: Family = Family {
+    name: "netdev_interface_running",
+    kind: "gauge",
+    help: "Whether the interface is operationally up",
+};
+const TRANSMIT_SPEED: Family = Family {
+    name: "netdev_interface_transmit_speed_bits_per_second",
+    kind: "gauge",
+    help: "Transmit link speed",
+};
+const RECEIVE_SPEED: Family = Family {
+    name: "netdev_interface_receive_speed_bits_per_second",
+    kind: "gauge",
+    help: "Receive link speed",
+};
+
+type Counter = fn(&InterfaceStats) -> u64;
+
+const COUNTERS: [(Family, Counter); 8] = [
+    (
+        Family {
+            name: "netdev_interface_receive_bytes_total",
+            kind: "counter",
+            help: "Bytes received",
+        },
+        |s| s.rx_bytes,
+    ),
+    (
+        Family {
+            name: "netdev_interface_transmit_bytes_total",
+            kind: "counter",
+            help: "Bytes transmitted",
+        },
+        |s| s.tx_bytes,
+    ),
+    (
+        Family {
+            name: "netdev_interface_receive_packets_total",
+            kind: "counter",
+            help: "Packets received",
+        },
+        |s| s.rx_packets,
+    ),
+    (
+        Family {
+            name: "netdev_interface_transmit_packets_total",
+            kind: "counter",
+            help: "Packets transmitted",
+        },
+        |s| s.tx_packets,
+    ),
+    (
+        Family {
+            name: "netdev_interface_receive_errors_total",
+            kind: "counter",
+            help: "Receive errors",
+        },
+        |s| s.rx_errors,
+    ),
+    (
+        Family {
+            name: "netdev_interface_transmit_errors_total",
+            kind: "counter",
+            help: "Transmit errors",
+        },
+        |s| s.tx_errors,
+    ),
+    (
+        Family {
+            name: "netdev_interface_receive_dropped_total",
+            kind: "counter",
+            help: "Received packets dropped",
+        },
+        |s| s.rx_dropped,
+    ),
+    (
+        Family {
+            name: "netdev_interface_transmit_dropped_total",
+            kind: "counter",
+            help: "Transmitted packets dropped",
+        },
+        |s| s.tx_dropped,
+    ),
+];
+
+/// Escape a label value: backslash, double quote and line feed must be escaped.
+fn escape_label_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn write_header(out: &mut String, family: &Family) {
+    let _ = writeln!(out, "# HELP {} {}", family.name, family.help);
+    let _ = writeln!(out, "# TYPE {} {}", family.name, family.kind);
+}
+
+fn write_family<F>(out: &mut String, family: &Family, interfaces: &[Interface], value: F)
+where
+    F: Fn(&Interface) -> Option<u64>,
+{
+    let mut samples = interfaces
+        .iter()
+        .filter_map(|iface| value(iface).map(|v| (iface, v)))
+        .peekable();
+    if samples.peek().is_none() {
+        return;
+    }
+    write_header(out, family);
+    for (iface, v) in samples {
+        let _ = writeln!(
+            out,
+            "{}{{interface=\"{}\"}} {}",
+            family.name,
+            escape_label_value(&iface.name),
+            v
+        );
+    }
+}
+
+/// Render the state and counters of the interfaces in the Prometheus text format.
+///
+/// Each interface is identified by the `interface` label. Counters are only emitted for
+/// interfaces with [`Interface::stats`] available.
+pub fn encode_prometheus(interfaces: &[Interface]) -> String {
+    let mut out = String::new();
+    if !interfaces.is_empty() {
+        write_header(&mut out, &INFO);
+        for iface in interfaces {
+            let _ = writeln!(
+                out,
+                "{}{{interface=\"{}\",index=\"{}\",type=\"{}\",mac=\"{}\"}} 1",
+                INFO.name,
+                escape_label_value(&iface.name),
+                iface.index,
+                escape_label_value(&iface.if_type.name()),
+                iface
+                    .mac_addr
+                    .map(|mac| mac.to_string())
+                    .unwrap_or_default()
+            );
+        }
+    }
+    write_family(
+        &mut out,
+        &UP,
+        interfaces,
+        |iface| Some(iface.is_up() as u64),
+    );
+    write_family(&mut out, &RUNNING, interfaces, |iface| {
+        Some(iface.is_running() as u64)
+    });
+    write_family(&mut out, &TRANSMIT_SPEED, interfaces, |iface| {
+        iface.transmit_speed
+    });
+    write_family(&mut out, &RECEIVE_SPEED, interfaces, |iface| {
+        iface.receive_speed
+    });
+    for (family, counter) in &COUNTERS {
+        write_family(&mut out, family, interfaces, |iface| {
+            iface.stats.as_ref().map(counter)
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_label_value() {
+        assert_eq!(escape_label_value("eth0"), "eth0");
+        assert_eq!(escape_label_value("a\\b\"c\nd"), "a\\\\b\\\"c\\nd");
+    }
+
+    #[test]
+    fn test_encode_prometheus() {
+        let mut iface = Interface::dummy();
+        iface.name = String::from("Ethernet \"2\"");
+        iface.index = 7;
+        iface.stats = Some(InterfaceStats {
+            rx_bytes: 1234,
+            ..Default::default()
+        });
+        let text = encode_prometheus(&[iface]);
+        assert!(text.contains("# TYPE netdev_interface_receive_bytes_total counter\n"));
+        assert!(text.contains(
+            "netdev_interface_receive_bytes_total{interface=\"Ethernet \\\"2\\\"\"} 1234\n"
+        ));
+        assert!(text.contains("index=\"7\""));
+        // No speed is known, so the family is omitted
+        assert!(!text.contains("speed"));
+        assert!(encode_prometheus(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_encode_interfaces() {
+        println!("{}", encode_prometheus(&crate::get_interfaces()));
+    }
+}
@@ -0,0 +1,277 @@
+//! Fake backend for unit-testing code that uses netdev.
+//!
+//! Install an [`InterfaceProvider`] with [`set_provider`] and the enumeration
+//! functions ([`get_interfaces`](crate::get_interfaces),
+//! [`get_default_interface`](crate::get_default_interface),
+//! [`get_default_gateway`](crate::get_default_gateway),
+//! [`get_routes`](crate::route::get_routes), ...) return its data instead of
+//! querying the OS. The provider is installed for the calling thread only, so
+//! tests running in parallel do not see each other's fixtures.
+//!
+//! ```
+//! use netdev::mock::{self, InterfaceBuilder, MockProvider};
+//! use std::net::Ipv4Addr;
+//!
+//! let eth0 = InterfaceBuilder::new(2, "eth0")
+//!     .up()
+//!     .ipv4(Ipv4Addr::new(192, 168, 1, 10), 24)
+//!     .default_route(true)
+//!     .build();
+//! let _guard = mock::set_provider(MockProvider::new().interface(eth0));
+//! assert_eq!(netdev::get_default_interface().unwrap().name, "eth0");
+//! ```
+
+use crate::interface::{EnumOptions, Interface};
+use crate::ip::{IpNet, Ipv4Net, Ipv6Net};
+use crate::route::{NextHop, RouteEntry};
+use std::cell::RefCell;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+
+/// Source of interface and routing data for the enumeration functions
+pub trait InterfaceProvider: Send + Sync {
+    /// List the interfaces matching `options`
+    fn interfaces(&self, options: &EnumOptions) -> Vec<Interface>;
+    /// List the entries of the main routing table
+    fn routes(&self) -> io::Result<Vec<RouteEntry>> {
+        Ok(Vec::new())
+    }
+    /// Local IP address used for outgoing connections. Defaults to the first
+    /// address of the interface marked as default.
+    fn local_ipaddr(&self) -> Option<IpAddr> {
+        let interfaces = self.interfaces(&EnumOptions::default());
+        let iface = interfaces.iter().find(|iface| iface.default)?;
+        iface
+            .ipv4
+            .first()
+            .map(|net| IpAddr::V4(net.addr))
+            .or_else(|| iface.ipv6.first().map(|net| IpAddr::V6(net.addr)))
+    }
+}
+
+thread_local! {
+    static PROVIDER: RefCell<Option<Arc<dyn InterfaceProvider>>> = const { RefCell::new(None) };
+}
+
+/// Restores the previously installed provider when dropped
+#[must_use = "the provider is uninstalled when the guard is dropped"]
+pub struct ProviderGuard {
+    previous: Option<Arc<dyn InterfaceProvider>>,
+}
+
+impl Drop for ProviderGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        PROVIDER.with(|provider| *provider.borrow_mut() = previous);
+    }
+}
+
+/// Route the enumeration functions on this thread through `provider` until the
+/// returned guard is dropped
+pub fn set_provider<P: InterfaceProvider + 'static>(provider: P) -> ProviderGuard {
+    install(Some(Arc::new(provider)))
+}
+
+pub(crate) fn install(provider: Option<Arc<dyn InterfaceProvider>>) -> ProviderGuard {
+    let previous = PROVIDER.with(|current| current.replace(provider));
+    ProviderGuard { previous }
+}
+
+/// The provider installed on this thread, if any
+pub(crate) fn current() -> Option<Arc<dyn InterfaceProvider>> {
+    PROVIDER.with(|provider| provider.borrow().clone())
+}
+
+/// [`InterfaceProvider`] returning fixed interfaces and routes
+#[derive(Clone, Debug, Default)]
+pub struct MockProvider {
+    pub interfaces: Vec<Interface>,
+    pub routes: Vec<RouteEntry>,
+}
+
+impl MockProvider {
+    /// Construct a provider without interfaces or routes
+    pub fn new() -> MockProvider {
+        MockProvider::default()
+    }
+    /// Add an interface
+    pub fn interface(mut self, interface: Interface) -> MockProvider {
+        self.interfaces.push(interface);
+        self
+    }
+    /// Add a routing table entry
+    pub fn route(mut self, route: RouteEntry) -> MockProvider {
+        self.routes.push(route);
+        self
+    }
+}
+
+impl InterfaceProvider for MockProvider {
+    /// Applies the flag and address family filters of `options` like the OS backends
+    fn interfaces(&self, options: &EnumOptions) -> Vec<Interface> {
+        self.interfaces
+            .iter()
+            .filter(|iface| options.accepts_flags(iface.flags) && options.accepts_name(&iface.name))
+            .cloned()
+            .map(|mut iface| {
+                if !options.address_families.includes_v4() {
+                    iface.ipv4.clear();
+                    iface.gateways.iter_mut().for_each(|gw| gw.ipv4.clear());
+                    iface.dns_servers.retain(|ip| !ip.is_ipv4());
+                }
+                if !options.address_families.includes_v6() {
+                    iface.ipv6.clear();
+                    iface.gateways.iter_mut().for_each(|gw| gw.ipv6.clear());
+                    iface.dns_servers.retain(|ip| !ip.is_ipv6());
+                }
+                iface
+                    .gateways
+                    .retain(|gw| !gw.ipv4.is_empty() || !gw.ipv6.is_empty());
+                iface
+            })
+            .collect()
+    }
+    fn routes(&self) -> io::Result<Vec<RouteEntry>> {
+        Ok(self.routes.clone())
+    }
+}
+
+pub use crate::interface::InterfaceBuilder;
+
+/// Builder for fake [`RouteEntry`] fixtures
+#[derive(Clone, Debug)]
+pub struct RouteBuilder {
+    route: RouteEntry,
+}
+
+impl RouteBuilder {
+    /// Start a route to `destination`
+    pub fn new(destination: IpNet) -> RouteBuilder {
+        RouteBuilder {
+            route: RouteEntry::new(destination),
+        }
+    }
+    /// Start a default route via `gateway`
+    pub fn default_via(gateway: IpAddr) -> RouteBuilder {
+        let destination = match gateway {
+            IpAddr::V4(_) => IpNet::V4(Ipv4Net::new(Ipv4Addr::UNSPECIFIED, 0)),
+            IpAddr::V6(_) => IpNet::V6(Ipv6Net::new(Ipv6Addr::UNSPECIFIED, 0)),
+        };
+        RouteBuilder::new(destination).gateway(gateway)
+    }
+    /// Set the next hop
+    pub fn gateway(mut self, gateway: IpAddr) -> RouteBuilder {
+        self.route.gateway = Some(gateway);
+        self
+    }
+    /// Set the outgoing interface
+    pub fn if_index(mut self, if_index: u32) -> RouteBuilder {
+        self.route.if_index = Some(if_index);
+        self
+    }
+    /// Set the metric
+    pub fn metric(mut self, metric: u32) -> RouteBuilder {
+        self.route.metric = Some(metric);
+        self
+    }
+    /// Add a next hop, making the route a multipath one once there are two.
+    /// The first next hop also sets the gateway and the outgoing interface.
+    pub fn next_hop(mut self, hop: NextHop) -> RouteBuilder {
+        if self.route.next_hops.is_empty() {
+            self.route.gateway = hop.gateway;
+            self.route.if_index = hop.if_index;
+        }
+        self.route.next_hops.push(hop);
+        self
+    }
+    /// Finish the route
+    pub fn build(self) -> RouteEntry {
+        self.route
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::NetworkDevice;
+    use crate::interface::{AddressFamily, InterfaceType};
+    use crate::mac::MacAddr;
+    use std::time::Duration;
+
+    fn fixture() -> MockProvider {
+        let router = NetworkDevice {
+            mac_addr: MacAddr::new(0x02, 0, 0, 0, 0, 0x01),
+            ipv4: vec![Ipv4Addr::new(192, 168, 1, 1)],
+            ipv6: vec![Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)],
+        };
+        MockProvider::new()
+            .interface(InterfaceBuilder::new(1, "lo").loopback().up().build())
+            .interface(
+                InterfaceBuilder::new(2, "eth0")
+                    .if_type(InterfaceType::Ethernet)
+                    .mac_addr(MacAddr::new(0x02, 0, 0, 0, 0, 0x10))
+                    .up()
+                    .ipv4(Ipv4Addr::new(192, 168, 1, 10), 24)
+                    .ipv6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 0x10), 64)
+                    .gateway(router)
+                    .default_route(true)
+                    .build(),
+            )
+            .interface(InterfaceBuilder::new(3, "wlan0").build())
+            .route(
+                RouteBuilder::default_via(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)))
+                    .if_index(2)
+                    .metric(100)
+                    .build(),
+            )
+    }
+
+    #[test]
+    fn test_mock_provider() {
+        let _guard = set_provider(fixture());
+        assert_eq!(crate::get_interfaces().len(), 3);
+        assert_eq!(crate::get_default_interface().unwrap().index, 2);
+        assert_eq!(
+            crate::get_default_gateway().unwrap().ipv4,
+            vec![Ipv4Addr::new(192, 168, 1, 1)]
+        );
+        assert_eq!(crate::rank_interfaces()[0].name, "eth0");
+        let routes = crate::route::get_routes().unwrap();
+        assert_eq!(routes.len(), 1);
+        assert!(routes[0].is_default());
+
+        let v6 = crate::interfaces_with(AddressFamily::V6);
+        assert!(v6.iter().all(|iface| iface.ipv4.is_empty()));
+        assert_eq!(v6[1].gateways[0].ipv4, Vec::<Ipv4Addr>::new());
+
+        let options = EnumOptions {
+            include_down: false,
+            include_loopback: false,
+            ..EnumOptions::default()
+        };
+        let names: Vec<String> = crate::interface::get_interfaces_with_options(&options)
+            .into_iter()
+            .map(|iface| iface.name)
+            .collect();
+        assert_eq!(names, vec!["eth0"]);
+
+        // The provider is carried over to the enumeration thread.
+        let interfaces = crate::interface::interfaces_with_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(interfaces.len(), 3);
+    }
+
+    #[test]
+    fn test_provider_guard() {
+        {
+            let _guard = set_provider(MockProvider::new());
+            assert!(crate::get_interfaces().is_empty());
+            {
+                let _inner = set_provider(fixture());
+                assert_eq!(crate::get_interfaces().len(), 3);
+            }
+            assert!(crate::get_interfaces().is_empty());
+        }
+        assert!(current().is_none());
+    }
+}
@@ -0,0 +1,155 @@
+use super::{Change, ChangeKind};
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Change recorded in a [`History`]
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Event {
+    /// When the change was recorded
+    pub time: SystemTime,
+    /// The change
+    pub change: Change,
+}
+
+/// Check if `event` is about interface `if_index`, or any interface if `None`
+fn is_of(event: &Event, if_index: Option<u32>) -> bool {
+    if_index.is_none() || if_index == Some(event.change.if_index)
+}
+
+/// Ring buffer of the most recent interface changes.
+///
+/// Once `capacity` events are stored, recording a new one drops the oldest.
+/// Events are expected to be recorded in chronological order.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct History {
+    capacity: usize,
+    events: VecDeque<Event>,
+}
+
+impl History {
+    /// Create an empty history holding up to `capacity` events
+    pub fn new(capacity: usize) -> History {
+        History {
+            capacity,
+            events: VecDeque::with_capacity(capacity.min(4096)),
+        }
+    }
+    /// Returns the maximum number of events kept
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    /// Returns the number of events stored
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+    /// Check if no events are stored
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+    /// Remove all events
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+    /// Record changes as happening now
+    pub fn record(&mut self, changes: impl IntoIterator<Item = Change>) {
+        let now = SystemTime::now();
+        for change in changes {
+            self.record_at(change, now);
+        }
+    }
+    /// Record a change that happened at `time`
+    pub fn record_at(&mut self, change: Change, time: SystemTime) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(Event { time, change });
+    }
+    /// Iterate over the stored events, oldest first
+    pub fn events(&self) -> impl DoubleEndedIterator<Item = &Event> {
+        self.events.iter()
+    }
+    /// Iterate over the events recorded within `window` of now, oldest first
+    pub fn recent(&self, window: Duration) -> impl DoubleEndedIterator<Item = &Event> {
+        let since = SystemTime::now()
+            .checked_sub(window)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        self.events.iter().filter(move |event| event.time >= since)
+    }
+    /// Returns the most recent event of interface `if_index`, or of any interface if `None`,
+    /// that matches `f`
+    pub fn last_matching(
+        &self,
+        if_index: Option<u32>,
+        f: impl Fn(&ChangeKind) -> bool,
+    ) -> Option<&Event> {
+        self.events
+            .iter()
+            .rev()
+            .find(|event| is_of(event, if_index) && f(&event.change.kind))
+    }
+    /// Returns when the default gateway last changed
+    pub fn last_default_gateway_change(&self) -> Option<SystemTime> {
+        self.last_matching(None, |kind| *kind == ChangeKind::DefaultGatewayChanged)
+            .map(|event| event.time)
+    }
+    /// Count how often the link of interface `if_index`, or of any interface if `None`,
+    /// went down within `window` of now
+    pub fn link_flaps(&self, if_index: Option<u32>, window: Duration) -> usize {
+        self.recent(window)
+            .filter(|event| event.change.kind == ChangeKind::LinkDown && is_of(event, if_index))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(if_index: u32, kind: ChangeKind) -> Change {
+        Change {
+            if_index,
+            if_name: format!("eth{}", if_index),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_history() {
+        let now = SystemTime::now();
+        let hours_ago = |hours: u64| now - Duration::from_secs(hours * 3600);
+        let mut history = History::new(4);
+        history.record_at(change(1, ChangeKind::DefaultGatewayChanged), hours_ago(3));
+        history.record_at(change(1, ChangeKind::LinkDown), hours_ago(2));
+        history.record_at(change(2, ChangeKind::DefaultGatewayChanged), hours_ago(2));
+        history.record_at(change(1, ChangeKind::LinkUp), hours_ago(2));
+        history.record(vec![
+            change(1, ChangeKind::LinkDown),
+            change(2, ChangeKind::LinkDown),
+        ]);
+        // The two oldest events were dropped
+        assert_eq!(history.len(), 4);
+        assert_eq!(
+            history.events().next().unwrap().change.kind,
+            ChangeKind::DefaultGatewayChanged
+        );
+        assert_eq!(history.last_default_gateway_change(), Some(hours_ago(2)));
+        let hour = Duration::from_secs(3600);
+        assert_eq!(history.link_flaps(None, hour), 2);
+        assert_eq!(history.link_flaps(Some(1), hour), 1);
+        assert_eq!(history.link_flaps(Some(1), hour * 3), 1);
+        let last = history.last_matching(Some(1), |kind| *kind == ChangeKind::LinkUp);
+        assert_eq!(last.map(|event| event.time), Some(hours_ago(2)));
+
+        let mut empty = History::new(0);
+        empty.record(vec![change(1, ChangeKind::Added)]);
+        assert!(empty.is_empty());
+    }
+}
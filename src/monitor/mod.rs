@@ -0,0 +1,297 @@
+//! Detection of interface changes.
+//!
+//! [`Monitor`] re-enumerates the interfaces each time it is polled and reports
+//! what changed since the previous poll. [`History`] keeps the most recent
+//! changes with their timestamps for later questions such as how often a link
+//! went down in the last hour. With the `stream` feature, [`addresses`] turns
+//! address assignments into a `futures_core::Stream` for async applications.
+//!
+//! ```no_run
+//! use netdev::monitor::{History, Monitor};
+//! use std::time::Duration;
+//!
+//! let mut monitor = Monitor::new();
+//! let mut history = History::new(1024);
+//! loop {
+//!     std::thread::sleep(Duration::from_secs(1));
+//!     let changes = monitor.poll();
+//!     for change in &changes {
+//!         println!("{}: {:?}", change.if_name, change.kind);
+//!     }
+//!     history.record(changes);
+//!     println!("flaps in the last hour: {}", history.link_flaps(None, Duration::from_secs(3600)));
+//! }
+//! ```
+
+mod history;
+pub use self::history::*;
+
+#[cfg(feature = "stream")]
+mod stream;
+#[cfg(feature = "stream")]
+pub use self::stream::*;
+
+use crate::interface::{EnumOptions, Enumerator, Interface, Snapshot};
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::time::Instant;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// What changed about an interface
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ChangeKind {
+    /// The interface appeared
+    Added,
+    /// The interface disappeared
+    Removed,
+    /// The interface became up and running
+    LinkUp,
+    /// The interface stopped being up and running
+    LinkDown,
+    /// An address was assigned to the interface
+    AddressAdded(IpAddr),
+    /// An address was removed from the interface
+    AddressRemoved(IpAddr),
+    /// The set of gateways of the interface changed
+    GatewaysChanged,
+    /// The default gateway changed, either on the default interface or because
+    /// another interface became the default one. Reported for the new default
+    /// interface, or for the previous one if there is no default interface anymore.
+    DefaultGatewayChanged,
+}
+
+/// Change of one interface
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Change {
+    /// Index of the interface
+    pub if_index: u32,
+    /// Name of the interface
+    pub if_name: String,
+    /// What changed
+    pub kind: ChangeKind,
+}
+
+fn is_link_up(interface: &Interface) -> bool {
+    interface.is_up() && interface.is_running()
+}
+
+fn addrs_of(interface: &Interface) -> Vec<IpAddr> {
+    let v4 = interface.ipv4.iter().map(|net| IpAddr::V4(net.addr));
+    let v6 = interface.ipv6.iter().map(|net| IpAddr::V6(net.addr));
+    v4.chain(v6).collect()
+}
+
+fn same_interface(a: &Interface, b: &Interface) -> bool {
+    a.netns == b.netns && a.index == b.index
+}
+
+fn gateway_addrs(interface: &Interface) -> HashSet<IpAddr> {
+    interface
+        .gateways
+        .iter()
+        .flat_map(|gateway| {
+            let v4 = gateway.ipv4.iter().map(|ip| IpAddr::V4(*ip));
+            let v6 = gateway.ipv6.iter().map(|ip| IpAddr::V6(*ip));
+            v4.chain(v6)
+        })
+        .collect()
+}
+
+/// Compare two enumerations of the interfaces and list the changes between them.
+///
+/// Interfaces are matched by namespace and index, so same-named interfaces of
+/// different network namespaces are never confused.
+pub fn diff(old: &[Interface], new: &[Interface]) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let mut push = |interface: &Interface, kind: ChangeKind| {
+        changes.push(Change {
+            if_index: interface.index,
+            if_name: interface.name.clone(),
+            kind,
+        })
+    };
+    for interface in old {
+        if !new.iter().any(|other| same_interface(other, interface)) {
+            push(interface, ChangeKind::Removed);
+        }
+    }
+    for interface in new {
+        let Some(previous) = old.iter().find(|other| same_interface(other, interface)) else {
+            push(interface, ChangeKind::Added);
+            continue;
+        };
+        if previous.content_eq(interface) {
+            continue;
+        }
+        match (is_link_up(previous), is_link_up(interface)) {
+            (false, true) => push(interface, ChangeKind::LinkUp),
+            (true, false) => push(interface, ChangeKind::LinkDown),
+            _ => {}
+        }
+        let (before, after) = (addrs_of(previous), addrs_of(interface));
+        for addr in before.iter().filter(|addr| !after.contains(addr)) {
+            push(interface, ChangeKind::AddressRemoved(*addr));
+        }
+        for addr in after.iter().filter(|addr| !before.contains(addr)) {
+            push(interface, ChangeKind::AddressAdded(*addr));
+        }
+        if gateway_addrs(previous) != gateway_addrs(interface) {
+            push(interface, ChangeKind::GatewaysChanged);
+        }
+    }
+    let old_default = old.iter().find(|interface| interface.default);
+    let new_default = new.iter().find(|interface| interface.default);
+    let default_gateways =
+        |interface: Option<&Interface>| interface.map(gateway_addrs).unwrap_or_default();
+    if default_gateways(old_default) != default_gateways(new_default) {
+        if let Some(interface) = new_default.or(old_default) {
+            push(interface, ChangeKind::DefaultGatewayChanged);
+        }
+    }
+    changes
+}
+
+/// Poll-based interface change detector
+///
+/// The monitor counts the polls that found changes, so callers can check
+/// whether anything changed since they last looked by keeping the
+/// [`generation`](Monitor::generation) number instead of a copy of the interfaces.
+#[derive(Clone, Debug, Default)]
+pub struct Monitor {
+    enumerator: Enumerator,
+    generation: u64,
+    polled_at: Option<Instant>,
+}
+
+impl Monitor {
+    /// Create a monitor with the default enumeration options and take the first snapshot
+    pub fn new() -> Monitor {
+        Monitor::with_options(EnumOptions::default())
+    }
+    /// Create a monitor with the given enumeration options and take the first snapshot
+    pub fn with_options(options: EnumOptions) -> Monitor {
+        let mut enumerator = Enumerator::with_options(options);
+        let polled_at = Instant::now();
+        enumerator.refresh();
+        Monitor {
+            enumerator,
+            generation: 0,
+            polled_at: Some(polled_at),
+        }
+    }
+    /// Enumerate the interfaces again and return what changed since the previous snapshot
+    pub fn poll(&mut self) -> Vec<Change> {
+        let previous = self.enumerator.interfaces().to_vec();
+        self.polled_at = Some(Instant::now());
+        let changes = diff(&previous, self.enumerator.refresh());
+        if !changes.is_empty() {
+            self.generation += 1;
+        }
+        changes
+    }
+    /// Returns the interfaces as of the last poll
+    pub fn interfaces(&self) -> &[Interface] {
+        self.enumerator.interfaces()
+    }
+    /// Returns the number of polls that found changes so far
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+    /// Check if a poll found changes after generation `generation`
+    pub fn changed_since(&self, generation: u64) -> bool {
+        self.generation > generation
+    }
+    /// Returns the interfaces as of the last poll, with the time of that poll
+    /// and the current generation
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            taken_at: self.polled_at.unwrap_or_else(Instant::now),
+            interfaces: self.enumerator.interfaces().to_vec(),
+            generation: self.generation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::NetworkDevice;
+    use crate::ip::Ipv4Net;
+    use std::net::Ipv4Addr;
+
+    fn interface(index: u32, name: &str) -> Interface {
+        let mut interface = Interface::dummy();
+        interface.index = index;
+        interface.name = name.to_string();
+        interface
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut eth0 = interface(2, "eth0");
+        eth0.ipv4
+            .push(Ipv4Net::new(Ipv4Addr::new(192, 168, 1, 20), 24));
+        let wlan0 = interface(3, "wlan0");
+        let old = vec![eth0.clone(), wlan0];
+
+        eth0.flags = Interface::builder().up().build().flags;
+        eth0.ipv4[0] = Ipv4Net::new(Ipv4Addr::new(192, 168, 1, 21), 24);
+        let mut gateway = NetworkDevice::new();
+        gateway.ipv4.push(Ipv4Addr::new(192, 168, 1, 1));
+        eth0.gateways.push(gateway);
+        eth0.default = true;
+        let new = vec![interface(4, "wg0"), eth0];
+
+        let kinds: Vec<(u32, ChangeKind)> = diff(&old, &new)
+            .into_iter()
+            .map(|change| (change.if_index, change.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (3, ChangeKind::Removed),
+                (4, ChangeKind::Added),
+                (2, ChangeKind::LinkUp),
+                (
+                    2,
+                    ChangeKind::AddressRemoved(IpAddr::from([192, 168, 1, 20]))
+                ),
+                (2, ChangeKind::AddressAdded(IpAddr::from([192, 168, 1, 21]))),
+                (2, ChangeKind::GatewaysChanged),
+                (2, ChangeKind::DefaultGatewayChanged),
+            ]
+        );
+        assert!(diff(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_diff_namespaces() {
+        let mut host = interface(2, "eth0");
+        host.netns = Some(4026531840);
+        let mut container = host.clone();
+        container.netns = Some(4026532288);
+        let kinds: Vec<ChangeKind> = diff(&[host], &[container])
+            .into_iter()
+            .map(|change| change.kind)
+            .collect();
+        assert_eq!(kinds, vec![ChangeKind::Removed, ChangeKind::Added]);
+    }
+
+    #[test]
+    fn test_monitor_poll() {
+        let mut monitor = Monitor::new();
+        let before = monitor.snapshot();
+        let changes = monitor.poll();
+        println!("{:?}", changes);
+        assert!(!monitor.interfaces().is_empty() || crate::get_interfaces().is_empty());
+        assert_eq!(
+            monitor.changed_since(before.generation),
+            !changes.is_empty()
+        );
+        assert!(monitor.snapshot().taken_at >= before.taken_at);
+    }
+}
@@ -0,0 +1,237 @@
+use super::{Change, ChangeKind, Monitor};
+use crate::zone;
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Interval between polls of the stream returned by [`addresses`]
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Address assigned to or removed from an interface
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AddressEvent {
+    /// Index of the interface
+    pub if_index: u32,
+    /// Name of the interface
+    pub if_name: String,
+    /// The address
+    pub addr: IpAddr,
+    /// `true` if the address was assigned, `false` if it was removed
+    pub added: bool,
+}
+
+impl AddressEvent {
+    /// Check if the address is neither unspecified, loopback nor link-local
+    pub fn is_global(&self) -> bool {
+        match self.addr {
+            IpAddr::V4(addr) => {
+                !addr.is_unspecified() && !addr.is_loopback() && !addr.is_link_local()
+            }
+            IpAddr::V6(addr) => {
+                !addr.is_unspecified() && !addr.is_loopback() && !zone::is_link_local(&addr)
+            }
+        }
+    }
+}
+
+/// Convert `change` into an address event if it is one about interface `if_index`,
+/// or any interface if `None`
+fn address_event(change: Change, if_index: Option<u32>) -> Option<AddressEvent> {
+    if if_index.is_some_and(|if_index| if_index != change.if_index) {
+        return None;
+    }
+    let (addr, added) = match change.kind {
+        ChangeKind::AddressAdded(addr) => (addr, true),
+        ChangeKind::AddressRemoved(addr) => (addr, false),
+        _ => return None,
+    };
+    Some(AddressEvent {
+        if_index: change.if_index,
+        if_name: change.if_name,
+        addr,
+        added,
+    })
+}
+
+#[derive(Default)]
+struct State {
+    events: VecDeque<AddressEvent>,
+    waker: Option<Waker>,
+}
+
+/// Stream of [`AddressEvent`]s, returned by [`addresses`].
+///
+/// A background thread polls a [`Monitor`] and stops once the stream is dropped.
+/// It uses the [`mock`](crate::mock) provider of the thread that created the
+/// stream, if any. The stream does not depend on any particular async runtime.
+pub struct AddressStream {
+    state: Arc<Mutex<State>>,
+}
+
+impl Stream for AddressStream {
+    type Item = AddressEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<AddressEvent>> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match state.events.pop_front() {
+            Some(event) => Poll::Ready(Some(event)),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+fn run(mut monitor: Monitor, if_index: Option<u32>, interval: Duration, state: Weak<Mutex<State>>) {
+    loop {
+        std::thread::sleep(interval);
+        let Some(state) = state.upgrade() else {
+            return;
+        };
+        let events: Vec<AddressEvent> = monitor
+            .poll()
+            .into_iter()
+            .filter_map(|change| address_event(change, if_index))
+            .collect();
+        if events.is_empty() {
+            continue;
+        }
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        state.events.extend(events);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Watch the addresses of interface `if_index`, or of all interfaces if `None`.
+///
+/// The interfaces are enumerated once before returning, so only changes after
+/// this call are reported. They are then polled every [`DEFAULT_POLL_INTERVAL`].
+///
+/// ```no_run
+/// use futures_lite::StreamExt;
+///
+/// # async fn wait(if_index: u32) {
+/// let mut addresses = netdev::monitor::addresses(Some(if_index));
+/// while let Some(event) = addresses.next().await {
+///     if event.added && event.addr.is_ipv6() && event.is_global() {
+///         println!("rebinding to {}", event.addr);
+///     }
+/// }
+/// # }
+/// ```
+pub fn addresses(if_index: Option<u32>) -> AddressStream {
+    addresses_with(Monitor::new(), if_index, DEFAULT_POLL_INTERVAL)
+}
+
+/// Watch the addresses seen by `monitor`, polling it every `interval`.
+///
+/// Use this to restrict the enumeration with [`EnumOptions`](crate::interface::EnumOptions)
+/// through [`Monitor::with_options`].
+pub fn addresses_with(
+    monitor: Monitor,
+    if_index: Option<u32>,
+    interval: Duration,
+) -> AddressStream {
+    let state = Arc::new(Mutex::new(State::default()));
+    let weak = Arc::downgrade(&state);
+    #[cfg(feature = "mock")]
+    let provider = crate::mock::current();
+    std::thread::spawn(move || {
+        #[cfg(feature = "mock")]
+        let _guard = crate::mock::install(provider);
+        run(monitor, if_index, interval, weak)
+    });
+    AddressStream { state }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(if_index: u32, kind: ChangeKind) -> Change {
+        Change {
+            if_index,
+            if_name: format!("eth{}", if_index),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_address_event() {
+        let addr: IpAddr = "2001:db8::1".parse().unwrap();
+        let event = address_event(change(2, ChangeKind::AddressAdded(addr)), None).unwrap();
+        assert_eq!(event.addr, addr);
+        assert!(event.added);
+        assert!(event.is_global());
+        let event = address_event(change(2, ChangeKind::AddressRemoved(addr)), Some(2)).unwrap();
+        assert!(!event.added);
+        assert!(address_event(change(2, ChangeKind::AddressAdded(addr)), Some(3)).is_none());
+        assert!(address_event(change(2, ChangeKind::LinkUp), None).is_none());
+        let link_local = "fe80::1".parse().unwrap();
+        let event = address_event(change(2, ChangeKind::AddressAdded(link_local)), None).unwrap();
+        assert!(!event.is_global());
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn test_addresses() {
+        use crate::interface::{EnumOptions, Interface, InterfaceBuilder};
+        use crate::mock::{self, InterfaceProvider};
+        use std::net::{Ipv4Addr, Ipv6Addr};
+        use std::time::Instant;
+
+        // Returns whatever the test last stored
+        struct Fixture(Arc<Mutex<Vec<Interface>>>);
+        impl InterfaceProvider for Fixture {
+            fn interfaces(&self, _options: &EnumOptions) -> Vec<Interface> {
+                self.0.lock().unwrap().clone()
+            }
+        }
+        let v4 = Ipv4Addr::new(192, 168, 1, 10);
+        let v6: Ipv6Addr = "2001:db8::10".parse().unwrap();
+        let lo = InterfaceBuilder::new(1, "lo").loopback().up();
+        let eth0 = InterfaceBuilder::new(2, "eth0").up();
+        let interfaces = Arc::new(Mutex::new(vec![
+            lo.clone().build(),
+            eth0.clone().ipv4(v4, 24).build(),
+        ]));
+        let _guard = mock::set_provider(Fixture(interfaces.clone()));
+        let mut stream = addresses_with(Monitor::new(), Some(2), Duration::from_millis(10));
+
+        // eth0 swaps its IPv4 address for an IPv6 one; lo is filtered out
+        *interfaces.lock().unwrap() = vec![
+            lo.ipv6(Ipv6Addr::LOCALHOST, 128).build(),
+            eth0.ipv6(v6, 64).build(),
+        ];
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut events = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while events.len() < 2 && Instant::now() < deadline {
+            match Pin::new(&mut stream).poll_next(&mut cx) {
+                Poll::Ready(Some(event)) => events.push(event),
+                _ => std::thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        events.sort_by_key(|event| event.added);
+        let event = |addr: IpAddr, added| AddressEvent {
+            if_index: 2,
+            if_name: "eth0".to_string(),
+            addr,
+            added,
+        };
+        assert_eq!(events, [event(v4.into(), false), event(v6.into(), true)]);
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+    }
+}
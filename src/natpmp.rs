@@ -0,0 +1,247 @@
+//! NAT Port Mapping Protocol client (RFC 6886).
+//!
+//! Asks the gateway found by [`get_default_gateway`](crate::get_default_gateway)
+//! for its external IPv4 address and for port mappings, so peers outside the
+//! NAT can reach a local port.
+//!
+//! ```no_run
+//! use netdev::natpmp::Client;
+//! use netdev::sockets::Protocol;
+//! use std::time::Duration;
+//!
+//! let client = Client::from_default_gateway()?;
+//! println!("external address: {}", client.external_address()?);
+//! let mapping = client.add_mapping(Protocol::Udp, 4000, 4000, Duration::from_secs(3600))?;
+//! println!("mapped to port {}", mapping.external_port);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::sockets::Protocol;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddrV4, UdpSocket};
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// UDP port the gateway listens on
+pub const NATPMP_PORT: u16 = 5351;
+
+const VERSION: u8 = 0;
+const OP_EXTERNAL_ADDRESS: u8 = 0;
+const OP_MAP_UDP: u8 = 1;
+const OP_MAP_TCP: u8 = 2;
+/// Responses carry the request opcode plus 128
+const OP_RESPONSE: u8 = 128;
+/// The request is repeated this many times, waiting twice as long each time
+const MAX_ATTEMPTS: u32 = 4;
+const INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Port mapping granted by the gateway
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PortMapping {
+    /// Transport protocol
+    pub protocol: Protocol,
+    /// Local port traffic is forwarded to
+    pub internal_port: u16,
+    /// Port on the gateway's external address. May differ from the requested one.
+    pub external_port: u16,
+    /// Time until the mapping expires unless it is renewed
+    pub lifetime: Duration,
+}
+
+/// NAT-PMP client for one gateway
+#[derive(Clone, Debug)]
+pub struct Client {
+    gateway: Ipv4Addr,
+}
+
+impl Client {
+    /// Create a client for the gateway at `gateway`
+    pub fn new(gateway: Ipv4Addr) -> Client {
+        Client { gateway }
+    }
+    /// Create a client for the IPv4 default gateway
+    pub fn from_default_gateway() -> io::Result<Client> {
+        let gateway = crate::gateway::get_default_gateway()
+            .map_err(|err| io::Error::new(io::ErrorKind::NotFound, err))?;
+        let addr = gateway.ipv4.first().copied().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "the default gateway has no IPv4 address",
+            )
+        })?;
+        Ok(Client::new(addr))
+    }
+    /// Returns the address of the gateway
+    pub fn gateway(&self) -> Ipv4Addr {
+        self.gateway
+    }
+    /// Get the gateway's external IPv4 address
+    pub fn external_address(&self) -> io::Result<Ipv4Addr> {
+        let resp = self.request(&[VERSION, OP_EXTERNAL_ADDRESS], 12)?;
+        Ok(Ipv4Addr::new(resp[8], resp[9], resp[10], resp[11]))
+    }
+    /// Map `external_port` on the gateway to `internal_port` on this host for `lifetime`.
+    ///
+    /// The gateway may assign a different external port, and a shorter lifetime;
+    /// check the returned mapping. An `external_port` of 0 lets the gateway choose.
+    /// Mappings should be renewed before half their lifetime has passed.
+    pub fn add_mapping(
+        &self,
+        protocol: Protocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: Duration,
+    ) -> io::Result<PortMapping> {
+        if internal_port == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "internal port must not be 0",
+            ));
+        }
+        let lifetime = u32::try_from(lifetime.as_secs()).unwrap_or(u32::MAX);
+        if lifetime == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "use delete_mapping to remove a mapping",
+            ));
+        }
+        self.map(protocol, internal_port, external_port, lifetime)
+    }
+    /// Delete the mapping of `internal_port` created by this host
+    pub fn delete_mapping(&self, protocol: Protocol, internal_port: u16) -> io::Result<()> {
+        self.map(protocol, internal_port, 0, 0).map(|_| ())
+    }
+    fn map(
+        &self,
+        protocol: Protocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: u32,
+    ) -> io::Result<PortMapping> {
+        let req = build_mapping_request(protocol, internal_port, external_port, lifetime);
+        let resp = self.request(&req, 16)?;
+        Ok(parse_mapping_response(&resp, protocol))
+    }
+    /// Send a request, retrying with exponential backoff, and return a successful
+    /// response of at least `min_len` bytes
+    fn request(&self, req: &[u8], min_len: usize) -> io::Result<Vec<u8>> {
+        let socket = UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0))?;
+        // Only accept datagrams from the gateway
+        socket.connect(SocketAddrV4::new(self.gateway, NATPMP_PORT))?;
+        let mut timeout = INITIAL_TIMEOUT;
+        let mut buf = [0u8; 16];
+        for _ in 0..MAX_ATTEMPTS {
+            socket.send(req)?;
+            socket.set_read_timeout(Some(timeout))?;
+            match socket.recv(&mut buf) {
+                Ok(len) => {
+                    if let Some(resp) = check_response(&buf[..len], req[1], min_len)? {
+                        return Ok(resp.to_vec());
+                    }
+                }
+                Err(err)
+                    if err.kind() == io::ErrorKind::WouldBlock
+                        || err.kind() == io::ErrorKind::TimedOut => {}
+                Err(err) => return Err(err),
+            }
+            timeout *= 2;
+        }
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("no NAT-PMP response from {}", self.gateway),
+        ))
+    }
+}
+
+fn build_mapping_request(
+    protocol: Protocol,
+    internal_port: u16,
+    external_port: u16,
+    lifetime: u32,
+) -> [u8; 12] {
+    let op = match protocol {
+        Protocol::Udp => OP_MAP_UDP,
+        Protocol::Tcp => OP_MAP_TCP,
+    };
+    let mut req = [0u8; 12];
+    req[0] = VERSION;
+    req[1] = op;
+    req[4..6].copy_from_slice(&internal_port.to_be_bytes());
+    req[6..8].copy_from_slice(&external_port.to_be_bytes());
+    req[8..12].copy_from_slice(&lifetime.to_be_bytes());
+    req
+}
+
+/// Check that `resp` answers the request with opcode `op`.
+///
+/// Returns `Ok(None)` for datagrams to ignore, and an error if the gateway
+/// rejected the request.
+fn check_response(resp: &[u8], op: u8, min_len: usize) -> io::Result<Option<&[u8]>> {
+    if resp.len() < 4 || resp[0] != VERSION || resp[1] != OP_RESPONSE + op {
+        return Ok(None);
+    }
+    let (kind, message) = match u16::from_be_bytes([resp[2], resp[3]]) {
+        0 if resp.len() >= min_len => return Ok(Some(resp)),
+        0 => return Ok(None),
+        1 => (io::ErrorKind::Unsupported, "unsupported NAT-PMP version"),
+        2 => (
+            io::ErrorKind::PermissionDenied,
+            "port mapping is disabled on the gateway",
+        ),
+        3 => (io::ErrorKind::Other, "the gateway has no external address"),
+        4 => (io::ErrorKind::Other, "the gateway is out of mappings"),
+        5 => (io::ErrorKind::Unsupported, "unsupported NAT-PMP opcode"),
+        _ => (io::ErrorKind::Other, "unknown NAT-PMP result code"),
+    };
+    Err(io::Error::new(kind, message))
+}
+
+fn parse_mapping_response(resp: &[u8], protocol: Protocol) -> PortMapping {
+    PortMapping {
+        protocol,
+        internal_port: u16::from_be_bytes([resp[8], resp[9]]),
+        external_port: u16::from_be_bytes([resp[10], resp[11]]),
+        lifetime: Duration::from_secs(
+            u32::from_be_bytes([resp[12], resp[13], resp[14], resp[15]]) as u64
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mapping_messages() {
+        let req = build_mapping_request(Protocol::Tcp, 8080, 80, 3600);
+        assert_eq!(req, [0, 2, 0, 0, 0x1f, 0x90, 0, 80, 0, 0, 0x0e, 0x10]);
+
+        let resp = [
+            0, 130, 0, 0, 0, 0, 0, 1, 0x1f, 0x90, 0x1f, 0x91, 0, 0, 0x07, 0x08,
+        ];
+        let resp = check_response(&resp, OP_MAP_TCP, 16).unwrap().unwrap();
+        let mapping = parse_mapping_response(resp, Protocol::Tcp);
+        assert_eq!(mapping.internal_port, 8080);
+        assert_eq!(mapping.external_port, 8081);
+        assert_eq!(mapping.lifetime, Duration::from_secs(1800));
+    }
+
+    #[test]
+    fn test_check_response() {
+        // Response to a different request
+        assert!(check_response(&[0, 129, 0, 0], OP_MAP_TCP, 4)
+            .unwrap()
+            .is_none());
+        // Truncated
+        assert!(
+            check_response(&[0, 128, 0, 0, 0, 0], OP_EXTERNAL_ADDRESS, 12)
+                .unwrap()
+                .is_none()
+        );
+        let err = check_response(&[0, 128, 0, 2], OP_EXTERNAL_ADDRESS, 12).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    }
+}
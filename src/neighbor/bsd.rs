@@ -0,0 +1,45 @@
+use crate::mac::MacAddr;
+use crate::sys::rtsock::{
+    finish_message, new_message, push_sockaddr, send, sockaddr_dl, sockaddr_ip, RTA_DST,
+    RTA_GATEWAY, RTF_HOST, RTF_LLINFO, RTF_STATIC, RTF_UP, RTM_ADD, RTM_CHANGE, RTM_DELETE,
+};
+use std::io;
+use std::net::IpAddr;
+
+// Host route to the address whose gateway is the link-layer address, as `arp -s` and
+// `ndp -s` install it
+fn build_message(rtm_type: u8, ip: IpAddr, lladdr: &[u8], if_index: u32) -> io::Result<Vec<u8>> {
+    let scope_id = match ip {
+        IpAddr::V6(ip) if crate::zone::is_link_local(&ip) => if_index,
+        _ => 0,
+    };
+    let mut buf = new_message();
+    push_sockaddr(&mut buf, &sockaddr_ip(ip, scope_id));
+    push_sockaddr(&mut buf, &sockaddr_dl(if_index, lladdr));
+    finish_message(
+        &mut buf,
+        rtm_type,
+        if_index,
+        RTF_UP | RTF_HOST | RTF_STATIC | RTF_LLINFO,
+        RTA_DST | RTA_GATEWAY,
+    )?;
+    Ok(buf)
+}
+
+pub fn add_neighbor_entry(ip: IpAddr, mac_addr: MacAddr, if_index: u32) -> io::Result<()> {
+    // The routing socket refuses to add over an existing entry, which is
+    // changed in place so that it is kept if the change fails
+    match send(&build_message(RTM_ADD, ip, &mac_addr.octets(), if_index)?) {
+        Err(err) if err.raw_os_error() == Some(libc::EEXIST) => send(&build_message(
+            RTM_CHANGE,
+            ip,
+            &mac_addr.octets(),
+            if_index,
+        )?),
+        result => result,
+    }
+}
+
+pub fn delete_neighbor_entry(ip: IpAddr, if_index: u32) -> io::Result<()> {
+    send(&build_message(RTM_DELETE, ip, &[], if_index)?)
+}
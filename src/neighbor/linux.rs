@@ -0,0 +1,42 @@
+use crate::mac::MacAddr;
+use crate::sys::netlink;
+use netlink_packet_core::{NLM_F_CREATE, NLM_F_REPLACE};
+use netlink_packet_route::{
+    rtnl::neighbour::nlas::Nla as NeighbourNla, NeighbourMessage, RtnlMessage, AF_INET, AF_INET6,
+    NUD_PERMANENT,
+};
+use std::io;
+use std::net::IpAddr;
+
+fn neighbour_message(ip: IpAddr, if_index: u32) -> NeighbourMessage {
+    let mut msg = NeighbourMessage::default();
+    msg.header.ifindex = if_index;
+    let destination = match ip {
+        IpAddr::V4(ip) => {
+            msg.header.family = AF_INET as u8;
+            ip.octets().to_vec()
+        }
+        IpAddr::V6(ip) => {
+            msg.header.family = AF_INET6 as u8;
+            ip.octets().to_vec()
+        }
+    };
+    msg.nlas.push(NeighbourNla::Destination(destination));
+    msg
+}
+
+pub fn add_neighbor_entry(ip: IpAddr, mac_addr: MacAddr, if_index: u32) -> io::Result<()> {
+    let mut msg = neighbour_message(ip, if_index);
+    msg.header.state = NUD_PERMANENT;
+    msg.nlas
+        .push(NeighbourNla::LinkLocalAddress(mac_addr.octets().to_vec()));
+    // Like `ip neigh replace`
+    netlink::request(RtnlMessage::NewNeighbour(msg), NLM_F_CREATE | NLM_F_REPLACE)
+}
+
+pub fn delete_neighbor_entry(ip: IpAddr, if_index: u32) -> io::Result<()> {
+    netlink::request(
+        RtnlMessage::DelNeighbour(neighbour_message(ip, if_index)),
+        0,
+    )
+}
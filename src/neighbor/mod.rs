@@ -0,0 +1,101 @@
+//! Neighbour cache (ARP and NDP) manipulation.
+//!
+//! Adding and deleting entries requires elevated privileges.
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod linux;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use self::linux::*;
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+mod bsd;
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+use self::bsd::*;
+
+#[cfg(target_os = "windows")]
+mod windows;
+#[cfg(target_os = "windows")]
+use self::windows::*;
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "windows"
+)))]
+mod unsupported;
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "windows"
+)))]
+use self::unsupported::*;
+
+use crate::interface::Interface;
+use crate::mac::MacAddr;
+use std::io;
+use std::net::IpAddr;
+
+fn check_neighbor_ip(ip: IpAddr) -> io::Result<()> {
+    if ip.is_unspecified() || ip.is_multicast() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "neighbor address must be a unicast address",
+        ));
+    }
+    Ok(())
+}
+
+/// Add a static entry mapping `ip` to `mac_addr` on `interface`, like `arp -s`
+///
+/// An existing entry for the address is replaced. IPv6 addresses add an NDP entry.
+pub fn add_neighbor(ip: IpAddr, mac_addr: MacAddr, interface: &Interface) -> io::Result<()> {
+    check_neighbor_ip(ip)?;
+    if mac_addr == MacAddr::zero() || mac_addr == MacAddr::broadcast() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "neighbor MAC address must be a unicast address",
+        ));
+    }
+    add_neighbor_entry(ip, mac_addr, interface.index)
+}
+
+/// Delete the entry for `ip` on `interface`, like `arp -d`
+pub fn delete_neighbor(ip: IpAddr, interface: &Interface) -> io::Result<()> {
+    check_neighbor_ip(ip)?;
+    delete_neighbor_entry(ip, interface.index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_add_neighbor_invalid() {
+        let interface = Interface::dummy();
+        let mac_addr = MacAddr::new(0x02, 0, 0, 0, 0, 1);
+        let err = add_neighbor(IpAddr::from([224, 0, 0, 1]), mac_addr, &interface).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        let err =
+            add_neighbor(IpAddr::from([192, 0, 2, 1]), MacAddr::zero(), &interface).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        let err = delete_neighbor(IpAddr::from([0, 0, 0, 0]), &interface).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,18 @@
+use crate::mac::MacAddr;
+use std::io;
+use std::net::IpAddr;
+
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Neighbour cache manipulation is not supported on this platform",
+    )
+}
+
+pub fn add_neighbor_entry(_ip: IpAddr, _mac_addr: MacAddr, _if_index: u32) -> io::Result<()> {
+    Err(unsupported())
+}
+
+pub fn delete_neighbor_entry(_ip: IpAddr, _if_index: u32) -> io::Result<()> {
+    Err(unsupported())
+}
@@ -0,0 +1,49 @@
+use crate::mac::MacAddr;
+use std::io;
+use std::net::IpAddr;
+use windows_sys::Win32::Foundation::{ERROR_OBJECT_ALREADY_EXISTS, NO_ERROR};
+use windows_sys::Win32::NetworkManagement::IpHelper::{
+    CreateIpNetEntry2, DeleteIpNetEntry2, SetIpNetEntry2, MIB_IPNET_ROW2,
+};
+use windows_sys::Win32::Networking::WinSock::{NlnsPermanent, AF_INET, AF_INET6};
+
+fn neighbour_row(ip: IpAddr, if_index: u32) -> MIB_IPNET_ROW2 {
+    let mut row: MIB_IPNET_ROW2 = unsafe { std::mem::zeroed() };
+    row.InterfaceIndex = if_index;
+    match ip {
+        IpAddr::V4(ip) => {
+            row.Address.Ipv4.sin_family = AF_INET;
+            row.Address.Ipv4.sin_addr.S_un.S_addr = u32::from_ne_bytes(ip.octets());
+        }
+        IpAddr::V6(ip) => {
+            row.Address.Ipv6.sin6_family = AF_INET6;
+            row.Address.Ipv6.sin6_addr.u.Byte = ip.octets();
+        }
+    }
+    row
+}
+
+pub fn add_neighbor_entry(ip: IpAddr, mac_addr: MacAddr, if_index: u32) -> io::Result<()> {
+    let mut row = neighbour_row(ip, if_index);
+    row.PhysicalAddress[..6].copy_from_slice(&mac_addr.octets());
+    row.PhysicalAddressLength = 6;
+    row.State = NlnsPermanent;
+    // CreateIpNetEntry2 fails if the address is already in the cache, where
+    // the entry is updated instead so that it is kept if that fails
+    let err = match unsafe { CreateIpNetEntry2(&row) } {
+        ERROR_OBJECT_ALREADY_EXISTS => unsafe { SetIpNetEntry2(&row) },
+        err => err,
+    };
+    match err {
+        NO_ERROR => Ok(()),
+        err => Err(io::Error::from_raw_os_error(err as i32)),
+    }
+}
+
+pub fn delete_neighbor_entry(ip: IpAddr, if_index: u32) -> io::Result<()> {
+    let row = neighbour_row(ip, if_index);
+    match unsafe { DeleteIpNetEntry2(&row) } {
+        NO_ERROR => Ok(()),
+        err => Err(io::Error::from_raw_os_error(err as i32)),
+    }
+}
@@ -0,0 +1,94 @@
+//! Network namespace aware enumeration (Linux)
+//!
+//! The enumeration runs on a dedicated thread that joins the target namespace with
+//! `setns(2)`, so the calling thread and the rest of the process are never moved.
+//! Entering a namespace requires `CAP_SYS_ADMIN`.
+//!
+//! Details read from sysfs (`/sys/class/net`), such as the interface type and speed,
+//! reflect the namespace sysfs was mounted in and may be missing for interfaces that
+//! only exist in the target namespace.
+//!
+//! Interfaces enumerated inside a namespace carry its inode number in
+//! [`Interface::netns`], which [`id`] returns for a namespace path.
+
+use crate::interface::{self, Interface};
+use std::fs::File;
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+const PATH_NAMED_NETNS: &str = "/var/run/netns";
+
+/// Returns the path of a named network namespace created by `ip netns add`
+pub fn named_path(name: &str) -> PathBuf {
+    Path::new(PATH_NAMED_NETNS).join(name)
+}
+
+/// Returns the path of the network namespace of the given process
+pub fn pid_path(pid: u32) -> PathBuf {
+    PathBuf::from(format!("/proc/{}/ns/net", pid))
+}
+
+/// Returns the inode number identifying the network namespace at `netns`
+pub fn id<P: AsRef<Path>>(netns: P) -> io::Result<u64> {
+    Ok(std::fs::metadata(netns.as_ref())?.ino())
+}
+
+/// Run `f` inside the network namespace at `netns` (e.g. `/var/run/netns/blue` or `/proc/<pid>/ns/net`)
+///
+/// `f` is executed on a scoped worker thread that enters the namespace, so it may borrow
+/// from the caller. The namespace is left when the thread exits.
+pub fn enter<P, F, T>(netns: P, f: F) -> io::Result<T>
+where
+    P: AsRef<Path>,
+    F: FnOnce() -> T + Send,
+    T: Send,
+{
+    let file = File::open(netns.as_ref())?;
+    std::thread::scope(|scope| {
+        let handle = scope.spawn(move || {
+            if unsafe { libc::setns(file.as_raw_fd(), libc::CLONE_NEWNET) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(f())
+        });
+        match handle.join() {
+            Ok(result) => result,
+            Err(_) => Err(io::Error::other("Network namespace worker thread panicked")),
+        }
+    })
+}
+
+/// Get a list of available Network Interfaces in the network namespace at `netns`
+pub fn interfaces_in<P: AsRef<Path>>(netns: P) -> io::Result<Vec<Interface>> {
+    enter(netns, interface::get_interfaces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_interfaces_in_self() {
+        // Entering our own namespace requires CAP_SYS_ADMIN, so only print the result.
+        match interfaces_in(pid_path(std::process::id())) {
+            Ok(interfaces) => {
+                for interface in interfaces {
+                    println!("{:#?}", interface);
+                }
+            }
+            Err(e) => println!("{}", e),
+        }
+    }
+    #[test]
+    fn test_id() {
+        let own = id(pid_path(std::process::id())).unwrap();
+        for interface in interface::get_interfaces() {
+            assert_eq!(interface.netns, Some(own));
+        }
+    }
+    #[test]
+    fn test_enter_missing_netns() {
+        assert!(enter(named_path("netdev-test-missing"), || ()).is_err());
+    }
+}
@@ -0,0 +1,701 @@
+//! Checked decoders for untrusted input.
+//!
+//! The functions here work on plain strings and byte slices, validate every
+//! length before reading and never panic, whatever the input. The interface
+//! and routing backends decode OS-provided `sockaddr`s through
+//! [`SockAddrView`], and the targets under `fuzz/` exercise them with
+//! arbitrary data.
+
+use crate::mac::{HardwareAddr, MacAddr, ParseMacAddrError};
+
+#[cfg(unix)]
+use std::io;
+#[cfg(unix)]
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+/// Parse a colon-separated MAC address such as `00:1b:21:0a:bc:de`
+///
+/// Each of the six components must be one or two hex digits. Signs,
+/// whitespace and empty components are rejected.
+pub fn mac_addr(s: &str) -> Result<MacAddr, ParseMacAddrError> {
+    let mut octets = [0u8; 6];
+    let mut count = 0;
+    for part in s.split(':') {
+        if count == 6 {
+            return Err(ParseMacAddrError::TooManyComponents);
+        }
+        if part.is_empty() || part.len() > 2 || !part.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseMacAddrError::InvalidComponent);
+        }
+        octets[count] =
+            u8::from_str_radix(part, 16).map_err(|_| ParseMacAddrError::InvalidComponent)?;
+        count += 1;
+    }
+    if count < 6 {
+        return Err(ParseMacAddrError::TooFewComponents);
+    }
+    Ok(MacAddr::from_octets(octets))
+}
+
+/// Parse a MAC address written as six two-digit hex octets, such as
+/// `00:1b:21:0a:bc:de` or `00-1B-21-0A-BC-DE`
+///
+/// All octets must be separated by the same separator, either `:` or `-`.
+/// This is the format accepted by `MacAddr`'s `FromStr` implementation.
+pub fn mac_addr_strict(s: &str) -> Result<MacAddr, ParseMacAddrError> {
+    let separator = match s.as_bytes().get(2) {
+        Some(b'-') => '-',
+        _ => ':',
+    };
+    let mut octets = [0u8; 6];
+    let mut count = 0;
+    for part in s.split(separator) {
+        if count == 6 {
+            return Err(ParseMacAddrError::TooManyComponents);
+        }
+        octets[count] = hex_octet(part, 2)?;
+        count += 1;
+    }
+    if count < 6 {
+        return Err(ParseMacAddrError::TooFewComponents);
+    }
+    Ok(MacAddr::from_octets(octets))
+}
+
+/// Parse a MAC address in any of the common loose spellings
+///
+/// On top of [`mac_addr_strict`], this accepts surrounding whitespace,
+/// single-digit octets (`0:1b:21:a:bc:de`), a mix of `:` and `-`
+/// separators, `0x` prefixes on the octets, and 12 hex digits without
+/// separators, with or without a `0x` prefix (`0x001b210abcde`).
+pub fn mac_addr_lenient(s: &str) -> Result<MacAddr, ParseMacAddrError> {
+    let s = s.trim();
+    if !s.contains([':', '-']) {
+        let digits = strip_hex_prefix(s);
+        if digits.len() != 12 {
+            return Err(if digits.len() < 12 {
+                ParseMacAddrError::TooFewComponents
+            } else {
+                ParseMacAddrError::TooManyComponents
+            });
+        }
+        // Checked first so the byte slicing below stays on char boundaries
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseMacAddrError::InvalidComponent);
+        }
+        let mut octets = [0u8; 6];
+        for (i, octet) in octets.iter_mut().enumerate() {
+            *octet = hex_octet(&digits[i * 2..i * 2 + 2], 2)?;
+        }
+        return Ok(MacAddr::from_octets(octets));
+    }
+    let mut octets = [0u8; 6];
+    let mut count = 0;
+    for part in s.split([':', '-']) {
+        if count == 6 {
+            return Err(ParseMacAddrError::TooManyComponents);
+        }
+        let digits = strip_hex_prefix(part);
+        if digits.len() > 2 {
+            return Err(ParseMacAddrError::InvalidComponent);
+        }
+        octets[count] = hex_octet(digits, digits.len())?;
+        count += 1;
+    }
+    if count < 6 {
+        return Err(ParseMacAddrError::TooFewComponents);
+    }
+    Ok(MacAddr::from_octets(octets))
+}
+
+fn strip_hex_prefix(s: &str) -> &str {
+    s.strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s)
+}
+
+/// Parse an octet of exactly `digits` hex digits, at least one
+fn hex_octet(s: &str, digits: usize) -> Result<u8, ParseMacAddrError> {
+    if s.is_empty() || s.len() != digits || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ParseMacAddrError::InvalidComponent);
+    }
+    u8::from_str_radix(s, 16).map_err(|_| ParseMacAddrError::InvalidComponent)
+}
+
+/// Longest hardware address accepted by [`hardware_addr`]
+pub const MAX_HARDWARE_ADDR_LEN: usize = 32;
+
+/// Parse a colon-separated hardware address of any length up to
+/// [`MAX_HARDWARE_ADDR_LEN`] bytes, such as an InfiniBand address
+///
+/// Components follow the rules of [`mac_addr`].
+pub fn hardware_addr(s: &str) -> Result<HardwareAddr, ParseMacAddrError> {
+    let mut octets = Vec::new();
+    for part in s.split(':') {
+        if octets.len() == MAX_HARDWARE_ADDR_LEN {
+            return Err(ParseMacAddrError::TooManyComponents);
+        }
+        if part.is_empty() || part.len() > 2 || !part.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseMacAddrError::InvalidComponent);
+        }
+        octets.push(u8::from_str_radix(part, 16).map_err(|_| ParseMacAddrError::InvalidComponent)?);
+    }
+    Ok(HardwareAddr::from_bytes(&octets))
+}
+
+#[cfg(unix)]
+const SOCKADDR_IN_LEN: usize = 16;
+#[cfg(unix)]
+const SOCKADDR_IN6_LEN: usize = 28;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const SOCKADDR_LL_LEN: usize = 20;
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+const SOCKADDR_DL_HEADER_LEN: usize = 8;
+
+#[cfg(unix)]
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Address family of a raw sockaddr
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn family(buf: &[u8]) -> io::Result<libc::c_int> {
+    match buf {
+        [a, b, ..] => Ok(u16::from_ne_bytes([*a, *b]) as libc::c_int),
+        _ => Err(invalid("sockaddr is too short")),
+    }
+}
+
+/// Address family of a raw sockaddr, checking `sa_len` against the buffer
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+fn family(buf: &[u8]) -> io::Result<libc::c_int> {
+    match buf {
+        [len, family, ..] if *len as usize <= buf.len() => Ok(*family as libc::c_int),
+        [_, _, ..] => Err(invalid("sa_len exceeds the buffer")),
+        _ => Err(invalid("sockaddr is too short")),
+    }
+}
+
+/// Usable length of a raw sockaddr: `sa_len` where the platform has it
+#[cfg(unix)]
+fn sockaddr_len(buf: &[u8]) -> usize {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let len = buf.len();
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let len = buf.first().map_or(0, |len| *len as usize).min(buf.len());
+    len
+}
+
+#[cfg(unix)]
+fn array<const N: usize>(buf: &[u8], offset: usize) -> [u8; N] {
+    let mut out = [0u8; N];
+    out.copy_from_slice(&buf[offset..offset + N]);
+    out
+}
+
+/// Raw sockaddr whose family could be read and, where the platform has
+/// `sa_len`, whose length fits the buffer.
+///
+/// The accessors check the family and the length of the structure they read
+/// and return an error instead of reading past the address, so a view can be
+/// built from any bytes the OS hands over, such as the addresses following a
+/// routing message header.
+#[cfg(unix)]
+#[derive(Clone, Copy, Debug)]
+pub struct SockAddrView<'a> {
+    /// The bytes of the sockaddr, cut to `sa_len` where the platform has it
+    buf: &'a [u8],
+    family: libc::c_int,
+}
+
+#[cfg(unix)]
+impl<'a> SockAddrView<'a> {
+    /// Check the header of the sockaddr at the start of `buf`
+    pub fn new(buf: &'a [u8]) -> io::Result<SockAddrView<'a>> {
+        let family = family(buf)?;
+        Ok(SockAddrView {
+            buf: &buf[..sockaddr_len(buf)],
+            family,
+        })
+    }
+    /// Returns the address family, such as `libc::AF_INET`
+    pub fn family(&self) -> libc::c_int {
+        self.family
+    }
+    /// Returns the bytes of the sockaddr
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.buf
+    }
+    /// Decode a `sockaddr_in` or `sockaddr_in6`
+    pub fn socket_addr(&self) -> io::Result<SocketAddr> {
+        let buf = self.buf;
+        if self.family == libc::AF_INET {
+            if buf.len() < SOCKADDR_IN_LEN {
+                return Err(invalid("sockaddr_in is too short"));
+            }
+            let port = u16::from_be_bytes(array(buf, 2));
+            let ip = Ipv4Addr::from(array::<4>(buf, 4));
+            Ok(SocketAddr::V4(SocketAddrV4::new(ip, port)))
+        } else if self.family == libc::AF_INET6 {
+            if buf.len() < SOCKADDR_IN6_LEN {
+                return Err(invalid("sockaddr_in6 is too short"));
+            }
+            let port = u16::from_be_bytes(array(buf, 2));
+            let flowinfo = u32::from_be_bytes(array(buf, 4));
+            let ip = Ipv6Addr::from(array::<16>(buf, 8));
+            let scope_id = u32::from_ne_bytes(array(buf, 24));
+            Ok(SocketAddr::V6(SocketAddrV6::new(
+                ip, port, flowinfo, scope_id,
+            )))
+        } else {
+            Err(invalid("unsupported address family"))
+        }
+    }
+    /// Decode the IP address of a `sockaddr_in` or `sockaddr_in6`
+    pub fn ip(&self) -> io::Result<IpAddr> {
+        Ok(self.socket_addr()?.ip())
+    }
+    /// Decode the hardware address of a `sockaddr_ll`, of length `sll_halen`.
+    ///
+    /// Returns `None` if the family is not `AF_PACKET` or the address is empty.
+    /// Addresses longer than the 8 bytes of `sll_addr` continue past the end of
+    /// the standard structure, as libc's `getifaddrs` stores them.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn hardware_addr(&self) -> io::Result<Option<HardwareAddr>> {
+        if self.family != libc::AF_PACKET {
+            return Ok(None);
+        }
+        let buf = self.buf;
+        if buf.len() < SOCKADDR_LL_LEN {
+            return Err(invalid("sockaddr_ll is too short"));
+        }
+        let halen = buf[11] as usize;
+        if halen == 0 {
+            return Ok(None);
+        }
+        let addr = buf
+            .get(12..12 + halen)
+            .ok_or_else(|| invalid("sll_halen exceeds the buffer"))?;
+        Ok(Some(HardwareAddr::from_bytes(addr)))
+    }
+    /// Decode the MAC address of a `sockaddr_ll`.
+    ///
+    /// Returns `None` if the family is not `AF_PACKET` or the hardware address
+    /// is not 6 bytes long.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn mac_addr(&self) -> io::Result<Option<MacAddr>> {
+        Ok(self.hardware_addr()?.and_then(|addr| addr.mac_addr()))
+    }
+    /// Decode the hardware address of a `sockaddr_dl`, of length `sdl_alen`.
+    ///
+    /// Returns `None` if the family is not `AF_LINK` or the address is empty.
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "openbsd",
+        target_os = "freebsd",
+        target_os = "netbsd"
+    ))]
+    pub fn hardware_addr(&self) -> io::Result<Option<HardwareAddr>> {
+        Ok(self
+            .link_layer_addr()?
+            .filter(|addr| !addr.is_empty())
+            .map(HardwareAddr::from_bytes))
+    }
+    /// Decode the MAC address of a `sockaddr_dl`.
+    ///
+    /// Returns `None` if the family is not `AF_LINK` or the link-layer address
+    /// is shorter than 6 bytes.
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "openbsd",
+        target_os = "freebsd",
+        target_os = "netbsd"
+    ))]
+    pub fn mac_addr(&self) -> io::Result<Option<MacAddr>> {
+        Ok(self
+            .link_layer_addr()?
+            .filter(|addr| addr.len() >= 6)
+            .map(|addr| MacAddr::from_octets(array(addr, 0))))
+    }
+    /// The `sdl_alen` bytes of a `sockaddr_dl` after the interface name
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "openbsd",
+        target_os = "freebsd",
+        target_os = "netbsd"
+    ))]
+    fn link_layer_addr(&self) -> io::Result<Option<&'a [u8]>> {
+        if self.family != libc::AF_LINK {
+            return Ok(None);
+        }
+        let buf = self.buf;
+        if buf.len() < SOCKADDR_DL_HEADER_LEN {
+            return Err(invalid("sockaddr_dl is too short"));
+        }
+        let start = SOCKADDR_DL_HEADER_LEN + buf[5] as usize;
+        let end = start + buf[6] as usize;
+        if end > buf.len() {
+            return Err(invalid("sockaddr_dl address exceeds sdl_len"));
+        }
+        Ok(Some(&buf[start..end]))
+    }
+}
+
+/// Decode a raw `sockaddr_in` or `sockaddr_in6`
+#[cfg(unix)]
+pub fn sockaddr(buf: &[u8]) -> io::Result<SocketAddr> {
+    SockAddrView::new(buf)?.socket_addr()
+}
+
+/// Decode a netmask sockaddr of the given family.
+///
+/// BSD kernels truncate netmasks after their last non-zero byte and may leave
+/// the family unset, so missing bytes are read as zero and the family byte is
+/// not checked there.
+#[cfg(unix)]
+pub fn netmask(buf: &[u8], ipv6: bool) -> IpAddr {
+    let len = sockaddr_len(buf);
+    let (offset, size) = if ipv6 { (8, 16) } else { (4, 4) };
+    let mut octets = [0u8; 16];
+    if len > offset {
+        let end = len.min(offset + size);
+        octets[..end - offset].copy_from_slice(&buf[offset..end]);
+    }
+    if ipv6 {
+        IpAddr::V6(Ipv6Addr::from(octets))
+    } else {
+        IpAddr::V4(Ipv4Addr::from(array::<4>(&octets, 0)))
+    }
+}
+
+/// Decode the MAC address of a raw link-layer sockaddr, see [`SockAddrView::mac_addr`]
+#[cfg(unix)]
+pub fn link_addr(buf: &[u8]) -> io::Result<Option<MacAddr>> {
+    SockAddrView::new(buf)?.mac_addr()
+}
+
+/// Decode the hardware address of a raw link-layer sockaddr, see
+/// [`SockAddrView::hardware_addr`]
+#[cfg(unix)]
+pub fn link_hardware_addr(buf: &[u8]) -> io::Result<Option<HardwareAddr>> {
+    SockAddrView::new(buf)?.hardware_addr()
+}
+
+/// Number of address slots returned by [`rt_addrs`], covering every `RTAX_*` index
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+pub const MAX_RT_ADDRS: usize = 16;
+
+/// Split the sockaddrs following a routing message header by their `RTAX_*`
+/// index, for the bits set in `rtm_addrs`.
+///
+/// Each sockaddr takes `sa_len` bytes rounded up to the platform's alignment,
+/// and an empty one (as BSD kernels write for an all-zero netmask) takes one
+/// alignment unit. The slices are cut to `sa_len` and may be empty.
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+pub fn rt_addrs(buf: &[u8], rtm_addrs: i32) -> io::Result<[Option<&[u8]>; MAX_RT_ADDRS]> {
+    let mut addrs = [None; MAX_RT_ADDRS];
+    let mut offset = 0;
+    for (index, addr) in addrs.iter_mut().enumerate() {
+        if rtm_addrs & (1 << index) == 0 {
+            continue;
+        }
+        let sa_len = *buf
+            .get(offset)
+            .ok_or_else(|| invalid("routing message addresses are truncated"))?
+            as usize;
+        *addr = Some(
+            buf.get(offset..offset + sa_len)
+                .ok_or_else(|| invalid("sa_len exceeds the routing message"))?,
+        );
+        offset += sa_len.max(1).next_multiple_of(crate::sys::SA_ALIGN);
+    }
+    Ok(addrs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mac_addr() {
+        let mac = MacAddr::new(0x00, 0x1b, 0x21, 0x0a, 0xbc, 0xde);
+        assert_eq!(mac_addr("00:1b:21:0a:bc:de"), Ok(mac));
+        assert_eq!(mac_addr("0:1B:21:A:bc:DE"), Ok(mac));
+        assert_eq!(
+            mac_addr("00:1b:21:0a:bc"),
+            Err(ParseMacAddrError::TooFewComponents)
+        );
+        assert_eq!(
+            mac_addr("00:1b:21:0a:bc:de:ff"),
+            Err(ParseMacAddrError::TooManyComponents)
+        );
+        for bad in [
+            "",
+            " 00:1b:21:0a:bc:de",
+            "00:1b:21:0a:bc:de ",
+            "00: 1b:21:0a:bc:de",
+            "+0:1b:21:0a:bc:de",
+            "000:1b:21:0a:bc:de",
+            "00::21:0a:bc:de",
+            "gg:1b:21:0a:bc:de",
+        ] {
+            assert_eq!(mac_addr(bad).ok(), None, "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_mac_addr_strict() {
+        let mac = MacAddr::new(0x00, 0x1b, 0x21, 0x0a, 0xbc, 0xde);
+        assert_eq!(mac_addr_strict("00:1b:21:0a:bc:de"), Ok(mac));
+        assert_eq!(mac_addr_strict("00-1B-21-0A-BC-DE"), Ok(mac));
+        assert_eq!(
+            mac_addr_strict("00:1b:21:0a:bc"),
+            Err(ParseMacAddrError::TooFewComponents)
+        );
+        assert_eq!(
+            mac_addr_strict("00-1b-21-0a-bc-de-ff"),
+            Err(ParseMacAddrError::TooManyComponents)
+        );
+        for bad in [
+            "0:1b:21:0a:bc:de",
+            "00:1b:21:a:bc:de",
+            "00:1b-21:0a:bc:de",
+            "00-1b-21-0a-bc:de",
+            " 00:1b:21:0a:bc:de",
+            "0x00:1b:21:0a:bc:de",
+            "001b210abcde",
+        ] {
+            assert_eq!(mac_addr_strict(bad).ok(), None, "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_mac_addr_lenient() {
+        let mac = MacAddr::new(0x00, 0x1b, 0x21, 0x0a, 0xbc, 0xde);
+        for good in [
+            "00:1b:21:0a:bc:de",
+            " 0:1B:21:A:bc:DE\n",
+            "00-1b:21-0a:bc-de",
+            "0x00:0x1b:0x21:0x0a:0xbc:0xde",
+            "001B210ABCDE",
+            "0x001b210abcde",
+        ] {
+            assert_eq!(mac_addr_lenient(good), Ok(mac), "{:?}", good);
+        }
+        assert_eq!(
+            mac_addr_lenient("001b210abc"),
+            Err(ParseMacAddrError::TooFewComponents)
+        );
+        for bad in [
+            "",
+            "00:1b:21:0a:bc:",
+            "00:1b:21:0a:bc:0x",
+            "000:1b:21:0a:bc:de",
+            "00 : 1b:21:0a:bc:de",
+            "0x0x001b210abcde",
+            "001b210abcdé",
+        ] {
+            assert_eq!(mac_addr_lenient(bad).ok(), None, "{:?}", bad);
+        }
+    }
+
+    /// Small deterministic generator so the no-panic checks run without a fuzzer
+    #[cfg(unix)]
+    fn pseudo_random_bytes(seed: &mut u64, len: usize) -> Vec<u8> {
+        (0..len)
+            .map(|_| {
+                *seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (*seed >> 56) as u8
+            })
+            .collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_arbitrary_sockaddr() {
+        let mut seed = 1;
+        for len in 0..64 {
+            for _ in 0..256 {
+                let mut buf = pseudo_random_bytes(&mut seed, len);
+                // Steer some inputs towards the interesting families.
+                if len >= 2 && seed % 2 == 0 {
+                    let families = [libc::AF_INET, libc::AF_INET6, libc::AF_UNSPEC];
+                    let family = families[(seed as usize / 2) % families.len()];
+                    #[cfg(any(target_os = "linux", target_os = "android"))]
+                    buf[..2].copy_from_slice(&(family as u16).to_ne_bytes());
+                    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+                    {
+                        buf[1] = family as u8;
+                    }
+                }
+                let _ = sockaddr(&buf);
+                let _ = link_addr(&buf);
+                let _ = link_hardware_addr(&buf);
+                let _ = netmask(&buf, false);
+                let _ = netmask(&buf, true);
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sockaddr() {
+        let mut sin: libc::sockaddr_in = unsafe { std::mem::zeroed() };
+        sin.sin_family = libc::AF_INET as libc::sa_family_t;
+        sin.sin_port = 8080u16.to_be();
+        sin.sin_addr.s_addr = u32::from_ne_bytes([192, 168, 1, 2]);
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        {
+            sin.sin_len = SOCKADDR_IN_LEN as u8;
+        }
+        let buf = unsafe {
+            std::slice::from_raw_parts(
+                &sin as *const libc::sockaddr_in as *const u8,
+                std::mem::size_of::<libc::sockaddr_in>(),
+            )
+        };
+        assert_eq!(sockaddr(buf).unwrap(), "192.168.1.2:8080".parse().unwrap());
+        assert!(sockaddr(&buf[..8]).is_err());
+        assert_eq!(
+            netmask(buf, false),
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 2))
+        );
+        assert_eq!(link_addr(buf).unwrap(), None);
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn test_link_hardware_addr() {
+        // sockaddr_ll of an IPoIB interface, with the address running past sll_addr
+        let mut buf = vec![0u8; 12 + 20];
+        buf[..2].copy_from_slice(&(libc::AF_PACKET as u16).to_ne_bytes());
+        buf[11] = 20;
+        buf[12] = 0x80;
+        let addr = link_hardware_addr(&buf).unwrap().unwrap();
+        assert!(matches!(addr, HardwareAddr::Infiniband20(_)));
+        assert_eq!(link_addr(&buf).unwrap(), None);
+        assert!(link_hardware_addr(&buf[..24]).is_err());
+        // No hardware address, as on a tun device
+        buf[11] = 0;
+        assert_eq!(link_hardware_addr(&buf).unwrap(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sockaddr_view() {
+        let mut buf = vec![0u8; SOCKADDR_IN6_LEN];
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        buf[..2].copy_from_slice(&(libc::AF_INET6 as u16).to_ne_bytes());
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        {
+            buf[0] = SOCKADDR_IN6_LEN as u8;
+            buf[1] = libc::AF_INET6 as u8;
+        }
+        buf[23] = 1;
+        let view = SockAddrView::new(&buf).unwrap();
+        assert_eq!(view.family(), libc::AF_INET6);
+        assert_eq!(view.as_bytes().len(), SOCKADDR_IN6_LEN);
+        assert_eq!(view.ip().unwrap(), IpAddr::V6(Ipv6Addr::LOCALHOST));
+        assert_eq!(view.hardware_addr().unwrap(), None);
+        // Cut short, the family is still readable but not the address
+        let view = SockAddrView::new(&buf[..16]);
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        assert!(view.unwrap().ip().is_err());
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        assert!(view.is_err());
+        assert!(SockAddrView::new(&buf[..1]).is_err());
+    }
+
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "openbsd",
+        target_os = "freebsd",
+        target_os = "netbsd"
+    ))]
+    #[test]
+    fn test_rt_addrs() {
+        let align = crate::sys::SA_ALIGN;
+        let mut buf = Vec::new();
+        // RTAX_DST: sockaddr_in6 of ::1
+        let mut dst = vec![28, libc::AF_INET6 as u8];
+        dst.resize(28, 0);
+        dst[23] = 1;
+        buf.extend_from_slice(&dst);
+        buf.resize(28usize.next_multiple_of(align), 0);
+        // RTAX_NETMASK: an empty /0 netmask, taking one alignment unit
+        buf.resize(buf.len() + align, 0);
+        // RTAX_IFP: sockaddr_dl with a MAC address after a 3-byte name
+        let dl = [
+            20,
+            libc::AF_LINK as u8,
+            2,
+            0,
+            6,
+            3,
+            6,
+            0,
+            b'e',
+            b'n',
+            b'0',
+            2,
+            0,
+            0,
+            0,
+            0,
+            1,
+            0,
+            0,
+            0,
+        ];
+        buf.extend_from_slice(&dl);
+        let addrs = rt_addrs(&buf, 0b10101).unwrap();
+        assert_eq!(
+            SockAddrView::new(addrs[0].unwrap()).unwrap().ip().unwrap(),
+            IpAddr::V6(Ipv6Addr::LOCALHOST)
+        );
+        assert_eq!(addrs[1], None);
+        assert_eq!(addrs[2], Some(&[][..]));
+        assert_eq!(
+            netmask(addrs[2].unwrap(), true),
+            IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+        );
+        let view = SockAddrView::new(addrs[4].unwrap()).unwrap();
+        assert_eq!(
+            view.mac_addr().unwrap(),
+            Some(MacAddr::new(2, 0, 0, 0, 0, 1))
+        );
+        // An address announced in rtm_addrs but missing from the message
+        assert!(rt_addrs(&buf, 0b110101).is_err());
+        assert!(rt_addrs(&buf[..20], 0b1).is_err());
+    }
+}
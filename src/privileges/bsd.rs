@@ -0,0 +1,44 @@
+use super::Capability;
+
+pub const PLATFORM_CAPABILITIES: &[Capability] = &[
+    Capability::RawSockets,
+    Capability::RouteModification,
+    Capability::ArpInjection,
+];
+
+/// BPF device checked for link-layer access
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "openbsd"))]
+const BPF_DEVICE: &[u8] = b"/dev/bpf0\0";
+#[cfg(any(target_os = "freebsd", target_os = "netbsd"))]
+const BPF_DEVICE: &[u8] = b"/dev/bpf\0";
+
+pub fn is_elevated() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+pub fn available_capabilities(elevated: bool) -> Vec<Capability> {
+    if elevated {
+        return PLATFORM_CAPABILITIES.to_vec();
+    }
+    // Capture tools commonly grant a group access to the BPF devices, which is
+    // enough to send frames without being root. access(2) checks the
+    // permission without opening, and so claiming, the device.
+    if unsafe {
+        libc::access(
+            BPF_DEVICE.as_ptr() as *const libc::c_char,
+            libc::R_OK | libc::W_OK,
+        )
+    } == 0
+    {
+        return vec![Capability::ArpInjection];
+    }
+    Vec::new()
+}
+
+pub fn hint(capability: Capability) -> &'static str {
+    match capability {
+        Capability::RawSockets | Capability::RouteModification => "run as root",
+        Capability::ArpInjection => "run as root or give the user read/write access to /dev/bpf*",
+        Capability::NetlinkAdmin => "netlink is only available on Linux",
+    }
+}
@@ -0,0 +1,75 @@
+use super::Capability;
+use std::fs;
+
+pub const PLATFORM_CAPABILITIES: &[Capability] = &[
+    Capability::RawSockets,
+    Capability::RouteModification,
+    Capability::NetlinkAdmin,
+    Capability::ArpInjection,
+];
+
+const CAP_NET_ADMIN: u32 = 12;
+const CAP_NET_RAW: u32 = 13;
+
+pub fn is_elevated() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+/// Parse the effective capability set from the contents of `/proc/self/status`
+fn parse_effective_caps(status: &str) -> Option<u64> {
+    let hex = status
+        .lines()
+        .find_map(|line| line.strip_prefix("CapEff:"))?
+        .trim();
+    u64::from_str_radix(hex, 16).ok()
+}
+
+pub fn available_capabilities(elevated: bool) -> Vec<Capability> {
+    // Without /proc, assume root holds every capability
+    let caps = match fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| parse_effective_caps(&status))
+    {
+        Some(caps) => caps,
+        None if elevated => u64::MAX,
+        None => 0,
+    };
+    let has = |cap: u32| caps & (1 << cap) != 0;
+    let mut available = Vec::new();
+    if has(CAP_NET_RAW) {
+        available.push(Capability::RawSockets);
+    }
+    if has(CAP_NET_ADMIN) {
+        available.push(Capability::RouteModification);
+        available.push(Capability::NetlinkAdmin);
+    }
+    if has(CAP_NET_RAW) {
+        available.push(Capability::ArpInjection);
+    }
+    available
+}
+
+pub fn hint(capability: Capability) -> &'static str {
+    match capability {
+        Capability::RawSockets | Capability::ArpInjection => {
+            "run as root or grant CAP_NET_RAW, e.g. `setcap cap_net_raw+ep <binary>`"
+        }
+        Capability::RouteModification | Capability::NetlinkAdmin => {
+            "run as root or grant CAP_NET_ADMIN, e.g. `setcap cap_net_admin+ep <binary>`"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_effective_caps() {
+        let status = "Name:\tcat\nCapInh:\t0000000000000000\nCapEff:\t0000000000003000\n";
+        let caps = parse_effective_caps(status).unwrap();
+        assert!(caps & (1 << CAP_NET_RAW) != 0);
+        assert!(caps & (1 << CAP_NET_ADMIN) != 0);
+        assert_eq!(parse_effective_caps("Name:\tcat\n"), None);
+    }
+}
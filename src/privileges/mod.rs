@@ -0,0 +1,142 @@
+//! Network privileges of the current process.
+//!
+//! [`check`] reports which privileged operations the process may perform, so
+//! an application can disable features up front and tell the user how to
+//! enable them, instead of surfacing `EPERM` or `ERROR_ACCESS_DENIED` later.
+//!
+//! ```
+//! let privileges = netdev::privileges::check();
+//! for capability in privileges.missing() {
+//!     println!("{:?} unavailable: {}", capability, capability.hint());
+//! }
+//! ```
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod linux;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use self::linux::*;
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+mod bsd;
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+use self::bsd::*;
+
+#[cfg(target_os = "windows")]
+mod windows;
+#[cfg(target_os = "windows")]
+use self::windows::*;
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "windows"
+)))]
+mod unsupported;
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "windows"
+)))]
+use self::unsupported::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Privileged network operation
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Capability {
+    /// Open raw IP sockets, e.g. for ICMP or custom protocols
+    RawSockets,
+    /// Add and delete routes, addresses and neighbour entries
+    RouteModification,
+    /// Send state-changing netlink requests (Linux only)
+    NetlinkAdmin,
+    /// Send and capture link-layer frames, e.g. ARP packets
+    ArpInjection,
+}
+
+impl Capability {
+    /// Capabilities that exist on the current platform
+    pub fn platform_capabilities() -> &'static [Capability] {
+        PLATFORM_CAPABILITIES
+    }
+    /// Actionable description of how to obtain this capability on the current platform
+    pub fn hint(&self) -> &'static str {
+        hint(*self)
+    }
+}
+
+/// Privileges of the current process, as returned by [`check`]
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Privileges {
+    /// Whether the process runs as root, or with an elevated token on Windows
+    pub elevated: bool,
+    /// Capabilities available to the process
+    pub available: Vec<Capability>,
+}
+
+impl Privileges {
+    /// Check if `capability` is available
+    pub fn has(&self, capability: Capability) -> bool {
+        self.available.contains(&capability)
+    }
+    /// Capabilities of the current platform that are not available
+    pub fn missing(&self) -> Vec<Capability> {
+        PLATFORM_CAPABILITIES
+            .iter()
+            .copied()
+            .filter(|capability| !self.has(*capability))
+            .collect()
+    }
+}
+
+/// Report the network privileges of the current process
+pub fn check() -> Privileges {
+    let elevated = is_elevated();
+    Privileges {
+        elevated,
+        available: available_capabilities(elevated),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let privileges = check();
+        println!("{:?}", privileges);
+        assert!(privileges
+            .available
+            .iter()
+            .all(|capability| PLATFORM_CAPABILITIES.contains(capability)));
+        for capability in privileges.missing() {
+            assert!(!capability.hint().is_empty());
+        }
+    }
+}
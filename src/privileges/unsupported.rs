@@ -0,0 +1,15 @@
+use super::Capability;
+
+pub const PLATFORM_CAPABILITIES: &[Capability] = &[];
+
+pub fn is_elevated() -> bool {
+    false
+}
+
+pub fn available_capabilities(_elevated: bool) -> Vec<Capability> {
+    Vec::new()
+}
+
+pub fn hint(_capability: Capability) -> &'static str {
+    "not supported on this platform"
+}
@@ -0,0 +1,57 @@
+use super::Capability;
+use std::ffi::c_void;
+use std::mem;
+use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+use windows_sys::Win32::Security::{
+    GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
+};
+use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+pub const PLATFORM_CAPABILITIES: &[Capability] = &[
+    Capability::RawSockets,
+    Capability::RouteModification,
+    Capability::ArpInjection,
+];
+
+/// Check the elevation of the process token. Services running as LocalSystem
+/// are elevated; LocalService and NetworkService are not.
+pub fn is_elevated() -> bool {
+    let mut token: HANDLE = 0;
+    if unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) } == 0 {
+        return false;
+    }
+    let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+    let mut len = 0u32;
+    let ret = unsafe {
+        GetTokenInformation(
+            token,
+            TokenElevation,
+            &mut elevation as *mut TOKEN_ELEVATION as *mut c_void,
+            mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut len,
+        )
+    };
+    unsafe { CloseHandle(token) };
+    ret != 0 && elevation.TokenIsElevated != 0
+}
+
+pub fn available_capabilities(elevated: bool) -> Vec<Capability> {
+    if elevated {
+        PLATFORM_CAPABILITIES.to_vec()
+    } else {
+        Vec::new()
+    }
+}
+
+pub fn hint(capability: Capability) -> &'static str {
+    match capability {
+        Capability::RawSockets => {
+            "run from an elevated prompt, or as a service under the LocalSystem account"
+        }
+        Capability::RouteModification | Capability::ArpInjection => {
+            "run from an elevated prompt, or as a service under the LocalSystem account; \
+             members of Network Configuration Operators may also change the configuration"
+        }
+        Capability::NetlinkAdmin => "netlink is only available on Linux",
+    }
+}
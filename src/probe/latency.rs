@@ -0,0 +1,223 @@
+use super::{bind_to, build_ping, echo_reply_seq, open_socket, payload};
+use crate::interface::Interface;
+use crate::sys;
+use socket2::{Domain, SockAddr, Socket};
+use std::io;
+use std::mem::MaybeUninit;
+use std::net::{IpAddr, SocketAddr, SocketAddrV6};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How long to wait for the replies of each round by default
+const ROUND_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Round-trip times measured to one target
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Latency {
+    /// Address that was pinged
+    pub target: IpAddr,
+    /// Number of echo requests sent
+    pub sent: u32,
+    /// Number of echo replies received in time
+    pub received: u32,
+    /// Shortest round-trip time. `None` if no reply was received.
+    pub min: Option<Duration>,
+    /// Mean round-trip time. `None` if no reply was received.
+    pub avg: Option<Duration>,
+    /// Longest round-trip time. `None` if no reply was received.
+    pub max: Option<Duration>,
+}
+
+impl Latency {
+    fn from_samples(target: IpAddr, sent: u32, samples: &[Duration]) -> Latency {
+        let received = samples.len() as u32;
+        Latency {
+            target,
+            sent,
+            received,
+            min: samples.iter().min().copied(),
+            avg: (received > 0).then(|| samples.iter().sum::<Duration>() / received),
+            max: samples.iter().max().copied(),
+        }
+    }
+    /// Returns the fraction of echo requests that got no reply, from 0.0 to 1.0
+    pub fn loss(&self) -> f64 {
+        if self.sent == 0 {
+            return 0.0;
+        }
+        1.0 - self.received as f64 / self.sent as f64
+    }
+}
+
+/// Ping each of `targets` `count` times and return the round-trip times and
+/// loss, in the order of `targets`.
+///
+/// The targets are pinged together in rounds: each round sends one echo
+/// request to every target and waits up to a second for the replies, so the
+/// whole measurement takes at most `count` seconds however many targets there are.
+/// With `via`, the pings are sent through that interface the same way as the
+/// probes of [`path_mtu`](super::path_mtu), so the uplinks of a multi-WAN
+/// router can be compared by measuring the same targets through each of them.
+/// Link-local IPv6 targets are reached on `via`.
+///
+/// The sockets need the same privileges as [`broadcast_ping`](super::broadcast_ping).
+pub fn latency(
+    targets: &[IpAddr],
+    via: Option<&Interface>,
+    count: u32,
+) -> io::Result<Vec<Latency>> {
+    latency_with_timeout(targets, via, count, ROUND_TIMEOUT)
+}
+
+/// Ping each of `targets` like [`latency`], waiting up to `timeout` for the
+/// replies of each round.
+pub fn latency_with_timeout(
+    targets: &[IpAddr],
+    via: Option<&Interface>,
+    count: u32,
+    timeout: Duration,
+) -> io::Result<Vec<Latency>> {
+    let scope_id = via.map(|interface| interface.index).unwrap_or(0);
+    let dests: Vec<SockAddr> = targets
+        .iter()
+        .map(|target| match target {
+            IpAddr::V6(addr) if crate::zone::is_link_local(addr) => {
+                SockAddr::from(SocketAddrV6::new(*addr, 0, 0, scope_id))
+            }
+            _ => SockAddr::from(SocketAddr::new(*target, 0)),
+        })
+        .collect();
+    let open = |v6: bool| -> io::Result<Option<Socket>> {
+        if !targets.iter().any(|target| target.is_ipv6() == v6) {
+            return Ok(None);
+        }
+        let socket = open_socket(if v6 { Domain::IPV6 } else { Domain::IPV4 })?;
+        // Replies are waited for on both sockets at once
+        socket.set_nonblocking(true)?;
+        if let Some(interface) = via {
+            bind_to(&socket, interface, v6);
+        }
+        Ok(Some(socket))
+    };
+    let sockets = [open(false)?, open(true)?];
+    let socket_of = |target: &IpAddr| sockets[target.is_ipv6() as usize].as_ref();
+
+    let payload = payload();
+    let id = std::process::id() as u16;
+    let mut samples: Vec<Vec<Duration>> = vec![Vec::new(); targets.len()];
+    let mut buf = [MaybeUninit::<u8>::uninit(); 1500];
+    for round in 0..count {
+        let seq = round as u16;
+        let mut sent_at: Vec<Option<Instant>> = vec![None; targets.len()];
+        for (i, (target, dest)) in targets.iter().zip(&dests).enumerate() {
+            let Some(socket) = socket_of(target) else {
+                continue;
+            };
+            let msg = build_ping(target.is_ipv6(), id, seq, &payload);
+            match socket.send_to(&msg, dest) {
+                Ok(_) => sent_at[i] = Some(Instant::now()),
+                // Unreachable targets count as lost
+                Err(e) => debug!("ping to {} failed: {}", target, e),
+            }
+        }
+        let deadline = Instant::now() + timeout;
+        let open: Vec<&Socket> = sockets.iter().flatten().collect();
+        while sent_at.iter().any(Option::is_some) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            match sys::wait_readable(&open, remaining) {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+            for socket in &open {
+                let (len, src) = match socket.recv_from(&mut buf) {
+                    Ok(received) => received,
+                    Err(e)
+                        if matches!(
+                            e.kind(),
+                            io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted
+                        ) =>
+                    {
+                        continue
+                    }
+                    Err(e) => return Err(e),
+                };
+                let received_at = Instant::now();
+                // SAFETY: recv_from initialized the first `len` bytes
+                let msg = unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const u8, len) };
+                if echo_reply_seq(msg, &payload) != Some(seq) {
+                    continue;
+                }
+                let Some(src) = src.as_socket().map(|src| src.ip()) else {
+                    continue;
+                };
+                if let Some(i) = targets.iter().position(|target| *target == src) {
+                    if let Some(sent) = sent_at[i].take() {
+                        samples[i].push(received_at - sent);
+                    }
+                }
+            }
+        }
+    }
+    Ok(targets
+        .iter()
+        .zip(&samples)
+        .map(|(target, samples)| Latency::from_samples(*target, count, samples))
+        .collect())
+}
+
+/// Ping the gateways of `interface` through it `count` times, like [`latency`]
+pub fn gateway_latency(interface: &Interface, count: u32) -> io::Result<Vec<Latency>> {
+    let mut targets: Vec<IpAddr> = Vec::new();
+    for gateway in &interface.gateways {
+        let v4 = gateway.ipv4.iter().map(|ip| IpAddr::V4(*ip));
+        let v6 = gateway.ipv6.iter().map(|ip| IpAddr::V6(*ip));
+        for ip in v4.chain(v6) {
+            if !targets.contains(&ip) {
+                targets.push(ip);
+            }
+        }
+    }
+    latency(&targets, Some(interface), count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_samples() {
+        let target = IpAddr::from([192, 168, 1, 1]);
+        let ms = Duration::from_millis;
+        let latency = Latency::from_samples(target, 4, &[ms(3), ms(1), ms(2)]);
+        assert_eq!(latency.received, 3);
+        assert_eq!(latency.min, Some(ms(1)));
+        assert_eq!(latency.avg, Some(ms(2)));
+        assert_eq!(latency.max, Some(ms(3)));
+        assert_eq!(latency.loss(), 0.25);
+
+        let lost = Latency::from_samples(target, 2, &[]);
+        assert_eq!((lost.min, lost.avg, lost.max), (None, None, None));
+        assert_eq!(lost.loss(), 1.0);
+        assert_eq!(Latency::from_samples(target, 0, &[]).loss(), 0.0);
+    }
+
+    #[test]
+    fn test_latency() {
+        let targets = [
+            IpAddr::from([127, 0, 0, 1]),
+            IpAddr::from([0u16, 0, 0, 0, 0, 0, 0, 1]),
+        ];
+        println!(
+            "{:?}",
+            latency_with_timeout(&targets, None, 2, Duration::from_millis(200))
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Quick presence checks on the local network.
+//!
+//! [`broadcast_ping`] sends one ICMP echo request to the broadcast address of
+//! each IPv4 subnet of an interface and counts the hosts that answer. It is a
+//! fast sanity check before a full [`scan`](crate::scan), and needs no
+//! privileges where the platform offers unprivileged ICMP sockets (macOS, and
+//! Linux when `net.ipv4.ping_group_range` includes the user). Elsewhere it
+//! falls back to a raw socket, which needs root or `CAP_NET_RAW`.
+//!
+//! Linux and Windows hosts ignore broadcast pings by default, so the count is
+//! a lower bound: routers, printers and most embedded devices answer, but no
+//! answer does not mean the segment is empty.
+//!
+//! [`path_mtu`] measures the largest packet that reaches a destination without
+//! fragmentation, for tuning VPN tunnels and QUIC datagram sizes. [`latency`]
+//! reports round-trip times and loss to a set of targets, optionally through a
+//! given interface to compare the uplinks of a multi-WAN router.
+
+mod latency;
+pub use self::latency::*;
+
+mod mtu;
+pub use self::mtu::*;
+
+use crate::interface::Interface;
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+use std::io;
+use std::mem::MaybeUninit;
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::time::{Duration, Instant};
+
+const ICMP_ECHO_REPLY: u8 = 0;
+const ICMP_ECHO_REQUEST: u8 = 8;
+const ICMPV6_ECHO_REQUEST: u8 = 128;
+const ICMPV6_ECHO_REPLY: u8 = 129;
+
+/// Payload of our echo requests, followed by the process id so that
+/// concurrent probes do not count each other's replies
+const PAYLOAD_TAG: &[u8; 8] = b"netdev\0\0";
+
+/// Internet checksum (RFC 1071)
+fn checksum(buf: &[u8]) -> u16 {
+    let mut sum: u32 = buf
+        .chunks(2)
+        .map(|chunk| u16::from_be_bytes([chunk[0], *chunk.get(1).unwrap_or(&0)]) as u32)
+        .sum();
+    while sum >> 16 != 0 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+fn payload() -> [u8; 12] {
+    let mut payload = [0u8; 12];
+    payload[..8].copy_from_slice(PAYLOAD_TAG);
+    payload[8..].copy_from_slice(&std::process::id().to_be_bytes());
+    payload
+}
+
+/// Build an echo request carrying `payload`
+fn build_echo_request(id: u16, seq: u16, payload: &[u8]) -> Vec<u8> {
+    let mut msg = vec![ICMP_ECHO_REQUEST, 0, 0, 0];
+    msg.extend_from_slice(&id.to_be_bytes());
+    msg.extend_from_slice(&seq.to_be_bytes());
+    msg.extend_from_slice(payload);
+    let sum = checksum(&msg);
+    msg[2..4].copy_from_slice(&sum.to_be_bytes());
+    msg
+}
+
+/// Build an ICMP or ICMPv6 echo request carrying `payload`. The kernel fills
+/// in the ICMPv6 checksum, which covers the IPv6 header.
+fn build_ping(v6: bool, id: u16, seq: u16, payload: &[u8]) -> Vec<u8> {
+    let mut msg = build_echo_request(id, seq, payload);
+    if v6 {
+        msg[0] = ICMPV6_ECHO_REQUEST;
+        msg[2..4].fill(0);
+    }
+    msg
+}
+
+/// Returns the sequence number of `buf` if it is an ICMP or ICMPv6 echo reply
+/// carrying `payload`. Raw sockets, and ICMP datagram sockets on macOS,
+/// deliver the IPv4 header as well, which is skipped.
+fn echo_reply_seq(buf: &[u8], payload: &[u8]) -> Option<u16> {
+    let msg = if buf.first().is_some_and(|b| b >> 4 == 4) {
+        let header_len = (buf[0] & 0x0f) as usize * 4;
+        buf.get(header_len..)?
+    } else {
+        buf
+    };
+    // The identifier is rewritten by Linux ICMP datagram sockets, so the
+    // payload is what tells our replies apart
+    let is_reply = msg.len() >= 8 + payload.len()
+        && matches!(msg[0], ICMP_ECHO_REPLY | ICMPV6_ECHO_REPLY)
+        && msg[1] == 0
+        && &msg[8..8 + payload.len()] == payload;
+    is_reply.then(|| u16::from_be_bytes([msg[6], msg[7]]))
+}
+
+/// Check if `buf` is an echo reply carrying `payload`
+fn is_echo_reply(buf: &[u8], payload: &[u8]) -> bool {
+    echo_reply_seq(buf, payload).is_some()
+}
+
+/// Open an unprivileged ICMP or ICMPv6 socket if the platform allows it, a raw one otherwise
+fn open_socket(domain: Domain) -> io::Result<Socket> {
+    let protocol = if domain == Domain::IPV6 {
+        Protocol::ICMPV6
+    } else {
+        Protocol::ICMPV4
+    };
+    match Socket::new(domain, Type::DGRAM, Some(protocol)) {
+        Ok(socket) => Ok(socket),
+        Err(_) => Socket::new(domain, Type::RAW, Some(protocol)),
+    }
+}
+
+/// Send the probes through `interface`. Failing to is not fatal, the route
+/// then decides, so it is only logged.
+fn bind_to(socket: &Socket, interface: &Interface, v6: bool) {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let _ = v6;
+        if let Err(e) = socket.bind_device(Some(interface.name.as_bytes())) {
+            // Needs CAP_NET_RAW before Linux 5.7
+            debug!("SO_BINDTODEVICE({}) failed: {}", interface.name, e);
+        }
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    {
+        use std::net::{IpAddr, SocketAddr};
+        // Link-local addresses would need a scope, global ones are enough here
+        let source = if v6 {
+            interface
+                .ipv6
+                .iter()
+                .map(|net| net.addr)
+                .find(|addr| !crate::zone::is_link_local(addr))
+                .map(IpAddr::V6)
+        } else {
+            interface.ipv4.first().map(|net| IpAddr::V4(net.addr))
+        };
+        let Some(source) = source else {
+            debug!("{} has no address to send probes from", interface.name);
+            return;
+        };
+        if let Err(e) = socket.bind(&SockAddr::from(SocketAddr::new(source, 0))) {
+            debug!("cannot bind probes to {}: {}", source, e);
+        }
+    }
+}
+
+/// Ping the broadcast address of each IPv4 subnet of `interface` and return
+/// the number of distinct hosts that answered within `timeout`.
+///
+/// Point-to-point subnets (/31 and /32) have no broadcast address and are skipped.
+pub fn broadcast_ping(interface: &Interface, timeout: Duration) -> io::Result<usize> {
+    let nets: Vec<_> = interface
+        .ipv4
+        .iter()
+        .filter(|net| net.prefix_len < 31 && !net.addr.is_unspecified())
+        .collect();
+    if nets.is_empty() {
+        return Ok(0);
+    }
+    let own: Vec<Ipv4Addr> = interface.ipv4.iter().map(|net| net.addr).collect();
+    let socket = open_socket(Domain::IPV4)?;
+    socket.set_broadcast(true)?;
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if let Err(e) = socket.bind_device(Some(interface.name.as_bytes())) {
+        // Needs CAP_NET_RAW before Linux 5.7, the route decides the interface then
+        debug!("SO_BINDTODEVICE({}) failed: {}", interface.name, e);
+    }
+    let payload = payload();
+    let id = std::process::id() as u16;
+    for (seq, net) in nets.iter().enumerate() {
+        let dst = SockAddr::from(SocketAddrV4::new(net.broadcast(), 0));
+        socket.send_to(&build_echo_request(id, seq as u16, &payload), &dst)?;
+    }
+
+    let deadline = Instant::now() + timeout;
+    let mut responders: Vec<Ipv4Addr> = Vec::new();
+    let mut buf = [MaybeUninit::<u8>::uninit(); 1500];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        socket.set_read_timeout(Some(remaining))?;
+        let (len, src) = match socket.recv_from(&mut buf) {
+            Ok(received) => received,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                break
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        // SAFETY: recv_from initialized the first `len` bytes
+        let msg = unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const u8, len) };
+        let Some(src) = src.as_socket_ipv4() else {
+            continue;
+        };
+        let ip = *src.ip();
+        if is_echo_reply(msg, &payload) && !own.contains(&ip) && !responders.contains(&ip) {
+            responders.push(ip);
+        }
+    }
+    Ok(responders.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum() {
+        let msg = build_echo_request(0x1234, 1, b"abcd");
+        assert_eq!(checksum(&msg), 0);
+        assert_eq!(checksum(&[0xff, 0xff]), 0);
+        assert_eq!(checksum(&[0x00, 0x01, 0xf2]), !0xf201);
+    }
+
+    #[test]
+    fn test_is_echo_reply() {
+        let payload = payload();
+        let mut reply = build_echo_request(0x1234, 0, &payload);
+        assert!(!is_echo_reply(&reply, &payload));
+        reply[0] = ICMP_ECHO_REPLY;
+        assert!(is_echo_reply(&reply, &payload));
+        assert!(!is_echo_reply(&reply, b"other payload"));
+
+        let mut packet = vec![0x45, 0, 0, 0, 0, 0, 0, 0, 64, 1, 0, 0];
+        packet.extend_from_slice(&[192, 168, 1, 1, 192, 168, 1, 20]);
+        packet.extend_from_slice(&reply);
+        assert!(is_echo_reply(&packet, &payload));
+        assert!(!is_echo_reply(&packet[..24], &payload));
+        assert_eq!(echo_reply_seq(&packet, &payload), Some(0));
+
+        let mut reply_v6 = vec![ICMPV6_ECHO_REPLY, 0, 0, 0, 0x12, 0x34, 0, 7];
+        reply_v6.extend_from_slice(&payload);
+        assert_eq!(echo_reply_seq(&reply_v6, &payload), Some(7));
+        reply_v6[0] = ICMPV6_ECHO_REQUEST;
+        assert_eq!(echo_reply_seq(&reply_v6, &payload), None);
+    }
+
+    #[test]
+    #[ignore = "pings the broadcast address of the local network"]
+    fn test_broadcast_ping() {
+        if let Ok(interface) = crate::get_default_interface() {
+            println!(
+                "{:?}",
+                broadcast_ping(&interface, Duration::from_millis(200))
+            );
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use super::{bind_to, build_ping, echo_reply_seq, open_socket, payload};
+use crate::interface::Interface;
+use crate::sys;
+use socket2::{Domain, SockAddr, Socket};
+use std::io;
+use std::mem::MaybeUninit;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+/// Smallest MTU an IPv4 link may have (RFC 791)
+const MIN_MTU_V4: u32 = 68;
+/// Smallest MTU an IPv6 link may have (RFC 8200)
+const MIN_MTU_V6: u32 = 1280;
+/// Upper bound when the MTU of the outgoing interface is unknown
+const DEFAULT_MTU: u32 = 1500;
+/// Largest IP packet
+const MAX_MTU: u32 = 65535;
+/// How long to wait for the reply to each probe by default
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+/// Probes sent for each size before concluding it does not fit
+const ATTEMPTS: usize = 2;
+
+/// Measure the path MTU to `dest`: the size of the largest IP packet that
+/// reaches it without fragmentation.
+///
+/// ICMP echo requests are sent with the Don't Fragment bit set (IPv6 never
+/// fragments in transit), and the size is narrowed down by binary search
+/// between the protocol minimum and the MTU of the outgoing interface, which
+/// is `via` when given and the one the route to `dest` uses otherwise. On Linux
+/// the probes are bound to `via`; elsewhere they are sent from one of its addresses.
+///
+/// Each size gets two probes with one second to answer, so a path that
+/// silently drops large packets takes a dozen seconds or more to measure. The
+/// result is only as good as the destination's willingness to answer pings:
+/// an error of kind `TimedOut` means even the smallest probe got no reply.
+///
+/// The socket needs the same privileges as [`broadcast_ping`](super::broadcast_ping).
+/// IPv4 probes are unsupported on NetBSD and OpenBSD, which cannot set the
+/// Don't Fragment bit per socket.
+pub fn path_mtu(dest: IpAddr, via: Option<&Interface>) -> io::Result<u32> {
+    path_mtu_with_timeout(dest, via, PROBE_TIMEOUT)
+}
+
+/// Measure the path MTU to `dest` like [`path_mtu`], waiting up to `timeout`
+/// for the reply to each probe.
+pub fn path_mtu_with_timeout(
+    dest: IpAddr,
+    via: Option<&Interface>,
+    timeout: Duration,
+) -> io::Result<u32> {
+    let egress = match via {
+        Some(interface) => Some(interface.clone()),
+        None => egress_interface(dest),
+    };
+    let upper = match egress.as_ref().map(link_mtu) {
+        Some(Ok(mtu)) => mtu.min(MAX_MTU),
+        Some(Err(e)) => {
+            debug!("cannot get the link MTU towards {}: {}", dest, e);
+            DEFAULT_MTU
+        }
+        None => DEFAULT_MTU,
+    };
+    let mut prober = Prober::new(dest, via, timeout)?;
+    let floor = if dest.is_ipv6() {
+        MIN_MTU_V6
+    } else {
+        MIN_MTU_V4
+    };
+    match search(floor, upper.max(floor), |mtu| prober.fits(mtu))? {
+        Some(mtu) => Ok(mtu),
+        None => Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("no echo reply from {}", dest),
+        )),
+    }
+}
+
+/// Find the largest size in `low..=high` that `fits`, assuming every smaller
+/// size fits too. Returns `None` if not even `low` fits.
+fn search(
+    mut low: u32,
+    mut high: u32,
+    mut fits: impl FnMut(u32) -> io::Result<bool>,
+) -> io::Result<Option<u32>> {
+    if !fits(low)? {
+        return Ok(None);
+    }
+    // Most paths carry the full link MTU, which settles it in one probe
+    if fits(high)? {
+        return Ok(Some(high));
+    }
+    // `low` fits and `high` does not
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if fits(mid)? {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Ok(Some(low))
+}
+
+/// Build an ICMP or ICMPv6 echo request that makes an IP packet of `mtu` bytes
+fn build_probe(v6: bool, id: u16, seq: u16, mtu: u32, payload: &[u8]) -> Vec<u8> {
+    let header_len = if v6 { 40 } else { 20 };
+    let mut body = payload.to_vec();
+    body.resize(
+        (mtu as usize)
+            .saturating_sub(header_len + 8)
+            .max(payload.len()),
+        0,
+    );
+    build_ping(v6, id, seq, &body)
+}
+
+/// Interface the route to `dest` goes through
+fn egress_interface(dest: IpAddr) -> Option<Interface> {
+    let decision = crate::route::lookup(dest)
+        .map_err(|e| debug!("route lookup for {} failed: {}", dest, e))
+        .ok()?;
+    crate::get_interfaces()
+        .into_iter()
+        .find(|interface| interface.index == decision.if_index)
+}
+
+struct Prober {
+    socket: Socket,
+    dest: SockAddr,
+    v6: bool,
+    id: u16,
+    seq: u16,
+    payload: [u8; 12],
+    timeout: Duration,
+}
+
+impl Prober {
+    fn new(dest: IpAddr, via: Option<&Interface>, timeout: Duration) -> io::Result<Prober> {
+        let v6 = dest.is_ipv6();
+        let socket = open_socket(if v6 { Domain::IPV6 } else { Domain::IPV4 })?;
+        set_dont_fragment(&socket, v6)?;
+        if let Some(interface) = via {
+            bind_to(&socket, interface, v6);
+        }
+        Ok(Prober {
+            socket,
+            dest: SockAddr::from(SocketAddr::new(dest, 0)),
+            v6,
+            id: std::process::id() as u16,
+            seq: 0,
+            payload: payload(),
+            timeout,
+        })
+    }
+
+    /// Check if a packet of `mtu` bytes reaches the destination
+    fn fits(&mut self, mtu: u32) -> io::Result<bool> {
+        for _ in 0..ATTEMPTS {
+            self.seq = self.seq.wrapping_add(1);
+            let msg = build_probe(self.v6, self.id, self.seq, mtu, &self.payload);
+            match self.socket.send_to(&msg, &self.dest) {
+                Ok(_) => {}
+                // Larger than the MTU of the outgoing interface
+                Err(e) if is_too_big(&e) => return Ok(false),
+                Err(e) => return Err(e),
+            }
+            if self.wait_reply()? {
+                trace!("{} bytes fit", mtu);
+                return Ok(true);
+            }
+        }
+        trace!("{} bytes do not fit", mtu);
+        Ok(false)
+    }
+
+    /// Wait for the reply to the last probe
+    fn wait_reply(&self) -> io::Result<bool> {
+        let deadline = Instant::now() + self.timeout;
+        let mut buf = [MaybeUninit::<u8>::uninit(); MAX_MTU as usize];
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(false);
+            }
+            self.socket.set_read_timeout(Some(remaining))?;
+            let (len, src) = match self.socket.recv_from(&mut buf) {
+                Ok(received) => received,
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    return Ok(false)
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            // SAFETY: recv_from initialized the first `len` bytes
+            let msg = unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const u8, len) };
+            let from_dest =
+                src.as_socket().map(|src| src.ip()) == self.dest.as_socket().map(|dest| dest.ip());
+            if from_dest && echo_reply_seq(msg, &self.payload) == Some(self.seq) {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+/// Set the Don't Fragment bit on outgoing IPv4 packets, and disable local
+/// fragmentation of IPv6 ones
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn set_dont_fragment(socket: &Socket, v6: bool) -> io::Result<()> {
+    // Unlike DO, PROBE ignores the path MTU the kernel may have cached for the
+    // destination, so sizes above it are still tried
+    if v6 {
+        sys::set_socket_option(
+            socket,
+            libc::IPPROTO_IPV6,
+            libc::IPV6_MTU_DISCOVER,
+            libc::IPV6_PMTUDISC_PROBE,
+        )
+    } else {
+        sys::set_socket_option(
+            socket,
+            libc::IPPROTO_IP,
+            libc::IP_MTU_DISCOVER,
+            libc::IP_PMTUDISC_PROBE,
+        )
+    }
+}
+
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "android"))))]
+fn set_dont_fragment(socket: &Socket, v6: bool) -> io::Result<()> {
+    if v6 {
+        return sys::set_socket_option(socket, libc::IPPROTO_IPV6, libc::IPV6_DONTFRAG, 1);
+    }
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+    return sys::set_socket_option(socket, libc::IPPROTO_IP, libc::IP_DONTFRAG, 1);
+    #[cfg(not(any(target_os = "macos", target_os = "ios", target_os = "freebsd")))]
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Setting the Don't Fragment bit is not supported on this platform",
+    ))
+}
+
+#[cfg(windows)]
+fn set_dont_fragment(socket: &Socket, v6: bool) -> io::Result<()> {
+    use windows_sys::Win32::Networking::WinSock::{
+        IPPROTO_IP, IPPROTO_IPV6, IPV6_DONTFRAG, IP_DONTFRAGMENT,
+    };
+    if v6 {
+        sys::set_socket_option(socket, IPPROTO_IPV6, IPV6_DONTFRAG, 1)
+    } else {
+        sys::set_socket_option(socket, IPPROTO_IP, IP_DONTFRAGMENT, 1)
+    }
+}
+
+#[cfg(unix)]
+fn is_too_big(e: &io::Error) -> bool {
+    e.raw_os_error() == Some(libc::EMSGSIZE)
+}
+
+#[cfg(windows)]
+fn is_too_big(e: &io::Error) -> bool {
+    e.raw_os_error() == Some(windows_sys::Win32::Networking::WinSock::WSAEMSGSIZE)
+}
+
+/// MTU of `interface`
+#[cfg(unix)]
+fn link_mtu(interface: &Interface) -> io::Result<u32> {
+    let mut req = sys::IfReqFlags::new(&interface.name)?;
+    sys::ifreq_ioctl(sys::SIOCGIFMTU, &mut req)?;
+    Ok(req.mtu())
+}
+
+/// MTU of `interface`
+#[cfg(windows)]
+fn link_mtu(interface: &Interface) -> io::Result<u32> {
+    use windows_sys::Win32::Foundation::NO_ERROR;
+    use windows_sys::Win32::NetworkManagement::IpHelper::{GetIfEntry2, MIB_IF_ROW2};
+    let mut row: MIB_IF_ROW2 = unsafe { std::mem::zeroed() };
+    row.InterfaceIndex = interface.index;
+    let ret = unsafe { GetIfEntry2(&mut row) };
+    if ret != NO_ERROR {
+        return Err(io::Error::from_raw_os_error(ret as i32));
+    }
+    Ok(row.Mtu)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::probe::{checksum, ICMPV6_ECHO_REPLY, ICMPV6_ECHO_REQUEST};
+
+    #[test]
+    fn test_search() {
+        for path in [68, 1280, 1420, 1499, 1500] {
+            let mut probes = 0;
+            let found = search(68, 1500, |mtu| {
+                probes += 1;
+                Ok(mtu <= path)
+            })
+            .unwrap();
+            assert_eq!(found, Some(path));
+            assert!(probes <= 13, "{} probes for {}", probes, path);
+        }
+        assert_eq!(search(1280, 1280, |_| Ok(true)).unwrap(), Some(1280));
+        assert_eq!(search(68, 1500, |_| Ok(false)).unwrap(), None);
+        let failing = search(68, 1500, |_| Err(io::Error::from(io::ErrorKind::Other)));
+        assert!(failing.is_err());
+    }
+
+    #[test]
+    fn test_build_probe() {
+        let payload = payload();
+        let msg = build_probe(false, 0x1234, 3, 1500, &payload);
+        assert_eq!(msg.len(), 1480);
+        assert_eq!(msg[0], 8);
+        assert_eq!(checksum(&msg), 0);
+        assert_eq!(&msg[8..20], &payload);
+
+        let msg = build_probe(true, 0x1234, 3, 1280, &payload);
+        assert_eq!(msg.len(), 1240);
+        assert_eq!(msg[..4], [ICMPV6_ECHO_REQUEST, 0, 0, 0]);
+        let mut reply = msg.clone();
+        reply[0] = ICMPV6_ECHO_REPLY;
+        assert_eq!(echo_reply_seq(&reply, &payload), Some(3));
+
+        // Never shorter than the payload
+        assert_eq!(build_probe(false, 0, 0, 20, &payload).len(), 20);
+    }
+
+    #[test]
+    fn test_path_mtu() {
+        let dest = IpAddr::from([127, 0, 0, 1]);
+        println!(
+            "{:?}",
+            path_mtu_with_timeout(dest, None, Duration::from_millis(200))
+        );
+    }
+}
@@ -0,0 +1,310 @@
+//! IPv6 router discovery.
+//!
+//! [`solicit`] sends a Router Solicitation out of an interface and collects the
+//! Router Advertisements that answer it (RFC 4861), including the prefixes,
+//! MTU and DNS servers (RFC 8106) they carry. As RFC 4861 requires, only
+//! advertisements from link-local addresses that arrive with a hop limit of 255
+//! are accepted. It opens a raw ICMPv6 socket, which needs root or
+//! `CAP_NET_RAW`, and is not supported on Windows.
+
+use crate::interface::Interface;
+use crate::ip::Ipv6Net;
+use crate::mac::MacAddr;
+use crate::sys;
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+use std::io;
+use std::mem::MaybeUninit;
+use std::net::{Ipv6Addr, SocketAddrV6};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+const ND_ROUTER_SOLICIT: u8 = 133;
+const ND_ROUTER_ADVERT: u8 = 134;
+
+const ND_OPT_SOURCE_LINKADDR: u8 = 1;
+const ND_OPT_PREFIX_INFORMATION: u8 = 3;
+const ND_OPT_MTU: u8 = 5;
+const ND_OPT_RDNSS: u8 = 25;
+
+const ND_RA_FLAG_MANAGED: u8 = 0x80;
+const ND_RA_FLAG_OTHER: u8 = 0x40;
+const ND_OPT_PI_FLAG_ONLINK: u8 = 0x80;
+const ND_OPT_PI_FLAG_AUTO: u8 = 0x40;
+
+/// Length of the fixed part of a Router Advertisement
+const RA_HEADER_LEN: usize = 16;
+/// Neighbor Discovery messages are only accepted with the maximum hop limit
+const ND_HOP_LIMIT: u32 = 255;
+
+/// All-routers link-local multicast address
+pub const ALL_ROUTERS: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 2);
+
+/// Prefix Information option of a Router Advertisement.
+///
+/// Lifetimes of `u32::MAX` seconds are infinite.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PrefixInfo {
+    /// Advertised prefix
+    pub prefix: Ipv6Net,
+    /// Addresses within the prefix are on the link
+    pub on_link: bool,
+    /// Hosts may configure addresses within the prefix with SLAAC
+    pub autonomous: bool,
+    /// How long the prefix stays valid
+    pub valid_lifetime: Duration,
+    /// How long addresses configured from the prefix stay preferred
+    pub preferred_lifetime: Duration,
+}
+
+/// Router Advertisement received from a router
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RouterAdvertisement {
+    /// Link-local address of the router
+    pub router: Ipv6Addr,
+    /// Hop limit hosts should use, 0 if unspecified
+    pub hop_limit: u8,
+    /// Addresses are available through DHCPv6 (M flag)
+    pub managed: bool,
+    /// Other configuration is available through DHCPv6 (O flag)
+    pub other_config: bool,
+    /// How long the router can be used as a default router, zero if it is not one
+    pub router_lifetime: Duration,
+    /// How long neighbors are considered reachable after a confirmation, zero if unspecified
+    pub reachable_time: Duration,
+    /// Time between retransmitted Neighbor Solicitations, zero if unspecified
+    pub retrans_timer: Duration,
+    /// Link-layer address of the router
+    pub source_mac: Option<MacAddr>,
+    /// Link MTU
+    pub mtu: Option<u32>,
+    /// Advertised prefixes
+    pub prefixes: Vec<PrefixInfo>,
+    /// Recursive DNS servers
+    pub rdnss: Vec<Ipv6Addr>,
+}
+
+fn read_u16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([buf[offset], buf[offset + 1]])
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([
+        buf[offset],
+        buf[offset + 1],
+        buf[offset + 2],
+        buf[offset + 3],
+    ])
+}
+
+fn read_ipv6(buf: &[u8], offset: usize) -> Ipv6Addr {
+    let mut octets = [0u8; 16];
+    octets.copy_from_slice(&buf[offset..offset + 16]);
+    Ipv6Addr::from(octets)
+}
+
+/// Build a Router Solicitation, with the Source Link-Layer Address option if
+/// `mac_addr` is known. The kernel fills in the checksum.
+fn build_solicit(mac_addr: Option<MacAddr>) -> Vec<u8> {
+    let mut msg = vec![ND_ROUTER_SOLICIT, 0, 0, 0, 0, 0, 0, 0];
+    if let Some(mac_addr) = mac_addr {
+        msg.extend_from_slice(&[ND_OPT_SOURCE_LINKADDR, 1]);
+        msg.extend_from_slice(&mac_addr.octets());
+    }
+    msg
+}
+
+/// Parse an ICMPv6 message from `router`, returning `None` if it is not a valid
+/// Router Advertisement
+pub fn parse_router_advert(buf: &[u8], router: Ipv6Addr) -> Option<RouterAdvertisement> {
+    if buf.len() < RA_HEADER_LEN || buf[0] != ND_ROUTER_ADVERT || buf[1] != 0 {
+        return None;
+    }
+    let mut ra = RouterAdvertisement {
+        router,
+        hop_limit: buf[4],
+        managed: buf[5] & ND_RA_FLAG_MANAGED != 0,
+        other_config: buf[5] & ND_RA_FLAG_OTHER != 0,
+        router_lifetime: Duration::from_secs(read_u16(buf, 6) as u64),
+        reachable_time: Duration::from_millis(read_u32(buf, 8) as u64),
+        retrans_timer: Duration::from_millis(read_u32(buf, 12) as u64),
+        source_mac: None,
+        mtu: None,
+        prefixes: Vec::new(),
+        rdnss: Vec::new(),
+    };
+    let mut options = &buf[RA_HEADER_LEN..];
+    while options.len() >= 2 {
+        // Option lengths are in units of 8 octets, and zero is invalid
+        let len = options[1] as usize * 8;
+        if len == 0 || len > options.len() {
+            return None;
+        }
+        let (option, rest) = options.split_at(len);
+        options = rest;
+        match option[0] {
+            ND_OPT_SOURCE_LINKADDR if len >= 8 => {
+                let mut octets = [0u8; 6];
+                octets.copy_from_slice(&option[2..8]);
+                ra.source_mac = Some(MacAddr::from_octets(octets));
+            }
+            ND_OPT_PREFIX_INFORMATION if len == 32 => {
+                let flags = option[3];
+                ra.prefixes.push(PrefixInfo {
+                    prefix: Ipv6Net::new(read_ipv6(option, 16), option[2].min(128)),
+                    on_link: flags & ND_OPT_PI_FLAG_ONLINK != 0,
+                    autonomous: flags & ND_OPT_PI_FLAG_AUTO != 0,
+                    valid_lifetime: Duration::from_secs(read_u32(option, 4) as u64),
+                    preferred_lifetime: Duration::from_secs(read_u32(option, 8) as u64),
+                });
+            }
+            ND_OPT_MTU if len == 8 => ra.mtu = Some(read_u32(option, 4)),
+            ND_OPT_RDNSS if len >= 24 => {
+                // Skips the lifetime, then one address per 16 octets
+                ra.rdnss
+                    .extend((8..len - 15).step_by(16).map(|i| read_ipv6(option, i)));
+            }
+            _ => {}
+        }
+    }
+    Some(ra)
+}
+
+/// Check the source and hop limit of a received Router Advertisement.
+///
+/// RFC 4861 only accepts advertisements from a link-local address with a hop
+/// limit of 255, which no router would have forwarded.
+fn is_valid_source(src: &Ipv6Addr, hop_limit: Option<u8>) -> bool {
+    hop_limit.map(u32::from) == Some(ND_HOP_LIMIT) && crate::zone::is_link_local(src)
+}
+
+/// Send a Router Solicitation out of `interface` and collect the Router
+/// Advertisements received until `timeout` elapses, one per router.
+///
+/// Routers answer solicitations within a few seconds, and unsolicited
+/// advertisements received in the meantime are included too.
+pub fn solicit(interface: &Interface, timeout: Duration) -> io::Result<Vec<RouterAdvertisement>> {
+    let socket = Socket::new(Domain::IPV6, Type::RAW, Some(Protocol::ICMPV6))?;
+    socket.set_multicast_if_v6(interface.index)?;
+    socket.set_multicast_hops_v6(ND_HOP_LIMIT)?;
+    socket.set_unicast_hops_v6(ND_HOP_LIMIT)?;
+    sys::set_recv_hop_limit(&socket)?;
+    let dst = SocketAddrV6::new(ALL_ROUTERS, 0, 0, interface.index);
+    socket.send_to(&build_solicit(interface.mac_addr), &SockAddr::from(dst))?;
+
+    let deadline = Instant::now() + timeout;
+    let mut ras: Vec<RouterAdvertisement> = Vec::new();
+    let mut buf = [MaybeUninit::<u8>::uninit(); 1500];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        socket.set_read_timeout(Some(remaining))?;
+        let (len, src, hop_limit) = match sys::recv_with_hop_limit(&socket, &mut buf) {
+            Ok(received) => received,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                break
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        // SAFETY: recvmsg initialized the first `len` bytes
+        let msg = unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const u8, len) };
+        // Advertisements from other links carry their own scope
+        if src.scope_id() != 0 && src.scope_id() != interface.index {
+            continue;
+        }
+        if !is_valid_source(src.ip(), hop_limit) {
+            continue;
+        }
+        if let Some(ra) = parse_router_advert(msg, *src.ip()) {
+            // Keeps the latest advertisement of each router
+            ras.retain(|other| other.router != ra.router);
+            ras.push(ra);
+        }
+    }
+    Ok(ras)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_solicit() {
+        assert_eq!(build_solicit(None), vec![133, 0, 0, 0, 0, 0, 0, 0]);
+        let msg = build_solicit(Some(MacAddr::new(0x02, 0, 0, 0, 0, 1)));
+        assert_eq!(&msg[8..], &[1, 1, 0x02, 0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_parse_router_advert() {
+        let router: Ipv6Addr = "fe80::1".parse().unwrap();
+        let prefix: Ipv6Addr = "2001:db8:1::".parse().unwrap();
+        let dns: Ipv6Addr = "2001:db8::53".parse().unwrap();
+        let mut msg = vec![
+            134, 0, 0, 0, 64, 0x40, 0x07, 0x08, 0, 0, 0x75, 0x30, 0, 0, 0x03, 0xe8,
+        ];
+        msg.extend_from_slice(&[1, 1, 0x02, 0, 0, 0, 0, 1]);
+        msg.extend_from_slice(&[5, 1, 0, 0, 0, 0, 0x05, 0xdc]);
+        msg.extend_from_slice(&[
+            3, 4, 64, 0xc0, 0, 0x27, 0x8d, 0, 0, 0, 0x0e, 0x10, 0, 0, 0, 0,
+        ]);
+        msg.extend_from_slice(&prefix.octets());
+        msg.extend_from_slice(&[25, 3, 0, 0, 0, 0, 0x0e, 0x10]);
+        msg.extend_from_slice(&dns.octets());
+
+        let ra = parse_router_advert(&msg, router).unwrap();
+        assert_eq!(ra.router, router);
+        assert_eq!(ra.hop_limit, 64);
+        assert!(!ra.managed && ra.other_config);
+        assert_eq!(ra.router_lifetime, Duration::from_secs(1800));
+        assert_eq!(ra.reachable_time, Duration::from_secs(30));
+        assert_eq!(ra.retrans_timer, Duration::from_secs(1));
+        assert_eq!(ra.source_mac, Some(MacAddr::new(0x02, 0, 0, 0, 0, 1)));
+        assert_eq!(ra.mtu, Some(1500));
+        assert_eq!(
+            ra.prefixes,
+            vec![PrefixInfo {
+                prefix: Ipv6Net::new(prefix, 64),
+                on_link: true,
+                autonomous: true,
+                valid_lifetime: Duration::from_secs(2_592_000),
+                preferred_lifetime: Duration::from_secs(3600),
+            }]
+        );
+        assert_eq!(ra.rdnss, vec![dns]);
+
+        // Truncated option
+        assert!(parse_router_advert(&msg[..msg.len() - 1], router).is_none());
+        // Router Solicitation
+        assert!(parse_router_advert(&build_solicit(None), router).is_none());
+    }
+
+    #[test]
+    fn test_is_valid_source() {
+        let router: Ipv6Addr = "fe80::1".parse().unwrap();
+        assert!(is_valid_source(&router, Some(255)));
+        // Forwarded by a router on the way
+        assert!(!is_valid_source(&router, Some(254)));
+        assert!(!is_valid_source(&router, None));
+        assert!(!is_valid_source(&"2001:db8::1".parse().unwrap(), Some(255)));
+    }
+
+    #[test]
+    #[ignore = "sends router solicitations on the local network"]
+    fn test_solicit() {
+        if let Ok(interface) = crate::get_default_interface() {
+            println!("{:?}", solicit(&interface, Duration::from_secs(1)));
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use crate::interface::Interface;
+use crate::sys;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read};
+use std::mem;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd};
+use std::time::Duration;
+
+// <net/bpf.h> ioctls, encoded as in <sys/ioccom.h>
+const IOC_OUT: u64 = 0x40000000;
+const IOC_IN: u64 = 0x80000000;
+
+const fn ioc(dir: u64, num: u64, len: usize) -> u64 {
+    dir | ((len as u64 & 0x1fff) << 16) | ((b'B' as u64) << 8) | num
+}
+
+#[cfg(target_os = "netbsd")]
+const IFREQ_LEN: usize = 144;
+#[cfg(not(target_os = "netbsd"))]
+const IFREQ_LEN: usize = 32;
+
+const BIOCGBLEN: u64 = ioc(IOC_OUT, 102, mem::size_of::<u32>());
+const BIOCSETF: u64 = ioc(IOC_IN, 103, mem::size_of::<BpfProgram>());
+const BIOCPROMISC: u64 = ioc(0x20000000, 105, 0);
+const BIOCSETIF: u64 = ioc(IOC_IN, 108, IFREQ_LEN);
+const BIOCIMMEDIATE: u64 = ioc(IOC_IN, 112, mem::size_of::<u32>());
+
+/// Records in a read buffer start on `BPF_ALIGNMENT` boundaries
+#[cfg(any(target_os = "macos", target_os = "openbsd"))]
+const BPF_ALIGNMENT: usize = mem::size_of::<u32>();
+#[cfg(any(target_os = "freebsd", target_os = "netbsd"))]
+const BPF_ALIGNMENT: usize = mem::size_of::<libc::c_long>();
+
+#[cfg(any(target_os = "macos", target_os = "openbsd"))]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct BpfTimeval {
+    tv_sec: u32,
+    tv_usec: u32,
+}
+#[cfg(target_os = "freebsd")]
+type BpfTimeval = libc::timeval;
+#[cfg(target_os = "netbsd")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct BpfTimeval {
+    tv_sec: libc::c_long,
+    tv_usec: libc::c_long,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct BpfHdr {
+    bh_tstamp: BpfTimeval,
+    bh_caplen: u32,
+    bh_datalen: u32,
+    bh_hdrlen: u16,
+}
+
+#[repr(C)]
+struct BpfProgram {
+    bf_len: libc::c_uint,
+    bf_insns: *const BpfInsn,
+}
+
+/// A single classic BPF instruction (`struct bpf_insn`)
+///
+/// Programs can be generated with `tcpdump -dd <expression>`.
+#[repr(C)]
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct BpfInsn {
+    pub code: u16,
+    pub jt: u8,
+    pub jf: u8,
+    pub k: u32,
+}
+
+impl BpfInsn {
+    /// Construct an instruction from its four fields
+    pub const fn new(code: u16, jt: u8, jf: u8, k: u32) -> BpfInsn {
+        BpfInsn { code, jt, jf, k }
+    }
+}
+
+/// A captured packet inside a buffer read from a [`Bpf`] device
+#[derive(Clone, Copy, Debug)]
+pub struct BpfPacket<'a> {
+    /// Capture time, relative to the Unix epoch
+    pub timestamp: Duration,
+    /// Length of the packet on the wire; `data` may be shorter
+    pub len: usize,
+    /// Captured bytes, starting with the link-layer header
+    pub data: &'a [u8],
+}
+
+/// Iterator over the packets in a buffer read from a [`Bpf`] device
+pub struct BpfPackets<'a> {
+    buf: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Iterator for BpfPackets<'a> {
+    type Item = BpfPacket<'a>;
+
+    fn next(&mut self) -> Option<BpfPacket<'a>> {
+        let rest = self.buf.get(self.offset..)?;
+        // The kernel's header length excludes the struct's tail padding.
+        let min_hdrlen = mem::offset_of!(BpfHdr, bh_hdrlen) + mem::size_of::<u16>();
+        if rest.len() < min_hdrlen {
+            return None;
+        }
+        let mut hdr: BpfHdr = unsafe { mem::zeroed() };
+        let len = rest.len().min(mem::size_of::<BpfHdr>());
+        unsafe {
+            std::ptr::copy_nonoverlapping(rest.as_ptr(), &mut hdr as *mut BpfHdr as *mut u8, len)
+        };
+        let start = hdr.bh_hdrlen as usize;
+        let end = start + hdr.bh_caplen as usize;
+        // A record that does not advance the offset would be returned forever
+        if start < min_hdrlen || end == 0 {
+            return None;
+        }
+        let data = rest.get(start..end)?;
+        self.offset += end.next_multiple_of(BPF_ALIGNMENT);
+        Some(BpfPacket {
+            timestamp: Duration::new(hdr.bh_tstamp.tv_sec as u64, 0)
+                + Duration::from_micros(hdr.bh_tstamp.tv_usec as u64),
+            len: hdr.bh_datalen as usize,
+            data,
+        })
+    }
+}
+
+/// A BPF device attached to a network interface
+///
+/// Reads return whole buffers of packets, which can be split with
+/// [`Bpf::packets`]. The buffer passed to `read` must be at least
+/// [`Bpf::buffer_len`] bytes long. Opening a device usually requires root.
+#[derive(Debug)]
+pub struct Bpf {
+    file: File,
+    buffer_len: usize,
+}
+
+impl Bpf {
+    /// Open the first free `/dev/bpf*` device and attach it to `interface`
+    pub fn open(interface: &Interface) -> io::Result<Bpf> {
+        let file = open_device()?;
+        let mut buffer_len: u32 = 0;
+        ioctl(&file, BIOCGBLEN, &mut buffer_len)?;
+        let mut req = sys::IfReqFlags::new(&interface.name)?;
+        ioctl(&file, BIOCSETIF, &mut req)?;
+        Ok(Bpf {
+            file,
+            buffer_len: buffer_len as usize,
+        })
+    }
+    /// Size of the kernel buffer, and the minimum size of a read buffer
+    pub fn buffer_len(&self) -> usize {
+        self.buffer_len
+    }
+    /// Return packets as soon as they arrive instead of when the buffer fills up
+    pub fn set_immediate(&self, enabled: bool) -> io::Result<()> {
+        let mut value: u32 = enabled as u32;
+        ioctl(&self.file, BIOCIMMEDIATE, &mut value)
+    }
+    /// Put the interface into promiscuous mode for as long as the device is open
+    pub fn set_promiscuous(&self) -> io::Result<()> {
+        let ret = unsafe { libc::ioctl(self.file.as_raw_fd(), BIOCPROMISC as _) };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+    /// Install a filter program; only packets it accepts are captured
+    pub fn set_filter(&self, program: &[BpfInsn]) -> io::Result<()> {
+        let mut prog = BpfProgram {
+            bf_len: program.len() as libc::c_uint,
+            bf_insns: program.as_ptr(),
+        };
+        ioctl(&self.file, BIOCSETF, &mut prog)
+    }
+    /// Split a buffer filled by `read` into packets
+    pub fn packets(buf: &[u8]) -> BpfPackets<'_> {
+        BpfPackets { buf, offset: 0 }
+    }
+}
+
+impl Read for Bpf {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buf)
+    }
+}
+
+impl AsRawFd for Bpf {
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
+
+impl AsFd for Bpf {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.file.as_fd()
+    }
+}
+
+fn open_device() -> io::Result<File> {
+    let open = |path: &str| OpenOptions::new().read(true).write(true).open(path);
+    // FreeBSD and recent macOS provide a cloning device.
+    match open("/dev/bpf") {
+        Ok(file) => return Ok(file),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) if err.raw_os_error() == Some(libc::EBUSY) => {}
+        Err(err) => return Err(err),
+    }
+    for i in 0..256 {
+        match open(&format!("/dev/bpf{}", i)) {
+            Ok(file) => return Ok(file),
+            Err(err) if err.raw_os_error() == Some(libc::EBUSY) => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::AddrInUse,
+        "no free BPF device",
+    ))
+}
+
+fn ioctl<T>(file: &File, request: u64, arg: &mut T) -> io::Result<()> {
+    let ret = unsafe { libc::ioctl(file.as_raw_fd(), request as _, arg as *mut T) };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(buf: &mut Vec<u8>, sec: u32, data: &[u8]) {
+        let hdrlen = mem::size_of::<BpfHdr>();
+        let mut hdr: BpfHdr = unsafe { mem::zeroed() };
+        hdr.bh_tstamp.tv_sec = sec as _;
+        hdr.bh_tstamp.tv_usec = 500 as _;
+        hdr.bh_caplen = data.len() as u32;
+        hdr.bh_datalen = data.len() as u32 + 10;
+        hdr.bh_hdrlen = hdrlen as u16;
+        let bytes =
+            unsafe { std::slice::from_raw_parts(&hdr as *const BpfHdr as *const u8, hdrlen) };
+        buf.extend_from_slice(bytes);
+        buf.extend_from_slice(data);
+        buf.resize(buf.len().next_multiple_of(BPF_ALIGNMENT), 0);
+    }
+
+    #[test]
+    fn test_packets() {
+        let mut buf = Vec::new();
+        record(&mut buf, 1, &[1, 2, 3]);
+        record(&mut buf, 2, &[4, 5, 6, 7, 8]);
+        let packets: Vec<BpfPacket> = Bpf::packets(&buf).collect();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].data, &[1, 2, 3]);
+        assert_eq!(packets[0].len, 13);
+        assert_eq!(packets[1].data, &[4, 5, 6, 7, 8]);
+        assert_eq!(packets[1].timestamp, Duration::new(2, 500_000));
+        // A truncated record is not returned
+        assert_eq!(Bpf::packets(&buf[..buf.len() - 8]).count(), 1);
+    }
+
+    #[test]
+    fn test_packets_zeroed() {
+        let buf = vec![0u8; 4 * mem::size_of::<BpfHdr>()];
+        assert_eq!(Bpf::packets(&buf).count(), 0);
+    }
+
+    #[test]
+    fn test_open() {
+        if let Some(interface) = crate::interface::get_interfaces().first() {
+            println!("{:?}", Bpf::open(interface));
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Raw link-layer access bound to a network interface.
+//!
+//! [`Bpf`] wraps a `/dev/bpf*` device on macOS and the BSDs, and
+//! [`packet_socket`] opens an `AF_PACKET` socket on Linux and Android.
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+mod bpf;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod packet;
+#[cfg(any(
+    target_os = "macos",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+pub use self::bpf::*;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use self::packet::*;
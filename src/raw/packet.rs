@@ -0,0 +1,165 @@
+use crate::interface::Interface;
+use std::io;
+use std::mem;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+// <linux/if_packet.h>
+const PACKET_ADD_MEMBERSHIP: libc::c_int = 1;
+const PACKET_MR_PROMISC: libc::c_ushort = 1;
+const PACKET_FANOUT: libc::c_int = 18;
+
+/// All protocols, for use as the `protocol` argument of [`packet_socket`]
+pub const ETH_P_ALL: u16 = 0x0003;
+
+#[repr(C)]
+struct PacketMreq {
+    mr_ifindex: libc::c_int,
+    mr_type: libc::c_ushort,
+    mr_alen: libc::c_ushort,
+    mr_address: [u8; 8],
+}
+
+/// How packets are spread across the sockets of a fanout group
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum FanoutMode {
+    /// By flow hash, so each flow stays on one socket
+    Hash,
+    /// Round-robin
+    LoadBalance,
+    /// By the CPU the packet arrived on
+    Cpu,
+    /// Fill one socket before moving on to the next
+    Rollover,
+    /// Randomly
+    Random,
+    /// By the recorded receive queue of the packet
+    QueueMapping,
+}
+
+impl FanoutMode {
+    fn id(&self) -> u32 {
+        match self {
+            FanoutMode::Hash => 0,
+            FanoutMode::LoadBalance => 1,
+            FanoutMode::Cpu => 2,
+            FanoutMode::Rollover => 3,
+            FanoutMode::Random => 4,
+            FanoutMode::QueueMapping => 5,
+        }
+    }
+}
+
+/// Fanout group membership for a packet socket
+///
+/// Sockets joining the same group id with the same mode share the traffic of
+/// the interface instead of each receiving a copy.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct Fanout {
+    pub group: u16,
+    pub mode: FanoutMode,
+}
+
+/// Options for [`packet_socket_with_options`]
+#[derive(Clone, Copy, Default, Eq, PartialEq, Hash, Debug)]
+pub struct PacketSocketOptions {
+    /// Put the interface into promiscuous mode for as long as the socket is open
+    pub promiscuous: bool,
+    /// Join a fanout group
+    pub fanout: Option<Fanout>,
+}
+
+/// Open an `AF_PACKET` socket bound to `interface`
+///
+/// `protocol` is an EtherType in host byte order, or [`ETH_P_ALL`]. The socket
+/// is `SOCK_RAW`, so reads and writes include the link-layer header.
+/// Opening one requires `CAP_NET_RAW`.
+pub fn packet_socket(interface: &Interface, protocol: u16) -> io::Result<OwnedFd> {
+    packet_socket_with_options(interface, protocol, &PacketSocketOptions::default())
+}
+
+/// Open an `AF_PACKET` socket bound to `interface` with the given options
+pub fn packet_socket_with_options(
+    interface: &Interface,
+    protocol: u16,
+    options: &PacketSocketOptions,
+) -> io::Result<OwnedFd> {
+    let fd = unsafe {
+        libc::socket(
+            libc::AF_PACKET,
+            libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+            protocol.to_be() as libc::c_int,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    let mut addr: libc::sockaddr_ll = unsafe { mem::zeroed() };
+    addr.sll_family = libc::AF_PACKET as libc::c_ushort;
+    addr.sll_protocol = protocol.to_be();
+    addr.sll_ifindex = interface.index as libc::c_int;
+    let ret = unsafe {
+        libc::bind(
+            fd.as_raw_fd(),
+            &addr as *const libc::sockaddr_ll as *const libc::sockaddr,
+            mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+        )
+    };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    if options.promiscuous {
+        let mreq = PacketMreq {
+            mr_ifindex: interface.index as libc::c_int,
+            mr_type: PACKET_MR_PROMISC,
+            mr_alen: 0,
+            mr_address: [0; 8],
+        };
+        setsockopt(&fd, PACKET_ADD_MEMBERSHIP, &mreq)?;
+    }
+    if let Some(fanout) = options.fanout {
+        let arg: u32 = fanout.group as u32 | fanout.mode.id() << 16;
+        setsockopt(&fd, PACKET_FANOUT, &arg)?;
+    }
+    Ok(fd)
+}
+
+fn setsockopt<T>(fd: &OwnedFd, name: libc::c_int, value: &T) -> io::Result<()> {
+    let ret = unsafe {
+        libc::setsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_PACKET,
+            name,
+            value as *const T as *const libc::c_void,
+            mem::size_of::<T>() as libc::socklen_t,
+        )
+    };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packet_socket() {
+        if let Some(interface) = crate::interface::get_interfaces().first() {
+            let options = PacketSocketOptions {
+                promiscuous: false,
+                fanout: Some(Fanout {
+                    group: std::process::id() as u16,
+                    mode: FanoutMode::Hash,
+                }),
+            };
+            println!(
+                "{:?}",
+                packet_socket_with_options(interface, ETH_P_ALL, &options)
+            );
+        }
+    }
+}
@@ -0,0 +1,369 @@
+//! Enumeration of the interfaces of a remote host.
+//!
+//! The remote host runs [`serve`] (or [`serve_stdio`], see the `remote_agent`
+//! example) on one end of a transport such as an SSH channel, and a [`Client`]
+//! on the other end returns its data as the usual [`Interface`] and
+//! [`RouteEntry`] values. A client is also a [`Backend`], so it can be installed
+//! with [`set_backend`](crate::backend::set_backend) to point the enumeration
+//! functions at the remote host.
+//!
+//! The protocol is line-delimited JSON. On connect the server announces itself:
+//!
+//! ```text
+//! {"protocol": "netdev-remote", "version": 1, "schema": 1, "methods": ["interfaces", "routes"]}
+//! ```
+//!
+//! `schema` is the [`crate::json`] schema version used for interfaces. Each
+//! request is answered by one response with the same id:
+//!
+//! ```text
+//! -> {"id": 1, "method": "interfaces", "options": EnumOptions | null}
+//! <- {"id": 1, "result": [Interface]}
+//! -> {"id": 2, "method": "routes"}
+//! <- {"id": 2, "error": "routing table access is not supported on this platform"}
+//! ```
+//!
+//! Routes are encoded with the serde derives of [`RouteEntry`].
+
+use crate::backend::Backend;
+use crate::interface::{self, EnumOptions, Interface};
+use crate::json;
+use crate::route::{self, RouteEntry};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::sync::Mutex;
+
+/// Name announced in the server's greeting
+pub const PROTOCOL_NAME: &str = "netdev-remote";
+/// Version of the protocol spoken by this crate
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Longest line accepted from the other end, so a broken or hostile peer
+/// cannot exhaust memory
+const MAX_LINE: u64 = 16 << 20;
+
+const METHOD_INTERFACES: &str = "interfaces";
+const METHOD_ROUTES: &str = "routes";
+
+#[derive(Serialize, Deserialize)]
+struct Hello {
+    protocol: String,
+    version: u32,
+    schema: u32,
+    #[serde(default)]
+    methods: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Request {
+    id: u64,
+    method: String,
+    #[serde(default)]
+    options: Option<EnumOptions>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Response {
+    id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn invalid_data<E: std::fmt::Display>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+fn write_line<W: Write, M: Serialize>(writer: &mut W, message: &M) -> io::Result<()> {
+    let mut line = serde_json::to_vec(message).map_err(invalid_data)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    writer.flush()
+}
+
+/// Read one line of at most [`MAX_LINE`] bytes, returning 0 at end of stream
+fn read_bounded_line<R: BufRead>(reader: &mut R, line: &mut String) -> io::Result<usize> {
+    line.clear();
+    let len = reader.by_ref().take(MAX_LINE).read_line(line)?;
+    if len as u64 == MAX_LINE && !line.ends_with('\n') {
+        return Err(invalid_data(format!("line longer than {} bytes", MAX_LINE)));
+    }
+    Ok(len)
+}
+
+/// Read one line, failing with [`io::ErrorKind::UnexpectedEof`] at end of stream
+fn read_line<R: BufRead>(reader: &mut R, line: &mut String) -> io::Result<()> {
+    if read_bounded_line(reader, line)? == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "the remote end closed the connection",
+        ));
+    }
+    Ok(())
+}
+
+/// Answer requests read from `reader` on `writer` until `reader` reaches end of stream
+///
+/// Requests are answered with the local enumeration functions, so an installed
+/// [`Backend`] is honored.
+pub fn serve<R: Read, W: Write>(reader: R, mut writer: W) -> io::Result<()> {
+    let hello = Hello {
+        protocol: PROTOCOL_NAME.to_string(),
+        version: PROTOCOL_VERSION,
+        schema: json::SCHEMA_VERSION,
+        methods: vec![METHOD_INTERFACES.to_string(), METHOD_ROUTES.to_string()],
+    };
+    write_line(&mut writer, &hello)?;
+    let mut reader = BufReader::new(reader);
+    let mut line = String::new();
+    loop {
+        if read_bounded_line(&mut reader, &mut line)? == 0 {
+            return Ok(());
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => handle(request),
+            Err(err) => Response {
+                id: None,
+                result: None,
+                error: Some(format!("malformed request: {}", err)),
+            },
+        };
+        write_line(&mut writer, &response)?;
+    }
+}
+
+/// [`serve`] on the standard input and output of the process
+pub fn serve_stdio() -> io::Result<()> {
+    serve(io::stdin().lock(), io::stdout().lock())
+}
+
+fn handle(request: Request) -> Response {
+    debug!("remote request {}: {}", request.id, request.method);
+    let result = match request.method.as_str() {
+        METHOD_INTERFACES => {
+            let options = request.options.unwrap_or_default();
+            interface::get_interfaces_with_options(&options)
+                .iter()
+                .map(json::interface_to_value)
+                .collect::<Result<Vec<Value>, _>>()
+                .map(Value::Array)
+                .map_err(|err| err.to_string())
+        }
+        METHOD_ROUTES => route::get_routes()
+            .map_err(|err| err.to_string())
+            .and_then(|routes| serde_json::to_value(routes).map_err(|err| err.to_string())),
+        method => Err(format!("unknown method {}", method)),
+    };
+    match result {
+        Ok(result) => Response {
+            id: Some(request.id),
+            result: Some(result),
+            error: None,
+        },
+        Err(error) => Response {
+            id: Some(request.id),
+            result: None,
+            error: Some(error),
+        },
+    }
+}
+
+struct Connection<T> {
+    stream: BufReader<T>,
+    next_id: u64,
+    line: String,
+}
+
+/// Client for a host running [`serve`]
+///
+/// Requests are sent one at a time; the client can be shared between threads
+/// if the transport can be sent between them.
+pub struct Client<T> {
+    connection: Mutex<Connection<T>>,
+    methods: Vec<String>,
+}
+
+impl<T: Read + Write> Client<T> {
+    /// Start a session on `transport`, reading the server's greeting
+    pub fn new(transport: T) -> io::Result<Client<T>> {
+        let mut stream = BufReader::new(transport);
+        let mut line = String::new();
+        read_line(&mut stream, &mut line)?;
+        let hello: Hello = serde_json::from_str(&line).map_err(invalid_data)?;
+        if hello.protocol != PROTOCOL_NAME {
+            return Err(invalid_data(format!(
+                "the remote end speaks {}, not {}",
+                hello.protocol, PROTOCOL_NAME
+            )));
+        }
+        if hello.version != PROTOCOL_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("unsupported protocol version {}", hello.version),
+            ));
+        }
+        if hello.schema != json::SCHEMA_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("unsupported interface schema version {}", hello.schema),
+            ));
+        }
+        Ok(Client {
+            connection: Mutex::new(Connection {
+                stream,
+                next_id: 1,
+                line,
+            }),
+            methods: hello.methods,
+        })
+    }
+    /// Returns the methods the server announced
+    pub fn methods(&self) -> &[String] {
+        &self.methods
+    }
+    /// Get the remote host's interfaces
+    pub fn interfaces(&self) -> io::Result<Vec<Interface>> {
+        self.interfaces_with_options(&EnumOptions::default())
+    }
+    /// Get the remote host's interfaces matching `options`
+    pub fn interfaces_with_options(&self, options: &EnumOptions) -> io::Result<Vec<Interface>> {
+        let result = self.call(METHOD_INTERFACES, Some(options.clone()))?;
+        let values = match result {
+            Value::Array(values) => values,
+            _ => return Err(invalid_data("interfaces result is not an array")),
+        };
+        values
+            .into_iter()
+            .map(|value| json::interface_from_value(value).map_err(invalid_data))
+            .collect()
+    }
+    /// Get the entries of the remote host's main routing table
+    pub fn routes(&self) -> io::Result<Vec<RouteEntry>> {
+        let result = self.call(METHOD_ROUTES, None)?;
+        serde_json::from_value(result).map_err(invalid_data)
+    }
+    /// End the session, returning the transport
+    pub fn into_inner(self) -> T {
+        self.connection
+            .into_inner()
+            .unwrap_or_else(|err| err.into_inner())
+            .stream
+            .into_inner()
+    }
+    fn call(&self, method: &str, options: Option<EnumOptions>) -> io::Result<Value> {
+        let mut connection = self
+            .connection
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        let Connection {
+            stream,
+            next_id,
+            line,
+        } = &mut *connection;
+        let id = *next_id;
+        *next_id += 1;
+        let request = Request {
+            id,
+            method: method.to_string(),
+            options,
+        };
+        write_line(stream.get_mut(), &request)?;
+        loop {
+            read_line(stream, line)?;
+            let response: Response = serde_json::from_str(line).map_err(invalid_data)?;
+            // Skip answers to requests abandoned after an error
+            if response.id.is_some() && response.id != Some(id) {
+                continue;
+            }
+            return match (response.result, response.error) {
+                (_, Some(error)) => Err(io::Error::other(error)),
+                (Some(result), None) => Ok(result),
+                (None, None) => Err(invalid_data("response has neither result nor error")),
+            };
+        }
+    }
+}
+
+impl<T: Read + Write + Send> Backend for Client<T> {
+    fn name(&self) -> &str {
+        "remote"
+    }
+    /// Returns no interfaces if the request fails
+    fn interfaces(&self, options: &EnumOptions) -> Vec<Interface> {
+        match self.interfaces_with_options(options) {
+            Ok(interfaces) => interfaces,
+            Err(err) => {
+                debug!("remote enumeration failed: {}", err);
+                Vec::new()
+            }
+        }
+    }
+    fn routes(&self) -> io::Result<Vec<RouteEntry>> {
+        Client::routes(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, TcpListener, TcpStream};
+    use std::thread;
+
+    #[test]
+    fn test_remote_interfaces() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            serve(stream.try_clone().unwrap(), stream).unwrap();
+        });
+        let client = Client::new(TcpStream::connect(addr).unwrap()).unwrap();
+        assert!(client.methods().iter().any(|m| m == METHOD_INTERFACES));
+        let remote: Vec<u32> = client
+            .interfaces()
+            .unwrap()
+            .iter()
+            .map(|i| i.index)
+            .collect();
+        let local: Vec<u32> = interface::get_interfaces()
+            .iter()
+            .map(|i| i.index)
+            .collect();
+        assert_eq!(remote, local);
+        println!("{:?}", client.routes());
+        let err = client.call("reboot", None).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        drop(client);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_wrong_protocol() {
+        let greeting: &[u8] = b"{\"protocol\":\"other\",\"version\":1,\"schema\":1}\n";
+        let transport = io::Cursor::new(greeting.to_vec());
+        let err = Client::new(transport).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let greeting: &[u8] = b"{\"protocol\":\"netdev-remote\",\"version\":1,\"schema\":2}\n";
+        let transport = io::Cursor::new(greeting.to_vec());
+        let err = Client::new(transport).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn test_line_too_long() {
+        let mut reader = io::Cursor::new(vec![b'x'; MAX_LINE as usize + 1]);
+        let mut line = String::new();
+        let err = read_bounded_line(&mut reader, &mut line).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut reader = io::Cursor::new(b"{}\n".to_vec());
+        assert_eq!(read_bounded_line(&mut reader, &mut line).unwrap(), 3);
+        assert_eq!(read_bounded_line(&mut reader, &mut line).unwrap(), 0);
+    }
+}
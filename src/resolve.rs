@@ -0,0 +1,419 @@
+//! Resolution of `.local` host names with multicast DNS (RFC 6762), and of
+//! addresses to host names with PTR queries.
+//!
+//! mDNS queries are sent from an ephemeral port as one-shot "legacy unicast" queries
+//! (RFC 6762 section 6.7), so responders answer the querier directly and the
+//! system's mDNS daemon, if any, is neither needed nor disturbed.
+//!
+//! [`reverse`] asks the DNS servers of the interface the address is on, as
+//! found by enumeration, so a gateway or neighbour gets the name the local
+//! network's resolver gives it.
+
+use crate::dns::{self, DNS_TYPE_A, DNS_TYPE_AAAA, DNS_TYPE_PTR};
+use crate::interface::{self, Interface};
+use crate::zone;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, UdpSocket};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// UDP port of multicast DNS
+pub const MDNS_PORT: u16 = 5353;
+/// IPv4 multicast group of multicast DNS
+pub const MDNS_GROUP_V4: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+/// IPv6 link-local multicast group of multicast DNS
+pub const MDNS_GROUP_V6: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0xfb);
+
+/// UDP port of DNS
+const DNS_PORT: u16 = 53;
+
+/// How long [`local`] waits for answers, and [`reverse`] for each DNS server
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often the query sockets are checked for answers
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Resolve a `.local` name, such as `printer.local`, to its addresses
+///
+/// The query is sent on every interface that is up and supports multicast,
+/// over IPv4 and IPv6. Each answer is returned with the interface it arrived on,
+/// which is needed to reach IPv6 link-local addresses. The call waits
+/// [`DEFAULT_TIMEOUT`] for answers.
+pub fn local(name: &str) -> io::Result<Vec<(IpAddr, Interface)>> {
+    local_with_timeout(name, DEFAULT_TIMEOUT)
+}
+
+/// Resolve a `.local` name, waiting up to `timeout` for answers
+pub fn local_with_timeout(name: &str, timeout: Duration) -> io::Result<Vec<(IpAddr, Interface)>> {
+    let name = name.trim_end_matches('.');
+    if !is_local_name(name) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a .local name", name),
+        ));
+    }
+    // Concurrent lookups in this process must not take each other's answers
+    let id = dns::random_id();
+    let deadline = Instant::now() + timeout;
+    let mut queries = Vec::new();
+    for iface in interface::get_interfaces() {
+        if !iface.is_up() || !iface.is_multicast() || iface.is_loopback() {
+            continue;
+        }
+        if let Ok(socket) = query_v4(&iface, id, name) {
+            queries.push((socket, iface.index));
+        }
+        if let Ok(socket) = query_v6(&iface, id, name) {
+            queries.push((socket, iface.index));
+        }
+    }
+    if queries.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrNotAvailable,
+            "no interface can send multicast DNS queries",
+        ));
+    }
+    let mut found: Vec<(IpAddr, u32)> = Vec::new();
+    let mut buf = [0u8; 1500];
+    while Instant::now() < deadline {
+        for (socket, if_index) in &queries {
+            while let Ok(len) = socket.recv(&mut buf) {
+                for ip in parse_mdns_response(&buf[..len], id, name) {
+                    if !found.contains(&(ip, *if_index)) {
+                        found.push((ip, *if_index));
+                    }
+                }
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    // Look the interfaces up again, so the caller gets them as they are now
+    let interfaces = interface::get_interfaces();
+    Ok(found
+        .into_iter()
+        .filter_map(|(ip, if_index)| {
+            let iface = interfaces.iter().find(|iface| iface.index == if_index)?;
+            Some((ip, iface.clone()))
+        })
+        .collect())
+}
+
+/// Look up the host name of `ip`, such as a gateway or neighbour address
+///
+/// The PTR query goes to the DNS servers of the interface whose network holds
+/// `ip`, then to those of the default interface, waiting [`DEFAULT_TIMEOUT`]
+/// for each. Returns `None` if the servers know no name for the address.
+pub fn reverse(ip: IpAddr) -> io::Result<Option<String>> {
+    reverse_with_timeout(ip, DEFAULT_TIMEOUT)
+}
+
+/// Look up the host name of `ip`, waiting up to `timeout` for each DNS server
+pub fn reverse_with_timeout(ip: IpAddr, timeout: Duration) -> io::Result<Option<String>> {
+    let servers = dns_servers_for(&interface::get_interfaces(), ip);
+    if servers.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrNotAvailable,
+            "no DNS server is known",
+        ));
+    }
+    reverse_via(ip, &servers, timeout)
+}
+
+/// Look up the host name of `ip` with the given DNS servers, asking them in
+/// order until one answers
+pub fn reverse_via(
+    ip: IpAddr,
+    servers: &[IpAddr],
+    timeout: Duration,
+) -> io::Result<Option<String>> {
+    let mut last_err = io::Error::new(io::ErrorKind::InvalidInput, "no DNS server given");
+    for server in servers {
+        match ptr_query(ip, *server, timeout) {
+            Ok(name) => return Ok(name),
+            Err(err) => last_err = err,
+        }
+    }
+    Err(last_err)
+}
+
+/// Render `ip` with its host name, such as `router.lan (192.168.1.1)`, or as
+/// the bare address if it has none
+pub fn display_name(ip: IpAddr) -> String {
+    match reverse(ip) {
+        Ok(Some(name)) => format!("{} ({})", name, ip),
+        _ => ip.to_string(),
+    }
+}
+
+/// DNS servers to ask about `ip`: those of the interface on its network first,
+/// then those of the default interface
+fn dns_servers_for(interfaces: &[Interface], ip: IpAddr) -> Vec<IpAddr> {
+    let on_link = interfaces.iter().filter(|iface| match ip {
+        IpAddr::V4(ip) => iface.ipv4.iter().any(|net| net.contains(ip)),
+        IpAddr::V6(ip) => iface.ipv6.iter().any(|net| net.contains(ip)),
+    });
+    let default = interfaces.iter().filter(|iface| iface.default);
+    let mut servers: Vec<IpAddr> = Vec::new();
+    for server in on_link.chain(default).flat_map(|iface| &iface.dns_servers) {
+        if !servers.contains(server) {
+            servers.push(*server);
+        }
+    }
+    servers
+}
+
+/// Name queried for the PTR record of `ip`, such as `1.1.168.192.in-addr.arpa`
+fn reverse_name(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, d] = ip.octets();
+            format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
+        }
+        IpAddr::V6(ip) => {
+            let mut name = String::with_capacity(72);
+            for byte in ip.octets().iter().rev() {
+                name.push_str(&format!("{:x}.{:x}.", byte & 0xf, byte >> 4));
+            }
+            name.push_str("ip6.arpa");
+            name
+        }
+    }
+}
+
+fn ptr_query(ip: IpAddr, server: IpAddr, timeout: Duration) -> io::Result<Option<String>> {
+    let id = dns::random_id();
+    let qname = reverse_name(ip);
+    let query = dns::build_query(id, &qname, DNS_TYPE_PTR, dns::FLAGS_RECURSION_DESIRED);
+    let local = match server {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+    let socket = UdpSocket::bind(SocketAddr::new(local, 0))?;
+    socket.set_read_timeout(Some(timeout))?;
+    socket.connect(SocketAddr::new(server, DNS_PORT))?;
+    socket.send(&query)?;
+    let deadline = Instant::now() + timeout;
+    let mut buf = [0u8; 1500];
+    loop {
+        let len = socket.recv(&mut buf)?;
+        // Skip stray datagrams that are not the answer to this query
+        if let Some(name) = parse_ptr_response(&buf[..len], id, &qname) {
+            return Ok(name);
+        }
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "no answer"));
+        }
+        socket.set_read_timeout(Some(left))?;
+    }
+}
+
+/// Decode the answer to the PTR query for `qname` with the given id: `None` if
+/// `buf` is not that answer, `Some(None)` if the name does not exist or has no
+/// PTR record
+fn parse_ptr_response(buf: &[u8], id: u16, qname: &str) -> Option<Option<String>> {
+    if dns::read_u16(buf, 0)? != id {
+        return None;
+    }
+    let (name, qtype) = dns::question(buf)?;
+    if qtype != DNS_TYPE_PTR || !name.eq_ignore_ascii_case(qname) {
+        return None;
+    }
+    let flags = dns::read_u16(buf, 2)?;
+    if flags & 0x8000 != 0 && flags & 0x000f == dns::RCODE_NXDOMAIN {
+        return Some(None);
+    }
+    let records = dns::parse_answers(buf, id)?;
+    Some(
+        records
+            .iter()
+            .filter(|record| record.rtype == DNS_TYPE_PTR)
+            .find_map(|record| dns::read_name(buf, record.rdata_pos)),
+    )
+}
+
+fn is_local_name(name: &str) -> bool {
+    let len = name.len();
+    len > 6 && name.is_char_boundary(len - 6) && name[len - 6..].eq_ignore_ascii_case(".local")
+}
+
+fn query_v4(iface: &Interface, id: u16, name: &str) -> io::Result<UdpSocket> {
+    let local = iface
+        .ipv4
+        .first()
+        .map(|net| net.addr)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::AddrNotAvailable, "no IPv4 address"))?;
+    let socket = UdpSocket::bind(SocketAddrV4::new(local, 0))?;
+    iface.set_multicast_if_v4(&socket)?;
+    socket.set_nonblocking(true)?;
+    let query = dns::build_query(id, name, DNS_TYPE_A, 0);
+    socket.send_to(&query, SocketAddrV4::new(MDNS_GROUP_V4, MDNS_PORT))?;
+    Ok(socket)
+}
+
+fn query_v6(iface: &Interface, id: u16, name: &str) -> io::Result<UdpSocket> {
+    let local = iface
+        .ipv6
+        .iter()
+        .map(|net| net.addr)
+        .find(zone::is_link_local)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::AddrNotAvailable,
+                "no IPv6 link-local address",
+            )
+        })?;
+    let socket = UdpSocket::bind(SocketAddr::V6(SocketAddrV6::new(local, 0, 0, iface.index)))?;
+    socket.set_nonblocking(true)?;
+    let query = dns::build_query(id, name, DNS_TYPE_AAAA, 0);
+    // The scope id selects the interface the query leaves on
+    socket.send_to(
+        &query,
+        SocketAddrV6::new(MDNS_GROUP_V6, MDNS_PORT, 0, iface.index),
+    )?;
+    Ok(socket)
+}
+
+/// Collect the A and AAAA records for `name` from a response.
+///
+/// Both families are accepted regardless of the query type, as responders
+/// commonly include the other family's addresses.
+fn parse_mdns_response(buf: &[u8], id: u16, name: &str) -> Vec<IpAddr> {
+    let records = match dns::parse_answers(buf, id) {
+        Some(records) => records,
+        None => return vec![],
+    };
+    records
+        .into_iter()
+        .filter(|record| record.name.eq_ignore_ascii_case(name))
+        .filter_map(|record| match (record.rtype, record.rdata.len()) {
+            (DNS_TYPE_A, 4) => Some(IpAddr::from(<[u8; 4]>::try_from(record.rdata).ok()?)),
+            (DNS_TYPE_AAAA, 16) => Some(IpAddr::from(<[u8; 16]>::try_from(record.rdata).ok()?)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mdns_response() {
+        let id = 0x4242;
+        let mut resp = dns::build_query(id, "Host.local", DNS_TYPE_A, 0);
+        // Turn the query into an authoritative response with three answers:
+        // an A and an AAAA record for the name, and an A record for another name.
+        resp[2] = 0x84;
+        resp[7] = 3;
+        resp.extend_from_slice(&[0xc0, 0x0c, 0, 1, 0x80, 1, 0, 0, 0, 120, 0, 4, 192, 0, 2, 7]);
+        resp.extend_from_slice(&[0xc0, 0x0c, 0, 28, 0x80, 1, 0, 0, 0, 120, 0, 16]);
+        resp.extend_from_slice(&Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 7).octets());
+        resp.extend_from_slice(&[5, b'o', b't', b'h', b'e', b'r', 0xc0, 0x11]);
+        resp.extend_from_slice(&[0, 1, 0x80, 1, 0, 0, 0, 120, 0, 4, 192, 0, 2, 8]);
+        assert_eq!(
+            parse_mdns_response(&resp, id, "host.local"),
+            vec![
+                IpAddr::V4(Ipv4Addr::new(192, 0, 2, 7)),
+                IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 7)),
+            ]
+        );
+        assert!(parse_mdns_response(&resp, id + 1, "host.local").is_empty());
+    }
+
+    #[test]
+    fn test_reverse_name() {
+        assert_eq!(
+            reverse_name(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 254))),
+            "254.1.168.192.in-addr.arpa"
+        );
+        let name = reverse_name(IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0x1)));
+        assert_eq!(
+            name,
+            "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"
+        );
+    }
+
+    #[test]
+    fn test_parse_ptr_response() {
+        let id = 0x1234;
+        let mut resp = dns::build_query(id, "1.1.168.192.in-addr.arpa", DNS_TYPE_PTR, 0);
+        resp[2] = 0x81;
+        resp[3] = 0x80;
+        resp[7] = 1;
+        // PTR answer for the question name: router.lan
+        resp.extend_from_slice(&[0xc0, 0x0c, 0, 12, 0, 1, 0, 0, 0, 60, 0, 12]);
+        resp.extend_from_slice(&[
+            6, b'r', b'o', b'u', b't', b'e', b'r', 3, b'l', b'a', b'n', 0,
+        ]);
+        let qname = "1.1.168.192.in-addr.arpa";
+        assert_eq!(
+            parse_ptr_response(&resp, id, qname),
+            Some(Some("router.lan".to_string()))
+        );
+        assert_eq!(parse_ptr_response(&resp, id + 1, qname), None);
+        // The answer to another question with the same id
+        assert_eq!(
+            parse_ptr_response(&resp, id, "2.1.168.192.in-addr.arpa"),
+            None
+        );
+        let mut other_type = resp.clone();
+        other_type[12 + qname.len() + 3] = 1;
+        assert_eq!(parse_ptr_response(&other_type, id, qname), None);
+
+        let mut nxdomain = dns::build_query(id, "2.1.168.192.in-addr.arpa", DNS_TYPE_PTR, 0);
+        nxdomain[2] = 0x81;
+        nxdomain[3] = 0x83;
+        assert_eq!(
+            parse_ptr_response(&nxdomain, id, "2.1.168.192.in-addr.arpa"),
+            Some(None)
+        );
+    }
+
+    #[test]
+    fn test_dns_servers_for() {
+        use crate::interface::InterfaceBuilder;
+
+        let lan_dns = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+        let wan_dns = IpAddr::V4(Ipv4Addr::new(9, 9, 9, 9));
+        let interfaces = vec![
+            InterfaceBuilder::new(2, "eth0")
+                .ipv4(Ipv4Addr::new(192, 168, 1, 20), 24)
+                .dns_server(lan_dns)
+                .build(),
+            InterfaceBuilder::new(3, "wg0")
+                .ipv4(Ipv4Addr::new(10, 8, 0, 2), 24)
+                .dns_server(wan_dns)
+                .dns_server(lan_dns)
+                .default_route(true)
+                .build(),
+        ];
+        let gateway = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+        assert_eq!(
+            dns_servers_for(&interfaces, gateway),
+            vec![lan_dns, wan_dns]
+        );
+        let remote = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 5));
+        assert_eq!(dns_servers_for(&interfaces, remote), vec![wan_dns, lan_dns]);
+    }
+
+    #[test]
+    #[ignore = "needs network access"]
+    fn test_reverse() {
+        for interface in interface::get_interfaces() {
+            for gateway in &interface.gateways {
+                for ip in &gateway.ipv4 {
+                    println!("{}", display_name(IpAddr::V4(*ip)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_local_invalid() {
+        assert!(is_local_name("printer.LOCAL"));
+        assert!(!is_local_name(".local"));
+        let err = local("example.com").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
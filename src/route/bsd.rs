@@ -0,0 +1,189 @@
+use super::{RouteDecision, RouteEntry};
+use crate::error::Error;
+use crate::interface::AddressFamily;
+use crate::ip::IpNet;
+use std::io;
+use std::net::IpAddr;
+
+#[cfg(not(target_os = "openbsd"))]
+use crate::parse::{self, SockAddrView};
+#[cfg(not(target_os = "openbsd"))]
+use crate::sys::rtsock::{RTAX_GATEWAY, RTAX_IFA, RTA_IFP, RTM_GET, RT_MSGHDR_LEN};
+#[cfg(not(target_os = "openbsd"))]
+use std::net::Ipv6Addr;
+
+#[cfg(not(target_os = "openbsd"))]
+use crate::sys::rtsock::{
+    self, finish_message, new_message, push_sockaddr, sockaddr_dl, sockaddr_ip, RTA_DST,
+    RTF_GATEWAY, RTF_HOST, RTF_UP,
+};
+#[cfg(all(feature = "config", not(target_os = "openbsd")))]
+use crate::sys::rtsock::{RTA_GATEWAY, RTA_NETMASK, RTF_STATIC};
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+use crate::gateway::macos as gateway;
+
+#[cfg(any(target_os = "openbsd", target_os = "freebsd", target_os = "netbsd"))]
+use crate::gateway::bsd as gateway;
+
+pub fn list_routes() -> io::Result<Vec<RouteEntry>> {
+    Ok(gateway::list_routes(AddressFamily::Both)?
+        .into_iter()
+        .map(|route| RouteEntry {
+            destination: IpNet::new(route.destination, route.prefix),
+            gateway: route.gateway,
+            if_index: route.ifindex,
+            metric: None,
+            next_hops: Vec::new(),
+        })
+        .collect())
+}
+
+/// Clear the interface index the kernel embeds in the second 16-bit word of
+/// link-local addresses
+#[cfg(not(target_os = "openbsd"))]
+fn strip_embedded_scope(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(ip) if ip.segments()[0] & 0xffc0 == 0xfe80 => {
+            let mut segs = ip.segments();
+            segs[1] = 0;
+            IpAddr::V6(Ipv6Addr::from(segs))
+        }
+        ip => ip,
+    }
+}
+
+#[cfg(not(target_os = "openbsd"))]
+pub fn lookup_route(destination: IpAddr) -> Result<RouteDecision, Error> {
+    let mut msg = new_message();
+    push_sockaddr(&mut msg, &sockaddr_ip(destination, 0));
+    // An empty link-layer sockaddr asks for the interface in the reply
+    push_sockaddr(&mut msg, &sockaddr_dl(0, &[]));
+    finish_message(&mut msg, RTM_GET, 0, RTF_UP | RTF_HOST, RTA_DST | RTA_IFP)
+        .and_then(|()| rtsock::query(&msg))
+        .and_then(|reply| parse_get_reply(&reply, destination))
+        .map_err(|e| Error::syscall("route(RTM_GET)", e))
+}
+
+/// Decode the reply to an `RTM_GET` message
+#[cfg(not(target_os = "openbsd"))]
+fn parse_get_reply(reply: &[u8], destination: IpAddr) -> io::Result<RouteDecision> {
+    let if_index = u16::from_ne_bytes([reply[4], reply[5]]) as u32;
+    let flags = i32::from_ne_bytes([reply[8], reply[9], reply[10], reply[11]]);
+    let rtm_addrs = i32::from_ne_bytes([reply[12], reply[13], reply[14], reply[15]]);
+    let addrs = parse::rt_addrs(&reply[RT_MSGHDR_LEN..], rtm_addrs)?;
+    let ip_at = |index: usize| {
+        addrs[index]
+            .and_then(|sa| SockAddrView::new(sa).ok()?.ip().ok())
+            .map(strip_embedded_scope)
+    };
+    Ok(RouteDecision {
+        destination,
+        if_index,
+        // On-link routes carry the link-layer address of the interface instead
+        gateway: if flags & RTF_GATEWAY != 0 {
+            ip_at(RTAX_GATEWAY)
+        } else {
+            None
+        },
+        source: ip_at(RTAX_IFA),
+    })
+}
+
+#[cfg(target_os = "openbsd")]
+pub fn lookup_route(_destination: IpAddr) -> Result<RouteDecision, Error> {
+    Err(Error::syscall(
+        "route(RTM_GET)",
+        io::Error::from(io::ErrorKind::Unsupported),
+    ))
+}
+
+#[cfg(all(feature = "config", not(target_os = "openbsd")))]
+fn build_message(rtm_type: u8, route: &RouteEntry) -> io::Result<Vec<u8>> {
+    let destination = route.destination.network();
+    let prefix_len = route.destination.prefix_len();
+    let is_host = prefix_len == route.destination.max_prefix_len();
+    let mut flags = RTF_UP | RTF_STATIC;
+    let mut addrs = RTA_DST;
+    let mut buf = new_message();
+    push_sockaddr(&mut buf, &sockaddr_ip(destination, 0));
+    match (route.gateway, route.if_index) {
+        (Some(gateway), if_index) => {
+            if gateway.is_ipv4() != destination.is_ipv4() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "gateway and destination address families differ",
+                ));
+            }
+            let scope_id = match gateway {
+                IpAddr::V6(ip) if ip.segments()[0] & 0xffc0 == 0xfe80 => if_index.unwrap_or(0),
+                _ => 0,
+            };
+            push_sockaddr(&mut buf, &sockaddr_ip(gateway, scope_id));
+            flags |= RTF_GATEWAY;
+            addrs |= RTA_GATEWAY;
+        }
+        (None, Some(if_index)) => {
+            push_sockaddr(&mut buf, &sockaddr_dl(if_index, &[]));
+            addrs |= RTA_GATEWAY;
+        }
+        (None, None) => {}
+    }
+    if is_host {
+        flags |= RTF_HOST;
+    } else {
+        push_sockaddr(&mut buf, &sockaddr_ip(route.destination.netmask(), 0));
+        addrs |= RTA_NETMASK;
+    }
+    finish_message(
+        &mut buf,
+        rtm_type,
+        route.if_index.unwrap_or(0),
+        flags,
+        addrs,
+    )?;
+    Ok(buf)
+}
+
+#[cfg(all(feature = "config", not(target_os = "openbsd")))]
+pub fn add_route_entry(route: &RouteEntry) -> io::Result<()> {
+    rtsock::send(&build_message(rtsock::RTM_ADD, route)?)
+}
+
+#[cfg(all(feature = "config", not(target_os = "openbsd")))]
+pub fn delete_route_entry(route: &RouteEntry) -> io::Result<()> {
+    rtsock::send(&build_message(rtsock::RTM_DELETE, route)?)
+}
+
+#[cfg(all(feature = "config", target_os = "openbsd"))]
+pub fn add_route_entry(_route: &RouteEntry) -> io::Result<()> {
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+}
+
+#[cfg(all(feature = "config", target_os = "openbsd"))]
+pub fn delete_route_entry(_route: &RouteEntry) -> io::Result<()> {
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+}
+
+#[cfg(all(test, not(target_os = "openbsd")))]
+mod tests {
+    use super::*;
+    use crate::sys::rtsock::{RTA_GATEWAY, RTA_IFA};
+
+    #[test]
+    fn test_parse_get_reply() {
+        let destination = IpAddr::from([1, 1, 1, 1]);
+        let gateway: IpAddr = "fe80:4::1".parse().unwrap();
+        let mut reply = new_message();
+        push_sockaddr(&mut reply, &sockaddr_ip(destination, 0));
+        push_sockaddr(&mut reply, &sockaddr_ip(gateway, 0));
+        push_sockaddr(&mut reply, &sockaddr_dl(4, &[]));
+        push_sockaddr(&mut reply, &sockaddr_ip(IpAddr::from([192, 168, 1, 20]), 0));
+        let addrs = RTA_DST | RTA_GATEWAY | RTA_IFP | RTA_IFA;
+        finish_message(&mut reply, RTM_GET, 4, RTF_UP | RTF_GATEWAY, addrs).unwrap();
+        let decision = parse_get_reply(&reply, destination).unwrap();
+        assert_eq!(decision.if_index, 4);
+        assert_eq!(decision.gateway, Some("fe80::1".parse().unwrap()));
+        assert_eq!(decision.source, Some(IpAddr::from([192, 168, 1, 20])));
+    }
+}
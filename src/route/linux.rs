@@ -0,0 +1,468 @@
+use super::{NextHop, RouteDecision, RouteEntry, RoutingRule, RuleAction};
+use crate::error::Error;
+use crate::interface::AddressFamily;
+use crate::ip::IpNet;
+use crate::sys::netlink;
+use netlink_packet_route::{
+    rtnl::route::nlas::Nla as RouteNla, rtnl::rule::nlas::Nla as RuleNla, RouteFlags, RouteMessage,
+    RtnlMessage, RuleMessage, AF_INET, AF_INET6, FIB_RULE_INVERT, FR_ACT_BLACKHOLE, FR_ACT_GOTO,
+    FR_ACT_NOP, FR_ACT_PROHIBIT, FR_ACT_TO_TBL, FR_ACT_UNREACHABLE, RTN_UNICAST, RT_TABLE_MAIN,
+};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+#[cfg(feature = "config")]
+use netlink_packet_core::{NLM_F_CREATE, NLM_F_EXCL};
+#[cfg(feature = "config")]
+use netlink_packet_route::{RTPROT_STATIC, RT_SCOPE_LINK, RT_SCOPE_NOWHERE, RT_SCOPE_UNIVERSE};
+
+fn bytes_to_ip(family: u16, bytes: &[u8]) -> Option<IpAddr> {
+    match family {
+        AF_INET => Some(IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?))),
+        AF_INET6 => Some(IpAddr::V6(Ipv6Addr::from(
+            <[u8; 16]>::try_from(bytes).ok()?,
+        ))),
+        _ => None,
+    }
+}
+
+const RTA_GATEWAY: u16 = 5;
+const RTA_VIA: u16 = 18;
+const RTNH_F_DEAD: u8 = 1;
+/// Length of `struct rtnexthop`
+const RTNH_LEN: usize = 8;
+
+fn align4(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+/// Parse the `struct rtnexthop` entries of an `RTA_MULTIPATH` attribute,
+/// leaving out the hops the kernel marked dead (link down).
+fn parse_multipath(family: u16, mut buf: &[u8]) -> Vec<NextHop> {
+    let mut hops = Vec::new();
+    while buf.len() >= RTNH_LEN {
+        let len = u16::from_ne_bytes([buf[0], buf[1]]) as usize;
+        if len < RTNH_LEN || len > buf.len() {
+            break;
+        }
+        let flags = buf[2];
+        let if_index = u32::from_ne_bytes([buf[4], buf[5], buf[6], buf[7]]);
+        let mut gateway = None;
+        let mut attrs = &buf[RTNH_LEN..len];
+        while attrs.len() >= 4 {
+            let attr_len = u16::from_ne_bytes([attrs[0], attrs[1]]) as usize;
+            let kind = u16::from_ne_bytes([attrs[2], attrs[3]]);
+            if attr_len < 4 || attr_len > attrs.len() {
+                break;
+            }
+            let payload = &attrs[4..attr_len];
+            match kind {
+                RTA_GATEWAY => gateway = bytes_to_ip(family, payload),
+                // IPv4 routes may have an IPv6 next hop, after its family
+                RTA_VIA if payload.len() >= 2 => {
+                    let via_family = u16::from_ne_bytes([payload[0], payload[1]]);
+                    gateway = bytes_to_ip(via_family, &payload[2..]);
+                }
+                _ => {}
+            }
+            attrs = &attrs[align4(attr_len).min(attrs.len())..];
+        }
+        if flags & RTNH_F_DEAD == 0 {
+            hops.push(NextHop {
+                gateway,
+                if_index: (if_index != 0).then_some(if_index),
+                // The kernel stores the weight minus one
+                weight: buf[3] as u16 + 1,
+            });
+        }
+        buf = &buf[align4(len).min(buf.len())..];
+    }
+    hops
+}
+
+fn message_to_route(msg: RouteMessage, table_id: u32) -> Option<RouteEntry> {
+    let family = msg.header.address_family as u16;
+    if msg.header.kind != RTN_UNICAST {
+        return None;
+    }
+    let mut destination = match family {
+        AF_INET => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        AF_INET6 => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        _ => return None,
+    };
+    let mut table = msg.header.table as u32;
+    let mut route = RouteEntry::new(IpNet::new(destination, 0));
+    for nla in msg.nlas {
+        match nla {
+            RouteNla::Destination(bytes) => destination = bytes_to_ip(family, &bytes)?,
+            RouteNla::Gateway(bytes) => route.gateway = bytes_to_ip(family, &bytes),
+            RouteNla::Oif(index) => route.if_index = Some(index),
+            RouteNla::Priority(metric) => route.metric = Some(metric),
+            RouteNla::Table(id) => table = id,
+            RouteNla::MultiPath(bytes) => route.next_hops = parse_multipath(family, &bytes),
+            _ => {}
+        }
+    }
+    if table != table_id {
+        return None;
+    }
+    if let Some(first) = route.next_hops.first() {
+        route.gateway = route.gateway.or(first.gateway);
+        route.if_index = route.if_index.or(first.if_index);
+    }
+    route.destination = IpNet::new(destination, msg.header.destination_prefix_length);
+    Some(route)
+}
+
+pub fn list_routes() -> io::Result<Vec<RouteEntry>> {
+    list_table_routes(RT_TABLE_MAIN as u32)
+}
+
+pub fn list_table_routes(table_id: u32) -> io::Result<Vec<RouteEntry>> {
+    let msgs = netlink::dump(RtnlMessage::GetRoute(RouteMessage::default()))?;
+    Ok(msgs
+        .into_iter()
+        .filter_map(|msg| match msg {
+            RtnlMessage::NewRoute(msg) => message_to_route(msg, table_id),
+            _ => None,
+        })
+        .collect())
+}
+
+pub fn lookup_route(destination: IpAddr) -> Result<RouteDecision, Error> {
+    let mut msg = RouteMessage::default();
+    let (family, bytes) = match destination {
+        IpAddr::V4(ip) => (AF_INET, ip.octets().to_vec()),
+        IpAddr::V6(ip) => (AF_INET6, ip.octets().to_vec()),
+    };
+    msg.header.address_family = family as u8;
+    msg.header.destination_prefix_length = bytes.len() as u8 * 8;
+    // Report the route from its table instead of the routing cache
+    msg.header.flags = RouteFlags::RTM_F_LOOKUP_TABLE;
+    msg.nlas.push(RouteNla::Destination(bytes));
+    let reply = netlink::get(RtnlMessage::GetRoute(msg))
+        .map_err(|e| Error::syscall("netlink(RTM_GETROUTE)", e))?;
+    match reply {
+        RtnlMessage::NewRoute(msg) => message_to_decision(msg, destination),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        Error::syscall(
+            "netlink(RTM_GETROUTE)",
+            io::Error::other("unexpected reply to a route lookup"),
+        )
+    })
+}
+
+fn message_to_decision(msg: RouteMessage, destination: IpAddr) -> Option<RouteDecision> {
+    let family = msg.header.address_family as u16;
+    let mut if_index = None;
+    let mut gateway = None;
+    let mut source = None;
+    for nla in msg.nlas {
+        match nla {
+            RouteNla::Oif(index) => if_index = Some(index),
+            RouteNla::Gateway(bytes) => gateway = bytes_to_ip(family, &bytes),
+            RouteNla::PrefSource(bytes) => source = bytes_to_ip(family, &bytes),
+            _ => {}
+        }
+    }
+    Some(RouteDecision {
+        destination,
+        if_index: if_index?,
+        gateway,
+        source,
+    })
+}
+
+fn message_to_rule(msg: RuleMessage) -> Option<RoutingRule> {
+    let family = msg.header.family as u16;
+    let (family, unspecified) = match family {
+        AF_INET => (AddressFamily::V4, IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+        AF_INET6 => (AddressFamily::V6, IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+        _ => return None,
+    };
+    let mut table = msg.header.table as u32;
+    let mut goto = None;
+    let mut rule = RoutingRule {
+        family,
+        priority: 0,
+        source: None,
+        destination: None,
+        iif: None,
+        oif: None,
+        fwmark: None,
+        fwmask: None,
+        tos: msg.header.tos,
+        invert: msg.header.flags & FIB_RULE_INVERT != 0,
+        action: RuleAction::Unknown(msg.header.action),
+    };
+    // Selectors without an address attribute match any address
+    if msg.header.src_len > 0 {
+        rule.source = Some(IpNet::new(unspecified, msg.header.src_len));
+    }
+    if msg.header.dst_len > 0 {
+        rule.destination = Some(IpNet::new(unspecified, msg.header.dst_len));
+    }
+    for nla in msg.nlas {
+        match nla {
+            RuleNla::Priority(priority) => rule.priority = priority,
+            RuleNla::Source(bytes) => {
+                rule.source = Some(IpNet::new(
+                    bytes_to_ip(family_code(family), &bytes)?,
+                    msg.header.src_len,
+                ));
+            }
+            RuleNla::Destination(bytes) => {
+                rule.destination = Some(IpNet::new(
+                    bytes_to_ip(family_code(family), &bytes)?,
+                    msg.header.dst_len,
+                ));
+            }
+            RuleNla::Iifname(name) => rule.iif = Some(name),
+            RuleNla::OifName(name) => rule.oif = Some(name),
+            RuleNla::FwMark(mark) => rule.fwmark = Some(mark),
+            RuleNla::FwMask(mask) => rule.fwmask = Some(mask),
+            RuleNla::Table(id) => table = id,
+            RuleNla::Goto(priority) => goto = Some(priority),
+            _ => {}
+        }
+    }
+    rule.action = match msg.header.action {
+        FR_ACT_TO_TBL => RuleAction::Lookup(table),
+        FR_ACT_GOTO => RuleAction::Goto(goto.unwrap_or(0)),
+        FR_ACT_NOP => RuleAction::Nop,
+        FR_ACT_BLACKHOLE => RuleAction::Blackhole,
+        FR_ACT_UNREACHABLE => RuleAction::Unreachable,
+        FR_ACT_PROHIBIT => RuleAction::Prohibit,
+        action => RuleAction::Unknown(action),
+    };
+    Some(rule)
+}
+
+fn family_code(family: AddressFamily) -> u16 {
+    match family {
+        AddressFamily::V6 => AF_INET6,
+        _ => AF_INET,
+    }
+}
+
+pub fn list_rules() -> io::Result<Vec<RoutingRule>> {
+    let msgs = netlink::dump(RtnlMessage::GetRule(RuleMessage::default()))?;
+    let mut rules: Vec<RoutingRule> = msgs
+        .into_iter()
+        .filter_map(|msg| match msg {
+            RtnlMessage::NewRule(msg) => message_to_rule(msg),
+            _ => None,
+        })
+        .collect();
+    rules.sort_by_key(|rule| rule.priority);
+    Ok(rules)
+}
+
+#[cfg(feature = "config")]
+fn ip_to_bytes(ip: IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(ip) => ip.octets().to_vec(),
+        IpAddr::V6(ip) => ip.octets().to_vec(),
+    }
+}
+
+/// Encode next hops as the payload of an `RTA_MULTIPATH` attribute
+#[cfg(feature = "config")]
+fn build_multipath(hops: &[NextHop]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for hop in hops {
+        let mut attr = Vec::new();
+        if let Some(gateway) = hop.gateway {
+            let bytes = ip_to_bytes(gateway);
+            attr.extend_from_slice(&(4 + bytes.len() as u16).to_ne_bytes());
+            attr.extend_from_slice(&RTA_GATEWAY.to_ne_bytes());
+            attr.extend_from_slice(&bytes);
+        }
+        buf.extend_from_slice(&((RTNH_LEN + attr.len()) as u16).to_ne_bytes());
+        buf.push(0);
+        buf.push(hop.weight.clamp(1, 256).saturating_sub(1) as u8);
+        buf.extend_from_slice(&hop.if_index.unwrap_or(0).to_ne_bytes());
+        buf.extend_from_slice(&attr);
+    }
+    buf
+}
+
+#[cfg(feature = "config")]
+fn route_to_message(route: &RouteEntry) -> io::Result<RouteMessage> {
+    let destination = route.destination.network();
+    if let Some(gateway) = route.gateway {
+        if gateway.is_ipv4() != destination.is_ipv4() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "gateway and destination address families differ",
+            ));
+        }
+    }
+    let mut msg = RouteMessage::default();
+    msg.header.address_family = match destination {
+        IpAddr::V4(_) => AF_INET as u8,
+        IpAddr::V6(_) => AF_INET6 as u8,
+    };
+    msg.header.destination_prefix_length = route.destination.prefix_len();
+    msg.header.table = RT_TABLE_MAIN;
+    msg.header.kind = RTN_UNICAST;
+    if route.destination.prefix_len() > 0 {
+        msg.nlas
+            .push(RouteNla::Destination(ip_to_bytes(destination)));
+    }
+    if !route.next_hops.is_empty() {
+        if route.next_hops.iter().any(|hop| {
+            hop.gateway
+                .is_some_and(|gateway| gateway.is_ipv4() != destination.is_ipv4())
+        }) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "gateway and destination address families differ",
+            ));
+        }
+        msg.nlas
+            .push(RouteNla::MultiPath(build_multipath(&route.next_hops)));
+    } else {
+        if let Some(gateway) = route.gateway {
+            msg.nlas.push(RouteNla::Gateway(ip_to_bytes(gateway)));
+        }
+        if let Some(index) = route.if_index {
+            msg.nlas.push(RouteNla::Oif(index));
+        }
+    }
+    if let Some(metric) = route.metric {
+        msg.nlas.push(RouteNla::Priority(metric));
+    }
+    Ok(msg)
+}
+
+#[cfg(feature = "config")]
+pub fn add_route_entry(route: &RouteEntry) -> io::Result<()> {
+    let mut msg = route_to_message(route)?;
+    msg.header.protocol = RTPROT_STATIC;
+    msg.header.scope = if route.gateway.is_some() || !route.next_hops.is_empty() {
+        RT_SCOPE_UNIVERSE
+    } else {
+        RT_SCOPE_LINK
+    };
+    netlink::request(RtnlMessage::NewRoute(msg), NLM_F_CREATE | NLM_F_EXCL)
+}
+
+#[cfg(feature = "config")]
+pub fn delete_route_entry(route: &RouteEntry) -> io::Result<()> {
+    let mut msg = route_to_message(route)?;
+    // Match routes of any scope, like `ip route del`
+    msg.header.scope = RT_SCOPE_NOWHERE;
+    netlink::request(RtnlMessage::DelRoute(msg), 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_to_decision() {
+        let destination = IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1));
+        let mut msg = RouteMessage::default();
+        msg.header.address_family = AF_INET as u8;
+        msg.nlas.push(RouteNla::Destination(vec![1, 1, 1, 1]));
+        assert_eq!(message_to_decision(msg.clone(), destination), None);
+        msg.nlas.push(RouteNla::Oif(2));
+        msg.nlas.push(RouteNla::Gateway(vec![192, 168, 1, 1]));
+        msg.nlas.push(RouteNla::PrefSource(vec![192, 168, 1, 20]));
+        assert_eq!(
+            message_to_decision(msg, destination),
+            Some(RouteDecision {
+                destination,
+                if_index: 2,
+                gateway: Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))),
+                source: Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20))),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_multipath() {
+        let mut buf = Vec::new();
+        // Via 192.168.1.1 on interface 2 with weight 1
+        buf.extend_from_slice(&16u16.to_ne_bytes());
+        buf.extend_from_slice(&[0, 0]);
+        buf.extend_from_slice(&2u32.to_ne_bytes());
+        buf.extend_from_slice(&8u16.to_ne_bytes());
+        buf.extend_from_slice(&RTA_GATEWAY.to_ne_bytes());
+        buf.extend_from_slice(&[192, 168, 1, 1]);
+        // Via 192.168.2.1 on interface 3 with weight 3
+        buf.extend_from_slice(&16u16.to_ne_bytes());
+        buf.extend_from_slice(&[0, 2]);
+        buf.extend_from_slice(&3u32.to_ne_bytes());
+        buf.extend_from_slice(&8u16.to_ne_bytes());
+        buf.extend_from_slice(&RTA_GATEWAY.to_ne_bytes());
+        buf.extend_from_slice(&[192, 168, 2, 1]);
+        // Dead hop on interface 4
+        buf.extend_from_slice(&8u16.to_ne_bytes());
+        buf.extend_from_slice(&[RTNH_F_DEAD, 0]);
+        buf.extend_from_slice(&4u32.to_ne_bytes());
+
+        let hops = parse_multipath(AF_INET, &buf);
+        assert_eq!(
+            hops,
+            vec![
+                NextHop {
+                    gateway: Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))),
+                    if_index: Some(2),
+                    weight: 1,
+                },
+                NextHop {
+                    gateway: Some(IpAddr::V4(Ipv4Addr::new(192, 168, 2, 1))),
+                    if_index: Some(3),
+                    weight: 3,
+                },
+            ]
+        );
+        #[cfg(feature = "config")]
+        assert_eq!(parse_multipath(AF_INET, &build_multipath(&hops)), hops);
+
+        let mut msg = RouteMessage::default();
+        msg.header.address_family = AF_INET as u8;
+        msg.header.kind = RTN_UNICAST;
+        msg.header.table = RT_TABLE_MAIN;
+        msg.nlas.push(RouteNla::MultiPath(buf));
+        let route = message_to_route(msg, RT_TABLE_MAIN as u32).unwrap();
+        assert!(route.is_default() && route.is_multipath());
+        assert_eq!(route.gateway, hops[0].gateway);
+        assert_eq!(route.if_index, Some(2));
+    }
+
+    #[test]
+    fn test_message_to_rule() {
+        let mut msg = RuleMessage::default();
+        msg.header.family = AF_INET as u8;
+        msg.header.src_len = 24;
+        msg.header.action = FR_ACT_TO_TBL;
+        msg.header.table = 100;
+        msg.nlas.push(RuleNla::Priority(1000));
+        msg.nlas.push(RuleNla::Source(vec![10, 8, 0, 0]));
+        msg.nlas.push(RuleNla::FwMark(0x1));
+        let rule = message_to_rule(msg).unwrap();
+        assert_eq!(rule.family, AddressFamily::V4);
+        assert_eq!(rule.priority, 1000);
+        assert_eq!(
+            rule.source,
+            Some(IpNet::new(IpAddr::V4(Ipv4Addr::new(10, 8, 0, 0)), 24))
+        );
+        assert_eq!(rule.destination, None);
+        assert_eq!(rule.fwmark, Some(0x1));
+        assert_eq!(rule.action, RuleAction::Lookup(100));
+
+        let mut msg = RuleMessage::default();
+        msg.header.family = AF_INET6 as u8;
+        msg.header.action = FR_ACT_GOTO;
+        msg.header.flags = FIB_RULE_INVERT;
+        msg.nlas.push(RuleNla::Goto(32766));
+        let rule = message_to_rule(msg).unwrap();
+        assert!(rule.invert);
+        assert_eq!(rule.action, RuleAction::Goto(32766));
+    }
+}
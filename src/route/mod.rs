@@ -0,0 +1,314 @@
+//! Routing table access.
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod linux;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use self::linux::*;
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+mod bsd;
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+use self::bsd::*;
+
+#[cfg(target_os = "windows")]
+mod windows;
+#[cfg(target_os = "windows")]
+use self::windows::*;
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "windows"
+)))]
+mod unsupported;
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "windows"
+)))]
+use self::unsupported::*;
+
+use crate::error::Error;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::interface::AddressFamily;
+use crate::ip::IpNet;
+use std::io;
+use std::net::IpAddr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// One of the next hops of a multipath route
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NextHop {
+    /// Next hop router. `None` for a hop directly on the link.
+    pub gateway: Option<IpAddr>,
+    /// Index of the outgoing interface
+    pub if_index: Option<u32>,
+    /// Relative share of the traffic sent through this hop, at least 1
+    pub weight: u16,
+}
+
+/// Entry of the routing table
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RouteEntry {
+    /// Destination network
+    pub destination: IpNet,
+    /// Next hop. `None` for routes directly on the link. For multipath
+    /// routes, the first of `next_hops`.
+    pub gateway: Option<IpAddr>,
+    /// Index of the outgoing interface. For multipath routes, that of the
+    /// first of `next_hops`.
+    pub if_index: Option<u32>,
+    /// Route metric. Not available on BSD and macOS.
+    pub metric: Option<u32>,
+    /// Next hops the traffic is balanced over (ECMP, `RTA_MULTIPATH` on Linux).
+    /// Empty for routes with a single next hop, given by `gateway` and `if_index`.
+    /// Other platforms list each path of a multipath route as its own entry.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub next_hops: Vec<NextHop>,
+}
+
+impl RouteEntry {
+    /// Construct a new RouteEntry for the destination network
+    pub fn new(destination: IpNet) -> RouteEntry {
+        RouteEntry {
+            destination,
+            gateway: None,
+            if_index: None,
+            metric: None,
+            next_hops: Vec::new(),
+        }
+    }
+    /// Check if this is a default route (`0.0.0.0/0` or `::/0`)
+    pub fn is_default(&self) -> bool {
+        self.destination.prefix_len() == 0
+    }
+    /// Check if the route balances traffic over more than one next hop
+    pub fn is_multipath(&self) -> bool {
+        self.next_hops.len() > 1
+    }
+    /// Returns every next hop of the route, with a weight of 1 for a route
+    /// with a single next hop
+    pub fn hops(&self) -> Vec<NextHop> {
+        if !self.next_hops.is_empty() {
+            return self.next_hops.clone();
+        }
+        vec![NextHop {
+            gateway: self.gateway,
+            if_index: self.if_index,
+            weight: 1,
+        }]
+    }
+}
+
+/// Route the kernel selected for a destination
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RouteDecision {
+    /// Destination that was looked up
+    pub destination: IpAddr,
+    /// Index of the outgoing interface
+    pub if_index: u32,
+    /// Next hop. `None` if the destination is directly on the link.
+    pub gateway: Option<IpAddr>,
+    /// Source address packets to the destination are sent from
+    pub source: Option<IpAddr>,
+}
+
+/// Ask the kernel which route it would use to reach `destination`, without
+/// sending any packet.
+///
+/// Unlike connecting a UDP socket, this also reports the gateway. Policy
+/// routing rules are taken into account on Linux.
+pub fn lookup(destination: IpAddr) -> Result<RouteDecision, Error> {
+    lookup_route(destination)
+}
+
+/// Get the entries of the main routing table
+pub fn get_routes() -> io::Result<Vec<RouteEntry>> {
+    #[cfg(feature = "mock")]
+    if let Some(provider) = crate::mock::current() {
+        return provider.routes();
+    }
+    if let Some(backend) = crate::backend::installed() {
+        return backend.routes();
+    }
+    list_routes()
+}
+
+/// Read the routing table from the OS
+pub(crate) fn os_routes() -> io::Result<Vec<RouteEntry>> {
+    list_routes()
+}
+
+/// ID of the local routing table
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub const RT_TABLE_LOCAL: u32 = 255;
+/// ID of the main routing table
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub const RT_TABLE_MAIN: u32 = 254;
+/// ID of the default routing table
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub const RT_TABLE_DEFAULT: u32 = 253;
+
+/// Action taken when a policy routing rule matches
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RuleAction {
+    /// Look up the route in the table with this ID
+    Lookup(u32),
+    /// Continue with the rule of this priority
+    Goto(u32),
+    /// Do nothing
+    Nop,
+    /// Silently drop the packet
+    Blackhole,
+    /// Reject the packet with "network unreachable"
+    Unreachable,
+    /// Reject the packet with "communication prohibited"
+    Prohibit,
+    /// Action not known to this crate (`FR_ACT_*` value)
+    Unknown(u8),
+}
+
+/// Policy routing rule, as listed by `ip rule`
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RoutingRule {
+    /// Address family the rule applies to (`V4` or `V6`)
+    pub family: AddressFamily,
+    /// Priority. Rules are evaluated in ascending order.
+    pub priority: u32,
+    /// Source prefix selector. `None` matches all sources.
+    pub source: Option<IpNet>,
+    /// Destination prefix selector. `None` matches all destinations.
+    pub destination: Option<IpNet>,
+    /// Incoming interface selector
+    pub iif: Option<String>,
+    /// Outgoing interface selector
+    pub oif: Option<String>,
+    /// Firewall mark selector
+    pub fwmark: Option<u32>,
+    /// Mask applied to the firewall mark before matching
+    pub fwmask: Option<u32>,
+    /// TOS selector, 0 matches any
+    pub tos: u8,
+    /// The selector is inverted (`ip rule add not ...`)
+    pub invert: bool,
+    /// What to do when the rule matches
+    pub action: RuleAction,
+}
+
+/// Get the policy routing rules, sorted by priority
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn rules() -> io::Result<Vec<RoutingRule>> {
+    list_rules()
+}
+
+/// Get the unicast entries of the routing table with the given ID
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn get_routes_in_table(table_id: u32) -> io::Result<Vec<RouteEntry>> {
+    list_table_routes(table_id)
+}
+
+/// Add a route (requires elevated privileges)
+#[cfg(feature = "config")]
+pub fn add_route(route: &RouteEntry) -> io::Result<()> {
+    if route.gateway.is_none() && route.if_index.is_none() && route.next_hops.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "route needs a gateway or an interface",
+        ));
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    if !route.next_hops.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "multipath routes are only supported on Linux",
+        ));
+    }
+    add_route_entry(route)
+}
+
+/// Delete a route (requires elevated privileges)
+#[cfg(feature = "config")]
+pub fn delete_route(route: &RouteEntry) -> io::Result<()> {
+    delete_route_entry(route)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_hops() {
+        let mut route = RouteEntry::new(IpNet::new(IpAddr::from([0, 0, 0, 0]), 0));
+        route.gateway = Some(IpAddr::from([192, 168, 1, 1]));
+        assert!(!route.is_multipath());
+        assert_eq!(route.hops()[0].gateway, route.gateway);
+        let hop = |last: u8, weight: u16| NextHop {
+            gateway: Some(IpAddr::from([192, 168, 1, last])),
+            if_index: Some(2),
+            weight,
+        };
+        route.next_hops = vec![hop(1, 1), hop(2, 3)];
+        assert!(route.is_multipath());
+        assert_eq!(route.hops(), route.next_hops);
+    }
+    #[test]
+    fn test_routes() {
+        println!("{:?}", get_routes());
+    }
+    #[test]
+    fn test_lookup() {
+        println!("{:?}", lookup(IpAddr::from([1, 1, 1, 1])));
+        if let Ok(decision) = lookup(IpAddr::from([127, 0, 0, 1])) {
+            assert_eq!(decision.gateway, None);
+        }
+    }
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn test_rules() {
+        let rules = rules().unwrap();
+        for rule in &rules {
+            println!("{:?}", rule);
+        }
+        assert_eq!(
+            get_routes_in_table(RT_TABLE_MAIN).unwrap(),
+            get_routes().unwrap()
+        );
+    }
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_add_route_invalid() {
+        let route = RouteEntry::new(IpNet::new(IpAddr::from([192, 0, 2, 0]), 24));
+        let err = add_route(&route).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,29 @@
+use super::{RouteDecision, RouteEntry};
+use crate::error::Error;
+use std::io;
+use std::net::IpAddr;
+
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Routing table access is not supported on this platform",
+    )
+}
+
+pub fn list_routes() -> io::Result<Vec<RouteEntry>> {
+    Err(unsupported())
+}
+
+pub fn lookup_route(_destination: IpAddr) -> Result<RouteDecision, Error> {
+    Err(Error::syscall("route lookup", unsupported()))
+}
+
+#[cfg(feature = "config")]
+pub fn add_route_entry(_route: &RouteEntry) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(feature = "config")]
+pub fn delete_route_entry(_route: &RouteEntry) -> io::Result<()> {
+    Err(unsupported())
+}
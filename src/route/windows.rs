@@ -0,0 +1,154 @@
+use super::{RouteDecision, RouteEntry};
+use crate::error::Error;
+use crate::ip::IpNet;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use windows_sys::Win32::Foundation::NO_ERROR;
+use windows_sys::Win32::NetworkManagement::IpHelper::{
+    FreeMibTable, GetBestRoute2, GetIpForwardTable2, MIB_IPFORWARD_ROW2, MIB_IPFORWARD_TABLE2,
+};
+use windows_sys::Win32::Networking::WinSock::{AF_INET, AF_INET6, AF_UNSPEC, SOCKADDR_INET};
+
+#[cfg(feature = "config")]
+use windows_sys::Win32::NetworkManagement::IpHelper::{
+    CreateIpForwardEntry2, DeleteIpForwardEntry2, InitializeIpForwardEntry,
+};
+#[cfg(feature = "config")]
+use windows_sys::Win32::Networking::WinSock::MIB_IPPROTO_NETMGMT;
+
+fn sockaddr_to_ip(addr: &SOCKADDR_INET) -> Option<IpAddr> {
+    unsafe {
+        match addr.si_family {
+            AF_INET => Some(IpAddr::V4(Ipv4Addr::from(
+                addr.Ipv4.sin_addr.S_un.S_addr.to_ne_bytes(),
+            ))),
+            AF_INET6 => Some(IpAddr::V6(Ipv6Addr::from(addr.Ipv6.sin6_addr.u.Byte))),
+            _ => None,
+        }
+    }
+}
+
+pub fn list_routes() -> io::Result<Vec<RouteEntry>> {
+    let mut table: *mut MIB_IPFORWARD_TABLE2 = std::ptr::null_mut();
+    let ret = unsafe { GetIpForwardTable2(AF_UNSPEC, &mut table) };
+    if ret != NO_ERROR {
+        return Err(io::Error::from_raw_os_error(ret as i32));
+    }
+    let rows = unsafe {
+        std::slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize)
+    };
+    let mut routes = Vec::new();
+    for row in rows {
+        let destination = match sockaddr_to_ip(&row.DestinationPrefix.Prefix) {
+            Some(ip) => ip,
+            None => continue,
+        };
+        routes.push(RouteEntry {
+            destination: IpNet::new(destination, row.DestinationPrefix.PrefixLength),
+            gateway: sockaddr_to_ip(&row.NextHop).filter(|ip| !ip.is_unspecified()),
+            if_index: Some(row.InterfaceIndex),
+            metric: Some(row.Metric),
+            next_hops: Vec::new(),
+        });
+    }
+    unsafe { FreeMibTable(table as *const _) };
+    Ok(routes)
+}
+
+fn ip_to_sockaddr(ip: IpAddr) -> SOCKADDR_INET {
+    let mut addr: SOCKADDR_INET = unsafe { std::mem::zeroed() };
+    match ip {
+        IpAddr::V4(ip) => {
+            addr.Ipv4.sin_family = AF_INET;
+            addr.Ipv4.sin_addr.S_un.S_addr = u32::from_ne_bytes(ip.octets());
+        }
+        IpAddr::V6(ip) => {
+            addr.Ipv6.sin6_family = AF_INET6;
+            addr.Ipv6.sin6_addr.u.Byte = ip.octets();
+        }
+    }
+    addr
+}
+
+pub fn lookup_route(destination: IpAddr) -> Result<RouteDecision, Error> {
+    let dst = ip_to_sockaddr(destination);
+    let mut row: MIB_IPFORWARD_ROW2 = unsafe { std::mem::zeroed() };
+    let mut source: SOCKADDR_INET = unsafe { std::mem::zeroed() };
+    let ret = unsafe {
+        GetBestRoute2(
+            std::ptr::null(),
+            0,
+            std::ptr::null(),
+            &dst,
+            0,
+            &mut row,
+            &mut source,
+        )
+    };
+    if ret != NO_ERROR {
+        return Err(Error::syscall(
+            "GetBestRoute2",
+            io::Error::from_raw_os_error(ret as i32),
+        ));
+    }
+    Ok(RouteDecision {
+        destination,
+        if_index: row.InterfaceIndex,
+        gateway: sockaddr_to_ip(&row.NextHop).filter(|ip| !ip.is_unspecified()),
+        source: sockaddr_to_ip(&source),
+    })
+}
+
+#[cfg(feature = "config")]
+fn route_to_row(route: &RouteEntry) -> io::Result<MIB_IPFORWARD_ROW2> {
+    let destination = route.destination.network();
+    let if_index = route.if_index.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "an interface index is required on Windows",
+        )
+    })?;
+    let gateway = match route.gateway {
+        Some(gateway) if gateway.is_ipv4() != destination.is_ipv4() => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "gateway and destination address families differ",
+            ));
+        }
+        Some(gateway) => gateway,
+        // On-link routes have an unspecified next hop of the same family
+        None => match destination {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        },
+    };
+    let mut row: MIB_IPFORWARD_ROW2 = unsafe { std::mem::zeroed() };
+    unsafe { InitializeIpForwardEntry(&mut row) };
+    row.InterfaceIndex = if_index;
+    row.DestinationPrefix.Prefix = ip_to_sockaddr(destination);
+    row.DestinationPrefix.PrefixLength = route.destination.prefix_len();
+    row.NextHop = ip_to_sockaddr(gateway);
+    row.Protocol = MIB_IPPROTO_NETMGMT;
+    if let Some(metric) = route.metric {
+        row.Metric = metric;
+    }
+    Ok(row)
+}
+
+#[cfg(feature = "config")]
+pub fn add_route_entry(route: &RouteEntry) -> io::Result<()> {
+    let row = route_to_row(route)?;
+    match unsafe { CreateIpForwardEntry2(&row) } {
+        NO_ERROR => Ok(()),
+        err => Err(io::Error::from_raw_os_error(err as i32)),
+    }
+}
+
+#[cfg(feature = "config")]
+pub fn delete_route_entry(route: &RouteEntry) -> io::Result<()> {
+    let row = route_to_row(route)?;
+    match unsafe { DeleteIpForwardEntry2(&row) } {
+        NO_ERROR => Ok(()),
+        err => Err(io::Error::from_raw_os_error(err as i32)),
+    }
+}
@@ -0,0 +1,61 @@
+use crate::interface::Interface;
+use crate::raw::{Bpf, BpfInsn};
+use std::io::{self, Read};
+use std::os::fd::AsRawFd;
+
+/// `ether proto arp`, from `tcpdump -dd arp`
+const ARP_FILTER: [BpfInsn; 4] = [
+    BpfInsn::new(0x28, 0, 0, 0x0000000c),
+    BpfInsn::new(0x15, 0, 1, 0x00000806),
+    BpfInsn::new(0x06, 0, 0, 0x00040000),
+    BpfInsn::new(0x06, 0, 0, 0x00000000),
+];
+
+/// BPF device capturing the ARP frames of the interface
+pub(super) struct Link {
+    bpf: Bpf,
+    buf: Vec<u8>,
+}
+
+impl Link {
+    pub(super) fn open(interface: &Interface) -> io::Result<Link> {
+        let bpf = Bpf::open(interface)?;
+        bpf.set_immediate(true)?;
+        bpf.set_filter(&ARP_FILTER)?;
+        let fd = bpf.as_raw_fd();
+        let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+        if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let buf = vec![0; bpf.buffer_len()];
+        Ok(Link { bpf, buf })
+    }
+    pub(super) fn send(&mut self, frame: &[u8]) -> io::Result<()> {
+        let ret = unsafe {
+            libc::write(
+                self.bpf.as_raw_fd(),
+                frame.as_ptr() as *const libc::c_void,
+                frame.len(),
+            )
+        };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+    /// Pass each frame captured so far to `f`, without waiting
+    pub(super) fn recv(&mut self, f: &mut impl FnMut(&[u8])) -> io::Result<()> {
+        loop {
+            let n = match self.bpf.read(&mut self.buf) {
+                Ok(0) => return Ok(()),
+                Ok(n) => n,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            for packet in Bpf::packets(&self.buf[..n]) {
+                f(packet.data);
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::interface::Interface;
+use crate::raw;
+use std::io;
+use std::os::fd::{AsRawFd, OwnedFd};
+
+/// `AF_PACKET` socket receiving the ARP frames of the interface
+pub(super) struct Link {
+    fd: OwnedFd,
+}
+
+impl Link {
+    pub(super) fn open(interface: &Interface) -> io::Result<Link> {
+        let fd = raw::packet_socket(interface, super::ETHERTYPE_ARP)?;
+        Ok(Link { fd })
+    }
+    pub(super) fn send(&mut self, frame: &[u8]) -> io::Result<()> {
+        let ret = unsafe {
+            libc::send(
+                self.fd.as_raw_fd(),
+                frame.as_ptr() as *const libc::c_void,
+                frame.len(),
+                0,
+            )
+        };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+    /// Pass each frame received so far to `f`, without waiting
+    pub(super) fn recv(&mut self, f: &mut impl FnMut(&[u8])) -> io::Result<()> {
+        let mut buf = [0u8; 1514];
+        loop {
+            let n = unsafe {
+                libc::recv(
+                    self.fd.as_raw_fd(),
+                    buf.as_mut_ptr() as *mut libc::c_void,
+                    buf.len(),
+                    libc::MSG_DONTWAIT,
+                )
+            };
+            if n < 0 {
+                let err = io::Error::last_os_error();
+                return match err.kind() {
+                    io::ErrorKind::WouldBlock => Ok(()),
+                    io::ErrorKind::Interrupted => continue,
+                    _ => Err(err),
+                };
+            }
+            f(&buf[..n as usize]);
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! Discovery of hosts on the local network.
+//!
+//! [`arp_scan`] sends an ARP request to every address of the IPv4 subnets
+//! attached to an interface and collects the replies. It uses the
+//! [`raw`](crate::raw) link-layer access, so it needs the same privileges:
+//! `CAP_NET_RAW` on Linux and Android, access to `/dev/bpf*` on macOS and the BSDs.
+//! [`ndp_scan`] does the same for IPv6 with Neighbor Discovery over raw ICMPv6
+//! sockets, which need root or `CAP_NET_RAW`.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! let interface = netdev::get_default_interface().unwrap();
+//! for (ip, mac) in netdev::scan::arp_scan(&interface, Duration::from_secs(1)).unwrap() {
+//!     println!("{} is at {}", ip, mac);
+//! }
+//! ```
+
+mod ndp;
+pub use self::ndp::*;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod linux;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use self::linux::*;
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+mod bpf;
+#[cfg(any(
+    target_os = "macos",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+use self::bpf::*;
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+)))]
+mod unsupported;
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+)))]
+use self::unsupported::*;
+
+use crate::interface::Interface;
+use crate::ip::Ipv4Net;
+use crate::mac::MacAddr;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Shortest prefix length of the subnets swept by [`arp_scan`]; wider subnets
+/// are skipped as sweeping them would take minutes
+pub const MIN_PREFIX_LEN: u8 = 16;
+
+/// EtherType of ARP
+const ETHERTYPE_ARP: u16 = 0x0806;
+/// Length of an ARP request frame, padded to the Ethernet minimum
+const FRAME_LEN: usize = 60;
+/// Number of ARP requests sent per second, which keeps a /24 under a second
+const ARP_RATE: u32 = 1000;
+
+/// Spaces out the packets of a scan to at most `rate` per second
+struct Pacer {
+    interval: Duration,
+    next: Instant,
+}
+
+impl Pacer {
+    fn new(rate: u32) -> Pacer {
+        Pacer {
+            interval: Duration::from_secs(1) / rate.max(1),
+            next: Instant::now(),
+        }
+    }
+    /// Check if the next packet may be sent now, and if so count it as sent
+    fn ready(&mut self) -> bool {
+        let now = Instant::now();
+        if now < self.next {
+            return false;
+        }
+        self.next = self.next.max(now - self.interval) + self.interval;
+        true
+    }
+    /// Wait until the next packet may be sent and count it as sent
+    fn wait(&mut self) {
+        thread::sleep(self.next.saturating_duration_since(Instant::now()));
+        self.ready();
+    }
+}
+
+/// Find the hosts on the IPv4 subnets of `interface` with ARP requests.
+///
+/// Every address of each subnet with a prefix length of at least
+/// [`MIN_PREFIX_LEN`] is asked for once, and the answers are collected until
+/// `timeout` after the last request. Returns each host that answered with its
+/// MAC address, ordered by address.
+pub fn arp_scan(interface: &Interface, timeout: Duration) -> io::Result<Vec<(IpAddr, MacAddr)>> {
+    let mac_addr = interface.mac_addr.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} has no MAC address", interface.name),
+        )
+    })?;
+    let nets: Vec<Ipv4Net> = interface
+        .ipv4
+        .iter()
+        .filter(|net| net.prefix_len >= MIN_PREFIX_LEN && net.prefix_len < 32)
+        .copied()
+        .collect();
+    let own: Vec<Ipv4Addr> = nets.iter().map(|net| net.addr).collect();
+    let mut found: Vec<(Ipv4Addr, MacAddr)> = Vec::new();
+    if nets.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut link = Link::open(interface)?;
+    let mut pacer = Pacer::new(ARP_RATE);
+    let mut collect = |frame: &[u8]| {
+        if let Some((ip, mac)) = parse_arp_reply(frame, &own) {
+            if !found.iter().any(|(known, _)| *known == ip) {
+                found.push((ip, mac));
+            }
+        }
+    };
+    for target in interface.hosts_v4() {
+        // Targets only on a subnet too large to sweep are skipped
+        let Some(net) = nets.iter().find(|net| net.contains(target)) else {
+            continue;
+        };
+        pacer.wait();
+        link.send(&build_arp_request(mac_addr, net.addr, target))?;
+        link.recv(&mut collect)?;
+    }
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        link.recv(&mut collect)?;
+        thread::sleep(Duration::from_millis(10));
+    }
+    found.sort();
+    Ok(found
+        .into_iter()
+        .map(|(ip, mac)| (IpAddr::V4(ip), mac))
+        .collect())
+}
+
+/// Build a broadcast Ethernet frame asking who has `target`
+fn build_arp_request(
+    sender_mac: MacAddr,
+    sender_ip: Ipv4Addr,
+    target: Ipv4Addr,
+) -> [u8; FRAME_LEN] {
+    let mut frame = [0u8; FRAME_LEN];
+    frame[0..6].copy_from_slice(&MacAddr::broadcast().octets());
+    frame[6..12].copy_from_slice(&sender_mac.octets());
+    frame[12..14].copy_from_slice(&ETHERTYPE_ARP.to_be_bytes());
+    let arp = &mut frame[14..42];
+    arp[0..2].copy_from_slice(&1u16.to_be_bytes()); // Ethernet
+    arp[2..4].copy_from_slice(&0x0800u16.to_be_bytes()); // IPv4
+    arp[4] = 6;
+    arp[5] = 4;
+    arp[6..8].copy_from_slice(&1u16.to_be_bytes()); // request
+    arp[8..14].copy_from_slice(&sender_mac.octets());
+    arp[14..18].copy_from_slice(&sender_ip.octets());
+    arp[24..28].copy_from_slice(&target.octets());
+    frame
+}
+
+/// Returns the sender of `frame` if it is an ARP reply to one of the `own` addresses
+fn parse_arp_reply(frame: &[u8], own: &[Ipv4Addr]) -> Option<(Ipv4Addr, MacAddr)> {
+    if frame.len() < 42 || frame[12..14] != ETHERTYPE_ARP.to_be_bytes() {
+        return None;
+    }
+    let arp = &frame[14..42];
+    if arp[2..4] != [0x08, 0x00] || arp[4] != 6 || arp[5] != 4 || arp[6..8] != [0, 2] {
+        return None;
+    }
+    let sha = MacAddr::from_octets(arp[8..14].try_into().ok()?);
+    let spa = Ipv4Addr::from(<[u8; 4]>::try_from(&arp[14..18]).ok()?);
+    let tpa = Ipv4Addr::from(<[u8; 4]>::try_from(&arp[24..28]).ok()?);
+    if own.contains(&tpa) && spa != tpa {
+        Some((spa, sha))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arp_reply() {
+        let own_mac = MacAddr::new(0x02, 0, 0, 0, 0, 1);
+        let peer_mac = MacAddr::new(0x02, 0, 0, 0, 0, 2);
+        let own = Ipv4Addr::new(192, 168, 1, 20);
+        let peer = Ipv4Addr::new(192, 168, 1, 1);
+        let request = build_arp_request(own_mac, own, peer);
+        // Our own request is not a reply
+        assert_eq!(parse_arp_reply(&request, &[own]), None);
+
+        let mut reply = request;
+        reply[0..6].copy_from_slice(&own_mac.octets());
+        reply[6..12].copy_from_slice(&peer_mac.octets());
+        reply[21] = 2;
+        reply[22..28].copy_from_slice(&peer_mac.octets());
+        reply[28..32].copy_from_slice(&peer.octets());
+        reply[32..38].copy_from_slice(&own_mac.octets());
+        reply[38..42].copy_from_slice(&own.octets());
+        assert_eq!(parse_arp_reply(&reply, &[own]), Some((peer, peer_mac)));
+        assert_eq!(parse_arp_reply(&reply, &[Ipv4Addr::new(10, 0, 0, 1)]), None);
+        assert_eq!(parse_arp_reply(&reply[..40], &[own]), None);
+    }
+
+    #[test]
+    fn test_pacer() {
+        let mut pacer = Pacer::new(10);
+        assert!(pacer.ready());
+        assert!(!pacer.ready());
+        let start = Instant::now();
+        pacer.wait();
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[test]
+    #[ignore = "scans the local network"]
+    fn test_arp_scan() {
+        if let Ok(interface) = crate::get_default_interface() {
+            println!("{:?}", arp_scan(&interface, Duration::from_millis(100)));
+        }
+    }
+}
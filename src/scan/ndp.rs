@@ -0,0 +1,287 @@
+use super::Pacer;
+use crate::interface::Interface;
+use crate::mac::MacAddr;
+use crate::sys;
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+use std::io;
+use std::mem::MaybeUninit;
+use std::net::{IpAddr, Ipv6Addr, SocketAddrV6};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const ICMPV6_ECHO_REQUEST: u8 = 128;
+const ICMPV6_ECHO_REPLY: u8 = 129;
+const ND_NEIGHBOR_SOLICIT: u8 = 135;
+const ND_NEIGHBOR_ADVERT: u8 = 136;
+
+const ND_OPT_SOURCE_LINKADDR: u8 = 1;
+const ND_OPT_TARGET_LINKADDR: u8 = 2;
+
+/// Neighbor Discovery messages are only accepted with the maximum hop limit
+const ND_HOP_LIMIT: u32 = 255;
+/// Length of the fixed part of a Neighbor Solicitation or Advertisement
+const NS_HEADER_LEN: usize = 24;
+
+/// All-nodes link-local multicast address
+pub const ALL_NODES: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 1);
+
+/// Default number of Neighbor Solicitations [`ndp_scan`] sends per second
+pub const DEFAULT_RATE: u32 = 100;
+
+/// Message of interest received during a scan
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+enum Reply {
+    /// Answer to our echo request to all nodes
+    Echo,
+    /// Neighbor Advertisement for a target, with its link-layer address if included
+    Advert(Ipv6Addr, Option<MacAddr>),
+}
+
+/// Solicited-node multicast address of `addr` (RFC 4291)
+fn solicited_node(addr: Ipv6Addr) -> Ipv6Addr {
+    let o = addr.octets();
+    Ipv6Addr::new(
+        0xff02,
+        0,
+        0,
+        0,
+        0,
+        1,
+        0xff00 | o[13] as u16,
+        u16::from_be_bytes([o[14], o[15]]),
+    )
+}
+
+/// Build an echo request. The kernel fills in the checksum.
+fn build_echo_request(id: u16, seq: u16) -> Vec<u8> {
+    let mut msg = vec![ICMPV6_ECHO_REQUEST, 0, 0, 0];
+    msg.extend_from_slice(&id.to_be_bytes());
+    msg.extend_from_slice(&seq.to_be_bytes());
+    msg
+}
+
+/// Build a Neighbor Solicitation for `target`, with the Source Link-Layer
+/// Address option if `mac_addr` is known. The kernel fills in the checksum.
+fn build_neighbor_solicit(target: Ipv6Addr, mac_addr: Option<MacAddr>) -> Vec<u8> {
+    let mut msg = vec![ND_NEIGHBOR_SOLICIT, 0, 0, 0, 0, 0, 0, 0];
+    msg.extend_from_slice(&target.octets());
+    if let Some(mac_addr) = mac_addr {
+        msg.extend_from_slice(&[ND_OPT_SOURCE_LINKADDR, 1]);
+        msg.extend_from_slice(&mac_addr.octets());
+    }
+    msg
+}
+
+/// Parse an ICMPv6 message, returning `None` if it is neither an echo reply
+/// with identifier `id` nor a valid Neighbor Advertisement
+fn parse_reply(buf: &[u8], id: u16) -> Option<Reply> {
+    if buf.len() < 8 || buf[1] != 0 {
+        return None;
+    }
+    match buf[0] {
+        ICMPV6_ECHO_REPLY if buf[4..6] == id.to_be_bytes() => Some(Reply::Echo),
+        ND_NEIGHBOR_ADVERT if buf.len() >= NS_HEADER_LEN => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(&buf[8..24]);
+            let mut mac_addr = None;
+            let mut options = &buf[NS_HEADER_LEN..];
+            while options.len() >= 2 {
+                // Option lengths are in units of 8 octets, and zero is invalid
+                let len = options[1] as usize * 8;
+                if len == 0 || len > options.len() {
+                    return None;
+                }
+                if options[0] == ND_OPT_TARGET_LINKADDR && len >= 8 {
+                    let mut mac = [0u8; 6];
+                    mac.copy_from_slice(&options[2..8]);
+                    mac_addr = Some(MacAddr::from_octets(mac));
+                }
+                options = &options[len..];
+            }
+            Some(Reply::Advert(Ipv6Addr::from(octets), mac_addr))
+        }
+        _ => None,
+    }
+}
+
+/// Raw ICMPv6 socket bound to one of the interface's addresses
+fn open_socket(interface: &Interface, addr: Ipv6Addr) -> io::Result<Socket> {
+    let socket = Socket::new(Domain::IPV6, Type::RAW, Some(Protocol::ICMPV6))?;
+    let scope_id = if crate::zone::is_link_local(&addr) {
+        interface.index
+    } else {
+        0
+    };
+    socket.bind(&SockAddr::from(SocketAddrV6::new(addr, 0, 0, scope_id)))?;
+    socket.set_multicast_if_v6(interface.index)?;
+    socket.set_multicast_hops_v6(ND_HOP_LIMIT)?;
+    socket.set_unicast_hops_v6(ND_HOP_LIMIT)?;
+    sys::set_recv_hop_limit(&socket)?;
+    socket.set_nonblocking(true)?;
+    Ok(socket)
+}
+
+/// Find the IPv6 hosts on the link of `interface` with Neighbor Discovery,
+/// sending at most [`DEFAULT_RATE`] solicitations per second.
+///
+/// See [`ndp_scan_with_rate`].
+pub fn ndp_scan(interface: &Interface, timeout: Duration) -> io::Result<Vec<(IpAddr, MacAddr)>> {
+    ndp_scan_with_rate(interface, timeout, DEFAULT_RATE)
+}
+
+/// Find the IPv6 hosts on the link of `interface` with Neighbor Discovery.
+///
+/// IPv6 subnets are too large to sweep, so the hosts are found by pinging
+/// the all-nodes group from each of the interface's addresses. Every address
+/// that answers is then sent a Neighbor Solicitation on its solicited-node
+/// group, at most `rate` per second, and the Neighbor Advertisements are
+/// collected until `timeout` after the last solicitation. Returns each
+/// address that advertised its MAC address, ordered by address. Hosts that
+/// ignore multicast pings, as Windows does by default, are only found if they
+/// happen to advertise themselves during the scan. Advertisements are only
+/// accepted with a hop limit of 255, as RFC 4861 requires.
+///
+/// Opens raw ICMPv6 sockets, which needs root or `CAP_NET_RAW`. Not supported
+/// on Windows, whose raw sockets do not report the hop limit.
+pub fn ndp_scan_with_rate(
+    interface: &Interface,
+    timeout: Duration,
+    rate: u32,
+) -> io::Result<Vec<(IpAddr, MacAddr)>> {
+    let own: Vec<Ipv6Addr> = interface.ipv6.iter().map(|net| net.addr).collect();
+    let mut sockets = Vec::new();
+    let mut last_err = None;
+    for addr in &own {
+        match open_socket(interface, *addr) {
+            Ok(socket) => sockets.push(socket),
+            // Tentative or deprecated addresses cannot be bound to
+            Err(e) => last_err = Some(e),
+        }
+    }
+    if sockets.is_empty() {
+        return match last_err {
+            Some(e) => Err(e),
+            None => Ok(Vec::new()),
+        };
+    }
+    // Raw sockets see every echo reply on the host, so other scans and pings
+    // of this process must use their own identifier
+    let id = crate::dns::random_id();
+    let all_nodes = SockAddr::from(SocketAddrV6::new(ALL_NODES, 0, 0, interface.index));
+    for (seq, socket) in sockets.iter().enumerate() {
+        socket.send_to(&build_echo_request(id, seq as u16), &all_nodes)?;
+    }
+
+    let mut pacer = Pacer::new(rate);
+    // Addresses to solicit, with the index of the socket that heard them
+    let mut pending: Vec<(Ipv6Addr, usize)> = Vec::new();
+    let mut solicited: Vec<Ipv6Addr> = Vec::new();
+    let mut found: Vec<(Ipv6Addr, MacAddr)> = Vec::new();
+    let mut deadline = Instant::now() + timeout;
+    let mut buf = [MaybeUninit::<u8>::uninit(); 1500];
+    while Instant::now() < deadline || !pending.is_empty() {
+        for (i, socket) in sockets.iter().enumerate() {
+            loop {
+                let (len, src, hop_limit) = match sys::recv_with_hop_limit(socket, &mut buf) {
+                    Ok(received) => received,
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                };
+                // SAFETY: recvmsg initialized the first `len` bytes
+                let msg = unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const u8, len) };
+                // Messages from other links carry their own scope
+                if src.scope_id() != 0 && src.scope_id() != interface.index {
+                    continue;
+                }
+                match parse_reply(msg, id) {
+                    Some(Reply::Echo) => {
+                        let addr = *src.ip();
+                        if !own.contains(&addr) && !solicited.contains(&addr) {
+                            solicited.push(addr);
+                            pending.push((addr, i));
+                        }
+                    }
+                    // Forwarded advertisements are not from the link
+                    Some(Reply::Advert(target, Some(mac_addr)))
+                        if hop_limit.map(u32::from) == Some(ND_HOP_LIMIT)
+                            && !own.contains(&target)
+                            && !found.iter().any(|(ip, _)| *ip == target) =>
+                    {
+                        found.push((target, mac_addr));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        while !pending.is_empty() && pacer.ready() {
+            let (target, i) = pending.remove(0);
+            if found.iter().any(|(ip, _)| *ip == target) {
+                continue;
+            }
+            let dst = SockAddr::from(SocketAddrV6::new(
+                solicited_node(target),
+                0,
+                0,
+                interface.index,
+            ));
+            sockets[i].send_to(&build_neighbor_solicit(target, interface.mac_addr), &dst)?;
+            deadline = deadline.max(Instant::now() + timeout);
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    found.sort();
+    Ok(found
+        .into_iter()
+        .map(|(ip, mac)| (IpAddr::V6(ip), mac))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solicited_node() {
+        let addr: Ipv6Addr = "2001:db8::2aa:ff:fe28:9c5a".parse().unwrap();
+        assert_eq!(
+            solicited_node(addr),
+            "ff02::1:ff28:9c5a".parse::<Ipv6Addr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        let mut echo = build_echo_request(0x1234, 1);
+        assert_eq!(parse_reply(&echo, 0x1234), None);
+        echo[0] = ICMPV6_ECHO_REPLY;
+        assert_eq!(parse_reply(&echo, 0x1234), Some(Reply::Echo));
+        assert_eq!(parse_reply(&echo, 0x4321), None);
+
+        let target: Ipv6Addr = "fe80::2".parse().unwrap();
+        let mac = MacAddr::new(0x02, 0, 0, 0, 0, 2);
+        let solicit = build_neighbor_solicit(target, Some(MacAddr::new(0x02, 0, 0, 0, 0, 1)));
+        assert_eq!(&solicit[24..], &[1, 1, 0x02, 0, 0, 0, 0, 1]);
+        assert_eq!(parse_reply(&solicit, 0), None);
+
+        let mut advert = vec![ND_NEIGHBOR_ADVERT, 0, 0, 0, 0x60, 0, 0, 0];
+        advert.extend_from_slice(&target.octets());
+        assert_eq!(parse_reply(&advert, 0), Some(Reply::Advert(target, None)));
+        advert.extend_from_slice(&[ND_OPT_TARGET_LINKADDR, 1]);
+        advert.extend_from_slice(&mac.octets());
+        assert_eq!(
+            parse_reply(&advert, 0),
+            Some(Reply::Advert(target, Some(mac)))
+        );
+        // Truncated option
+        assert_eq!(parse_reply(&advert[..advert.len() - 1], 0), None);
+    }
+
+    #[test]
+    #[ignore = "scans the local network"]
+    fn test_ndp_scan() {
+        if let Ok(interface) = crate::get_default_interface() {
+            println!("{:?}", ndp_scan(&interface, Duration::from_millis(200)));
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use crate::interface::Interface;
+use std::io;
+
+pub(super) struct Link;
+
+impl Link {
+    pub(super) fn open(_interface: &Interface) -> io::Result<Link> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "ARP scan is not supported on this platform",
+        ))
+    }
+    pub(super) fn send(&mut self, _frame: &[u8]) -> io::Result<()> {
+        Ok(())
+    }
+    pub(super) fn recv(&mut self, _f: &mut impl FnMut(&[u8])) -> io::Result<()> {
+        Ok(())
+    }
+}
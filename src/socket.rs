@@ -0,0 +1,195 @@
+//! Traffic marking for sockets.
+//!
+//! [`set_dscp`] sets the Differentiated Services code point (RFC 2474) of the
+//! packets a socket sends, through `IP_TOS` or `IPV6_TCLASS` depending on its
+//! family, and [`set_priority`] the Linux queueing priority. Together with
+//! [`Interface::bind_socket`](crate::Interface::bind_socket), applications can
+//! choose both the interface their traffic leaves through and how it is treated.
+//!
+//! ```no_run
+//! use socket2::{Domain, Socket, Type};
+//!
+//! let socket = Socket::new(Domain::IPV4, Type::DGRAM, None).unwrap();
+//! let interface = netdev::get_default_interface().unwrap();
+//! interface.bind_socket(&socket).unwrap();
+//! // Expedited Forwarding, for voice
+//! netdev::socket::set_dscp(&socket, netdev::socket::DSCP_EF).unwrap();
+//! ```
+
+use socket2::Socket;
+use std::io;
+
+/// Default forwarding, best effort
+pub const DSCP_DEFAULT: u8 = 0;
+/// Expedited Forwarding (RFC 3246), for low-latency traffic such as voice
+pub const DSCP_EF: u8 = 46;
+/// Assured Forwarding class 4, low drop precedence (RFC 2597), for interactive video
+pub const DSCP_AF41: u8 = 34;
+/// Class selector 1, lower effort (RFC 8622), for background transfers
+pub const DSCP_CS1: u8 = 8;
+
+/// Largest DSCP value, which is 6 bits wide
+const DSCP_MAX: u8 = 63;
+/// ECN bits of the TOS byte, left untouched
+#[cfg(unix)]
+const ECN_MASK: u32 = 0x03;
+
+/// Set the DSCP of the packets sent by `socket`.
+///
+/// The ECN bits of the TOS or traffic class byte are preserved. On Linux, an
+/// IPv6 socket also gets the IPv4 TOS set, for the traffic it sends to
+/// IPv4-mapped addresses.
+///
+/// Windows ignores these socket options and marks traffic through QoS
+/// policies instead, so an error of kind `Unsupported` is returned there, as
+/// for IPv6 sockets on iOS.
+pub fn set_dscp(socket: &Socket, dscp: u8) -> io::Result<()> {
+    if dscp > DSCP_MAX {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "DSCP must be between 0 and 63",
+        ));
+    }
+    set_traffic_class(socket, dscp)
+}
+
+/// Returns the DSCP of the packets sent by `socket`
+pub fn dscp(socket: &Socket) -> io::Result<u8> {
+    traffic_class(socket).map(|class| (class >> 2) as u8)
+}
+
+/// Set the priority of the packets sent by `socket`, which selects the queue
+/// of the outgoing interface they go to and, on VLAN interfaces, their PCP.
+///
+/// Values above 6 need `CAP_NET_ADMIN`. Only supported on Linux and Android.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn set_priority(socket: &Socket, priority: u32) -> io::Result<()> {
+    socket.set_priority(priority)
+}
+
+/// Set the priority of the packets sent by `socket`.
+///
+/// Not supported on this platform.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub fn set_priority(_socket: &Socket, _priority: u32) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Setting the socket priority is not supported on this platform",
+    ))
+}
+
+#[cfg(unix)]
+fn set_traffic_class(socket: &Socket, dscp: u8) -> io::Result<()> {
+    let v6 = socket.local_addr()?.is_ipv6();
+    let class = (dscp as u32) << 2 | (traffic_class(socket)? & ECN_MASK);
+    if v6 {
+        set_tclass_v6(socket, class)?;
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        if let Err(e) = socket.set_tos_v4(class) {
+            debug!("IP_TOS on an IPv6 socket failed: {}", e);
+        }
+        Ok(())
+    } else {
+        socket.set_tos_v4(class)
+    }
+}
+
+#[cfg(unix)]
+fn traffic_class(socket: &Socket) -> io::Result<u32> {
+    if socket.local_addr()?.is_ipv6() {
+        tclass_v6(socket)
+    } else {
+        socket.tos_v4()
+    }
+}
+
+#[cfg(all(unix, not(target_os = "ios")))]
+fn set_tclass_v6(socket: &Socket, class: u32) -> io::Result<()> {
+    socket.set_tclass_v6(class)
+}
+
+#[cfg(all(unix, not(target_os = "ios")))]
+fn tclass_v6(socket: &Socket) -> io::Result<u32> {
+    socket.tclass_v6()
+}
+
+// socket2 has no IPV6_TCLASS accessors on iOS
+#[cfg(target_os = "ios")]
+fn set_tclass_v6(_socket: &Socket, _class: u32) -> io::Result<()> {
+    Err(tclass_unsupported())
+}
+
+#[cfg(target_os = "ios")]
+fn tclass_v6(_socket: &Socket) -> io::Result<u32> {
+    Err(tclass_unsupported())
+}
+
+#[cfg(target_os = "ios")]
+fn tclass_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "The traffic class of IPv6 sockets is not supported on this platform",
+    )
+}
+
+#[cfg(not(unix))]
+fn set_traffic_class(_socket: &Socket, _dscp: u8) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Setting the DSCP of a socket is not supported on this platform",
+    ))
+}
+
+#[cfg(not(unix))]
+fn traffic_class(_socket: &Socket) -> io::Result<u32> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Getting the DSCP of a socket is not supported on this platform",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use socket2::{Domain, SockAddr, Type};
+    use std::net::SocketAddr;
+
+    fn udp_socket(addr: &str) -> Option<Socket> {
+        let addr: SocketAddr = addr.parse().unwrap();
+        let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, None).ok()?;
+        socket.bind(&SockAddr::from(addr)).ok()?;
+        Some(socket)
+    }
+
+    #[test]
+    fn test_dscp() {
+        let socket = udp_socket("127.0.0.1:0").unwrap();
+        assert_eq!(
+            set_dscp(&socket, 64).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        #[cfg(unix)]
+        {
+            set_dscp(&socket, DSCP_EF).unwrap();
+            assert_eq!(dscp(&socket).unwrap(), DSCP_EF);
+            set_dscp(&socket, DSCP_DEFAULT).unwrap();
+            assert_eq!(dscp(&socket).unwrap(), DSCP_DEFAULT);
+            #[cfg(not(target_os = "ios"))]
+            if let Some(socket) = udp_socket("[::1]:0") {
+                set_dscp(&socket, DSCP_AF41).unwrap();
+                assert_eq!(dscp(&socket).unwrap(), DSCP_AF41);
+            }
+        }
+        #[cfg(not(unix))]
+        assert_eq!(
+            set_dscp(&socket, DSCP_EF).unwrap_err().kind(),
+            io::ErrorKind::Unsupported
+        );
+    }
+
+    #[test]
+    fn test_set_priority() {
+        let socket = udp_socket("127.0.0.1:0").unwrap();
+        println!("{:?}", set_priority(&socket, 4));
+    }
+}
@@ -0,0 +1,177 @@
+use super::{ListeningSocket, Protocol};
+use crate::gateway::linux::read_proc_net;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+// include/net/tcp_states.h
+const TCP_LISTEN: u8 = 0x0a;
+const TCP_CLOSE: u8 = 0x07;
+
+/// Addresses in /proc/net/{tcp,udp}* are printed as 32-bit words in host byte order.
+fn parse_hex_addr(hex: &str) -> Option<IpAddr> {
+    let mut octets = Vec::with_capacity(16);
+    for i in (0..hex.len()).step_by(8) {
+        let word = u32::from_str_radix(hex.get(i..i + 8)?, 16).ok()?;
+        octets.extend_from_slice(&word.to_ne_bytes());
+    }
+    match octets.len() {
+        4 => Some(IpAddr::V4(Ipv4Addr::from(
+            <[u8; 4]>::try_from(octets).ok()?,
+        ))),
+        16 => Some(IpAddr::V6(Ipv6Addr::from(
+            <[u8; 16]>::try_from(octets).ok()?,
+        ))),
+        _ => None,
+    }
+}
+
+fn parse_socket_addr(field: &str) -> Option<SocketAddr> {
+    let (addr, port) = field.split_once(':')?;
+    Some(SocketAddr::new(
+        parse_hex_addr(addr)?,
+        u16::from_str_radix(port, 16).ok()?,
+    ))
+}
+
+/// Parse a socket table, returning the listening sockets and their inodes
+fn parse_socket_table(text: &str, protocol: Protocol) -> Vec<(ListeningSocket, u64)> {
+    let mut sockets = Vec::new();
+    for row in text.lines().skip(1) {
+        let fields: Vec<&str> = row.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+        // fields[1]: local address, fields[2]: remote address
+        // fields[3]: state, fields[9]: inode
+        let state = u8::from_str_radix(fields[3], 16).unwrap_or(0);
+        let listening = match protocol {
+            Protocol::Tcp => state == TCP_LISTEN,
+            Protocol::Udp => state == TCP_CLOSE,
+        };
+        if !listening {
+            continue;
+        }
+        let local_addr = match parse_socket_addr(fields[1]) {
+            Some(addr) => addr,
+            None => continue,
+        };
+        let inode = fields[9].parse().unwrap_or(0);
+        sockets.push((
+            ListeningSocket {
+                protocol,
+                local_addr,
+                pid: None,
+                if_index: None,
+            },
+            inode,
+        ));
+    }
+    sockets
+}
+
+/// Map socket inodes to the processes holding them, skipping processes we cannot inspect
+fn socket_owners() -> HashMap<u64, u32> {
+    let mut owners = HashMap::new();
+    let entries = match fs::read_dir("/proc") {
+        Ok(entries) => entries,
+        Err(_) => return owners,
+    };
+    for entry in entries.flatten() {
+        let pid: u32 = match entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse().ok())
+        {
+            Some(pid) => pid,
+            None => continue,
+        };
+        let fds = match fs::read_dir(entry.path().join("fd")) {
+            Ok(fds) => fds,
+            Err(_) => continue,
+        };
+        for fd in fds.flatten() {
+            if let Ok(target) = fs::read_link(fd.path()) {
+                if let Some(inode) = target
+                    .to_str()
+                    .and_then(|target| target.strip_prefix("socket:["))
+                    .and_then(|target| target.strip_suffix(']'))
+                    .and_then(|inode| inode.parse().ok())
+                {
+                    owners.entry(inode).or_insert(pid);
+                }
+            }
+        }
+    }
+    owners
+}
+
+pub fn list_listening_sockets() -> io::Result<Vec<ListeningSocket>> {
+    let mut sockets = Vec::new();
+    let tables = [
+        ("tcp", Protocol::Tcp),
+        ("tcp6", Protocol::Tcp),
+        ("udp", Protocol::Udp),
+        ("udp6", Protocol::Udp),
+    ];
+    let mut found = false;
+    for (file, protocol) in tables {
+        // IPv6 tables are missing when IPv6 is disabled
+        if let Ok(text) = read_proc_net(file) {
+            found = true;
+            sockets.extend(parse_socket_table(&text, protocol));
+        }
+    }
+    if !found {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "socket tables not found in /proc/net",
+        ));
+    }
+    let owners = socket_owners();
+    Ok(sockets
+        .into_iter()
+        .map(|(mut socket, inode)| {
+            socket.pid = owners.get(&inode).copied();
+            socket
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_socket_table() {
+        let text = if cfg!(target_endian = "little") {
+            "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0035 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 871 1 0
+   1: 0100007F:E242 0100007F:BC8F 01 00000000:00000000 02:00000000 00000000     0        0 50035 2 0
+"
+        } else {
+            "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 7F000001:0035 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 871 1 0
+   1: 7F000001:E242 7F000001:BC8F 01 00000000:00000000 02:00000000 00000000     0        0 50035 2 0
+"
+        };
+        let sockets = parse_socket_table(text, Protocol::Tcp);
+        assert_eq!(sockets.len(), 1);
+        assert_eq!(sockets[0].0.local_addr, "127.0.0.1:53".parse().unwrap());
+        assert_eq!(sockets[0].1, 871);
+        assert!(parse_socket_table(text, Protocol::Udp).is_empty());
+    }
+
+    #[test]
+    fn test_parse_hex_addr_v6() {
+        let hex = if cfg!(target_endian = "little") {
+            "00000000000000000000000001000000"
+        } else {
+            "00000000000000000000000000000001"
+        };
+        assert_eq!(parse_hex_addr(hex), Some(IpAddr::V6(Ipv6Addr::LOCALHOST)));
+    }
+}
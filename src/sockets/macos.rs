@@ -0,0 +1,260 @@
+use super::{ListeningSocket, Protocol};
+use std::io;
+use std::mem::{size_of, MaybeUninit};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::os::raw::{c_int, c_void};
+
+// sys/proc_info.h
+const PROC_PIDFDSOCKETINFO: c_int = 3;
+const SOCKINFO_IN: c_int = 1;
+const SOCKINFO_TCP: c_int = 2;
+const TSI_S_LISTEN: c_int = 1;
+const INI_IPV4: u8 = 0x1;
+const INI_IPV6: u8 = 0x2;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct proc_fileinfo {
+    fi_openflags: u32,
+    fi_status: u32,
+    fi_offset: i64,
+    fi_type: i32,
+    fi_guardflags: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct vinfo_stat {
+    vst_dev: u32,
+    vst_mode: u16,
+    vst_nlink: u16,
+    vst_ino: u64,
+    vst_uid: u32,
+    vst_gid: u32,
+    vst_atime: i64,
+    vst_atimensec: i64,
+    vst_mtime: i64,
+    vst_mtimensec: i64,
+    vst_ctime: i64,
+    vst_ctimensec: i64,
+    vst_birthtime: i64,
+    vst_birthtimensec: i64,
+    vst_size: i64,
+    vst_blocks: i64,
+    vst_blksize: i32,
+    vst_flags: u32,
+    vst_gen: u32,
+    vst_rdev: u32,
+    vst_qspare: [i64; 2],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct sockbuf_info {
+    sbi_cc: u32,
+    sbi_hiwat: u32,
+    sbi_mbcnt: u32,
+    sbi_mbmax: u32,
+    sbi_lowat: u32,
+    sbi_flags: i16,
+    sbi_timeo: i16,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct in6_sockinfo {
+    in6_hlim: u8,
+    in6_cksum: c_int,
+    in6_ifindex: u16,
+    in6_hops: i16,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct in_sockinfo {
+    insi_fport: c_int,
+    insi_lport: c_int,
+    insi_gencnt: u64,
+    insi_flags: u32,
+    insi_flow: u32,
+    insi_vflag: u8,
+    insi_ip_ttl: u8,
+    rfu_1: u32,
+    /// union of in4in6_addr and in6_addr
+    insi_faddr: [u32; 4],
+    insi_laddr: [u32; 4],
+    insi_v4: u8,
+    insi_v6: in6_sockinfo,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct tcp_sockinfo {
+    tcpsi_ini: in_sockinfo,
+    tcpsi_state: c_int,
+    tcpsi_timer: [c_int; 4],
+    tcpsi_mss: c_int,
+    tcpsi_flags: u32,
+    rfu_1: u32,
+    tcpsi_tp: u64,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+union soi_proto {
+    pri_in: in_sockinfo,
+    pri_tcp: tcp_sockinfo,
+    /// Size of the largest member, un_sockinfo
+    _size: [u64; 66],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct socket_info {
+    soi_stat: vinfo_stat,
+    soi_so: u64,
+    soi_pcb: u64,
+    soi_type: c_int,
+    soi_protocol: c_int,
+    soi_family: c_int,
+    soi_options: i16,
+    soi_linger: i16,
+    soi_state: i16,
+    soi_qlen: i16,
+    soi_incqlen: i16,
+    soi_qlimit: i16,
+    soi_timeo: i16,
+    soi_error: u16,
+    soi_oobmark: u32,
+    soi_rcv: sockbuf_info,
+    soi_snd: sockbuf_info,
+    soi_kind: c_int,
+    rfu_1: u32,
+    soi_proto: soi_proto,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct socket_fdinfo {
+    pfi: proc_fileinfo,
+    psi: socket_info,
+}
+
+fn list_pids() -> io::Result<Vec<libc::pid_t>> {
+    let count = unsafe { libc::proc_listallpids(std::ptr::null_mut(), 0) };
+    if count <= 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // Leave room for processes started in between the calls
+    let mut pids: Vec<libc::pid_t> = vec![0; count as usize + 64];
+    let count = unsafe {
+        libc::proc_listallpids(
+            pids.as_mut_ptr() as *mut c_void,
+            (pids.len() * size_of::<libc::pid_t>()) as c_int,
+        )
+    };
+    if count <= 0 {
+        return Err(io::Error::last_os_error());
+    }
+    pids.truncate(count as usize);
+    Ok(pids)
+}
+
+fn list_fds(pid: libc::pid_t) -> Vec<libc::proc_fdinfo> {
+    let size =
+        unsafe { libc::proc_pidinfo(pid, libc::PROC_PIDLISTFDS, 0, std::ptr::null_mut(), 0) };
+    if size <= 0 {
+        return Vec::new();
+    }
+    let mut fds: Vec<libc::proc_fdinfo> =
+        Vec::with_capacity(size as usize / size_of::<libc::proc_fdinfo>());
+    let size = unsafe {
+        libc::proc_pidinfo(
+            pid,
+            libc::PROC_PIDLISTFDS,
+            0,
+            fds.as_mut_ptr() as *mut c_void,
+            (fds.capacity() * size_of::<libc::proc_fdinfo>()) as c_int,
+        )
+    };
+    if size <= 0 {
+        return Vec::new();
+    }
+    unsafe { fds.set_len(size as usize / size_of::<libc::proc_fdinfo>()) };
+    fds
+}
+
+fn socket_addr(vflag: u8, addr: &[u32; 4], port: c_int) -> Option<SocketAddr> {
+    let port = u16::from_be(port as u16);
+    let octets: [u8; 16] = unsafe { std::mem::transmute(*addr) };
+    let ip = if vflag & INI_IPV4 != 0 {
+        // in4in6_addr: the IPv4 address is in the last word
+        IpAddr::V4(Ipv4Addr::new(
+            octets[12], octets[13], octets[14], octets[15],
+        ))
+    } else if vflag & INI_IPV6 != 0 {
+        IpAddr::V6(Ipv6Addr::from(octets))
+    } else {
+        return None;
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+fn socket_info(pid: libc::pid_t, fd: c_int) -> Option<ListeningSocket> {
+    let mut info = MaybeUninit::<socket_fdinfo>::zeroed();
+    let size = unsafe {
+        libc::proc_pidfdinfo(
+            pid,
+            fd,
+            PROC_PIDFDSOCKETINFO,
+            info.as_mut_ptr() as *mut c_void,
+            size_of::<socket_fdinfo>() as c_int,
+        )
+    };
+    if size <= 0 {
+        return None;
+    }
+    let psi = unsafe { info.assume_init() }.psi;
+    let (protocol, ini) = match psi.soi_kind {
+        SOCKINFO_TCP => {
+            let tcp = unsafe { psi.soi_proto.pri_tcp };
+            if tcp.tcpsi_state != TSI_S_LISTEN {
+                return None;
+            }
+            (Protocol::Tcp, tcp.tcpsi_ini)
+        }
+        SOCKINFO_IN if psi.soi_protocol == libc::IPPROTO_UDP => {
+            let ini = unsafe { psi.soi_proto.pri_in };
+            // Connected UDP sockets have a foreign port
+            if ini.insi_fport != 0 {
+                return None;
+            }
+            (Protocol::Udp, ini)
+        }
+        _ => return None,
+    };
+    Some(ListeningSocket {
+        protocol,
+        local_addr: socket_addr(ini.insi_vflag, &ini.insi_laddr, ini.insi_lport)?,
+        pid: Some(pid as u32),
+        if_index: None,
+    })
+}
+
+pub fn list_listening_sockets() -> io::Result<Vec<ListeningSocket>> {
+    let mut sockets = Vec::new();
+    for pid in list_pids()? {
+        for fd in list_fds(pid) {
+            if fd.proc_fdtype != libc::PROX_FDTYPE_SOCKET as u32 {
+                continue;
+            }
+            if let Some(socket) = socket_info(pid, fd.proc_fd) {
+                // A socket duplicated within a process is reported once
+                if !sockets.contains(&socket) {
+                    sockets.push(socket);
+                }
+            }
+        }
+    }
+    Ok(sockets)
+}
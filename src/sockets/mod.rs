@@ -0,0 +1,103 @@
+//! Inventory of listening TCP and UDP sockets.
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod linux;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use self::linux::*;
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "macos")]
+use self::macos::*;
+
+#[cfg(target_os = "windows")]
+mod windows;
+#[cfg(target_os = "windows")]
+use self::windows::*;
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "windows"
+)))]
+mod unsupported;
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "windows"
+)))]
+use self::unsupported::*;
+
+use crate::interface::{self, Interface};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Transport protocol of a socket
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+/// TCP socket in the listen state or unconnected UDP socket
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ListeningSocket {
+    /// Transport protocol
+    pub protocol: Protocol,
+    /// Local address and port the socket is bound to
+    pub local_addr: SocketAddr,
+    /// ID of the owning process, if it may be inspected by the caller
+    pub pid: Option<u32>,
+    /// Index of the interface owning the local address.
+    /// `None` for sockets bound to the wildcard address.
+    pub if_index: Option<u32>,
+}
+
+impl ListeningSocket {
+    /// Check if the socket is bound to the wildcard address (`0.0.0.0` or `::`)
+    pub fn is_wildcard(&self) -> bool {
+        self.local_addr.ip().is_unspecified()
+    }
+    /// Check if the socket accepts traffic addressed to the network interface
+    pub fn is_bound_to(&self, interface: &Interface) -> bool {
+        match self.local_addr.ip() {
+            IpAddr::V4(ip) => {
+                ip.is_unspecified() || interface.ipv4.iter().any(|net| net.addr == ip)
+            }
+            IpAddr::V6(ip) => {
+                ip.is_unspecified() || interface.ipv6.iter().any(|net| net.addr == ip)
+            }
+        }
+    }
+}
+
+/// Get the listening TCP sockets and unconnected UDP sockets.
+///
+/// The owning process is only reported for processes the caller is allowed to inspect.
+pub fn listening() -> io::Result<Vec<ListeningSocket>> {
+    let mut sockets = list_listening_sockets()?;
+    let interfaces = interface::get_interfaces();
+    for socket in sockets.iter_mut().filter(|socket| !socket.is_wildcard()) {
+        socket.if_index = interfaces
+            .iter()
+            .find(|iface| socket.is_bound_to(iface))
+            .map(|iface| iface.index);
+    }
+    Ok(sockets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_listening() {
+        println!("{:?}", listening());
+    }
+}
@@ -0,0 +1,9 @@
+use super::ListeningSocket;
+use std::io;
+
+pub fn list_listening_sockets() -> io::Result<Vec<ListeningSocket>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Listing sockets is not supported on this platform",
+    ))
+}
@@ -0,0 +1,111 @@
+use super::{ListeningSocket, Protocol};
+use std::ffi::c_void;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
+use windows_sys::Win32::Foundation::{ERROR_INSUFFICIENT_BUFFER, FALSE, NO_ERROR};
+use windows_sys::Win32::NetworkManagement::IpHelper::{
+    GetExtendedTcpTable, GetExtendedUdpTable, MIB_TCP6ROW_OWNER_PID, MIB_TCPROW_OWNER_PID,
+    MIB_UDP6ROW_OWNER_PID, MIB_UDPROW_OWNER_PID, TCP_TABLE_OWNER_PID_LISTENER, UDP_TABLE_OWNER_PID,
+};
+use windows_sys::Win32::Networking::WinSock::{AF_INET, AF_INET6};
+
+/// Run a GetExtended*Table query, growing the buffer as requested, and copy out the rows.
+///
+/// The tables are a `DWORD dwNumEntries` followed by the rows.
+fn query_table<R: Copy>(query: impl Fn(*mut c_void, *mut u32) -> u32) -> io::Result<Vec<R>> {
+    let mut size: u32 = 0;
+    let mut buf: Vec<u64> = Vec::new();
+    loop {
+        let ret = query(buf.as_mut_ptr() as *mut c_void, &mut size);
+        match ret {
+            NO_ERROR => break,
+            ERROR_INSUFFICIENT_BUFFER => buf.resize((size as usize).div_ceil(8), 0),
+            err => return Err(io::Error::from_raw_os_error(err as i32)),
+        }
+    }
+    if buf.is_empty() {
+        return Ok(Vec::new());
+    }
+    let base = buf.as_ptr() as *const u8;
+    let count = unsafe { *(base as *const u32) } as usize;
+    let rows_offset = std::mem::size_of::<u32>().next_multiple_of(std::mem::align_of::<R>());
+    if rows_offset + count * std::mem::size_of::<R>() > size as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "socket table is truncated",
+        ));
+    }
+    let rows = unsafe { base.add(rows_offset) as *const R };
+    Ok((0..count)
+        .map(|i| unsafe { std::ptr::read_unaligned(rows.add(i)) })
+        .collect())
+}
+
+/// Ports are stored in network byte order in the low 16 bits
+fn port(dw_port: u32) -> u16 {
+    u16::from_be(dw_port as u16)
+}
+
+fn ipv4(dw_addr: u32) -> IpAddr {
+    IpAddr::V4(Ipv4Addr::from(dw_addr.to_ne_bytes()))
+}
+
+fn ipv6(addr: [u8; 16], port: u16, scope_id: u32) -> SocketAddr {
+    SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::from(addr), port, 0, scope_id))
+}
+
+fn socket(protocol: Protocol, local_addr: SocketAddr, pid: u32) -> ListeningSocket {
+    ListeningSocket {
+        protocol,
+        local_addr,
+        pid: Some(pid),
+        if_index: None,
+    }
+}
+
+pub fn list_listening_sockets() -> io::Result<Vec<ListeningSocket>> {
+    let mut sockets = Vec::new();
+    let tcp4: Vec<MIB_TCPROW_OWNER_PID> = query_table(|buf, size| unsafe {
+        GetExtendedTcpTable(
+            buf,
+            size,
+            FALSE,
+            AF_INET as u32,
+            TCP_TABLE_OWNER_PID_LISTENER,
+            0,
+        )
+    })?;
+    for row in tcp4 {
+        let addr = SocketAddr::new(ipv4(row.dwLocalAddr), port(row.dwLocalPort));
+        sockets.push(socket(Protocol::Tcp, addr, row.dwOwningPid));
+    }
+    let tcp6: Vec<MIB_TCP6ROW_OWNER_PID> = query_table(|buf, size| unsafe {
+        GetExtendedTcpTable(
+            buf,
+            size,
+            FALSE,
+            AF_INET6 as u32,
+            TCP_TABLE_OWNER_PID_LISTENER,
+            0,
+        )
+    })?;
+    for row in tcp6 {
+        let addr = ipv6(row.ucLocalAddr, port(row.dwLocalPort), row.dwLocalScopeId);
+        sockets.push(socket(Protocol::Tcp, addr, row.dwOwningPid));
+    }
+    let udp4: Vec<MIB_UDPROW_OWNER_PID> = query_table(|buf, size| unsafe {
+        GetExtendedUdpTable(buf, size, FALSE, AF_INET as u32, UDP_TABLE_OWNER_PID, 0)
+    })?;
+    for row in udp4 {
+        let addr = SocketAddr::new(ipv4(row.dwLocalAddr), port(row.dwLocalPort));
+        sockets.push(socket(Protocol::Udp, addr, row.dwOwningPid));
+    }
+    let udp6: Vec<MIB_UDP6ROW_OWNER_PID> = query_table(|buf, size| unsafe {
+        GetExtendedUdpTable(buf, size, FALSE, AF_INET6 as u32, UDP_TABLE_OWNER_PID, 0)
+    })?;
+    for row in udp6 {
+        let addr = ipv6(row.ucLocalAddr, port(row.dwLocalPort), row.dwLocalScopeId);
+        sockets.push(socket(Protocol::Udp, addr, row.dwOwningPid));
+    }
+    Ok(sockets)
+}
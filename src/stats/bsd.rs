@@ -0,0 +1,64 @@
+use super::InterfaceStats;
+use crate::interface::Interface;
+use std::ffi::CStr;
+use std::mem::MaybeUninit;
+use std::time::SystemTime;
+
+/// Convert the `if_data` attached to an `AF_LINK` ifaddrs entry.
+pub(crate) fn stats_from_if_data(data: &libc::if_data) -> InterfaceStats {
+    InterfaceStats {
+        rx_bytes: counter(data.ifi_ibytes),
+        tx_bytes: counter(data.ifi_obytes),
+        rx_packets: counter(data.ifi_ipackets),
+        tx_packets: counter(data.ifi_opackets),
+        rx_errors: counter(data.ifi_ierrors),
+        tx_errors: counter(data.ifi_oerrors),
+        rx_dropped: counter(data.ifi_iqdrops),
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd"))]
+        tx_dropped: data.ifi_oqdrops,
+        #[cfg(not(any(target_os = "freebsd", target_os = "openbsd")))]
+        tx_dropped: 0,
+        timestamp: Some(SystemTime::now()),
+        ..Default::default()
+    }
+}
+
+/// Widen an `if_data` counter, which is 32 bits wide on Apple platforms
+fn counter(value: impl Into<u64>) -> u64 {
+    value.into()
+}
+
+/// Read counters from an ifaddrs entry if it carries link-level data.
+pub(crate) fn stats_from_ifaddrs(addr_ref: &libc::ifaddrs) -> Option<InterfaceStats> {
+    if addr_ref.ifa_addr.is_null() || addr_ref.ifa_data.is_null() {
+        return None;
+    }
+    if unsafe { (*addr_ref.ifa_addr).sa_family } as libc::c_int != libc::AF_LINK {
+        return None;
+    }
+    let data = unsafe { &*(addr_ref.ifa_data as *const libc::if_data) };
+    Some(stats_from_if_data(data))
+}
+
+pub(crate) fn get_interface_stats(interface: &Interface) -> Option<InterfaceStats> {
+    let mut addrs: MaybeUninit<*mut libc::ifaddrs> = MaybeUninit::uninit();
+    if unsafe { libc::getifaddrs(addrs.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    let addrs = unsafe { addrs.assume_init() };
+    let mut stats = None;
+    let mut addr = addrs;
+    while !addr.is_null() {
+        let addr_ref: &libc::ifaddrs = unsafe { &*addr };
+        let name = unsafe { CStr::from_ptr(addr_ref.ifa_name) };
+        if name.to_bytes() == interface.name.as_bytes() {
+            stats = stats_from_ifaddrs(addr_ref);
+            if stats.is_some() {
+                break;
+            }
+        }
+        addr = addr_ref.ifa_next;
+    }
+    unsafe { libc::freeifaddrs(addrs) };
+    stats
+}
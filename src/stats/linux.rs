@@ -0,0 +1,221 @@
+use super::{InterfaceStats, QueueStats};
+use crate::interface::Interface;
+use crate::sys::{ethtool, netlink};
+use std::fs::read_to_string;
+use std::io;
+use std::time::SystemTime;
+
+fn read_counter(if_name: &str, counter: &str) -> Option<u64> {
+    let path = format!("/sys/class/net/{}/statistics/{}", if_name, counter);
+    read_to_string(path).ok()?.trim().parse().ok()
+}
+
+const RTM_GETLINK: u16 = 18;
+const IFLA_IFNAME: u16 = 3;
+const IFLA_STATS64: u16 = 23;
+/// Size of `struct ifinfomsg`
+const IFINFOMSG_LEN: usize = 16;
+
+/// Decode the leading counters of a `struct rtnl_link_stats64`
+pub(crate) fn stats_from_stats64(bytes: &[u8]) -> Option<InterfaceStats> {
+    let counter = |i: usize| {
+        let field = bytes.get(i * 8..i * 8 + 8)?;
+        Some(u64::from_ne_bytes(field.try_into().ok()?))
+    };
+    Some(InterfaceStats {
+        rx_packets: counter(0)?,
+        tx_packets: counter(1)?,
+        rx_bytes: counter(2)?,
+        tx_bytes: counter(3)?,
+        rx_errors: counter(4)?,
+        tx_errors: counter(5)?,
+        rx_dropped: counter(6)?,
+        tx_dropped: counter(7)?,
+        timestamp: Some(SystemTime::now()),
+        ..Default::default()
+    })
+}
+
+/// Decode the index, name and 64-bit counters of an `RTM_NEWLINK` message
+fn parse_link_stats(payload: &[u8]) -> Option<(u32, String, InterfaceStats)> {
+    let index = netlink::read_u32(payload, 4)?;
+    let mut name = None;
+    let mut stats = None;
+    for (kind, attr) in netlink::iter_nlas(payload.get(IFINFOMSG_LEN..)?) {
+        match kind {
+            // NUL-terminated
+            IFLA_IFNAME => {
+                name = Some(String::from_utf8_lossy(attr.split(|b| *b == 0).next()?).into_owned())
+            }
+            IFLA_STATS64 => stats = stats_from_stats64(attr),
+            _ => {}
+        }
+    }
+    Some((index, name?, stats?))
+}
+
+/// Set the counters of `interfaces` from a single `RTM_GETLINK` dump, so that
+/// they are all read at the same time and as 64-bit values. Interfaces are
+/// matched by index and name, so that counters cannot be given to an
+/// interface that replaced the one they belong to.
+pub(crate) fn add_link_stats(interfaces: &mut [Interface]) -> io::Result<()> {
+    let replies = netlink::dump_raw(RTM_GETLINK, &[0u8; IFINFOMSG_LEN])?;
+    for (index, name, stats) in replies
+        .iter()
+        .filter_map(|payload| parse_link_stats(payload))
+    {
+        if let Some(iface) = interfaces
+            .iter_mut()
+            .find(|iface| iface.index == index && iface.name == name)
+        {
+            iface.stats = Some(stats);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn get_interface_stats(interface: &Interface) -> Option<InterfaceStats> {
+    let name = interface.name.as_str();
+    Some(InterfaceStats {
+        rx_bytes: read_counter(name, "rx_bytes")?,
+        tx_bytes: read_counter(name, "tx_bytes")?,
+        rx_packets: read_counter(name, "rx_packets").unwrap_or(0),
+        tx_packets: read_counter(name, "tx_packets").unwrap_or(0),
+        rx_errors: read_counter(name, "rx_errors").unwrap_or(0),
+        tx_errors: read_counter(name, "tx_errors").unwrap_or(0),
+        rx_dropped: read_counter(name, "rx_dropped").unwrap_or(0),
+        tx_dropped: read_counter(name, "tx_dropped").unwrap_or(0),
+        timestamp: Some(SystemTime::now()),
+        ..Default::default()
+    })
+}
+
+/// Counter of one queue, decoded from a driver statistic name
+#[derive(Debug, PartialEq)]
+struct QueueCounter {
+    tx: bool,
+    index: u32,
+    bytes: bool,
+}
+
+/// Decode the per-queue statistic names used by common drivers:
+/// `rx_queue_0_packets` (virtio_net, igb, ixgbe), `rx-0.rx_packets` (i40e, ice),
+/// `rx-0.packets` and `rx0_packets` (mlx5)
+fn parse_queue_counter(name: &str) -> Option<QueueCounter> {
+    let (tx, rest) = if let Some(rest) = name.strip_prefix("rx") {
+        (false, rest)
+    } else {
+        (true, name.strip_prefix("tx")?)
+    };
+    let rest = rest
+        .strip_prefix("_queue_")
+        .or_else(|| rest.strip_prefix('-'))
+        .unwrap_or(rest);
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    let index = rest[..digits].parse().ok()?;
+    let counter = rest[digits..]
+        .strip_prefix('_')
+        .or_else(|| rest[digits..].strip_prefix('.'))?;
+    let direction = if tx { "tx_" } else { "rx_" };
+    let bytes = match counter.strip_prefix(direction).unwrap_or(counter) {
+        "bytes" => true,
+        "packets" => false,
+        _ => return None,
+    };
+    Some(QueueCounter { tx, index, bytes })
+}
+
+/// Add the driver statistics and the per-queue counters derived from them.
+/// Drivers without statistics, such as loopback, leave `stats` unchanged.
+pub(crate) fn add_driver_stats(stats: &mut InterfaceStats, if_name: &str) {
+    let names = match ethtool::get_strings(if_name, ethtool::ETH_SS_STATS) {
+        Ok(names) => names,
+        Err(_) => return,
+    };
+    let values = match ethtool::get_stats(if_name, names.len()) {
+        Ok(values) => values,
+        Err(_) => return,
+    };
+    for (name, value) in names.into_iter().zip(values) {
+        if let Some(counter) = parse_queue_counter(&name) {
+            let queues = if counter.tx {
+                &mut stats.tx_queues
+            } else {
+                &mut stats.rx_queues
+            };
+            let queue = match queues.iter().position(|q| q.index == counter.index) {
+                Some(pos) => &mut queues[pos],
+                None => {
+                    queues.push(QueueStats {
+                        index: counter.index,
+                        ..Default::default()
+                    });
+                    queues.last_mut().unwrap()
+                }
+            };
+            if counter.bytes {
+                queue.bytes = value;
+            } else {
+                queue.packets = value;
+            }
+        }
+        stats.nic.insert(name, value);
+    }
+    stats.rx_queues.sort_by_key(|q| q.index);
+    stats.tx_queues.sort_by_key(|q| q.index);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_link_stats() {
+        let mut msg = vec![0u8; IFINFOMSG_LEN];
+        msg[4..8].copy_from_slice(&7u32.to_ne_bytes());
+        // IFLA_IFNAME "eth0"
+        msg.extend_from_slice(&9u16.to_ne_bytes());
+        msg.extend_from_slice(&IFLA_IFNAME.to_ne_bytes());
+        msg.extend_from_slice(b"eth0\0\0\0\0");
+        // IFLA_STATS64 with the eight leading counters, the bytes beyond 4 GiB
+        msg.extend_from_slice(&(4u16 + 64).to_ne_bytes());
+        msg.extend_from_slice(&IFLA_STATS64.to_ne_bytes());
+        for value in [10u64, 20, 5 << 32, 6 << 32, 1, 2, 3, 4] {
+            msg.extend_from_slice(&value.to_ne_bytes());
+        }
+        let (index, name, stats) = parse_link_stats(&msg).unwrap();
+        assert_eq!((index, name.as_str()), (7, "eth0"));
+        assert_eq!((stats.rx_packets, stats.tx_packets), (10, 20));
+        assert_eq!((stats.rx_bytes, stats.tx_bytes), (5 << 32, 6 << 32));
+        assert_eq!((stats.rx_errors, stats.tx_errors), (1, 2));
+        assert_eq!((stats.rx_dropped, stats.tx_dropped), (3, 4));
+        // Truncated counters
+        assert!(parse_link_stats(&msg[..msg.len() - 8]).is_none());
+    }
+
+    #[test]
+    fn test_parse_queue_counter() {
+        let counter = |tx, index, bytes| Some(QueueCounter { tx, index, bytes });
+        assert_eq!(
+            parse_queue_counter("rx_queue_0_packets"),
+            counter(false, 0, false)
+        );
+        assert_eq!(
+            parse_queue_counter("tx_queue_12_bytes"),
+            counter(true, 12, true)
+        );
+        assert_eq!(
+            parse_queue_counter("rx-3.rx_bytes"),
+            counter(false, 3, true)
+        );
+        assert_eq!(parse_queue_counter("tx-1.packets"), counter(true, 1, false));
+        assert_eq!(parse_queue_counter("rx7_packets"), counter(false, 7, false));
+        assert_eq!(parse_queue_counter("rx_queue_0_drops"), None);
+        assert_eq!(parse_queue_counter("rx_packets"), None);
+        assert_eq!(parse_queue_counter("rx_queue_0_xdp_packets"), None);
+        assert_eq!(parse_queue_counter("peer_ifindex"), None);
+    }
+}
@@ -0,0 +1,115 @@
+//! Interface traffic counters and rate computation.
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod linux;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(crate) use self::linux::*;
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+mod bsd;
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+pub(crate) use self::bsd::*;
+
+#[cfg(target_os = "windows")]
+mod windows;
+#[cfg(target_os = "windows")]
+pub(crate) use self::windows::*;
+
+mod rate;
+pub use self::rate::*;
+
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Counters of one hardware queue
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QueueStats {
+    /// Queue number
+    pub index: u32,
+    /// Packets through the queue
+    pub packets: u64,
+    /// Bytes through the queue
+    pub bytes: u64,
+}
+
+/// Traffic counters of a network interface
+///
+/// Counters are cumulative since the interface was created or the system booted.
+/// Some platforms keep them as 32-bit values, so they may wrap around.
+///
+/// The per-queue and driver counters are only filled in by
+/// [`Interface::update_detailed_stats`](crate::interface::Interface::update_detailed_stats).
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InterfaceStats {
+    /// Bytes received
+    pub rx_bytes: u64,
+    /// Bytes transmitted
+    pub tx_bytes: u64,
+    /// Packets received
+    pub rx_packets: u64,
+    /// Packets transmitted
+    pub tx_packets: u64,
+    /// Receive errors
+    pub rx_errors: u64,
+    /// Transmit errors
+    pub tx_errors: u64,
+    /// Received packets dropped
+    pub rx_dropped: u64,
+    /// Transmit packets dropped
+    pub tx_dropped: u64,
+    /// Time at which the counters were read
+    pub timestamp: Option<SystemTime>,
+    /// Receive queue counters, ordered by queue number (Linux only)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rx_queues: Vec<QueueStats>,
+    /// Transmit queue counters, ordered by queue number (Linux only)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tx_queues: Vec<QueueStats>,
+    /// Driver-specific counters by name, as listed by `ethtool -S` (Linux only)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub nic: BTreeMap<String, u64>,
+}
+
+/// Read the aggregate counters plus, where supported, the driver and queue counters
+pub(crate) fn get_detailed_stats(
+    interface: &crate::interface::Interface,
+) -> Option<InterfaceStats> {
+    #[allow(unused_mut)]
+    let mut stats = get_interface_stats(interface)?;
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    add_driver_stats(&mut stats, &interface.name);
+    Some(stats)
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "windows"
+)))]
+pub(crate) fn get_interface_stats(
+    _interface: &crate::interface::Interface,
+) -> Option<InterfaceStats> {
+    None
+}
@@ -0,0 +1,179 @@
+use super::InterfaceStats;
+use crate::interface::Interface;
+use std::collections::HashMap;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Traffic rate of a network interface over one sampling interval
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InterfaceRate {
+    /// Index of network interface
+    pub index: u32,
+    /// Name of network interface
+    pub name: String,
+    /// Receive rate in bits per second
+    pub rx_bps: u64,
+    /// Transmit rate in bits per second
+    pub tx_bps: u64,
+    /// Length of the interval the rate was computed over
+    pub elapsed: Duration,
+}
+
+/// Difference between two readings of a cumulative counter.
+///
+/// A decrease is treated as a 32-bit wrap if the previous value fits in 32 bits,
+/// otherwise as a counter reset, in which case the current value is the delta.
+pub fn counter_delta(prev: u64, cur: u64) -> u64 {
+    if cur >= prev {
+        cur - prev
+    } else if prev <= u32::MAX as u64 {
+        (u32::MAX as u64 - prev) + cur + 1
+    } else {
+        cur
+    }
+}
+
+/// Convert a byte count over an interval to bits per second.
+pub fn bits_per_second(bytes: u64, elapsed: Duration) -> u64 {
+    let nanos = elapsed.as_nanos();
+    if nanos == 0 {
+        return 0;
+    }
+    (bytes as u128 * 8 * 1_000_000_000 / nanos).min(u64::MAX as u128) as u64
+}
+
+/// Samples traffic counters of a set of interfaces and computes rx/tx rates.
+///
+/// Used as an iterator, it sleeps for the interval between samples and yields the
+/// rates of all interfaces whose counters could be read.
+#[derive(Debug)]
+pub struct RateMonitor {
+    interfaces: Vec<Interface>,
+    interval: Duration,
+    samples: HashMap<u32, (Instant, InterfaceStats)>,
+    last_sample: Instant,
+}
+
+impl RateMonitor {
+    /// Construct a new RateMonitor and take the initial sample
+    pub fn new(interfaces: Vec<Interface>, interval: Duration) -> RateMonitor {
+        let mut monitor = RateMonitor {
+            interfaces,
+            interval,
+            samples: HashMap::new(),
+            last_sample: Instant::now(),
+        };
+        monitor.sample();
+        monitor
+    }
+    /// Get the sampling interval
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+    /// Get the monitored interfaces
+    pub fn interfaces(&self) -> &[Interface] {
+        &self.interfaces
+    }
+    /// Read the counters now and return rates since the previous sample.
+    ///
+    /// Interfaces without a previous sample are recorded but not reported.
+    pub fn sample(&mut self) -> Vec<InterfaceRate> {
+        let mut rates = Vec::new();
+        let now = Instant::now();
+        for iface in &mut self.interfaces {
+            if iface.update_stats().is_err() {
+                continue;
+            }
+            let stats = match &iface.stats {
+                Some(stats) => stats,
+                None => continue,
+            };
+            if let Some((prev_time, prev)) = self.samples.insert(iface.index, (now, stats.clone()))
+            {
+                rates.push(compute_rate(iface, &prev, stats, now - prev_time));
+            }
+        }
+        self.last_sample = now;
+        rates
+    }
+}
+
+impl Iterator for RateMonitor {
+    type Item = Vec<InterfaceRate>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let elapsed = self.last_sample.elapsed();
+        if elapsed < self.interval {
+            thread::sleep(self.interval - elapsed);
+        }
+        Some(self.sample())
+    }
+}
+
+fn compute_rate(
+    iface: &Interface,
+    prev: &InterfaceStats,
+    cur: &InterfaceStats,
+    elapsed: Duration,
+) -> InterfaceRate {
+    InterfaceRate {
+        index: iface.index,
+        name: iface.name.clone(),
+        rx_bps: bits_per_second(counter_delta(prev.rx_bytes, cur.rx_bytes), elapsed),
+        tx_bps: bits_per_second(counter_delta(prev.tx_bytes, cur.tx_bytes), elapsed),
+        elapsed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_delta() {
+        assert_eq!(counter_delta(100, 250), 150);
+        // 32-bit wrap
+        assert_eq!(counter_delta(u32::MAX as u64 - 9, 5), 15);
+        assert_eq!(counter_delta(u32::MAX as u64, 0), 1);
+        // 64-bit counters do not wrap in practice, so a decrease is a reset
+        assert_eq!(counter_delta(u32::MAX as u64 + 100, 40), 40);
+    }
+
+    #[test]
+    fn test_compute_rate_wrap() {
+        let iface = Interface::dummy();
+        let prev = InterfaceStats {
+            rx_bytes: u32::MAX as u64 - 999,
+            tx_bytes: 1000,
+            ..Default::default()
+        };
+        let cur = InterfaceStats {
+            rx_bytes: 1000,
+            tx_bytes: 126_000,
+            ..Default::default()
+        };
+        let rate = compute_rate(&iface, &prev, &cur, Duration::from_secs(2));
+        assert_eq!(rate.rx_bps, 2000 * 8 / 2);
+        assert_eq!(rate.tx_bps, 125_000 * 8 / 2);
+        assert_eq!(bits_per_second(1000, Duration::ZERO), 0);
+    }
+
+    #[test]
+    fn test_rate_monitor() {
+        let mut monitor = RateMonitor::new(crate::get_interfaces(), Duration::from_millis(100));
+        let rates = monitor.next().unwrap();
+        println!("{:?}", rates);
+        // Interfaces whose counters could not be read in either sample are skipped
+        assert!(rates.len() <= monitor.interfaces().len());
+        for rate in &rates {
+            assert!(monitor
+                .interfaces()
+                .iter()
+                .any(|iface| iface.index == rate.index));
+            assert!(rate.elapsed >= monitor.interval());
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use super::InterfaceStats;
+use crate::interface::Interface;
+use std::time::SystemTime;
+use windows_sys::Win32::Foundation::NO_ERROR;
+use windows_sys::Win32::NetworkManagement::IpHelper::{GetIfEntry2, MIB_IF_ROW2};
+
+pub(crate) fn get_interface_stats(interface: &Interface) -> Option<InterfaceStats> {
+    get_interface_stats_by_index(interface.index)
+}
+
+pub(crate) fn get_interface_stats_by_index(if_index: u32) -> Option<InterfaceStats> {
+    let mut row: MIB_IF_ROW2 = unsafe { std::mem::zeroed() };
+    row.InterfaceIndex = if_index;
+    if unsafe { GetIfEntry2(&mut row) } != NO_ERROR {
+        return None;
+    }
+    Some(InterfaceStats {
+        rx_bytes: row.InOctets,
+        tx_bytes: row.OutOctets,
+        rx_packets: row.InUcastPkts + row.InNUcastPkts,
+        tx_packets: row.OutUcastPkts + row.OutNUcastPkts,
+        rx_errors: row.InErrors,
+        tx_errors: row.OutErrors,
+        rx_dropped: row.InDiscards,
+        tx_dropped: row.OutDiscards,
+        timestamp: Some(SystemTime::now()),
+        ..Default::default()
+    })
+}
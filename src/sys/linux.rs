@@ -1,18 +1,139 @@
 // ARP protocol HARDWARE identifiers
 pub mod if_arp {
     pub const ARPHRD_ETHER: u32 = libc::ARPHRD_ETHER as u32;
-    pub const ARPHRD_IEEE802: u32 = libc::ARPHRD_IEEE802 as u32;
-    pub const ARPHRD_FDDI: u32 = libc::ARPHRD_FDDI as u32;
-    pub const ARPHRD_PPP: u32 = libc::ARPHRD_PPP as u32;
-    pub const ARPHRD_LOOPBACK: u32 = libc::ARPHRD_LOOPBACK as u32;
-    pub const ARPHRD_EETHER: u32 = libc::ARPHRD_EETHER as u32;
-    pub const ARPHRD_SLIP: u32 = libc::ARPHRD_SLIP as u32;
-    pub const ARPHRD_ATM: u32 = libc::ARPHRD_ATM as u32;
-    pub const ARPHRD_IEEE80211: u32 = libc::ARPHRD_IEEE80211 as u32;
-    pub const ARPHRD_TUNNEL: u32 = libc::ARPHRD_TUNNEL as u32;
-    pub const ARPHRD_X25: u32 = libc::ARPHRD_X25 as u32;
-    pub const ARPHRD_IEEE1394: u32 = libc::ARPHRD_IEEE1394 as u32;
-    pub const ARPHRD_CAN: u32 = libc::ARPHRD_CAN as u32;
 }
 
 pub use libc::IFF_LOWER_UP;
+
+// ethtool ioctl interface (linux/ethtool.h)
+pub mod ethtool {
+    use crate::error::Error;
+    use std::ffi::c_void;
+    use std::io;
+
+    pub const SIOCETHTOOL: u64 = 0x8946;
+
+    pub const ETHTOOL_GDRVINFO: u32 = 0x03;
+    pub const ETHTOOL_GWOL: u32 = 0x05;
+    pub const ETHTOOL_GSTRINGS: u32 = 0x1b;
+    pub const ETHTOOL_GSTATS: u32 = 0x1d;
+    pub const ETHTOOL_GSSET_INFO: u32 = 0x37;
+    pub const ETHTOOL_GFEATURES: u32 = 0x3a;
+    pub const ETHTOOL_GET_TS_INFO: u32 = 0x41;
+
+    pub const ETH_SS_STATS: u32 = 1;
+    pub const ETH_SS_FEATURES: u32 = 4;
+    pub const ETH_GSTRING_LEN: usize = 32;
+
+    pub const WAKE_PHY: u32 = 1 << 0;
+    pub const WAKE_UCAST: u32 = 1 << 1;
+    pub const WAKE_MCAST: u32 = 1 << 2;
+    pub const WAKE_BCAST: u32 = 1 << 3;
+    pub const WAKE_ARP: u32 = 1 << 4;
+    pub const WAKE_MAGIC: u32 = 1 << 5;
+    pub const WAKE_MAGICSECURE: u32 = 1 << 6;
+    pub const WAKE_FILTER: u32 = 1 << 7;
+
+    /// `struct ifreq` carrying a pointer to the ethtool command in `ifr_data`
+    #[repr(C)]
+    struct IfReqData {
+        ifr_name: [libc::c_char; libc::IFNAMSIZ],
+        ifr_data: *mut c_void,
+        _pad: [u8; 24],
+    }
+
+    /// Issue an ethtool command.
+    ///
+    /// # Safety
+    ///
+    /// `cmd` must point to a writable buffer starting with the command number,
+    /// laid out and sized as the kernel expects for that command, including
+    /// the trailing arrays whose length the command gives.
+    pub unsafe fn ethtool_ioctl(if_name: &str, cmd: *mut c_void) -> io::Result<()> {
+        let bytes = if_name.as_bytes();
+        if bytes.len() >= libc::IFNAMSIZ || bytes.contains(&0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid interface name",
+            ));
+        }
+        let mut req = IfReqData {
+            ifr_name: [0; libc::IFNAMSIZ],
+            ifr_data: cmd,
+            _pad: [0; 24],
+        };
+        for (dst, src) in req.ifr_name.iter_mut().zip(bytes) {
+            *dst = *src as libc::c_char;
+        }
+        let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0) };
+        if fd < 0 {
+            return Err(Error::last_os_error("socket").into());
+        }
+        let ret = unsafe { libc::ioctl(fd, SIOCETHTOOL as _, &mut req as *mut IfReqData) };
+        let err = Error::last_os_error("ioctl(SIOCETHTOOL)");
+        unsafe { libc::close(fd) };
+        if ret < 0 {
+            Err(err.on(if_name).into())
+        } else {
+            Ok(())
+        }
+    }
+
+    #[repr(C)]
+    struct EthtoolSsetInfo {
+        cmd: u32,
+        reserved: u32,
+        sset_mask: u64,
+        data: [u32; 1],
+    }
+
+    /// Get the strings of an ethtool string set (`ETH_SS_*`), in index order
+    pub fn get_strings(if_name: &str, string_set: u32) -> io::Result<Vec<String>> {
+        let mut sset = EthtoolSsetInfo {
+            cmd: ETHTOOL_GSSET_INFO,
+            reserved: 0,
+            sset_mask: 1 << string_set,
+            data: [0],
+        };
+        // SAFETY: ethtool_sset_info with room for the one set in the mask
+        unsafe { ethtool_ioctl(if_name, &mut sset as *mut EthtoolSsetInfo as *mut c_void)? };
+        if sset.sset_mask == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "ethtool string set is not available",
+            ));
+        }
+        let count = sset.data[0] as usize;
+        // struct ethtool_gstrings: cmd, string_set, len, then len * ETH_GSTRING_LEN bytes
+        let words = ETH_GSTRING_LEN / 4;
+        let mut buf: Vec<u32> = vec![0; 3 + count * words];
+        buf[0] = ETHTOOL_GSTRINGS;
+        buf[1] = string_set;
+        buf[2] = count as u32;
+        // SAFETY: the buffer holds the header and `count` strings
+        unsafe { ethtool_ioctl(if_name, buf.as_mut_ptr() as *mut c_void)? };
+        let names = buf[3..]
+            .chunks(words)
+            .map(|chunk| {
+                let bytes: Vec<u8> = chunk.iter().flat_map(|w| w.to_ne_bytes()).collect();
+                let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+                String::from_utf8_lossy(&bytes[..end]).into_owned()
+            })
+            .collect();
+        Ok(names)
+    }
+
+    /// Get the driver statistics (`ethtool -S`), in the order of their `ETH_SS_STATS` names
+    pub fn get_stats(if_name: &str, count: usize) -> io::Result<Vec<u64>> {
+        // struct ethtool_stats: cmd, n_stats, then n_stats u64 values
+        let mut buf: Vec<u64> = vec![0; 1 + count];
+        let mut header = [0u8; 8];
+        header[..4].copy_from_slice(&ETHTOOL_GSTATS.to_ne_bytes());
+        header[4..].copy_from_slice(&(count as u32).to_ne_bytes());
+        buf[0] = u64::from_ne_bytes(header);
+        // SAFETY: the buffer holds the header and `count` values
+        unsafe { ethtool_ioctl(if_name, buf.as_mut_ptr() as *mut c_void)? };
+        buf.remove(0);
+        Ok(buf)
+    }
+}
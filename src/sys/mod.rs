@@ -12,3 +12,14 @@ pub use self::windows::*;
 mod linux;
 #[cfg(any(target_os = "linux", target_os = "android"))]
 pub use self::linux::*;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(crate) mod netlink;
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+pub(crate) mod rtsock;
@@ -0,0 +1,305 @@
+//! Minimal rtnetlink dump support shared by the Linux and Android backends.
+
+use crate::error::Error;
+#[cfg(feature = "config")]
+use netlink_packet_core::NLM_F_ACK;
+use netlink_packet_core::{
+    NetlinkHeader, NetlinkMessage, NetlinkPayload, NLM_F_DUMP, NLM_F_REQUEST,
+};
+use netlink_packet_route::RtnlMessage;
+use netlink_sys::{protocols::NETLINK_ROUTE, Socket};
+use std::io;
+
+/// Iterator over the replies to a netlink dump request.
+pub(crate) struct NetlinkIter<'a> {
+    socket: &'a Socket,
+    /// Buffer for received data.
+    buf: Vec<u8>,
+    /// Size of the data available in `buf`.
+    size: usize,
+    /// Offset into the data currently in `buf`.
+    offset: usize,
+    /// Are we don iterating?
+    done: bool,
+}
+
+impl<'a> NetlinkIter<'a> {
+    pub(crate) fn new(socket: &'a Socket, msg: RtnlMessage) -> io::Result<Self> {
+        let mut packet = NetlinkMessage::new(NetlinkHeader::default(), NetlinkPayload::from(msg));
+        packet.header.flags = NLM_F_DUMP | NLM_F_REQUEST;
+        packet.header.sequence_number = 1;
+        packet.finalize();
+
+        let mut buf = vec![0; packet.header.length as usize];
+        if buf.len() != packet.buffer_len() {
+            return Err(io::Error::other("Buffer length mismatch in netlink packet"));
+        }
+        packet.serialize(&mut buf[..]);
+        socket.send(&buf[..], 0)?;
+
+        Ok(NetlinkIter {
+            socket,
+            offset: 0,
+            size: 0,
+            // Links with many VFs produce messages larger than a page
+            buf: vec![0u8; 32768],
+            done: false,
+        })
+    }
+}
+
+impl<'a> Iterator for NetlinkIter<'a> {
+    type Item = io::Result<RtnlMessage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        while !self.done {
+            // Outer loop
+            if self.size == 0 {
+                match self.socket.recv(&mut &mut self.buf[..], 0) {
+                    Ok(size) => {
+                        self.size = size;
+                        self.offset = 0;
+                    }
+                    Err(err) => {
+                        self.done = true;
+                        return Some(Err(err));
+                    }
+                }
+            }
+
+            let bytes = &self.buf[self.offset..];
+            match NetlinkMessage::<RtnlMessage>::deserialize(bytes) {
+                Ok(packet) => {
+                    self.offset += packet.header.length as usize;
+                    if packet.header.length == 0 || self.offset == self.size {
+                        // mark this message as fully read
+                        self.size = 0;
+                    }
+                    match packet.payload {
+                        NetlinkPayload::Done(_) => {
+                            self.done = true;
+                            return None;
+                        }
+                        NetlinkPayload::Error(err) => {
+                            self.done = true;
+                            return Some(Err(io::Error::other(err.to_string())));
+                        }
+                        NetlinkPayload::InnerMessage(msg) => return Some(Ok(msg)),
+                        _ => {
+                            continue;
+                        }
+                    }
+                }
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(io::Error::other(err.to_string())));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Open a route netlink socket
+fn open() -> io::Result<Socket> {
+    Socket::new(NETLINK_ROUTE).map_err(|err| Error::syscall("socket(AF_NETLINK)", err).into())
+}
+
+/// Open a route netlink socket, send a dump request and collect the replies.
+pub(crate) fn dump(msg: RtnlMessage) -> io::Result<Vec<RtnlMessage>> {
+    let _span = debug_span!("netlink_dump");
+    let socket = open()?;
+    let result: io::Result<Vec<RtnlMessage>> = NetlinkIter::new(&socket, msg)?.collect();
+    match &result {
+        Ok(msgs) => debug!("netlink dump returned {} messages", msgs.len()),
+        Err(err) => debug!("netlink dump failed: {}", err),
+    }
+    result
+}
+
+/// Send a request that is not a dump and return the kernel's reply.
+pub(crate) fn get(msg: RtnlMessage) -> io::Result<RtnlMessage> {
+    let _span = debug_span!("netlink_get");
+    let socket = open()?;
+    let mut packet = NetlinkMessage::new(NetlinkHeader::default(), NetlinkPayload::from(msg));
+    packet.header.flags = NLM_F_REQUEST;
+    packet.header.sequence_number = 1;
+    packet.finalize();
+    let mut buf = vec![0; packet.buffer_len()];
+    packet.serialize(&mut buf[..]);
+    socket.send(&buf[..], 0)?;
+
+    let mut buf = vec![0u8; 4096];
+    loop {
+        let size = socket.recv(&mut &mut buf[..], 0)?;
+        let packet = NetlinkMessage::<RtnlMessage>::deserialize(&buf[..size])
+            .map_err(|err| io::Error::other(err.to_string()))?;
+        match packet.payload {
+            NetlinkPayload::InnerMessage(msg) => return Ok(msg),
+            NetlinkPayload::Error(err) => {
+                if let Some(code) = err.code {
+                    return Err(io::Error::from_raw_os_error(-code.get()));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Send a request with the given extra flags and wait for the kernel's acknowledgement.
+#[cfg(feature = "config")]
+pub(crate) fn request(msg: RtnlMessage, flags: u16) -> io::Result<()> {
+    let _span = debug_span!("netlink_request");
+    let socket = open()?;
+    let mut packet = NetlinkMessage::new(NetlinkHeader::default(), NetlinkPayload::from(msg));
+    packet.header.flags = NLM_F_REQUEST | NLM_F_ACK | flags;
+    packet.header.sequence_number = 1;
+    packet.finalize();
+    let mut buf = vec![0; packet.buffer_len()];
+    packet.serialize(&mut buf[..]);
+    socket.send(&buf[..], 0)?;
+
+    let mut buf = vec![0u8; 4096];
+    loop {
+        let size = socket.recv(&mut &mut buf[..], 0)?;
+        let mut offset = 0;
+        while offset < size {
+            let packet = NetlinkMessage::<RtnlMessage>::deserialize(&buf[offset..size])
+                .map_err(|err| io::Error::other(err.to_string()))?;
+            if packet.header.length == 0 {
+                // Would never advance to the acknowledgement
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "netlink message with a zero length",
+                ));
+            }
+            offset += packet.header.length as usize;
+            if let NetlinkPayload::Error(err) = packet.payload {
+                return match err.code {
+                    None => Ok(()),
+                    Some(code) => {
+                        let err = io::Error::from_raw_os_error(-code.get());
+                        debug!("netlink request rejected: {}", err);
+                        Err(err)
+                    }
+                };
+            }
+        }
+    }
+}
+
+/// Iterate over the netlink attributes in `buf` as (type, payload) pairs
+pub(crate) fn iter_nlas(mut buf: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    std::iter::from_fn(move || {
+        if buf.len() < 4 {
+            return None;
+        }
+        let len = u16::from_ne_bytes([buf[0], buf[1]]) as usize;
+        // Strip NLA_F_NESTED and NLA_F_NET_BYTEORDER
+        let kind = u16::from_ne_bytes([buf[2], buf[3]]) & 0x3fff;
+        if len < 4 || len > buf.len() {
+            return None;
+        }
+        let payload = &buf[4..len];
+        buf = &buf[((len + 3) & !3).min(buf.len())..];
+        Some((kind, payload))
+    })
+}
+
+pub(crate) fn read_u32(buf: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_ne_bytes(
+        buf.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+const NLMSG_HDR_LEN: usize = 16;
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+const NLM_F_MULTI: u16 = 2;
+
+/// Send a request whose payload, the family header followed by attributes,
+/// is built by the caller.
+///
+/// Used for messages `netlink_packet_route` cannot represent or fails to decode.
+pub(crate) fn send_raw(
+    socket: &Socket,
+    msg_type: u16,
+    flags: u16,
+    payload: &[u8],
+) -> io::Result<()> {
+    let len = NLMSG_HDR_LEN + payload.len();
+    let mut buf = Vec::with_capacity(len);
+    buf.extend_from_slice(&(len as u32).to_ne_bytes());
+    buf.extend_from_slice(&msg_type.to_ne_bytes());
+    buf.extend_from_slice(&flags.to_ne_bytes());
+    // Sequence number and port id
+    buf.extend_from_slice(&1u32.to_ne_bytes());
+    buf.extend_from_slice(&0u32.to_ne_bytes());
+    buf.extend_from_slice(payload);
+    socket.send(&buf, 0)?;
+    Ok(())
+}
+
+/// Receive the replies to a request sent with [`send_raw`], passing the type
+/// and payload of each one to `f`, until the dump is done or a single-part
+/// reply has been read
+pub(crate) fn recv_raw(socket: &Socket, mut f: impl FnMut(u16, &[u8])) -> io::Result<()> {
+    let mut buf = vec![0u8; 32768];
+    loop {
+        let size = socket.recv(&mut &mut buf[..], 0)?;
+        let mut data = &buf[..size];
+        while data.len() >= NLMSG_HDR_LEN {
+            let len = read_u32(data, 0).unwrap_or(0) as usize;
+            let kind = u16::from_ne_bytes([data[4], data[5]]);
+            let flags = u16::from_ne_bytes([data[6], data[7]]);
+            if len < NLMSG_HDR_LEN || len > data.len() {
+                return Err(io::Error::other("truncated netlink message"));
+            }
+            let payload = &data[NLMSG_HDR_LEN..len];
+            match kind {
+                NLMSG_DONE => return Ok(()),
+                NLMSG_ERROR => {
+                    return match read_u32(payload, 0).unwrap_or(0) as i32 {
+                        0 => Ok(()),
+                        code => Err(io::Error::from_raw_os_error(-code)),
+                    };
+                }
+                _ => {
+                    f(kind, payload);
+                    if flags & NLM_F_MULTI == 0 {
+                        return Ok(());
+                    }
+                }
+            }
+            data = &data[((len + 3) & !3).min(data.len())..];
+        }
+    }
+}
+
+/// Open a route netlink socket, send a raw dump request and collect the
+/// payloads of the replies
+pub(crate) fn dump_raw(msg_type: u16, payload: &[u8]) -> io::Result<Vec<Vec<u8>>> {
+    let _span = debug_span!("netlink_dump_raw");
+    let socket = open()?;
+    send_raw(&socket, msg_type, NLM_F_DUMP | NLM_F_REQUEST, payload)?;
+    let mut replies = Vec::new();
+    recv_raw(&socket, |_, payload| replies.push(payload.to_vec()))?;
+    debug!("netlink dump returned {} messages", replies.len());
+    Ok(replies)
+}
+
+/// Open a route netlink socket, send a raw request that is not a dump and
+/// return the payload of the reply
+pub(crate) fn get_raw(msg_type: u16, payload: &[u8]) -> io::Result<Option<Vec<u8>>> {
+    let _span = debug_span!("netlink_get_raw");
+    let socket = open()?;
+    send_raw(&socket, msg_type, NLM_F_REQUEST, payload)?;
+    let mut reply = None;
+    recv_raw(&socket, |_, payload| reply = Some(payload.to_vec()))?;
+    Ok(reply)
+}
@@ -0,0 +1,186 @@
+//! Writing to and querying the routing socket (`route(4)`).
+//!
+//! The message header starts with the same fields on macOS, FreeBSD and NetBSD, so
+//! only its total size and the sockaddr alignment differ.
+
+use super::SA_ALIGN;
+use std::io;
+use std::net::IpAddr;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::sync::atomic::{AtomicI32, Ordering};
+
+#[cfg(feature = "config")]
+pub(crate) const RTM_ADD: u8 = 0x1;
+#[cfg(feature = "config")]
+pub(crate) const RTM_DELETE: u8 = 0x2;
+#[cfg(feature = "config")]
+pub(crate) const RTM_CHANGE: u8 = 0x3;
+pub(crate) const RTM_GET: u8 = 0x4;
+#[cfg(not(target_os = "netbsd"))]
+const RTM_VERSION: u8 = 5;
+#[cfg(target_os = "netbsd")]
+const RTM_VERSION: u8 = 4;
+pub(crate) const RTF_UP: i32 = 0x1;
+pub(crate) const RTF_GATEWAY: i32 = 0x2;
+pub(crate) const RTF_HOST: i32 = 0x4;
+// RTF_LLINFO on macOS, RTF_LLDATA on FreeBSD and NetBSD
+#[cfg(feature = "config")]
+pub(crate) const RTF_LLINFO: i32 = 0x400;
+#[cfg(feature = "config")]
+pub(crate) const RTF_STATIC: i32 = 0x800;
+pub(crate) const RTA_DST: i32 = 0x1;
+#[cfg(any(test, feature = "config"))]
+pub(crate) const RTA_GATEWAY: i32 = 0x2;
+#[cfg(feature = "config")]
+pub(crate) const RTA_NETMASK: i32 = 0x4;
+pub(crate) const RTA_IFP: i32 = 0x10;
+#[cfg(test)]
+pub(crate) const RTA_IFA: i32 = 0x20;
+/// Index of the gateway and the interface address in the sockaddrs of a message
+pub(crate) const RTAX_GATEWAY: usize = 1;
+pub(crate) const RTAX_IFA: usize = 5;
+const AF_LINK: u8 = 18;
+const IFT_ETHER: u8 = 0x6;
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub(crate) const RT_MSGHDR_LEN: usize = 92;
+// rtm_inits and the 14 rt_metrics fields are u_long
+#[cfg(target_os = "freebsd")]
+pub(crate) const RT_MSGHDR_LEN: usize = 32 + 15 * std::mem::size_of::<std::os::raw::c_ulong>();
+// rt_metrics holds ten 64-bit fields
+#[cfg(target_os = "netbsd")]
+pub(crate) const RT_MSGHDR_LEN: usize = 36_usize.next_multiple_of(std::mem::align_of::<u64>()) + 80;
+
+/// Message with room for the header, to be followed by sockaddrs
+pub(crate) fn new_message() -> Vec<u8> {
+    vec![0u8; RT_MSGHDR_LEN]
+}
+
+pub(crate) fn push_sockaddr(buf: &mut Vec<u8>, sa: &[u8]) {
+    buf.extend_from_slice(sa);
+    buf.resize(
+        buf.len() + sa.len().next_multiple_of(SA_ALIGN) - sa.len(),
+        0,
+    );
+}
+
+pub(crate) fn sockaddr_ip(ip: IpAddr, scope_id: u32) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(ip) => {
+            let mut sa = vec![16, libc::AF_INET as u8, 0, 0];
+            sa.extend_from_slice(&ip.octets());
+            sa.resize(16, 0);
+            sa
+        }
+        IpAddr::V6(ip) => {
+            let mut sa = vec![28, libc::AF_INET6 as u8, 0, 0, 0, 0, 0, 0];
+            sa.extend_from_slice(&ip.octets());
+            sa.extend_from_slice(&scope_id.to_ne_bytes());
+            sa
+        }
+    }
+}
+
+/// Link-layer sockaddr for an interface, optionally carrying an Ethernet address
+pub(crate) fn sockaddr_dl(if_index: u32, lladdr: &[u8]) -> Vec<u8> {
+    let mut sa = vec![20, AF_LINK];
+    sa.extend_from_slice(&(if_index as u16).to_ne_bytes());
+    if !lladdr.is_empty() {
+        // sdl_type, sdl_nlen, sdl_alen, sdl_slen, then the address in sdl_data
+        sa.extend_from_slice(&[IFT_ETHER, 0, lladdr.len() as u8, 0]);
+        sa.extend_from_slice(lladdr);
+    }
+    sa.resize(20, 0);
+    sa
+}
+
+/// Sequence number of the last message sent, so that concurrent queries of
+/// this process get their own replies
+static SEQ: AtomicI32 = AtomicI32::new(0);
+
+fn next_seq() -> i32 {
+    SEQ.fetch_add(1, Ordering::Relaxed).wrapping_add(1)
+}
+
+/// Fill in the header of a message built with [`new_message`]
+pub(crate) fn finish_message(
+    buf: &mut [u8],
+    rtm_type: u8,
+    if_index: u32,
+    flags: i32,
+    addrs: i32,
+) -> io::Result<()> {
+    let msg_len = u16::try_from(buf.len()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "routing socket message too long",
+        )
+    })?;
+    buf[0..2].copy_from_slice(&msg_len.to_ne_bytes());
+    buf[2] = RTM_VERSION;
+    buf[3] = rtm_type;
+    buf[4..6].copy_from_slice(&(if_index as u16).to_ne_bytes());
+    buf[8..12].copy_from_slice(&flags.to_ne_bytes());
+    buf[12..16].copy_from_slice(&addrs.to_ne_bytes());
+    buf[16..20].copy_from_slice(&(std::process::id() as i32).to_ne_bytes());
+    buf[20..24].copy_from_slice(&next_seq().to_ne_bytes());
+    Ok(())
+}
+
+fn open() -> io::Result<OwnedFd> {
+    let fd = unsafe { libc::socket(libc::PF_ROUTE, libc::SOCK_RAW, libc::AF_UNSPEC) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+fn write(fd: &OwnedFd, msg: &[u8]) -> io::Result<()> {
+    let ret = unsafe {
+        libc::write(
+            fd.as_raw_fd(),
+            msg.as_ptr() as *const libc::c_void,
+            msg.len(),
+        )
+    };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "config")]
+pub(crate) fn send(msg: &[u8]) -> io::Result<()> {
+    write(&open()?, msg)
+}
+
+/// Send a message built with [`finish_message`] and return the kernel's reply
+/// to it. The socket also receives messages of other processes, which are skipped.
+pub(crate) fn query(msg: &[u8]) -> io::Result<Vec<u8>> {
+    let fd = open()?;
+    write(&fd, msg)?;
+    let mut buf = vec![0u8; 2048];
+    loop {
+        let len = unsafe {
+            libc::read(
+                fd.as_raw_fd(),
+                buf.as_mut_ptr() as *mut libc::c_void,
+                buf.len(),
+            )
+        };
+        if len < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let reply = &buf[..len as usize];
+        // Matches rtm_type, rtm_pid and rtm_seq
+        if reply.len() < RT_MSGHDR_LEN || reply[3] != msg[3] || reply[16..24] != msg[16..24] {
+            continue;
+        }
+        // rtm_errno
+        let errno = i32::from_ne_bytes([reply[24], reply[25], reply[26], reply[27]]);
+        if errno != 0 {
+            return Err(io::Error::from_raw_os_error(errno));
+        }
+        return Ok(reply.to_vec());
+    }
+}
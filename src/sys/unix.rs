@@ -1,62 +1,264 @@
+use crate::error::Error;
 use std::io;
-use std::mem;
-use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
-
-pub type SockAddrIn = libc::sockaddr_in;
-pub type SockAddrIn6 = libc::sockaddr_in6;
-pub type SockAddrStorage = libc::sockaddr_storage;
-pub type InAddr = libc::in_addr;
-
-pub const AF_INET: libc::c_int = libc::AF_INET;
-pub const AF_INET6: libc::c_int = libc::AF_INET6;
-
-pub use libc::{IFF_BROADCAST, IFF_LOOPBACK, IFF_MULTICAST, IFF_POINTOPOINT, IFF_RUNNING, IFF_UP};
-
-fn ntohs(u: u16) -> u16 {
-    u16::from_be(u)
-}
-
-pub fn sockaddr_to_addr(storage: &SockAddrStorage, len: usize) -> io::Result<SocketAddr> {
-    match storage.ss_family as libc::c_int {
-        AF_INET => {
-            assert!(len as usize >= mem::size_of::<SockAddrIn>());
-            let storage: &SockAddrIn = unsafe { mem::transmute(storage) };
-            let ip = ipv4_addr_int(storage.sin_addr);
-            // octets
-            let o1 = (ip >> 24) as u8;
-            let o2 = (ip >> 16) as u8;
-            let o3 = (ip >> 8) as u8;
-            let o4 = ip as u8;
-            let sockaddrv4 =
-                SocketAddrV4::new(Ipv4Addr::new(o1, o2, o3, o4), ntohs(storage.sin_port));
-            Ok(SocketAddr::V4(sockaddrv4))
+
+pub use libc::{
+    IFF_BROADCAST, IFF_LOOPBACK, IFF_MULTICAST, IFF_POINTOPOINT, IFF_PROMISC, IFF_RUNNING, IFF_UP,
+};
+
+// Interface flag ioctls. The BSD request codes encode the size of `struct ifreq`,
+// which is 32 bytes except on NetBSD where it embeds a sockaddr_storage.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub const SIOCGIFFLAGS: u64 = 0x8913;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[cfg(feature = "config")]
+pub const SIOCSIFFLAGS: u64 = 0x8914;
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "openbsd"
+))]
+pub const SIOCGIFFLAGS: u64 = 0xc0206911;
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "openbsd"
+))]
+#[cfg(feature = "config")]
+pub const SIOCSIFFLAGS: u64 = 0x80206910;
+#[cfg(target_os = "netbsd")]
+pub const SIOCGIFFLAGS: u64 = 0xc0906911;
+#[cfg(target_os = "netbsd")]
+#[cfg(feature = "config")]
+pub const SIOCSIFFLAGS: u64 = 0x80906910;
+
+// Interface MTU ioctl, for the upper bound of path MTU probes
+#[cfg(all(any(target_os = "linux", target_os = "android"), feature = "probe"))]
+pub const SIOCGIFMTU: u64 = 0x8921;
+#[cfg(all(
+    any(target_os = "macos", target_os = "ios", target_os = "freebsd"),
+    feature = "probe"
+))]
+pub const SIOCGIFMTU: u64 = 0xc0206933;
+#[cfg(all(target_os = "openbsd", feature = "probe"))]
+pub const SIOCGIFMTU: u64 = 0xc020697e;
+#[cfg(all(target_os = "netbsd", feature = "probe"))]
+pub const SIOCGIFMTU: u64 = 0xc090697e;
+
+/// User-requested promiscuous mode (FreeBSD keeps IFF_PROMISC for the kernel's own count)
+#[cfg(all(target_os = "freebsd", feature = "config"))]
+pub const IFF_PPROMISC: u32 = 0x20000;
+
+/// `struct ifreq` as used by the flag and MTU ioctls, padded to cover the largest platform layout
+#[repr(C)]
+pub struct IfReqFlags {
+    pub ifr_name: [libc::c_char; libc::IFNAMSIZ],
+    /// `ifr_flags`, followed by `ifr_flagshigh` on FreeBSD. Overlaid by `ifr_mtu`.
+    pub ifr_flags: [libc::c_short; 2],
+    _pad: [u8; 128],
+}
+
+impl IfReqFlags {
+    pub fn new(if_name: &str) -> io::Result<IfReqFlags> {
+        let bytes = if_name.as_bytes();
+        if bytes.len() >= libc::IFNAMSIZ || bytes.contains(&0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid interface name",
+            ));
+        }
+        let mut req = IfReqFlags {
+            ifr_name: [0; libc::IFNAMSIZ],
+            ifr_flags: [0; 2],
+            _pad: [0; 128],
+        };
+        for (dst, src) in req.ifr_name.iter_mut().zip(bytes) {
+            *dst = *src as libc::c_char;
+        }
+        Ok(req)
+    }
+    pub fn flags(&self) -> u32 {
+        (self.ifr_flags[0] as u16 as u32) | ((self.ifr_flags[1] as u16 as u32) << 16)
+    }
+    #[cfg(feature = "probe")]
+    pub fn mtu(&self) -> u32 {
+        let [low, high] = self.ifr_flags.map(|half| half.to_ne_bytes());
+        i32::from_ne_bytes([low[0], low[1], high[0], high[1]]) as u32
+    }
+    #[cfg(feature = "config")]
+    pub fn set_flags(&mut self, flags: u32) {
+        self.ifr_flags[0] = flags as u16 as libc::c_short;
+        #[cfg(target_os = "freebsd")]
+        {
+            self.ifr_flags[1] = (flags >> 16) as u16 as libc::c_short;
+        }
+    }
+}
+
+/// Run an interface ioctl on a throwaway datagram socket
+pub fn ifreq_ioctl(request: u64, req: &mut IfReqFlags) -> io::Result<()> {
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0) };
+    if fd < 0 {
+        return Err(Error::last_os_error("socket").into());
+    }
+    let ret = unsafe { libc::ioctl(fd, request as _, req as *mut IfReqFlags) };
+    let err = Error::last_os_error("ioctl");
+    unsafe { libc::close(fd) };
+    if ret < 0 {
+        let name = unsafe { std::ffi::CStr::from_ptr(req.ifr_name.as_ptr()) };
+        Err(err.on(&name.to_string_lossy()).into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Alignment of the sockaddrs following a routing message header
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub const SA_ALIGN: usize = 4;
+#[cfg(any(target_os = "freebsd", target_os = "openbsd"))]
+pub const SA_ALIGN: usize = std::mem::size_of::<std::os::raw::c_long>();
+#[cfg(target_os = "netbsd")]
+pub const SA_ALIGN: usize = 8;
+
+/// View the bytes of a sockaddr returned by the OS, bounded by its real size.
+///
+/// # Safety
+/// `sa` must be null or point to a sockaddr that stays valid for `'a`.
+pub unsafe fn sockaddr_bytes<'a>(sa: *const libc::sockaddr) -> Option<&'a [u8]> {
+    if sa.is_null() {
+        return None;
+    }
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let len = match (*sa).sa_family as libc::c_int {
+        libc::AF_INET => std::mem::size_of::<libc::sockaddr_in>(),
+        libc::AF_INET6 => std::mem::size_of::<libc::sockaddr_in6>(),
+        // libc's getifaddrs stores hardware addresses longer than the 8 bytes of
+        // sll_addr, such as InfiniBand's, in a larger structure with room for 24.
+        libc::AF_PACKET => {
+            let halen = *(sa as *const u8).add(11) as usize;
+            12 + halen.clamp(8, 24)
         }
-        AF_INET6 => {
-            assert!(len as usize >= mem::size_of::<SockAddrIn6>());
-            let storage: &SockAddrIn6 = unsafe { mem::transmute(storage) };
-            let arr: [u16; 8] = unsafe { mem::transmute(storage.sin6_addr.s6_addr) };
-            // hextets
-            let h1 = ntohs(arr[0]);
-            let h2 = ntohs(arr[1]);
-            let h3 = ntohs(arr[2]);
-            let h4 = ntohs(arr[3]);
-            let h5 = ntohs(arr[4]);
-            let h6 = ntohs(arr[5]);
-            let h7 = ntohs(arr[6]);
-            let h8 = ntohs(arr[7]);
-            let ip = Ipv6Addr::new(h1, h2, h3, h4, h5, h6, h7, h8);
-            Ok(SocketAddr::V6(SocketAddrV6::new(
-                ip,
-                ntohs(storage.sin6_port),
-                u32::from_be(storage.sin6_flowinfo),
-                storage.sin6_scope_id,
-            )))
+        _ => std::mem::size_of::<libc::sockaddr>(),
+    };
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let len = (*sa).sa_len as usize;
+    Some(std::slice::from_raw_parts(sa as *const u8, len))
+}
+
+/// Set an integer socket option that socket2 has no setter for
+#[cfg(any(feature = "ra", feature = "scan", feature = "probe"))]
+pub fn set_socket_option(
+    socket: &socket2::Socket,
+    level: libc::c_int,
+    option: libc::c_int,
+    value: libc::c_int,
+) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+    let ret = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            level,
+            option,
+            &value as *const libc::c_int as *const libc::c_void,
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+// RFC 3542 options, missing from libc on NetBSD and OpenBSD
+#[cfg(all(
+    any(feature = "ra", feature = "scan"),
+    not(any(target_os = "netbsd", target_os = "openbsd"))
+))]
+use libc::{IPV6_HOPLIMIT, IPV6_RECVHOPLIMIT};
+#[cfg(all(
+    any(feature = "ra", feature = "scan"),
+    any(target_os = "netbsd", target_os = "openbsd")
+))]
+const IPV6_RECVHOPLIMIT: libc::c_int = 37;
+#[cfg(all(
+    any(feature = "ra", feature = "scan"),
+    any(target_os = "netbsd", target_os = "openbsd")
+))]
+const IPV6_HOPLIMIT: libc::c_int = 47;
+
+/// Have [`recv_with_hop_limit`] report the hop limit of the packets received on
+/// an IPv6 socket
+#[cfg(any(feature = "ra", feature = "scan"))]
+pub fn set_recv_hop_limit(socket: &socket2::Socket) -> io::Result<()> {
+    set_socket_option(socket, libc::IPPROTO_IPV6, IPV6_RECVHOPLIMIT, 1)
+}
+
+/// Receive a packet on an IPv6 socket, returning its length, its source and
+/// the hop limit it arrived with if [`set_recv_hop_limit`] was called
+#[cfg(any(feature = "ra", feature = "scan"))]
+pub fn recv_with_hop_limit(
+    socket: &socket2::Socket,
+    buf: &mut [std::mem::MaybeUninit<u8>],
+) -> io::Result<(usize, std::net::SocketAddrV6, Option<u8>)> {
+    use std::os::fd::AsRawFd;
+    let mut addr: libc::sockaddr_in6 = unsafe { std::mem::zeroed() };
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+        iov_len: buf.len(),
+    };
+    // Room for a few control messages, aligned for cmsghdr
+    let mut control = [0u64; 16];
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_name = &mut addr as *mut libc::sockaddr_in6 as *mut libc::c_void;
+    msg.msg_namelen = std::mem::size_of::<libc::sockaddr_in6>() as libc::socklen_t;
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+    msg.msg_controllen = std::mem::size_of_val(&control) as _;
+    let len = unsafe { libc::recvmsg(socket.as_raw_fd(), &mut msg, 0) };
+    if len < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let mut hop_limit = None;
+    let mut cmsg = unsafe { libc::CMSG_FIRSTHDR(&msg) };
+    while !cmsg.is_null() {
+        let hdr = unsafe { &*cmsg };
+        if hdr.cmsg_level == libc::IPPROTO_IPV6 && hdr.cmsg_type == IPV6_HOPLIMIT {
+            let value = unsafe { (libc::CMSG_DATA(cmsg) as *const libc::c_int).read_unaligned() };
+            hop_limit = u8::try_from(value).ok();
         }
-        _ => Err(io::Error::new(io::ErrorKind::InvalidData, "Not supported")),
+        cmsg = unsafe { libc::CMSG_NXTHDR(&msg, cmsg) };
     }
+    let src = std::net::SocketAddrV6::new(
+        addr.sin6_addr.s6_addr.into(),
+        u16::from_be(addr.sin6_port),
+        addr.sin6_flowinfo,
+        addr.sin6_scope_id,
+    );
+    Ok((len as usize, src, hop_limit))
 }
 
-#[inline(always)]
-pub fn ipv4_addr_int(addr: InAddr) -> u32 {
-    (addr.s_addr as u32).to_be()
+/// Wait up to `timeout` until one of `sockets` has a packet to receive.
+/// Returns false if the time ran out.
+#[cfg(feature = "probe")]
+pub fn wait_readable(
+    sockets: &[&socket2::Socket],
+    timeout: std::time::Duration,
+) -> io::Result<bool> {
+    use std::os::fd::AsRawFd;
+    let mut fds: Vec<libc::pollfd> = sockets
+        .iter()
+        .map(|socket| libc::pollfd {
+            fd: socket.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        })
+        .collect();
+    let millis = timeout.as_millis().clamp(1, i32::MAX as u128) as libc::c_int;
+    let ret = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, millis) };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(ret > 0)
 }
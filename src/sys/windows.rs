@@ -5,3 +5,81 @@ pub const IFF_BROADCAST: u32 = ws::IFF_BROADCAST;
 pub const IFF_LOOPBACK: u32 = ws::IFF_LOOPBACK;
 pub const IFF_POINTOPOINT: u32 = ws::IFF_POINTTOPOINT;
 pub const IFF_MULTICAST: u32 = ws::IFF_MULTICAST;
+
+/// CTL_CODE(FILE_DEVICE_PHYSICAL_NETCARD, 0, METHOD_OUT_DIRECT, FILE_ANY_ACCESS)
+pub const IOCTL_NDIS_QUERY_GLOBAL_STATS: u32 = 0x0017_0002;
+
+/// Set an integer socket option that socket2 has no setter for
+#[cfg(feature = "probe")]
+pub fn set_socket_option(
+    socket: &socket2::Socket,
+    level: i32,
+    option: i32,
+    value: i32,
+) -> std::io::Result<()> {
+    use std::os::windows::io::AsRawSocket;
+    let ret = unsafe {
+        ws::setsockopt(
+            socket.as_raw_socket() as ws::SOCKET,
+            level,
+            option,
+            &value as *const i32 as *const u8,
+            std::mem::size_of::<i32>() as i32,
+        )
+    };
+    if ret != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Have [`recv_with_hop_limit`] report the hop limit of received packets.
+///
+/// Windows only reports it through `WSARecvMsg`, which is not used.
+#[cfg(any(feature = "ra", feature = "scan"))]
+pub fn set_recv_hop_limit(_socket: &socket2::Socket) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "Receiving the hop limit of packets is not supported on this platform",
+    ))
+}
+
+/// Receive a packet on an IPv6 socket with its source and hop limit.
+///
+/// Not supported on this platform.
+#[cfg(any(feature = "ra", feature = "scan"))]
+pub fn recv_with_hop_limit(
+    _socket: &socket2::Socket,
+    _buf: &mut [std::mem::MaybeUninit<u8>],
+) -> std::io::Result<(usize, std::net::SocketAddrV6, Option<u8>)> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "Receiving the hop limit of packets is not supported on this platform",
+    ))
+}
+
+/// Wait up to `timeout` until one of `sockets` has a packet to receive.
+/// Returns false if the time ran out.
+#[cfg(feature = "probe")]
+pub fn wait_readable(
+    sockets: &[&socket2::Socket],
+    timeout: std::time::Duration,
+) -> std::io::Result<bool> {
+    use std::os::windows::io::AsRawSocket;
+    let mut fds: Vec<ws::WSAPOLLFD> = sockets
+        .iter()
+        .map(|socket| ws::WSAPOLLFD {
+            fd: socket.as_raw_socket() as ws::SOCKET,
+            events: ws::POLLIN,
+            revents: 0,
+        })
+        .collect();
+    let millis = timeout.as_millis().clamp(1, i32::MAX as u128) as i32;
+    let ret = unsafe { ws::WSAPoll(fds.as_mut_ptr(), fds.len() as u32, millis) };
+    if ret < 0 {
+        return Err(std::io::Error::from_raw_os_error(unsafe {
+            ws::WSAGetLastError()
+        }));
+    }
+    Ok(ret > 0)
+}
@@ -0,0 +1,295 @@
+//! Kernel WireGuard over generic netlink (`WG_CMD_GET_DEVICE`).
+
+use super::{Device, Key, Peer};
+use crate::ip::IpNet;
+use crate::sys::netlink;
+use netlink_sys::{protocols::NETLINK_GENERIC, Socket};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::{Duration, SystemTime};
+
+const NLM_F_REQUEST: u16 = 0x01;
+const NLM_F_DUMP: u16 = 0x300;
+const GENL_HDR_LEN: usize = 4;
+
+const GENL_ID_CTRL: u16 = 0x10;
+const CTRL_CMD_GETFAMILY: u8 = 3;
+const CTRL_ATTR_FAMILY_ID: u16 = 1;
+const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+
+const WG_GENL_NAME: &[u8] = b"wireguard\0";
+const WG_GENL_VERSION: u8 = 1;
+const WG_CMD_GET_DEVICE: u8 = 0;
+
+const WGDEVICE_A_IFNAME: u16 = 2;
+const WGDEVICE_A_PUBLIC_KEY: u16 = 4;
+const WGDEVICE_A_LISTEN_PORT: u16 = 6;
+const WGDEVICE_A_FWMARK: u16 = 7;
+const WGDEVICE_A_PEERS: u16 = 8;
+
+const WGPEER_A_PUBLIC_KEY: u16 = 1;
+const WGPEER_A_ENDPOINT: u16 = 4;
+const WGPEER_A_PERSISTENT_KEEPALIVE_INTERVAL: u16 = 5;
+const WGPEER_A_LAST_HANDSHAKE_TIME: u16 = 6;
+const WGPEER_A_RX_BYTES: u16 = 7;
+const WGPEER_A_TX_BYTES: u16 = 8;
+const WGPEER_A_ALLOWEDIPS: u16 = 9;
+
+const WGALLOWEDIP_A_FAMILY: u16 = 1;
+const WGALLOWEDIP_A_IPADDR: u16 = 2;
+const WGALLOWEDIP_A_CIDR_MASK: u16 = 3;
+
+pub(super) fn get_device(name: &str) -> io::Result<Device> {
+    let socket = Socket::new(NETLINK_GENERIC)?;
+    let family = match resolve_family(&socket) {
+        Ok(family) => family,
+        // The kernel module is not loaded
+        Err(err) if err.raw_os_error() == Some(libc::ENOENT) => {
+            return Err(io::Error::new(io::ErrorKind::NotFound, err))
+        }
+        Err(err) => return Err(err),
+    };
+    let mut attrs = Vec::new();
+    let mut ifname = name.as_bytes().to_vec();
+    ifname.push(0);
+    push_nla(&mut attrs, WGDEVICE_A_IFNAME, &ifname);
+    send(
+        &socket,
+        family,
+        NLM_F_REQUEST | NLM_F_DUMP,
+        WG_CMD_GET_DEVICE,
+        WG_GENL_VERSION,
+        &attrs,
+    )?;
+    let mut device = Device {
+        name: name.to_string(),
+        public_key: None,
+        listen_port: 0,
+        fwmark: 0,
+        peers: Vec::new(),
+    };
+    recv(&socket, |payload| parse_device(payload, &mut device)).map_err(|err| {
+        match err.raw_os_error() {
+            // Not a WireGuard device, or no such device
+            Some(libc::ENODEV) | Some(libc::EOPNOTSUPP) => {
+                io::Error::new(io::ErrorKind::NotFound, err)
+            }
+            _ => err,
+        }
+    })?;
+    Ok(device)
+}
+
+/// Look up the id of the `wireguard` generic netlink family
+fn resolve_family(socket: &Socket) -> io::Result<u16> {
+    let mut attrs = Vec::new();
+    push_nla(&mut attrs, CTRL_ATTR_FAMILY_NAME, WG_GENL_NAME);
+    send(
+        socket,
+        GENL_ID_CTRL,
+        NLM_F_REQUEST,
+        CTRL_CMD_GETFAMILY,
+        1,
+        &attrs,
+    )?;
+    let mut family = None;
+    recv(socket, |payload| {
+        for (kind, attr) in netlink::iter_nlas(payload) {
+            if kind == CTRL_ATTR_FAMILY_ID && attr.len() >= 2 {
+                family = Some(u16::from_ne_bytes([attr[0], attr[1]]));
+            }
+        }
+    })?;
+    family.ok_or_else(|| io::Error::other("no family id in generic netlink reply"))
+}
+
+fn push_nla(buf: &mut Vec<u8>, kind: u16, payload: &[u8]) {
+    buf.extend_from_slice(&((4 + payload.len()) as u16).to_ne_bytes());
+    buf.extend_from_slice(&kind.to_ne_bytes());
+    buf.extend_from_slice(payload);
+    buf.resize((buf.len() + 3) & !3, 0);
+}
+
+fn send(
+    socket: &Socket,
+    family: u16,
+    flags: u16,
+    cmd: u8,
+    version: u8,
+    attrs: &[u8],
+) -> io::Result<()> {
+    let mut payload = vec![cmd, version, 0, 0];
+    payload.extend_from_slice(attrs);
+    netlink::send_raw(socket, family, flags, &payload)
+}
+
+/// Receive replies, passing the attributes following the generic netlink
+/// header of each one to `f`
+fn recv(socket: &Socket, mut f: impl FnMut(&[u8])) -> io::Result<()> {
+    netlink::recv_raw(socket, |_, payload| {
+        if payload.len() >= GENL_HDR_LEN {
+            f(&payload[GENL_HDR_LEN..]);
+        }
+    })
+}
+
+/// Merge one message of a `WG_CMD_GET_DEVICE` dump into `device`.
+///
+/// Devices with many peers or allowed IPs are split across messages; a peer
+/// continued from the previous message repeats its public key.
+fn parse_device(buf: &[u8], device: &mut Device) {
+    for (kind, attr) in netlink::iter_nlas(buf) {
+        match kind {
+            WGDEVICE_A_PUBLIC_KEY => device.public_key = Key::from_slice(attr),
+            WGDEVICE_A_LISTEN_PORT if attr.len() >= 2 => {
+                device.listen_port = u16::from_ne_bytes([attr[0], attr[1]])
+            }
+            WGDEVICE_A_FWMARK => device.fwmark = netlink::read_u32(attr, 0).unwrap_or(0),
+            WGDEVICE_A_PEERS => {
+                for (_, peer_attrs) in netlink::iter_nlas(attr) {
+                    if let Some(peer) = parse_peer(peer_attrs) {
+                        match device.peers.last_mut() {
+                            Some(last) if last.public_key == peer.public_key => {
+                                last.allowed_ips.extend(peer.allowed_ips)
+                            }
+                            _ => device.peers.push(peer),
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn read_u64(buf: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_ne_bytes(
+        buf.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+fn parse_peer(buf: &[u8]) -> Option<Peer> {
+    let key = netlink::iter_nlas(buf)
+        .find(|(kind, _)| *kind == WGPEER_A_PUBLIC_KEY)
+        .and_then(|(_, attr)| Key::from_slice(attr))?;
+    let mut peer = Peer::new(key);
+    for (kind, attr) in netlink::iter_nlas(buf) {
+        match kind {
+            WGPEER_A_ENDPOINT => peer.endpoint = crate::parse::sockaddr(attr).ok(),
+            WGPEER_A_PERSISTENT_KEEPALIVE_INTERVAL if attr.len() >= 2 => {
+                let secs = u16::from_ne_bytes([attr[0], attr[1]]);
+                peer.persistent_keepalive = (secs != 0).then(|| Duration::from_secs(secs as u64));
+            }
+            WGPEER_A_LAST_HANDSHAKE_TIME => {
+                // struct __kernel_timespec
+                let sec = read_u64(attr, 0).unwrap_or(0);
+                let nsec = read_u64(attr, 8).unwrap_or(0);
+                if sec != 0 || nsec != 0 {
+                    peer.last_handshake =
+                        Some(SystemTime::UNIX_EPOCH + Duration::new(sec, nsec as u32));
+                }
+            }
+            WGPEER_A_RX_BYTES => peer.rx_bytes = read_u64(attr, 0).unwrap_or(0),
+            WGPEER_A_TX_BYTES => peer.tx_bytes = read_u64(attr, 0).unwrap_or(0),
+            WGPEER_A_ALLOWEDIPS => {
+                peer.allowed_ips = netlink::iter_nlas(attr)
+                    .filter_map(|(_, ip_attrs)| parse_allowed_ip(ip_attrs))
+                    .collect()
+            }
+            _ => {}
+        }
+    }
+    Some(peer)
+}
+
+fn parse_allowed_ip(buf: &[u8]) -> Option<IpNet> {
+    let mut family = None;
+    let mut addr = None;
+    let mut prefix_len = None;
+    for (kind, attr) in netlink::iter_nlas(buf) {
+        match kind {
+            WGALLOWEDIP_A_FAMILY if attr.len() >= 2 => {
+                family = Some(u16::from_ne_bytes([attr[0], attr[1]]) as i32)
+            }
+            WGALLOWEDIP_A_IPADDR => addr = Some(attr),
+            WGALLOWEDIP_A_CIDR_MASK => prefix_len = attr.first().copied(),
+            _ => {}
+        }
+    }
+    let (addr, prefix_len) = (addr?, prefix_len?);
+    let addr = match family? {
+        libc::AF_INET if prefix_len <= 32 => {
+            IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(addr).ok()?))
+        }
+        libc::AF_INET6 if prefix_len <= 128 => {
+            IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(addr).ok()?))
+        }
+        _ => return None,
+    };
+    Some(IpNet::new(addr, prefix_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nla(kind: u16, payload: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        push_nla(&mut buf, kind, payload);
+        buf
+    }
+
+    fn peer(key: u8, ip: [u8; 4], prefix_len: u8) -> Vec<u8> {
+        let mut allowed_ip = nla(WGALLOWEDIP_A_FAMILY, &(libc::AF_INET as u16).to_ne_bytes());
+        allowed_ip.extend(nla(WGALLOWEDIP_A_IPADDR, &ip));
+        allowed_ip.extend(nla(WGALLOWEDIP_A_CIDR_MASK, &[prefix_len]));
+        let mut attrs = nla(WGPEER_A_PUBLIC_KEY, &[key; 32]);
+        attrs.extend(nla(WGPEER_A_ALLOWEDIPS, &nla(0, &allowed_ip)));
+        attrs
+    }
+
+    #[test]
+    fn test_parse_device() {
+        let mut device = Device {
+            name: "wg0".to_string(),
+            public_key: None,
+            listen_port: 0,
+            fwmark: 0,
+            peers: Vec::new(),
+        };
+        let mut endpoint = vec![0u8; 16];
+        endpoint[..2].copy_from_slice(&(libc::AF_INET as u16).to_ne_bytes());
+        endpoint[2..4].copy_from_slice(&51820u16.to_be_bytes());
+        endpoint[4..8].copy_from_slice(&[198, 51, 100, 1]);
+        let mut first_peer = peer(1, [10, 0, 0, 1], 32);
+        first_peer.extend(nla(WGPEER_A_ENDPOINT, &endpoint));
+        first_peer.extend(nla(
+            WGPEER_A_PERSISTENT_KEEPALIVE_INTERVAL,
+            &25u16.to_ne_bytes(),
+        ));
+
+        let mut msg = nla(WGDEVICE_A_PUBLIC_KEY, &[9; 32]);
+        msg.extend(nla(WGDEVICE_A_LISTEN_PORT, &51820u16.to_ne_bytes()));
+        msg.extend(nla(WGDEVICE_A_PEERS, &nla(0, &first_peer)));
+        parse_device(&msg, &mut device);
+        // The next message continues the first peer and adds a second one
+        let mut peers = nla(0, &peer(1, [10, 0, 1, 0], 24));
+        peers.extend(nla(1, &peer(2, [10, 0, 2, 0], 24)));
+        parse_device(&nla(WGDEVICE_A_PEERS, &peers), &mut device);
+
+        assert_eq!(device.public_key, Some(Key::new([9; 32])));
+        assert_eq!(device.listen_port, 51820);
+        assert_eq!(device.peers.len(), 2);
+        let peer = &device.peers[0];
+        assert_eq!(peer.endpoint, Some("198.51.100.1:51820".parse().unwrap()));
+        assert_eq!(peer.persistent_keepalive, Some(Duration::from_secs(25)));
+        assert_eq!(
+            peer.allowed_ips,
+            vec![
+                "10.0.0.1/32".parse().unwrap(),
+                "10.0.1.0/24".parse().unwrap()
+            ]
+        );
+        assert_eq!(device.peers[1].public_key, Key::new([2; 32]));
+    }
+}
@@ -0,0 +1,258 @@
+//! WireGuard device and peer metadata.
+//!
+//! On Linux and Android the kernel module is queried over its generic netlink
+//! family. Userspace implementations such as wireguard-go, and the ones used
+//! on the BSDs, macOS and Windows, are queried through the cross-platform
+//! UAPI socket instead. Linux falls back to the UAPI socket when the kernel
+//! module is not loaded.
+//!
+//! Reading WireGuard state requires the same privileges as `wg show`.
+//!
+//! ```no_run
+//! let device = netdev::wireguard::get_device("wg0")?;
+//! for peer in &device.peers {
+//!     println!("{} {:?} {:?}", peer.public_key, peer.endpoint, peer.allowed_ips);
+//! }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod linux;
+mod uapi;
+
+use crate::interface::Interface;
+use crate::ip::IpNet;
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Length of a Curve25519 key in bytes
+pub const KEY_LEN: usize = 32;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// WireGuard public key.
+///
+/// Displayed and parsed in the base64 form used by `wg` and configuration files.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd, Default)]
+pub struct Key([u8; KEY_LEN]);
+
+impl Key {
+    /// Construct a key from its raw bytes
+    pub fn new(bytes: [u8; KEY_LEN]) -> Key {
+        Key(bytes)
+    }
+    /// Returns the raw bytes of the key
+    pub fn as_bytes(&self) -> &[u8; KEY_LEN] {
+        &self.0
+    }
+    /// Construct a key from its lowercase or uppercase hex form, as used by the UAPI
+    pub fn from_hex(s: &str) -> Option<Key> {
+        if s.len() != KEY_LEN * 2 || !s.is_ascii() {
+            return None;
+        }
+        let mut bytes = [0u8; KEY_LEN];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).ok()?;
+        }
+        Some(Key(bytes))
+    }
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub(crate) fn from_slice(buf: &[u8]) -> Option<Key> {
+        Some(Key(buf.try_into().ok()?))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = [b'='; 44];
+        for (chunk, out) in self.0.chunks(3).zip(out.chunks_mut(4)) {
+            let n = (chunk[0] as u32) << 16
+                | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+                | *chunk.get(2).unwrap_or(&0) as u32;
+            // The last chunk has two bytes, which encode to three characters
+            for (i, c) in out.iter_mut().enumerate().take(chunk.len() + 1) {
+                *c = BASE64[(n >> (18 - 6 * i) & 0x3f) as usize];
+            }
+        }
+        f.write_str(std::str::from_utf8(&out).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Key({})", self)
+    }
+}
+
+/// Error returned when a string is not a base64 encoded key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseKeyError;
+
+impl fmt::Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid WireGuard key")
+    }
+}
+
+impl std::error::Error for ParseKeyError {}
+
+impl FromStr for Key {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Key, ParseKeyError> {
+        let s = s.as_bytes();
+        if s.len() != 44 || s[43] != b'=' {
+            return Err(ParseKeyError);
+        }
+        let mut bytes = [0u8; KEY_LEN];
+        let mut n: u32 = 0;
+        let mut pos = 0;
+        for (i, c) in s[..43].iter().enumerate() {
+            let value = BASE64.iter().position(|b| b == c).ok_or(ParseKeyError)? as u32;
+            n = n << 6 | value;
+            if i % 4 == 3 {
+                bytes[pos..pos + 3].copy_from_slice(&n.to_be_bytes()[1..]);
+                pos += 3;
+                n = 0;
+            }
+        }
+        // 43 characters leave 18 bits, of which the low two must be zero
+        if n & 0x3 != 0 {
+            return Err(ParseKeyError);
+        }
+        bytes[30] = (n >> 10) as u8;
+        bytes[31] = (n >> 2) as u8;
+        Ok(Key(bytes))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Key, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Peer configured on a WireGuard device
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Peer {
+    /// Public key identifying the peer
+    pub public_key: Key,
+    /// Last known endpoint of the peer, if any
+    pub endpoint: Option<SocketAddr>,
+    /// Networks routed to the peer, and accepted from it
+    pub allowed_ips: Vec<IpNet>,
+    /// Time of the last completed handshake, `None` if there was none
+    pub last_handshake: Option<SystemTime>,
+    /// Bytes received from the peer
+    pub rx_bytes: u64,
+    /// Bytes sent to the peer
+    pub tx_bytes: u64,
+    /// Keepalive interval, `None` when disabled
+    pub persistent_keepalive: Option<Duration>,
+}
+
+impl Peer {
+    fn new(public_key: Key) -> Peer {
+        Peer {
+            public_key,
+            endpoint: None,
+            allowed_ips: Vec::new(),
+            last_handshake: None,
+            rx_bytes: 0,
+            tx_bytes: 0,
+            persistent_keepalive: None,
+        }
+    }
+}
+
+/// Configuration and state of a WireGuard device
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Device {
+    /// Interface name
+    pub name: String,
+    /// Public key of the device.
+    /// `None` when queried through the UAPI socket, which only reports the private key.
+    pub public_key: Option<Key>,
+    /// UDP port the device listens on, 0 if not set
+    pub listen_port: u16,
+    /// Mark applied to outgoing packets, 0 if not set
+    pub fwmark: u32,
+    /// Configured peers
+    pub peers: Vec<Peer>,
+}
+
+/// Get the WireGuard configuration and state of the device `name`.
+///
+/// Returns an error of kind `NotFound` if there is no WireGuard device with that name.
+pub fn get_device(name: &str) -> io::Result<Device> {
+    let _span = debug_span!("wireguard_get_device");
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    match linux::get_device(name) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            debug!("{} not found over netlink, trying UAPI: {}", name, err)
+        }
+        result => return result,
+    }
+    uapi::get_device(name)
+}
+
+impl Interface {
+    /// Get the WireGuard configuration of this interface.
+    ///
+    /// Returns `Ok(None)` if the interface is not a WireGuard device.
+    /// See [`get_device`].
+    pub fn wireguard(&self) -> io::Result<Option<Device>> {
+        match get_device(&self.name) {
+            Ok(device) => Ok(Some(device)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_base64() {
+        let mut bytes = [0u8; KEY_LEN];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = i as u8 * 8;
+        }
+        let key = Key::new(bytes);
+        let s = key.to_string();
+        assert_eq!(s, "AAgQGCAoMDhASFBYYGhweICIkJigqLC4wMjQ2ODo8Pg=");
+        assert_eq!(s.parse::<Key>(), Ok(key));
+        assert_eq!(Key::default().to_string(), "A".repeat(43) + "=");
+        assert!("AAgQGCAoMDhASFBYYGhweICIkJigqLC4wMjQ2ODo8Ph="
+            .parse::<Key>()
+            .is_err());
+        assert!("AAgQ".parse::<Key>().is_err());
+        assert_eq!(Key::from_hex(&"08".repeat(32)), Some(Key::new([8; 32])));
+    }
+
+    #[test]
+    fn test_get_device() {
+        for interface in crate::interface::get_interfaces() {
+            println!("{}: {:?}", interface.name, interface.wireguard());
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Cross-platform userspace API, spoken by wireguard-go and other userspace
+//! implementations over a Unix socket or, on Windows, a named pipe.
+
+use super::{Device, Key, Peer};
+use std::io::{self, BufRead, BufReader, Write};
+use std::time::{Duration, SystemTime};
+
+#[cfg(not(target_os = "windows"))]
+const SOCKET_DIR: &str = "/var/run/wireguard";
+#[cfg(target_os = "windows")]
+const PIPE_PREFIX: &str = r"\\.\pipe\ProtectedPrefix\Administrators\WireGuard\";
+
+#[cfg(not(target_os = "windows"))]
+fn connect(name: &str) -> io::Result<std::os::unix::net::UnixStream> {
+    std::os::unix::net::UnixStream::connect(format!("{}/{}.sock", SOCKET_DIR, name))
+}
+
+#[cfg(target_os = "windows")]
+fn connect(name: &str) -> io::Result<std::fs::File> {
+    std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(format!("{}{}", PIPE_PREFIX, name))
+}
+
+pub(super) fn get_device(name: &str) -> io::Result<Device> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid interface name {:?}", name),
+        ));
+    }
+    let mut stream = connect(name).map_err(|err| match err.kind() {
+        // A missing socket means no userspace device of that name
+        io::ErrorKind::ConnectionRefused => io::Error::new(io::ErrorKind::NotFound, err),
+        _ => err,
+    })?;
+    stream.write_all(b"get=1\n\n")?;
+    let mut reader = BufReader::new(stream);
+    let mut response = String::new();
+    // The response ends with an empty line
+    loop {
+        let len = reader.read_line(&mut response)?;
+        if len == 0 || response.ends_with("\n\n") {
+            break;
+        }
+    }
+    parse_get_response(name, &response)
+}
+
+/// Parse the `key=value` lines answering a `get=1` request
+fn parse_get_response(name: &str, response: &str) -> io::Result<Device> {
+    let invalid = |line: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid UAPI line {:?}", line),
+        )
+    };
+    let mut device = Device {
+        name: name.to_string(),
+        public_key: None,
+        listen_port: 0,
+        fwmark: 0,
+        peers: Vec::new(),
+    };
+    let mut handshake_sec = 0u64;
+    for line in response.lines() {
+        if line.is_empty() {
+            break;
+        }
+        let (key, value) = line.split_once('=').ok_or_else(|| invalid(line))?;
+        match (key, device.peers.last_mut()) {
+            ("errno", _) => {
+                let errno: i32 = value.parse().map_err(|_| invalid(line))?;
+                if errno != 0 {
+                    return Err(io::Error::other(format!(
+                        "WireGuard UAPI request failed with errno {}",
+                        errno
+                    )));
+                }
+            }
+            ("public_key", _) => {
+                let key = Key::from_hex(value).ok_or_else(|| invalid(line))?;
+                device.peers.push(Peer::new(key));
+            }
+            ("listen_port", None) => {
+                device.listen_port = value.parse().map_err(|_| invalid(line))?
+            }
+            ("fwmark", None) => device.fwmark = value.parse().map_err(|_| invalid(line))?,
+            ("endpoint", Some(peer)) => {
+                // IPv6 endpoints are bracketed, which SocketAddr parses directly
+                peer.endpoint = Some(value.parse().map_err(|_| invalid(line))?);
+            }
+            ("allowed_ip", Some(peer)) => {
+                peer.allowed_ips
+                    .push(value.parse().map_err(|_| invalid(line))?);
+            }
+            ("last_handshake_time_sec", Some(_)) => {
+                handshake_sec = value.parse().map_err(|_| invalid(line))?;
+            }
+            ("last_handshake_time_nsec", Some(peer)) => {
+                let nsec: u32 = value.parse().map_err(|_| invalid(line))?;
+                // Both zero means no handshake yet
+                if handshake_sec != 0 || nsec != 0 {
+                    peer.last_handshake =
+                        Some(SystemTime::UNIX_EPOCH + Duration::new(handshake_sec, nsec));
+                }
+            }
+            ("rx_bytes", Some(peer)) => peer.rx_bytes = value.parse().map_err(|_| invalid(line))?,
+            ("tx_bytes", Some(peer)) => peer.tx_bytes = value.parse().map_err(|_| invalid(line))?,
+            ("persistent_keepalive_interval", Some(peer)) => {
+                let secs: u64 = value.parse().map_err(|_| invalid(line))?;
+                peer.persistent_keepalive = (secs != 0).then(|| Duration::from_secs(secs));
+            }
+            // private_key, preshared_key, protocol_version and any keys added later
+            _ => {}
+        }
+    }
+    Ok(device)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+
+    #[test]
+    fn test_parse_get_response() {
+        let response =
+            "private_key=e84b5a6d2717c1003a13b431570353dbaca9146cf150c5f8575680feba52027a\n\
+            listen_port=51820\n\
+            fwmark=0\n\
+            public_key=b85996fecc9c7f1fc6d2572a76eda11d59bcd20be8e543b15ce4bd85a8e75a33\n\
+            preshared_key=0000000000000000000000000000000000000000000000000000000000000000\n\
+            protocol_version=1\n\
+            endpoint=[abcd:23::33%2]:51820\n\
+            last_handshake_time_sec=1700000000\n\
+            last_handshake_time_nsec=500\n\
+            tx_bytes=38333\n\
+            rx_bytes=2224\n\
+            persistent_keepalive_interval=25\n\
+            allowed_ip=192.168.4.4/32\n\
+            allowed_ip=fd00::/64\n\
+            public_key=58402e695ba1772b1cc9309755f043251ea77fdcf10fbe63989ceb7e19321376\n\
+            endpoint=182.122.22.19:3233\n\
+            last_handshake_time_sec=0\n\
+            last_handshake_time_nsec=0\n\
+            persistent_keepalive_interval=0\n\
+            allowed_ip=192.168.4.6/32\n\
+            errno=0\n\n";
+        let device = parse_get_response("wg0", response).unwrap();
+        assert_eq!(device.public_key, None);
+        assert_eq!(device.listen_port, 51820);
+        assert_eq!(device.peers.len(), 2);
+        let peer = &device.peers[0];
+        assert_eq!(peer.public_key.as_bytes()[0], 0xb8);
+        assert_eq!(
+            peer.endpoint,
+            Some("[abcd:23::33%2]:51820".parse::<SocketAddr>().unwrap())
+        );
+        assert_eq!(
+            peer.last_handshake,
+            Some(SystemTime::UNIX_EPOCH + Duration::new(1700000000, 500))
+        );
+        assert_eq!((peer.rx_bytes, peer.tx_bytes), (2224, 38333));
+        assert_eq!(peer.persistent_keepalive, Some(Duration::from_secs(25)));
+        assert_eq!(peer.allowed_ips.len(), 2);
+        let peer = &device.peers[1];
+        assert_eq!(peer.last_handshake, None);
+        assert_eq!(peer.persistent_keepalive, None);
+        assert_eq!(peer.allowed_ips, vec!["192.168.4.6/32".parse().unwrap()]);
+
+        let err = parse_get_response("wg0", "errno=19\n\n").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        assert!(parse_get_response("wg0", "listen_port=x\n\n").is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! Wake-on-LAN.
+//!
+//! [`send_magic_packet`] wakes a sleeping host, and
+//! [`Interface::wol_modes`] reports which wake-up events a local NIC
+//! supports and has enabled.
+
+use crate::interface::Interface;
+use crate::mac::MacAddr;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddrV4, UdpSocket};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// UDP port magic packets are sent to (discard)
+pub const WOL_PORT: u16 = 9;
+
+/// Event that can wake a host
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum WolMode {
+    /// Link state change
+    Phy,
+    /// Unicast frame addressed to the NIC
+    Unicast,
+    /// Multicast frame
+    Multicast,
+    /// Broadcast frame
+    Broadcast,
+    /// ARP request for one of the host's addresses
+    Arp,
+    /// Magic packet
+    MagicPacket,
+    /// Magic packet with a SecureOn password
+    SecureOn,
+    /// Frame matching a programmed pattern filter
+    Filter,
+}
+
+/// Wake-on-LAN modes of an interface
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WolModes {
+    /// Modes the NIC supports
+    pub supported: Vec<WolMode>,
+    /// Modes currently enabled
+    pub enabled: Vec<WolMode>,
+}
+
+impl WolModes {
+    /// Check if waking by magic packet is enabled
+    pub fn magic_packet_enabled(&self) -> bool {
+        self.enabled.contains(&WolMode::MagicPacket)
+    }
+}
+
+/// Build a magic packet: six `0xff` bytes followed by the MAC address repeated 16 times
+pub fn magic_packet(mac_addr: MacAddr) -> [u8; 102] {
+    let mut packet = [0xff; 102];
+    for chunk in packet[6..].chunks_exact_mut(6) {
+        chunk.copy_from_slice(&mac_addr.octets());
+    }
+    packet
+}
+
+/// Send a magic packet to wake the host with the given MAC address
+///
+/// With `via`, the packet is broadcast on the subnet of that interface's first
+/// IPv4 address. Otherwise it goes to the limited broadcast address through
+/// the interface the OS picks.
+pub fn send_magic_packet(mac_addr: MacAddr, via: Option<&Interface>) -> io::Result<()> {
+    let (local, broadcast) = match via {
+        Some(interface) => {
+            let net = interface.ipv4.first().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::AddrNotAvailable,
+                    format!("{} has no IPv4 address", interface.name),
+                )
+            })?;
+            (net.addr, net.broadcast())
+        }
+        None => (Ipv4Addr::UNSPECIFIED, Ipv4Addr::BROADCAST),
+    };
+    let socket = UdpSocket::bind(SocketAddrV4::new(local, 0))?;
+    socket.set_broadcast(true)?;
+    socket.send_to(
+        &magic_packet(mac_addr),
+        SocketAddrV4::new(broadcast, WOL_PORT),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_magic_packet() {
+        let mac_addr = MacAddr::new(0x00, 0x1b, 0x21, 0x0a, 0xbc, 0xde);
+        let packet = magic_packet(mac_addr);
+        assert_eq!(packet[..6], [0xff; 6]);
+        assert_eq!(packet[6..12], mac_addr.octets());
+        assert_eq!(packet[96..], mac_addr.octets());
+    }
+
+    #[test]
+    fn test_wol_modes() {
+        for interface in crate::interface::get_interfaces() {
+            println!("{}: {:?}", interface.name, interface.wol_modes());
+        }
+    }
+}
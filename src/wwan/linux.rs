@@ -0,0 +1,161 @@
+use super::{AccessTechnology, WwanInfo};
+use crate::interface::Interface;
+use std::collections::HashMap;
+use std::io;
+use zbus::blocking::fdo::{ObjectManagerProxy, PropertiesProxy};
+use zbus::blocking::Connection;
+use zbus::names::InterfaceName;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+
+const MM_SERVICE: &str = "org.freedesktop.ModemManager1";
+const MM_PATH: &str = "/org/freedesktop/ModemManager1";
+const MM_MODEM: &str = "org.freedesktop.ModemManager1.Modem";
+const MM_MODEM_3GPP: &str = "org.freedesktop.ModemManager1.Modem.Modem3gpp";
+const MM_BEARER: &str = "org.freedesktop.ModemManager1.Bearer";
+
+// MMModemPortType
+const MM_MODEM_PORT_TYPE_NET: u32 = 2;
+
+// MMModem3gppRegistrationState values that indicate roaming
+const MM_MODEM_3GPP_REGISTRATION_STATE_ROAMING: [u32; 3] = [5, 7, 10];
+
+type Properties = HashMap<String, OwnedValue>;
+
+fn dbus_error(err: zbus::Error) -> io::Error {
+    match err {
+        zbus::Error::InputOutput(err) => io::Error::new(err.kind(), err.to_string()),
+        err => io::Error::other(err),
+    }
+}
+
+fn fdo_error(err: zbus::fdo::Error) -> io::Error {
+    io::Error::other(err)
+}
+
+fn prop<T>(props: &Properties, key: &str) -> Option<T>
+where
+    T: TryFrom<OwnedValue>,
+{
+    props
+        .get(key)
+        .and_then(|v| v.try_clone().ok())
+        .and_then(|v| T::try_from(v).ok())
+}
+
+/// Map a MMModemAccessTechnology bitmask to the most capable technology it contains.
+fn access_technology(mask: u32) -> Option<AccessTechnology> {
+    if mask == 0 {
+        None
+    } else if mask & (1 << 15) != 0 {
+        Some(AccessTechnology::Nr5g)
+    } else if mask & ((1 << 14) | (1 << 16) | (1 << 17)) != 0 {
+        Some(AccessTechnology::Lte)
+    } else if mask & ((1 << 10) | (1 << 11) | (1 << 12) | (1 << 13)) != 0 {
+        Some(AccessTechnology::Cdma)
+    } else if mask & 0x3e0 != 0 {
+        Some(AccessTechnology::Umts)
+    } else if mask & 0x1e != 0 {
+        Some(AccessTechnology::Gsm)
+    } else {
+        Some(AccessTechnology::Unknown)
+    }
+}
+
+fn bearer_properties(conn: &Connection, path: &OwnedObjectPath) -> io::Result<Properties> {
+    let proxy = PropertiesProxy::builder(conn)
+        .destination(MM_SERVICE)
+        .map_err(dbus_error)?
+        .path(path.as_ref())
+        .map_err(dbus_error)?
+        .build()
+        .map_err(dbus_error)?;
+    proxy
+        .get_all(InterfaceName::from_static_str_unchecked(MM_BEARER))
+        .map_err(fdo_error)
+}
+
+pub fn wwan_info(interface: &Interface) -> io::Result<Option<WwanInfo>> {
+    let conn = Connection::system().map_err(dbus_error)?;
+    let manager = ObjectManagerProxy::builder(&conn)
+        .destination(MM_SERVICE)
+        .map_err(dbus_error)?
+        .path(MM_PATH)
+        .map_err(dbus_error)?
+        .build()
+        .map_err(dbus_error)?;
+    let objects = manager.get_managed_objects().map_err(fdo_error)?;
+
+    for ifaces in objects.values() {
+        let modem = match ifaces.iter().find(|(name, _)| name.as_str() == MM_MODEM) {
+            Some((_, props)) => props,
+            None => continue,
+        };
+        let ports: Vec<(String, u32)> = prop(modem, "Ports").unwrap_or_default();
+        let bearers: Vec<OwnedObjectPath> = prop(modem, "Bearers").unwrap_or_default();
+
+        let mut matched = ports
+            .iter()
+            .any(|(name, kind)| *kind == MM_MODEM_PORT_TYPE_NET && *name == interface.name);
+        let mut apn = None;
+        for path in &bearers {
+            let bearer = match bearer_properties(&conn, path) {
+                Ok(bearer) => bearer,
+                Err(_) => continue,
+            };
+            let bearer_iface: Option<String> = prop(&bearer, "Interface");
+            if bearer_iface.as_deref() != Some(interface.name.as_str()) {
+                continue;
+            }
+            matched = true;
+            let settings: Option<HashMap<String, OwnedValue>> = prop(&bearer, "Properties");
+            apn = settings
+                .as_ref()
+                .and_then(|s| prop::<String>(s, "apn"))
+                .filter(|apn| !apn.is_empty());
+            break;
+        }
+        if !matched {
+            continue;
+        }
+
+        let gpp = ifaces
+            .iter()
+            .find(|(name, _)| name.as_str() == MM_MODEM_3GPP)
+            .map(|(_, props)| props);
+        let signal: Option<(u32, bool)> = prop(modem, "SignalQuality");
+        return Ok(Some(WwanInfo {
+            carrier: gpp
+                .and_then(|p| prop::<String>(p, "OperatorName"))
+                .filter(|name| !name.is_empty()),
+            signal_quality: signal.map(|(quality, _recent)| quality.min(100) as u8),
+            apn,
+            technology: prop::<u32>(modem, "AccessTechnologies").and_then(access_technology),
+            roaming: gpp
+                .and_then(|p| prop::<u32>(p, "RegistrationState"))
+                .map(|state| MM_MODEM_3GPP_REGISTRATION_STATE_ROAMING.contains(&state)),
+            metered: None,
+        }));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access_technology() {
+        assert_eq!(access_technology(0), None);
+        assert_eq!(access_technology(1 << 1), Some(AccessTechnology::Gsm));
+        assert_eq!(access_technology(1 << 8), Some(AccessTechnology::Umts));
+        assert_eq!(
+            access_technology((1 << 14) | (1 << 5)),
+            Some(AccessTechnology::Lte)
+        );
+        assert_eq!(
+            access_technology((1 << 15) | (1 << 14)),
+            Some(AccessTechnology::Nr5g)
+        );
+    }
+}
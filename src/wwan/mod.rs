@@ -0,0 +1,82 @@
+//! Cellular (WWAN) link details.
+//!
+//! On Linux the information is queried from ModemManager over the system D-Bus.
+//! On Windows only the connection cost reported by the Windows Connection Manager is available.
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+use self::linux::*;
+
+#[cfg(target_os = "windows")]
+mod windows;
+#[cfg(target_os = "windows")]
+use self::windows::*;
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+mod unsupported;
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+use self::unsupported::*;
+
+use crate::interface::Interface;
+use std::io;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Radio access technology of a cellular link
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AccessTechnology {
+    /// GSM, GPRS or EDGE (2G)
+    Gsm,
+    /// UMTS or HSPA (3G)
+    Umts,
+    /// CDMA2000 1xRTT or EV-DO
+    Cdma,
+    /// LTE, including LTE Cat-M and NB-IoT (4G)
+    Lte,
+    /// 5G New Radio
+    Nr5g,
+    /// Other or unknown technology
+    Unknown,
+}
+
+/// Details of a cellular modem backing a network interface
+///
+/// Fields are `None` when the value is not reported by the platform.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WwanInfo {
+    /// Name of the network operator
+    pub carrier: Option<String>,
+    /// Signal quality in percent (0-100)
+    pub signal_quality: Option<u8>,
+    /// Access Point Name of the active bearer
+    pub apn: Option<String>,
+    /// Current radio access technology
+    pub technology: Option<AccessTechnology>,
+    /// The connection is registered on a roaming network
+    pub roaming: Option<bool>,
+    /// The connection is metered (billed by usage or capped)
+    pub metered: Option<bool>,
+}
+
+/// Get cellular details for the interface.
+///
+/// Returns `Ok(None)` if the interface is not backed by a cellular modem.
+pub fn get_wwan_info(interface: &Interface) -> io::Result<Option<WwanInfo>> {
+    wwan_info(interface)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wwan_info() {
+        for interface in crate::get_interfaces() {
+            println!("{}: {:?}", interface.name, get_wwan_info(&interface));
+        }
+    }
+}
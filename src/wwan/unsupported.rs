@@ -0,0 +1,10 @@
+use super::WwanInfo;
+use crate::interface::Interface;
+use std::io;
+
+pub fn wwan_info(_interface: &Interface) -> io::Result<Option<WwanInfo>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "WWAN details are not supported on this platform",
+    ))
+}
@@ -0,0 +1,52 @@
+use super::WwanInfo;
+use crate::interface::{Interface, InterfaceType};
+use std::{io, mem, ptr};
+use windows_sys::core::GUID;
+use windows_sys::Win32::Foundation::NO_ERROR;
+use windows_sys::Win32::NetworkManagement::WindowsConnectionManager::{
+    wcm_intf_property_connection_cost, WcmFreeMemory, WcmQueryProperty, WCM_CONNECTION_COST_FIXED,
+    WCM_CONNECTION_COST_ROAMING, WCM_CONNECTION_COST_VARIABLE,
+};
+
+fn connection_cost(guid: &GUID) -> io::Result<u32> {
+    let mut size: u32 = 0;
+    let mut data: *mut u8 = ptr::null_mut();
+    let ret = unsafe {
+        WcmQueryProperty(
+            guid,
+            ptr::null(),
+            wcm_intf_property_connection_cost,
+            ptr::null(),
+            &mut size,
+            &mut data,
+        )
+    };
+    if ret != NO_ERROR {
+        return Err(io::Error::from_raw_os_error(ret as i32));
+    }
+    if data.is_null() {
+        return Err(io::Error::other("WcmQueryProperty returned no data"));
+    }
+    let cost = if size as usize >= mem::size_of::<u32>() {
+        Some(unsafe { ptr::read_unaligned(data as *const u32) })
+    } else {
+        None
+    };
+    unsafe { WcmFreeMemory(data as *mut _) };
+    cost.ok_or_else(|| io::Error::other("unexpected connection cost size"))
+}
+
+pub fn wwan_info(interface: &Interface) -> io::Result<Option<WwanInfo>> {
+    match interface.if_type {
+        InterfaceType::Wwanpp | InterfaceType::Wwanpp2 => {}
+        _ => return Ok(None),
+    }
+    let guid = crate::interface::guid_of(interface.index)?;
+    let cost = connection_cost(&guid)?;
+    let metered = cost & (WCM_CONNECTION_COST_FIXED | WCM_CONNECTION_COST_VARIABLE) as u32 != 0;
+    Ok(Some(WwanInfo {
+        roaming: Some(cost & WCM_CONNECTION_COST_ROAMING as u32 != 0),
+        metered: Some(metered),
+        ..WwanInfo::default()
+    }))
+}
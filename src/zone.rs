@@ -0,0 +1,134 @@
+//! Link-local IPv6 addresses and their zone identifiers.
+//!
+//! A link-local address is only meaningful together with the interface it is
+//! reachable on. In text the interface is written as a zone after a `%`
+//! (RFC 4007): `fe80::1%eth0` on Unix, `fe80::1%5` on Windows. In a socket
+//! address it is the `scope_id`, which is the interface index.
+
+use crate::mac::MacAddr;
+use std::io;
+use std::net::Ipv6Addr;
+
+/// Returns true if `addr` is in `fe80::/10`
+pub fn is_link_local(addr: &Ipv6Addr) -> bool {
+    addr.segments()[0] & 0xffc0 == 0xfe80
+}
+
+/// Build the SLAAC link-local address for a MAC address, using the modified
+/// EUI-64 interface identifier of RFC 4291 appendix A
+pub fn link_local_addr(mac_addr: MacAddr) -> Ipv6Addr {
+    let m = mac_addr.octets();
+    Ipv6Addr::new(
+        0xfe80,
+        0,
+        0,
+        0,
+        u16::from_be_bytes([m[0] ^ 0x02, m[1]]),
+        u16::from_be_bytes([m[2], 0xff]),
+        u16::from_be_bytes([0xfe, m[3]]),
+        u16::from_be_bytes([m[4], m[5]]),
+    )
+}
+
+/// Get the scope id (interface index) for an interface name, or on Windows
+/// an adapter GUID or alias
+pub fn scope_id(name: &str) -> Option<u32> {
+    crate::interface::name_to_index(name).ok()
+}
+
+/// Get the name of the interface with the given scope id (interface index)
+pub fn zone_name(scope_id: u32) -> Option<String> {
+    crate::interface::index_to_name(scope_id).ok()
+}
+
+/// Format an address with its zone, in the form the platform's tools expect.
+///
+/// Unix uses the interface name (`fe80::1%eth0`), falling back to the number if
+/// the index is unknown. Windows always uses the number (`fe80::1%5`). A scope id
+/// of 0 means no zone.
+pub fn format_scoped(addr: &Ipv6Addr, scope_id: u32) -> String {
+    if scope_id == 0 {
+        return addr.to_string();
+    }
+    #[cfg(unix)]
+    let zone = zone_name(scope_id).unwrap_or_else(|| scope_id.to_string());
+    #[cfg(not(unix))]
+    let zone = scope_id.to_string();
+    format!("{}%{}", addr, zone)
+}
+
+/// Parse an address with an optional zone, such as `fe80::1%eth0` or `fe80::1%5`.
+///
+/// Returns the address and its scope id, which is 0 if there is no zone.
+/// Interface names are resolved to their index.
+pub fn parse_scoped(s: &str) -> io::Result<(Ipv6Addr, u32)> {
+    let (addr, zone) = match s.split_once('%') {
+        Some((addr, zone)) => (addr, Some(zone)),
+        None => (s, None),
+    };
+    let addr: Ipv6Addr = addr
+        .parse()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "invalid IPv6 address"))?;
+    let scope_id = match zone {
+        None => 0,
+        Some("") => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "empty zone identifier",
+            ))
+        }
+        Some(zone) => match zone.parse::<u32>() {
+            Ok(index) => index,
+            Err(_) => scope_id(zone).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("unknown interface {}", zone),
+                )
+            })?,
+        },
+    };
+    Ok((addr, scope_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_local_addr() {
+        let mac = MacAddr::new(0x00, 0x1b, 0x21, 0x0a, 0xbc, 0xde);
+        let addr = link_local_addr(mac);
+        assert_eq!(
+            addr,
+            "fe80::21b:21ff:fe0a:bcde".parse::<Ipv6Addr>().unwrap()
+        );
+        assert!(is_link_local(&addr));
+        assert!(!is_link_local(&Ipv6Addr::LOCALHOST));
+    }
+
+    #[test]
+    fn test_parse_scoped() {
+        let addr: Ipv6Addr = "fe80::1".parse().unwrap();
+        assert_eq!(parse_scoped("fe80::1").unwrap(), (addr, 0));
+        assert_eq!(parse_scoped("fe80::1%5").unwrap(), (addr, 5));
+        assert!(parse_scoped("fe80::1%").is_err());
+        assert!(parse_scoped("fe80::zz%5").is_err());
+        assert_eq!(
+            parse_scoped("fe80::1%no-such-interface0")
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotFound
+        );
+        assert_eq!(format_scoped(&addr, 0), "fe80::1");
+    }
+
+    #[test]
+    fn test_zone_round_trip() {
+        let addr: Ipv6Addr = "fe80::1".parse().unwrap();
+        for iface in crate::interface::get_interfaces() {
+            let text = format_scoped(&addr, iface.index);
+            println!("{}", text);
+            assert_eq!(parse_scoped(&text).unwrap(), (addr, iface.index));
+        }
+    }
+}